@@ -130,9 +130,14 @@ verifyRouter.post("/submit", async (req: Request, res: Response) => {
 
     pendingChallenges.delete(challengeId);
 
-    const fingerprintHash = createHash("sha256")
+    // The digest of the submitted fingerprint is what goes on chain; the
+    // record stores its SHA-256 as the fingerprint hash.
+    const fingerprintBytes = createHash("sha256")
         .update(JSON.stringify(fingerprintData))
         .digest();
+    const fingerprintHash = createHash("sha256")
+        .update(fingerprintBytes)
+        .digest();
 
     const verificationLevel = determineVerificationLevel(
         validationResult.score,
@@ -143,7 +148,7 @@ verifyRouter.post("/submit", async (req: Request, res: Response) => {
         const registrationResult = await solanaService.prepareRegistrationTransaction(
             wallet,
             Array.from(challenge.nonce),
-            Array.from(fingerprintHash)
+            Array.from(fingerprintBytes)
         );
 
         res.json({
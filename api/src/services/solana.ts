@@ -10,8 +10,23 @@ const SEEDS = {
     HUMAN: Buffer.from("human"),
     SESSION: Buffer.from("session"),
     INTERACTION: Buffer.from("interaction"),
+    FINGERPRINT: Buffer.from("fingerprint"),
+    TOMBSTONE: Buffer.from("tombstone"),
+    COMPROMISED: Buffer.from("compromised"),
+    STATS: Buffer.from("stats"),
+    PERSONALITY: Buffer.from("personality"),
+    EPOCH_STATS: Buffer.from("epoch_stats"),
 };
 
+/** Byte offset of the packed `HumanRecord.flags` field, discriminator included. */
+const HUMAN_RECORD_FLAGS_OFFSET = 113;
+/** Byte offset of `PlatformRegistry.treasury`, discriminator included. */
+const REGISTRY_TREASURY_OFFSET = 905;
+/** Number of StatsBucket shards the session counters are spread over. */
+const STATS_BUCKET_COUNT = 16;
+/** Length in seconds of the day an EpochStats account counts activity for. */
+const EPOCH_STATS_DURATION = 86400;
+
 /**
  * Solana interaction service for the your.fun API.
  * Handles transaction preparation, account lookups, and on-chain state queries.
@@ -29,40 +44,77 @@ export class SolanaService {
     /**
      * Prepares a registration transaction for Proof-of-Human verification.
      * Returns a serialized transaction that the client can sign and submit.
+     * The record's on-chain fingerprint hash is the SHA-256 of
+     * `fingerprintData`.
      */
     async prepareRegistrationTransaction(
         walletAddress: string,
         challengeNonce: number[],
-        fingerprintHash: number[]
+        fingerprintData: number[]
     ): Promise<{ transaction: string; registryAddress: string; humanRecordAddress: string }> {
         const wallet = new PublicKey(walletAddress);
         const [registryAddress] = this.deriveRegistryAddress();
         const [humanRecordAddress] = this.deriveHumanRecordAddress(wallet);
+        const fingerprintHash = createHash("sha256")
+            .update(Buffer.from(fingerprintData))
+            .digest();
+        const [fingerprintAddress] = PublicKey.findProgramAddressSync(
+            [SEEDS.FINGERPRINT, humanRecordAddress.toBuffer()],
+            this.programId
+        );
+        const [tombstoneAddress] = PublicKey.findProgramAddressSync(
+            [SEEDS.TOMBSTONE, wallet.toBuffer()],
+            this.programId
+        );
+        const [compromisedAddress] = PublicKey.findProgramAddressSync(
+            [SEEDS.COMPROMISED, fingerprintHash],
+            this.programId
+        );
 
         const registryInfo = await this.connection.getAccountInfo(registryAddress);
         if (!registryInfo) {
             throw new Error("Platform registry has not been initialized");
         }
 
+        // Fees go to the treasury, or to the authority until one is set.
         const authorityOffset = 8;
-        const authority = new PublicKey(
-            registryInfo.data.slice(authorityOffset, authorityOffset + 32)
+        const treasury = new PublicKey(
+            registryInfo.data.slice(REGISTRY_TREASURY_OFFSET, REGISTRY_TREASURY_OFFSET + 32)
         );
+        const feeWallet = treasury.equals(PublicKey.default)
+            ? new PublicKey(registryInfo.data.slice(authorityOffset, authorityOffset + 32))
+            : treasury;
+
+        const fingerprintLength = Buffer.alloc(4);
+        fingerprintLength.writeUInt32LE(fingerprintData.length);
 
         const discriminator = this.computeDiscriminator("register_human");
         const instructionData = Buffer.concat([
             discriminator,
             Buffer.from(challengeNonce),
-            Buffer.from(fingerprintHash),
+            fingerprintLength,
+            Buffer.from(fingerprintData),
+            Buffer.from([0]), // promo_code: None
         ]);
 
+        // Omitted optional accounts are passed as the program ID.
+        const omitted = { pubkey: this.programId, isSigner: false, isWritable: false };
         const ix = {
             programId: this.programId,
             keys: [
                 { pubkey: registryAddress, isSigner: false, isWritable: true },
                 { pubkey: humanRecordAddress, isSigner: false, isWritable: true },
+                { pubkey: fingerprintAddress, isSigner: false, isWritable: true },
+                { pubkey: tombstoneAddress, isSigner: false, isWritable: true },
+                omitted, // invite
+                { pubkey: compromisedAddress, isSigner: false, isWritable: false },
                 { pubkey: wallet, isSigner: true, isWritable: true },
-                { pubkey: authority, isSigner: false, isWritable: true },
+                { pubkey: feeWallet, isSigner: false, isWritable: true },
+                omitted, // insurance_fund
+                omitted, // matching_campaign
+                omitted, // scholarship_pool
+                omitted, // promo
+                omitted, // promo_redemption
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
             data: instructionData,
@@ -127,10 +179,10 @@ export class SolanaService {
         const verificationLevel = data[offset];
         offset += 1;
 
-        offset += 32;
+        offset = HUMAN_RECORD_FLAGS_OFFSET;
 
-        const isActive = data[offset] === 1;
-        offset += 1;
+        const isActive = (data.readUInt16LE(offset) & 1) !== 0;
+        offset += 2;
 
         const sessionCountBuf = data.slice(offset, offset + 8);
         const sessionCount = Number(sessionCountBuf.readBigInt64LE(0));
@@ -214,7 +266,7 @@ export class SolanaService {
             throw new Error("Human record not found");
         }
 
-        const sessionCountOffset = 8 + 32 + 32 + 8 + 1 + 32 + 1;
+        const sessionCountOffset = HUMAN_RECORD_FLAGS_OFFSET + 2;
         const sessionCountBuf = humanInfo.data.slice(
             sessionCountOffset,
             sessionCountOffset + 8
@@ -228,21 +280,49 @@ export class SolanaService {
             [SEEDS.SESSION, humanRecordAddress.toBuffer(), indexBuffer],
             this.programId
         );
+        const [statsBucketAddress] = PublicKey.findProgramAddressSync(
+            [SEEDS.STATS, Buffer.from([sessionAddress.toBuffer()[0] % STATS_BUCKET_COUNT])],
+            this.programId
+        );
+        const [personalityConfigAddress] = PublicKey.findProgramAddressSync(
+            [SEEDS.PERSONALITY, Buffer.from([personalityId])],
+            this.programId
+        );
+        const epochBuffer = Buffer.alloc(8);
+        epochBuffer.writeBigUInt64LE(BigInt(Math.floor(Date.now() / 1000 / EPOCH_STATS_DURATION)));
+        const [epochStatsAddress] = PublicKey.findProgramAddressSync(
+            [SEEDS.EPOCH_STATS, epochBuffer],
+            this.programId
+        );
 
         const discriminator = this.computeDiscriminator("create_session");
         const instructionData = Buffer.concat([
             discriminator,
             Buffer.from([personalityId]),
             Buffer.from(initialTopic),
+            Buffer.from([0]), // goal_type: none
+            Buffer.alloc(8), // goal_target
+            Buffer.alloc(8), // escrow_rate_per_minute: unbilled
         ]);
 
+        // Omitted optional accounts are passed as the program ID.
+        const omitted = { pubkey: this.programId, isSigner: false, isWritable: false };
         const ix = {
             programId: this.programId,
             keys: [
-                { pubkey: registryAddress, isSigner: false, isWritable: true },
+                { pubkey: registryAddress, isSigner: false, isWritable: false },
                 { pubkey: humanRecordAddress, isSigner: false, isWritable: true },
                 { pubkey: sessionAddress, isSigner: false, isWritable: true },
+                { pubkey: statsBucketAddress, isSigner: false, isWritable: true },
+                { pubkey: epochStatsAddress, isSigner: false, isWritable: true },
+                omitted, // active_index
+                { pubkey: personalityConfigAddress, isSigner: false, isWritable: false },
                 { pubkey: wallet, isSigner: true, isWritable: true },
+                omitted, // supervisor
+                omitted, // booster_mint
+                omitted, // booster_token
+                omitted, // token_program
+                omitted, // registered_topic
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
             data: instructionData,
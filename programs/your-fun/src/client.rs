@@ -0,0 +1,2456 @@
+//! Off-chain instruction builders for Rust services.
+//!
+//! Every builder derives the PDAs it needs and takes only the high-level
+//! inputs, so callers are insulated from account ordering. Compiled only with
+//! the `client` feature (or for the crate's own unit tests) and never for
+//! the on-chain target.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+use crate::instructions::{InteractionInput, InteractionInputV2, LivenessChallenge, VerifyEntry};
+use crate::scoring::SUB_SCORE_COUNT;
+use crate::state::{
+    ActiveSessionIndex, AdminAction, Appeal, Candidate, CompromisedFingerprint, ConfigUpdate, DocumentAttestation, DocumentIssuer, Election, EpochStats, FeeVault, FingerprintData, Handle, Highlights, HumanRecord, InsuranceClaim, InsuranceFund, InteractionLog, InviteEntry, LegacyImport, MatchingCampaign, Milestone, PendingConfig, PersonalityConfig,
+    PersonalityStats, PersonalityTreasury, PlatformRegistry, Promo, PromoRedemption, RaffleEpoch, RaffleTicketPage, RegionStats, ScholarshipApproval, ScholarshipPool, SessionAccount, SessionTemplate, StatsBucket, Tombstone, Topic, TopicProgress,
+    VerifierAccount, VerifierStats, Vesting, VoteEscrow, WaitlistEntry,
+};
+
+pub fn registry_address() -> Pubkey {
+    Pubkey::find_program_address(&[PlatformRegistry::SEED], &crate::ID).0
+}
+
+pub fn human_record_address(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[HumanRecord::SEED, wallet.as_ref()], &crate::ID).0
+}
+
+pub fn fingerprint_address(human_record: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FingerprintData::SEED, human_record.as_ref()], &crate::ID).0
+}
+
+pub fn tombstone_address(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[Tombstone::SEED, wallet.as_ref()], &crate::ID).0
+}
+
+pub fn legacy_import_address(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[LegacyImport::SEED, wallet.as_ref()], &crate::ID).0
+}
+
+/// Builds `import_legacy_record` for `wallet`, whose record under the
+/// previous deployment is `legacy_record`; `payer` funds the new accounts.
+pub fn build_import_legacy_record_ix(payer: &Pubkey, wallet: &Pubkey, legacy_record: &Pubkey) -> Instruction {
+    let human_record = human_record_address(wallet);
+    instruction(
+        crate::accounts::ImportLegacyRecord {
+            registry: registry_address(),
+            legacy_record: *legacy_record,
+            human_record,
+            fingerprint: fingerprint_address(&human_record),
+            legacy_import: legacy_import_address(wallet),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::ImportLegacyRecord { wallet: *wallet },
+    )
+}
+
+pub fn session_address(human_record: &Pubkey, session_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SessionAccount::SEED,
+            human_record.as_ref(),
+            &session_index.to_le_bytes(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn interaction_address(session: &Pubkey, interaction_index: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            InteractionLog::SEED,
+            session.as_ref(),
+            &interaction_index.to_le_bytes(),
+        ],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn stats_bucket_address(session: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[StatsBucket::SEED, &[StatsBucket::index_for(session)]],
+        &crate::ID,
+    )
+    .0
+}
+
+/// The EpochStats of the day containing `now`; instructions that count
+/// towards it take the time the transaction is expected to land.
+pub fn epoch_stats_address(now: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[EpochStats::SEED, &EpochStats::epoch_at(now).to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn active_index_address(human_record: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ActiveSessionIndex::SEED, human_record.as_ref()], &crate::ID).0
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn admin_action_address(action_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[AdminAction::SEED, registry_address().as_ref(), &action_index.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Builds `set_paused`. Admin instructions write their audit entry at the
+/// registry's current `admin_action_count`.
+pub fn build_set_paused_ix(
+    signer: &Pubkey,
+    co_authority: Option<&Pubkey>,
+    admin_action_count: u64,
+    paused: bool,
+) -> Instruction {
+    instruction(
+        crate::accounts::SetPaused {
+            registry: registry_address(),
+            admin_action: admin_action_address(admin_action_count),
+            signer: *signer,
+            co_authority: co_authority.copied(),
+            system_program: system_program::ID,
+        },
+        crate::instruction::SetPaused { paused },
+    )
+}
+
+pub fn build_set_pause_flags_ix(
+    authority: &Pubkey,
+    co_authority: Option<&Pubkey>,
+    admin_action_count: u64,
+    flags: u8,
+) -> Instruction {
+    instruction(
+        crate::accounts::SetPauseFlags {
+            registry: registry_address(),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            co_authority: co_authority.copied(),
+            system_program: system_program::ID,
+        },
+        crate::instruction::SetPauseFlags { flags },
+    )
+}
+
+pub fn pending_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[PendingConfig::SEED], &crate::ID).0
+}
+
+pub fn build_queue_config_update_ix(authority: &Pubkey, admin_action_count: u64, update: ConfigUpdate) -> Instruction {
+    instruction(
+        crate::accounts::QueueConfigUpdate {
+            registry: registry_address(),
+            pending_config: pending_config_address(),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::QueueConfigUpdate { update },
+    )
+}
+
+/// Builds the permissionless `execute_config_update`; `authority` only
+/// receives the pending update's rent.
+pub fn build_execute_config_update_ix(authority: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::ExecuteConfigUpdate {
+            registry: registry_address(),
+            pending_config: pending_config_address(),
+            authority: *authority,
+        },
+        crate::instruction::ExecuteConfigUpdate {},
+    )
+}
+
+pub fn build_cancel_config_update_ix(authority: &Pubkey, admin_action_count: u64) -> Instruction {
+    instruction(
+        crate::accounts::CancelConfigUpdate {
+            registry: registry_address(),
+            pending_config: pending_config_address(),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CancelConfigUpdate {},
+    )
+}
+
+/// Builds any setter taking `AdminConfig` from its instruction data, e.g.
+/// `crate::instruction::SetModerator { moderator }`.
+pub fn build_admin_config_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    data: impl InstructionData,
+) -> Instruction {
+    instruction(
+        crate::accounts::AdminConfig {
+            registry: registry_address(),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        data,
+    )
+}
+
+/// As `build_admin_config_ix`, for the setters taking `CoSignedAdminConfig`.
+pub fn build_co_signed_admin_config_ix(
+    authority: &Pubkey,
+    co_authority: Option<&Pubkey>,
+    admin_action_count: u64,
+    data: impl InstructionData,
+) -> Instruction {
+    instruction(
+        crate::accounts::CoSignedAdminConfig {
+            registry: registry_address(),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            co_authority: co_authority.copied(),
+            system_program: system_program::ID,
+        },
+        data,
+    )
+}
+
+pub fn build_schedule_maintenance_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    start_at: i64,
+    end_at: i64,
+) -> Instruction {
+    instruction(
+        schedule_maintenance_accounts(authority, admin_action_count),
+        crate::instruction::ScheduleMaintenance { start_at, end_at },
+    )
+}
+
+pub fn build_cancel_maintenance_ix(authority: &Pubkey, admin_action_count: u64) -> Instruction {
+    instruction(
+        schedule_maintenance_accounts(authority, admin_action_count),
+        crate::instruction::CancelMaintenance {},
+    )
+}
+
+fn schedule_maintenance_accounts(authority: &Pubkey, admin_action_count: u64) -> crate::accounts::ScheduleMaintenance {
+    crate::accounts::ScheduleMaintenance {
+        registry: registry_address(),
+        admin_action: admin_action_address(admin_action_count),
+        authority: *authority,
+        system_program: system_program::ID,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn build_initialize_ix(
+    authority: &Pubkey,
+    verification_fee_lamports: u64,
+    min_behavioral_score: u8,
+    max_session_duration: i64,
+    max_interactions_per_session: u32,
+    min_fee_lamports: u64,
+    max_fee_lamports: u64,
+    treasury: Pubkey,
+) -> Instruction {
+    instruction(
+        crate::accounts::Initialize {
+            registry: registry_address(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::Initialize {
+            verification_fee_lamports,
+            min_behavioral_score,
+            max_session_duration,
+            max_interactions_per_session,
+            min_fee_lamports,
+            max_fee_lamports,
+            treasury,
+        },
+    )
+}
+
+pub fn compromised_address(fingerprint_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CompromisedFingerprint::SEED, fingerprint_hash.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Builds `list_compromised_fingerprint`. An `elected` signer lists as a
+/// verifier and supplies its VerifierAccount; the authority does not.
+pub fn build_list_compromised_fingerprint_ix(
+    signer: &Pubkey,
+    elected: bool,
+    fingerprint_hash: [u8; 32],
+    reason_code: u8,
+) -> Instruction {
+    instruction(
+        crate::accounts::ListCompromisedFingerprint {
+            registry: registry_address(),
+            compromised: compromised_address(&fingerprint_hash),
+            verifier_account: elected.then(|| verifier_account_address(signer)),
+            signer: *signer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::ListCompromisedFingerprint {
+            fingerprint_hash,
+            reason_code,
+        },
+    )
+}
+
+/// Builds `delist_compromised_fingerprint`; `listed_by` gets the rent back.
+pub fn build_delist_compromised_fingerprint_ix(
+    authority: &Pubkey,
+    listed_by: &Pubkey,
+    fingerprint_hash: &[u8; 32],
+) -> Instruction {
+    instruction(
+        crate::accounts::DelistCompromisedFingerprint {
+            registry: registry_address(),
+            compromised: compromised_address(fingerprint_hash),
+            listed_by: *listed_by,
+            authority: *authority,
+        },
+        crate::instruction::DelistCompromisedFingerprint {},
+    )
+}
+
+/// Builds `flag_compromised_record` for `wallet`, whose record carries
+/// `fingerprint_hash`.
+pub fn build_flag_compromised_record_ix(wallet: &Pubkey, fingerprint_hash: &[u8; 32]) -> Instruction {
+    instruction(
+        crate::accounts::FlagCompromisedRecord {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+            compromised: compromised_address(fingerprint_hash),
+        },
+        crate::instruction::FlagCompromisedRecord {},
+    )
+}
+
+pub fn invite_address(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[InviteEntry::SEED, wallet.as_ref()], &crate::ID).0
+}
+
+/// Builds `create_invites` for `wallets`, whose invite PDAs follow as
+/// remaining accounts.
+pub fn build_create_invites_ix(authority: &Pubkey, admin_action_count: u64, wallets: Vec<Pubkey>) -> Instruction {
+    let mut ix = instruction(
+        crate::accounts::CreateInvites {
+            registry: registry_address(),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateInvites { wallets: wallets.clone() },
+    );
+    ix.accounts
+        .extend(wallets.iter().map(|wallet| AccountMeta::new(invite_address(wallet), false)));
+    ix
+}
+
+pub fn build_revoke_invite_ix(authority: &Pubkey, admin_action_count: u64, wallet: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::RevokeInvite {
+            registry: registry_address(),
+            invite: invite_address(wallet),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RevokeInvite {},
+    )
+}
+
+pub fn insurance_fund_address() -> Pubkey {
+    Pubkey::find_program_address(&[InsuranceFund::SEED], &crate::ID).0
+}
+
+pub fn fee_vault_address() -> Pubkey {
+    FeeVault::address()
+}
+
+pub fn build_init_fee_vault_ix(authority: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::InitFeeVault {
+            registry: registry_address(),
+            fee_vault: fee_vault_address(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitFeeVault {},
+    )
+}
+
+/// Builds `withdraw_fees` moving `amount` out of the fee vault.
+pub fn build_withdraw_fees_ix(
+    authority: &Pubkey,
+    co_authority: Option<&Pubkey>,
+    admin_action_count: u64,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        crate::accounts::WithdrawFees {
+            registry: registry_address(),
+            fee_vault: fee_vault_address(),
+            recipient: *recipient,
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            co_authority: co_authority.copied(),
+            system_program: system_program::ID,
+        },
+        crate::instruction::WithdrawFees { amount },
+    )
+}
+
+pub fn insurance_claim_address(appeal: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[InsuranceClaim::SEED, appeal.as_ref()], &crate::ID).0
+}
+
+pub fn build_init_insurance_fund_ix(authority: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::InitInsuranceFund {
+            registry: registry_address(),
+            insurance_fund: insurance_fund_address(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitInsuranceFund {},
+    )
+}
+
+/// Builds `pay_insurance_claim` compensating the approved `appeal`.
+pub fn build_pay_insurance_claim_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    appeal: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+    claim_ref_hash: [u8; 32],
+) -> Instruction {
+    instruction(
+        crate::accounts::PayInsuranceClaim {
+            registry: registry_address(),
+            insurance_fund: insurance_fund_address(),
+            appeal: *appeal,
+            claim: insurance_claim_address(appeal),
+            recipient: *recipient,
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::PayInsuranceClaim { amount, claim_ref_hash },
+    )
+}
+
+pub fn matching_campaign_address(matcher: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[MatchingCampaign::SEED, matcher.as_ref()], &crate::ID).0
+}
+
+pub fn promo_address(code_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[Promo::SEED, code_hash.as_ref()], &crate::ID).0
+}
+
+pub fn promo_redemption_address(promo: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[PromoRedemption::SEED, promo.as_ref(), wallet.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn raffle_epoch_address(epoch: u64) -> Pubkey {
+    Pubkey::find_program_address(&[RaffleEpoch::SEED, &epoch.to_le_bytes()], &crate::ID).0
+}
+
+pub fn raffle_page_address(epoch: u64, page: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[RaffleTicketPage::SEED, &epoch.to_le_bytes(), &page.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn waitlist_entry_address(position: u64) -> Pubkey {
+    Pubkey::find_program_address(&[WaitlistEntry::SEED, &position.to_le_bytes()], &crate::ID).0
+}
+
+pub fn handle_address(handle: &str) -> Pubkey {
+    Pubkey::find_program_address(&[Handle::SEED, &Handle::seed_hash(handle)], &crate::ID).0
+}
+
+pub fn scholarship_pool_address() -> Pubkey {
+    Pubkey::find_program_address(&[ScholarshipPool::SEED], &crate::ID).0
+}
+
+pub fn scholarship_approval_address(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ScholarshipApproval::SEED, wallet.as_ref()], &crate::ID).0
+}
+
+pub fn build_fund_scholarship_pool_ix(donor: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        crate::accounts::FundScholarshipPool {
+            pool: scholarship_pool_address(),
+            donor: *donor,
+            system_program: system_program::ID,
+        },
+        crate::instruction::FundScholarshipPool { amount },
+    )
+}
+
+/// Builds `approve_scholarship` of `wallet`; `officer` is the authority or
+/// the registry's program officer.
+pub fn build_approve_scholarship_ix(officer: &Pubkey, wallet: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::ApproveScholarship {
+            registry: registry_address(),
+            approval: scholarship_approval_address(&wallet),
+            officer: *officer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::ApproveScholarship { wallet },
+    )
+}
+
+pub fn appeal_address(human_record: &Pubkey, revoked_at: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[Appeal::SEED, human_record.as_ref(), &revoked_at.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Builds `file_appeal` against the revocation of `owner`'s record at
+/// `revoked_at`.
+pub fn build_file_appeal_ix(owner: &Pubkey, revoked_at: i64, evidence_hash: [u8; 32]) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::FileAppeal {
+            registry: registry_address(),
+            human_record,
+            appeal: appeal_address(&human_record, revoked_at),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        crate::instruction::FileAppeal { evidence_hash },
+    )
+}
+
+/// Builds `resolve_appeal` for `wallet`'s appeal of the revocation at
+/// `revoked_at`; `region_code` is the one recorded on the human.
+pub fn build_resolve_appeal_ix(
+    arbiter: &Pubkey,
+    wallet: &Pubkey,
+    revoked_at: i64,
+    region_code: u16,
+    approve: bool,
+    note_hash: [u8; 32],
+) -> Instruction {
+    let human_record = human_record_address(wallet);
+    instruction(
+        crate::accounts::ResolveAppeal {
+            registry: registry_address(),
+            human_record,
+            appeal: appeal_address(&human_record, revoked_at),
+            region_stats: region_stats_address(region_code),
+            arbiter: *arbiter,
+        },
+        crate::instruction::ResolveAppeal { approve, note_hash },
+    )
+}
+
+/// Builds `register_human`. Pass `invited` while registration is
+/// permissioned so the user's invite is supplied, and `insured` while an
+/// insurance fee share is set so the insurance fund is. `matcher` selects a
+/// matching campaign to match the fee into the scholarship pool, and
+/// `promo_code` redeems that code's promo.
+#[allow(clippy::too_many_arguments)]
+pub fn build_register_human_ix(
+    user: &Pubkey,
+    fee_receiver: &Pubkey,
+    invited: bool,
+    insured: bool,
+    matcher: Option<&Pubkey>,
+    promo_code: Option<Vec<u8>>,
+    challenge_nonce: [u8; 32],
+    fingerprint_data: Vec<u8>,
+) -> Instruction {
+    let human_record = human_record_address(user);
+    let fingerprint_hash = hash(&fingerprint_data).to_bytes();
+    let promo = promo_code
+        .as_ref()
+        .map(|code| promo_address(&hash(code).to_bytes()));
+    instruction(
+        crate::accounts::RegisterHuman {
+            registry: registry_address(),
+            human_record,
+            fingerprint: fingerprint_address(&human_record),
+            tombstone: tombstone_address(user),
+            invite: invited.then(|| invite_address(user)),
+            compromised: compromised_address(&fingerprint_hash),
+            user: *user,
+            fee_receiver: *fee_receiver,
+            insurance_fund: insured.then(insurance_fund_address),
+            matching_campaign: matcher.map(matching_campaign_address),
+            scholarship_pool: matcher.map(|_| scholarship_pool_address()),
+            promo,
+            promo_redemption: promo.map(|promo| promo_redemption_address(&promo, user)),
+            system_program: system_program::ID,
+        },
+        crate::instruction::RegisterHuman {
+            challenge_nonce,
+            fingerprint_data,
+            promo_code,
+        },
+    )
+}
+
+/// Builds `register_human_scholarship`; the user needs an unexpired
+/// scholarship approval. `invited` and `insured` as for
+/// `build_register_human_ix`.
+pub fn build_register_human_scholarship_ix(
+    user: &Pubkey,
+    fee_receiver: &Pubkey,
+    invited: bool,
+    insured: bool,
+    challenge_nonce: [u8; 32],
+    fingerprint_data: Vec<u8>,
+) -> Instruction {
+    let human_record = human_record_address(user);
+    let fingerprint_hash = hash(&fingerprint_data).to_bytes();
+    instruction(
+        crate::accounts::RegisterHumanScholarship {
+            registry: registry_address(),
+            human_record,
+            fingerprint: fingerprint_address(&human_record),
+            tombstone: tombstone_address(user),
+            invite: invited.then(|| invite_address(user)),
+            compromised: compromised_address(&fingerprint_hash),
+            pool: scholarship_pool_address(),
+            approval: scholarship_approval_address(user),
+            user: *user,
+            fee_receiver: *fee_receiver,
+            insurance_fund: insured.then(insurance_fund_address),
+            system_program: system_program::ID,
+        },
+        crate::instruction::RegisterHumanScholarship {
+            challenge_nonce,
+            fingerprint_data,
+        },
+    )
+}
+
+/// Builds `close_human_record`. `region_code` is the human's recorded
+/// region, `handle` the handle it holds, and `fee_receiver` the fee wallet,
+/// needed while a verifier's share of the fee is still held on the record.
+pub fn build_close_human_record_ix(
+    owner: &Pubkey,
+    region_code: u16,
+    handle: Option<&str>,
+    fee_receiver: Option<&Pubkey>,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::CloseHumanRecord {
+            registry: registry_address(),
+            human_record,
+            fingerprint: fingerprint_address(&human_record),
+            region_stats: region_stats_address(region_code),
+            tombstone: tombstone_address(owner),
+            handle: handle.map(handle_address),
+            owner: *owner,
+            fee_receiver: fee_receiver.copied(),
+            system_program: system_program::ID,
+        },
+        crate::instruction::CloseHumanRecord {},
+    )
+}
+
+/// Builds `revoke_human` for `wallet`, whose record is in `region_code`.
+/// Pass `handle` when the human holds one.
+pub fn build_revoke_human_ix(
+    authority: &Pubkey,
+    wallet: &Pubkey,
+    region_code: u16,
+    admin_action_count: u64,
+    handle: Option<&str>,
+) -> Instruction {
+    instruction(
+        crate::accounts::RevokeHuman {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+            region_stats: region_stats_address(region_code),
+            admin_action: admin_action_address(admin_action_count),
+            handle: handle.map(handle_address),
+            wallet: *wallet,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RevokeHuman {},
+    )
+}
+
+/// Builds `merge_human_records`, folding `duplicate_wallet`'s record into
+/// `primary_wallet`'s; both wallets sign along with the authority.
+/// `duplicate_region_code` is the one recorded on the duplicate.
+pub fn build_merge_human_records_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    primary_wallet: &Pubkey,
+    duplicate_wallet: &Pubkey,
+    duplicate_region_code: u16,
+) -> Instruction {
+    instruction(
+        crate::accounts::MergeHumanRecords {
+            registry: registry_address(),
+            primary: human_record_address(primary_wallet),
+            duplicate: human_record_address(duplicate_wallet),
+            duplicate_region_stats: region_stats_address(duplicate_region_code),
+            admin_action: admin_action_address(admin_action_count),
+            primary_wallet: *primary_wallet,
+            duplicate_wallet: *duplicate_wallet,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::MergeHumanRecords {},
+    )
+}
+
+/// Builds `migrate_human_record` for `wallet`'s record, with `payer`
+/// covering any growth.
+pub fn build_migrate_human_record_ix(payer: &Pubkey, wallet: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::MigrateHumanRecord {
+            human_record: human_record_address(wallet),
+            wallet: *wallet,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::MigrateHumanRecord {},
+    )
+}
+
+/// Builds `migrate_session` for session `session_index` of `owner`, with
+/// `payer` covering any growth.
+pub fn build_migrate_session_ix(payer: &Pubkey, owner: &Pubkey, session_index: u64) -> Instruction {
+    instruction(
+        crate::accounts::MigrateSession {
+            session: session_address(&human_record_address(owner), session_index),
+            owner: *owner,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::MigrateSession {},
+    )
+}
+
+/// Builds `migrate_registry`, with `authority` covering any growth.
+pub fn build_migrate_registry_ix(authority: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::MigrateRegistry {
+            registry: registry_address(),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::MigrateRegistry {},
+    )
+}
+
+/// Builds `aggregate_stats` for StatsBucket `bucket_index`.
+pub fn build_aggregate_stats_ix(bucket_index: u8) -> Instruction {
+    instruction(
+        crate::accounts::AggregateStats {
+            registry: registry_address(),
+            stats_bucket: Pubkey::find_program_address(&[StatsBucket::SEED, &[bucket_index]], &crate::ID).0,
+        },
+        crate::instruction::AggregateStats {},
+    )
+}
+
+pub fn region_stats_address(region_code: u16) -> Pubkey {
+    Pubkey::find_program_address(&[RegionStats::SEED, &region_code.to_le_bytes()], &crate::ID).0
+}
+
+pub fn verifier_stats_address(verifier: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VerifierStats::SEED, verifier.as_ref()], &crate::ID).0
+}
+
+pub fn verifier_account_address(verifier: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VerifierAccount::SEED, verifier.as_ref()], &crate::ID).0
+}
+
+pub fn election_address() -> Pubkey {
+    Pubkey::find_program_address(&[Election::SEED], &crate::ID).0
+}
+
+pub fn election_vault_address() -> Pubkey {
+    Pubkey::find_program_address(&[Election::VAULT_SEED], &crate::ID).0
+}
+
+pub fn candidate_address(epoch: u64, candidate: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[Candidate::SEED, &epoch.to_le_bytes(), candidate.as_ref()], &crate::ID).0
+}
+
+pub fn vote_escrow_address(epoch: u64, candidate: &Pubkey, voter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[VoteEscrow::SEED, &epoch.to_le_bytes(), candidate.as_ref(), voter.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn build_init_election_ix(
+    authority: &Pubkey,
+    governance_mint: &Pubkey,
+    epoch_duration: i64,
+    seats: u8,
+) -> Instruction {
+    instruction(
+        crate::accounts::InitElection {
+            registry: registry_address(),
+            election: election_address(),
+            governance_mint: *governance_mint,
+            vault: election_vault_address(),
+            authority: *authority,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitElection { epoch_duration, seats },
+    )
+}
+
+/// Builds `nominate_verifier` for the election's `current_epoch`.
+pub fn build_nominate_verifier_ix(payer: &Pubkey, epoch: u64, candidate: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::NominateVerifier {
+            election: election_address(),
+            candidate_account: candidate_address(epoch, candidate),
+            verifier_account: verifier_account_address(candidate),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::NominateVerifier { candidate: *candidate },
+    )
+}
+
+/// Builds `vote_for_verifier`, locking `amount` from `voter_token`.
+pub fn build_vote_for_verifier_ix(
+    voter: &Pubkey,
+    voter_token: &Pubkey,
+    epoch: u64,
+    candidate: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        crate::accounts::VoteForVerifier {
+            election: election_address(),
+            candidate_account: candidate_address(epoch, candidate),
+            vote_escrow: vote_escrow_address(epoch, candidate, voter),
+            voter_token: *voter_token,
+            vault: election_vault_address(),
+            voter: *voter,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        crate::instruction::VoteForVerifier { amount },
+    )
+}
+
+/// Builds `elect_verifiers` for `epoch`; `candidates` must list every
+/// nomination of the epoch.
+pub fn build_elect_verifiers_ix(epoch: u64, candidates: &[Pubkey]) -> Instruction {
+    let mut ix = instruction(
+        crate::accounts::ElectVerifiers { election: election_address() },
+        crate::instruction::ElectVerifiers {},
+    );
+    for candidate in candidates {
+        ix.accounts
+            .push(AccountMeta::new_readonly(candidate_address(epoch, candidate), false));
+        ix.accounts
+            .push(AccountMeta::new(verifier_account_address(candidate), false));
+    }
+    ix
+}
+
+pub fn build_retire_verifier_ix(verifier: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::RetireVerifier {
+            election: election_address(),
+            verifier_account: verifier_account_address(verifier),
+        },
+        crate::instruction::RetireVerifier {},
+    )
+}
+
+/// Builds `withdraw_vote` for the vote `voter` cast for `candidate` in
+/// `epoch`, returning the tokens to `voter_token`.
+pub fn build_withdraw_vote_ix(voter: &Pubkey, voter_token: &Pubkey, epoch: u64, candidate: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::WithdrawVote {
+            election: election_address(),
+            vote_escrow: vote_escrow_address(epoch, candidate, voter),
+            voter_token: *voter_token,
+            vault: election_vault_address(),
+            voter: *voter,
+            token_program: anchor_spl::token::ID,
+        },
+        crate::instruction::WithdrawVote {},
+    )
+}
+
+pub fn document_issuer_address(issuer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[DocumentIssuer::SEED, issuer.as_ref()], &crate::ID).0
+}
+
+pub fn document_attestation_address(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[DocumentAttestation::SEED, wallet.as_ref()], &crate::ID).0
+}
+
+pub fn build_set_document_issuer_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    issuer: &Pubkey,
+    issuer_id: u16,
+    approved: bool,
+) -> Instruction {
+    instruction(
+        crate::accounts::SetDocumentIssuer {
+            registry: registry_address(),
+            document_issuer: document_issuer_address(issuer),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SetDocumentIssuer {
+            issuer: *issuer,
+            issuer_id,
+            approved,
+        },
+    )
+}
+
+/// Builds `attest_document`, in which `issuer` attests `wallet`'s document.
+pub fn build_attest_document_ix(
+    issuer: &Pubkey,
+    wallet: &Pubkey,
+    document_commitment: [u8; 32],
+    expires_at: i64,
+) -> Instruction {
+    instruction(
+        crate::accounts::AttestDocument {
+            document_issuer: document_issuer_address(issuer),
+            document_attestation: document_attestation_address(wallet),
+            issuer: *issuer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::AttestDocument {
+            wallet: *wallet,
+            document_commitment,
+            expires_at,
+        },
+    )
+}
+
+/// Builds `verify_human`. Pass `elected` for an elected verifier; the
+/// registry authority verifies without a verifier account.
+/// `fingerprint_hash` is the one stored on the human's record, and
+/// `attestation_issuer` the issuer of the wallet's document attestation,
+/// needed for Full verification only. `supervisor` is
+/// `Pubkey::default()` unless the human is to be supervised. Pass the
+/// registry's `waitlist_count` as `waitlist_position` when the approval will
+/// be waitlisted. `now` picks the day's EpochStats, as for
+/// `epoch_stats_address`.
+#[allow(clippy::too_many_arguments)]
+pub fn build_verify_human_ix(
+    verifier: &Pubkey,
+    elected: bool,
+    wallet: &Pubkey,
+    attestation_issuer: Option<&Pubkey>,
+    fingerprint_hash: &[u8; 32],
+    challenge_response: [u8; 32],
+    behavioral_score: u8,
+    verification_level: u8,
+    region_code: u16,
+    attribute_commitments: [[u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+    supervisor: Pubkey,
+    waitlist_position: Option<u64>,
+    now: i64,
+) -> Instruction {
+    instruction(
+        verify_human_accounts(
+            verifier,
+            elected,
+            wallet,
+            attestation_issuer,
+            fingerprint_hash,
+            region_code,
+            waitlist_position,
+            now,
+        ),
+        crate::instruction::VerifyHuman {
+            challenge_response,
+            behavioral_score,
+            verification_level,
+            region_code,
+            attribute_commitments,
+            supervisor,
+        },
+    )
+}
+
+/// Builds `reject_verification` of `wallet`'s pending verification. Pass
+/// `fee_receiver` while a verifier fee is held on the record.
+pub fn build_reject_verification_ix(
+    verifier: &Pubkey,
+    elected: bool,
+    wallet: &Pubkey,
+    fee_receiver: Option<&Pubkey>,
+    reason_code: u8,
+) -> Instruction {
+    instruction(
+        crate::accounts::RejectVerification {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+            verifier_account: elected.then(|| verifier_account_address(verifier)),
+            verifier_stats: verifier_stats_address(verifier),
+            verifier: *verifier,
+            fee_receiver: fee_receiver.copied(),
+            system_program: system_program::ID,
+        },
+        crate::instruction::RejectVerification { reason_code },
+    )
+}
+
+/// Builds `contest_verification` of `wallet`'s pending verification, by a
+/// verifier other than the one that approved it. `fee_receiver` as for
+/// `build_reject_verification_ix`.
+pub fn build_contest_verification_ix(
+    verifier: &Pubkey,
+    elected: bool,
+    wallet: &Pubkey,
+    fee_receiver: Option<&Pubkey>,
+    reason_code: u8,
+) -> Instruction {
+    instruction(
+        crate::accounts::ContestVerification {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+            verifier_account: elected.then(|| verifier_account_address(verifier)),
+            verifier: *verifier,
+            fee_receiver: fee_receiver.copied(),
+        },
+        crate::instruction::ContestVerification { reason_code },
+    )
+}
+
+/// Builds `verify_human_v2`; the accounts are those of `verify_human`.
+#[allow(clippy::too_many_arguments)]
+pub fn build_verify_human_v2_ix(
+    verifier: &Pubkey,
+    elected: bool,
+    wallet: &Pubkey,
+    attestation_issuer: Option<&Pubkey>,
+    fingerprint_hash: &[u8; 32],
+    challenge_response: [u8; 32],
+    sub_scores: [u8; SUB_SCORE_COUNT],
+    verification_level: u8,
+    region_code: u16,
+    attribute_commitments: [[u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+    supervisor: Pubkey,
+    waitlist_position: Option<u64>,
+    now: i64,
+) -> Instruction {
+    instruction(
+        verify_human_accounts(
+            verifier,
+            elected,
+            wallet,
+            attestation_issuer,
+            fingerprint_hash,
+            region_code,
+            waitlist_position,
+            now,
+        ),
+        crate::instruction::VerifyHumanV2 {
+            challenge_response,
+            sub_scores,
+            verification_level,
+            region_code,
+            attribute_commitments,
+            supervisor,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn verify_human_accounts(
+    verifier: &Pubkey,
+    elected: bool,
+    wallet: &Pubkey,
+    attestation_issuer: Option<&Pubkey>,
+    fingerprint_hash: &[u8; 32],
+    region_code: u16,
+    waitlist_position: Option<u64>,
+    now: i64,
+) -> crate::accounts::VerifyHuman {
+    crate::accounts::VerifyHuman {
+        registry: registry_address(),
+        human_record: human_record_address(wallet),
+        region_stats: region_stats_address(region_code),
+        epoch_stats: epoch_stats_address(now),
+        compromised: compromised_address(fingerprint_hash),
+        document_attestation: attestation_issuer.map(|_| document_attestation_address(wallet)),
+        document_issuer: attestation_issuer.map(document_issuer_address),
+        verifier_account: elected.then(|| verifier_account_address(verifier)),
+        verifier_stats: verifier_stats_address(verifier),
+        waitlist_entry: waitlist_position.map(waitlist_entry_address),
+        verifier: *verifier,
+        system_program: system_program::ID,
+    }
+}
+
+/// Builds `humanity_score` for `wallet`; the score is in the transaction's
+/// return data.
+pub fn build_humanity_score_ix(wallet: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::HumanityScore {
+            human_record: human_record_address(wallet),
+        },
+        crate::instruction::HumanityScore {},
+    )
+}
+
+/// Builds `verify_attribute` for `wallet`'s slot `index`; the result is in
+/// the transaction's return data.
+pub fn build_verify_attribute_ix(
+    wallet: &Pubkey,
+    index: u8,
+    value_hash: [u8; 32],
+    salt: [u8; 32],
+) -> Instruction {
+    instruction(
+        crate::accounts::VerifyAttribute {
+            human_record: human_record_address(wallet),
+        },
+        crate::instruction::VerifyAttribute {
+            index,
+            value_hash,
+            salt,
+        },
+    )
+}
+
+/// Builds `admit_from_waitlist` for the entries from `first_position` on,
+/// each given by its wallet and its approval's region. `now` picks the day's
+/// EpochStats.
+pub fn build_admit_from_waitlist_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    first_position: u64,
+    entries: &[(Pubkey, u16)],
+    now: i64,
+) -> Instruction {
+    let mut ix = instruction(
+        crate::accounts::AdmitFromWaitlist {
+            registry: registry_address(),
+            admin_action: admin_action_address(admin_action_count),
+            epoch_stats: epoch_stats_address(now),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::AdmitFromWaitlist { count: entries.len() as u8 },
+    );
+    for (position, (wallet, region_code)) in (first_position..).zip(entries) {
+        ix.accounts.extend([
+            AccountMeta::new(waitlist_entry_address(position), false),
+            AccountMeta::new(human_record_address(wallet), false),
+            AccountMeta::new(region_stats_address(*region_code), false),
+        ]);
+    }
+    ix
+}
+
+/// Builds a batch verification; `entries` gives each wallet with the
+/// fingerprint hash on its record and its proof. `now` picks the day's
+/// EpochStats.
+pub fn build_verify_humans_batch_ix(
+    verifier: &Pubkey,
+    elected: bool,
+    entries: Vec<(Pubkey, [u8; 32], VerifyEntry)>,
+    now: i64,
+) -> Instruction {
+    let mut ix = instruction(
+        crate::accounts::VerifyHumansBatch {
+            registry: registry_address(),
+            epoch_stats: epoch_stats_address(now),
+            verifier_account: elected.then(|| verifier_account_address(verifier)),
+            verifier_stats: verifier_stats_address(verifier),
+            verifier: *verifier,
+            system_program: system_program::ID,
+        },
+        crate::instruction::VerifyHumansBatch {
+            entries: entries.iter().map(|(_, _, entry)| entry.clone()).collect(),
+        },
+    );
+    ix.accounts.extend(entries.iter().flat_map(|(wallet, fingerprint_hash, entry)| {
+        [
+            AccountMeta::new(human_record_address(wallet), false),
+            AccountMeta::new(region_stats_address(entry.region_code), false),
+            AccountMeta::new_readonly(compromised_address(fingerprint_hash), false),
+        ]
+    }));
+    ix
+}
+
+pub fn build_set_supervisor_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    wallet: &Pubkey,
+    supervisor: Pubkey,
+) -> Instruction {
+    instruction(
+        crate::accounts::SetSupervisor {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SetSupervisor { supervisor },
+    )
+}
+
+/// Builds `clear_supervisor`, signed by both `owner` and `authority`.
+pub fn build_clear_supervisor_ix(owner: &Pubkey, authority: &Pubkey, admin_action_count: u64) -> Instruction {
+    instruction(
+        crate::accounts::ClearSupervisor {
+            registry: registry_address(),
+            human_record: human_record_address(owner),
+            admin_action: admin_action_address(admin_action_count),
+            owner: *owner,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::ClearSupervisor {},
+    )
+}
+
+/// Builds `create_session` for the human's next session. Pass the record's
+/// current `session_count` as `session_index`, and a `goal_type` of 0 with a
+/// `goal_target` of 0 for a session without a goal. A supervised human's
+/// session needs the `supervisor` to co-sign. `booster` is an accepted
+/// booster mint and the owner's token account of it, one token of which is
+/// burned. A nonzero `escrow_rate_per_minute` locks escrow for the longest
+/// possible session. Set `registered_topic` to pass the topic's Topic
+/// account, required while registered topics are enforced. `now` picks the
+/// day's EpochStats.
+#[allow(clippy::too_many_arguments)]
+pub fn build_create_session_ix(
+    owner: &Pubkey,
+    supervisor: Option<&Pubkey>,
+    session_index: u64,
+    personality_id: u8,
+    initial_topic: [u8; 32],
+    goal_type: u8,
+    goal_target: u64,
+    track_in_index: bool,
+    booster: Option<(&Pubkey, &Pubkey)>,
+    escrow_rate_per_minute: u64,
+    registered_topic: bool,
+    now: i64,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::CreateSession {
+            registry: registry_address(),
+            human_record,
+            session,
+            stats_bucket: stats_bucket_address(&session),
+            epoch_stats: epoch_stats_address(now),
+            active_index: track_in_index.then(|| active_index_address(&human_record)),
+            personality_config: personality_config_address(personality_id),
+            owner: *owner,
+            supervisor: supervisor.copied(),
+            booster_mint: booster.map(|(mint, _)| *mint),
+            booster_token: booster.map(|(_, token)| *token),
+            token_program: booster.map(|_| anchor_spl::token::ID),
+            registered_topic: registered_topic.then(|| topic_address(&initial_topic)),
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateSession {
+            personality_id,
+            initial_topic,
+            goal_type,
+            goal_target,
+            escrow_rate_per_minute,
+        },
+    )
+}
+
+pub fn personality_config_address(personality_id: u8) -> Pubkey {
+    Pubkey::find_program_address(&[PersonalityConfig::SEED, &[personality_id]], &crate::ID).0
+}
+
+/// Builds `set_personality_partner`; the default `partner` removes it.
+pub fn build_set_personality_partner_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    personality_id: u8,
+    partner: Pubkey,
+    partner_share_bps: u16,
+) -> Instruction {
+    instruction(
+        crate::accounts::SetPersonalityPartner {
+            registry: registry_address(),
+            personality_config: personality_config_address(personality_id),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SetPersonalityPartner { personality_id, partner, partner_share_bps },
+    )
+}
+
+pub fn build_claim_partner_revenue_ix(partner: &Pubkey, personality_id: u8) -> Instruction {
+    instruction(
+        crate::accounts::ClaimPartnerRevenue {
+            personality_config: personality_config_address(personality_id),
+            partner: *partner,
+        },
+        crate::instruction::ClaimPartnerRevenue {},
+    )
+}
+
+/// Builds `configure_matching` for `matcher`'s campaign.
+pub fn build_configure_matching_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    matcher: Pubkey,
+    ratio_bps: u16,
+    cap_lamports: u64,
+    ends_at: i64,
+) -> Instruction {
+    instruction(
+        crate::accounts::ConfigureMatching {
+            registry: registry_address(),
+            campaign: matching_campaign_address(&matcher),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::ConfigureMatching { matcher, ratio_bps, cap_lamports, ends_at },
+    )
+}
+
+pub fn build_fund_matching_escrow_ix(matcher: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        crate::accounts::FundMatchingEscrow {
+            campaign: matching_campaign_address(matcher),
+            matcher: *matcher,
+            system_program: system_program::ID,
+        },
+        crate::instruction::FundMatchingEscrow { amount },
+    )
+}
+
+pub fn build_reclaim_matching_escrow_ix(matcher: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::ReclaimMatchingEscrow {
+            campaign: matching_campaign_address(matcher),
+            matcher: *matcher,
+        },
+        crate::instruction::ReclaimMatchingEscrow {},
+    )
+}
+
+/// Builds `set_personality_sunset`; a `deprecated_after` of 0 lifts it.
+pub fn build_set_personality_sunset_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    personality_id: u8,
+    deprecated_after: i64,
+) -> Instruction {
+    instruction(
+        crate::accounts::SetPersonalitySunset {
+            registry: registry_address(),
+            personality_config: personality_config_address(personality_id),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SetPersonalitySunset { personality_id, deprecated_after },
+    )
+}
+
+pub fn personality_stats_address(personality_id: u8) -> Pubkey {
+    Pubkey::find_program_address(&[PersonalityStats::SEED, &[personality_id]], &crate::ID).0
+}
+
+pub fn topic_address(topic: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[Topic::SEED, topic.as_ref()], &crate::ID).0
+}
+
+pub fn topic_progress_address(human_record: &Pubkey, topic: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[TopicProgress::SEED, human_record.as_ref(), topic.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// Builds `register_topic`; `curator` is the authority or the registry's
+/// curator.
+pub fn build_register_topic_ix(curator: &Pubkey, topic: [u8; 32], metadata_hash: [u8; 32]) -> Instruction {
+    instruction(
+        crate::accounts::RegisterTopic {
+            registry: registry_address(),
+            registered_topic: topic_address(&topic),
+            curator: *curator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RegisterTopic { topic, metadata_hash },
+    )
+}
+
+pub fn build_set_topic_enabled_ix(curator: &Pubkey, topic: [u8; 32], enabled: bool) -> Instruction {
+    instruction(
+        crate::accounts::SetTopicEnabled {
+            registry: registry_address(),
+            registered_topic: topic_address(&topic),
+            curator: *curator,
+        },
+        crate::instruction::SetTopicEnabled { enabled },
+    )
+}
+
+/// Builds `update_topic`. Set `registered_topic` to pass the topic's Topic
+/// account, required while registered topics are enforced.
+pub fn build_update_topic_ix(owner: &Pubkey, session_index: u64, topic: [u8; 32], registered_topic: bool) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::UpdateTopic {
+            registry: registry_address(),
+            human_record,
+            session: session_address(&human_record, session_index),
+            registered_topic: registered_topic.then(|| topic_address(&topic)),
+            owner: *owner,
+        },
+        crate::instruction::UpdateTopic { topic },
+    )
+}
+
+pub fn build_init_topic_progress_ix(owner: &Pubkey, topic: [u8; 32]) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::InitTopicProgress {
+            human_record,
+            topic_progress: topic_progress_address(&human_record, &topic),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        crate::instruction::InitTopicProgress { topic },
+    )
+}
+
+pub fn highlights_address(human_record: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[Highlights::SEED, human_record.as_ref()], &crate::ID).0
+}
+
+/// Builds `pin_highlight` for the log at `interaction_index` of the owner's
+/// session `session_index`.
+pub fn build_pin_highlight_ix(owner: &Pubkey, session_index: u64, interaction_index: u32) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::PinHighlight {
+            human_record,
+            session,
+            interaction: interaction_address(&session, interaction_index),
+            highlights: highlights_address(&human_record),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        crate::instruction::PinHighlight {},
+    )
+}
+
+pub fn build_unpin_highlight_ix(owner: &Pubkey, session: Pubkey, interaction_index: u32) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::UnpinHighlight {
+            human_record,
+            highlights: highlights_address(&human_record),
+            owner: *owner,
+        },
+        crate::instruction::UnpinHighlight { session, interaction_index },
+    )
+}
+
+pub fn personality_treasury_address(personality_id: u8) -> Pubkey {
+    Pubkey::find_program_address(&[PersonalityTreasury::SEED, &[personality_id]], &crate::ID).0
+}
+
+pub fn build_tip_personality_ix(tipper: &Pubkey, personality_id: u8, amount: u64) -> Instruction {
+    instruction(
+        crate::accounts::TipPersonality {
+            personality_config: personality_config_address(personality_id),
+            treasury: personality_treasury_address(personality_id),
+            tipper: *tipper,
+            system_program: system_program::ID,
+        },
+        crate::instruction::TipPersonality { personality_id, amount },
+    )
+}
+
+pub fn build_withdraw_personality_tips_ix(
+    authority: &Pubkey,
+    personality_id: u8,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        crate::accounts::WithdrawPersonalityTips {
+            registry: registry_address(),
+            treasury: personality_treasury_address(personality_id),
+            recipient: *recipient,
+            authority: *authority,
+        },
+        crate::instruction::WithdrawPersonalityTips { amount },
+    )
+}
+
+pub fn milestone_address(milestone_id: u16) -> Pubkey {
+    Pubkey::find_program_address(&[Milestone::SEED, &milestone_id.to_le_bytes()], &crate::ID).0
+}
+
+pub fn milestone_vault_address(milestone_id: u16) -> Pubkey {
+    Pubkey::find_program_address(&[Milestone::VAULT_SEED, &milestone_id.to_le_bytes()], &crate::ID).0
+}
+
+pub fn vesting_address(human_record: &Pubkey, milestone_id: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[Vesting::SEED, human_record.as_ref(), &milestone_id.to_le_bytes()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn session_template_address(template_id: u16) -> Pubkey {
+    Pubkey::find_program_address(&[SessionTemplate::SEED, &template_id.to_le_bytes()], &crate::ID).0
+}
+
+/// Builds `create_template`; `curator` is the authority or the registry's
+/// curator.
+pub fn build_create_template_ix(
+    curator: &Pubkey,
+    template_id: u16,
+    personality_id: u8,
+    topic: [u8; 32],
+    duration_override: i64,
+    scoring_profile: u8,
+) -> Instruction {
+    instruction(
+        crate::accounts::CreateTemplate {
+            registry: registry_address(),
+            template: session_template_address(template_id),
+            curator: *curator,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateTemplate {
+            template_id,
+            personality_id,
+            topic,
+            duration_override,
+            scoring_profile,
+        },
+    )
+}
+
+pub fn build_set_template_enabled_ix(curator: &Pubkey, template_id: u16, enabled: bool) -> Instruction {
+    instruction(
+        crate::accounts::SetTemplateEnabled {
+            registry: registry_address(),
+            template: session_template_address(template_id),
+            curator: *curator,
+        },
+        crate::instruction::SetTemplateEnabled { enabled },
+    )
+}
+
+/// Builds `create_session_from_template` for the human's next session, at
+/// `session_index`. `personality_id` must be the template's. `now` picks the
+/// day's EpochStats.
+pub fn build_create_session_from_template_ix(
+    owner: &Pubkey,
+    session_index: u64,
+    template_id: u16,
+    personality_id: u8,
+    now: i64,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::CreateSessionFromTemplate {
+            registry: registry_address(),
+            template: session_template_address(template_id),
+            human_record,
+            session,
+            stats_bucket: stats_bucket_address(&session),
+            epoch_stats: epoch_stats_address(now),
+            active_index: None,
+            personality_config: personality_config_address(personality_id),
+            owner: *owner,
+            supervisor: None,
+            system_program: system_program::ID,
+        },
+        crate::instruction::CreateSessionFromTemplate { template_id },
+    )
+}
+
+/// Builds `close_session`. `personality_id` must be the session's current
+/// personality; `closer` is the owner or the human's supervisor.
+/// `fee_receiver` is needed when the session holds escrow. `raffle` is the
+/// current raffle epoch and its latest page, to enter the human.
+#[allow(clippy::too_many_arguments)]
+pub fn build_close_session_ix(
+    owner: &Pubkey,
+    closer: &Pubkey,
+    session_index: u64,
+    personality_id: u8,
+    track_in_index: bool,
+    fee_receiver: Option<&Pubkey>,
+    raffle: Option<(u64, u32)>,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::CloseSession {
+            registry: registry_address(),
+            human_record,
+            session,
+            active_index: track_in_index.then(|| active_index_address(&human_record)),
+            stats_bucket: stats_bucket_address(&session),
+            personality_stats: personality_stats_address(personality_id),
+            owner: *owner,
+            closer: *closer,
+            fee_receiver: fee_receiver.copied(),
+            raffle_epoch: raffle.map(|(epoch, _)| raffle_epoch_address(epoch)),
+            raffle_page: raffle.map(|(epoch, page)| raffle_page_address(epoch, page)),
+            personality_config: personality_config_address(personality_id),
+            system_program: system_program::ID,
+        },
+        crate::instruction::CloseSession {},
+    )
+}
+
+/// Builds `extend_session`; a supervised human's `supervisor` co-signs.
+pub fn build_extend_session_ix(
+    owner: &Pubkey,
+    supervisor: Option<&Pubkey>,
+    session_index: u64,
+    additional_duration: i64,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::ExtendSession {
+            registry: registry_address(),
+            human_record,
+            session: session_address(&human_record, session_index),
+            owner: *owner,
+            supervisor: supervisor.copied(),
+        },
+        crate::instruction::ExtendSession { additional_duration },
+    )
+}
+
+/// Builds `reopen_session` for an expired session with personality
+/// `personality_id`. `fee_receiver` is the registry's fee wallet; pass
+/// `insured` while an insurance fee share is set.
+pub fn build_reopen_session_ix(
+    owner: &Pubkey,
+    session_index: u64,
+    personality_id: u8,
+    fee_receiver: &Pubkey,
+    insured: bool,
+    additional_duration: i64,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::ReopenSession {
+            registry: registry_address(),
+            human_record,
+            session: session_address(&human_record, session_index),
+            owner: *owner,
+            fee_receiver: *fee_receiver,
+            insurance_fund: insured.then(insurance_fund_address),
+            personality_config: personality_config_address(personality_id),
+            system_program: system_program::ID,
+        },
+        crate::instruction::ReopenSession { additional_duration },
+    )
+}
+
+/// Builds `force_close_session`, signed by the authority or moderator.
+pub fn build_force_close_session_ix(
+    moderator: &Pubkey,
+    owner: &Pubkey,
+    session_index: u64,
+    track_in_index: bool,
+    reason_code: u8,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::ForceCloseSession {
+            registry: registry_address(),
+            human_record,
+            session,
+            active_index: track_in_index.then(|| active_index_address(&human_record)),
+            stats_bucket: stats_bucket_address(&session),
+            moderator: *moderator,
+        },
+        crate::instruction::ForceCloseSession { reason_code },
+    )
+}
+
+pub fn build_admin_force_close_session_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    owner: &Pubkey,
+    session_index: u64,
+    track_in_index: bool,
+    reason_code: u8,
+    forfeit_score: bool,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::AdminForceCloseSession {
+            registry: registry_address(),
+            human_record,
+            session,
+            active_index: track_in_index.then(|| active_index_address(&human_record)),
+            stats_bucket: stats_bucket_address(&session),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::AdminForceCloseSession { reason_code, forfeit_score },
+    )
+}
+
+/// Builds `open_raffle_page`, adding page `page` (the epoch's current page
+/// count) to the raffle of `epoch`.
+pub fn build_open_raffle_page_ix(payer: &Pubkey, epoch: u64, page: u32) -> Instruction {
+    instruction(
+        crate::accounts::OpenRafflePage {
+            raffle_epoch: raffle_epoch_address(epoch),
+            raffle_page: raffle_page_address(epoch, page),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::OpenRafflePage { epoch },
+    )
+}
+
+/// Builds `fund_raffle`, adding `amount` lamports to the prize of `epoch`.
+pub fn build_fund_raffle_ix(funder: &Pubkey, epoch: u64, amount: u64) -> Instruction {
+    instruction(
+        crate::accounts::FundRaffle {
+            raffle_epoch: raffle_epoch_address(epoch),
+            funder: *funder,
+            system_program: system_program::ID,
+        },
+        crate::instruction::FundRaffle { amount },
+    )
+}
+
+/// Builds `commit_raffle` for the ended raffle of `epoch`.
+pub fn build_commit_raffle_ix(epoch: u64) -> Instruction {
+    instruction(
+        crate::accounts::CommitRaffle { raffle_epoch: raffle_epoch_address(epoch) },
+        crate::instruction::CommitRaffle {},
+    )
+}
+
+/// Builds `draw_raffle` for the committed raffle of `epoch`, passing the page
+/// expected to hold the winning ticket.
+pub fn build_draw_raffle_ix(epoch: u64, winning_page: u32) -> Instruction {
+    instruction(
+        crate::accounts::DrawRaffle {
+            raffle_epoch: raffle_epoch_address(epoch),
+            winning_page: raffle_page_address(epoch, winning_page),
+            slot_hashes: sysvar::slot_hashes::ID,
+        },
+        crate::instruction::DrawRaffle {},
+    )
+}
+
+/// Builds `claim_raffle_prize` for the drawn raffle of `epoch`.
+pub fn build_claim_raffle_prize_ix(winner: &Pubkey, epoch: u64) -> Instruction {
+    instruction(
+        crate::accounts::ClaimRafflePrize {
+            raffle_epoch: raffle_epoch_address(epoch),
+            winner: *winner,
+        },
+        crate::instruction::ClaimRafflePrize {},
+    )
+}
+
+/// Builds `recompute_session_score`, passing the interaction logs of indices
+/// below `interaction_count`.
+pub fn build_recompute_session_score_ix(
+    authority: &Pubkey,
+    admin_action_count: u64,
+    owner: &Pubkey,
+    session_index: u64,
+    interaction_count: u32,
+    target_scoring_profile: Option<u8>,
+) -> Instruction {
+    let session = session_address(&human_record_address(owner), session_index);
+    let mut ix = instruction(
+        crate::accounts::RecomputeSessionScore {
+            registry: registry_address(),
+            session,
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RecomputeSessionScore { target_scoring_profile },
+    );
+    ix.accounts.extend(
+        (0..interaction_count)
+            .map(|index| AccountMeta::new_readonly(interaction_address(&session, index), false)),
+    );
+    ix
+}
+
+/// Builds `assert_human_invariants`, passing the addresses of every session
+/// index below `session_count`.
+pub fn build_assert_human_invariants_ix(wallet: &Pubkey, session_count: u64) -> Instruction {
+    let human_record = human_record_address(wallet);
+    let mut ix = instruction(
+        crate::accounts::AssertHumanInvariants { human_record },
+        crate::instruction::AssertHumanInvariants {},
+    );
+    ix.accounts.extend(
+        (0..session_count)
+            .map(|index| AccountMeta::new_readonly(session_address(&human_record, index), false)),
+    );
+    ix
+}
+
+/// Builds `prove_liveness` for `owner`. A challenge must come with the
+/// co-signing `verifier`, flagged `elected` unless it is the authority.
+pub fn build_prove_liveness_ix(
+    owner: &Pubkey,
+    verifier: Option<(&Pubkey, bool)>,
+    challenge: Option<LivenessChallenge>,
+) -> Instruction {
+    instruction(
+        crate::accounts::ProveLiveness {
+            registry: registry_address(),
+            human_record: human_record_address(owner),
+            verifier_account: verifier
+                .filter(|(_, elected)| *elected)
+                .map(|(verifier, _)| verifier_account_address(verifier)),
+            verifier: verifier.map(|(verifier, _)| *verifier),
+            owner: *owner,
+        },
+        crate::instruction::ProveLiveness { challenge },
+    )
+}
+
+/// Builds `apply_liveness_decay` for `wallet`'s record.
+pub fn build_apply_liveness_decay_ix(wallet: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::ApplyLivenessDecay {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+        },
+        crate::instruction::ApplyLivenessDecay {},
+    )
+}
+
+/// Builds `freeze_human` suspending `wallet` until `until`.
+pub fn build_freeze_human_ix(authority: &Pubkey, admin_action_count: u64, wallet: &Pubkey, until: i64) -> Instruction {
+    instruction(
+        crate::accounts::FreezeHuman {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::FreezeHuman { until },
+    )
+}
+
+pub fn build_unfreeze_human_ix(authority: &Pubkey, admin_action_count: u64, wallet: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::FreezeHuman {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+            admin_action: admin_action_address(admin_action_count),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        crate::instruction::UnfreezeHuman {},
+    )
+}
+
+/// Builds `expire_verification` for `wallet`'s record.
+pub fn build_expire_verification_ix(wallet: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::ExpireVerification {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+        },
+        crate::instruction::ExpireVerification {},
+    )
+}
+
+/// Builds `verification_level` for `wallet`; the result is in the
+/// transaction's return data.
+pub fn build_verification_level_ix(wallet: &Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::GetVerificationLevel {
+            registry: registry_address(),
+            human_record: human_record_address(wallet),
+        },
+        crate::instruction::VerificationLevel {},
+    )
+}
+
+/// Builds `claim_handle` for `owner`.
+pub fn build_claim_handle_ix(owner: &Pubkey, handle: &str) -> Instruction {
+    instruction(
+        crate::accounts::ClaimHandle {
+            human_record: human_record_address(owner),
+            handle_account: handle_address(handle),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        crate::instruction::ClaimHandle {
+            handle: handle.to_string(),
+        },
+    )
+}
+
+/// Builds `release_handle` for `owner`'s current `handle`.
+pub fn build_release_handle_ix(owner: &Pubkey, handle: &str) -> Instruction {
+    instruction(
+        crate::accounts::ReleaseHandle {
+            human_record: human_record_address(owner),
+            handle_account: handle_address(handle),
+            owner: *owner,
+        },
+        crate::instruction::ReleaseHandle {},
+    )
+}
+
+/// Builds `resolve_handle`; the holder's wallet is in the transaction's
+/// return data.
+pub fn build_resolve_handle_ix(handle: &str) -> Instruction {
+    instruction(
+        crate::accounts::ResolveHandle {
+            handle_account: handle_address(handle),
+        },
+        crate::instruction::ResolveHandle {
+            handle: handle.to_string(),
+        },
+    )
+}
+
+/// Builds `finalize_session`. Topic progress accounts are not included.
+pub fn build_finalize_session_ix(owner: &Pubkey, session_index: u64) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::FinalizeSession {
+            human_record,
+            session: session_address(&human_record, session_index),
+        },
+        crate::instruction::FinalizeSession {},
+    )
+}
+
+/// Builds `contest_settlement` of a settling session, by the authority or,
+/// with `elected`, a verifier.
+pub fn build_contest_settlement_ix(
+    verifier: &Pubkey,
+    elected: bool,
+    owner: &Pubkey,
+    session_index: u64,
+    reason_code: u8,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::ContestSettlement {
+            registry: registry_address(),
+            human_record,
+            session: session_address(&human_record, session_index),
+            verifier_account: elected.then(|| verifier_account_address(verifier)),
+            verifier: *verifier,
+        },
+        crate::instruction::ContestSettlement { reason_code },
+    )
+}
+
+/// Builds `record_interaction`. Pass the session's current
+/// `interaction_count` as `interaction_index`, and zeros for
+/// `content_uri_hash` and `content_size` to omit the content reference.
+/// Set `oracle_cosigned` while companion co-signing is on; the transaction
+/// must then carry the companion's ed25519 instruction. `private` records
+/// the interaction in privacy mode, where `content_hash` may be all zeros.
+/// `now` picks the day's EpochStats.
+#[allow(clippy::too_many_arguments)]
+pub fn build_record_interaction_ix(
+    user: &Pubkey,
+    session_index: u64,
+    interaction_index: u32,
+    content_hash: [u8; 32],
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+    content_uri_hash: [u8; 32],
+    content_size: u32,
+    oracle_cosigned: bool,
+    private: bool,
+    now: i64,
+) -> Instruction {
+    let human_record = human_record_address(user);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::RecordInteraction {
+            registry: registry_address(),
+            human_record,
+            session,
+            interaction: interaction_address(&session, interaction_index),
+            stats_bucket: stats_bucket_address(&session),
+            epoch_stats: epoch_stats_address(now),
+            instructions: oracle_cosigned.then_some(sysvar::instructions::ID),
+            user: *user,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RecordInteraction {
+            content_hash,
+            interaction_type,
+            score,
+            duration_seconds,
+            content_uri_hash,
+            content_size,
+            private,
+        },
+    )
+}
+
+/// Builds `record_interaction_v2`; the accounts, `oracle_cosigned` and
+/// `now` are as for `build_record_interaction_ix`.
+pub fn build_record_interaction_v2_ix(
+    user: &Pubkey,
+    session_index: u64,
+    interaction_index: u32,
+    input: InteractionInputV2,
+    oracle_cosigned: bool,
+    now: i64,
+) -> Instruction {
+    let human_record = human_record_address(user);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::RecordInteraction {
+            registry: registry_address(),
+            human_record,
+            session,
+            interaction: interaction_address(&session, interaction_index),
+            stats_bucket: stats_bucket_address(&session),
+            epoch_stats: epoch_stats_address(now),
+            instructions: oracle_cosigned.then_some(sysvar::instructions::ID),
+            user: *user,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RecordInteractionV2 { input },
+    )
+}
+
+/// Builds `record_unlogged_interaction`; `oracle_cosigned` and `now` as for
+/// `build_record_interaction_ix`.
+#[allow(clippy::too_many_arguments)]
+pub fn build_record_unlogged_interaction_ix(
+    user: &Pubkey,
+    session_index: u64,
+    content_hash: [u8; 32],
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+    oracle_cosigned: bool,
+    now: i64,
+) -> Instruction {
+    let human_record = human_record_address(user);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::RecordUnloggedInteraction {
+            registry: registry_address(),
+            human_record,
+            session,
+            stats_bucket: stats_bucket_address(&session),
+            epoch_stats: epoch_stats_address(now),
+            instructions: oracle_cosigned.then_some(sysvar::instructions::ID),
+            user: *user,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RecordUnloggedInteraction {
+            content_hash,
+            interaction_type,
+            score,
+            duration_seconds,
+        },
+    )
+}
+
+/// Builds `submit_interaction_bundle` for `bundle`, signed as
+/// `bundle_message`. The transaction must carry the matching ed25519
+/// instruction before it. `payer` submits it, and `now` picks the day's
+/// EpochStats.
+pub fn build_submit_interaction_bundle_ix(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    session_index: u64,
+    bundle: Vec<InteractionInput>,
+    bundle_signature: [u8; 64],
+    now: i64,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::SubmitInteractionBundle {
+            registry: registry_address(),
+            human_record,
+            session,
+            stats_bucket: stats_bucket_address(&session),
+            epoch_stats: epoch_stats_address(now),
+            instructions: sysvar::instructions::ID,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SubmitInteractionBundle { bundle, bundle_signature },
+    )
+}
+
+/// Builds `set_spending_cap`; a `lamports_per_30d` of 0 removes the cap.
+pub fn build_set_spending_cap_ix(owner: &Pubkey, lamports_per_30d: u64) -> Instruction {
+    instruction(
+        crate::accounts::SetSpendingCap {
+            human_record: human_record_address(owner),
+            owner: *owner,
+        },
+        crate::instruction::SetSpendingCap { lamports_per_30d },
+    )
+}
+
+pub fn build_set_interaction_delegate_ix(owner: &Pubkey, delegate: Pubkey) -> Instruction {
+    instruction(
+        crate::accounts::SetInteractionDelegate {
+            human_record: human_record_address(owner),
+            owner: *owner,
+        },
+        crate::instruction::SetInteractionDelegate { delegate },
+    )
+}
+
+pub fn build_reap_interaction_ix(
+    owner: &Pubkey,
+    session_index: u64,
+    interaction_index: u32,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    let session = session_address(&human_record, session_index);
+    instruction(
+        crate::accounts::ReapInteraction {
+            human_record,
+            session,
+            interaction: interaction_address(&session, interaction_index),
+            owner: *owner,
+        },
+        crate::instruction::ReapInteraction {},
+    )
+}
+
+/// Builds `rate_session`. `personality_id` must be the personality the
+/// session closed with.
+pub fn build_rate_session_ix(
+    owner: &Pubkey,
+    session_index: u64,
+    personality_id: u8,
+    rating: u8,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::RateSession {
+            human_record,
+            session: session_address(&human_record, session_index),
+            personality_stats: personality_stats_address(personality_id),
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        crate::instruction::RateSession { rating },
+    )
+}
+
+/// Builds `reclaim_session`. `personality_id` must be the session's
+/// personality; `fee_receiver` is needed when the session still holds
+/// escrow.
+pub fn build_reclaim_session_ix(
+    owner: &Pubkey,
+    session_index: u64,
+    personality_id: u8,
+    track_in_index: bool,
+    fee_receiver: Option<&Pubkey>,
+) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::ReclaimSession {
+            registry: registry_address(),
+            human_record,
+            session: session_address(&human_record, session_index),
+            active_index: track_in_index.then(|| active_index_address(&human_record)),
+            owner: *owner,
+            fee_receiver: fee_receiver.copied(),
+            personality_config: personality_config_address(personality_id),
+        },
+        crate::instruction::ReclaimSession {},
+    )
+}
+
+/// Builds `preview_score` for a session of `owner`; the increment comes back
+/// as little-endian `u64` return data when simulated.
+pub fn build_preview_score_ix(
+    owner: &Pubkey,
+    session_index: u64,
+    score: u8,
+    interaction_type: u8,
+    duration_seconds: u32,
+    private: bool,
+) -> Instruction {
+    instruction(
+        crate::accounts::PreviewScore {
+            registry: registry_address(),
+            session: session_address(&human_record_address(owner), session_index),
+        },
+        crate::instruction::PreviewScore {
+            score,
+            interaction_type,
+            duration_seconds,
+            private,
+        },
+    )
+}
+
+pub fn build_heartbeat_session_ix(owner: &Pubkey, session_index: u64) -> Instruction {
+    let human_record = human_record_address(owner);
+    instruction(
+        crate::accounts::HeartbeatSession {
+            registry: registry_address(),
+            human_record,
+            session: session_address(&human_record, session_index),
+            owner: *owner,
+        },
+        crate::instruction::HeartbeatSession {},
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::AccountMeta;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{Harness, NOW};
+
+    /// Anchor's instruction data: the `global:<name>` sighash, then the
+    /// Borsh-encoded arguments.
+    fn data(name: &str, args: impl AnchorSerialize) -> Vec<u8> {
+        let mut data = hash(format!("global:{name}").as_bytes()).to_bytes()[..8].to_vec();
+        args.serialize(&mut data).unwrap();
+        data
+    }
+
+    /// An absent optional account, which Anchor passes as the program id.
+    fn absent() -> AccountMeta {
+        AccountMeta::new_readonly(crate::ID, false)
+    }
+
+    /// Runs `ix` on a fresh bank started from `accounts` and returns the
+    /// raw data of `outputs` afterwards.
+    fn run(accounts: Vec<FixtureAccount>, ix: Instruction, signer: &Keypair, outputs: &[Pubkey]) -> Vec<Vec<u8>> {
+        let mut harness = Harness::start(accounts);
+        harness.fund(&signer.pubkey(), 10_000_000_000);
+        harness.process(ix, &[signer]).unwrap();
+        outputs
+            .iter()
+            .map(|address| harness.raw_account(address))
+            .collect()
+    }
+
+    #[test]
+    fn register_human_matches_a_hand_built_instruction() {
+        let authority = Keypair::new();
+        let user = Keypair::new();
+        let accounts = FixtureBuilder::new().with_authority(authority.pubkey()).build();
+        let challenge_nonce = [7u8; 32];
+        let fingerprint_data = vec![9u8; 48];
+
+        let human_record = Pubkey::find_program_address(
+            &[HumanRecord::SEED, user.pubkey().as_ref()],
+            &crate::ID,
+        )
+        .0;
+        let fingerprint =
+            Pubkey::find_program_address(&[FingerprintData::SEED, human_record.as_ref()], &crate::ID).0;
+        let hand_built = Instruction {
+            program_id: crate::ID,
+            accounts: vec![
+                AccountMeta::new(Pubkey::find_program_address(&[PlatformRegistry::SEED], &crate::ID).0, false),
+                AccountMeta::new(human_record, false),
+                AccountMeta::new(fingerprint, false),
+                AccountMeta::new(
+                    Pubkey::find_program_address(&[Tombstone::SEED, user.pubkey().as_ref()], &crate::ID).0,
+                    false,
+                ),
+                absent(),
+                AccountMeta::new_readonly(
+                    Pubkey::find_program_address(
+                        &[CompromisedFingerprint::SEED, hash(&fingerprint_data).as_ref()],
+                        &crate::ID,
+                    )
+                    .0,
+                    false,
+                ),
+                AccountMeta::new(user.pubkey(), true),
+                AccountMeta::new(authority.pubkey(), false),
+                absent(),
+                absent(),
+                absent(),
+                absent(),
+                absent(),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data: data(
+                "register_human",
+                (challenge_nonce, fingerprint_data.clone(), None::<Vec<u8>>),
+            ),
+        };
+        let built = build_register_human_ix(
+            &user.pubkey(),
+            &authority.pubkey(),
+            false,
+            false,
+            None,
+            None,
+            challenge_nonce,
+            fingerprint_data,
+        );
+        assert_eq!(built, hand_built);
+
+        let outputs = [human_record, fingerprint];
+        assert_eq!(
+            run(accounts.clone(), built, &user, &outputs),
+            run(accounts, hand_built, &user, &outputs)
+        );
+    }
+
+    #[test]
+    fn create_session_matches_a_hand_built_instruction() {
+        let owner = Keypair::new();
+        let accounts = FixtureBuilder::new().with_verified_human(owner.pubkey()).build();
+        let initial_topic = [2u8; 32];
+
+        let human_record = Pubkey::find_program_address(
+            &[HumanRecord::SEED, owner.pubkey().as_ref()],
+            &crate::ID,
+        )
+        .0;
+        let session = Pubkey::find_program_address(
+            &[SessionAccount::SEED, human_record.as_ref(), &0u64.to_le_bytes()],
+            &crate::ID,
+        )
+        .0;
+        let hand_built = Instruction {
+            program_id: crate::ID,
+            accounts: vec![
+                AccountMeta::new_readonly(
+                    Pubkey::find_program_address(&[PlatformRegistry::SEED], &crate::ID).0,
+                    false,
+                ),
+                AccountMeta::new(human_record, false),
+                AccountMeta::new(session, false),
+                AccountMeta::new(stats_bucket_address(&session), false),
+                AccountMeta::new(epoch_stats_address(NOW), false),
+                absent(),
+                AccountMeta::new_readonly(
+                    Pubkey::find_program_address(&[PersonalityConfig::SEED, &[0]], &crate::ID).0,
+                    false,
+                ),
+                AccountMeta::new(owner.pubkey(), true),
+                absent(),
+                absent(),
+                absent(),
+                absent(),
+                absent(),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data: data("create_session", (0u8, initial_topic, 0u8, 0u64, 0u64)),
+        };
+        let built = build_create_session_ix(
+            &owner.pubkey(),
+            None,
+            0,
+            0,
+            initial_topic,
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        );
+        assert_eq!(built, hand_built);
+
+        let outputs = [human_record, session];
+        assert_eq!(
+            run(accounts.clone(), built, &owner, &outputs),
+            run(accounts, hand_built, &owner, &outputs)
+        );
+    }
+}
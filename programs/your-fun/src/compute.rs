@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+#[cfg(target_os = "solana")]
+use crate::error::YourFunError;
+
+/// Budget agreed for `record_interaction`, the highest-volume instruction,
+/// in a build without the `verbose-logs` feature.
+pub const RECORD_INTERACTION_BUDGET_CU: u32 = 20_000;
+
+/// Compute-unit ceilings per instruction, kept well under what mobile wallets
+/// request by default. Benchmarks compare measured consumption against these.
+pub const CU_CEILINGS: &[(&str, u32)] = &[
+    ("register_human", 60_000),
+    ("verify_human", 30_000),
+    ("verify_humans_batch", 400_000),
+    ("create_session", 40_000),
+    ("record_interaction", RECORD_INTERACTION_BUDGET_CU),
+    ("record_unlogged_interaction", 25_000),
+];
+
+/// Units `verify_human` must still have before it starts writing the record.
+pub const VERIFY_HUMAN_WRITE_RESERVE_CU: u64 = 5_000;
+/// Units `record_interaction` must still have before it mutates any account.
+pub const RECORD_INTERACTION_WRITE_RESERVE_CU: u64 = 8_000;
+
+/// Returns the ceiling for the named instruction, if one is defined.
+pub fn cu_ceiling(instruction: &str) -> Option<u32> {
+    CU_CEILINGS
+        .iter()
+        .find(|(name, _)| *name == instruction)
+        .map(|(_, ceiling)| *ceiling)
+}
+
+/// Fails with `InsufficientComputeBudget` unless at least `reserve` units
+/// remain, so an instruction aborts before its writes rather than midway.
+/// Off-chain builds have no meter, so the check is a no-op there.
+pub fn require_remaining_compute_units(reserve: u64) -> Result<()> {
+    #[cfg(target_os = "solana")]
+    require!(
+        solana_program::compute_units::sol_remaining_compute_units() >= reserve,
+        YourFunError::InsufficientComputeBudget
+    );
+
+    #[cfg(not(target_os = "solana"))]
+    let _ = reserve;
+
+    Ok(())
+}
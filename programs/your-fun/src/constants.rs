@@ -0,0 +1,54 @@
+//! Seeds, sizes, and limits for integrators. Values are taken from the
+//! program's own definitions so they cannot drift from what it enforces.
+
+use crate::state::{
+    ActiveSessionIndex, FingerprintData, HumanRecord, InteractionLog, InteractionType,
+    PlatformRegistry, RegionStats, SessionAccount, StatsBucket, Tombstone,
+};
+
+pub const REGISTRY_SEED: &[u8] = PlatformRegistry::SEED;
+pub const HUMAN_SEED: &[u8] = HumanRecord::SEED;
+pub const FINGERPRINT_SEED: &[u8] = FingerprintData::SEED;
+pub const TOMBSTONE_SEED: &[u8] = Tombstone::SEED;
+pub const SESSION_SEED: &[u8] = SessionAccount::SEED;
+pub const ACTIVE_SESSION_INDEX_SEED: &[u8] = ActiveSessionIndex::SEED;
+pub const INTERACTION_SEED: &[u8] = InteractionLog::SEED;
+pub const STATS_SEED: &[u8] = StatsBucket::SEED;
+pub const REGION_SEED: &[u8] = RegionStats::SEED;
+
+/// Length of content hashes, challenge nonces, and fingerprint hashes.
+pub const HASH_LEN: usize = 32;
+/// Highest score accepted for an interaction or behavioral analysis.
+pub const MAX_SCORE: u8 = 100;
+/// Highest valid `InteractionType` discriminant.
+pub const MAX_INTERACTION_TYPE: u8 = InteractionType::Review as u8;
+/// Denominator of every basis-point setting.
+pub const MAX_BPS: u16 = 10_000;
+
+pub const DEFAULT_MAX_FINGERPRINT_LEN: u16 = PlatformRegistry::DEFAULT_MAX_FINGERPRINT_LEN;
+pub const MAX_FINGERPRINT_LEN_CEILING: u16 = PlatformRegistry::MAX_FINGERPRINT_LEN_CEILING;
+pub const STATS_BUCKET_COUNT: u8 = StatsBucket::COUNT;
+pub const ACTIVE_SESSION_INDEX_CAPACITY: usize = ActiveSessionIndex::CAPACITY;
+pub const MAX_REGION_CODE: u16 = RegionStats::MAX_REGION_CODE;
+
+/// Size of the Anchor account discriminator preceding every account.
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// Byte offset of `HumanRecord::wallet`, counted from the start of the
+/// account data (discriminator included), for memcmp filters.
+pub const HUMAN_RECORD_WALLET_OFFSET: usize = DISCRIMINATOR_LEN;
+/// Byte offset of `HumanRecord::flags`. Mirrored by the SDKs.
+pub const HUMAN_RECORD_FLAGS_OFFSET: usize = DISCRIMINATOR_LEN + 32 + 32 + 8 + 1 + 32;
+/// Byte offset of `SessionAccount::human_record`.
+pub const SESSION_HUMAN_RECORD_OFFSET: usize = DISCRIMINATOR_LEN;
+/// Byte offset of `SessionAccount::owner`.
+pub const SESSION_OWNER_OFFSET: usize = DISCRIMINATOR_LEN + 32;
+/// Byte offset of `SessionAccount::flags`. Mirrored by the SDKs.
+pub const SESSION_FLAGS_OFFSET: usize = DISCRIMINATOR_LEN + 32 + 32 + 8 + 8 + 8 + 8;
+/// Byte offset of `PlatformRegistry::state_version`. Guaranteed stable: the
+/// fields before it are never reordered or resized.
+pub const REGISTRY_STATE_VERSION_OFFSET: usize = 141;
+/// Byte offset of `PlatformRegistry::treasury`. Mirrored by the SDKs.
+pub const REGISTRY_TREASURY_OFFSET: usize = 905;
+/// Byte offset of `InteractionLog::session`.
+pub const INTERACTION_SESSION_OFFSET: usize = DISCRIMINATOR_LEN;
@@ -0,0 +1,149 @@
+//! Challenge hashing shared by the program and off-chain verifiers.
+//! Free of Anchor types so it builds unchanged for any target.
+
+/// Computes a deterministic challenge hash from the nonce and fingerprint.
+/// The fingerprint is the SHA-256 of the full registered fingerprint data, so
+/// every byte of it influences the result.
+/// Uses XOR folding as a lightweight on-chain hash combination.
+#[inline]
+pub fn compute_challenge_hash(nonce: &[u8; 32], fingerprint: &[u8; 32]) -> [u8; 32] {
+    let mut result = [0u8; 32];
+    for i in 0..32 {
+        result[i] = nonce[i] ^ fingerprint[i];
+        result[i] = result[i].wrapping_add(nonce[(i + 7) % 32]);
+        result[i] ^= fingerprint[(i + 13) % 32];
+    }
+
+    for round in 0..4 {
+        for i in 0..32 {
+            let prev = result[(i + 31) % 32];
+            let next = result[(i + 1) % 32];
+            result[i] = result[i]
+                .wrapping_add(prev.wrapping_mul(next))
+                .wrapping_add(round as u8);
+        }
+    }
+
+    result
+}
+
+/// Domain separator at the start of every companion message.
+pub const COMPANION_MESSAGE_DOMAIN: &[u8; 18] = b"your-fun:companion";
+
+/// Length of the message built by `companion_message`.
+pub const COMPANION_MESSAGE_LEN: usize = 18 + 32 + 32 + 4 + 32 + 1 + 1 + 4;
+
+/// Serializes the interaction the companion oracle co-signs: the domain,
+/// the session address and its current `session_nonce`, then the
+/// interaction's index, content hash, type, score and duration, with
+/// integers little-endian. The backend must sign exactly these bytes.
+pub fn companion_message(
+    session: &[u8; 32],
+    session_nonce: &[u8; 32],
+    interaction_index: u32,
+    content_hash: &[u8; 32],
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+) -> [u8; COMPANION_MESSAGE_LEN] {
+    let mut message = [0u8; COMPANION_MESSAGE_LEN];
+    let parts: [&[u8]; 8] = [
+        COMPANION_MESSAGE_DOMAIN,
+        session,
+        session_nonce,
+        &interaction_index.to_le_bytes(),
+        content_hash,
+        &[interaction_type],
+        &[score],
+        &duration_seconds.to_le_bytes(),
+    ];
+    let mut offset = 0;
+    for part in parts {
+        message[offset..offset + part.len()].copy_from_slice(part);
+        offset += part.len();
+    }
+    message
+}
+
+/// Advances a session's interaction hash chain: the SHA-256 of the previous
+/// head, then the interaction's index, content hash, score and timestamp,
+/// with integers little-endian. A session's chain starts from all zeros, so
+/// replaying its interactions in order from there reproduces `chain_head`,
+/// and each log's `prev_chain_head` lets any contiguous slice be checked.
+pub fn next_chain_head(
+    prev_chain_head: &[u8; 32],
+    interaction_index: u32,
+    content_hash: &[u8; 32],
+    score: u8,
+    timestamp: i64,
+) -> [u8; 32] {
+    solana_program::hash::hashv(&[
+        prev_chain_head,
+        &interaction_index.to_le_bytes(),
+        content_hash,
+        &[score],
+        &timestamp.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes32(hex: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    fn counting(start: u8) -> [u8; 32] {
+        std::array::from_fn(|i| start + i as u8)
+    }
+
+    #[test]
+    fn challenge_hash_known_answers() {
+        let cases = [
+            (
+                counting(0),
+                counting(32),
+                "6cd154bbbc05a427dce154a30cddc4ff7c19c43bbcd504d7bc9134d364256324",
+            ),
+            (
+                [0u8; 32],
+                [0u8; 32],
+                "602b68f9884f20bdc063d8c15857302550aba8e9285fc00d6073f89178076bce",
+            ),
+            (
+                [0xff; 32],
+                [0x5a; 32],
+                "688998c30855f0bff091b8ebd8cd603760a9e8230825d0af504198db601d37b0",
+            ),
+        ];
+        for (nonce, fingerprint, expected) in cases {
+            assert_eq!(compute_challenge_hash(&nonce, &fingerprint), bytes32(expected));
+        }
+    }
+
+    #[test]
+    fn chain_head_known_answer() {
+        assert_eq!(
+            next_chain_head(&[0u8; 32], 0, &[0x11; 32], 80, 1_700_000_000),
+            bytes32("58e0c27b8de01d8466e6bd8d60abd95ca08d0792ca37a57d1f1742c26803de5c")
+        );
+    }
+
+    #[test]
+    fn companion_message_layout() {
+        let message = companion_message(&[1; 32], &[2; 32], 7, &[3; 32], 1, 90, 300);
+        assert_eq!(&message[..18], COMPANION_MESSAGE_DOMAIN);
+        assert_eq!(message[18..50], [1; 32]);
+        assert_eq!(message[50..82], [2; 32]);
+        assert_eq!(message[82..86], 7u32.to_le_bytes());
+        assert_eq!(message[86..118], [3; 32]);
+        assert_eq!(message[118..120], [1, 90]);
+        assert_eq!(message[120..], 300u32.to_le_bytes());
+    }
+}
@@ -58,4 +58,445 @@ pub enum YourFunError {
 
     #[msg("Numerical overflow occurred during computation")]
     NumericalOverflow,
+
+    #[msg("The challenge nonce must not be all zeros")]
+    ZeroChallengeNonce,
+
+    #[msg("The fingerprint hash must not be all zeros")]
+    ZeroFingerprintHash,
+
+    #[msg("The challenge nonce must differ from the fingerprint hash")]
+    NonceMatchesFingerprint,
+
+    #[msg("The fee receiver must be a system-owned wallet with no data or the program treasury")]
+    InvalidFeeReceiver,
+
+    #[msg("This wallet closed its human record too recently to register again")]
+    ReregistrationCooldownActive,
+
+    #[msg("The provided configuration value is out of the allowed range")]
+    InvalidConfigValue,
+
+    #[msg("The fingerprint data must not be empty")]
+    FingerprintEmpty,
+
+    #[msg("The session must be closed before its account can be reclaimed")]
+    SessionStillActive,
+
+    #[msg("The session still has interaction logs that must be reaped first")]
+    SessionHasOpenLogs,
+
+    #[msg("This human record has not been verified")]
+    HumanNotVerified,
+
+    #[msg("The account is not a valid legacy account for this migration")]
+    InvalidLegacyAccount,
+
+    #[msg("The account has already been migrated to the current layout")]
+    AccountAlreadyMigrated,
+
+    #[msg("The stats bucket index is out of range")]
+    InvalidStatsBucket,
+
+    #[msg("Interactions scoring below the log threshold must be recorded unlogged")]
+    InteractionBelowLogThreshold,
+
+    #[msg("Interactions scoring at or above the log threshold must be logged")]
+    InteractionRequiresLog,
+
+    #[msg("Not enough compute units left to complete this instruction")]
+    InsufficientComputeBudget,
+
+    #[msg("The batch is empty, too large, or does not match the supplied accounts")]
+    InvalidBatchSize,
+
+    #[msg("A batch account is not a writable HumanRecord at its expected address")]
+    InvalidBatchAccount,
+
+    #[msg("The active session index is full; close or prune sessions first")]
+    ActiveSessionIndexFull,
+
+    #[msg("Invalid interaction type")]
+    InvalidInteractionType,
+
+    #[msg("Invalid session close reason")]
+    InvalidCloseReason,
+
+    #[msg("The registry configuration is frozen")]
+    ConfigFrozen,
+
+    #[msg("Session heartbeats are rate limited; try again later")]
+    HeartbeatTooSoon,
+
+    #[msg("The fee is outside the registry's fee bounds")]
+    FeeOutOfBounds,
+
+    #[msg("The signer is not an approved verifier")]
+    UnauthorizedVerifier,
+
+    #[msg("The election epoch has ended")]
+    ElectionEpochEnded,
+
+    #[msg("The election epoch has not ended yet")]
+    ElectionEpochNotEnded,
+
+    #[msg("The candidates supplied do not match the epoch's nominations")]
+    InvalidCandidateSet,
+
+    #[msg("Votes can only be withdrawn after their epoch has been elected")]
+    VoteStillLocked,
+
+    #[msg("The vote amount must be greater than zero")]
+    InvalidVoteAmount,
+
+    #[msg("The verifier was seated by the most recent election")]
+    VerifierStillSeated,
+
+    #[msg("Registration is invite-only and no invite was provided")]
+    InviteRequired,
+
+    #[msg("The invite has already been used")]
+    InviteAlreadyConsumed,
+
+    #[msg("The region code is not a valid ISO 3166 numeric code")]
+    InvalidRegionCode,
+
+    #[msg("Verification is not available in this region")]
+    RegionNotAllowed,
+
+    #[msg("The companion personality has been retired for new sessions")]
+    PersonalityDeprecated,
+
+    #[msg("The platform is down for scheduled maintenance")]
+    MaintenanceWindow,
+
+    #[msg("A maintenance window is already scheduled")]
+    MaintenanceAlreadyScheduled,
+
+    #[msg("No maintenance window is pending")]
+    NoMaintenanceScheduled,
+
+    #[msg("The human's verification has lapsed and must be renewed")]
+    VerificationLapsed,
+
+    #[msg("The behavioral fingerprint is on the compromised list")]
+    FingerprintCompromised,
+
+    #[msg("Legacy record imports are not open")]
+    LegacyImportClosed,
+
+    #[msg("The human record is not revoked")]
+    HumanNotRevoked,
+
+    #[msg("The appeal window for this revocation has closed")]
+    AppealWindowClosed,
+
+    #[msg("The appeal has already been resolved")]
+    AppealAlreadyResolved,
+
+    #[msg("Full verification requires a document attestation")]
+    DocumentAttestationRequired,
+
+    #[msg("The document attestation has expired")]
+    DocumentAttestationExpired,
+
+    #[msg("The document attestation is bound to a different wallet")]
+    AttestationWalletMismatch,
+
+    #[msg("The document issuer is not approved")]
+    DocumentIssuerNotApproved,
+
+    #[msg("The attribute index is out of range")]
+    InvalidAttributeIndex,
+
+    #[msg("Sub-scores must be between 0 and 100")]
+    InvalidSubScore,
+
+    #[msg("Sub-score weights must sum to 10000 basis points")]
+    InvalidSubScoreWeights,
+
+    #[msg("The human's verification has not taken effect yet")]
+    VerificationPending,
+
+    #[msg("The verification is no longer inside its contest window")]
+    ContestWindowClosed,
+
+    #[msg("The human record was merged into another record")]
+    RecordMerged,
+
+    #[msg("The record still has open sessions")]
+    OpenSessionsRemain,
+
+    #[msg("Unknown scoring profile")]
+    InvalidScoringProfile,
+
+    #[msg("The session template is disabled")]
+    TemplateDisabled,
+
+    #[msg("Unknown goal type, or a goal target of 0 or above its cap")]
+    InvalidGoal,
+
+    #[msg("The session is still running or its reopen grace window has passed")]
+    ReopenWindowClosed,
+
+    #[msg("The session has been reopened the maximum number of times")]
+    ReopenLimitReached,
+
+    #[msg("The session already covers the maximum number of topics")]
+    TopicLimitReached,
+
+    #[msg("TopicProgress account does not belong to this human or session")]
+    InvalidTopicProgress,
+
+    #[msg("The human's supervisor must co-sign this instruction")]
+    SupervisorSignatureRequired,
+
+    #[msg("An interaction bundle must hold between 1 and MAX_BUNDLE_LEN entries")]
+    InvalidBundleLength,
+
+    #[msg("Bundle timestamps must strictly increase, after the session's last activity")]
+    BundleOutOfOrder,
+
+    #[msg("A bundle timestamp lies outside the session's active window")]
+    BundleTimestampOutOfWindow,
+
+    #[msg("No matching ed25519 signature of the bundle by the owner or delegate")]
+    InvalidBundleSignature,
+
+    #[msg("A content reference needs both a URI hash and a size within the limit")]
+    InvalidContentReference,
+
+    #[msg("This interaction is already pinned")]
+    HighlightAlreadyPinned,
+
+    #[msg("The highlights list is full")]
+    HighlightsFull,
+
+    #[msg("This interaction is not pinned")]
+    HighlightNotFound,
+
+    #[msg("Missing companion oracle co-signature over the session nonce")]
+    CompanionCosignatureRequired,
+
+    #[msg("The session is not awaiting settlement")]
+    SessionNotSettling,
+
+    #[msg("The settlement window has not ended yet")]
+    SettlementWindowOpen,
+
+    #[msg("The settlement window has ended")]
+    SettlementWindowClosed,
+
+    #[msg("Learning score is below the milestone threshold")]
+    MilestoneNotReached,
+
+    #[msg("No newly vested tokens to claim")]
+    NothingVested,
+
+    #[msg("This mint is not an accepted booster")]
+    BoosterNotAccepted,
+
+    #[msg("Booster requires its mint, a token account and the token program")]
+    InvalidBooster,
+
+    #[msg("The booster list is full")]
+    BoosterListFull,
+
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+
+    #[msg("Withdrawal exceeds the available balance")]
+    InsufficientFunds,
+
+    #[msg("No unexpired scholarship approval for this wallet")]
+    ScholarshipNotApproved,
+
+    #[msg("This scholarship approval has already been used")]
+    ScholarshipAlreadyUsed,
+
+    #[msg("The scholarship pool cannot cover the verification fee")]
+    ScholarshipPoolExhausted,
+
+    #[msg("The insurance fund must be supplied while an insurance fee share is set")]
+    InsuranceFundRequired,
+
+    #[msg("Insurance claims require an approved appeal")]
+    AppealNotApproved,
+
+    #[msg("The fee receiver must be supplied to settle an escrow")]
+    FeeReceiverRequired,
+
+    #[msg("No partner revenue has accrued")]
+    NoPartnerRevenue,
+
+    #[msg("The raffle epoch is not the current one")]
+    RaffleEpochMismatch,
+
+    #[msg("The raffle ticket page is full or not the latest")]
+    RafflePageFull,
+
+    #[msg("The latest raffle ticket page still has room")]
+    RafflePageNotFull,
+
+    #[msg("The raffle epoch has not ended")]
+    RaffleEpochOpen,
+
+    #[msg("The raffle has no tickets")]
+    RaffleNoTickets,
+
+    #[msg("The raffle draw is already committed")]
+    RaffleAlreadyCommitted,
+
+    #[msg("The raffle's draw slot has not been reached")]
+    RaffleDrawNotReady,
+
+    #[msg("The raffle's draw slot hash is no longer available; commit again")]
+    RaffleCommitmentExpired,
+
+    #[msg("The raffle has already been drawn")]
+    RaffleAlreadyDrawn,
+
+    #[msg("The ticket page does not hold the winning ticket")]
+    RaffleWrongPage,
+
+    #[msg("The raffle prize has already been claimed")]
+    RafflePrizeAlreadyClaimed,
+
+    #[msg("The matching campaign has not ended")]
+    MatchingCampaignActive,
+
+    #[msg("The promo code does not match the promo")]
+    InvalidPromoCode,
+
+    #[msg("The promo code has expired")]
+    PromoExpired,
+
+    #[msg("The promo code has no uses left")]
+    PromoExhausted,
+
+    #[msg("A promo requires its code and redemption marker")]
+    PromoCodeRequired,
+
+    #[msg("Invariant violated: the sessions passed must number session_count")]
+    AuditSessionCountMismatch,
+
+    #[msg("Invariant violated: a session account is missing or out of index order")]
+    AuditSessionIndexMismatch,
+
+    #[msg("Invariant violated: a session does not belong to this human")]
+    AuditSessionOwnerMismatch,
+
+    #[msg("Invariant violated: active_session_count differs from the open sessions")]
+    AuditActiveSessionDrift,
+
+    #[msg("Invariant violated: total_interactions differs from the sessions' interactions")]
+    AuditInteractionDrift,
+
+    #[msg("Invariant violated: learning_score is below the settled session scores")]
+    AuditScoreDrift,
+
+    #[msg("Spend would exceed the spending cap for the current window")]
+    SpendingCapExceeded,
+
+    #[msg("Topic is not registered")]
+    TopicNotRegistered,
+
+    #[msg("Topic is disabled")]
+    TopicDisabled,
+
+    #[msg("Every interaction log of the session must be passed, in order")]
+    MissingInteractionLog,
+
+    #[msg("Sessions that covered more than one topic cannot be rescored")]
+    ScoreRecomputeUnsupported,
+
+    #[msg("Instruction input version is not supported by this program")]
+    UnsupportedInputVersion,
+
+    #[msg("Verification cap reached; the approval must be waitlisted")]
+    VerificationCapReached,
+
+    #[msg("Human is already waitlisted")]
+    AlreadyWaitlisted,
+
+    #[msg("Waitlist entry must be passed exactly when the approval is waitlisted")]
+    InvalidWaitlistEntry,
+
+    #[msg("Liveness decay is not due for this record")]
+    LivenessNotDue,
+
+    #[msg("A verifier-co-signed challenge is required to prove liveness")]
+    LivenessChallengeRequired,
+
+    #[msg("Liveness challenge nonce must not be reused")]
+    StaleLivenessChallenge,
+
+    #[msg("Handle must be 3 to 20 characters of a-z, 0-9 and underscore")]
+    InvalidHandle,
+
+    #[msg("Human already holds a handle")]
+    HandleAlreadyClaimed,
+
+    #[msg("The human's Handle account must be passed to release it")]
+    HandleAccountRequired,
+
+    #[msg("Fee receiver does not match the registry's treasury")]
+    TreasuryMismatch,
+
+    #[msg("The queued config update's timelock has not elapsed")]
+    ConfigTimelockNotElapsed,
+
+    #[msg("Config changes go through the timelock queue while it is set")]
+    ConfigTimelocked,
+
+    #[msg("The co-authority must co-sign this instruction")]
+    CoAuthorityRequired,
+
+    #[msg("Co-signer is not the configured co-authority")]
+    CoAuthorityMismatch,
+
+    #[msg("Fee schedule tiers must start at 0 and ascend strictly")]
+    InvalidFeeSchedule,
+
+    #[msg("Fee change exceeds the registry's maximum delta")]
+    FeeDeltaTooLarge,
+
+    #[msg("The fee update cooldown has not elapsed")]
+    FeeUpdateTooSoon,
+
+    #[msg("Epoch is not the current stats epoch")]
+    StatsEpochMismatch,
+
+    #[msg("Human record is temporarily frozen")]
+    HumanRecordFrozen,
+
+    #[msg("Human record is not frozen")]
+    HumanRecordNotFrozen,
+
+    #[msg("The human already has the maximum number of sessions open")]
+    TooManyActiveSessions,
+
+    #[msg("Interaction follows the previous one too closely")]
+    InteractionTooSoon,
+
+    #[msg("Interaction is shorter than the registry minimum duration")]
+    InteractionTooShort,
+
+    #[msg("Account layout version does not match the program; migrate it first")]
+    AccountVersionMismatch,
+
+    #[msg("The human's verification has not lapsed")]
+    VerificationNotLapsed,
+
+    #[msg("Rating must be between 1 and 5")]
+    InvalidRating,
+
+    #[msg("The session has already been rated")]
+    SessionAlreadyRated,
+
+    #[msg("Minimum behavioral score must be between 0 and 100")]
+    InvalidScoreConfig,
+
+    #[msg("The minimum fee exceeds the maximum fee")]
+    InvalidFeeBounds,
 }
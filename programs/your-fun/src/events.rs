@@ -0,0 +1,616 @@
+use anchor_lang::prelude::*;
+use crate::state::ConfigUpdate;
+
+/// Emitted when a wallet initiates human registration.
+#[event]
+pub struct HumanRegistered {
+    pub wallet: Pubkey,
+    pub human_record: Pubkey,
+    pub fee_paid: u64,
+    pub registered_at: i64,
+}
+
+/// Emitted when a session is closed and its score settled.
+#[event]
+pub struct SessionClosed {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_index: u64,
+    pub session_score: u64,
+    pub settled_score: u64,
+    pub last_interaction_at: i64,
+    pub closed_at: i64,
+    pub close_reason: u8,
+    /// Whether the session met its goal; false for sessions without one.
+    pub goal_met: bool,
+    /// Part of `settled_score` awarded for meeting the goal.
+    pub goal_bonus: u64,
+    /// Score held back for `finalize_session`; 0 when settled at close.
+    pub pending_score: u64,
+    /// When the pending score may be finalized (0 when settled at close).
+    pub settles_at: i64,
+    /// Final head of the session's interaction hash chain.
+    pub chain_head: [u8; 32],
+}
+
+/// Emitted when an escrowed session is billed and the rest refunded.
+#[event]
+pub struct SessionEscrowSettled {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_index: u64,
+    pub billed: u64,
+    pub refunded: u64,
+}
+
+/// Emitted when a settling session's pending score reaches the human.
+#[event]
+pub struct SessionFinalized {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_index: u64,
+    pub settled_score: u64,
+    pub finalized_at: i64,
+}
+
+/// Emitted when a settling session's pending score is contested and
+/// forfeited.
+#[event]
+pub struct SettlementContested {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_index: u64,
+    pub contested_by: Pubkey,
+    pub reason_code: u8,
+    pub forfeited_score: u64,
+    pub contested_at: i64,
+}
+
+/// Emitted once per submitted offline interaction bundle, after the
+/// per-entry `InteractionRecorded` events.
+#[event]
+pub struct InteractionBundleSubmitted {
+    pub session: Pubkey,
+    /// The owner or delegate key that signed the bundle.
+    pub signer: Pubkey,
+    pub bundle_hash: [u8; 32],
+    pub entries: u8,
+    pub score_increment: u64,
+}
+
+/// Emitted when a human's supervisor is set or cleared.
+#[event]
+pub struct SupervisorChanged {
+    pub wallet: Pubkey,
+    /// The new supervisor; `Pubkey::default()` when cleared.
+    pub supervisor: Pubkey,
+    pub changed_by: Pubkey,
+}
+
+/// Emitted when an expired session is reopened for a fee.
+#[event]
+pub struct SessionReopened {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_index: u64,
+    pub reopen_count: u8,
+    pub fee_paid: u64,
+    pub expires_at: i64,
+}
+
+/// Emitted when a HumanRecord is closed and a tombstone is written.
+#[event]
+pub struct HumanRecordClosed {
+    pub wallet: Pubkey,
+    pub learning_score: u64,
+    pub strikes: u8,
+    pub closed_at: i64,
+}
+
+/// Emitted when a closed session's account is reclaimed, preserving its
+/// terminal state for indexers.
+#[event]
+pub struct SessionReclaimed {
+    pub session: Pubkey,
+    pub human_record: Pubkey,
+    pub owner: Pubkey,
+    pub session_index: u64,
+    pub interaction_count: u32,
+    pub session_score: u64,
+    pub closed_at: i64,
+    pub close_reason: u8,
+}
+
+/// Emitted when an owner rates a closed session.
+#[event]
+pub struct SessionRated {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub personality_id: u8,
+    pub rating: u8,
+    pub rated_at: i64,
+}
+
+/// Emitted when the authority revokes a verified human.
+#[event]
+pub struct HumanRevoked {
+    pub wallet: Pubkey,
+    pub revoked_by: Pubkey,
+    pub revoked_at: i64,
+}
+
+/// Emitted when the authority suspends a human until `frozen_until`.
+#[event]
+pub struct HumanFrozen {
+    pub wallet: Pubkey,
+    pub frozen_until: i64,
+    pub frozen_by: Pubkey,
+}
+
+/// Emitted when the authority lifts a freeze early.
+#[event]
+pub struct HumanUnfrozen {
+    pub wallet: Pubkey,
+    pub unfrozen_by: Pubkey,
+    pub unfrozen_at: i64,
+}
+
+/// Emitted when a moderator terminates a session.
+#[event]
+pub struct SessionForceClosed {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub moderator: Pubkey,
+    pub reason_code: u8,
+    pub session_score: u64,
+    pub settled_score: u64,
+    pub closed_at: i64,
+}
+
+/// Emitted for interactions recorded without an InteractionLog account.
+#[event]
+pub struct InteractionRecorded {
+    pub session: Pubkey,
+    pub user: Pubkey,
+    pub interaction_index: u32,
+    pub content_hash: [u8; 32],
+    pub interaction_type: u8,
+    pub score: u8,
+    pub duration_seconds: u32,
+    pub score_increment: u64,
+    pub timestamp: i64,
+    /// The session's chain head before this interaction, standing in for
+    /// the log's `prev_chain_head`.
+    pub prev_chain_head: [u8; 32],
+}
+
+/// Emitted when the registry is paused or resumed.
+#[event]
+pub struct RegistryPauseChanged {
+    pub paused: bool,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
+
+/// Emitted when the set of individually paused operations changes.
+#[event]
+pub struct RegistryPauseFlagsChanged {
+    pub pause_flags: u8,
+    pub changed_by: Pubkey,
+    pub changed_at: i64,
+}
+
+/// Emitted when a personality's sunset is set or cleared.
+#[event]
+pub struct PersonalityDeprecated {
+    pub personality_id: u8,
+    pub deprecated_after: i64,
+    pub set_by: Pubkey,
+}
+
+/// Emitted when a maintenance window is scheduled, or cancelled (zeros).
+#[event]
+pub struct MaintenanceWindowChanged {
+    pub start_at: i64,
+    pub end_at: i64,
+    pub changed_by: Pubkey,
+}
+
+/// Emitted the first time an instruction observes a human in a new
+/// verification status.
+#[event]
+pub struct VerificationStatusChanged {
+    pub wallet: Pubkey,
+    pub previous_status: u8,
+    pub status: u8,
+    pub observed_at: i64,
+}
+
+/// Emitted when a fingerprint hash is added to or removed from the
+/// compromised list.
+#[event]
+pub struct CompromisedFingerprintChanged {
+    pub fingerprint_hash: [u8; 32],
+    pub listed: bool,
+    pub reason_code: u8,
+    pub changed_by: Pubkey,
+}
+
+/// Emitted when a verified record is flagged because its fingerprint is listed.
+#[event]
+pub struct ReverificationRequired {
+    pub wallet: Pubkey,
+    pub fingerprint_hash: [u8; 32],
+    pub flagged_at: i64,
+}
+
+/// Emitted when a HumanRecord is imported from the previous deployment.
+#[event]
+pub struct LegacyRecordImported {
+    pub wallet: Pubkey,
+    pub legacy_record: Pubkey,
+    pub imported_at: i64,
+}
+
+/// Emitted when a verifier rejects a pending verification.
+#[event]
+pub struct VerificationRejected {
+    pub wallet: Pubkey,
+    pub verifier: Pubkey,
+    pub reason_code: u8,
+    pub rejected_at: i64,
+}
+
+/// Emitted when an approval pays the verifier its share of the
+/// registration fee.
+#[event]
+pub struct VerifierFeePaid {
+    pub wallet: Pubkey,
+    pub verifier: Pubkey,
+    pub amount: u64,
+    pub paid_at: i64,
+}
+
+/// Emitted when a revoked human files an appeal.
+#[event]
+pub struct AppealFiled {
+    pub wallet: Pubkey,
+    pub appeal: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub filed_at: i64,
+}
+
+/// Emitted when the arbiter decides an appeal.
+#[event]
+pub struct AppealResolved {
+    pub wallet: Pubkey,
+    pub appeal: Pubkey,
+    pub approved: bool,
+    pub note_hash: [u8; 32],
+    pub resolved_at: i64,
+}
+
+/// Emitted when the authority approves or removes a document issuer.
+#[event]
+pub struct DocumentIssuerChanged {
+    pub issuer: Pubkey,
+    pub issuer_id: u16,
+    pub approved: bool,
+}
+
+/// Emitted when an issuer attests a wallet's identity document.
+#[event]
+pub struct DocumentAttested {
+    pub wallet: Pubkey,
+    pub issuer: Pubkey,
+    pub issuer_id: u16,
+    pub document_commitment: [u8; 32],
+    pub expires_at: i64,
+}
+
+/// Emitted when a pending verification is contested and voided.
+#[event]
+pub struct VerificationContested {
+    pub wallet: Pubkey,
+    pub verifier: Pubkey,
+    pub contested_by: Pubkey,
+    pub reason_code: u8,
+    pub contested_at: i64,
+}
+
+/// Emitted when a duplicate HumanRecord is merged into its primary.
+#[event]
+pub struct HumanRecordsMerged {
+    pub primary: Pubkey,
+    pub duplicate: Pubkey,
+    pub learning_score_moved: u64,
+    pub merged_at: i64,
+}
+
+/// Emitted when a human claims a milestone reward and its vesting starts.
+#[event]
+pub struct MilestoneClaimed {
+    pub wallet: Pubkey,
+    pub milestone_id: u16,
+    pub total_amount: u64,
+    pub cliff_at: i64,
+    pub vests_until: i64,
+}
+
+/// Emitted when vested milestone tokens are released.
+#[event]
+pub struct VestedTokensClaimed {
+    pub wallet: Pubkey,
+    pub milestone_id: u16,
+    pub amount: u64,
+    pub claimed_so_far: u64,
+}
+
+/// Emitted when a user tips a personality.
+#[event]
+pub struct PersonalityTipped {
+    pub personality_id: u8,
+    pub tipper: Pubkey,
+    pub amount: u64,
+    pub total_tips: u64,
+}
+
+/// Emitted when tips are withdrawn from a personality treasury.
+#[event]
+pub struct PersonalityTipsWithdrawn {
+    pub personality_id: u8,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when lamports are donated to the scholarship pool.
+#[event]
+pub struct ScholarshipPoolFunded {
+    pub donor: Pubkey,
+    pub amount: u64,
+    pub total_donated: u64,
+}
+
+/// Emitted when a wallet is approved for a scholarship.
+#[event]
+pub struct ScholarshipApproved {
+    pub wallet: Pubkey,
+    pub approved_by: Pubkey,
+    pub expires_at: i64,
+}
+
+/// Emitted when a registration's fee is paid from the scholarship pool.
+#[event]
+pub struct ScholarshipRedeemed {
+    pub wallet: Pubkey,
+    pub fee_paid: u64,
+}
+
+/// Emitted when the insurance fund pays a claim.
+#[event]
+pub struct InsuranceClaimPaid {
+    pub appeal: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub claim_ref_hash: [u8; 32],
+    pub total_out: u64,
+}
+
+/// Emitted when the authority sets or clears a personality's partner.
+#[event]
+pub struct PersonalityPartnerSet {
+    pub personality_id: u8,
+    pub partner: Pubkey,
+    pub partner_share_bps: u16,
+}
+
+/// Emitted when a partner's share of a session fee is accrued.
+#[event]
+pub struct PartnerRevenueAccrued {
+    pub personality_id: u8,
+    pub partner: Pubkey,
+    pub amount: u64,
+    pub partner_accrued: u64,
+}
+
+/// Emitted when a partner claims its accrued revenue.
+#[event]
+pub struct PartnerRevenueClaimed {
+    pub personality_id: u8,
+    pub partner: Pubkey,
+    pub amount: u64,
+    pub partner_claimed: u64,
+}
+
+/// Emitted when a human gets a ticket in a raffle epoch.
+#[event]
+pub struct RaffleEntered {
+    pub epoch: u64,
+    pub wallet: Pubkey,
+    pub ticket_index: u64,
+}
+
+/// Emitted when lamports are added to a raffle's prize.
+#[event]
+pub struct RaffleFunded {
+    pub epoch: u64,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub prize_lamports: u64,
+}
+
+/// Emitted when the slot seeding a raffle draw is fixed.
+#[event]
+pub struct RaffleCommitted {
+    pub epoch: u64,
+    pub ticket_count: u64,
+    pub draw_slot: u64,
+}
+
+/// Emitted when a raffle's winner is drawn.
+#[event]
+pub struct RaffleDrawn {
+    pub epoch: u64,
+    pub winner: Pubkey,
+    pub winner_index: u64,
+    pub ticket_count: u64,
+    pub slot_hash: [u8; 32],
+}
+
+/// Emitted when a raffle winner claims the prize.
+#[event]
+pub struct RafflePrizeClaimed {
+    pub epoch: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when a verification fee is matched into the scholarship pool.
+#[event]
+pub struct FeeMatched {
+    pub matcher: Pubkey,
+    pub wallet: Pubkey,
+    pub amount: u64,
+    pub total_matched: u64,
+}
+
+/// Emitted when a matcher reclaims its escrow after the campaign.
+#[event]
+pub struct MatchingEscrowReclaimed {
+    pub matcher: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when a promo code is created.
+#[event]
+pub struct PromoCreated {
+    pub promo: Pubkey,
+    pub discount_bps: u16,
+    pub max_uses: u32,
+    pub expires_at: i64,
+}
+
+/// Emitted when a registration redeems a promo code.
+#[event]
+pub struct PromoRedeemed {
+    pub promo: Pubkey,
+    pub wallet: Pubkey,
+    pub fee_paid: u64,
+    pub remaining_uses: u32,
+}
+
+/// Emitted when a HumanRecord passes `assert_human_invariants`.
+#[event]
+pub struct HumanAudited {
+    pub wallet: Pubkey,
+    pub session_count: u64,
+    pub live_sessions: u64,
+    pub session_interactions: u64,
+    pub settled_score: u64,
+    pub audited_at: i64,
+}
+
+/// Emitted when the authority rescores an open session from its logs.
+#[event]
+pub struct SessionScoreRecomputed {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_index: u64,
+    pub scoring_profile: u8,
+    pub old_score: u64,
+    pub new_score: u64,
+}
+
+/// Emitted when an approval is waitlisted because of the verification cap.
+#[event]
+pub struct HumanWaitlisted {
+    pub wallet: Pubkey,
+    pub position: u64,
+    pub waitlisted_at: i64,
+}
+
+/// Emitted when a waitlisted approval is applied, or passed over.
+#[event]
+pub struct WaitlistEntryProcessed {
+    pub wallet: Pubkey,
+    pub position: u64,
+    pub admitted: bool,
+    pub processed_at: i64,
+}
+
+/// Emitted when a human proves liveness.
+#[event]
+pub struct LivenessProven {
+    pub wallet: Pubkey,
+    pub verification_level: u8,
+    pub proven_at: i64,
+}
+
+/// Emitted when liveness decay lowers, or keeps at Basic, a human's level.
+#[event]
+pub struct LivenessDecayed {
+    pub wallet: Pubkey,
+    pub previous_level: u8,
+    pub verification_level: u8,
+    pub decayed_at: i64,
+}
+
+/// Emitted when a human claims a handle.
+#[event]
+pub struct HandleClaimed {
+    pub wallet: Pubkey,
+    pub handle: String,
+    pub claimed_at: i64,
+}
+
+/// Emitted when a handle is released by its owner, or on close or revocation.
+#[event]
+pub struct HandleReleased {
+    pub wallet: Pubkey,
+    pub handle: String,
+    pub released_at: i64,
+}
+
+/// Emitted when the authority withdraws from the fee vault.
+#[event]
+pub struct FeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_fees_withdrawn: u64,
+    pub withdrawn_at: i64,
+}
+
+/// Emitted when a config update is queued behind the timelock.
+#[event]
+pub struct ConfigUpdateQueued {
+    pub update: ConfigUpdate,
+    pub effective_at: i64,
+}
+
+/// Emitted when a queued config update is applied.
+#[event]
+pub struct ConfigUpdateExecuted {
+    pub update: ConfigUpdate,
+    pub executed_at: i64,
+}
+
+/// Emitted when a queued config update is withdrawn.
+#[event]
+pub struct ConfigUpdateCancelled {
+    pub update: ConfigUpdate,
+    pub cancelled_at: i64,
+}
+
+/// Emitted by `initialize`, every administrative instruction and
+/// `execute_config_update`, once per call.
+#[event]
+pub struct ConfigUpdated {
+    /// `PlatformRegistry::config_version` after the change.
+    pub version: u32,
+    /// `PlatformRegistry::CONFIG_*` bits of the fields that may have changed.
+    pub field_mask: u32,
+    pub old_fee: u64,
+    pub new_fee: u64,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+}
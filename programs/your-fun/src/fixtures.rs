@@ -0,0 +1,568 @@
+//! Account fixtures for downstream integration tests.
+//!
+//! `FixtureBuilder` produces fully serialized accounts (discriminator, bump
+//! and seeds included) that can be injected into ProgramTest, LiteSVM or any
+//! other harness, so tests can start from "registry initialized, human
+//! verified, session open" without replaying the instructions.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::{pubkey, system_program};
+use anchor_spl::token::spl_token;
+
+use crate::client;
+use crate::crypto::compute_challenge_hash;
+use crate::scoring::{SUB_SCORE_COUNT, TYPE_MULTIPLIER_COUNT};
+use crate::state::{
+    BoosterMint, CloseReason, FeeSchedule, FingerprintData, GoalType, HumanRecord, PlatformRegistry,
+    RegionStats, ScoringProfile, SessionAccount, StatsBucket, TopicScore, VerificationLevel, VerificationStatus,
+};
+
+/// A serialized program account ready to be loaded into a test harness.
+#[derive(Clone, Debug)]
+pub struct FixtureAccount {
+    pub address: Pubkey,
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+impl FixtureAccount {
+    /// Serializes `account` as a rent-exempt program account at `address`,
+    /// for accounts the builder has no method for.
+    pub fn new<T: AccountSerialize>(address: Pubkey, account: &T) -> Self {
+        let mut data = Vec::new();
+        account
+            .try_serialize(&mut data)
+            .expect("fixture account serializes");
+        Self {
+            address,
+            lamports: Rent::default().minimum_balance(data.len()),
+            owner: crate::ID,
+            data,
+        }
+    }
+
+    /// A rent-exempt SPL token program account at `address` holding `state`,
+    /// such as a mint or a token account.
+    pub fn token<T: Pack>(address: Pubkey, state: T) -> Self {
+        let mut data = vec![0u8; T::LEN];
+        state.pack_into_slice(&mut data);
+        Self {
+            address,
+            lamports: Rent::default().minimum_balance(T::LEN),
+            owner: spl_token::ID,
+            data,
+        }
+    }
+}
+
+/// Owner of builtin programs such as the system program.
+const NATIVE_LOADER: Pubkey = pubkey!("NativeLoader1111111111111111111111111111111");
+
+/// Builds the `AccountInfo`s an instruction with `metas` would see, taking
+/// each account from `fixtures` and treating any other address as a funded
+/// system account (or as a builtin, for the system program). The backing
+/// memory is leaked, which keeps the infos usable with `try_accounts` in
+/// unit tests.
+pub fn account_infos(fixtures: &[FixtureAccount], metas: &[AccountMeta]) -> &'static [AccountInfo<'static>] {
+    let infos: Vec<_> = metas
+        .iter()
+        .map(|meta| {
+            let (lamports, owner, data, executable) =
+                match fixtures.iter().find(|f| f.address == meta.pubkey) {
+                    Some(f) => (f.lamports, f.owner, f.data.clone(), false),
+                    None if meta.pubkey == system_program::ID => {
+                        (1, NATIVE_LOADER, Vec::new(), true)
+                    }
+                    None if meta.pubkey == crate::ID => {
+                        (1, bpf_loader_upgradeable::ID, Vec::new(), true)
+                    }
+                    None => (LAMPORTS_PER_SOL, system_program::ID, Vec::new(), false),
+                };
+            AccountInfo::new(
+                Box::leak(Box::new(meta.pubkey)),
+                meta.is_signer,
+                meta.is_writable,
+                Box::leak(Box::new(lamports)),
+                Box::leak(data.into_boxed_slice()),
+                Box::leak(Box::new(owner)),
+                executable,
+                0,
+            )
+        })
+        .collect();
+    infos.leak()
+}
+
+/// The `challenge_response` that verifies a human added with
+/// `FixtureBuilder::with_registered_human`.
+pub fn registered_challenge_response(wallet: &Pubkey) -> [u8; 32] {
+    compute_challenge_hash(
+        &client::human_record_address(wallet).to_bytes(),
+        &hash(&wallet.to_bytes()).to_bytes(),
+    )
+}
+
+/// Returns a clock positioned at `unix_timestamp`.
+pub fn clock_at(unix_timestamp: i64) -> Clock {
+    Clock {
+        unix_timestamp,
+        ..Clock::default()
+    }
+}
+
+/// Moves `clock` forward by `seconds`, advancing the slot at 400ms per slot.
+pub fn advance_clock(clock: &mut Clock, seconds: i64) {
+    clock.unix_timestamp += seconds;
+    clock.slot += (seconds.max(0) as u64) * 5 / 2;
+}
+
+struct HumanFixture {
+    wallet: Pubkey,
+    record: HumanRecord,
+    fingerprint: Vec<u8>,
+    sessions: Vec<SessionAccount>,
+}
+
+/// Builds a consistent set of program accounts.
+pub struct FixtureBuilder {
+    now: i64,
+    registry: PlatformRegistry,
+    humans: Vec<HumanFixture>,
+    regions: Vec<u16>,
+}
+
+impl Default for FixtureBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixtureBuilder {
+    /// Starts from an initialized, unpaused registry with permissive limits.
+    pub fn new() -> Self {
+        let (_, bump) = Pubkey::find_program_address(&[PlatformRegistry::SEED], &crate::ID);
+        Self {
+            now: 1_700_000_000,
+            registry: PlatformRegistry {
+                authority: Pubkey::default(),
+                verification_fee_lamports: 0,
+                total_verified_humans: 0,
+                total_sessions_created: 0,
+                total_interactions: 0,
+                is_paused: false,
+                min_behavioral_score: 0,
+                max_session_duration: 3_600,
+                max_interactions_per_session: 100,
+                bump,
+                reregistration_cooldown: 0,
+                max_fingerprint_len: PlatformRegistry::DEFAULT_MAX_FINGERPRINT_LEN,
+                reverifications: 0,
+                moderator: Pubkey::default(),
+                force_close_settle_bps: 0,
+                log_threshold_score: 0,
+                config_frozen: false,
+                state_version: 0,
+                version: PlatformRegistry::CURRENT_VERSION,
+                _reserved: [0u8; 1],
+                guardian: Pubkey::default(),
+                guardian_can_unpause: false,
+                min_fee_lamports: 0,
+                max_fee_lamports: u64::MAX,
+                admin_action_count: 0,
+                permissioned_registration: false,
+                restrict_regions: false,
+                allowed_regions: [0u8; 125],
+                maintenance_start_at: 0,
+                maintenance_end_at: 0,
+                verification_validity: 0,
+                verification_grace_period: 0,
+                legacy_program_id: Pubkey::default(),
+                legacy_import_ends_at: 0,
+                arbiter: Pubkey::default(),
+                appeal_window: 0,
+                sub_score_weights_bps: PlatformRegistry::DEFAULT_SUB_SCORE_WEIGHTS_BPS,
+                contest_window: 0,
+                curator: Pubkey::default(),
+                goal_bonus_flat: 0,
+                goal_bonus_bps: 0,
+                reopen_grace_period: 0,
+                reopen_fee_lamports: 0,
+                max_content_size: 0,
+                companion_oracle: Pubkey::default(),
+                settlement_window: 0,
+                boosters: [BoosterMint::default(); PlatformRegistry::MAX_BOOSTERS],
+                program_officer: Pubkey::default(),
+                insurance_fee_bps: 0,
+                enforce_registered_topics: false,
+                private_score_bps: 0,
+                max_verified_humans: 0,
+                waitlist_count: 0,
+                waitlist_next: 0,
+                liveness_interval_seconds: 0,
+                liveness_challenge_required: false,
+                treasury: Pubkey::default(),
+                pause_flags: 0,
+                config_timelock_seconds: 0,
+                co_authority: Pubkey::default(),
+                config_version: 0,
+                fee_schedule: FeeSchedule::default(),
+                max_fee_delta_bps: PlatformRegistry::DEFAULT_MAX_FEE_DELTA_BPS,
+                fee_update_cooldown_seconds: 0,
+                last_fee_update_at: 0,
+                active_sessions: 0,
+                _reserved_pending: [0u8; 8],
+                max_active_sessions: 0,
+                min_interaction_gap_seconds: 0,
+                min_interaction_duration_seconds: 0,
+                type_multipliers: [0; TYPE_MULTIPLIER_COUNT],
+                max_personality_id: u8::MAX,
+                verifier_fee_share_bps: 0,
+                active_humans: 0,
+            },
+            humans: Vec::new(),
+            regions: Vec::new(),
+        }
+    }
+
+    /// Sets the timestamp used for every time field written afterwards.
+    pub fn at(mut self, unix_timestamp: i64) -> Self {
+        self.now = unix_timestamp;
+        self
+    }
+
+    pub fn with_authority(mut self, authority: Pubkey) -> Self {
+        self.registry.authority = authority;
+        self
+    }
+
+    /// Applies arbitrary changes to the registry.
+    pub fn with_registry(mut self, f: impl FnOnce(&mut PlatformRegistry)) -> Self {
+        f(&mut self.registry);
+        self
+    }
+
+    /// Adds a human for `wallet` that has registered but is not verified
+    /// yet. Its challenge response is `registered_challenge_response(wallet)`.
+    pub fn with_registered_human(mut self, wallet: Pubkey) -> Self {
+        let human_record = client::human_record_address(&wallet);
+        let (_, bump) =
+            Pubkey::find_program_address(&[HumanRecord::SEED, wallet.as_ref()], &crate::ID);
+        let fingerprint = wallet.to_bytes().to_vec();
+
+        self.humans.push(HumanFixture {
+            wallet,
+            record: HumanRecord {
+                wallet,
+                verified_by: Pubkey::default(),
+                verified_at: 0,
+                verification_level: 0,
+                fingerprint_hash: hash(&fingerprint).to_bytes(),
+                flags: 0,
+                session_count: 0,
+                total_interactions: 0,
+                last_active_at: self.now,
+                learning_score: 0,
+                challenge_nonce: human_record.to_bytes(),
+                bump,
+                registered_at: self.now,
+                strikes: 0,
+                revoked_at: 0,
+                force_closed_sessions: 0,
+                active_session_count: 0,
+                _reserved: [0u8; 3],
+                record_version: HumanRecord::CURRENT_VERSION,
+                behavioral_score: 0,
+                region_code: 0,
+                verification_status: VerificationStatus::Inactive as u8,
+                document_issuer: Pubkey::default(),
+                attribute_commitments: [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+                sub_scores: [0; SUB_SCORE_COUNT],
+                composite_score: 0,
+                effective_at: 0,
+                merged_into: Pubkey::default(),
+                supervisor: Pubkey::default(),
+                interaction_delegate: Pubkey::default(),
+                last_raffle_epoch: 0,
+                last_audited_at: 0,
+                spending_cap_lamports: 0,
+                spend_window_start: 0,
+                spent_in_window: 0,
+                last_liveness_at: 0,
+                liveness_decayed_at: 0,
+                handle_hash: [0u8; 32],
+                frozen_until: 0,
+                verifier_fee_escrow: 0,
+            },
+            fingerprint,
+            sessions: Vec::new(),
+        });
+        self
+    }
+
+    /// Adds a registered and verified human for `wallet`.
+    pub fn with_verified_human(mut self, wallet: Pubkey) -> Self {
+        self = self.with_registered_human(wallet);
+        let (now, authority) = (self.now, self.registry.authority);
+        let record = &mut self.humans.last_mut().expect("human just added").record;
+        record.verified_by = authority;
+        record.verified_at = now;
+        record.verification_level = VerificationLevel::Basic as u8;
+        record.set_active(true);
+        record.behavioral_score = 100;
+        record.verification_status = VerificationStatus::Active as u8;
+        record.sub_scores = [100, 0, 0];
+        record.composite_score = 100;
+        record.last_liveness_at = now;
+        self.registry.total_verified_humans += 1;
+        self
+    }
+
+    /// Applies arbitrary changes to the most recently added human's record.
+    ///
+    /// # Panics
+    /// If no human has been added yet.
+    pub fn with_human(mut self, f: impl FnOnce(&mut HumanRecord)) -> Self {
+        let human = self.humans.last_mut().expect("with_human requires a human");
+        f(&mut human.record);
+        self
+    }
+
+    /// Adds the RegionStats account of `region_code`, with no humans counted.
+    pub fn with_region_stats(mut self, region_code: u16) -> Self {
+        self.regions.push(region_code);
+        self
+    }
+
+    /// Opens a session for the most recently added human.
+    ///
+    /// # Panics
+    /// If no human has been added yet.
+    pub fn with_open_session(mut self) -> Self {
+        let now = self.now;
+        let max_duration = self.registry.max_session_duration;
+        let human = self
+            .humans
+            .last_mut()
+            .expect("with_open_session requires a human");
+        let human_record = client::human_record_address(&human.wallet);
+        let session_index = human.record.session_count;
+        let (session, bump) = Pubkey::find_program_address(
+            &[
+                SessionAccount::SEED,
+                human_record.as_ref(),
+                &session_index.to_le_bytes(),
+            ],
+            &crate::ID,
+        );
+
+        human.sessions.push(SessionAccount {
+            human_record,
+            owner: human.wallet,
+            session_index,
+            created_at: now,
+            last_interaction_at: now,
+            expires_at: now + max_duration,
+            flags: SessionAccount::FLAG_ACTIVE,
+            interaction_count: 0,
+            personality_id: 0,
+            current_topic: [1u8; 32],
+            session_score: 0,
+            bump,
+            closed_at: 0,
+            close_reason: CloseReason::None as u8,
+            open_logs: 0,
+            _reserved: [0u8; 1],
+            layout_version: SessionAccount::CURRENT_VERSION,
+            template_id: 0,
+            scoring_profile: ScoringProfile::Standard as u8,
+            goal_type: GoalType::None as u8,
+            goal_target: 0,
+            reopen_count: 0,
+            topics: [
+                TopicScore {
+                    topic: [1u8; 32],
+                    score: 0,
+                },
+                TopicScore::default(),
+                TopicScore::default(),
+                TopicScore::default(),
+            ],
+            topic_count: 1,
+            active_topic: 0,
+            session_nonce: SessionAccount::derive_nonce(&session, 0, &human.wallet, &[0u8; 32]),
+            pending_score: 0,
+            settles_at: 0,
+            booster_multiplier_bps: 0,
+            escrow_rate_per_minute: 0,
+            escrow_lamports: 0,
+            chain_head: [0; 32],
+        });
+        human.record.session_count += 1;
+        human.record.active_session_count += 1;
+        human.record.last_active_at = now;
+        self
+    }
+
+    /// Applies arbitrary changes to the most recently opened session.
+    ///
+    /// # Panics
+    /// If no session has been opened yet.
+    pub fn with_session(mut self, f: impl FnOnce(&mut SessionAccount)) -> Self {
+        let session = self
+            .humans
+            .last_mut()
+            .and_then(|human| human.sessions.last_mut())
+            .expect("with_session requires an open session");
+        f(session);
+        self
+    }
+
+    /// Serializes the registry, every stats bucket, and all humans with their
+    /// fingerprints and sessions.
+    pub fn build(self) -> Vec<FixtureAccount> {
+        let mut accounts = vec![FixtureAccount::new(
+            client::registry_address(),
+            &self.registry,
+        )];
+
+        for bucket_index in 0..StatsBucket::COUNT {
+            let (address, bump) =
+                Pubkey::find_program_address(&[StatsBucket::SEED, &[bucket_index]], &crate::ID);
+            accounts.push(FixtureAccount::new(
+                address,
+                &StatsBucket {
+                    bucket_index,
+                    pending_sessions_created: 0,
+                    pending_interactions: 0,
+                    last_aggregated_at: self.now,
+                    bump,
+                    pending_sessions_closed: 0,
+                    _reserved_score: [0u8; 8],
+                },
+            ));
+        }
+
+        for region_code in self.regions {
+            let address = client::region_stats_address(region_code);
+            let (_, bump) = Pubkey::find_program_address(
+                &[RegionStats::SEED, &region_code.to_le_bytes()],
+                &crate::ID,
+            );
+            accounts.push(FixtureAccount::new(
+                address,
+                &RegionStats {
+                    region_code,
+                    verified_humans: 0,
+                    bump,
+                },
+            ));
+        }
+
+        for human in self.humans {
+            let human_record = client::human_record_address(&human.wallet);
+            let (fingerprint, bump) = Pubkey::find_program_address(
+                &[FingerprintData::SEED, human_record.as_ref()],
+                &crate::ID,
+            );
+            accounts.push(FixtureAccount::new(human_record, &human.record));
+            accounts.push(FixtureAccount::new(
+                fingerprint,
+                &FingerprintData {
+                    human_record,
+                    data: human.fingerprint,
+                    bump,
+                },
+            ));
+            for session in human.sessions {
+                let address = client::session_address(&human_record, session.session_index);
+                accounts.push(FixtureAccount::new(address, &session));
+            }
+        }
+
+        accounts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::instructions::{HeartbeatSession, HeartbeatSessionBumps};
+
+    fn decode<T: AccountDeserialize>(accounts: &[FixtureAccount], address: &Pubkey) -> T {
+        let account = accounts
+            .iter()
+            .find(|a| a.address == *address)
+            .expect("fixture present");
+        assert_eq!(account.owner, crate::ID);
+        assert!(Rent::default().is_exempt(account.lamports, account.data.len()));
+        T::try_deserialize(&mut &account.data[..]).expect("fixture decodes")
+    }
+
+    #[test]
+    fn fixtures_decode_at_their_addresses() {
+        let wallet = Pubkey::new_unique();
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(wallet)
+            .with_open_session()
+            .with_open_session()
+            .build();
+        assert_eq!(accounts.len(), 1 + StatsBucket::COUNT as usize + 4);
+
+        let registry: PlatformRegistry = decode(&accounts, &client::registry_address());
+        assert_eq!(registry.total_verified_humans, 1);
+        let human_record = client::human_record_address(&wallet);
+        let record: HumanRecord = decode(&accounts, &human_record);
+        assert!(record.is_active());
+        assert_eq!(record.session_count, 2);
+        let fingerprint: FingerprintData = decode(&accounts, &client::fingerprint_address(&human_record));
+        assert_eq!(fingerprint.human_record, human_record);
+        for index in 0..2 {
+            let session: SessionAccount = decode(&accounts, &client::session_address(&human_record, index));
+            assert_eq!(session.session_index, index);
+            assert_eq!(session.owner, wallet);
+        }
+    }
+
+    #[test]
+    fn fixtures_satisfy_instruction_constraints() {
+        let wallet = Pubkey::new_unique();
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(wallet)
+            .with_open_session()
+            .build();
+        let ix = client::build_heartbeat_session_ix(&wallet, 0);
+        let infos = account_infos(&accounts, &ix.accounts);
+
+        let ctx = HeartbeatSession::try_accounts(
+            &crate::ID,
+            &mut &infos[..],
+            &ix.data,
+            &mut HeartbeatSessionBumps::default(),
+            &mut BTreeSet::new(),
+        )
+        .expect("fixtures pass the account constraints");
+        assert_eq!(ctx.session.owner, wallet);
+
+        // A session the wallet does not own is rejected.
+        let other = client::build_heartbeat_session_ix(&Pubkey::new_unique(), 0);
+        let mut metas = other.accounts;
+        metas[2] = ix.accounts[2].clone();
+        let infos = account_infos(&accounts, &metas);
+        assert!(HeartbeatSession::try_accounts(
+            &crate::ID,
+            &mut &infos[..],
+            &ix.data,
+            &mut HeartbeatSessionBumps::default(),
+            &mut BTreeSet::new(),
+        )
+        .is_err());
+    }
+}
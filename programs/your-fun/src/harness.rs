@@ -0,0 +1,431 @@
+//! In-process program runner for the crate's own unit tests.
+//!
+//! Loads `FixtureBuilder` accounts into solana-program-test with the program
+//! running natively, so instruction tests run under `cargo test` without an
+//! SBF build. Compute units are not metered natively; budgets are checked by
+//! the `compute_units` benchmarks instead.
+
+use std::cell::Cell;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::solana_program::program::{get_return_data, invoke, set_return_data};
+use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use anchor_lang::solana_program::slot_hashes::SlotHashes;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{Account as SdkAccount, AccountSharedData};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::ed25519_instruction::{DATA_START, PUBKEY_SERIALIZED_SIZE, SIGNATURE_SERIALIZED_SIZE};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+use crate::fixtures::FixtureAccount;
+
+/// Timestamp `FixtureBuilder` writes its accounts at, and the clock the
+/// harness starts at.
+pub const NOW: i64 = 1_700_000_000;
+
+/// The program's custom error for `error`, as a failed instruction reports it.
+pub fn custom(error: impl Into<u32>) -> InstructionError {
+    InstructionError::Custom(error.into())
+}
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // `entry` ties the slice to the lifetime of its elements.
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    // Restores rather than clears the flag, as `cpi_caller` may be running
+    // this program through CPI.
+    let previous = IN_PROGRAM.with(|in_program| in_program.replace(true));
+    let result = crate::entry(program_id, accounts, data);
+    IN_PROGRAM.with(|in_program| in_program.set(previous));
+    result
+}
+
+/// Address of `cpi_caller`, which every harness loads.
+pub const CPI_CALLER_ID: Pubkey = Pubkey::new_from_array([0xca; 32]);
+
+/// A fixture program standing in for an integrator. It invokes this program
+/// with its own instruction data and its accounts after the first, which
+/// must be this program, and passes the callee's return data on as its own.
+fn cpi_caller(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let previous = IN_PROGRAM.with(|in_program| in_program.replace(true));
+    let ix = Instruction {
+        program_id: crate::ID,
+        accounts: accounts[1..]
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: *info.key,
+                is_signer: info.is_signer,
+                is_writable: info.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    let result = invoke(&ix, accounts).and_then(|()| match get_return_data() {
+        Some((program_id, data)) if program_id == crate::ID => {
+            set_return_data(&data);
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    });
+    IN_PROGRAM.with(|in_program| in_program.set(previous));
+    result
+}
+
+/// Wraps `ix`, an instruction of this program, in a call from `cpi_caller`.
+pub fn through_cpi_caller(ix: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(crate::ID, false)];
+    accounts.extend(ix.accounts);
+    Instruction {
+        program_id: CPI_CALLER_ID,
+        accounts,
+        data: ix.data,
+    }
+}
+
+/// An ed25519 program instruction verifying `signer`'s signature of
+/// `message`, with the key, signature and message all in its own data, and
+/// that signature.
+pub fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> (Instruction, [u8; 64]) {
+    let signature: [u8; 64] = signer.sign_message(message).into();
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + PUBKEY_SERIALIZED_SIZE;
+    let message_offset = signature_offset + SIGNATURE_SERIALIZED_SIZE;
+
+    let mut data = vec![1u8, 0];
+    for field in [
+        signature_offset as u16,
+        u16::MAX,
+        public_key_offset as u16,
+        u16::MAX,
+        message_offset as u16,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(&signature);
+    data.extend_from_slice(message);
+    let ix = Instruction {
+        program_id: anchor_lang::solana_program::ed25519_program::ID,
+        accounts: vec![],
+        data,
+    };
+    (ix, signature)
+}
+
+thread_local! {
+    /// Set while the program runs inside a bank on this thread.
+    static IN_PROGRAM: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The syscall defaults of a plain host build.
+struct HostStubs;
+
+impl SyscallStubs for HostStubs {}
+
+/// Prefix under which `RoutedStubs` writes `sol_log_data` fields, i.e.
+/// events, into the transaction logs.
+const LOGGED_DATA_PREFIX: &str = "data: ";
+
+/// Sends syscalls to solana-program-test's stubs while the program runs in a
+/// bank, and to the host defaults otherwise. Its stubs need the bank's invoke
+/// context, so pure unit tests that log on other threads would panic once a
+/// bank had started in the same process.
+struct RoutedStubs {
+    bank: Box<dyn SyscallStubs>,
+}
+
+impl RoutedStubs {
+    fn target(&self) -> &dyn SyscallStubs {
+        if IN_PROGRAM.with(Cell::get) {
+            &*self.bank
+        } else {
+            &HostStubs
+        }
+    }
+}
+
+impl SyscallStubs for RoutedStubs {
+    fn sol_log(&self, message: &str) {
+        self.target().sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.target().sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.target().sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.target().sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.target().sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.target().sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.target().sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.target().sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.target().sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.target().sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.target().sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.target().sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        // solana-program-test has no stub for this, so events would only
+        // reach stdout; log them into the transaction instead.
+        if IN_PROGRAM.with(Cell::get) {
+            for field in fields {
+                self.bank.sol_log(&format!("{LOGGED_DATA_PREFIX}{}", STANDARD.encode(field)));
+            }
+        } else {
+            HostStubs.sol_log_data(fields)
+        }
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.target().sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.target().sol_get_stack_height()
+    }
+}
+
+/// Starts a throwaway bank before `main`, which makes solana-program-test
+/// install its syscall stubs, and wraps them in `RoutedStubs`. Doing it
+/// before any test thread exists leaves no window in which a logging unit
+/// test could reach the unwrapped stubs.
+extern "C" fn route_syscall_stubs() {
+    // Runtime logs are noise unless asked for with RUST_LOG.
+    if std::env::var_os("RUST_LOG").is_none() {
+        std::env::set_var("RUST_LOG", "error");
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(ProgramTest::default().start());
+    let bank = set_syscall_stubs(Box::new(HostStubs));
+    set_syscall_stubs(Box::new(RoutedStubs { bank }));
+}
+
+#[used]
+#[cfg_attr(target_os = "linux", link_section = ".init_array")]
+#[cfg_attr(target_os = "macos", link_section = "__DATA,__mod_init_func")]
+static ROUTE_SYSCALL_STUBS: extern "C" fn() = route_syscall_stubs;
+
+pub struct Harness {
+    runtime: tokio::runtime::Runtime,
+    context: ProgramTestContext,
+    /// Varies the compute budget instruction so repeated transactions
+    /// have distinct signatures.
+    sent: u32,
+}
+
+impl Harness {
+    /// Starts a bank holding `accounts`, with the clock at `NOW`.
+    pub fn start(accounts: Vec<FixtureAccount>) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut program_test = ProgramTest::new("your_fun", crate::ID, processor!(process_instruction));
+        program_test.add_program("cpi_caller", CPI_CALLER_ID, processor!(cpi_caller));
+        for fixture in accounts {
+            program_test.add_account(
+                fixture.address,
+                SdkAccount {
+                    lamports: fixture.lamports,
+                    data: fixture.data,
+                    owner: fixture.owner,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+        }
+        let context = runtime.block_on(program_test.start_with_context());
+        let mut harness = Self { runtime, context, sent: 0 };
+        harness.warp_to(NOW);
+        harness
+    }
+
+    /// A new wallet holding 10 SOL.
+    pub fn wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        self.fund(&wallet.pubkey(), 10 * LAMPORTS_PER_SOL);
+        wallet
+    }
+
+    /// Replaces `address` with a system account holding `lamports`.
+    pub fn fund(&mut self, address: &Pubkey, lamports: u64) {
+        self.context.set_account(
+            address,
+            &AccountSharedData::new(lamports, 0, &anchor_lang::system_program::ID),
+        );
+    }
+
+    /// Writes `fixture` into the bank, replacing any account at its address.
+    pub fn set_account(&mut self, fixture: FixtureAccount) {
+        let mut account = AccountSharedData::new(fixture.lamports, fixture.data.len(), &fixture.owner);
+        account.set_data_from_slice(&fixture.data);
+        self.context.set_account(&fixture.address, &account);
+    }
+
+    /// Moves the clock to `unix_timestamp`, keeping the slot.
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.runtime.block_on(self.context.banks_client.get_sysvar()).unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Moves the clock to `slot`, keeping the timestamp.
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        let mut clock: Clock = self.runtime.block_on(self.context.banks_client.get_sysvar()).unwrap();
+        clock.slot = slot;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Replaces the SlotHashes sysvar with the single entry `(slot, hash)`.
+    pub fn set_slot_hash(&mut self, slot: u64, hash: [u8; 32]) {
+        self.context.set_sysvar(&SlotHashes::new(&[(slot, Hash::new_from_array(hash))]));
+    }
+
+    fn transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        self.sent += 1;
+        let mut all = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000 - self.sent)];
+        all.extend_from_slice(instructions);
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(
+            &all,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        )
+    }
+
+    /// Executes `ix` signed by `signers`, with the harness paying fees.
+    pub fn process(&mut self, ix: Instruction, signers: &[&Keypair]) -> std::result::Result<(), InstructionError> {
+        self.process_all(&[ix], signers)
+    }
+
+    /// Executes `instructions` in one transaction and returns the error of
+    /// the one that failed, if any.
+    pub fn process_all(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), InstructionError> {
+        let tx = self.transaction(instructions, signers);
+        match self.runtime.block_on(self.context.banks_client.process_transaction(tx)) {
+            Ok(()) => Ok(()),
+            Err(err) => match err.unwrap() {
+                TransactionError::InstructionError(_, err) => Err(err),
+                other => panic!("transaction failed outside the program: {other:?}"),
+            },
+        }
+    }
+
+    /// Executes `ix` like `process` and returns the `E` events it emitted.
+    pub fn process_with_events<E: anchor_lang::Event>(
+        &mut self,
+        ix: Instruction,
+        signers: &[&Keypair],
+    ) -> std::result::Result<Vec<E>, InstructionError> {
+        let tx = self.transaction(&[ix], signers);
+        let processed = self
+            .runtime
+            .block_on(self.context.banks_client.process_transaction_with_metadata(tx))
+            .unwrap();
+        match processed.result {
+            Ok(()) => {}
+            Err(TransactionError::InstructionError(_, err)) => return Err(err),
+            Err(other) => panic!("transaction failed outside the program: {other:?}"),
+        }
+        let logs = processed.metadata.map(|metadata| metadata.log_messages).unwrap_or_default();
+        Ok(logs
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program log: ")?.strip_prefix(LOGGED_DATA_PREFIX))
+            .map(|data| STANDARD.decode(data).expect("logged data is base64"))
+            .filter(|data| data.starts_with(&E::DISCRIMINATOR))
+            .map(|data| E::deserialize(&mut &data[8..]).expect("event decodes"))
+            .collect())
+    }
+
+    /// Simulates `ix` and returns the data it set with `set_return_data`.
+    pub fn simulate(&mut self, ix: Instruction, signers: &[&Keypair]) -> std::result::Result<Vec<u8>, InstructionError> {
+        let tx = self.transaction(&[ix], signers);
+        let simulation = self
+            .runtime
+            .block_on(self.context.banks_client.simulate_transaction(tx))
+            .unwrap();
+        match simulation.result.expect("simulation result") {
+            Ok(()) => Ok(simulation
+                .simulation_details
+                .and_then(|details| details.return_data)
+                .map(|data| data.data)
+                .unwrap_or_default()),
+            Err(TransactionError::InstructionError(_, err)) => Err(err),
+            Err(other) => panic!("simulation failed outside the program: {other:?}"),
+        }
+    }
+
+    /// The account at `address`, if it exists.
+    pub fn try_account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> Option<T> {
+        let account = self
+            .runtime
+            .block_on(self.context.banks_client.get_account(*address))
+            .unwrap()?;
+        Some(T::try_deserialize(&mut &account.data[..]).expect("account decodes"))
+    }
+
+    /// Lamports held at `address`; 0 if there is no account.
+    pub fn balance(&mut self, address: &Pubkey) -> u64 {
+        self.runtime
+            .block_on(self.context.banks_client.get_balance(*address))
+            .unwrap()
+    }
+
+    /// The raw data of the account at `address`.
+    ///
+    /// # Panics
+    /// If it does not exist.
+    pub fn raw_account(&mut self, address: &Pubkey) -> Vec<u8> {
+        self.runtime
+            .block_on(self.context.banks_client.get_account(*address))
+            .unwrap()
+            .unwrap_or_else(|| panic!("no account at {address}"))
+            .data
+    }
+
+    /// The account at `address`.
+    ///
+    /// # Panics
+    /// If it does not exist or does not decode as a `T`.
+    pub fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        self.try_account(address)
+            .unwrap_or_else(|| panic!("no account at {address}"))
+    }
+}
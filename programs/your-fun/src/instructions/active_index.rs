@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use crate::state::{HumanRecord, SessionAccount, ActiveSessionIndex};
+use crate::error::YourFunError;
+
+#[derive(Accounts)]
+pub struct InitActiveSessionIndex<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ActiveSessionIndex::INIT_SPACE,
+        seeds = [ActiveSessionIndex::SEED, human_record.key().as_ref()],
+        bump,
+    )]
+    pub active_index: Box<Account<'info, ActiveSessionIndex>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the owner's active session index. Sessions opened before it
+/// existed are not backfilled; only sessions created afterwards are tracked.
+pub fn handler_init_active_session_index(ctx: Context<InitActiveSessionIndex>) -> Result<()> {
+    let index = &mut ctx.accounts.active_index;
+    index.human_record = ctx.accounts.human_record.key();
+    index.sessions = Vec::new();
+    index.bump = ctx.bumps.active_index;
+
+    msg!("Active session index initialized");
+    Ok(())
+}
+
+/// Drops index entries whose session account, supplied among `candidates`,
+/// has been reclaimed or is no longer active. Entries without a matching
+/// candidate are left alone, so pruning is driven by what the client passes.
+pub(crate) fn prune_closed_sessions(
+    index: &mut ActiveSessionIndex,
+    candidates: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    for info in candidates {
+        if !index.sessions.contains(info.key) {
+            continue;
+        }
+
+        let closed = if info.owner != program_id || info.data_is_empty() {
+            true
+        } else {
+            let data = info.try_borrow_data()?;
+            match SessionAccount::try_deserialize(&mut &data[..]) {
+                Ok(session) => !session.is_active(),
+                Err(_) => true,
+            }
+        };
+
+        if closed {
+            index.remove(info.key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Appends `session` to the index, pruning closed sessions from `candidates`
+/// first if the index is full.
+pub(crate) fn track_session(
+    index: &mut ActiveSessionIndex,
+    session: Pubkey,
+    candidates: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    if index.is_full() {
+        prune_closed_sessions(index, candidates, program_id)?;
+    }
+
+    require!(index.push(session), YourFunError::ActiveSessionIndexFull);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    use super::*;
+    use crate::fixtures::{account_infos, FixtureAccount};
+
+    fn index_of(sessions: &[Pubkey]) -> ActiveSessionIndex {
+        ActiveSessionIndex {
+            human_record: Pubkey::new_unique(),
+            sessions: sessions.to_vec(),
+            bump: 255,
+        }
+    }
+
+    fn session(active: bool) -> SessionAccount {
+        let zeroed = vec![0u8; SessionAccount::INIT_SPACE];
+        let mut session = SessionAccount::deserialize(&mut &zeroed[..]).unwrap();
+        session.set_active(active);
+        session
+    }
+
+    #[test]
+    fn sessions_keep_their_creation_order() {
+        let sessions: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut index = index_of(&[]);
+        for session in &sessions {
+            assert!(index.push(*session));
+        }
+        assert!(!index.push(sessions[0]), "duplicates are not added");
+
+        assert!(index.remove(&sessions[1]));
+        assert_eq!(index.sessions, [sessions[0], sessions[2], sessions[3]]);
+        assert!(!index.remove(&sessions[1]), "a missing entry is not an error");
+        assert!(index.push(sessions[1]));
+        assert_eq!(index.sessions, [sessions[0], sessions[2], sessions[3], sessions[1]]);
+    }
+
+    #[test]
+    fn a_full_index_makes_room_only_by_pruning_closed_sessions() {
+        let tracked: Vec<_> = (0..ActiveSessionIndex::CAPACITY).map(|_| Pubkey::new_unique()).collect();
+        let mut index = index_of(&tracked);
+        let new_session = Pubkey::new_unique();
+        assert!(!index.push(new_session));
+        assert_eq!(
+            track_session(&mut index, new_session, &[], &crate::ID).unwrap_err(),
+            YourFunError::ActiveSessionIndexFull.into()
+        );
+
+        // tracked[0] is still open, tracked[1] closed before the index knew,
+        // and tracked[2] was reclaimed; candidates outside the index are
+        // ignored.
+        let fixtures = [
+            FixtureAccount::new(tracked[0], &session(true)),
+            FixtureAccount::new(tracked[1], &session(false)),
+        ];
+        let candidates = [tracked[0], tracked[1], tracked[2], Pubkey::new_unique()]
+            .map(|address| AccountMeta::new_readonly(address, false));
+        let candidates = account_infos(&fixtures, &candidates);
+
+        track_session(&mut index, new_session, candidates, &crate::ID).unwrap();
+        assert_eq!(index.sessions.len(), ActiveSessionIndex::CAPACITY - 1);
+        assert_eq!(index.sessions[0], tracked[0]);
+        assert!(!index.sessions.contains(&tracked[1]) && !index.sessions.contains(&tracked[2]));
+        assert_eq!(index.sessions.last(), Some(&new_session));
+    }
+}
@@ -0,0 +1,2130 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::state::{PlatformRegistry, AdminAction, AdminActionKind, ConfigUpdate, FeeSchedule, PersonalityConfig, RegionStats};
+use crate::error::YourFunError;
+use crate::events::{ConfigUpdated, MaintenanceWindowChanged, PersonalityDeprecated, RegistryPauseChanged, RegistryPauseFlagsChanged};
+use crate::constants::{MAX_BPS, MAX_SCORE};
+use crate::scoring::{SUB_SCORE_COUNT, TYPE_MULTIPLIER_COUNT};
+
+/// Accounts for every configuration setter. Once the configuration is
+/// frozen all of them fail with `ConfigFrozen`; only operational instructions
+/// (registration, verification, revocation, sessions, interactions, stats
+/// aggregation, migrations and tombstone cleanup) keep working.
+#[derive(Accounts)]
+pub struct AdminConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = authority.key() == registry.authority
+            || !registry.is_guardian(&authority.key()) @ YourFunError::Unauthorized,
+        constraint = authority.key() == registry.authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AdminConfig<'info> {
+    /// Appends this operation to the audit trail.
+    fn record(&mut self, bump: u8, action: AdminActionKind, params: &impl AnchorSerialize) -> Result<()> {
+        record_admin_action(
+            &mut self.registry,
+            &mut self.admin_action,
+            bump,
+            action,
+            self.authority.key(),
+            params,
+        )
+    }
+
+    /// `record` for a change of the verification fee, which reports
+    /// `old_fee` and exactly the fields in `field_mask`.
+    fn record_fee_change(
+        &mut self,
+        bump: u8,
+        action: AdminActionKind,
+        field_mask: u32,
+        old_fee: u64,
+        params: &impl AnchorSerialize,
+    ) -> Result<()> {
+        let authority = self.authority.key();
+        write_admin_action(&mut self.registry, &mut self.admin_action, bump, action, authority, params)?;
+        publish_config_change(&mut self.registry, field_mask, old_fee, authority)
+    }
+}
+
+/// Writes the next AdminAction entry, advances the registry's counters and
+/// emits `ConfigUpdated` with the fields `action` may change. Fee changes
+/// go through `AdminConfig::record_fee_change` instead.
+pub(crate) fn record_admin_action(
+    registry: &mut PlatformRegistry,
+    admin_action: &mut AdminAction,
+    bump: u8,
+    action: AdminActionKind,
+    actor: Pubkey,
+    params: &impl AnchorSerialize,
+) -> Result<()> {
+    write_admin_action(registry, admin_action, bump, action, actor, params)?;
+    let fee = registry.verification_fee_lamports;
+    publish_config_change(registry, action.config_fields(), fee, actor)
+}
+
+/// Increments `config_version` and emits `ConfigUpdated`; `old_fee` is the
+/// verification fee before the change.
+pub(crate) fn publish_config_change(
+    registry: &mut PlatformRegistry,
+    field_mask: u32,
+    old_fee: u64,
+    updated_by: Pubkey,
+) -> Result<()> {
+    registry.bump_config_version()?;
+
+    emit!(ConfigUpdated {
+        version: registry.config_version,
+        field_mask,
+        old_fee,
+        new_fee: registry.verification_fee_lamports,
+        updated_by,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+fn write_admin_action(
+    registry: &mut PlatformRegistry,
+    admin_action: &mut AdminAction,
+    bump: u8,
+    action: AdminActionKind,
+    actor: Pubkey,
+    params: &impl AnchorSerialize,
+) -> Result<()> {
+    admin_action.action_index = registry.admin_action_count;
+    admin_action.action = action;
+    admin_action.actor = actor;
+    admin_action.params_hash = hash(&params.try_to_vec()?).to_bytes();
+    admin_action.timestamp = Clock::get()?.unix_timestamp;
+    admin_action.bump = bump;
+
+    registry.admin_action_count = registry
+        .admin_action_count
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    Ok(())
+}
+
+/// `AdminConfig` for the high-impact setters, which the co-authority must
+/// co-sign once one is configured.
+#[derive(Accounts)]
+pub struct CoSignedAdminConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = authority.key() == registry.authority
+            || !registry.is_guardian(&authority.key()) @ YourFunError::Unauthorized,
+        constraint = authority.key() == registry.authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Required while `registry.co_authority` is set.
+    pub co_authority: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CoSignedAdminConfig<'info> {
+    /// Checks the co-signature, then appends this operation to the audit trail.
+    fn record(&mut self, bump: u8, action: AdminActionKind, params: &impl AnchorSerialize) -> Result<()> {
+        self.registry
+            .require_co_authority(self.co_authority.as_ref().map(|s| s.key()))?;
+        record_admin_action(
+            &mut self.registry,
+            &mut self.admin_action,
+            bump,
+            action,
+            self.authority.key(),
+            params,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = signer.key() == registry.authority
+            || registry.is_guardian(&signer.key()) @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    /// The authority, or the guardian; pays for the audit entry.
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// Required to resume while `registry.co_authority` is set.
+    pub co_authority: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pauses or resumes the registry. Idempotent.
+///
+/// Not configuration, so it stays available after `freeze_config`. The
+/// guardian may always pause but may only resume when `guardian_can_unpause`
+/// is set. Resuming must be co-signed by the co-authority, when set.
+pub fn handler_set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let signer = ctx.accounts.signer.key();
+
+    if !paused {
+        if signer != registry.authority {
+            require!(registry.guardian_can_unpause, YourFunError::Unauthorized);
+        }
+        registry.require_co_authority(ctx.accounts.co_authority.as_ref().map(|s| s.key()))?;
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    registry.is_paused = paused;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::SetPaused,
+        signer,
+        &paused,
+    )?;
+
+    emit!(RegistryPauseChanged {
+        paused,
+        changed_by: signer,
+        changed_at: now,
+    });
+
+    msg!("Registry paused: {}", paused);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPauseFlags<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = authority.key() == registry.authority
+            || !registry.is_guardian(&authority.key()) @ YourFunError::Unauthorized,
+        constraint = authority.key() == registry.authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Required to lift a flag while `registry.co_authority` is set.
+    pub co_authority: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Replaces the set of individually paused operations with `flags`, a mask
+/// of the `PlatformRegistry::PAUSE_*` bits. `is_paused` keeps pausing all
+/// of them regardless. Like `set_paused` this stays available after
+/// `freeze_config`. Authority only; lifting any flag must be co-signed by
+/// the co-authority, when set.
+pub fn handler_set_pause_flags(ctx: Context<SetPauseFlags>, flags: u8) -> Result<()> {
+    require!(
+        flags & !PlatformRegistry::PAUSE_ALL == 0,
+        YourFunError::InvalidConfigValue
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    if registry.pause_flags & !flags != 0 {
+        registry.require_co_authority(ctx.accounts.co_authority.as_ref().map(|s| s.key()))?;
+    }
+    let authority = ctx.accounts.authority.key();
+    registry.pause_flags = flags;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::SetPauseFlags,
+        authority,
+        &flags,
+    )?;
+
+    emit!(RegistryPauseFlagsChanged {
+        pause_flags: flags,
+        changed_by: authority,
+        changed_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Registry pause flags set to {:#06b}", flags);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ScheduleMaintenance<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Schedules a window `[start_at, end_at)` during which pause-sensitive
+/// instructions fail with `MaintenanceWindow`. Only one window may be
+/// pending; like `set_paused` this stays available after `freeze_config`.
+pub fn handler_schedule_maintenance(
+    ctx: Context<ScheduleMaintenance>,
+    start_at: i64,
+    end_at: i64,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        start_at > 0 && start_at < end_at && end_at > now,
+        YourFunError::InvalidConfigValue
+    );
+    require!(
+        !registry.has_pending_maintenance(now),
+        YourFunError::MaintenanceAlreadyScheduled
+    );
+
+    registry.maintenance_start_at = start_at;
+    registry.maintenance_end_at = end_at;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::ScheduleMaintenance,
+        ctx.accounts.authority.key(),
+        &(start_at, end_at),
+    )?;
+
+    emit!(MaintenanceWindowChanged {
+        start_at,
+        end_at,
+        changed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Maintenance scheduled from {} to {}", start_at, end_at);
+    Ok(())
+}
+
+/// Cancels the pending maintenance window, ending it early if it has begun.
+pub fn handler_cancel_maintenance(ctx: Context<ScheduleMaintenance>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        registry.has_pending_maintenance(now),
+        YourFunError::NoMaintenanceScheduled
+    );
+
+    registry.maintenance_start_at = 0;
+    registry.maintenance_end_at = 0;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::CancelMaintenance,
+        ctx.accounts.authority.key(),
+        &(),
+    )?;
+
+    emit!(MaintenanceWindowChanged {
+        start_at: 0,
+        end_at: 0,
+        changed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Maintenance window cancelled");
+    Ok(())
+}
+
+pub fn handler_set_reregistration_cooldown(
+    ctx: Context<AdminConfig>,
+    cooldown_seconds: i64,
+) -> Result<()> {
+    require!(cooldown_seconds >= 0, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.reregistration_cooldown = cooldown_seconds;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetReregistrationCooldown, &cooldown_seconds)?;
+
+    msg!("Re-registration cooldown set to {}s", cooldown_seconds);
+    Ok(())
+}
+
+pub fn handler_set_max_fingerprint_len(
+    ctx: Context<AdminConfig>,
+    max_fingerprint_len: u16,
+) -> Result<()> {
+    require!(
+        max_fingerprint_len > 0
+            && max_fingerprint_len <= PlatformRegistry::MAX_FINGERPRINT_LEN_CEILING,
+        YourFunError::InvalidConfigValue
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    registry.max_fingerprint_len = max_fingerprint_len;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetMaxFingerprintLen, &max_fingerprint_len)?;
+
+    msg!("Maximum fingerprint length set to {} bytes", max_fingerprint_len);
+    Ok(())
+}
+
+pub fn handler_set_moderator(ctx: Context<AdminConfig>, moderator: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.moderator = moderator;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetModerator, &moderator)?;
+
+    msg!("Moderator set to {}", moderator);
+    Ok(())
+}
+
+pub fn handler_set_force_close_settle_bps(
+    ctx: Context<AdminConfig>,
+    settle_bps: u16,
+) -> Result<()> {
+    require!(settle_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.force_close_settle_bps = settle_bps;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetForceCloseSettleBps, &settle_bps)?;
+
+    msg!("Force-close settlement share set to {} bps", settle_bps);
+    Ok(())
+}
+
+pub fn handler_set_log_threshold_score(
+    ctx: Context<AdminConfig>,
+    log_threshold_score: u8,
+) -> Result<()> {
+    require!(log_threshold_score <= MAX_SCORE, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.log_threshold_score = log_threshold_score;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetLogThresholdScore, &log_threshold_score)?;
+
+    msg!("Interaction log threshold set to {}", log_threshold_score);
+    Ok(())
+}
+
+/// Irreversibly freezes the registry configuration. An emergency pause is
+/// not configuration and must stay available, so it must not go through
+/// `AdminConfig`.
+pub fn handler_freeze_config(ctx: Context<AdminConfig>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.config_frozen = true;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::FreezeConfig, &())?;
+
+    msg!("Registry configuration frozen");
+    Ok(())
+}
+
+/// Replaces the verification fee schedule; an empty one restores the flat
+/// fee. Disabled while a config timelock is set.
+pub fn handler_set_fee_schedule(ctx: Context<AdminConfig>, fee_schedule: FeeSchedule) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(registry.config_timelock_seconds == 0, YourFunError::ConfigTimelocked);
+    registry.validate_fee_schedule(&fee_schedule)?;
+
+    registry.fee_schedule = fee_schedule;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetFeeSchedule, &fee_schedule)?;
+
+    msg!("Fee schedule set with {} tiers", fee_schedule.tier_count);
+    Ok(())
+}
+
+/// Sets how far a single update may move the flat verification fee and
+/// how long must pass between changes. Co-signed by the co-authority, when
+/// set, so the guardrails cannot be loosened with the authority key alone.
+pub fn handler_set_fee_guardrails(
+    ctx: Context<CoSignedAdminConfig>,
+    max_fee_delta_bps: u16,
+    fee_update_cooldown_seconds: i64,
+) -> Result<()> {
+    require!(
+        max_fee_delta_bps > 0 && max_fee_delta_bps <= MAX_BPS && fee_update_cooldown_seconds >= 0,
+        YourFunError::InvalidConfigValue
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    registry.max_fee_delta_bps = max_fee_delta_bps;
+    registry.fee_update_cooldown_seconds = fee_update_cooldown_seconds;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetFeeGuardrails,
+        &(max_fee_delta_bps, fee_update_cooldown_seconds),
+    )?;
+
+    msg!(
+        "Fee guardrails set: max delta {} bps, cooldown {}s",
+        max_fee_delta_bps,
+        fee_update_cooldown_seconds
+    );
+    Ok(())
+}
+
+/// Sets the key that must co-sign high-impact admin instructions.
+/// `Pubkey::default()` removes the requirement. Changing it is itself
+/// co-signed by the current co-authority.
+pub fn handler_set_co_authority(ctx: Context<CoSignedAdminConfig>, co_authority: Pubkey) -> Result<()> {
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetCoAuthority, &co_authority)?;
+    let registry = &mut ctx.accounts.registry;
+    registry.co_authority = co_authority;
+    registry.bump_state_version()?;
+
+    msg!("Co-authority set to {}", co_authority);
+    Ok(())
+}
+
+/// Sets the guardian key and whether it may lift a pause.
+/// `Pubkey::default()` removes the guardian.
+pub fn handler_set_guardian(
+    ctx: Context<AdminConfig>,
+    guardian: Pubkey,
+    guardian_can_unpause: bool,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.guardian = guardian;
+    registry.guardian_can_unpause = guardian_can_unpause;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetGuardian, &(guardian, guardian_can_unpause))?;
+
+    msg!("Guardian set to {} (can unpause: {})", guardian, guardian_can_unpause);
+    Ok(())
+}
+
+/// Updates the verification fee. The new fee must lie within the registry's
+/// fee bounds and the per-update delta cap, and the fee cooldown must have
+/// passed. Disabled while a config timelock is set.
+pub fn handler_set_verification_fee(ctx: Context<AdminConfig>, fee_lamports: u64) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(registry.config_timelock_seconds == 0, YourFunError::ConfigTimelocked);
+    let now = Clock::get()?.unix_timestamp;
+    registry.validate_fee_update(fee_lamports, now)?;
+
+    let old_fee = registry.verification_fee_lamports;
+    registry.verification_fee_lamports = fee_lamports;
+    registry.last_fee_update_at = now;
+    registry.bump_state_version()?;
+    ctx.accounts.record_fee_change(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetVerificationFee,
+        PlatformRegistry::CONFIG_FEES,
+        old_fee,
+        &fee_lamports,
+    )?;
+
+    msg!("Verification fee set to {} lamports", fee_lamports);
+    Ok(())
+}
+
+/// Updates any of the parameters fixed at `initialize`, leaving those passed
+/// as `None` unchanged. The result is validated as at `initialize`, and a
+/// new fee as in `set_verification_fee`. Open sessions keep their
+/// `expires_at`; only new ones use a new `max_session_duration`. Disabled
+/// while a config timelock is set; queue the update instead.
+pub fn handler_update_config(
+    ctx: Context<AdminConfig>,
+    verification_fee_lamports: Option<u64>,
+    min_behavioral_score: Option<u8>,
+    max_session_duration: Option<i64>,
+    max_interactions_per_session: Option<u32>,
+) -> Result<()> {
+    let update = ConfigUpdate {
+        verification_fee_lamports,
+        min_behavioral_score,
+        max_session_duration,
+        max_interactions_per_session,
+        config_timelock_seconds: None,
+        fee_schedule: None,
+    };
+    let registry = &mut ctx.accounts.registry;
+    require!(registry.config_timelock_seconds == 0, YourFunError::ConfigTimelocked);
+    let old_fee = registry.verification_fee_lamports;
+    registry.apply_config_update(&update, Clock::get()?.unix_timestamp)?;
+    registry.bump_state_version()?;
+    let (fee, min_behavioral_score, max_session_duration, max_interactions_per_session) = (
+        registry.verification_fee_lamports,
+        registry.min_behavioral_score,
+        registry.max_session_duration,
+        registry.max_interactions_per_session,
+    );
+    ctx.accounts.record_fee_change(
+        ctx.bumps.admin_action,
+        AdminActionKind::UpdateConfig,
+        update.field_mask(),
+        old_fee,
+        &update,
+    )?;
+
+    msg!(
+        "Config updated: fee {} lamports, min score {}, max duration {}s, max interactions {}",
+        fee,
+        min_behavioral_score,
+        max_session_duration,
+        max_interactions_per_session
+    );
+    Ok(())
+}
+
+/// Raises the delay queued config updates wait before they can be executed;
+/// a non-zero delay also disables the immediate setters it covers. Lowering
+/// it would let changes through early, so that has to be queued as well.
+pub fn handler_set_config_timelock(ctx: Context<AdminConfig>, delay_seconds: i64) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(
+        delay_seconds >= registry.config_timelock_seconds,
+        YourFunError::ConfigTimelocked
+    );
+
+    registry.config_timelock_seconds = delay_seconds;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetConfigTimelock, &delay_seconds)?;
+
+    msg!("Config timelock set to {}s", delay_seconds);
+    Ok(())
+}
+
+/// Sets the wallet that receives fees, so the authority need not be the fee
+/// destination. `Pubkey::default()` sends fees to the authority again.
+pub fn handler_update_treasury(ctx: Context<CoSignedAdminConfig>, treasury: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.treasury = treasury;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::UpdateTreasury, &treasury)?;
+
+    msg!("Treasury set to {}", treasury);
+    Ok(())
+}
+
+/// Switches registration between open and invite-only. Existing invites are
+/// kept either way.
+pub fn handler_set_permissioned_registration(
+    ctx: Context<AdminConfig>,
+    permissioned: bool,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.permissioned_registration = permissioned;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetPermissionedRegistration, &permissioned)?;
+
+    msg!("Permissioned registration: {}", permissioned);
+    Ok(())
+}
+
+/// Turns region restrictions on or off. While off, every region is allowed
+/// and the bitmap is kept for when they are turned back on.
+pub fn handler_set_region_restriction(
+    ctx: Context<AdminConfig>,
+    restrict_regions: bool,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.restrict_regions = restrict_regions;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetRegionRestriction, &restrict_regions)?;
+
+    msg!("Region restrictions: {}", restrict_regions);
+    Ok(())
+}
+
+/// Marks one region as allowed or blocked. Humans already verified in a
+/// blocked region keep their status.
+pub fn handler_set_region_allowed(
+    ctx: Context<AdminConfig>,
+    region_code: u16,
+    allowed: bool,
+) -> Result<()> {
+    require!(
+        region_code <= RegionStats::MAX_REGION_CODE,
+        YourFunError::InvalidRegionCode
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    registry.set_region_allowed(region_code, allowed);
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetRegionAllowed, &(region_code, allowed))?;
+
+    msg!("Region {} allowed: {}", region_code, allowed);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(personality_id: u8)]
+pub struct SetPersonalitySunset<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PersonalityConfig::INIT_SPACE,
+        seeds = [PersonalityConfig::SEED, &[personality_id]],
+        bump,
+    )]
+    pub personality_config: Box<Account<'info, PersonalityConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Retires a personality for new sessions once `deprecated_after` has passed;
+/// sessions already running with it finish normally. 0 lifts the sunset.
+pub fn handler_set_personality_sunset(
+    ctx: Context<SetPersonalitySunset>,
+    personality_id: u8,
+    deprecated_after: i64,
+) -> Result<()> {
+    require!(deprecated_after >= 0, YourFunError::InvalidConfigValue);
+
+    let personality_config = &mut ctx.accounts.personality_config;
+    if personality_config.bump == 0 {
+        personality_config.personality_id = personality_id;
+        personality_config.bump = ctx.bumps.personality_config;
+    }
+    personality_config.deprecated_after = deprecated_after;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::SetPersonalitySunset,
+        ctx.accounts.authority.key(),
+        &(personality_id, deprecated_after),
+    )?;
+
+    emit!(PersonalityDeprecated {
+        personality_id,
+        deprecated_after,
+        set_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Personality {} deprecated after {}", personality_id, deprecated_after);
+    Ok(())
+}
+
+/// Sets how long a verification stays Active and how long its grace period
+/// lasts. A validity of 0 disables lapsing altogether.
+pub fn handler_set_verification_validity(
+    ctx: Context<AdminConfig>,
+    validity_seconds: i64,
+    grace_period_seconds: i64,
+) -> Result<()> {
+    require!(
+        validity_seconds >= 0 && grace_period_seconds >= 0,
+        YourFunError::InvalidConfigValue
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    registry.verification_validity = validity_seconds;
+    registry.verification_grace_period = grace_period_seconds;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetVerificationValidity,
+        &(validity_seconds, grace_period_seconds),
+    )?;
+
+    msg!(
+        "Verification validity set to {}s with a {}s grace period",
+        validity_seconds,
+        grace_period_seconds
+    );
+    Ok(())
+}
+
+/// Opens the window for importing HumanRecords from `legacy_program_id`,
+/// or moves its end. Once a window has closed it cannot be reopened.
+pub fn handler_set_legacy_import(
+    ctx: Context<AdminConfig>,
+    legacy_program_id: Pubkey,
+    import_ends_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let registry = &mut ctx.accounts.registry;
+    require!(
+        registry.legacy_import_ends_at == 0 || now < registry.legacy_import_ends_at,
+        YourFunError::LegacyImportClosed
+    );
+    require!(
+        legacy_program_id != Pubkey::default() && legacy_program_id != crate::ID,
+        YourFunError::InvalidConfigValue
+    );
+    require!(import_ends_at > now, YourFunError::InvalidConfigValue);
+
+    registry.legacy_program_id = legacy_program_id;
+    registry.legacy_import_ends_at = import_ends_at;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetLegacyImport,
+        &(legacy_program_id, import_ends_at),
+    )?;
+
+    msg!("Legacy imports from {} open until {}", legacy_program_id, import_ends_at);
+    Ok(())
+}
+
+/// Designates the appeal arbiter and how long after a revocation appeals
+/// may be filed. The arbiter must not be the authority, which revokes.
+/// `Pubkey::default()` disables appeals.
+pub fn handler_set_arbiter(
+    ctx: Context<AdminConfig>,
+    arbiter: Pubkey,
+    appeal_window: i64,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(arbiter != registry.authority, YourFunError::InvalidConfigValue);
+    require!(appeal_window >= 0, YourFunError::InvalidConfigValue);
+
+    registry.arbiter = arbiter;
+    registry.appeal_window = appeal_window;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetArbiter, &(arbiter, appeal_window))?;
+
+    msg!("Arbiter set to {} with a {}s appeal window", arbiter, appeal_window);
+    Ok(())
+}
+
+/// Sets the weights of the behavioral, social-graph and longevity sub-scores
+/// in the composite humanity score. They must sum to 10 000 basis points.
+pub fn handler_set_sub_score_weights(
+    ctx: Context<AdminConfig>,
+    weights_bps: [u16; SUB_SCORE_COUNT],
+) -> Result<()> {
+    let total: u32 = weights_bps.iter().map(|&w| w as u32).sum();
+    require!(total == MAX_BPS as u32, YourFunError::InvalidSubScoreWeights);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.sub_score_weights_bps = weights_bps;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetSubScoreWeights, &weights_bps)?;
+
+    msg!("Sub-score weights set to {:?} bps", weights_bps);
+    Ok(())
+}
+
+/// Sets how long a verification can be contested before it takes effect.
+/// 0 makes verifications effective immediately.
+pub fn handler_set_contest_window(ctx: Context<AdminConfig>, contest_window: i64) -> Result<()> {
+    require!(contest_window >= 0, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.contest_window = contest_window;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetContestWindow, &contest_window)?;
+
+    msg!("Contest window set to {}s", contest_window);
+    Ok(())
+}
+
+/// Designates the key that may publish session templates alongside the
+/// authority. `Pubkey::default()` removes it.
+pub fn handler_set_curator(ctx: Context<AdminConfig>, curator: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.curator = curator;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetCurator, &curator)?;
+
+    msg!("Curator set to {}", curator);
+    Ok(())
+}
+
+/// Sets the bonus a session earns for meeting its goal: a flat amount plus
+/// `goal_bonus_bps` of its score. Both 0 disables the bonus.
+pub fn handler_set_goal_bonus(
+    ctx: Context<AdminConfig>,
+    goal_bonus_flat: u64,
+    goal_bonus_bps: u16,
+) -> Result<()> {
+    require!(goal_bonus_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.goal_bonus_flat = goal_bonus_flat;
+    registry.goal_bonus_bps = goal_bonus_bps;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetGoalBonus,
+        &(goal_bonus_flat, goal_bonus_bps),
+    )?;
+
+    msg!("Goal bonus set to {} + {} bps", goal_bonus_flat, goal_bonus_bps);
+    Ok(())
+}
+
+/// Sets how long after expiry an unclosed session may be reopened, and the
+/// fee for doing so. A grace period of 0 disables reopening.
+pub fn handler_set_reopen_policy(
+    ctx: Context<AdminConfig>,
+    reopen_grace_period: i64,
+    reopen_fee_lamports: u64,
+) -> Result<()> {
+    require!(reopen_grace_period >= 0, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.reopen_grace_period = reopen_grace_period;
+    registry.reopen_fee_lamports = reopen_fee_lamports;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetReopenPolicy,
+        &(reopen_grace_period, reopen_fee_lamports),
+    )?;
+
+    msg!(
+        "Reopen grace period set to {}s, fee {} lamports",
+        reopen_grace_period,
+        reopen_fee_lamports
+    );
+    Ok(())
+}
+
+/// Sets the largest content size an InteractionLog may reference. 0 restores
+/// `PlatformRegistry::DEFAULT_MAX_CONTENT_SIZE`.
+pub fn handler_set_max_content_size(ctx: Context<AdminConfig>, max_content_size: u32) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.max_content_size = max_content_size;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetMaxContentSize, &max_content_size)?;
+
+    msg!("Max content size set to {}", ctx.accounts.registry.content_size_limit());
+    Ok(())
+}
+
+/// Turns on companion co-signing with `companion_oracle` as the signing key,
+/// or turns it off with `Pubkey::default()`.
+pub fn handler_set_companion_oracle(ctx: Context<AdminConfig>, companion_oracle: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.companion_oracle = companion_oracle;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetCompanionOracle, &companion_oracle)?;
+
+    msg!("Companion oracle set to {}", companion_oracle);
+    Ok(())
+}
+
+/// Sets how long a closed session's score stays pending, and contestable,
+/// before `finalize_session` settles it. 0 settles scores at close.
+pub fn handler_set_settlement_window(ctx: Context<AdminConfig>, settlement_window: i64) -> Result<()> {
+    require!(settlement_window >= 0, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.settlement_window = settlement_window;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetSettlementWindow, &settlement_window)?;
+
+    msg!("Settlement window set to {}s", settlement_window);
+    Ok(())
+}
+
+/// Lists `mint` as a booster granting `multiplier_bps` to sessions created
+/// by burning one of its tokens, or updates its multiplier. 0 delists it;
+/// sessions already boosted keep their multiplier.
+pub fn handler_set_booster(ctx: Context<AdminConfig>, mint: Pubkey, multiplier_bps: u16) -> Result<()> {
+    require!(mint != Pubkey::default(), YourFunError::InvalidConfigValue);
+    require!(
+        multiplier_bps == 0
+            || (multiplier_bps > MAX_BPS && multiplier_bps <= PlatformRegistry::MAX_BOOSTER_MULTIPLIER_BPS),
+        YourFunError::InvalidConfigValue
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    registry.set_booster(mint, multiplier_bps)?;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetBooster, &(mint, multiplier_bps))?;
+
+    msg!("Booster {} multiplier set to {} bps", mint, multiplier_bps);
+    Ok(())
+}
+
+/// Designates the key that may approve scholarships alongside the
+/// authority. `Pubkey::default()` removes it.
+pub fn handler_set_program_officer(ctx: Context<AdminConfig>, program_officer: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.program_officer = program_officer;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetProgramOfficer, &program_officer)?;
+
+    msg!("Program officer set to {}", program_officer);
+    Ok(())
+}
+
+/// Sets the share of every fee routed to the insurance fund. Once nonzero,
+/// fee-charging instructions must supply the fund.
+pub fn handler_set_insurance_fee_bps(ctx: Context<AdminConfig>, insurance_fee_bps: u16) -> Result<()> {
+    require!(insurance_fee_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.insurance_fee_bps = insurance_fee_bps;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetInsuranceFeeBps, &insurance_fee_bps)?;
+
+    msg!("Insurance fee share set to {} bps", insurance_fee_bps);
+    Ok(())
+}
+
+/// Sets the share of each verification fee held for, and later paid to,
+/// the approving verifier. Applies to registrations from now on; shares
+/// already held keep their amount.
+pub fn handler_set_verifier_fee_share(ctx: Context<AdminConfig>, verifier_fee_share_bps: u16) -> Result<()> {
+    require!(verifier_fee_share_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.verifier_fee_share_bps = verifier_fee_share_bps;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetVerifierFeeShare,
+        &verifier_fee_share_bps,
+    )?;
+
+    msg!("Verifier fee share set to {} bps", verifier_fee_share_bps);
+    Ok(())
+}
+
+/// Sets the score multiplier of each interaction type in basis points.
+/// Setting all to zero restores the built-in defaults. Open sessions pick
+/// the new multipliers up from their next interaction.
+pub fn handler_set_type_multipliers(
+    ctx: Context<AdminConfig>,
+    type_multipliers: [u16; TYPE_MULTIPLIER_COUNT],
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.type_multipliers = type_multipliers;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetTypeMultipliers,
+        &type_multipliers,
+    )?;
+
+    msg!("Interaction type multipliers set to {:?}", type_multipliers);
+    Ok(())
+}
+
+/// Sets the shortest duration an interaction may report; 0 removes the
+/// minimum. Interactions already recorded are not revisited.
+pub fn handler_set_min_interaction_duration(
+    ctx: Context<AdminConfig>,
+    duration_seconds: u16,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.min_interaction_duration_seconds = duration_seconds;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetMinInteractionDuration,
+        &duration_seconds,
+    )?;
+
+    msg!("Minimum interaction duration set to {}s", duration_seconds);
+    Ok(())
+}
+
+/// Sets the seconds that must pass between a session's interactions; 0
+/// removes the minimum. Bundled interactions are held to it by their
+/// timestamps.
+pub fn handler_set_min_interaction_gap(ctx: Context<AdminConfig>, gap_seconds: u16) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.min_interaction_gap_seconds = gap_seconds;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetMinInteractionGap, &gap_seconds)?;
+
+    msg!("Minimum interaction gap set to {}s", gap_seconds);
+    Ok(())
+}
+
+/// Sets the highest personality sessions may be opened with; 255 allows
+/// any. Open sessions and published templates are not revisited, but a
+/// template above the new maximum can no longer start sessions.
+pub fn handler_set_max_personality_id(ctx: Context<AdminConfig>, max_personality_id: u8) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.max_personality_id = max_personality_id;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetMaxPersonalityId, &max_personality_id)?;
+
+    msg!("Personalities capped at id {}", max_personality_id);
+    Ok(())
+}
+
+/// Caps the sessions a human may have open at once; 0 removes the cap.
+/// Sessions already open beyond a lowered cap stay open, but no new ones
+/// can be created until enough close.
+pub fn handler_set_max_active_sessions(ctx: Context<AdminConfig>, max_active_sessions: u8) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.max_active_sessions = max_active_sessions;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetMaxActiveSessions, &max_active_sessions)?;
+
+    msg!("Active sessions per human capped at {}", max_active_sessions);
+    Ok(())
+}
+
+/// Caps the number of humans verified at once; 0 removes the cap. Approvals
+/// beyond it are waitlisted. Raising the cap does not admit anyone by
+/// itself; `admit_from_waitlist` does.
+pub fn handler_set_max_verified_humans(ctx: Context<AdminConfig>, max_verified_humans: u64) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.max_verified_humans = max_verified_humans;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetMaxVerifiedHumans, &max_verified_humans)?;
+
+    msg!("Verified humans capped at {}", max_verified_humans);
+    Ok(())
+}
+
+/// Sets how long a verified human may go without proving liveness before
+/// `apply_liveness_decay` lowers their level (0 = never), and whether
+/// level-3 humans must prove it with a verifier-co-signed challenge.
+pub fn handler_set_liveness_policy(
+    ctx: Context<AdminConfig>,
+    interval_seconds: i64,
+    challenge_required: bool,
+) -> Result<()> {
+    require!(interval_seconds >= 0, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.liveness_interval_seconds = interval_seconds;
+    registry.liveness_challenge_required = challenge_required;
+    registry.bump_state_version()?;
+    ctx.accounts.record(
+        ctx.bumps.admin_action,
+        AdminActionKind::SetLivenessPolicy,
+        &(interval_seconds, challenge_required),
+    )?;
+
+    msg!(
+        "Liveness interval set to {}s, challenge required: {}",
+        interval_seconds,
+        challenge_required
+    );
+    Ok(())
+}
+
+/// Sets the share of the score increment private interactions earn.
+pub fn handler_set_private_score_bps(ctx: Context<AdminConfig>, private_score_bps: u16) -> Result<()> {
+    require!(private_score_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.private_score_bps = private_score_bps;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetPrivateScoreBps, &private_score_bps)?;
+
+    msg!("Private interactions earn {} bps of their score", private_score_bps);
+    Ok(())
+}
+
+/// Turns enforcement of registered topics on or off. Sessions already
+/// using an unregistered topic keep it.
+pub fn handler_set_enforce_registered_topics(ctx: Context<AdminConfig>, enforce: bool) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.enforce_registered_topics = enforce;
+    registry.bump_state_version()?;
+    ctx.accounts
+        .record(ctx.bumps.admin_action, AdminActionKind::SetEnforceRegisteredTopics, &enforce)?;
+
+    msg!("Registered topics enforced: {}", enforce);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::{HumanRecord, RaffleEpoch, SessionAccount, StatsBucket, VerificationLevel};
+
+    /// Starts from a registry with `authority` and `guardian` funded.
+    fn start(authority: &Keypair, guardian: &Keypair, configure: impl FnOnce(&mut PlatformRegistry)) -> Harness {
+        let guardian_key = guardian.pubkey();
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.guardian = guardian_key;
+                configure(registry);
+            })
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&guardian.pubkey(), 1_000_000_000);
+        harness
+    }
+
+    fn registry(harness: &mut Harness) -> PlatformRegistry {
+        harness.account(&client::registry_address())
+    }
+
+    fn set_paused(harness: &mut Harness, signer: &Keypair, paused: bool) -> std::result::Result<(), InstructionError> {
+        let count = registry(harness).admin_action_count;
+        harness.process(client::build_set_paused_ix(&signer.pubkey(), None, count, paused), &[signer])
+    }
+
+    #[test]
+    fn guardian_can_pause_and_unpause_only_when_allowed() {
+        let (authority, guardian) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &guardian, |_| {});
+        let stranger = harness.wallet();
+
+        set_paused(&mut harness, &guardian, true).unwrap();
+        assert!(registry(&mut harness).is_paused);
+        assert_eq!(
+            set_paused(&mut harness, &guardian, false),
+            Err(custom(YourFunError::Unauthorized))
+        );
+        assert_eq!(
+            set_paused(&mut harness, &stranger, true),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        set_paused(&mut harness, &authority, false).unwrap();
+        assert!(!registry(&mut harness).is_paused);
+
+        let mut harness = start(&authority, &guardian, |registry| registry.guardian_can_unpause = true);
+        set_paused(&mut harness, &guardian, true).unwrap();
+        set_paused(&mut harness, &guardian, false).unwrap();
+        assert!(!registry(&mut harness).is_paused);
+    }
+
+    /// The pause-gated instructions a paused registry must refuse: a
+    /// registration by `newcomer`, a verification of `pending` and a session
+    /// for `user`.
+    fn gated_ixs(authority: &Keypair, newcomer: &Keypair, pending: &Pubkey, user: &Keypair) -> [Instruction; 3] {
+        let register = client::build_register_human_ix(
+            &newcomer.pubkey(),
+            &authority.pubkey(),
+            false,
+            false,
+            None,
+            None,
+            [7u8; 32],
+            vec![0x5a; 64],
+        );
+        let verify = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            pending,
+            None,
+            &hash(&pending.to_bytes()).to_bytes(),
+            registered_challenge_response(pending),
+            100,
+            VerificationLevel::Basic as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            NOW,
+        );
+        let create_session = client::build_create_session_ix(
+            &user.pubkey(),
+            None,
+            0,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        );
+        [register, verify, create_session]
+    }
+
+    /// Runs the `gated_ixs`.
+    fn gated(
+        harness: &mut Harness,
+        authority: &Keypair,
+        newcomer: &Keypair,
+        pending: &Pubkey,
+        user: &Keypair,
+    ) -> [std::result::Result<(), InstructionError>; 3] {
+        let [register, verify, create_session] = gated_ixs(authority, newcomer, pending, user);
+        [
+            harness.process(register, &[newcomer]),
+            harness.process(verify, &[authority]),
+            harness.process(create_session, &[user]),
+        ]
+    }
+
+    #[test]
+    fn pausing_blocks_registration_verification_and_sessions_until_unpaused() {
+        let authority = Keypair::new();
+        let (newcomer, pending, user) = (Keypair::new(), Pubkey::new_unique(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_registered_human(pending)
+            .with_verified_human(user.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        for wallet in [&authority, &newcomer, &user] {
+            harness.fund(&wallet.pubkey(), 10_000_000_000);
+        }
+
+        set_paused(&mut harness, &authority, true).unwrap();
+        for result in gated(&mut harness, &authority, &newcomer, &pending, &user) {
+            assert_eq!(result, Err(custom(YourFunError::RegistryPaused)));
+        }
+
+        set_paused(&mut harness, &authority, false).unwrap();
+        for result in gated(&mut harness, &authority, &newcomer, &pending, &user) {
+            assert_eq!(result, Ok(()));
+        }
+    }
+
+    #[test]
+    fn each_pause_flag_blocks_only_its_own_operation() {
+        let flags = [
+            PlatformRegistry::PAUSE_REGISTRATIONS,
+            PlatformRegistry::PAUSE_VERIFICATIONS,
+            PlatformRegistry::PAUSE_SESSIONS,
+            PlatformRegistry::PAUSE_INTERACTIONS,
+        ];
+        for (paused, flag) in flags.into_iter().enumerate() {
+            let authority = Keypair::new();
+            let (newcomer, pending, user) = (Keypair::new(), Pubkey::new_unique(), Keypair::new());
+            let accounts = FixtureBuilder::new()
+                .with_authority(authority.pubkey())
+                .with_region_stats(0)
+                .with_registered_human(pending)
+                .with_verified_human(user.pubkey())
+                .with_open_session()
+                .build();
+            let mut harness = Harness::start(accounts);
+            for wallet in [&authority, &newcomer, &user] {
+                harness.fund(&wallet.pubkey(), 10_000_000_000);
+            }
+            let set_flags = client::build_set_pause_flags_ix(&authority.pubkey(), None, 0, flag);
+            harness.process(set_flags, &[&authority]).unwrap();
+
+            let [register, verify, _] = gated_ixs(&authority, &newcomer, &pending, &user);
+            let create_session = client::build_create_session_ix(
+                &user.pubkey(),
+                None,
+                1,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                NOW,
+            );
+            let interaction = client::build_record_interaction_ix(
+                &user.pubkey(),
+                0,
+                0,
+                [3u8; 32],
+                1,
+                80,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            );
+            let results = [
+                harness.process(register, &[&newcomer]),
+                harness.process(verify, &[&authority]),
+                harness.process(create_session, &[&user]),
+                harness.process(interaction, &[&user]),
+            ];
+            for (operation, result) in results.into_iter().enumerate() {
+                let expected = if operation == paused { Err(custom(YourFunError::RegistryPaused)) } else { Ok(()) };
+                assert_eq!(result, expected, "flag {flag:#06b}, operation {operation}");
+            }
+        }
+
+        // Undefined bits are rejected, and the guardian cannot set flags.
+        let (authority, guardian) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &guardian, |_| {});
+        let undefined = client::build_set_pause_flags_ix(&authority.pubkey(), None, 0, PlatformRegistry::PAUSE_ALL + 1);
+        assert_eq!(
+            harness.process(undefined, &[&authority]),
+            Err(custom(YourFunError::InvalidConfigValue))
+        );
+        let by_guardian = client::build_set_pause_flags_ix(&guardian.pubkey(), None, 0, PlatformRegistry::PAUSE_ALL);
+        assert_eq!(
+            harness.process(by_guardian, &[&guardian]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+    }
+
+    /// Runs a `CoSignedAdminConfig` setter, co-signed by `co_signer` if any.
+    fn co_signed(
+        harness: &mut Harness,
+        authority: &Keypair,
+        co_signer: Option<&Keypair>,
+        data: impl anchor_lang::InstructionData,
+    ) -> std::result::Result<(), InstructionError> {
+        let count = registry(harness).admin_action_count;
+        let co_signer_key = co_signer.map(|signer| signer.pubkey());
+        let ix = client::build_co_signed_admin_config_ix(&authority.pubkey(), co_signer_key.as_ref(), count, data);
+        let mut signers = vec![authority];
+        signers.extend(co_signer);
+        harness.process(ix, &signers)
+    }
+
+    #[test]
+    fn high_impact_setters_need_the_co_authority_only_once_it_is_set() {
+        let (authority, guardian, co_authority, impostor) = (Keypair::new(), Keypair::new(), Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &guardian, |_| {});
+        let treasury = |treasury| crate::instruction::UpdateTreasury { treasury };
+        let set_co_authority = |harness: &mut Harness, co_signer, co_authority| {
+            co_signed(harness, &authority, co_signer, crate::instruction::SetCoAuthority { co_authority })
+        };
+
+        // Disabled: the authority alone suffices.
+        co_signed(&mut harness, &authority, None, treasury(Pubkey::new_unique())).unwrap();
+        set_co_authority(&mut harness, None, co_authority.pubkey()).unwrap();
+        assert_eq!(registry(&mut harness).co_authority, co_authority.pubkey());
+
+        let target = Pubkey::new_unique();
+        assert_eq!(
+            co_signed(&mut harness, &authority, None, treasury(target)),
+            Err(custom(YourFunError::CoAuthorityRequired))
+        );
+        assert_eq!(
+            co_signed(&mut harness, &authority, Some(&impostor), treasury(target)),
+            Err(custom(YourFunError::CoAuthorityMismatch))
+        );
+        co_signed(&mut harness, &authority, Some(&co_authority), treasury(target)).unwrap();
+        assert_eq!(registry(&mut harness).treasury, target);
+
+        // Pausing and day-to-day setters stay with the authority; resuming does not.
+        set_paused(&mut harness, &authority, true).unwrap();
+        assert_eq!(
+            set_paused(&mut harness, &authority, false),
+            Err(custom(YourFunError::CoAuthorityRequired))
+        );
+        let count = registry(&mut harness).admin_action_count;
+        let resume = client::build_set_paused_ix(&authority.pubkey(), Some(&co_authority.pubkey()), count, false);
+        harness.process(resume, &[&authority, &co_authority]).unwrap();
+        let count = registry(&mut harness).admin_action_count;
+        let threshold = client::build_admin_config_ix(
+            &authority.pubkey(),
+            count,
+            crate::instruction::SetLogThresholdScore { log_threshold_score: 42 },
+        );
+        harness.process(threshold, &[&authority]).unwrap();
+
+        // The authority alone cannot remove the co-authority.
+        assert_eq!(
+            set_co_authority(&mut harness, None, Pubkey::default()),
+            Err(custom(YourFunError::CoAuthorityRequired))
+        );
+        set_co_authority(&mut harness, Some(&co_authority), Pubkey::default()).unwrap();
+        co_signed(&mut harness, &authority, None, treasury(Pubkey::new_unique())).unwrap();
+    }
+
+    #[test]
+    fn raffle_claims_and_session_closes_still_work_while_paused() {
+        let (authority, winner) = (Keypair::new(), Keypair::new());
+        let epoch = RaffleEpoch::epoch_at(NOW) - 1;
+        let prize = 500_000_000;
+        let mut raffle = FixtureAccount::new(
+            client::raffle_epoch_address(epoch),
+            &RaffleEpoch {
+                epoch,
+                ticket_count: 1,
+                page_count: 1,
+                prize_lamports: prize,
+                draw_slot: 1,
+                winner_index: 0,
+                winner: winner.pubkey(),
+                claimed: false,
+                bump: Pubkey::find_program_address(&[RaffleEpoch::SEED, &epoch.to_le_bytes()], &crate::ID).1,
+            },
+        );
+        raffle.lamports += prize;
+        let mut accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.is_paused = true)
+            .with_verified_human(winner.pubkey())
+            .with_open_session()
+            .build();
+        accounts.push(raffle);
+        let mut harness = Harness::start(accounts);
+        harness.fund(&winner.pubkey(), 1_000_000_000);
+
+        let claim = client::build_claim_raffle_prize_ix(&winner.pubkey(), epoch);
+        harness.process(claim, &[&winner]).unwrap();
+        let raffle: RaffleEpoch = harness.account(&client::raffle_epoch_address(epoch));
+        assert!(raffle.claimed);
+
+        let close = client::build_close_session_ix(&winner.pubkey(), &winner.pubkey(), 0, 0, false, None, None);
+        harness.process(close, &[&winner]).unwrap();
+        let session: SessionAccount = harness.account(&client::session_address(
+            &client::human_record_address(&winner.pubkey()),
+            0,
+        ));
+        assert!(!session.is_active());
+    }
+
+    #[test]
+    fn freezing_blocks_every_setter_but_not_pausing_or_operations() {
+        let authority = Keypair::new();
+        let (newcomer, pending, user) = (Keypair::new(), Pubkey::new_unique(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_registered_human(pending)
+            .with_verified_human(user.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        for wallet in [&authority, &newcomer, &user] {
+            harness.fund(&wallet.pubkey(), 10_000_000_000);
+        }
+
+        let threshold = client::build_admin_config_ix(
+            &authority.pubkey(),
+            0,
+            crate::instruction::SetLogThresholdScore { log_threshold_score: 10 },
+        );
+        harness.process(threshold, &[&authority]).unwrap();
+        let count = registry(&mut harness).admin_action_count;
+        let freeze = client::build_admin_config_ix(&authority.pubkey(), count, crate::instruction::FreezeConfig {});
+        harness.process(freeze, &[&authority]).unwrap();
+        assert!(registry(&mut harness).config_frozen);
+
+        let count = registry(&mut harness).admin_action_count;
+        let blocked = [
+            client::build_admin_config_ix(
+                &authority.pubkey(),
+                count,
+                crate::instruction::SetLogThresholdScore { log_threshold_score: 20 },
+            ),
+            client::build_admin_config_ix(
+                &authority.pubkey(),
+                count,
+                crate::instruction::SetModerator { moderator: Pubkey::new_unique() },
+            ),
+            client::build_admin_config_ix(
+                &authority.pubkey(),
+                count,
+                crate::instruction::SetVerificationFee { fee_lamports: 1 },
+            ),
+            client::build_admin_config_ix(&authority.pubkey(), count, crate::instruction::FreezeConfig {}),
+            client::build_co_signed_admin_config_ix(
+                &authority.pubkey(),
+                None,
+                count,
+                crate::instruction::UpdateTreasury { treasury: Pubkey::new_unique() },
+            ),
+            client::build_co_signed_admin_config_ix(
+                &authority.pubkey(),
+                None,
+                count,
+                crate::instruction::SetCoAuthority { co_authority: Pubkey::new_unique() },
+            ),
+            client::build_set_personality_sunset_ix(&authority.pubkey(), count, 0, NOW),
+        ];
+        for ix in blocked {
+            assert_eq!(harness.process(ix, &[&authority]), Err(custom(YourFunError::ConfigFrozen)));
+        }
+        let frozen = registry(&mut harness);
+        assert_eq!(frozen.log_threshold_score, 10);
+        assert_eq!(frozen.admin_action_count, count);
+
+        // The emergency pause and everyday operations are not configuration.
+        set_paused(&mut harness, &authority, true).unwrap();
+        set_paused(&mut harness, &authority, false).unwrap();
+        for result in gated(&mut harness, &authority, &newcomer, &pending, &user) {
+            assert_eq!(result, Ok(()));
+        }
+    }
+
+    #[test]
+    fn state_version_moves_once_with_every_registry_write_and_never_otherwise() {
+        let authority = Keypair::new();
+        let (newcomer, pending, user) = (Keypair::new(), Pubkey::new_unique(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_registered_human(pending)
+            .with_verified_human(user.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        for wallet in [&authority, &newcomer, &user] {
+            harness.fund(&wallet.pubkey(), 10_000_000_000);
+        }
+        let version = |data: &[u8]| {
+            let offset = crate::constants::REGISTRY_STATE_VERSION_OFFSET;
+            u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+        };
+        // Runs `ix` and checks it bumped the version exactly when it wrote
+        // anything else in the registry, returning whether it did.
+        let step = |harness: &mut Harness, ix: Instruction, signers: &[&Keypair]| {
+            let before = harness.raw_account(&client::registry_address());
+            harness.process(ix, signers).unwrap();
+            let after = harness.raw_account(&client::registry_address());
+            let bumps = version(&after) - version(&before);
+            let offset = crate::constants::REGISTRY_STATE_VERSION_OFFSET;
+            let mut unversioned = after.clone();
+            unversioned[offset..offset + 8].copy_from_slice(&before[offset..offset + 8]);
+            assert_eq!(bumps, u64::from(unversioned != before));
+            bumps == 1
+        };
+        let count = |harness: &mut Harness| registry(harness).admin_action_count;
+
+        let [register, verify, create_session] = gated_ixs(&authority, &newcomer, &pending, &user);
+        // A registration is not counted in the registry until verified.
+        assert!(!step(&mut harness, register, &[&newcomer]));
+        assert!(step(&mut harness, verify, &[&authority]));
+        let pause = client::build_set_paused_ix(&authority.pubkey(), None, count(&mut harness), true);
+        assert!(step(&mut harness, pause, &[&authority]));
+        let resume = client::build_set_paused_ix(&authority.pubkey(), None, count(&mut harness), false);
+        assert!(step(&mut harness, resume, &[&authority]));
+        let setter = client::build_admin_config_ix(
+            &authority.pubkey(),
+            count(&mut harness),
+            crate::instruction::SetLogThresholdScore { log_threshold_score: 10 },
+        );
+        assert!(step(&mut harness, setter, &[&authority]));
+
+        // Session activity leaves the registry alone until it is aggregated.
+        assert!(!step(&mut harness, create_session, &[&user]));
+        let interaction = client::build_record_interaction_ix(
+            &user.pubkey(),
+            0,
+            0,
+            [3u8; 32],
+            1,
+            80,
+            60,
+            [0u8; 32],
+            0,
+            false,
+            false,
+            NOW,
+        );
+        assert!(!step(&mut harness, interaction, &[&user]));
+        harness.warp_to(NOW + SessionAccount::HEARTBEAT_MIN_INTERVAL);
+        let heartbeat = client::build_heartbeat_session_ix(&user.pubkey(), 0);
+        assert!(!step(&mut harness, heartbeat, &[&user]));
+        let session = client::session_address(&client::human_record_address(&user.pubkey()), 0);
+        let bucket_index = (0..StatsBucket::COUNT)
+            .find(|&index| client::build_aggregate_stats_ix(index).accounts[1].pubkey == client::stats_bucket_address(&session))
+            .unwrap();
+        assert!(step(&mut harness, client::build_aggregate_stats_ix(bucket_index), &[]));
+
+        let revoke = client::build_revoke_human_ix(&authority.pubkey(), &pending, 0, count(&mut harness), None);
+        assert!(step(&mut harness, revoke, &[&authority]));
+    }
+
+    #[test]
+    fn every_admin_call_advances_the_config_version_once_and_reports_the_new_state() {
+        let (authority, wallet) = (Keypair::new(), Pubkey::new_unique());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.verification_fee_lamports = 1_000_000)
+            .with_region_stats(0)
+            .with_verified_human(wallet)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        let treasury = Pubkey::new_unique();
+        type Step = (fn(&Pubkey, u64, Pubkey, Pubkey) -> Instruction, u32);
+        let steps: [Step; 6] = [
+            (
+                |authority, count, _, _| client::build_set_paused_ix(authority, None, count, true),
+                PlatformRegistry::CONFIG_PAUSE,
+            ),
+            (
+                |authority, count, _, _| {
+                    let data = crate::instruction::SetLogThresholdScore { log_threshold_score: 10 };
+                    client::build_admin_config_ix(authority, count, data)
+                },
+                PlatformRegistry::CONFIG_SCORING,
+            ),
+            (
+                |authority, count, _, _| {
+                    let data = crate::instruction::SetVerificationFee { fee_lamports: 1_200_000 };
+                    client::build_admin_config_ix(authority, count, data)
+                },
+                PlatformRegistry::CONFIG_FEES,
+            ),
+            (
+                |authority, count, _, _| {
+                    let data = crate::instruction::UpdateConfig {
+                        verification_fee_lamports: Some(1_300_000),
+                        min_behavioral_score: None,
+                        max_session_duration: Some(7_200),
+                        max_interactions_per_session: None,
+                    };
+                    client::build_admin_config_ix(authority, count, data)
+                },
+                PlatformRegistry::CONFIG_FEES | PlatformRegistry::CONFIG_SESSIONS,
+            ),
+            (
+                |authority, count, treasury, _| {
+                    let data = crate::instruction::UpdateTreasury { treasury };
+                    client::build_co_signed_admin_config_ix(authority, None, count, data)
+                },
+                PlatformRegistry::CONFIG_TREASURY,
+            ),
+            (
+                |authority, count, _, wallet| client::build_revoke_human_ix(authority, &wallet, 0, count, None),
+                0,
+            ),
+        ];
+
+        for (index, (build, field_mask)) in steps.into_iter().enumerate() {
+            let before = registry(&mut harness);
+            let ix = build(&authority.pubkey(), before.admin_action_count, treasury, wallet);
+            let events = harness.process_with_events::<ConfigUpdated>(ix, &[&authority]).unwrap();
+            let after = registry(&mut harness);
+            assert_eq!(events.len(), 1, "step {index}");
+            let event = &events[0];
+            assert_eq!(after.config_version, before.config_version + 1, "step {index}");
+            assert_eq!(event.version, after.config_version);
+            assert_eq!(event.field_mask, field_mask, "step {index}");
+            assert_eq!(
+                (event.old_fee, event.new_fee),
+                (before.verification_fee_lamports, after.verification_fee_lamports)
+            );
+            assert_eq!((event.updated_by, event.updated_at), (authority.pubkey(), NOW));
+        }
+        let after = registry(&mut harness);
+        assert_eq!(
+            (after.config_version, after.verification_fee_lamports, after.max_session_duration, after.treasury),
+            (6, 1_300_000, 7_200, treasury)
+        );
+
+        // Operations that are not administrative leave the version alone.
+        let user = harness.wallet();
+        let register = client::build_register_human_ix(
+            &user.pubkey(),
+            &treasury,
+            false,
+            false,
+            None,
+            None,
+            [7u8; 32],
+            vec![0x5a; 64],
+        );
+        harness.fund(&treasury, 1_000_000_000);
+        let resume = client::build_set_paused_ix(&authority.pubkey(), None, registry(&mut harness).admin_action_count, false);
+        harness.process(resume, &[&authority]).unwrap();
+        let events = harness.process_with_events::<ConfigUpdated>(register, &[&user]).unwrap();
+        assert!(events.is_empty());
+        assert_eq!(registry(&mut harness).config_version, 7);
+    }
+
+    #[test]
+    fn fee_updates_stay_within_the_delta_cap_and_wait_out_the_cooldown() {
+        let (authority, guardian) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &guardian, |registry| registry.verification_fee_lamports = 1_000_000);
+        let cooldown = 3_600;
+        let set_fee = |harness: &mut Harness, fee_lamports| {
+            let count = registry(harness).admin_action_count;
+            let data = crate::instruction::SetVerificationFee { fee_lamports };
+            harness.process(client::build_admin_config_ix(&authority.pubkey(), count, data), &[&authority])
+        };
+        let guardrails = |max_fee_delta_bps, fee_update_cooldown_seconds| crate::instruction::SetFeeGuardrails {
+            max_fee_delta_bps,
+            fee_update_cooldown_seconds,
+        };
+
+        for (bps, seconds) in [(0, cooldown), (MAX_BPS + 1, cooldown), (5_000, -1)] {
+            assert_eq!(
+                co_signed(&mut harness, &authority, None, guardrails(bps, seconds)),
+                Err(custom(YourFunError::InvalidConfigValue))
+            );
+        }
+        co_signed(&mut harness, &authority, None, guardrails(5_000, cooldown)).unwrap();
+
+        // An increase of exactly the cap passes; one lamport more does not.
+        assert_eq!(set_fee(&mut harness, 1_500_001), Err(custom(YourFunError::FeeDeltaTooLarge)));
+        set_fee(&mut harness, 1_500_000).unwrap();
+        assert_eq!(registry(&mut harness).last_fee_update_at, NOW);
+
+        harness.warp_to(NOW + cooldown - 1);
+        assert_eq!(set_fee(&mut harness, 1_400_000), Err(custom(YourFunError::FeeUpdateTooSoon)));
+
+        // Decreases are capped against the current fee too.
+        harness.warp_to(NOW + cooldown);
+        assert_eq!(set_fee(&mut harness, 749_999), Err(custom(YourFunError::FeeDeltaTooLarge)));
+        set_fee(&mut harness, 750_000).unwrap();
+
+        // update_config shares the cooldown.
+        harness.warp_to(NOW + cooldown + 1);
+        let update = client::build_admin_config_ix(
+            &authority.pubkey(),
+            registry(&mut harness).admin_action_count,
+            crate::instruction::UpdateConfig {
+                verification_fee_lamports: Some(800_000),
+                min_behavioral_score: None,
+                max_session_duration: None,
+                max_interactions_per_session: None,
+            },
+        );
+        assert_eq!(harness.process(update, &[&authority]), Err(custom(YourFunError::FeeUpdateTooSoon)));
+        let after = registry(&mut harness);
+        assert_eq!((after.verification_fee_lamports, after.last_fee_update_at), (750_000, NOW + cooldown));
+    }
+
+    #[test]
+    fn admin_operations_append_sequential_audit_entries() {
+        let (authority, guardian, wallet) = (Keypair::new(), Keypair::new(), Pubkey::new_unique());
+        let guardian_key = guardian.pubkey();
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.guardian = guardian_key)
+            .with_region_stats(0)
+            .with_verified_human(wallet)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&guardian.pubkey(), 1_000_000_000);
+
+        set_paused(&mut harness, &guardian, true).unwrap();
+        harness.warp_to(NOW + 10);
+        let threshold = client::build_admin_config_ix(
+            &authority.pubkey(),
+            1,
+            crate::instruction::SetLogThresholdScore { log_threshold_score: 42 },
+        );
+        harness.process(threshold, &[&authority]).unwrap();
+        harness.warp_to(NOW + 20);
+        let revoke = client::build_revoke_human_ix(&authority.pubkey(), &wallet, 0, 2, None);
+        harness.process(revoke, &[&authority]).unwrap();
+        assert_eq!(registry(&mut harness).admin_action_count, 3);
+
+        let expected = [
+            (AdminActionKind::SetPaused, guardian.pubkey(), true.try_to_vec().unwrap(), NOW),
+            (AdminActionKind::SetLogThresholdScore, authority.pubkey(), vec![42], NOW + 10),
+            (AdminActionKind::RevokeHuman, authority.pubkey(), wallet.to_bytes().to_vec(), NOW + 20),
+        ];
+        for (index, (action, actor, params, timestamp)) in expected.into_iter().enumerate() {
+            let entry: AdminAction = harness.account(&client::admin_action_address(index as u64));
+            assert_eq!(entry.action_index, index as u64);
+            assert_eq!(entry.action, action, "entry {index}");
+            assert_eq!(entry.actor, actor);
+            assert_eq!(entry.params_hash, hash(&params).to_bytes());
+            assert_eq!(entry.timestamp, timestamp);
+        }
+
+        // A stale count points at an existing entry, which cannot be rewritten.
+        let overwrite = client::build_set_paused_ix(&authority.pubkey(), None, 0, false);
+        assert!(harness.process(overwrite, &[&authority]).is_err());
+        let first: AdminAction = harness.account(&client::admin_action_address(0));
+        assert_eq!(first.actor, guardian.pubkey());
+    }
+
+    #[test]
+    fn maintenance_windows_pause_only_while_open_and_can_be_cancelled() {
+        let authority = Keypair::new();
+        let accounts = FixtureBuilder::new().with_authority(authority.pubkey()).build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        let (start_at, end_at) = (NOW + 100, NOW + 200);
+        let register_at = |harness: &mut Harness, now| {
+            harness.warp_to(now);
+            let user = harness.wallet();
+            let ix = client::build_register_human_ix(
+                &user.pubkey(),
+                &authority.pubkey(),
+                false,
+                false,
+                None,
+                None,
+                [7u8; 32],
+                user.pubkey().to_bytes().to_vec(),
+            );
+            harness.process(ix, &[&user])
+        };
+        let schedule = |harness: &mut Harness, start_at, end_at| {
+            let count = registry(harness).admin_action_count;
+            let ix = client::build_schedule_maintenance_ix(&authority.pubkey(), count, start_at, end_at);
+            harness.process(ix, &[&authority])
+        };
+        let cancel = |harness: &mut Harness| {
+            let count = registry(harness).admin_action_count;
+            harness.process(client::build_cancel_maintenance_ix(&authority.pubkey(), count), &[&authority])
+        };
+
+        schedule(&mut harness, start_at, end_at).unwrap();
+        assert_eq!(
+            schedule(&mut harness, end_at, end_at + 100),
+            Err(custom(YourFunError::MaintenanceAlreadyScheduled))
+        );
+        register_at(&mut harness, start_at - 1).unwrap();
+        for now in [start_at, end_at - 1] {
+            assert_eq!(
+                register_at(&mut harness, now),
+                Err(custom(YourFunError::MaintenanceWindow))
+            );
+        }
+        register_at(&mut harness, end_at).unwrap();
+
+        // A past window is ignored, so a new one can be scheduled and then
+        // cancelled once it has begun.
+        assert_eq!(cancel(&mut harness), Err(custom(YourFunError::NoMaintenanceScheduled)));
+        schedule(&mut harness, NOW + 300, NOW + 400).unwrap();
+        assert_eq!(
+            register_at(&mut harness, NOW + 350),
+            Err(custom(YourFunError::MaintenanceWindow))
+        );
+        cancel(&mut harness).unwrap();
+        register_at(&mut harness, NOW + 351).unwrap();
+        assert_eq!(cancel(&mut harness), Err(custom(YourFunError::NoMaintenanceScheduled)));
+    }
+
+    #[test]
+    fn update_config_changes_only_the_fields_given_and_validates_the_result() {
+        let (authority, wallet) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.verification_fee_lamports = 1_000_000)
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let stranger = harness.wallet();
+        let update = |harness: &mut Harness, signer: &Keypair, fee, score, duration, cap| {
+            let count = registry(harness).admin_action_count;
+            let ix = client::build_admin_config_ix(
+                &signer.pubkey(),
+                count,
+                crate::instruction::UpdateConfig {
+                    verification_fee_lamports: fee,
+                    min_behavioral_score: score,
+                    max_session_duration: duration,
+                    max_interactions_per_session: cap,
+                },
+            );
+            harness.process(ix, &[signer])
+        };
+
+        update(&mut harness, &authority, None, Some(60), None, None).unwrap();
+        let after = registry(&mut harness);
+        assert_eq!(
+            (
+                after.verification_fee_lamports,
+                after.min_behavioral_score,
+                after.max_session_duration,
+                after.max_interactions_per_session,
+            ),
+            (1_000_000, 60, 3_600, 100)
+        );
+        update(&mut harness, &authority, Some(1_200_000), None, Some(7_200), Some(50)).unwrap();
+        let after = registry(&mut harness);
+        assert_eq!(
+            (
+                after.verification_fee_lamports,
+                after.min_behavioral_score,
+                after.max_session_duration,
+                after.max_interactions_per_session,
+            ),
+            (1_200_000, 60, 7_200, 50)
+        );
+        let entry: AdminAction = harness.account(&client::admin_action_address(1));
+        assert_eq!(entry.action, AdminActionKind::UpdateConfig);
+
+        // Each invalid value fails the whole update, leaving every field as it was.
+        let invalid = [
+            (None, Some(MAX_SCORE + 1), None, None, YourFunError::InvalidScoreConfig),
+            (None, None, Some(0), None, YourFunError::SessionDurationExceeded),
+            (None, None, None, Some(0), YourFunError::InvalidConfigValue),
+            (Some(10_000_000), Some(70), None, None, YourFunError::FeeDeltaTooLarge),
+        ];
+        for (fee, score, duration, cap, error) in invalid {
+            assert_eq!(
+                update(&mut harness, &authority, fee, score, duration, cap),
+                Err(custom(error))
+            );
+        }
+        assert_eq!(
+            update(&mut harness, &stranger, None, Some(10), None, None),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        let unchanged = registry(&mut harness);
+        assert_eq!(
+            (unchanged.verification_fee_lamports, unchanged.min_behavioral_score, unchanged.admin_action_count),
+            (1_200_000, 60, 2)
+        );
+
+        // The open session keeps its expiry; a new one uses the new duration.
+        let human_record = client::human_record_address(&wallet.pubkey());
+        let open: SessionAccount = harness.account(&client::session_address(&human_record, 0));
+        assert_eq!(open.expires_at, NOW + 3_600);
+        let create_session = client::build_create_session_ix(
+            &wallet.pubkey(),
+            None,
+            1,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        );
+        harness.process(create_session, &[&wallet]).unwrap();
+        let created: SessionAccount = harness.account(&client::session_address(&human_record, 1));
+        assert_eq!(created.expires_at, NOW + 7_200);
+    }
+}
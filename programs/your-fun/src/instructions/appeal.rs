@@ -0,0 +1,284 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, RegionStats, Appeal, AppealStatus};
+use crate::instructions::verify_human::{count_verified, refresh_verification_status};
+use crate::error::YourFunError;
+use crate::events::{AppealFiled, AppealResolved};
+
+#[derive(Accounts)]
+pub struct FileAppeal<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = !human_record.is_active() && human_record.revoked_at != 0
+            @ YourFunError::HumanNotRevoked,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Appeal::INIT_SPACE,
+        seeds = [
+            Appeal::SEED,
+            human_record.key().as_ref(),
+            &human_record.revoked_at.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub appeal: Box<Account<'info, Appeal>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Appeals the current revocation of the caller's record. Must be filed
+/// within `appeal_window` seconds of the revocation while an arbiter is
+/// configured.
+pub fn handler_file_appeal(ctx: Context<FileAppeal>, evidence_hash: [u8; 32]) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let human_record = &ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+
+    let window_ends_at = human_record
+        .revoked_at
+        .checked_add(registry.appeal_window)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    require!(
+        registry.arbiter != Pubkey::default() && now < window_ends_at,
+        YourFunError::AppealWindowClosed
+    );
+    require!(
+        evidence_hash.iter().any(|&b| b != 0),
+        YourFunError::InvalidContentHash
+    );
+
+    let appeal = &mut ctx.accounts.appeal;
+    appeal.human_record = human_record.key();
+    appeal.wallet = human_record.wallet;
+    appeal.revoked_at = human_record.revoked_at;
+    appeal.evidence_hash = evidence_hash;
+    appeal.filed_at = now;
+    appeal.status = AppealStatus::Pending as u8;
+    appeal.resolved_at = 0;
+    appeal.note_hash = [0u8; 32];
+    appeal.bump = ctx.bumps.appeal;
+
+    emit!(AppealFiled {
+        wallet: appeal.wallet,
+        appeal: appeal.key(),
+        evidence_hash,
+        filed_at: now,
+    });
+
+    msg!("Appeal filed for wallet: {}", appeal.wallet);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResolveAppeal<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.arbiter != Pubkey::default()
+            && arbiter.key() == registry.arbiter @ YourFunError::Unauthorized,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Appeal::SEED,
+            human_record.key().as_ref(),
+            &appeal.revoked_at.to_le_bytes(),
+        ],
+        bump = appeal.bump,
+        constraint = appeal.status == AppealStatus::Pending as u8
+            @ YourFunError::AppealAlreadyResolved,
+    )]
+    pub appeal: Box<Account<'info, Appeal>>,
+
+    /// Counters of the region recorded on the human.
+    #[account(
+        mut,
+        seeds = [RegionStats::SEED, &human_record.region_code.to_le_bytes()],
+        bump = region_stats.bump,
+    )]
+    pub region_stats: Box<Account<'info, RegionStats>>,
+
+    pub arbiter: Signer<'info>,
+}
+
+/// Decides a pending appeal. Approval reinstates the record as it was
+/// before the revocation; denial makes the revocation final, as its appeal
+/// can never be filed again.
+///
+/// If the record was reinstated some other way in the meantime, the appeal
+/// is moot and can only be denied.
+pub fn handler_resolve_appeal(
+    ctx: Context<ResolveAppeal>,
+    approve: bool,
+    note_hash: [u8; 32],
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let appeal = &mut ctx.accounts.appeal;
+    let now = Clock::get()?.unix_timestamp;
+
+    if approve {
+        require!(!human_record.is_merged(), YourFunError::RecordMerged);
+        require!(
+            !human_record.is_active() && human_record.revoked_at == appeal.revoked_at,
+            YourFunError::HumanNotRevoked
+        );
+
+        human_record.set_active(true);
+        human_record.revoked_at = 0;
+        human_record.effective_at = 0;
+        count_verified(registry, human_record, &mut ctx.accounts.region_stats, true)?;
+        refresh_verification_status(human_record, registry, now);
+        registry.bump_state_version()?;
+    }
+
+    appeal.status = if approve {
+        AppealStatus::Approved
+    } else {
+        AppealStatus::Denied
+    } as u8;
+    appeal.resolved_at = now;
+    appeal.note_hash = note_hash;
+
+    emit!(AppealResolved {
+        wallet: appeal.wallet,
+        appeal: appeal.key(),
+        approved: approve,
+        note_hash,
+        resolved_at: now,
+    });
+
+    msg!(
+        "Appeal for wallet {} {}",
+        appeal.wallet,
+        if approve { "approved" } else { "denied" }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    fn registry(harness: &mut Harness) -> PlatformRegistry {
+        harness.account(&client::registry_address())
+    }
+
+    fn appeal(harness: &mut Harness, wallet: &Pubkey) -> Appeal {
+        harness.account(&client::appeal_address(&client::human_record_address(wallet), NOW))
+    }
+
+    #[test]
+    fn each_revocation_gets_one_appeal_inside_its_window() {
+        let (authority, arbiter) = (Keypair::new(), Keypair::new());
+        let [approved, denied, late] = [0; 3].map(|_| Keypair::new());
+        let window = 1_000;
+        let mut builder = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.arbiter = arbiter.pubkey();
+                registry.appeal_window = window;
+            })
+            .with_region_stats(0);
+        for owner in [&approved, &denied, &late] {
+            builder = builder.with_verified_human(owner.pubkey());
+        }
+        let mut harness = Harness::start(builder.build());
+        for signer in [&authority, &approved, &denied, &late] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        for owner in [&approved, &denied, &late] {
+            let revoke = client::build_revoke_human_ix(
+                &authority.pubkey(),
+                &owner.pubkey(),
+                0,
+                registry(&mut harness).admin_action_count,
+                None,
+            );
+            harness.process(revoke, &[&authority]).unwrap();
+        }
+        assert_eq!(registry(&mut harness).total_verified_humans, 0);
+        let file = |owner: &Keypair| client::build_file_appeal_ix(&owner.pubkey(), NOW, [9u8; 32]);
+        let resolve = |arbiter: &Keypair, owner: &Keypair, approve| {
+            client::build_resolve_appeal_ix(&arbiter.pubkey(), &owner.pubkey(), NOW, 0, approve, [3u8; 32])
+        };
+
+        harness.warp_to(NOW + 10);
+        harness.process(file(&approved), &[&approved]).unwrap();
+        let filed = appeal(&mut harness, &approved.pubkey());
+        assert_eq!((filed.status, filed.filed_at, filed.revoked_at), (AppealStatus::Pending as u8, NOW + 10, NOW));
+        // The appeal of this revocation already exists.
+        assert_eq!(harness.process(file(&approved), &[&approved]), Err(InstructionError::Custom(0)));
+        assert_eq!(
+            harness.process(resolve(&authority, &approved, true), &[&authority]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+
+        harness.process(resolve(&arbiter, &approved, true), &[&arbiter]).unwrap();
+        let record: HumanRecord = harness.account(&client::human_record_address(&approved.pubkey()));
+        assert!(record.is_active());
+        assert_eq!(record.revoked_at, 0);
+        let resolved = appeal(&mut harness, &approved.pubkey());
+        assert_eq!(
+            (resolved.status, resolved.resolved_at, resolved.note_hash),
+            (AppealStatus::Approved as u8, NOW + 10, [3u8; 32])
+        );
+        assert_eq!(registry(&mut harness).total_verified_humans, 1);
+        let region: RegionStats = harness.account(&client::region_stats_address(0));
+        assert_eq!(region.verified_humans, 1);
+        assert_eq!(
+            harness.process(resolve(&arbiter, &approved, false), &[&arbiter]),
+            Err(custom(YourFunError::AppealAlreadyResolved))
+        );
+
+        harness.warp_to(NOW + window - 1);
+        harness.process(file(&denied), &[&denied]).unwrap();
+        harness.process(resolve(&arbiter, &denied, false), &[&arbiter]).unwrap();
+        assert_eq!(appeal(&mut harness, &denied.pubkey()).status, AppealStatus::Denied as u8);
+        let record: HumanRecord = harness.account(&client::human_record_address(&denied.pubkey()));
+        assert!(!record.is_active());
+        assert_eq!(record.revoked_at, NOW);
+        // A denial is final.
+        assert_eq!(harness.process(file(&denied), &[&denied]), Err(InstructionError::Custom(0)));
+        assert_eq!(
+            harness.process(resolve(&arbiter, &denied, true), &[&arbiter]),
+            Err(custom(YourFunError::AppealAlreadyResolved))
+        );
+        assert_eq!(registry(&mut harness).total_verified_humans, 1);
+
+        harness.warp_to(NOW + window);
+        assert_eq!(
+            harness.process(file(&late), &[&late]),
+            Err(custom(YourFunError::AppealWindowClosed))
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    PlatformRegistry, DocumentIssuer, DocumentAttestation, AdminAction, AdminActionKind,
+};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+use crate::events::{DocumentIssuerChanged, DocumentAttested};
+
+#[derive(Accounts)]
+#[instruction(issuer: Pubkey)]
+pub struct SetDocumentIssuer<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DocumentIssuer::INIT_SPACE,
+        seeds = [DocumentIssuer::SEED, issuer.as_ref()],
+        bump,
+    )]
+    pub document_issuer: Box<Account<'info, DocumentIssuer>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approves `issuer` to attest identity documents, or withdraws the
+/// approval. Attestations it already made stop being accepted once it is
+/// withdrawn.
+pub fn handler_set_document_issuer(
+    ctx: Context<SetDocumentIssuer>,
+    issuer: Pubkey,
+    issuer_id: u16,
+    approved: bool,
+) -> Result<()> {
+    require!(issuer != Pubkey::default(), YourFunError::InvalidConfigValue);
+
+    let document_issuer = &mut ctx.accounts.document_issuer;
+    if document_issuer.bump == 0 {
+        document_issuer.issuer = issuer;
+        document_issuer.bump = ctx.bumps.document_issuer;
+    }
+    document_issuer.issuer_id = issuer_id;
+    document_issuer.is_active = approved;
+    document_issuer.updated_at = Clock::get()?.unix_timestamp;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::SetDocumentIssuer,
+        ctx.accounts.authority.key(),
+        &(issuer, issuer_id, approved),
+    )?;
+
+    emit!(DocumentIssuerChanged {
+        issuer,
+        issuer_id,
+        approved,
+    });
+
+    msg!("Document issuer {} approved: {}", issuer, approved);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AttestDocument<'info> {
+    #[account(
+        seeds = [DocumentIssuer::SEED, issuer.key().as_ref()],
+        bump = document_issuer.bump,
+        constraint = document_issuer.is_active @ YourFunError::DocumentIssuerNotApproved,
+    )]
+    pub document_issuer: Box<Account<'info, DocumentIssuer>>,
+
+    #[account(
+        init_if_needed,
+        payer = issuer,
+        space = 8 + DocumentAttestation::INIT_SPACE,
+        seeds = [DocumentAttestation::SEED, wallet.as_ref()],
+        bump,
+    )]
+    pub document_attestation: Box<Account<'info, DocumentAttestation>>,
+
+    /// The approved issuer; pays for the attestation account.
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Records that the issuer checked an identity document of `wallet`,
+/// valid until `expires_at`. A wallet holds one attestation: its issuer may
+/// renew it at any time, another issuer only once it has expired.
+pub fn handler_attest_document(
+    ctx: Context<AttestDocument>,
+    wallet: Pubkey,
+    document_commitment: [u8; 32],
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at > now, YourFunError::InvalidConfigValue);
+    require!(
+        document_commitment.iter().any(|&b| b != 0),
+        YourFunError::InvalidContentHash
+    );
+
+    let issuer = ctx.accounts.issuer.key();
+    let issuer_id = ctx.accounts.document_issuer.issuer_id;
+    let attestation = &mut ctx.accounts.document_attestation;
+    if attestation.bump == 0 {
+        attestation.wallet = wallet;
+        attestation.bump = ctx.bumps.document_attestation;
+    } else {
+        require!(
+            attestation.issuer == issuer || attestation.is_expired_at(now),
+            YourFunError::Unauthorized
+        );
+    }
+    attestation.issuer = issuer;
+    attestation.issuer_id = issuer_id;
+    attestation.document_commitment = document_commitment;
+    attestation.attested_at = now;
+    attestation.expires_at = expires_at;
+
+    emit!(DocumentAttested {
+        wallet,
+        issuer,
+        issuer_id,
+        document_commitment,
+        expires_at,
+    });
+
+    msg!("Document attested for wallet: {}", wallet);
+    Ok(())
+}
+
+/// Checks the attestation backing a Full verification of `wallet` and
+/// returns its issuer.
+pub(crate) fn check_document_attestation(
+    attestation: Option<&DocumentAttestation>,
+    document_issuer: Option<&DocumentIssuer>,
+    wallet: &Pubkey,
+    now: i64,
+) -> Result<Pubkey> {
+    let attestation = attestation.ok_or(YourFunError::DocumentAttestationRequired)?;
+    require_keys_eq!(attestation.wallet, *wallet, YourFunError::AttestationWalletMismatch);
+    require!(
+        !attestation.is_expired_at(now),
+        YourFunError::DocumentAttestationExpired
+    );
+    require!(
+        document_issuer.is_some_and(|i| i.issuer == attestation.issuer && i.is_active),
+        YourFunError::DocumentIssuerNotApproved
+    );
+    Ok(attestation.issuer)
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+    use anchor_lang::solana_program::instruction::Instruction;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::{HumanRecord, VerificationLevel};
+
+    fn verify_ix(authority: &Keypair, wallet: &Pubkey, level: VerificationLevel, issuer: Option<&Pubkey>) -> Instruction {
+        client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            wallet,
+            issuer,
+            &hash(&wallet.to_bytes()).to_bytes(),
+            registered_challenge_response(wallet),
+            100,
+            level as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            NOW,
+        )
+    }
+
+    fn set_issuer(harness: &mut Harness, authority: &Keypair, issuer: &Pubkey, issuer_id: u16, approved: bool) {
+        let registry: PlatformRegistry = harness.account(&client::registry_address());
+        let ix = client::build_set_document_issuer_ix(
+            &authority.pubkey(),
+            registry.admin_action_count,
+            issuer,
+            issuer_id,
+            approved,
+        );
+        harness.process(ix, &[authority]).unwrap();
+    }
+
+    #[test]
+    fn full_verification_needs_a_current_attestation_for_the_wallet_from_an_approved_issuer() {
+        let [authority, issuer, withdrawn] = [0; 3].map(|_| Keypair::new());
+        let [valid, expired, mismatched, unapproved, enhanced] = [0; 5].map(|_| Pubkey::new_unique());
+        let mut builder = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0);
+        for wallet in [valid, expired, mismatched, unapproved, enhanced] {
+            builder = builder.with_registered_human(wallet);
+        }
+        let mut harness = Harness::start(builder.build());
+        for signer in [&authority, &issuer, &withdrawn] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        set_issuer(&mut harness, &authority, &issuer.pubkey(), 7, true);
+        set_issuer(&mut harness, &authority, &withdrawn.pubkey(), 8, true);
+        let attest = |issuer: &Keypair, wallet, expires_at| {
+            client::build_attest_document_ix(&issuer.pubkey(), wallet, [5u8; 32], expires_at)
+        };
+        harness.process(attest(&issuer, &valid, NOW + 1_000), &[&issuer]).unwrap();
+        harness.process(attest(&issuer, &expired, NOW + 10), &[&issuer]).unwrap();
+        harness.process(attest(&withdrawn, &unapproved, NOW + 1_000), &[&withdrawn]).unwrap();
+        set_issuer(&mut harness, &authority, &withdrawn.pubkey(), 8, false);
+        assert_eq!(
+            harness.process(attest(&withdrawn, &mismatched, NOW + 1_000), &[&withdrawn]),
+            Err(custom(YourFunError::DocumentIssuerNotApproved))
+        );
+        let attestation: DocumentAttestation = harness.account(&client::document_attestation_address(&valid));
+        assert_eq!((attestation.wallet, attestation.issuer_id, attestation.expires_at), (valid, 7, NOW + 1_000));
+
+        // Lower levels need no attestation; Full does.
+        harness
+            .process(verify_ix(&authority, &enhanced, VerificationLevel::Enhanced, None), &[&authority])
+            .unwrap();
+        assert_eq!(
+            harness.process(verify_ix(&authority, &valid, VerificationLevel::Full, None), &[&authority]),
+            Err(custom(YourFunError::DocumentAttestationRequired))
+        );
+
+        let mut borrowed = verify_ix(&authority, &mismatched, VerificationLevel::Full, Some(&issuer.pubkey()));
+        let slot = client::document_attestation_address(&mismatched);
+        for meta in borrowed.accounts.iter_mut().filter(|meta| meta.pubkey == slot) {
+            meta.pubkey = client::document_attestation_address(&valid);
+        }
+        assert_eq!(
+            harness.process(borrowed, &[&authority]),
+            Err(custom(YourFunError::AttestationWalletMismatch))
+        );
+        assert_eq!(
+            harness.process(
+                verify_ix(&authority, &unapproved, VerificationLevel::Full, Some(&withdrawn.pubkey())),
+                &[&authority]
+            ),
+            Err(custom(YourFunError::DocumentIssuerNotApproved))
+        );
+
+        harness
+            .process(verify_ix(&authority, &valid, VerificationLevel::Full, Some(&issuer.pubkey())), &[&authority])
+            .unwrap();
+        let record: HumanRecord = harness.account(&client::human_record_address(&valid));
+        assert_eq!(record.level().unwrap(), Some(VerificationLevel::Full));
+        assert_eq!(record.document_issuer, issuer.pubkey());
+
+        harness.warp_to(NOW + 10);
+        assert_eq!(
+            harness.process(
+                verify_ix(&authority, &expired, VerificationLevel::Full, Some(&issuer.pubkey())),
+                &[&authority]
+            ),
+            Err(custom(YourFunError::DocumentAttestationExpired))
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use crate::state::{CloseReason, HumanRecord, SessionAccount};
+use crate::error::YourFunError;
+use crate::events::HumanAudited;
+
+#[derive(Accounts)]
+pub struct AssertHumanInvariants<'info> {
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+}
+
+/// Recomputes a human's counters from its sessions and fails on the first
+/// invariant that does not hold; on success only `last_audited_at` is
+/// written. Permissionless.
+///
+/// Remaining accounts must be the session addresses for indices
+/// `0..session_count`, in order. The address of a session reclaimed since,
+/// or of an index counted in by a merge or import, holds no account. The
+/// invariants, checked in this order, are:
+///
+/// 1. one account is passed per index (`AuditSessionCountMismatch`);
+/// 2. each is the PDA of its index (`AuditSessionIndexMismatch`);
+/// 3. each live session belongs to this human (`AuditSessionOwnerMismatch`);
+/// 4. `active_session_count` equals the open sessions
+///    (`AuditActiveSessionDrift`);
+/// 5. `total_interactions` is at least the interactions of the sessions
+///    since the latest registration, and equal to it unless some session
+///    address holds no account (`AuditInteractionDrift`);
+/// 6. for a record never revoked, `learning_score` is at least the score of
+///    those sessions that completed or expired and have settled; goal
+///    bonuses and imported scores only add to it (`AuditScoreDrift`).
+///
+/// Invariants 5 and 6 are skipped for a record merged into another, whose
+/// totals moved to the primary.
+pub fn handler_assert_human_invariants(ctx: Context<AssertHumanInvariants>) -> Result<()> {
+    let human_record = &ctx.accounts.human_record;
+    let human_key = human_record.key();
+    let sessions = ctx.remaining_accounts;
+
+    require!(
+        sessions.len() as u64 == human_record.session_count,
+        YourFunError::AuditSessionCountMismatch
+    );
+
+    let mut live_sessions = 0u64;
+    let mut open_sessions = 0u64;
+    let mut vacant = false;
+    let mut session_interactions = 0u64;
+    let mut settled_score = 0u64;
+    for (index, info) in sessions.iter().enumerate() {
+        let index = index as u64;
+        let (expected, _) = Pubkey::find_program_address(
+            &[SessionAccount::SEED, human_key.as_ref(), &index.to_le_bytes()],
+            &crate::ID,
+        );
+        if info.key() != expected {
+            msg!("Session account for index {} is missing", index);
+            return err!(YourFunError::AuditSessionIndexMismatch);
+        }
+        if info.data_is_empty() {
+            vacant = true;
+            continue;
+        }
+
+        require_keys_eq!(*info.owner, crate::ID, YourFunError::AuditSessionOwnerMismatch);
+        let session = {
+            let data = info.try_borrow_data()?;
+            SessionAccount::try_deserialize(&mut &data[..])?
+        };
+        if session.human_record != human_key
+            || session.owner != human_record.wallet
+            || session.session_index != index
+        {
+            msg!("Session {} belongs to another human", index);
+            return err!(YourFunError::AuditSessionOwnerMismatch);
+        }
+        live_sessions += 1;
+        if session.is_active() {
+            open_sessions += 1;
+        }
+        if session.created_at < human_record.registered_at {
+            continue;
+        }
+
+        session_interactions = session_interactions
+            .checked_add(session.interaction_count as u64)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        let closed_normally = session.close_reason == CloseReason::Completed as u8
+            || session.close_reason == CloseReason::Expired as u8;
+        if closed_normally && !session.is_settling() && !session.is_contested() {
+            settled_score = settled_score
+                .checked_add(session.session_score)
+                .ok_or(YourFunError::NumericalOverflow)?;
+        }
+    }
+
+    if human_record.active_session_count as u64 != open_sessions {
+        msg!(
+            "active_session_count is {} but {} sessions are open",
+            human_record.active_session_count,
+            open_sessions
+        );
+        return err!(YourFunError::AuditActiveSessionDrift);
+    }
+
+    if human_record.merged_into == Pubkey::default() {
+        let total = human_record.total_interactions;
+        if total < session_interactions || (!vacant && total != session_interactions) {
+            msg!(
+                "total_interactions is {} but the sessions hold {}",
+                total,
+                session_interactions
+            );
+            return err!(YourFunError::AuditInteractionDrift);
+        }
+        if human_record.revoked_at == 0 && human_record.learning_score < settled_score {
+            msg!(
+                "learning_score is {} but {} was settled from sessions",
+                human_record.learning_score,
+                settled_score
+            );
+            return err!(YourFunError::AuditScoreDrift);
+        }
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let human_record = &mut ctx.accounts.human_record;
+    human_record.last_audited_at = now;
+
+    emit!(HumanAudited {
+        wallet: human_record.wallet,
+        session_count: human_record.session_count,
+        live_sessions,
+        session_interactions,
+        settled_score,
+        audited_at: now,
+    });
+
+    msg!("Human {} passed its invariants", human_record.wallet);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    /// A human with a completed session of score 40 and three interactions,
+    /// and two open sessions with two interactions between them.
+    fn start(wallet: &Keypair) -> Harness {
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .with_session(|session| {
+                session.set_active(false);
+                session.close_reason = CloseReason::Completed as u8;
+                session.closed_at = NOW;
+                session.interaction_count = 3;
+                session.session_score = 40;
+            })
+            .with_open_session()
+            .with_session(|session| session.interaction_count = 2)
+            .with_open_session()
+            .with_human(|record| {
+                record.active_session_count = 2;
+                record.total_interactions = 5;
+                record.learning_score = 40;
+            })
+            .build();
+        Harness::start(accounts)
+    }
+
+    fn audit(harness: &mut Harness, wallet: &Keypair, count: u64) -> std::result::Result<(), InstructionError> {
+        harness.process(client::build_assert_human_invariants_ix(&wallet.pubkey(), count), &[])
+    }
+
+    /// Rewrites session `index`, returning the original to restore.
+    fn doctor(harness: &mut Harness, wallet: &Keypair, index: u64, f: impl FnOnce(&mut SessionAccount)) -> FixtureAccount {
+        let address = client::session_address(&client::human_record_address(&wallet.pubkey()), index);
+        let original: SessionAccount = harness.account(&address);
+        let mut session = original.clone();
+        f(&mut session);
+        harness.set_account(FixtureAccount::new(address, &session));
+        FixtureAccount::new(address, &original)
+    }
+
+    #[test]
+    fn consistent_counters_pass_and_stamp_the_audit_time() {
+        let wallet = Keypair::new();
+        let mut harness = start(&wallet);
+        harness.warp_to(NOW + 60);
+
+        let events = harness
+            .process_with_events::<crate::events::HumanAudited>(
+                client::build_assert_human_invariants_ix(&wallet.pubkey(), 3),
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            (events[0].live_sessions, events[0].session_interactions, events[0].settled_score),
+            (3, 5, 40)
+        );
+        let record: HumanRecord = harness.account(&client::human_record_address(&wallet.pubkey()));
+        assert_eq!(record.last_audited_at, NOW + 60);
+        assert_eq!((record.total_interactions, record.learning_score), (5, 40));
+
+        // A reclaimed session leaves a vacant address, which lowers the
+        // interactions the record can be held to.
+        let reclaimed = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+        harness.fund(&reclaimed, 0);
+        audit(&mut harness, &wallet, 3).unwrap();
+    }
+
+    #[test]
+    fn a_doctored_session_fails_on_the_invariant_it_breaks() {
+        let wallet = Keypair::new();
+        let mut harness = start(&wallet);
+
+        let original = doctor(&mut harness, &wallet, 1, |session| session.interaction_count = 9);
+        assert_eq!(audit(&mut harness, &wallet, 3), Err(custom(YourFunError::AuditInteractionDrift)));
+        harness.set_account(original);
+
+        let original = doctor(&mut harness, &wallet, 0, |session| session.session_score = 41);
+        assert_eq!(audit(&mut harness, &wallet, 3), Err(custom(YourFunError::AuditScoreDrift)));
+        // Scores still settling are not yet owed to the record.
+        doctor(&mut harness, &wallet, 0, |session| session.set_settling(true));
+        audit(&mut harness, &wallet, 3).unwrap();
+        harness.set_account(original);
+
+        let original = doctor(&mut harness, &wallet, 2, |session| session.set_active(false));
+        assert_eq!(audit(&mut harness, &wallet, 3), Err(custom(YourFunError::AuditActiveSessionDrift)));
+        harness.set_account(original);
+
+        let original = doctor(&mut harness, &wallet, 2, |session| session.owner = Pubkey::new_unique());
+        assert_eq!(audit(&mut harness, &wallet, 3), Err(custom(YourFunError::AuditSessionOwnerMismatch)));
+        harness.set_account(original);
+
+        audit(&mut harness, &wallet, 3).unwrap();
+    }
+
+    #[test]
+    fn every_session_index_must_be_passed_in_order() {
+        let wallet = Keypair::new();
+        let mut harness = start(&wallet);
+
+        assert_eq!(audit(&mut harness, &wallet, 2), Err(custom(YourFunError::AuditSessionCountMismatch)));
+
+        let mut ix = client::build_assert_human_invariants_ix(&wallet.pubkey(), 3);
+        ix.accounts.swap(2, 3);
+        assert_eq!(harness.process(ix, &[]), Err(custom(YourFunError::AuditSessionIndexMismatch)));
+
+        // Index 1 replaced by a repeat of index 0.
+        let mut ix = client::build_assert_human_invariants_ix(&wallet.pubkey(), 3);
+        ix.accounts[2] = ix.accounts[1].clone();
+        assert_eq!(harness.process(ix, &[]), Err(custom(YourFunError::AuditSessionIndexMismatch)));
+
+        let record: HumanRecord = harness.account(&client::human_record_address(&wallet.pubkey()));
+        assert_eq!(record.last_audited_at, 0);
+    }
+}
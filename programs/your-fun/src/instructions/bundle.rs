@@ -0,0 +1,334 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::state::{PlatformRegistry, HumanRecord, SessionAccount, StatsBucket};
+use crate::instructions::interaction::{apply_interaction, InteractionPath};
+use crate::instructions::stats::{record_epoch_activity, EpochActivity};
+use crate::error::YourFunError;
+use crate::sigverify::has_ed25519_signature;
+use crate::events::{InteractionBundleSubmitted, InteractionRecorded};
+
+/// Maximum number of entries in one `submit_interaction_bundle`.
+pub const MAX_BUNDLE_LEN: usize = 16;
+
+/// Domain separator of the message signed over a bundle.
+pub const BUNDLE_SIGNING_DOMAIN: &[u8] = b"your-fun:interaction-bundle";
+
+/// One interaction buffered offline, with the time it happened.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InteractionInput {
+    pub content_hash: [u8; 32],
+    pub interaction_type: u8,
+    pub score: u8,
+    pub duration_seconds: u32,
+    pub occurred_at: i64,
+}
+
+/// The 32-byte message the owner or delegate signs for `bundle` on
+/// `session`. Binding the session keeps a bundle from being replayed on
+/// another one.
+pub fn bundle_message(session: &Pubkey, bundle: &[InteractionInput]) -> Result<[u8; 32]> {
+    Ok(hashv(&[BUNDLE_SIGNING_DOMAIN, session.as_ref(), &bundle.try_to_vec()?]).to_bytes())
+}
+
+#[derive(Accounts)]
+pub struct SubmitInteractionBundle<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    /// CHECK: The instructions sysvar, pinned by address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Funds the day's EpochStats if this is its first activity.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Applies interactions buffered offline, all or none.
+///
+/// The bundle must be signed, as `bundle_message`, by the owner or the
+/// record's interaction delegate through an ed25519 program instruction
+/// earlier in the same transaction; anyone may then submit it. Timestamps
+/// must strictly increase, start after the session's last activity, and lie
+/// before both its expiry and the current time, so a session that expired
+/// while offline can still take its bundle until it is closed.
+///
+/// Every entry is validated and scored like `record_unlogged_interaction`,
+/// except that the signed bundle stands in for the InteractionLog, so no
+/// log threshold applies. Bundles are refused while companion co-signing is
+/// on. The entries count towards the EpochStats of the day the bundle is
+/// submitted.
+pub fn handler_submit_interaction_bundle(
+    ctx: Context<SubmitInteractionBundle>,
+    bundle: Vec<InteractionInput>,
+    bundle_signature: [u8; 64],
+) -> Result<()> {
+    require!(
+        !bundle.is_empty() && bundle.len() <= MAX_BUNDLE_LEN,
+        YourFunError::InvalidBundleLength
+    );
+    // Offline entries cannot have been co-signed by the companion backend.
+    require!(
+        ctx.accounts.registry.companion_oracle == Pubkey::default(),
+        YourFunError::CompanionCosignatureRequired
+    );
+
+    let accounts = &mut *ctx.accounts;
+    let registry = &accounts.registry;
+    let human_record = &mut accounts.human_record;
+    let session = &mut accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        session.interaction_count as usize + bundle.len()
+            <= registry.max_interactions_per_session as usize,
+        YourFunError::InteractionLimitReached
+    );
+
+    let mut previous = session.last_interaction_at;
+    for entry in &bundle {
+        require!(entry.occurred_at > previous, YourFunError::BundleOutOfOrder);
+        require!(
+            entry.occurred_at < session.expires_at && entry.occurred_at <= now,
+            YourFunError::BundleTimestampOutOfWindow
+        );
+        previous = entry.occurred_at;
+    }
+
+    let message = bundle_message(&session.key(), &bundle)?;
+    let signer = [human_record.wallet, human_record.interaction_delegate]
+        .into_iter()
+        .filter(|key| *key != Pubkey::default())
+        .find(|key| {
+            has_ed25519_signature(&accounts.instructions, key, &message, Some(&bundle_signature))
+                .unwrap_or(false)
+        })
+        .ok_or(YourFunError::InvalidBundleSignature)?;
+
+    let mut score_increment: u64 = 0;
+    for entry in &bundle {
+        let applied = apply_interaction(
+            registry,
+            human_record,
+            session,
+            &mut accounts.stats_bucket,
+            entry.content_hash,
+            entry.interaction_type,
+            entry.score,
+            entry.duration_seconds,
+            InteractionPath::Bundled {
+                occurred_at: entry.occurred_at,
+            },
+        )?;
+        score_increment = score_increment
+            .checked_add(applied.score_increment)
+            .ok_or(YourFunError::NumericalOverflow)?;
+
+        emit!(InteractionRecorded {
+            session: session.key(),
+            user: human_record.wallet,
+            interaction_index: applied.interaction_index,
+            content_hash: entry.content_hash,
+            interaction_type: entry.interaction_type,
+            score: entry.score,
+            duration_seconds: entry.duration_seconds,
+            score_increment: applied.score_increment,
+            timestamp: applied.now,
+            prev_chain_head: applied.prev_chain_head,
+        });
+    }
+
+    record_epoch_activity(
+        &accounts.epoch_stats,
+        &accounts.payer,
+        &accounts.system_program,
+        now,
+        EpochActivity {
+            interactions: bundle.len() as u64,
+            score_awarded: score_increment,
+            ..Default::default()
+        },
+    )?;
+
+    emit!(InteractionBundleSubmitted {
+        session: session.key(),
+        signer,
+        bundle_hash: message,
+        entries: bundle.len() as u8,
+        score_increment,
+    });
+
+    msg!(
+        "Bundle of {} interactions applied to session {}",
+        bundle.len(),
+        session.session_index
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetInteractionDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Registers the key that may sign interaction bundles for the owner, such
+/// as an offline device's. `Pubkey::default()` removes it.
+pub fn handler_set_interaction_delegate(
+    ctx: Context<SetInteractionDelegate>,
+    delegate: Pubkey,
+) -> Result<()> {
+    let human_record = &mut ctx.accounts.human_record;
+    human_record.interaction_delegate = delegate;
+
+    msg!("Interaction delegate of {} set to {}", human_record.wallet, delegate);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, ed25519_instruction, Harness, NOW};
+    use crate::state::InteractionType;
+
+    const EXPIRES_AT: i64 = NOW + 600;
+
+    fn bundle(occurred_at: &[i64]) -> Vec<InteractionInput> {
+        occurred_at
+            .iter()
+            .enumerate()
+            .map(|(i, &occurred_at)| InteractionInput {
+                content_hash: [i as u8 + 1; 32],
+                interaction_type: InteractionType::Chat as u8,
+                score: 60,
+                duration_seconds: 30,
+                occurred_at,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn signed_bundles_apply_in_order_inside_the_window() {
+        let [owner, delegate, stranger, relayer] = [0; 4].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_registry(|registry| registry.max_interactions_per_session = 5)
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_session(|session| session.expires_at = EXPIRES_AT)
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&owner, &relayer] {
+            harness.fund(&signer.pubkey(), 1_000_000_000);
+        }
+        let session = client::session_address(&client::human_record_address(&owner.pubkey()), 0);
+        // Submitted after the session expired: entries from before then
+        // still count.
+        let now = EXPIRES_AT + 400;
+        harness.warp_to(now);
+        let submit_as = |harness: &mut Harness, signer: &Keypair, signed: &[InteractionInput], sent: Vec<InteractionInput>| {
+            let (verify, signature) = ed25519_instruction(signer, &bundle_message(&session, signed).unwrap());
+            let submit = client::build_submit_interaction_bundle_ix(&relayer.pubkey(), &owner.pubkey(), 0, sent, signature, now);
+            harness.process_all(&[verify, submit], &[&relayer])
+        };
+        let submit = |harness: &mut Harness, signer: &Keypair, entries: Vec<InteractionInput>| {
+            submit_as(harness, signer, &entries.clone(), entries)
+        };
+
+        // The fixture session's last activity is at NOW.
+        for occurred_at in [[NOW, NOW + 10], [NOW + 20, NOW + 20], [NOW + 30, NOW + 20]] {
+            assert_eq!(
+                submit(&mut harness, &owner, bundle(&occurred_at)),
+                Err(custom(YourFunError::BundleOutOfOrder)),
+                "{occurred_at:?}"
+            );
+        }
+        assert_eq!(
+            submit(&mut harness, &owner, bundle(&[NOW + 10, EXPIRES_AT])),
+            Err(custom(YourFunError::BundleTimestampOutOfWindow))
+        );
+        assert_eq!(
+            submit(&mut harness, &stranger, bundle(&[NOW + 10])),
+            Err(custom(YourFunError::InvalidBundleSignature))
+        );
+        // The owner's signature of another bundle does not carry over.
+        assert_eq!(
+            submit_as(&mut harness, &owner, &bundle(&[NOW + 10]), bundle(&[NOW + 20])),
+            Err(custom(YourFunError::InvalidBundleSignature))
+        );
+        assert_eq!(harness.account::<SessionAccount>(&session).interaction_count, 0);
+
+        let entries = bundle(&[NOW + 10, NOW + 20, NOW + 30]);
+        let (verify, signature) = ed25519_instruction(&owner, &bundle_message(&session, &entries).unwrap());
+        let submit_ix = client::build_submit_interaction_bundle_ix(&relayer.pubkey(), &owner.pubkey(), 0, entries, signature, now);
+        // Without the ed25519 instruction in the same transaction nothing
+        // vouches for the bundle.
+        assert_eq!(
+            harness.process(submit_ix.clone(), &[&relayer]),
+            Err(custom(YourFunError::InvalidBundleSignature))
+        );
+        harness.process_all(&[verify, submit_ix], &[&relayer]).unwrap();
+
+        let state: SessionAccount = harness.account(&session);
+        assert_eq!((state.interaction_count, state.last_interaction_at), (3, NOW + 30));
+        assert!(state.session_score > 0);
+
+        harness
+            .process(client::build_set_interaction_delegate_ix(&owner.pubkey(), delegate.pubkey()), &[&owner])
+            .unwrap();
+        submit(&mut harness, &delegate, bundle(&[NOW + 40])).unwrap();
+        assert_eq!(harness.account::<SessionAccount>(&session).interaction_count, 4);
+
+        // The bundle as a whole must fit under the session's interaction cap.
+        assert_eq!(
+            submit(&mut harness, &owner, bundle(&[NOW + 50, NOW + 60])),
+            Err(custom(YourFunError::InteractionLimitReached))
+        );
+    }
+}
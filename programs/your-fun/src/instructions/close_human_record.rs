@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, FingerprintData, RegionStats, Tombstone, Handle};
+use crate::instructions::handle::release_handle;
+use crate::instructions::register_human::forfeit_verifier_fee;
+use crate::error::YourFunError;
+use crate::events::HumanRecordClosed;
+
+#[derive(Accounts)]
+pub struct CloseHumanRecord<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.wallet == owner.key() @ YourFunError::Unauthorized,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [FingerprintData::SEED, human_record.key().as_ref()],
+        bump = fingerprint.bump,
+    )]
+    pub fingerprint: Box<Account<'info, FingerprintData>>,
+
+    /// Counters of the region recorded on the human.
+    #[account(
+        mut,
+        seeds = [RegionStats::SEED, &human_record.region_code.to_le_bytes()],
+        bump = region_stats.bump,
+    )]
+    pub region_stats: Box<Account<'info, RegionStats>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Tombstone::INIT_SPACE,
+        seeds = [Tombstone::SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub tombstone: Box<Account<'info, Tombstone>>,
+
+    /// The human's handle, released with the record. Required if one is held.
+    #[account(
+        mut,
+        close = owner,
+        seeds = [Handle::SEED, human_record.handle_hash.as_ref()],
+        bump = handle.bump,
+    )]
+    pub handle: Option<Box<Account<'info, Handle>>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Receives the verifier's share of the fee still held on the record;
+    /// required while one is held.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Closes the caller's HumanRecord, returning its rent, and writes a
+/// tombstone that `register_human` consults before allowing the wallet back in.
+/// A handle the human holds is released along with it. A verifier's share of
+/// the fee still held on a never-verified record goes to the platform.
+pub fn handler_close_human_record(ctx: Context<CloseHumanRecord>) -> Result<()> {
+    forfeit_verifier_fee(
+        &ctx.accounts.registry,
+        &mut ctx.accounts.human_record,
+        ctx.accounts.fee_receiver.as_deref(),
+    )?;
+    if ctx.accounts.human_record.has_handle() {
+        let handle = ctx.accounts.handle.as_ref().ok_or(YourFunError::HandleAccountRequired)?;
+        release_handle(&mut ctx.accounts.human_record, handle)?;
+    }
+
+    let registry = &mut ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let tombstone = &mut ctx.accounts.tombstone;
+    let clock = Clock::get()?;
+
+    if human_record.is_counted_as_verified() {
+        registry.total_verified_humans = registry
+            .total_verified_humans
+            .checked_sub(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+    registry.uncount_active_human(human_record);
+    if human_record.is_region_counted() {
+        ctx.accounts.region_stats.remove_verified()?;
+    }
+
+    registry.bump_state_version()?;
+
+    tombstone.wallet = human_record.wallet;
+    tombstone.closed_at = clock.unix_timestamp;
+    tombstone.prior_learning_score = human_record.learning_score;
+    tombstone.strikes = human_record.strikes;
+    tombstone.session_count = human_record.session_count;
+    tombstone.bump = ctx.bumps.tombstone;
+
+    emit!(HumanRecordClosed {
+        wallet: human_record.wallet,
+        learning_score: human_record.learning_score,
+        strikes: human_record.strikes,
+        closed_at: clock.unix_timestamp,
+    });
+
+    msg!("Human record closed for wallet: {}", human_record.wallet);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseTombstone<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [Tombstone::SEED, tombstone.wallet.as_ref()],
+        bump = tombstone.bump,
+    )]
+    pub tombstone: Box<Account<'info, Tombstone>>,
+
+    /// The wallet that paid for the tombstone and receives its rent.
+    /// CHECK: Only used as the lamport destination; validated against the tombstone.
+    #[account(
+        mut,
+        constraint = wallet.key() == tombstone.wallet @ YourFunError::Unauthorized,
+    )]
+    pub wallet: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Removes a tombstone for support cases, lifting the cooldown and
+/// discarding the carried-forward strikes.
+pub fn handler_close_tombstone(ctx: Context<CloseTombstone>) -> Result<()> {
+    msg!("Tombstone removed for wallet: {}", ctx.accounts.tombstone.wallet);
+    Ok(())
+}
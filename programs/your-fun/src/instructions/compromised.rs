@@ -0,0 +1,321 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, CompromisedFingerprint, VerifierAccount};
+use crate::instructions::verify_human::refresh_verification_status;
+use crate::error::YourFunError;
+use crate::events::{CompromisedFingerprintChanged, ReverificationRequired};
+
+#[derive(Accounts)]
+#[instruction(fingerprint_hash: [u8; 32])]
+pub struct ListCompromisedFingerprint<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + CompromisedFingerprint::INIT_SPACE,
+        seeds = [CompromisedFingerprint::SEED, fingerprint_hash.as_ref()],
+        bump,
+    )]
+    pub compromised: Box<Account<'info, CompromisedFingerprint>>,
+
+    /// Required unless the signer is the registry authority.
+    #[account(
+        seeds = [VerifierAccount::SEED, signer.key().as_ref()],
+        bump = verifier_account.bump,
+    )]
+    pub verifier_account: Option<Box<Account<'info, VerifierAccount>>>,
+
+    /// The authority or an elected verifier.
+    #[account(
+        mut,
+        constraint = signer.key() == registry.authority
+            || verifier_account.as_ref().is_some_and(|v| v.is_active)
+            @ YourFunError::UnauthorizedVerifier,
+    )]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds a fingerprint hash to the compromised list, blocking new
+/// registrations and verifications that use it.
+pub fn handler_list_compromised_fingerprint(
+    ctx: Context<ListCompromisedFingerprint>,
+    fingerprint_hash: [u8; 32],
+    reason_code: u8,
+) -> Result<()> {
+    require!(
+        fingerprint_hash.iter().any(|&b| b != 0),
+        YourFunError::ZeroFingerprintHash
+    );
+
+    let compromised = &mut ctx.accounts.compromised;
+    compromised.fingerprint_hash = fingerprint_hash;
+    compromised.reason_code = reason_code;
+    compromised.listed_by = ctx.accounts.signer.key();
+    compromised.listed_at = Clock::get()?.unix_timestamp;
+    compromised.bump = ctx.bumps.compromised;
+
+    emit!(CompromisedFingerprintChanged {
+        fingerprint_hash,
+        listed: true,
+        reason_code,
+        changed_by: compromised.listed_by,
+    });
+
+    msg!("Fingerprint listed as compromised (reason {})", reason_code);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DelistCompromisedFingerprint<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        close = listed_by,
+        seeds = [CompromisedFingerprint::SEED, compromised.fingerprint_hash.as_ref()],
+        bump = compromised.bump,
+    )]
+    pub compromised: Box<Account<'info, CompromisedFingerprint>>,
+
+    /// The signer that listed the fingerprint and receives its rent.
+    /// CHECK: Only used as the lamport destination; validated against the listing.
+    #[account(
+        mut,
+        constraint = listed_by.key() == compromised.listed_by @ YourFunError::Unauthorized,
+    )]
+    pub listed_by: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Removes a fingerprint from the compromised list. Records flagged while
+/// it was listed become usable again once re-verified.
+pub fn handler_delist_compromised_fingerprint(
+    ctx: Context<DelistCompromisedFingerprint>,
+) -> Result<()> {
+    let compromised = &ctx.accounts.compromised;
+
+    emit!(CompromisedFingerprintChanged {
+        fingerprint_hash: compromised.fingerprint_hash,
+        listed: false,
+        reason_code: compromised.reason_code,
+        changed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Fingerprint removed from the compromised list");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FlagCompromisedRecord<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanNotVerified,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        seeds = [CompromisedFingerprint::SEED, human_record.fingerprint_hash.as_ref()],
+        bump = compromised.bump,
+    )]
+    pub compromised: Box<Account<'info, CompromisedFingerprint>>,
+}
+
+/// Flags a verified record whose fingerprint is listed, so its verification
+/// counts as lapsed until re-verified. Permissionless; idempotent.
+pub fn handler_flag_compromised_record(ctx: Context<FlagCompromisedRecord>) -> Result<()> {
+    let human_record = &mut ctx.accounts.human_record;
+    if human_record.requires_reverification() {
+        return Ok(());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    human_record.set_requires_reverification(true);
+    refresh_verification_status(human_record, &ctx.accounts.registry, now);
+
+    emit!(ReverificationRequired {
+        wallet: human_record.wallet,
+        fingerprint_hash: human_record.fingerprint_hash,
+        flagged_at: now,
+    });
+
+    msg!("Re-verification required for wallet: {}", human_record.wallet);
+    Ok(())
+}
+
+/// Fails with `FingerprintCompromised` when `info`, which must be the
+/// CompromisedFingerprint address for `fingerprint_hash`, holds a listing.
+/// The account is usually empty, meaning the fingerprint is not listed.
+pub(crate) fn require_not_compromised(info: &AccountInfo, fingerprint_hash: &[u8; 32]) -> Result<()> {
+    let (expected, _) = Pubkey::find_program_address(
+        &[CompromisedFingerprint::SEED, fingerprint_hash.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(info.key(), expected, ErrorCode::ConstraintSeeds);
+    require!(
+        info.owner != &crate::ID || info.data_is_empty(),
+        YourFunError::FingerprintCompromised
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::{VerificationLevel, VerificationStatus};
+
+    fn verify(
+        harness: &mut Harness,
+        authority: &Keypair,
+        wallet: &Pubkey,
+    ) -> std::result::Result<(), InstructionError> {
+        let ix = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            wallet,
+            None,
+            &hash(&wallet.to_bytes()).to_bytes(),
+            registered_challenge_response(wallet),
+            100,
+            VerificationLevel::Basic as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            NOW,
+        );
+        harness.process(ix, &[authority])
+    }
+
+    #[test]
+    fn listed_fingerprints_block_registration_until_delisted() {
+        let authority = Keypair::new();
+        let accounts = FixtureBuilder::new().with_authority(authority.pubkey()).build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        let (user, outsider) = (harness.wallet(), harness.wallet());
+        let fingerprint = vec![0x5a; 64];
+        let fingerprint_hash = hash(&fingerprint).to_bytes();
+        let register = |harness: &mut Harness| {
+            let ix = client::build_register_human_ix(
+                &user.pubkey(),
+                &authority.pubkey(),
+                false,
+                false,
+                None,
+                None,
+                [7u8; 32],
+                fingerprint.clone(),
+            );
+            harness.process(ix, &[&user])
+        };
+
+        let by_outsider =
+            client::build_list_compromised_fingerprint_ix(&outsider.pubkey(), false, fingerprint_hash, 3);
+        assert_eq!(
+            harness.process(by_outsider, &[&outsider]),
+            Err(custom(YourFunError::UnauthorizedVerifier))
+        );
+        let list = client::build_list_compromised_fingerprint_ix(&authority.pubkey(), false, fingerprint_hash, 3);
+        harness.process(list, &[&authority]).unwrap();
+        let listing: CompromisedFingerprint = harness.account(&client::compromised_address(&fingerprint_hash));
+        assert_eq!(
+            (listing.fingerprint_hash, listing.reason_code, listing.listed_by, listing.listed_at),
+            (fingerprint_hash, 3, authority.pubkey(), NOW)
+        );
+
+        assert_eq!(register(&mut harness), Err(custom(YourFunError::FingerprintCompromised)));
+        assert!(harness
+            .try_account::<HumanRecord>(&client::human_record_address(&user.pubkey()))
+            .is_none());
+
+        let delist = client::build_delist_compromised_fingerprint_ix(
+            &authority.pubkey(),
+            &authority.pubkey(),
+            &fingerprint_hash,
+        );
+        harness.process(delist, &[&authority]).unwrap();
+        assert!(harness
+            .try_account::<CompromisedFingerprint>(&client::compromised_address(&fingerprint_hash))
+            .is_none());
+        register(&mut harness).unwrap();
+    }
+
+    #[test]
+    fn flagged_records_lapse_until_reverified_after_delisting() {
+        let authority = Keypair::new();
+        let wallet = Pubkey::new_unique();
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_verified_human(wallet)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        let human_record = client::human_record_address(&wallet);
+        let fingerprint_hash = harness.account::<HumanRecord>(&human_record).fingerprint_hash;
+        let flag = || client::build_flag_compromised_record_ix(&wallet, &fingerprint_hash);
+
+        // Nothing to flag against while the fingerprint is not listed.
+        assert_eq!(
+            harness.process(flag(), &[]),
+            Err(custom(ErrorCode::AccountNotInitialized))
+        );
+
+        let list = client::build_list_compromised_fingerprint_ix(&authority.pubkey(), false, fingerprint_hash, 1);
+        harness.process(list, &[&authority]).unwrap();
+        let events = harness.process_with_events::<ReverificationRequired>(flag(), &[]).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].wallet, events[0].flagged_at), (wallet, NOW));
+        let record: HumanRecord = harness.account(&human_record);
+        assert!(record.is_active());
+        assert!(record.requires_reverification());
+        assert_eq!(record.verification_status, VerificationStatus::Lapsed as u8);
+
+        // Flagging again changes nothing and announces nothing.
+        assert!(harness
+            .process_with_events::<ReverificationRequired>(flag(), &[])
+            .unwrap()
+            .is_empty());
+        assert_eq!(verify(&mut harness, &authority, &wallet), Err(custom(YourFunError::FingerprintCompromised)));
+
+        let delist = client::build_delist_compromised_fingerprint_ix(
+            &authority.pubkey(),
+            &authority.pubkey(),
+            &fingerprint_hash,
+        );
+        harness.process(delist, &[&authority]).unwrap();
+        // Delisting alone does not restore the record; re-verification does.
+        assert!(harness.account::<HumanRecord>(&human_record).requires_reverification());
+        verify(&mut harness, &authority, &wallet).unwrap();
+        let record: HumanRecord = harness.account(&human_record);
+        assert!(!record.requires_reverification());
+        assert_eq!(record.verification_status, VerificationStatus::Active as u8);
+    }
+}
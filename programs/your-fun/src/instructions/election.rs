@@ -0,0 +1,572 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{PlatformRegistry, Election, Candidate, VoteEscrow, VerifierAccount};
+use crate::error::YourFunError;
+
+#[derive(Accounts)]
+pub struct InitElection<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Election::INIT_SPACE,
+        seeds = [Election::SEED],
+        bump,
+    )]
+    pub election: Box<Account<'info, Election>>,
+
+    pub governance_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [Election::VAULT_SEED],
+        bump,
+        token::mint = governance_mint,
+        token::authority = election,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets up verifier elections and opens the first epoch. Authority only.
+pub fn handler_init_election(
+    ctx: Context<InitElection>,
+    epoch_duration: i64,
+    seats: u8,
+) -> Result<()> {
+    require!(epoch_duration > 0, YourFunError::InvalidConfigValue);
+    require!(
+        seats > 0 && seats <= Election::MAX_SEATS,
+        YourFunError::InvalidConfigValue
+    );
+
+    let election = &mut ctx.accounts.election;
+    election.governance_mint = ctx.accounts.governance_mint.key();
+    election.vault = ctx.accounts.vault.key();
+    election.epoch_duration = epoch_duration;
+    election.seats = seats;
+    election.current_epoch = 0;
+    election.epoch_started_at = Clock::get()?.unix_timestamp;
+    election.candidate_count = 0;
+    election.bump = ctx.bumps.election;
+    election.vault_bump = ctx.bumps.vault;
+
+    msg!("Verifier election initialized with {} seats", seats);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(candidate: Pubkey)]
+pub struct NominateVerifier<'info> {
+    #[account(
+        mut,
+        seeds = [Election::SEED],
+        bump = election.bump,
+    )]
+    pub election: Box<Account<'info, Election>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Candidate::INIT_SPACE,
+        seeds = [
+            Candidate::SEED,
+            &election.current_epoch.to_le_bytes(),
+            candidate.as_ref(),
+        ],
+        bump,
+    )]
+    pub candidate_account: Box<Account<'info, Candidate>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + VerifierAccount::INIT_SPACE,
+        seeds = [VerifierAccount::SEED, candidate.as_ref()],
+        bump,
+    )]
+    pub verifier_account: Box<Account<'info, VerifierAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Nominates a verifier candidate for the current epoch. Permissionless;
+/// the payer funds the candidate and, on first nomination, verifier accounts.
+pub fn handler_nominate_verifier(ctx: Context<NominateVerifier>, candidate: Pubkey) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(now < election.epoch_ends_at()?, YourFunError::ElectionEpochEnded);
+    require!(
+        election.candidate_count < Election::MAX_CANDIDATES,
+        YourFunError::InvalidCandidateSet
+    );
+
+    let candidate_account = &mut ctx.accounts.candidate_account;
+    candidate_account.candidate = candidate;
+    candidate_account.epoch = election.current_epoch;
+    candidate_account.total_stake = 0;
+    candidate_account.nomination_order = election.candidate_count;
+    candidate_account.bump = ctx.bumps.candidate_account;
+
+    election.candidate_count += 1;
+
+    let verifier_account = &mut ctx.accounts.verifier_account;
+    if verifier_account.bump == 0 {
+        verifier_account.verifier = candidate;
+        verifier_account.is_active = false;
+        verifier_account.elected_epoch = 0;
+        verifier_account.stake = 0;
+        verifier_account.bump = ctx.bumps.verifier_account;
+    }
+
+    msg!("Verifier {} nominated for epoch {}", candidate, election.current_epoch);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VoteForVerifier<'info> {
+    #[account(
+        seeds = [Election::SEED],
+        bump = election.bump,
+    )]
+    pub election: Box<Account<'info, Election>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Candidate::SEED,
+            &candidate_account.epoch.to_le_bytes(),
+            candidate_account.candidate.as_ref(),
+        ],
+        bump = candidate_account.bump,
+        constraint = candidate_account.epoch == election.current_epoch @ YourFunError::ElectionEpochEnded,
+    )]
+    pub candidate_account: Box<Account<'info, Candidate>>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + VoteEscrow::INIT_SPACE,
+        seeds = [
+            VoteEscrow::SEED,
+            &candidate_account.epoch.to_le_bytes(),
+            candidate_account.candidate.as_ref(),
+            voter.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub vote_escrow: Box<Account<'info, VoteEscrow>>,
+
+    #[account(
+        mut,
+        token::mint = election.governance_mint,
+        token::authority = voter,
+    )]
+    pub voter_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = election.vault,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Locks `amount` governance tokens behind a candidate until the epoch has
+/// been elected. Repeated votes for the same candidate add to one escrow.
+pub fn handler_vote_for_verifier(ctx: Context<VoteForVerifier>, amount: u64) -> Result<()> {
+    require!(amount > 0, YourFunError::InvalidVoteAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now < ctx.accounts.election.epoch_ends_at()?,
+        YourFunError::ElectionEpochEnded
+    );
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.voter_token.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.voter.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let candidate_account = &mut ctx.accounts.candidate_account;
+    let vote_escrow = &mut ctx.accounts.vote_escrow;
+    if vote_escrow.bump == 0 {
+        vote_escrow.voter = ctx.accounts.voter.key();
+        vote_escrow.candidate = candidate_account.candidate;
+        vote_escrow.epoch = candidate_account.epoch;
+        vote_escrow.amount = 0;
+        vote_escrow.bump = ctx.bumps.vote_escrow;
+    }
+    vote_escrow.amount = vote_escrow
+        .amount
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    candidate_account.total_stake = candidate_account
+        .total_stake
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    msg!(
+        "{} tokens locked for verifier {}",
+        amount,
+        candidate_account.candidate
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ElectVerifiers<'info> {
+    #[account(
+        mut,
+        seeds = [Election::SEED],
+        bump = election.bump,
+    )]
+    pub election: Box<Account<'info, Election>>,
+    // Every candidate of the epoch follows as remaining accounts, each paired
+    // with its writable VerifierAccount: [candidate_0, verifier_0, ...].
+}
+
+/// Closes the current epoch: seats the `seats` candidates with the most
+/// stake and unseats the other candidates, then opens the next epoch.
+///
+/// Permissionless once the epoch has ended. All nominations must be supplied
+/// so nobody can be left out of the count. Equal stakes are broken in favour
+/// of the earlier nomination. Verifiers seated earlier who did not stand
+/// again are retired separately through `retire_verifier`.
+pub fn handler_elect_verifiers<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ElectVerifiers<'info>>,
+) -> Result<()> {
+    let election = &mut ctx.accounts.election;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(now >= election.epoch_ends_at()?, YourFunError::ElectionEpochNotEnded);
+    require!(
+        ctx.remaining_accounts.len() == 2 * election.candidate_count as usize,
+        YourFunError::InvalidCandidateSet
+    );
+
+    let epoch = election.current_epoch;
+    let mut seen: u32 = 0;
+    let mut ranked = Vec::with_capacity(election.candidate_count as usize);
+
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let candidate = Account::<Candidate>::try_from(&pair[0])?;
+        require!(candidate.epoch == epoch, YourFunError::InvalidCandidateSet);
+        let expected = Pubkey::create_program_address(
+            &[
+                Candidate::SEED,
+                &epoch.to_le_bytes(),
+                candidate.candidate.as_ref(),
+                &[candidate.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| error!(YourFunError::InvalidCandidateSet))?;
+        require_keys_eq!(pair[0].key(), expected, YourFunError::InvalidCandidateSet);
+
+        let order_bit = 1u32
+            .checked_shl(candidate.nomination_order)
+            .ok_or(YourFunError::InvalidCandidateSet)?;
+        require!(seen & order_bit == 0, YourFunError::InvalidCandidateSet);
+        seen |= order_bit;
+
+        let verifier = Account::<VerifierAccount>::try_from(&pair[1])?;
+        require!(pair[1].is_writable, YourFunError::InvalidCandidateSet);
+        require_keys_eq!(
+            verifier.verifier,
+            candidate.candidate,
+            YourFunError::InvalidCandidateSet
+        );
+
+        ranked.push((candidate.total_stake, candidate.nomination_order, verifier));
+    }
+
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    for (rank, (stake, _, verifier)) in ranked.iter_mut().enumerate() {
+        if rank < election.seats as usize {
+            verifier.is_active = true;
+            verifier.elected_epoch = epoch;
+            verifier.stake = *stake;
+        } else {
+            verifier.is_active = false;
+        }
+        verifier.exit(ctx.program_id)?;
+    }
+
+    election.current_epoch = epoch
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    election.epoch_started_at = now;
+    election.candidate_count = 0;
+
+    msg!(
+        "Epoch {} elected: {} candidates, {} seats",
+        epoch,
+        ranked.len(),
+        election.seats
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RetireVerifier<'info> {
+    #[account(
+        seeds = [Election::SEED],
+        bump = election.bump,
+    )]
+    pub election: Box<Account<'info, Election>>,
+
+    #[account(
+        mut,
+        seeds = [VerifierAccount::SEED, verifier_account.verifier.as_ref()],
+        bump = verifier_account.bump,
+        constraint = verifier_account.is_active
+            && verifier_account.elected_epoch + 1 < election.current_epoch
+            @ YourFunError::VerifierStillSeated,
+    )]
+    pub verifier_account: Box<Account<'info, VerifierAccount>>,
+}
+
+/// Unseats a verifier whose term has lapsed because it was not elected in
+/// the most recent election. Permissionless.
+pub fn handler_retire_verifier(ctx: Context<RetireVerifier>) -> Result<()> {
+    let verifier_account = &mut ctx.accounts.verifier_account;
+    verifier_account.is_active = false;
+
+    msg!("Verifier {} retired", verifier_account.verifier);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVote<'info> {
+    #[account(
+        seeds = [Election::SEED],
+        bump = election.bump,
+    )]
+    pub election: Box<Account<'info, Election>>,
+
+    #[account(
+        mut,
+        close = voter,
+        seeds = [
+            VoteEscrow::SEED,
+            &vote_escrow.epoch.to_le_bytes(),
+            vote_escrow.candidate.as_ref(),
+            voter.key().as_ref(),
+        ],
+        bump = vote_escrow.bump,
+        has_one = voter @ YourFunError::Unauthorized,
+        constraint = vote_escrow.epoch < election.current_epoch @ YourFunError::VoteStillLocked,
+    )]
+    pub vote_escrow: Box<Account<'info, VoteEscrow>>,
+
+    #[account(
+        mut,
+        token::mint = election.governance_mint,
+        token::authority = voter,
+    )]
+    pub voter_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = election.vault,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Returns a vote's locked tokens once its epoch has been elected.
+pub fn handler_withdraw_vote(ctx: Context<WithdrawVote>) -> Result<()> {
+    let amount = ctx.accounts.vote_escrow.amount;
+    let bump = [ctx.accounts.election.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[Election::SEED, &bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.voter_token.to_account_info(),
+                authority: ctx.accounts.election.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    msg!("{} vote tokens withdrawn", amount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::program_option::COption;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    const EPOCH: i64 = 1_000;
+    const BALANCE: u64 = 1_000;
+
+    /// A token account of `mint` owned by `owner`, holding `BALANCE`.
+    fn token_account(address: Pubkey, mint: Pubkey, owner: Pubkey) -> FixtureAccount {
+        FixtureAccount::token(
+            address,
+            spl_token::state::Account {
+                mint,
+                owner,
+                amount: BALANCE,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn balance(harness: &mut Harness, address: &Pubkey) -> u64 {
+        spl_token::state::Account::unpack(&harness.raw_account(address)).unwrap().amount
+    }
+
+    fn verifier(harness: &mut Harness, candidate: &Pubkey) -> VerifierAccount {
+        harness.account(&client::verifier_account_address(candidate))
+    }
+
+    #[test]
+    fn a_full_election_seats_the_top_stakes_and_breaks_ties_by_nomination() {
+        let authority = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let voters: Vec<_> = (0..3).map(|_| (Keypair::new(), Pubkey::new_unique())).collect();
+        let [first, second, third] = [0; 3].map(|_| Pubkey::new_unique());
+
+        let mut accounts = FixtureBuilder::new().with_authority(authority.pubkey()).build();
+        accounts.push(FixtureAccount::token(
+            mint,
+            spl_token::state::Mint {
+                mint_authority: COption::Some(authority.pubkey()),
+                supply: BALANCE * voters.len() as u64,
+                decimals: 0,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+        ));
+        for (voter, token) in &voters {
+            accounts.push(token_account(*token, mint, voter.pubkey()));
+        }
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        for (voter, _) in &voters {
+            harness.fund(&voter.pubkey(), 1_000_000_000);
+        }
+
+        let init = client::build_init_election_ix(&authority.pubkey(), &mint, EPOCH, 2);
+        harness.process(init, &[&authority]).unwrap();
+        for candidate in [first, second, third] {
+            let nominate = client::build_nominate_verifier_ix(&authority.pubkey(), 0, &candidate);
+            harness.process(nominate, &[&authority]).unwrap();
+        }
+        // `second` and `third` tie; `second` was nominated first.
+        for ((voter, token), (candidate, amount)) in voters.iter().zip([(first, 100), (second, 40), (third, 40)]) {
+            let vote = client::build_vote_for_verifier_ix(&voter.pubkey(), token, 0, &candidate, amount);
+            harness.process(vote, &[voter]).unwrap();
+        }
+        assert_eq!(balance(&mut harness, &client::election_vault_address()), 180);
+
+        let elect = || client::build_elect_verifiers_ix(0, &[first, second, third]);
+        assert_eq!(
+            harness.process(elect(), &[]),
+            Err(custom(YourFunError::ElectionEpochNotEnded))
+        );
+        let (voter, token) = &voters[2];
+        let withdraw = || client::build_withdraw_vote_ix(&voter.pubkey(), token, 0, &third);
+        assert_eq!(
+            harness.process(withdraw(), &[voter]),
+            Err(custom(YourFunError::VoteStillLocked))
+        );
+
+        harness.warp_to(NOW + EPOCH);
+        let late = client::build_vote_for_verifier_ix(&voter.pubkey(), token, 0, &third, 1);
+        assert_eq!(
+            harness.process(late, &[voter]),
+            Err(custom(YourFunError::ElectionEpochEnded))
+        );
+        // Every nomination must be counted.
+        assert_eq!(
+            harness.process(client::build_elect_verifiers_ix(0, &[first, second]), &[]),
+            Err(custom(YourFunError::InvalidCandidateSet))
+        );
+        harness.process(elect(), &[]).unwrap();
+
+        let seated = [first, second, third].map(|candidate| verifier(&mut harness, &candidate));
+        let active: Vec<_> = seated.iter().map(|verifier| verifier.is_active).collect();
+        let stakes: Vec<_> = seated.iter().map(|verifier| verifier.stake).collect();
+        assert_eq!(active, [true, true, false]);
+        assert_eq!(stakes, [100, 40, 0]);
+        assert_eq!(seated[0].elected_epoch, 0);
+        let election: Election = harness.account(&client::election_address());
+        assert_eq!((election.current_epoch, election.candidate_count), (1, 0));
+        assert_eq!(election.epoch_started_at, NOW + EPOCH);
+
+        // Votes unlock once their epoch has been elected, losing ones included.
+        harness.process(withdraw(), &[voter]).unwrap();
+        assert_eq!(balance(&mut harness, token), BALANCE);
+        assert!(harness
+            .try_account::<VoteEscrow>(&client::vote_escrow_address(0, &third, &voter.pubkey()))
+            .is_none());
+
+        // A verifier that does not stand again is retired after the next
+        // election; one re-elected keeps its seat.
+        let nominate = client::build_nominate_verifier_ix(&authority.pubkey(), 1, &first);
+        harness.process(nominate, &[&authority]).unwrap();
+        assert_eq!(
+            harness.process(client::build_retire_verifier_ix(&second), &[]),
+            Err(custom(YourFunError::VerifierStillSeated))
+        );
+        harness.warp_to(NOW + 2 * EPOCH);
+        harness
+            .process(client::build_elect_verifiers_ix(1, &[first]), &[])
+            .unwrap();
+        harness.process(client::build_retire_verifier_ix(&second), &[]).unwrap();
+        assert!(!verifier(&mut harness, &second).is_active);
+        assert_eq!(
+            harness.process(client::build_retire_verifier_ix(&first), &[]),
+            Err(custom(YourFunError::VerifierStillSeated))
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, AdminAction, AdminActionKind, FeeVault};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+use crate::events::FeesWithdrawn;
+
+#[derive(Accounts)]
+pub struct InitFeeVault<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FeeVault::INIT_SPACE,
+        seeds = [FeeVault::SEED],
+        bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the fee vault; setting it as the treasury routes fees into it.
+/// Authority only.
+pub fn handler_init_fee_vault(ctx: Context<InitFeeVault>) -> Result<()> {
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.total_fees_collected = 0;
+    fee_vault.total_fees_withdrawn = 0;
+    fee_vault.bump = ctx.bumps.fee_vault;
+
+    msg!("Fee vault initialized");
+    Ok(())
+}
+
+/// Adds `amount` to the vault's collected total when `fee_receiver` is the
+/// fee vault, after the lamports have been moved in. Other receivers are
+/// left alone.
+pub(crate) fn credit_fee_vault(fee_receiver: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let Some(mut fee_vault) = load_fee_vault(fee_receiver)? else {
+        return Ok(());
+    };
+    fee_vault.total_fees_collected = fee_vault
+        .total_fees_collected
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    let mut data = fee_receiver.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    fee_vault.try_serialize(&mut writer)
+}
+
+/// Reads `fee_receiver` as the fee vault when the program owns it, checking
+/// its address against the stored bump; `None` for any other owner.
+pub(crate) fn load_fee_vault(fee_receiver: &AccountInfo) -> Result<Option<FeeVault>> {
+    if fee_receiver.owner != &crate::ID {
+        return Ok(None);
+    }
+    let fee_vault = FeeVault::try_deserialize(&mut &fee_receiver.try_borrow_data()?[..])
+        .map_err(|_| error!(YourFunError::InvalidFeeReceiver))?;
+    require_keys_eq!(
+        fee_receiver.key(),
+        fee_vault.derived_address()?,
+        YourFunError::InvalidFeeReceiver
+    );
+    Ok(Some(fee_vault))
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [FeeVault::SEED],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// CHECK: Only receives lamports.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Required while `registry.co_authority` is set.
+    pub co_authority: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves `amount` lamports out of the fee vault to `recipient`, keeping the
+/// vault rent-exempt. Authority only, co-signed by the co-authority when set.
+pub fn handler_withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    require!(amount > 0, YourFunError::ZeroAmount);
+    ctx.accounts
+        .registry
+        .require_co_authority(ctx.accounts.co_authority.as_ref().map(|s| s.key()))?;
+
+    let vault_info = ctx.accounts.fee_vault.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(vault_info.data_len());
+    require!(
+        vault_info.lamports().saturating_sub(rent_exempt) >= amount,
+        YourFunError::InsufficientFunds
+    );
+
+    let recipient_info = ctx.accounts.recipient.to_account_info();
+    **vault_info.try_borrow_mut_lamports()? -= amount;
+    **recipient_info.try_borrow_mut_lamports()? = recipient_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.total_fees_withdrawn = fee_vault
+        .total_fees_withdrawn
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::WithdrawFees,
+        ctx.accounts.authority.key(),
+        &(amount, recipient_info.key()),
+    )?;
+
+    emit!(FeesWithdrawn {
+        recipient: recipient_info.key(),
+        amount,
+        total_fees_withdrawn: fee_vault.total_fees_withdrawn,
+        withdrawn_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Withdrew {} lamports of fees to {}", amount, recipient_info.key());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness};
+
+    const FEE: u64 = 1_000_000;
+
+    /// Starts from a registry charging `fee` whose treasury is the fee vault.
+    fn start(authority: &Keypair, fee: u64) -> Harness {
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.verification_fee_lamports = fee;
+                registry.treasury = FeeVault::address();
+            })
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.process(client::build_init_fee_vault_ix(&authority.pubkey()), &[authority]).unwrap();
+        harness
+    }
+
+    fn register(harness: &mut Harness) {
+        let user = harness.wallet();
+        let ix = client::build_register_human_ix(
+            &user.pubkey(),
+            &client::fee_vault_address(),
+            false,
+            false,
+            None,
+            None,
+            [7u8; 32],
+            vec![0x5a; 64],
+        );
+        harness.process(ix, &[&user]).unwrap();
+    }
+
+    /// Withdraws `amount` to `recipient`, returning the vault's withdrawn
+    /// total reported by `FeesWithdrawn`.
+    fn withdraw(
+        harness: &mut Harness,
+        signer: &Keypair,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> std::result::Result<u64, InstructionError> {
+        let registry: PlatformRegistry = harness.account(&client::registry_address());
+        let ix = client::build_withdraw_fees_ix(&signer.pubkey(), None, registry.admin_action_count, recipient, amount);
+        let events = harness.process_with_events::<FeesWithdrawn>(ix, &[signer])?;
+        assert_eq!((events[0].recipient, events[0].amount), (*recipient, amount));
+        Ok(events[0].total_fees_withdrawn)
+    }
+
+    #[test]
+    fn withdrawals_stop_at_the_rent_exempt_minimum() {
+        let authority = Keypair::new();
+        let mut harness = start(&authority, FEE);
+        let recipient = Pubkey::new_unique();
+        harness.fund(&recipient, 1_000_000_000);
+        let rent_exempt = Rent::default().minimum_balance(8 + FeeVault::INIT_SPACE);
+        let vault = client::fee_vault_address();
+        assert_eq!(harness.balance(&vault), rent_exempt);
+
+        register(&mut harness);
+        register(&mut harness);
+        assert_eq!(harness.balance(&vault), rent_exempt + 2 * FEE);
+        assert_eq!(harness.account::<FeeVault>(&vault).total_fees_collected, 2 * FEE);
+
+        let stranger = harness.wallet();
+        assert_eq!(
+            withdraw(&mut harness, &stranger, &recipient, FEE),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        assert_eq!(
+            withdraw(&mut harness, &authority, &recipient, 0),
+            Err(custom(YourFunError::ZeroAmount))
+        );
+        assert_eq!(
+            withdraw(&mut harness, &authority, &recipient, 2 * FEE + 1),
+            Err(custom(YourFunError::InsufficientFunds))
+        );
+
+        assert_eq!(withdraw(&mut harness, &authority, &recipient, FEE / 2), Ok(FEE / 2));
+        // Down to exactly the rent-exempt minimum.
+        assert_eq!(withdraw(&mut harness, &authority, &recipient, FEE + FEE / 2), Ok(2 * FEE));
+        assert_eq!(harness.balance(&vault), rent_exempt);
+        assert_eq!(harness.balance(&recipient), 1_000_000_000 + 2 * FEE);
+        assert_eq!(
+            withdraw(&mut harness, &authority, &recipient, 1),
+            Err(custom(YourFunError::InsufficientFunds))
+        );
+
+        let vault_state: FeeVault = harness.account(&vault);
+        assert_eq!((vault_state.total_fees_collected, vault_state.total_fees_withdrawn), (2 * FEE, 2 * FEE));
+    }
+
+    #[test]
+    fn a_zero_fee_registry_never_touches_the_vault() {
+        let authority = Keypair::new();
+        let mut harness = start(&authority, 0);
+        let vault = client::fee_vault_address();
+        let before = harness.balance(&vault);
+
+        register(&mut harness);
+        assert_eq!(harness.balance(&vault), before);
+        let vault_state: FeeVault = harness.account(&vault);
+        assert_eq!((vault_state.total_fees_collected, vault_state.total_fees_withdrawn), (0, 0));
+        assert_eq!(
+            withdraw(&mut harness, &authority, &Pubkey::new_unique(), 1),
+            Err(custom(YourFunError::InsufficientFunds))
+        );
+    }
+}
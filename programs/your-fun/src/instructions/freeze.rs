@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, AdminAction, AdminActionKind};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+use crate::events::{HumanFrozen, HumanUnfrozen};
+
+/// Accounts for `freeze_human` and `unfreeze_human`. Suspension is
+/// operational, so unlike the config setters it keeps working after
+/// `freeze_config`.
+#[derive(Accounts)]
+pub struct FreezeHuman<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Suspends the human until `until`, replacing any freeze in effect, so a
+/// freeze can be extended or shortened. While frozen the human can neither
+/// create sessions nor record interactions; activity resumes on its own
+/// once `until` passes. A timestamp not in the future changes nothing.
+/// Unlike revocation this leaves the verification and its counts alone.
+pub fn handler_freeze_human(ctx: Context<FreezeHuman>, until: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let human_record = &mut ctx.accounts.human_record;
+    if until > now {
+        human_record.frozen_until = until;
+    }
+    let (wallet, frozen_until) = (human_record.wallet, human_record.frozen_until);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::FreezeHuman,
+        ctx.accounts.authority.key(),
+        &(wallet, until),
+    )?;
+
+    if until <= now {
+        msg!("Freeze of {} ends in the past; nothing changed", wallet);
+        return Ok(());
+    }
+
+    emit!(HumanFrozen {
+        wallet,
+        frozen_until,
+        frozen_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Human record {} frozen until {}", wallet, frozen_until);
+    Ok(())
+}
+
+/// Lifts the human's freeze before it ends. Fails if none is in effect.
+pub fn handler_unfreeze_human(ctx: Context<FreezeHuman>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let human_record = &mut ctx.accounts.human_record;
+    require!(human_record.is_frozen(now), YourFunError::HumanRecordNotFrozen);
+    human_record.frozen_until = 0;
+    let wallet = human_record.wallet;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::UnfreezeHuman,
+        ctx.accounts.authority.key(),
+        &wallet,
+    )?;
+
+    emit!(HumanUnfrozen {
+        wallet,
+        unfrozen_by: ctx.accounts.authority.key(),
+        unfrozen_at: now,
+    });
+
+    msg!("Human record {} unfrozen", wallet);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    fn count(harness: &mut Harness) -> u64 {
+        harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count
+    }
+
+    #[test]
+    fn frozen_humans_stay_idle_until_the_freeze_ends_or_is_lifted() {
+        let (authority, wallet) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&wallet.pubkey(), 10_000_000_000);
+        let human_record = client::human_record_address(&wallet.pubkey());
+        let freeze = |harness: &mut Harness, until| {
+            let ix = client::build_freeze_human_ix(&authority.pubkey(), count(harness), &wallet.pubkey(), until);
+            harness.process(ix, &[&authority])
+        };
+        let unfreeze = |harness: &mut Harness| {
+            let ix = client::build_unfreeze_human_ix(&authority.pubkey(), count(harness), &wallet.pubkey());
+            harness.process(ix, &[&authority])
+        };
+        let mut interactions = 0;
+        // Tries an interaction and a new session at `now`.
+        let mut act = |harness: &mut Harness, now| -> [std::result::Result<(), InstructionError>; 2] {
+            harness.warp_to(now);
+            let interaction = client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                interactions,
+                [3u8; 32],
+                1,
+                80,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                now,
+            );
+            let session_index = harness.account::<HumanRecord>(&human_record).session_count;
+            let create_session = client::build_create_session_ix(
+                &wallet.pubkey(),
+                None,
+                session_index,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                now,
+            );
+            let results = [harness.process(interaction, &[&wallet]), harness.process(create_session, &[&wallet])];
+            if results[0].is_ok() {
+                interactions += 1;
+            }
+            results
+        };
+        let frozen = [Err(custom(YourFunError::HumanRecordFrozen)), Err(custom(YourFunError::HumanRecordFrozen))];
+
+        // A freeze ending in the past changes nothing.
+        freeze(&mut harness, NOW - 1).unwrap();
+        assert_eq!(harness.account::<HumanRecord>(&human_record).frozen_until, 0);
+        assert_eq!(unfreeze(&mut harness), Err(custom(YourFunError::HumanRecordNotFrozen)));
+        assert_eq!(act(&mut harness, NOW), [Ok(()), Ok(())]);
+
+        freeze(&mut harness, NOW + 100).unwrap();
+        assert_eq!(act(&mut harness, NOW + 99), frozen);
+        // Extending replaces the end of the freeze.
+        freeze(&mut harness, NOW + 200).unwrap();
+        assert_eq!(harness.account::<HumanRecord>(&human_record).frozen_until, NOW + 200);
+        assert_eq!(act(&mut harness, NOW + 100), frozen);
+        assert_eq!(act(&mut harness, NOW + 200), [Ok(()), Ok(())]);
+
+        // Unfreezing early restores activity at once.
+        freeze(&mut harness, NOW + 1_000).unwrap();
+        assert_eq!(act(&mut harness, NOW + 300), frozen);
+        unfreeze(&mut harness).unwrap();
+        assert_eq!(act(&mut harness, NOW + 300), [Ok(()), Ok(())]);
+        let record: HumanRecord = harness.account(&human_record);
+        assert!(record.is_active());
+        assert_eq!(record.frozen_until, 0);
+
+        let stranger = harness.wallet();
+        let by_stranger = client::build_freeze_human_ix(&stranger.pubkey(), count(&mut harness), &wallet.pubkey(), NOW + 1_000);
+        assert_eq!(
+            harness.process(by_stranger, &[&stranger]),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+    }
+}
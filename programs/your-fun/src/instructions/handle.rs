@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use crate::state::{Handle, HumanRecord};
+use crate::error::YourFunError;
+use crate::events::{HandleClaimed, HandleReleased};
+
+#[derive(Accounts)]
+#[instruction(handle: String)]
+pub struct ClaimHandle<'info> {
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    /// Fails to initialize while anyone holds the handle.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Handle::INIT_SPACE,
+        seeds = [Handle::SEED, Handle::seed_hash(&handle).as_ref()],
+        bump,
+    )]
+    pub handle_account: Box<Account<'info, Handle>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Binds a handle to the owner's verified identity. Handles are
+/// case-insensitive and stored lowercased; a human holds at most one.
+pub fn handler_claim_handle(ctx: Context<ClaimHandle>, handle: String) -> Result<()> {
+    let handle = handle.to_ascii_lowercase();
+    require!(Handle::is_valid(&handle), YourFunError::InvalidHandle);
+
+    let human_record = &mut ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        human_record.is_effective(now) && !human_record.is_merged(),
+        YourFunError::HumanNotVerified
+    );
+    require!(!human_record.has_handle(), YourFunError::HandleAlreadyClaimed);
+
+    let handle_account = &mut ctx.accounts.handle_account;
+    handle_account.handle = handle;
+    handle_account.wallet = human_record.wallet;
+    handle_account.claimed_at = now;
+    handle_account.bump = ctx.bumps.handle_account;
+    human_record.handle_hash = Handle::seed_hash(&handle_account.handle);
+
+    emit!(HandleClaimed {
+        wallet: human_record.wallet,
+        handle: handle_account.handle.clone(),
+        claimed_at: now,
+    });
+
+    msg!("Handle @{} claimed by {}", handle_account.handle, human_record.wallet);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReleaseHandle<'info> {
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [Handle::SEED, human_record.handle_hash.as_ref()],
+        bump = handle_account.bump,
+    )]
+    pub handle_account: Box<Account<'info, Handle>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Gives up the owner's handle, returning its rent, so anyone may claim it.
+pub fn handler_release_handle(ctx: Context<ReleaseHandle>) -> Result<()> {
+    release_handle(&mut ctx.accounts.human_record, &ctx.accounts.handle_account)
+}
+
+/// Clears the record's handle and announces it; the caller closes the
+/// Handle account. Shared by `release_handle`, record closure and revocation.
+pub(crate) fn release_handle(human_record: &mut HumanRecord, handle_account: &Handle) -> Result<()> {
+    human_record.handle_hash = [0u8; 32];
+
+    emit!(HandleReleased {
+        wallet: human_record.wallet,
+        handle: handle_account.handle.clone(),
+        released_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Handle @{} released by {}", handle_account.handle, human_record.wallet);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(handle: String)]
+pub struct ResolveHandle<'info> {
+    #[account(
+        seeds = [Handle::SEED, Handle::seed_hash(&handle).as_ref()],
+        bump = handle_account.bump,
+    )]
+    pub handle_account: Box<Account<'info, Handle>>,
+}
+
+/// Returns the wallet holding `handle` as return data. Fails when the
+/// handle is unclaimed. Meant for CPI.
+pub fn handler_resolve_handle(ctx: Context<ResolveHandle>, _handle: String) -> Result<Pubkey> {
+    Ok(ctx.accounts.handle_account.wallet)
+}
@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+use crate::state::{HumanRecord, SessionAccount, Highlights, Highlight};
+use crate::instructions::interaction::load_interaction_log;
+use crate::error::YourFunError;
+
+#[derive(Accounts)]
+pub struct PinHighlight<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    /// The InteractionLog to pin.
+    /// CHECK: Decoded by `load_interaction_log`, which checks it belongs to
+    /// `session`.
+    pub interaction: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Highlights::INIT_SPACE,
+        seeds = [Highlights::SEED, human_record.key().as_ref()],
+        bump,
+    )]
+    pub highlights: Box<Account<'info, Highlights>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pins a logged interaction of one of the owner's sessions, creating the
+/// owner's Highlights account on first use. The pin outlives the log, so it
+/// stays after the interaction is reaped.
+pub fn handler_pin_highlight(ctx: Context<PinHighlight>) -> Result<()> {
+    let session = ctx.accounts.session.key();
+    let interaction = load_interaction_log(&ctx.accounts.interaction, &session)?;
+
+    let highlights = &mut ctx.accounts.highlights;
+    if highlights.bump == 0 {
+        highlights.human_record = ctx.accounts.human_record.key();
+        highlights.bump = ctx.bumps.highlights;
+    }
+    highlights.pin(Highlight {
+        session,
+        interaction_index: interaction.interaction_index,
+        content_hash: interaction.content_hash,
+        pinned_at: Clock::get()?.unix_timestamp,
+    })?;
+
+    msg!(
+        "Interaction {} of session {} pinned",
+        interaction.interaction_index,
+        ctx.accounts.session.session_index
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnpinHighlight<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [Highlights::SEED, human_record.key().as_ref()],
+        bump = highlights.bump,
+    )]
+    pub highlights: Box<Account<'info, Highlights>>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Removes a pinned interaction, freeing its slot.
+pub fn handler_unpin_highlight(
+    ctx: Context<UnpinHighlight>,
+    session: Pubkey,
+    interaction_index: u32,
+) -> Result<()> {
+    ctx.accounts
+        .highlights
+        .unpin(&session, interaction_index)?;
+
+    msg!("Interaction {} of session {} unpinned", interaction_index, session);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::InteractionType;
+
+    #[test]
+    fn interactions_are_pinned_once_unpinned_and_capped() {
+        let [owner, other] = [0; 2].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_verified_human(other.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        for wallet in [&owner, &other] {
+            harness.fund(&wallet.pubkey(), 1_000_000_000);
+        }
+        let human_record = client::human_record_address(&owner.pubkey());
+        let session = client::session_address(&human_record, 0);
+        let record = |harness: &mut Harness, wallet: &Keypair, index: u32| {
+            let ix = client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                index,
+                [index as u8 + 1; 32],
+                InteractionType::Chat as u8,
+                80,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            );
+            harness.process(ix, &[wallet]).unwrap();
+        };
+        let pin = |harness: &mut Harness, index: u32| {
+            harness.process(client::build_pin_highlight_ix(&owner.pubkey(), 0, index), &[&owner])
+        };
+        let capacity = Highlights::CAPACITY as u32;
+        for index in 0..=capacity {
+            record(&mut harness, &owner, index);
+        }
+        record(&mut harness, &other, 0);
+
+        pin(&mut harness, 0).unwrap();
+        let highlights: Highlights = harness.account(&client::highlights_address(&human_record));
+        assert_eq!(highlights.human_record, human_record);
+        assert_eq!(
+            highlights.entries,
+            [Highlight { session, interaction_index: 0, content_hash: [1u8; 32], pinned_at: NOW }]
+        );
+        assert_eq!(pin(&mut harness, 0), Err(custom(YourFunError::HighlightAlreadyPinned)));
+
+        // Only existing logs of the owner's own sessions can be pinned.
+        assert_eq!(
+            pin(&mut harness, capacity + 1),
+            Err(custom(YourFunError::InvalidLegacyAccount))
+        );
+        let other_session = client::session_address(&client::human_record_address(&other.pubkey()), 0);
+        let mut ix = client::build_pin_highlight_ix(&owner.pubkey(), 0, 0);
+        ix.accounts[2].pubkey = client::interaction_address(&other_session, 0);
+        assert_eq!(
+            harness.process(ix, &[&owner]),
+            Err(custom(anchor_lang::error::ErrorCode::ConstraintSeeds))
+        );
+
+        let unpin = |harness: &mut Harness, index: u32| {
+            harness.process(client::build_unpin_highlight_ix(&owner.pubkey(), session, index), &[&owner])
+        };
+        unpin(&mut harness, 0).unwrap();
+        assert!(harness.account::<Highlights>(&client::highlights_address(&human_record)).entries.is_empty());
+        assert_eq!(unpin(&mut harness, 0), Err(custom(YourFunError::HighlightNotFound)));
+
+        for index in 0..capacity {
+            pin(&mut harness, index).unwrap();
+        }
+        assert_eq!(pin(&mut harness, capacity), Err(custom(YourFunError::HighlightsFull)));
+        // Unpinning frees a slot for it.
+        unpin(&mut harness, 3).unwrap();
+        pin(&mut harness, capacity).unwrap();
+    }
+}
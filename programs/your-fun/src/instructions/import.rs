@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::{PlatformRegistry, HumanRecord, FingerprintData, LegacyImport};
+use crate::instructions::migrate::{check_owned_account, decode_human_record};
+use crate::instructions::verify_human::refresh_verification_status;
+use crate::error::YourFunError;
+use crate::events::LegacyRecordImported;
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ImportLegacyRecord<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    /// The wallet's HumanRecord under the previous deployment.
+    /// CHECK: Owner, discriminator and PDA derivation under
+    /// `registry.legacy_program_id` are checked in the handler.
+    pub legacy_record: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + HumanRecord::INIT_SPACE,
+        seeds = [HumanRecord::SEED, wallet.as_ref()],
+        bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    /// Imported records carry no raw fingerprint; the account exists so the
+    /// record can be closed like any other.
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + FingerprintData::space_for(0),
+        seeds = [FingerprintData::SEED, human_record.key().as_ref()],
+        bump,
+    )]
+    pub fingerprint: Box<Account<'info, FingerprintData>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LegacyImport::INIT_SPACE,
+        seeds = [LegacyImport::SEED, wallet.as_ref()],
+        bump,
+    )]
+    pub legacy_import: Box<Account<'info, LegacyImport>>,
+
+    /// Pays the rent of the new accounts; anyone may run the import.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Copies a wallet's HumanRecord from the previous deployment into a fresh
+/// record here, without charging a verification fee.
+///
+/// Permissionless, so operators can crank the migration, but only while the
+/// registry's import window is open. Each wallet can be imported once: the
+/// LegacyImport marker outlives the record, so closing it does not allow a
+/// second import. Sessions are not carried over; the session count is, so
+/// new session PDAs never collide with the old indices.
+pub fn handler_import_legacy_record(ctx: Context<ImportLegacyRecord>, wallet: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let now = Clock::get()?.unix_timestamp;
+    require!(registry.legacy_import_open(now), YourFunError::LegacyImportClosed);
+
+    let info = ctx.accounts.legacy_record.to_account_info();
+    let legacy_program_id = registry.legacy_program_id;
+    check_owned_account(&info, &legacy_program_id, &HumanRecord::DISCRIMINATOR)?;
+    let legacy = decode_human_record(&info)?;
+
+    require_keys_eq!(legacy.wallet, wallet, YourFunError::InvalidLegacyAccount);
+    let expected = Pubkey::create_program_address(
+        &[HumanRecord::SEED, wallet.as_ref(), &[legacy.bump]],
+        &legacy_program_id,
+    )
+    .map_err(|_| error!(YourFunError::InvalidLegacyAccount))?;
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidLegacyAccount);
+
+    let human_record = &mut ctx.accounts.human_record;
+    human_record.wallet = wallet;
+    human_record.verified_by = legacy.verified_by;
+    human_record.verified_at = legacy.verified_at;
+    human_record.verification_level = legacy.verification_level;
+    human_record.fingerprint_hash = legacy.fingerprint_hash;
+    human_record.flags = 0;
+    human_record.set_active(legacy.is_active());
+    human_record.session_count = legacy.session_count;
+    human_record.total_interactions = legacy.total_interactions;
+    human_record.last_active_at = legacy.last_active_at;
+    human_record.learning_score = legacy.learning_score;
+    human_record.challenge_nonce = legacy.challenge_nonce;
+    human_record.bump = ctx.bumps.human_record;
+    human_record.registered_at = legacy.registered_at;
+    human_record.strikes = legacy.strikes;
+    human_record.revoked_at = legacy.revoked_at;
+    human_record.force_closed_sessions = legacy.force_closed_sessions;
+    human_record.active_session_count = 0;
+    human_record._reserved = [0u8; 3];
+    human_record.record_version = HumanRecord::CURRENT_VERSION;
+    human_record.behavioral_score = legacy.behavioral_score;
+    // Regions did not carry over; the human is in no region's count until
+    // re-verified.
+    human_record.region_code = 0;
+    human_record.verification_status = 0;
+    // Imported verifications were already in effect on the old deployment.
+    human_record.effective_at = 0;
+    // Points at the old deployment's primary; only its being set matters.
+    human_record.merged_into = legacy.merged_into;
+    // Supervision consent was given for the person, not the deployment.
+    human_record.supervisor = legacy.supervisor;
+    // Delegated devices are re-registered against the new deployment.
+    human_record.interaction_delegate = Pubkey::default();
+    human_record.last_raffle_epoch = 0;
+    human_record.last_audited_at = 0;
+    // Spends on the old deployment do not count here.
+    human_record.spending_cap_lamports = 0;
+    human_record.spend_window_start = 0;
+    human_record.spent_in_window = 0;
+    // Liveness carries over, including a decay already applied; records from
+    // before liveness was tracked count from their verification.
+    human_record.last_liveness_at = legacy.last_liveness_at.max(legacy.verified_at);
+    human_record.liveness_decayed_at = legacy.liveness_decayed_at;
+    human_record.set_liveness_lapsed(legacy.is_liveness_lapsed());
+    // Handles belong to the old deployment and are claimed again here.
+    human_record.handle_hash = [0u8; 32];
+    human_record.frozen_until = 0;
+    human_record.verifier_fee_escrow = 0;
+    human_record.document_issuer = legacy.document_issuer;
+    human_record.attribute_commitments = legacy.attribute_commitments;
+    if legacy.record_version < 6 {
+        human_record.sub_scores = [legacy.behavioral_score, 0, 0];
+        human_record.composite_score = legacy.behavioral_score;
+    } else {
+        human_record.sub_scores = legacy.sub_scores;
+        human_record.composite_score = legacy.composite_score;
+    }
+    refresh_verification_status(human_record, registry, now);
+
+    if human_record.is_counted_as_verified() {
+        registry.total_verified_humans = registry
+            .total_verified_humans
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        registry.count_active_human(human_record)?;
+    }
+    registry.bump_state_version()?;
+
+    let fingerprint = &mut ctx.accounts.fingerprint;
+    fingerprint.human_record = human_record.key();
+    fingerprint.data = Vec::new();
+    fingerprint.bump = ctx.bumps.fingerprint;
+
+    let legacy_import = &mut ctx.accounts.legacy_import;
+    legacy_import.wallet = wallet;
+    legacy_import.legacy_record = info.key();
+    legacy_import.imported_at = now;
+    legacy_import.bump = ctx.bumps.legacy_import;
+
+    emit!(LegacyRecordImported {
+        wallet,
+        legacy_record: info.key(),
+        imported_at: now,
+    });
+
+    msg!("Legacy human record imported for wallet: {}", wallet);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::Signer;
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    /// `wallet`'s verified record as the deployment `legacy_program_id` holds
+    /// it, at its PDA under that program.
+    fn legacy_fixture(legacy_program_id: &Pubkey, wallet: &Pubkey) -> (FixtureAccount, HumanRecord) {
+        let fixtures = FixtureBuilder::new().with_verified_human(*wallet).build();
+        let current = client::human_record_address(wallet);
+        let data = &fixtures.iter().find(|f| f.address == current).unwrap().data;
+        let mut record = HumanRecord::try_deserialize(&mut &data[..]).unwrap();
+        let (address, bump) =
+            Pubkey::find_program_address(&[HumanRecord::SEED, wallet.as_ref()], legacy_program_id);
+        record.bump = bump;
+        record.verified_at = NOW - 5_000;
+        record.learning_score = 42;
+        record.session_count = 7;
+        record.total_interactions = 130;
+        let mut fixture = FixtureAccount::new(address, &record);
+        fixture.owner = *legacy_program_id;
+        (fixture, record)
+    }
+
+    #[test]
+    fn records_import_once_and_only_inside_the_window() {
+        let legacy_program_id = Pubkey::new_unique();
+        let ends_at = NOW + 1_000;
+        let [wallet, late, spoofed] = [0; 3].map(|_| Pubkey::new_unique());
+        let (legacy, expected) = legacy_fixture(&legacy_program_id, &wallet);
+        let (late_legacy, _) = legacy_fixture(&legacy_program_id, &late);
+        let (mut spoof, _) = legacy_fixture(&legacy_program_id, &spoofed);
+        spoof.owner = Pubkey::new_unique();
+        let mut accounts = FixtureBuilder::new()
+            .with_registry(|registry| {
+                registry.legacy_program_id = legacy_program_id;
+                registry.legacy_import_ends_at = ends_at;
+            })
+            .build();
+        accounts.extend([legacy.clone(), late_legacy.clone(), spoof.clone()]);
+        let mut harness = Harness::start(accounts);
+        let payer = harness.wallet();
+        let import = |wallet, legacy_record| client::build_import_legacy_record_ix(&payer.pubkey(), wallet, legacy_record);
+        let total_verified =
+            |harness: &mut Harness| harness.account::<PlatformRegistry>(&client::registry_address()).total_verified_humans;
+
+        assert_eq!(
+            harness.process(import(&spoofed, &spoof.address), &[&payer]),
+            Err(custom(YourFunError::InvalidLegacyAccount))
+        );
+        assert_eq!(
+            harness.process(import(&late, &legacy.address), &[&payer]),
+            Err(custom(YourFunError::InvalidLegacyAccount))
+        );
+
+        harness.process(import(&wallet, &legacy.address), &[&payer]).unwrap();
+        let record: HumanRecord = harness.account(&client::human_record_address(&wallet));
+        assert_eq!(
+            (record.verification_level, record.verified_at, record.registered_at, record.learning_score),
+            (expected.verification_level, expected.verified_at, expected.registered_at, 42)
+        );
+        assert_eq!((record.session_count, record.total_interactions), (7, 130));
+        assert!(record.is_active());
+        assert_eq!(total_verified(&mut harness), 1);
+        let marker: LegacyImport = harness.account(&client::legacy_import_address(&wallet));
+        assert_eq!((marker.legacy_record, marker.imported_at), (legacy.address, NOW));
+
+        // The record and its marker already exist, so the system program
+        // refuses to create them again.
+        assert_eq!(
+            harness.process(import(&wallet, &legacy.address), &[&payer]),
+            Err(InstructionError::Custom(0))
+        );
+        assert_eq!(total_verified(&mut harness), 1);
+
+        harness.warp_to(ends_at);
+        assert_eq!(
+            harness.process(import(&late, &late_legacy.address), &[&payer]),
+            Err(custom(YourFunError::LegacyImportClosed))
+        );
+        assert!(harness
+            .try_account::<HumanRecord>(&client::human_record_address(&late))
+            .is_none());
+    }
+}
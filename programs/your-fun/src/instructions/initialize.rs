@@ -1,17 +1,18 @@
 use anchor_lang::prelude::*;
-use crate::state::PlatformRegistry;
-use crate::error::YourFunError;
+use crate::state::{BoosterMint, FeeSchedule, PlatformRegistry};
+use crate::events::ConfigUpdated;
+use crate::scoring::TYPE_MULTIPLIER_COUNT;
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(
         init,
         payer = authority,
-        space = PlatformRegistry::SIZE,
+        space = 8 + PlatformRegistry::INIT_SPACE,
         seeds = [PlatformRegistry::SEED],
         bump,
     )]
-    pub registry: Account<'info, PlatformRegistry>,
+    pub registry: Box<Account<'info, PlatformRegistry>>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -19,24 +20,31 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler_initialize(
     ctx: Context<Initialize>,
     verification_fee_lamports: u64,
     min_behavioral_score: u8,
     max_session_duration: i64,
     max_interactions_per_session: u32,
+    min_fee_lamports: u64,
+    max_fee_lamports: u64,
+    treasury: Pubkey,
 ) -> Result<()> {
     let registry = &mut ctx.accounts.registry;
 
-    require!(
-        min_behavioral_score <= 100,
-        YourFunError::InvalidVerificationLevel
-    );
-    require!(
-        max_session_duration > 0,
-        YourFunError::SessionDurationExceeded
-    );
+    PlatformRegistry::validate_fee_bounds(
+        min_fee_lamports,
+        max_fee_lamports,
+        verification_fee_lamports,
+    )?;
+    PlatformRegistry::validate_core_config(
+        min_behavioral_score,
+        max_session_duration,
+        max_interactions_per_session,
+    )?;
 
+    let now = Clock::get()?.unix_timestamp;
     registry.authority = ctx.accounts.authority.key();
     registry.verification_fee_lamports = verification_fee_lamports;
     registry.total_verified_humans = 0;
@@ -47,7 +55,79 @@ pub fn handler_initialize(
     registry.max_session_duration = max_session_duration;
     registry.max_interactions_per_session = max_interactions_per_session;
     registry.bump = ctx.bumps.registry;
-    registry._reserved = [0u8; 64];
+    registry.reregistration_cooldown = 0;
+    registry.max_fingerprint_len = PlatformRegistry::DEFAULT_MAX_FINGERPRINT_LEN;
+    registry.reverifications = 0;
+    registry.moderator = Pubkey::default();
+    registry.force_close_settle_bps = 0;
+    registry.log_threshold_score = 0;
+    registry.config_frozen = false;
+    registry.state_version = 0;
+    registry.version = PlatformRegistry::CURRENT_VERSION;
+    registry._reserved = [0u8; 1];
+    registry.guardian = Pubkey::default();
+    registry.guardian_can_unpause = false;
+    registry.min_fee_lamports = min_fee_lamports;
+    registry.max_fee_lamports = max_fee_lamports;
+    registry.admin_action_count = 0;
+    registry.permissioned_registration = false;
+    registry.restrict_regions = false;
+    registry.allowed_regions = [0u8; 125];
+    registry.maintenance_start_at = 0;
+    registry.maintenance_end_at = 0;
+    registry.verification_validity = 0;
+    registry.verification_grace_period = 0;
+    registry.legacy_program_id = Pubkey::default();
+    registry.legacy_import_ends_at = 0;
+    registry.arbiter = Pubkey::default();
+    registry.appeal_window = 0;
+    registry.sub_score_weights_bps = PlatformRegistry::DEFAULT_SUB_SCORE_WEIGHTS_BPS;
+    registry.contest_window = 0;
+    registry.curator = Pubkey::default();
+    registry.goal_bonus_flat = 0;
+    registry.goal_bonus_bps = 0;
+    registry.reopen_grace_period = 0;
+    registry.reopen_fee_lamports = 0;
+    registry.max_content_size = 0;
+    registry.companion_oracle = Pubkey::default();
+    registry.settlement_window = 0;
+    registry.boosters = [BoosterMint::default(); PlatformRegistry::MAX_BOOSTERS];
+    registry.program_officer = Pubkey::default();
+    registry.insurance_fee_bps = 0;
+    registry.enforce_registered_topics = false;
+    registry.private_score_bps = 0;
+    registry.max_verified_humans = 0;
+    registry.waitlist_count = 0;
+    registry.waitlist_next = 0;
+    registry.liveness_interval_seconds = 0;
+    registry.liveness_challenge_required = false;
+    registry.treasury = treasury;
+    registry.pause_flags = 0;
+    registry.config_timelock_seconds = 0;
+    registry.co_authority = Pubkey::default();
+    registry.config_version = 0;
+    registry.fee_schedule = FeeSchedule::default();
+    registry.max_fee_delta_bps = PlatformRegistry::DEFAULT_MAX_FEE_DELTA_BPS;
+    registry.fee_update_cooldown_seconds = 0;
+    registry.last_fee_update_at = now;
+    registry.active_sessions = 0;
+    registry._reserved_pending = [0u8; 8];
+    registry.max_active_sessions = 0;
+    registry.min_interaction_gap_seconds = 0;
+    registry.min_interaction_duration_seconds = 0;
+    registry.type_multipliers = [0; TYPE_MULTIPLIER_COUNT];
+    registry.max_personality_id = u8::MAX;
+    registry.verifier_fee_share_bps = 0;
+    registry.active_humans = 0;
+
+    emit!(ConfigUpdated {
+        version: 0,
+        field_mask: PlatformRegistry::CONFIG_ALL,
+        old_fee: 0,
+        new_fee: verification_fee_lamports,
+        updated_by: registry.authority,
+        updated_at: now,
+    });
 
     msg!("Platform registry initialized with fee: {} lamports", verification_fee_lamports);
     Ok(())
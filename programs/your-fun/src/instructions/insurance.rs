@@ -0,0 +1,293 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    PlatformRegistry, AdminAction, AdminActionKind, Appeal, AppealStatus, InsuranceFund,
+    InsuranceClaim,
+};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+use crate::events::InsuranceClaimPaid;
+
+#[derive(Accounts)]
+pub struct InitInsuranceFund<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + InsuranceFund::INIT_SPACE,
+        seeds = [InsuranceFund::SEED],
+        bump,
+    )]
+    pub insurance_fund: Box<Account<'info, InsuranceFund>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the insurance fund that `insurance_fee_bps` of every fee is
+/// routed to. Authority only.
+pub fn handler_init_insurance_fund(ctx: Context<InitInsuranceFund>) -> Result<()> {
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.total_in = 0;
+    insurance_fund.total_out = 0;
+    insurance_fund.claims_paid = 0;
+    insurance_fund.bump = ctx.bumps.insurance_fund;
+
+    msg!("Insurance fund initialized");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PayInsuranceClaim<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [InsuranceFund::SEED],
+        bump = insurance_fund.bump,
+    )]
+    pub insurance_fund: Box<Account<'info, InsuranceFund>>,
+
+    /// The resolved appeal the payout compensates.
+    #[account(
+        seeds = [Appeal::SEED, appeal.human_record.as_ref(), &appeal.revoked_at.to_le_bytes()],
+        bump = appeal.bump,
+        constraint = appeal.status == AppealStatus::Approved as u8 @ YourFunError::AppealNotApproved,
+    )]
+    pub appeal: Box<Account<'info, Appeal>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + InsuranceClaim::INIT_SPACE,
+        seeds = [InsuranceClaim::SEED, appeal.key().as_ref()],
+        bump,
+    )]
+    pub claim: Box<Account<'info, InsuranceClaim>>,
+
+    /// CHECK: Only receives lamports.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays `amount` lamports from the insurance fund to `recipient` as
+/// compensation for an approved appeal, keeping the fund rent-exempt. Each
+/// appeal can be paid once. Authority only.
+pub fn handler_pay_insurance_claim(
+    ctx: Context<PayInsuranceClaim>,
+    amount: u64,
+    claim_ref_hash: [u8; 32],
+) -> Result<()> {
+    require!(amount > 0, YourFunError::ZeroAmount);
+
+    let fund_info = ctx.accounts.insurance_fund.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(fund_info.data_len());
+    require!(
+        fund_info.lamports().saturating_sub(rent_exempt) >= amount,
+        YourFunError::InsufficientFunds
+    );
+
+    let recipient_info = ctx.accounts.recipient.to_account_info();
+    **fund_info.try_borrow_mut_lamports()? -= amount;
+    **recipient_info.try_borrow_mut_lamports()? = recipient_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.total_out = insurance_fund
+        .total_out
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    insurance_fund.claims_paid = insurance_fund
+        .claims_paid
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let claim = &mut ctx.accounts.claim;
+    claim.appeal = ctx.accounts.appeal.key();
+    claim.recipient = recipient_info.key();
+    claim.amount = amount;
+    claim.claim_ref_hash = claim_ref_hash;
+    claim.paid_at = Clock::get()?.unix_timestamp;
+    claim.bump = ctx.bumps.claim;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::PayInsuranceClaim,
+        ctx.accounts.authority.key(),
+        &(claim.appeal, claim.recipient, amount, claim_ref_hash),
+    )?;
+
+    emit!(InsuranceClaimPaid {
+        appeal: claim.appeal,
+        recipient: claim.recipient,
+        amount,
+        claim_ref_hash,
+        total_out: insurance_fund.total_out,
+    });
+
+    msg!("Insurance claim of {} lamports paid", amount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::error::ErrorCode;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    const FEE: u64 = 10_000_000;
+
+    /// An appeal of `wallet`'s revocation at `NOW` with `status`.
+    fn appeal(wallet: &Pubkey, status: AppealStatus) -> FixtureAccount {
+        let human_record = client::human_record_address(wallet);
+        let (address, bump) =
+            Pubkey::find_program_address(&[Appeal::SEED, human_record.as_ref(), &NOW.to_le_bytes()], &crate::ID);
+        FixtureAccount::new(
+            address,
+            &Appeal {
+                human_record,
+                wallet: *wallet,
+                revoked_at: NOW,
+                evidence_hash: [1u8; 32],
+                filed_at: NOW,
+                status: status as u8,
+                resolved_at: NOW,
+                note_hash: [0u8; 32],
+                bump,
+            },
+        )
+    }
+
+    #[test]
+    fn a_share_of_platform_fees_accrues_and_pays_out_only_against_approved_appeals() {
+        let [authority, stranger] = [0; 2].map(|_| Keypair::new());
+        let (approved_wallet, pending_wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.verification_fee_lamports = FEE;
+                registry.verifier_fee_share_bps = 2_000;
+                registry.insurance_fee_bps = 2_500;
+            })
+            .build();
+        let approved = appeal(&approved_wallet, AppealStatus::Approved);
+        let pending = appeal(&pending_wallet, AppealStatus::Pending);
+        let (approved_appeal, pending_appeal) = (approved.address, pending.address);
+        accounts.extend([approved, pending]);
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &stranger] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let fund = client::insurance_fund_address();
+        harness.process(client::build_init_insurance_fund_ix(&authority.pubkey()), &[&authority]).unwrap();
+        let rent = harness.balance(&fund);
+
+        let users: Vec<_> = (0..2).map(|_| harness.wallet()).collect();
+        let register = |harness: &mut Harness, i: usize, insured: bool| {
+            let ix = client::build_register_human_ix(
+                &users[i].pubkey(),
+                &authority.pubkey(),
+                false,
+                insured,
+                None,
+                None,
+                [7u8; 32],
+                vec![i as u8 + 1; 64],
+            );
+            harness.process(ix, &[&users[i]])
+        };
+        assert_eq!(register(&mut harness, 0, false), Err(custom(YourFunError::InsuranceFundRequired)));
+        let fee_wallet = harness.balance(&authority.pubkey());
+        for i in 0..2 {
+            register(&mut harness, i, true).unwrap();
+        }
+        // 20% of each fee is held for the verifier; a quarter of the rest is
+        // insured.
+        let insured = (FEE - FEE / 5) / 4;
+        assert_eq!(harness.balance(&fund), rent + 2 * insured);
+        assert_eq!(harness.balance(&authority.pubkey()), fee_wallet + 2 * (FEE - FEE / 5 - insured));
+        assert_eq!(harness.account::<InsuranceFund>(&fund).total_in, 2 * insured);
+
+        let recipient = Pubkey::new_unique();
+        let pay = |harness: &mut Harness, signer: &Keypair, appeal: &Pubkey, amount: u64| {
+            let count = harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count;
+            let ix = client::build_pay_insurance_claim_ix(&signer.pubkey(), count, appeal, &recipient, amount, [9u8; 32]);
+            harness.process(ix, &[signer])
+        };
+        let missing = Pubkey::new_unique();
+        assert_eq!(
+            pay(&mut harness, &authority, &missing, insured),
+            Err(custom(ErrorCode::AccountNotInitialized))
+        );
+        assert_eq!(
+            pay(&mut harness, &authority, &pending_appeal, insured),
+            Err(custom(YourFunError::AppealNotApproved))
+        );
+        assert_eq!(
+            pay(&mut harness, &stranger, &approved_appeal, insured),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        assert_eq!(
+            pay(&mut harness, &authority, &approved_appeal, 2 * insured + 1),
+            Err(custom(YourFunError::InsufficientFunds))
+        );
+
+        pay(&mut harness, &authority, &approved_appeal, 2 * insured).unwrap();
+        assert_eq!(harness.balance(&recipient), 2 * insured);
+        assert_eq!(harness.balance(&fund), rent);
+        let state: InsuranceFund = harness.account(&fund);
+        assert_eq!((state.total_in, state.total_out, state.claims_paid), (2 * insured, 2 * insured, 1));
+        let claim: InsuranceClaim = harness.account(&client::insurance_claim_address(&approved_appeal));
+        assert_eq!(
+            (claim.appeal, claim.recipient, claim.amount, claim.claim_ref_hash),
+            (approved_appeal, recipient, 2 * insured, [9u8; 32])
+        );
+
+        // Each appeal is paid at most once: its claim account exists.
+        assert_eq!(
+            pay(&mut harness, &authority, &approved_appeal, 1),
+            Err(anchor_lang::solana_program::instruction::InstructionError::Custom(0))
+        );
+    }
+}
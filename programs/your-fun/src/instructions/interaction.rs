@@ -1,24 +1,74 @@
 use anchor_lang::prelude::*;
-use crate::state::{PlatformRegistry, HumanRecord, SessionAccount, InteractionLog};
+use anchor_lang::Discriminator;
+use crate::state::{
+    PlatformRegistry, HumanRecord, SessionAccount, InteractionLog, StatsBucket, InteractionType,
+    VerificationStatus,
+};
+use crate::instructions::verify_human::refresh_verification_status;
+use crate::instructions::migrate::{check_owned_account, read_padded};
+use crate::instructions::stats::{record_epoch_activity, EpochActivity};
 use crate::error::YourFunError;
+use crate::constants::MAX_SCORE;
+use crate::crypto::{companion_message, next_chain_head};
+use crate::sigverify::has_ed25519_signature;
+use crate::events::InteractionRecorded;
+use crate::scoring::{apply_booster, apply_private_share, apply_scoring_profile, compute_score_increment};
+use crate::compute::{require_remaining_compute_units, RECORD_INTERACTION_WRITE_RESERVE_CU};
+
+/// Argument of `record_interaction_v2`. Fields added later are optional, so
+/// older clients keep building valid inputs; `version` names the newest
+/// layout the client filled in, and inputs from a newer layout than this
+/// program knows are rejected instead of having fields silently dropped.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct InteractionInputV2 {
+    /// Input layout version; at most `InteractionInputV2::VERSION`.
+    pub version: u8,
+    pub content_hash: [u8; 32],
+    pub interaction_type: u8,
+    pub score: u8,
+    pub duration_seconds: u32,
+    /// Where the content can be fetched for audits, with `content_size`
+    /// (`None` = not provided).
+    pub content_uri_hash: Option<[u8; 32]>,
+    pub content_size: Option<u32>,
+    /// Records the interaction in privacy mode (`None` = not private).
+    pub private: Option<bool>,
+}
+
+impl InteractionInputV2 {
+    /// Newest input layout this program build understands.
+    pub const VERSION: u8 = 1;
+
+    /// An input of the current version without any optional field.
+    pub fn new(content_hash: [u8; 32], interaction_type: u8, score: u8, duration_seconds: u32) -> Self {
+        Self {
+            version: Self::VERSION,
+            content_hash,
+            interaction_type,
+            score,
+            duration_seconds,
+            content_uri_hash: None,
+            content_size: None,
+            private: None,
+        }
+    }
+}
 
 #[derive(Accounts)]
-#[instruction(content_hash: [u8; 32], interaction_type: u8)]
 pub struct RecordInteraction<'info> {
     #[account(
-        mut,
         seeds = [PlatformRegistry::SEED],
         bump = registry.bump,
     )]
-    pub registry: Account<'info, PlatformRegistry>,
+    pub registry: Box<Account<'info, PlatformRegistry>>,
 
     #[account(
         mut,
         seeds = [HumanRecord::SEED, user.key().as_ref()],
         bump = human_record.bump,
-        constraint = human_record.is_active @ YourFunError::HumanRecordRevoked,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
     )]
-    pub human_record: Account<'info, HumanRecord>,
+    pub human_record: Box<Account<'info, HumanRecord>>,
 
     #[account(
         mut,
@@ -28,15 +78,15 @@ pub struct RecordInteraction<'info> {
             &session.session_index.to_le_bytes(),
         ],
         bump = session.bump,
-        constraint = session.is_active @ YourFunError::SessionInactive,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
         constraint = session.owner == user.key() @ YourFunError::Unauthorized,
     )]
-    pub session: Account<'info, SessionAccount>,
+    pub session: Box<Account<'info, SessionAccount>>,
 
     #[account(
         init,
         payer = user,
-        space = InteractionLog::SIZE,
+        space = 8 + InteractionLog::INIT_SPACE,
         seeds = [
             InteractionLog::SEED,
             session.key().as_ref(),
@@ -44,7 +94,24 @@ pub struct RecordInteraction<'info> {
         ],
         bump,
     )]
-    pub interaction: Account<'info, InteractionLog>,
+    pub interaction: Box<Account<'info, InteractionLog>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    /// Required while companion co-signing is on.
+    /// CHECK: The instructions sysvar, pinned by address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
@@ -52,109 +119,1235 @@ pub struct RecordInteraction<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Records an interaction; the legacy form of `record_interaction_v2`, with
+/// zeros for an omitted content reference.
+#[allow(clippy::too_many_arguments)]
 pub fn handler_record_interaction(
     ctx: Context<RecordInteraction>,
     content_hash: [u8; 32],
     interaction_type: u8,
     score: u8,
     duration_seconds: u32,
+    content_uri_hash: [u8; 32],
+    content_size: u32,
+    private: bool,
 ) -> Result<()> {
-    let registry = &mut ctx.accounts.registry;
-    let human_record = &mut ctx.accounts.human_record;
-    let session = &mut ctx.accounts.session;
-    let interaction = &mut ctx.accounts.interaction;
-    let clock = Clock::get()?;
+    let input = InteractionInputV2 {
+        content_uri_hash: Some(content_uri_hash),
+        content_size: Some(content_size),
+        private: Some(private),
+        ..InteractionInputV2::new(content_hash, interaction_type, score, duration_seconds)
+    };
+    record_interaction(ctx, input)
+}
 
-    require!(
-        session.expires_at > clock.unix_timestamp,
-        YourFunError::SessionExpired
-    );
+/// Records an interaction described by a versioned input.
+pub fn handler_record_interaction_v2(
+    ctx: Context<RecordInteraction>,
+    input: InteractionInputV2,
+) -> Result<()> {
+    record_interaction(ctx, input)
+}
 
+/// Records an interaction and folds its score into the session. Both entry
+/// points land here, so this is the one place their input is validated.
+///
+/// This is the highest-volume instruction, so validations run cheapest-first:
+/// pure argument checks, then account state, and the clock sysvar is read
+/// once only after those pass. The summary log line is only emitted with the
+/// `verbose-logs` feature.
+///
+/// `content_uri_hash` and `content_size` optionally say where the content
+/// can be fetched for audits; both are zero when omitted.
+///
+/// In privacy mode (`private`) the content hash may be all zeros, the log is
+/// marked private, and the interaction earns only `private_score_bps` of its
+/// score increment.
+fn record_interaction(ctx: Context<RecordInteraction>, input: InteractionInputV2) -> Result<()> {
     require!(
-        session.interaction_count < registry.max_interactions_per_session,
-        YourFunError::InteractionLimitReached
+        (1..=InteractionInputV2::VERSION).contains(&input.version),
+        YourFunError::UnsupportedInputVersion
     );
+    let InteractionInputV2 {
+        content_hash,
+        interaction_type,
+        score,
+        duration_seconds,
+        ..
+    } = input;
+    let content_uri_hash = input.content_uri_hash.unwrap_or_default();
+    let content_size = input.content_size.unwrap_or_default();
+    let private = input.private.unwrap_or_default();
 
+    let uri_provided = content_uri_hash.iter().any(|&b| b != 0);
     require!(
-        interaction_type <= 3,
-        YourFunError::InvalidContentHash
+        if uri_provided {
+            content_size != 0 && content_size <= ctx.accounts.registry.content_size_limit()
+        } else {
+            content_size == 0
+        },
+        YourFunError::InvalidContentReference
     );
 
-    require!(score <= 100, YourFunError::InvalidVerificationLevel);
+    let accounts = &mut *ctx.accounts;
+    require_companion_cosignature(
+        &accounts.registry,
+        &accounts.session,
+        accounts.instructions.as_ref(),
+        content_hash,
+        interaction_type,
+        score,
+        duration_seconds,
+    )?;
+    let applied = apply_interaction(
+        &accounts.registry,
+        &mut accounts.human_record,
+        &mut accounts.session,
+        &mut accounts.stats_bucket,
+        content_hash,
+        interaction_type,
+        score,
+        duration_seconds,
+        InteractionPath::Logged { private },
+    )?;
+    record_epoch_activity(
+        &accounts.epoch_stats,
+        &accounts.user,
+        &accounts.system_program,
+        applied.now,
+        EpochActivity {
+            interactions: 1,
+            score_awarded: applied.score_increment,
+            ..Default::default()
+        },
+    )?;
 
-    let is_zero_hash = content_hash.iter().all(|&b| b == 0);
-    require!(!is_zero_hash, YourFunError::InvalidContentHash);
+    let session = &mut accounts.session;
+    session.open_logs = session
+        .open_logs
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
 
+    let interaction = &mut accounts.interaction;
     interaction.session = session.key();
-    interaction.user = ctx.accounts.user.key();
-    interaction.interaction_index = session.interaction_count;
-    interaction.timestamp = clock.unix_timestamp;
+    interaction.user = accounts.user.key();
+    interaction.interaction_index = applied.interaction_index;
+    interaction.timestamp = applied.now;
     interaction.content_hash = content_hash;
     interaction.interaction_type = interaction_type;
     interaction.score = score;
     interaction.duration_seconds = duration_seconds;
     interaction.bump = ctx.bumps.interaction;
+    interaction.content_uri_hash = content_uri_hash;
+    interaction.content_size = content_size;
+    interaction.prev_chain_head = applied.prev_chain_head;
+    interaction.private = private;
+
+    #[cfg(feature = "verbose-logs")]
+    msg!(
+        "Interaction {} recorded: type={}, score={}, duration={}s",
+        applied.interaction_index,
+        interaction_type,
+        score,
+        duration_seconds
+    );
+
+    Ok(())
+}
 
+#[derive(Accounts)]
+pub struct RecordUnloggedInteraction<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, user.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+        constraint = session.owner == user.key() @ YourFunError::Unauthorized,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    /// Required while companion co-signing is on.
+    /// CHECK: The instructions sysvar, pinned by address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Records an interaction scoring below `log_threshold_score` without creating
+/// an InteractionLog account. Counters and the session score are updated
+/// exactly as in `record_interaction`, and the interaction index is consumed,
+/// so the log PDA for that index simply never exists.
+pub fn handler_record_unlogged_interaction(
+    ctx: Context<RecordUnloggedInteraction>,
+    content_hash: [u8; 32],
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    require_companion_cosignature(
+        &accounts.registry,
+        &accounts.session,
+        accounts.instructions.as_ref(),
+        content_hash,
+        interaction_type,
+        score,
+        duration_seconds,
+    )?;
+    let applied = apply_interaction(
+        &accounts.registry,
+        &mut accounts.human_record,
+        &mut accounts.session,
+        &mut accounts.stats_bucket,
+        content_hash,
+        interaction_type,
+        score,
+        duration_seconds,
+        InteractionPath::Unlogged,
+    )?;
+    record_epoch_activity(
+        &accounts.epoch_stats,
+        &accounts.user,
+        &accounts.system_program,
+        applied.now,
+        EpochActivity {
+            interactions: 1,
+            score_awarded: applied.score_increment,
+            ..Default::default()
+        },
+    )?;
+
+    emit!(InteractionRecorded {
+        session: accounts.session.key(),
+        user: accounts.user.key(),
+        interaction_index: applied.interaction_index,
+        content_hash,
+        interaction_type,
+        score,
+        duration_seconds,
+        score_increment: applied.score_increment,
+        timestamp: applied.now,
+        prev_chain_head: applied.prev_chain_head,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PreviewScore<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    /// The session whose scoring profile and booster apply.
+    pub session: Box<Account<'info, SessionAccount>>,
+}
+
+/// Returns the score increment `record_interaction` would award for these
+/// inputs in `session`, without touching any account. The value comes from
+/// the same `session_score_increment` call as the real path, so the two
+/// cannot diverge.
+pub fn handler_preview_score(
+    ctx: Context<PreviewScore>,
+    score: u8,
+    interaction_type: u8,
+    duration_seconds: u32,
+    private: bool,
+) -> Result<u64> {
+    let session = &ctx.accounts.session;
+    session_score_increment(
+        &ctx.accounts.registry,
+        session.scoring_profile,
+        session.booster_multiplier_bps,
+        interaction_type,
+        score,
+        duration_seconds,
+        private,
+    )
+}
+
+/// Validates the scoring inputs and computes the session score increment
+/// with the registry's type multipliers.
+fn score_interaction(
+    registry: &PlatformRegistry,
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+) -> Result<u64> {
+    let interaction_type = InteractionType::try_from(interaction_type)?;
+
+    require!(score <= MAX_SCORE, YourFunError::InvalidVerificationLevel);
+
+    compute_score_increment(score, interaction_type as u8, duration_seconds, &registry.type_multipliers)
+        .ok_or(error!(YourFunError::NumericalOverflow))
+}
+
+/// Runs the full scoring pipeline for an interaction in a session with the
+/// given scoring profile and booster: the base increment, then the profile,
+/// then the booster, then the private share for a private interaction.
+pub(crate) fn session_score_increment(
+    registry: &PlatformRegistry,
+    scoring_profile: u8,
+    booster_multiplier_bps: u16,
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+    private: bool,
+) -> Result<u64> {
+    apply_scoring_profile(
+        score_interaction(registry, interaction_type, score, duration_seconds)?,
+        scoring_profile,
+        interaction_type,
+    )
+    .and_then(|increment| apply_booster(increment, booster_multiplier_bps))
+    .and_then(|increment| match private {
+        true => apply_private_share(increment, registry.private_score_bps),
+        false => Some(increment),
+    })
+    .ok_or(error!(YourFunError::NumericalOverflow))
+}
+
+/// How an interaction reaches `apply_interaction`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InteractionPath {
+    /// With an InteractionLog; the score must reach `log_threshold_score`.
+    /// A private interaction may have a zero content hash and earns only
+    /// `private_score_bps` of its increment.
+    Logged { private: bool },
+    /// Without a log; the score must stay below `log_threshold_score`.
+    Unlogged,
+    /// From a signed offline bundle, which stands in for the log whatever
+    /// the score. The interaction happened at `occurred_at` rather than now.
+    Bundled { occurred_at: i64 },
+}
+
+/// While the registry names a companion oracle, requires its ed25519
+/// signature, verified earlier in the transaction, over the
+/// `companion_message` for the session's next interaction. The message binds
+/// the session nonce, so signatures for another session, or from before a
+/// reopen or extension, are rejected.
+fn require_companion_cosignature(
+    registry: &PlatformRegistry,
+    session: &Account<SessionAccount>,
+    instructions: Option<&UncheckedAccount>,
+    content_hash: [u8; 32],
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+) -> Result<()> {
+    if registry.companion_oracle == Pubkey::default() {
+        return Ok(());
+    }
+    let instructions = instructions.ok_or(YourFunError::CompanionCosignatureRequired)?;
+    let message = companion_message(
+        &session.key().to_bytes(),
+        &session.session_nonce,
+        session.interaction_count,
+        &content_hash,
+        interaction_type,
+        score,
+        duration_seconds,
+    );
+    require!(
+        has_ed25519_signature(instructions, &registry.companion_oracle, &message, None)?,
+        YourFunError::CompanionCosignatureRequired
+    );
+    Ok(())
+}
+
+/// Outcome of an interaction applied to the session and its counters.
+pub(crate) struct AppliedInteraction {
+    pub interaction_index: u32,
+    pub score_increment: u64,
+    /// When the interaction happened: the current time unless bundled.
+    pub now: i64,
+    /// The session's chain head before this interaction.
+    pub prev_chain_head: [u8; 32],
+}
+
+/// Validates an interaction and applies it to the session, the human record
+/// and the stats bucket. Shared by the logged, unlogged and bundled paths so
+/// all advance `interaction_count` and the counters identically; the caller
+/// is responsible for anything specific to its path.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_interaction(
+    registry: &PlatformRegistry,
+    human_record: &mut HumanRecord,
+    session: &mut SessionAccount,
+    stats_bucket: &mut StatsBucket,
+    content_hash: [u8; 32],
+    interaction_type: u8,
+    score: u8,
+    duration_seconds: u32,
+    path: InteractionPath,
+) -> Result<AppliedInteraction> {
+    require!(
+        duration_seconds >= registry.min_interaction_duration_seconds as u32,
+        YourFunError::InteractionTooShort
+    );
+    let private = path == InteractionPath::Logged { private: true };
+    let score_increment = session_score_increment(
+        registry,
+        session.scoring_profile,
+        session.booster_multiplier_bps,
+        interaction_type,
+        score,
+        duration_seconds,
+        private,
+    )?;
+
+    let is_zero_hash = content_hash.iter().all(|&b| b == 0);
+    require!(private || !is_zero_hash, YourFunError::InvalidContentHash);
+
+    match path {
+        InteractionPath::Logged { .. } => require!(
+            score >= registry.log_threshold_score,
+            YourFunError::InteractionBelowLogThreshold
+        ),
+        InteractionPath::Unlogged => require!(
+            score < registry.log_threshold_score,
+            YourFunError::InteractionRequiresLog
+        ),
+        InteractionPath::Bundled { .. } => {}
+    }
+
+    require!(
+        session.interaction_count < registry.max_interactions_per_session,
+        YourFunError::InteractionLimitReached
+    );
+
+    let clock_now = Clock::get()?.unix_timestamp;
+    registry.require_unpaused(PlatformRegistry::PAUSE_INTERACTIONS, clock_now)?;
+    require!(!human_record.is_frozen(clock_now), YourFunError::HumanRecordFrozen);
+    let now = match path {
+        InteractionPath::Bundled { occurred_at } => occurred_at,
+        _ => clock_now,
+    };
+    require!(session.expires_at > now, YourFunError::SessionExpired);
+    require!(
+        registry.min_interaction_gap_seconds == 0
+            || session.interaction_count == 0
+            || now.saturating_sub(session.last_interaction_at)
+                >= registry.min_interaction_gap_seconds as i64,
+        YourFunError::InteractionTooSoon
+    );
+    require!(
+        refresh_verification_status(human_record, registry, clock_now) != VerificationStatus::Lapsed,
+        YourFunError::VerificationLapsed
+    );
+
+    require_remaining_compute_units(RECORD_INTERACTION_WRITE_RESERVE_CU)?;
+
+    let interaction_index = session.interaction_count;
     session.interaction_count = session
         .interaction_count
         .checked_add(1)
         .ok_or(YourFunError::NumericalOverflow)?;
-    session.last_interaction_at = clock.unix_timestamp;
+    session.last_interaction_at = now;
+    let prev_chain_head = session.chain_head;
+    session.chain_head =
+        next_chain_head(&prev_chain_head, interaction_index, &content_hash, score, now);
 
-    let score_increment = compute_score_increment(score, interaction_type, duration_seconds);
     session.session_score = session
         .session_score
         .checked_add(score_increment)
         .ok_or(YourFunError::NumericalOverflow)?;
+    session
+        .credit_active_topic(score_increment)
+        .ok_or(YourFunError::NumericalOverflow)?;
 
     human_record.total_interactions = human_record
         .total_interactions
         .checked_add(1)
         .ok_or(YourFunError::NumericalOverflow)?;
-    human_record.last_active_at = clock.unix_timestamp;
+    human_record.last_active_at = human_record.last_active_at.max(now);
 
-    registry.total_interactions = registry
-        .total_interactions
+    stats_bucket.pending_interactions = stats_bucket
+        .pending_interactions
         .checked_add(1)
         .ok_or(YourFunError::NumericalOverflow)?;
 
+    Ok(AppliedInteraction {
+        interaction_index,
+        score_increment,
+        now,
+        prev_chain_head,
+    })
+}
+
+/// Decodes an InteractionLog of `session` in the current or any earlier
+/// layout, checking ownership, discriminator and PDA derivation.
+pub(crate) fn load_interaction_log(info: &AccountInfo, session: &Pubkey) -> Result<InteractionLog> {
+    check_owned_account(info, &crate::ID, &InteractionLog::DISCRIMINATOR)?;
+    let interaction: InteractionLog = read_padded(info, 8 + InteractionLog::INIT_SPACE)?;
+    let expected = Pubkey::create_program_address(
+        &[
+            InteractionLog::SEED,
+            session.as_ref(),
+            &interaction.interaction_index.to_le_bytes(),
+            &[interaction.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(ErrorCode::ConstraintSeeds))?;
+    require_keys_eq!(info.key(), expected, ErrorCode::ConstraintSeeds);
+    Ok(interaction)
+}
+
+#[derive(Accounts)]
+pub struct ReapInteraction<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = !session.is_active() @ YourFunError::SessionStillActive,
+        constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    /// CHECK: May predate content references, so it is decoded in the
+    /// handler, which checks ownership, discriminator and PDA derivation.
+    #[account(mut)]
+    pub interaction: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Closes an interaction log of a closed session and returns its rent to the
+/// session owner. The interaction index is not reused since
+/// `interaction_count` is left untouched. Logs in the layout before content
+/// references are accepted too.
+pub fn handler_reap_interaction(ctx: Context<ReapInteraction>) -> Result<()> {
+    let info = ctx.accounts.interaction.to_account_info();
+    let interaction = load_interaction_log(&info, &ctx.accounts.session.key())?;
+
+    let owner_info = ctx.accounts.owner.to_account_info();
+    let refund = info.lamports();
+    **owner_info.try_borrow_mut_lamports()? = owner_info
+        .lamports()
+        .checked_add(refund)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&anchor_lang::system_program::ID);
+    info.realloc(0, false)?;
+
+    let session = &mut ctx.accounts.session;
+
+    session.open_logs = session
+        .open_logs
+        .checked_sub(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
     msg!(
-        "Interaction {} recorded: type={}, score={}, duration={}s",
+        "Interaction {} of session {} reaped",
         interaction.interaction_index,
-        interaction_type,
-        score,
-        duration_seconds
+        session.session_index
     );
 
     Ok(())
 }
 
-/// Computes a weighted score increment based on interaction quality metrics.
-///
-/// The scoring formula applies multipliers based on interaction type:
-///   - Chat (0): 1x base
-///   - Quiz (1): 2x base
-///   - Exercise (2): 3x base
-///   - Review (3): 1.5x base
-///
-/// Duration acts as a diminishing-returns bonus capped at 300 seconds.
-fn compute_score_increment(score: u8, interaction_type: u8, duration_seconds: u32) -> u64 {
-    let base_score = score as u64;
-
-    let type_multiplier: u64 = match interaction_type {
-        0 => 100,
-        1 => 200,
-        2 => 300,
-        3 => 150,
-        _ => 100,
-    };
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::constants::MAX_BPS;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, ed25519_instruction, Harness, NOW};
+    use crate::state::{EpochStats, InteractionType, ScoringProfile};
+
+    #[test]
+    fn preview_matches_the_recorded_increment() {
+        let wallet = Keypair::new();
+        let accounts = FixtureBuilder::new()
+            .with_registry(|registry| registry.private_score_bps = 4_000)
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .with_session(|session| {
+                session.scoring_profile = ScoringProfile::QuizHeavy as u8;
+                session.booster_multiplier_bps = 12_500;
+            })
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+
+        let cases = [
+            (InteractionType::Quiz, false),
+            (InteractionType::Quiz, true),
+            (InteractionType::Chat, false),
+        ];
+        let mut previews = Vec::new();
+        for (index, (interaction_type, private)) in cases.into_iter().enumerate() {
+            let (score, duration_seconds) = (80, 300);
+            let preview = harness
+                .simulate(
+                    client::build_preview_score_ix(
+                        &wallet.pubkey(),
+                        0,
+                        score,
+                        interaction_type as u8,
+                        duration_seconds,
+                        private,
+                    ),
+                    &[],
+                )
+                .unwrap();
+            let preview = u64::from_le_bytes(preview.try_into().unwrap());
+
+            let before = harness.account::<SessionAccount>(&session).session_score;
+            let content_hash = if private { [0u8; 32] } else { [index as u8 + 1; 32] };
+            harness
+                .process(
+                    client::build_record_interaction_ix(
+                        &wallet.pubkey(),
+                        0,
+                        index as u32,
+                        content_hash,
+                        interaction_type as u8,
+                        score,
+                        duration_seconds,
+                        [0u8; 32],
+                        0,
+                        false,
+                        private,
+                        NOW,
+                    ),
+                    &[&wallet],
+                )
+                .unwrap();
+            let after = harness.account::<SessionAccount>(&session).session_score;
+            assert_eq!(preview, after - before, "{interaction_type:?}, private: {private}");
+            previews.push(preview);
+        }
+        // The private share reduced the preview as much as the record.
+        assert!(previews[1] < previews[0]);
+    }
+
+    #[test]
+    fn interactions_are_logged_only_from_the_threshold_up() {
+        let wallet = Keypair::new();
+        let threshold = 50;
+        let accounts = FixtureBuilder::new()
+            .with_registry(|registry| registry.log_threshold_score = threshold)
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let human_record = client::human_record_address(&wallet.pubkey());
+        let session = client::session_address(&human_record, 0);
+        let logged = |index: u32, score: u8| {
+            client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                index,
+                [index as u8 + 1; 32],
+                InteractionType::Chat as u8,
+                score,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            )
+        };
+        let unlogged = |score: u8| {
+            client::build_record_unlogged_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                [9u8; 32],
+                InteractionType::Chat as u8,
+                score,
+                60,
+                false,
+                NOW,
+            )
+        };
+
+        assert_eq!(
+            harness.process(logged(0, threshold - 1), &[&wallet]),
+            Err(custom(YourFunError::InteractionBelowLogThreshold))
+        );
+        assert_eq!(
+            harness.process(unlogged(threshold), &[&wallet]),
+            Err(custom(YourFunError::InteractionRequiresLog))
+        );
+
+        harness.process(logged(0, threshold), &[&wallet]).unwrap();
+        let after_logged: SessionAccount = harness.account(&session);
+        assert!(harness.try_account::<InteractionLog>(&client::interaction_address(&session, 0)).is_some());
+        assert!(after_logged.session_score > 0);
+
+        harness.process(unlogged(threshold - 1), &[&wallet]).unwrap();
+        let after_unlogged: SessionAccount = harness.account(&session);
+        assert!(harness.try_account::<InteractionLog>(&client::interaction_address(&session, 1)).is_none());
+        assert!(after_unlogged.session_score > after_logged.session_score);
+
+        // Both kinds count alike everywhere; only the logged one holds a log.
+        assert_eq!(after_unlogged.interaction_count, 2);
+        assert_eq!(after_unlogged.open_logs, 1);
+        let human: HumanRecord = harness.account(&human_record);
+        assert_eq!(human.total_interactions, 2);
+        let bucket: StatsBucket = harness.account(&client::stats_bucket_address(&session));
+        assert_eq!(bucket.pending_interactions, 2);
+        let day: EpochStats = harness.account(&client::epoch_stats_address(NOW));
+        assert_eq!(day.interactions, 2);
+    }
+
+    #[test]
+    fn interaction_types_outside_the_enum_are_rejected() {
+        let wallet = Keypair::new();
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+        let record = |interaction_type: u8| {
+            client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                0,
+                [1u8; 32],
+                interaction_type,
+                80,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            )
+        };
+
+        for interaction_type in [InteractionType::Review as u8 + 1, u8::MAX] {
+            assert_eq!(
+                harness.process(record(interaction_type), &[&wallet]),
+                Err(custom(YourFunError::InvalidInteractionType)),
+                "type {interaction_type}"
+            );
+        }
+
+        harness.process(record(InteractionType::Review as u8), &[&wallet]).unwrap();
+        let log: InteractionLog = harness.account(&client::interaction_address(&session, 0));
+        assert_eq!(log.interaction_type, 3);
+        assert_eq!(log.kind().unwrap(), InteractionType::Review);
+    }
+
+    #[test]
+    fn interactions_wait_out_the_gap_except_the_first_and_when_disabled() {
+        let (authority, wallet) = (Keypair::new(), Keypair::new());
+        let gap = 30;
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.min_interaction_gap_seconds = gap)
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let record = |harness: &mut Harness, interaction_index, now| {
+            harness.warp_to(now);
+            let ix = client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                interaction_index,
+                [1u8; 32],
+                1,
+                80,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                now,
+            );
+            harness.process(ix, &[&wallet])
+        };
+        let gap = gap as i64;
+
+        // The session's first interaction is exempt, even right after it opened.
+        record(&mut harness, 0, NOW).unwrap();
+        assert_eq!(record(&mut harness, 1, NOW + gap - 1), Err(custom(YourFunError::InteractionTooSoon)));
+        record(&mut harness, 1, NOW + gap).unwrap();
+        assert_eq!(record(&mut harness, 2, NOW + gap), Err(custom(YourFunError::InteractionTooSoon)));
+
+        let disable = client::build_admin_config_ix(
+            &authority.pubkey(),
+            0,
+            crate::instruction::SetMinInteractionGap { gap_seconds: 0 },
+        );
+        harness.process(disable, &[&authority]).unwrap();
+        record(&mut harness, 2, NOW + gap).unwrap();
+        record(&mut harness, 3, NOW + gap).unwrap();
+    }
+
+    #[test]
+    fn interactions_shorter_than_the_configured_minimum_are_rejected() {
+        let (authority, wallet) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+        let record = |harness: &mut Harness, interaction_index, duration_seconds| {
+            let ix = client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                interaction_index,
+                [1u8; 32],
+                1,
+                80,
+                duration_seconds,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            );
+            harness.process(ix, &[&wallet])
+        };
+
+        // The default of 0 accepts zero-second interactions, as before.
+        record(&mut harness, 0, 0).unwrap();
+        let set_minimum = client::build_admin_config_ix(
+            &authority.pubkey(),
+            0,
+            crate::instruction::SetMinInteractionDuration { duration_seconds: 30 },
+        );
+        harness.process(set_minimum, &[&authority]).unwrap();
+
+        let before = harness.account::<SessionAccount>(&session);
+        for duration_seconds in [0, 29] {
+            assert_eq!(
+                record(&mut harness, 1, duration_seconds),
+                Err(custom(YourFunError::InteractionTooShort))
+            );
+        }
+        let rejected = harness.account::<SessionAccount>(&session);
+        assert_eq!(
+            (rejected.interaction_count, rejected.session_score),
+            (before.interaction_count, before.session_score)
+        );
+        record(&mut harness, 1, 30).unwrap();
+        assert_eq!(harness.account::<SessionAccount>(&session).interaction_count, 2);
+    }
+
+    #[test]
+    fn content_references_are_optional_but_complete_and_bounded() {
+        let wallet = Keypair::new();
+        let max_content_size = 1_000;
+        let accounts = FixtureBuilder::new()
+            .with_registry(|registry| registry.max_content_size = max_content_size)
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+        let record = |index: u32, content_uri_hash: [u8; 32], content_size: u32| {
+            client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                index,
+                [index as u8 + 1; 32],
+                InteractionType::Chat as u8,
+                80,
+                60,
+                content_uri_hash,
+                content_size,
+                false,
+                false,
+                NOW,
+            )
+        };
+
+        for (content_uri_hash, content_size) in
+            [([7u8; 32], 0), ([7u8; 32], max_content_size + 1), ([0u8; 32], 1)]
+        {
+            assert_eq!(
+                harness.process(record(0, content_uri_hash, content_size), &[&wallet]),
+                Err(custom(YourFunError::InvalidContentReference)),
+                "size {content_size}"
+            );
+        }
+
+        harness.process(record(0, [0u8; 32], 0), &[&wallet]).unwrap();
+        harness.process(record(1, [7u8; 32], max_content_size), &[&wallet]).unwrap();
+        let omitted: InteractionLog = harness.account(&client::interaction_address(&session, 0));
+        assert_eq!((omitted.content_uri_hash, omitted.content_size), ([0u8; 32], 0));
+        let provided: InteractionLog = harness.account(&client::interaction_address(&session, 1));
+        assert_eq!((provided.content_uri_hash, provided.content_size), ([7u8; 32], max_content_size));
+    }
+
+    #[test]
+    fn companion_cosignatures_bind_the_session_nonce_which_reopening_rotates() {
+        let [authority, wallet, oracle] = [0; 3].map(|_| Keypair::new());
+        let expires_at = NOW + 600;
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.companion_oracle = oracle.pubkey();
+                registry.reopen_grace_period = 120;
+            })
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .with_session(|session| session.expires_at = expires_at)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+        let (content_hash, score, duration_seconds) = ([5u8; 32], 80, 60);
+        let cosign = |signer: &Keypair, nonce: &[u8; 32], index: u32| {
+            let message = crate::crypto::companion_message(
+                &session.to_bytes(),
+                nonce,
+                index,
+                &content_hash,
+                InteractionType::Chat as u8,
+                score,
+                duration_seconds,
+            );
+            ed25519_instruction(signer, &message).0
+        };
+        let record = |index: u32, oracle_cosigned: bool, now: i64| {
+            client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                index,
+                content_hash,
+                InteractionType::Chat as u8,
+                score,
+                duration_seconds,
+                [0u8; 32],
+                0,
+                oracle_cosigned,
+                false,
+                now,
+            )
+        };
+        let nonce = harness.account::<SessionAccount>(&session).session_nonce;
+
+        assert_eq!(
+            harness.process(record(0, false, NOW), &[&wallet]),
+            Err(custom(YourFunError::CompanionCosignatureRequired))
+        );
+        for verify in [cosign(&oracle, &[9u8; 32], 0), cosign(&oracle, &nonce, 1), cosign(&wallet, &nonce, 0)] {
+            assert_eq!(
+                harness.process_all(&[verify, record(0, true, NOW)], &[&wallet]),
+                Err(custom(YourFunError::CompanionCosignatureRequired))
+            );
+        }
+        harness.process_all(&[cosign(&oracle, &nonce, 0), record(0, true, NOW)], &[&wallet]).unwrap();
+
+        harness.warp_to(expires_at);
+        let reopen = client::build_reopen_session_ix(&wallet.pubkey(), 0, 0, &authority.pubkey(), false, 300);
+        harness.process(reopen, &[&wallet]).unwrap();
+        let rotated = harness.account::<SessionAccount>(&session).session_nonce;
+        assert_ne!(rotated, nonce);
+
+        // A co-signature made before the reopen no longer applies.
+        assert_eq!(
+            harness.process_all(&[cosign(&oracle, &nonce, 1), record(1, true, expires_at)], &[&wallet]),
+            Err(custom(YourFunError::CompanionCosignatureRequired))
+        );
+        harness
+            .process_all(&[cosign(&oracle, &rotated, 1), record(1, true, expires_at)], &[&wallet])
+            .unwrap();
+    }
+
+    #[test]
+    fn private_interactions_may_omit_the_hash_and_earn_the_configured_share() {
+        let (authority, wallet) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+        let set_share = |harness: &mut Harness, private_score_bps: u16| {
+            let count = harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count;
+            let ix = client::build_admin_config_ix(
+                &authority.pubkey(),
+                count,
+                crate::instruction::SetPrivateScoreBps { private_score_bps },
+            );
+            harness.process(ix, &[&authority])
+        };
+        let mut interactions = 0;
+        // Returns the session score the interaction added.
+        let mut record = |harness: &mut Harness, content_hash: [u8; 32], private: bool| {
+            let ix = client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                interactions,
+                content_hash,
+                InteractionType::Chat as u8,
+                80,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                private,
+                NOW,
+            );
+            let before = harness.account::<SessionAccount>(&session).session_score;
+            harness.process(ix, &[&wallet])?;
+            interactions += 1;
+            Ok::<_, InstructionError>(harness.account::<SessionAccount>(&session).session_score - before)
+        };
+
+        assert_eq!(set_share(&mut harness, MAX_BPS + 1), Err(custom(YourFunError::InvalidConfigValue)));
+        set_share(&mut harness, 5_000).unwrap();
+
+        // Only a private interaction may leave its content hash out.
+        assert_eq!(record(&mut harness, [0u8; 32], false), Err(custom(YourFunError::InvalidContentHash)));
+        let full = record(&mut harness, [1u8; 32], false).unwrap();
+        let redacted = record(&mut harness, [0u8; 32], true).unwrap();
+        assert_eq!(redacted, full / 2);
+        // A private interaction may still carry a hash, for the same haircut.
+        assert_eq!(record(&mut harness, [2u8; 32], true).unwrap(), full / 2);
+        let log: InteractionLog = harness.account(&client::interaction_address(&session, 1));
+        assert!(log.private);
+        assert_eq!(log.content_hash, [0u8; 32]);
+        assert!(!harness.account::<InteractionLog>(&client::interaction_address(&session, 0)).private);
+
+        set_share(&mut harness, 0).unwrap();
+        assert_eq!(record(&mut harness, [0u8; 32], true).unwrap(), 0);
+
+        // No path amends a log; rescoring from the logs, the nearest thing,
+        // keeps each private log's haircut at the current share.
+        set_share(&mut harness, 5_000).unwrap();
+        let count = harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count;
+        let rescore = client::build_recompute_session_score_ix(&authority.pubkey(), count, &wallet.pubkey(), 0, 4, None);
+        harness.process(rescore, &[&authority]).unwrap();
+        assert_eq!(harness.account::<SessionAccount>(&session).session_score, full + 3 * (full / 2));
+    }
+
+    #[test]
+    fn both_entry_points_validate_and_record_alike() {
+        let (legacy, v2) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_registry(|registry| registry.private_score_bps = 5_000)
+            .with_verified_human(legacy.pubkey())
+            .with_open_session()
+            .with_verified_human(v2.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&legacy.pubkey(), 1_000_000_000);
+        harness.fund(&v2.pubkey(), 1_000_000_000);
+        let session_of = |wallet: &Keypair| client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+        // Content hash, content URI hash and size, and the private flag.
+        type Input = ([u8; 32], Option<([u8; 32], u32)>, Option<bool>);
+        let inputs: [Input; 4] = [
+            ([1u8; 32], None, None),
+            ([2u8; 32], Some(([7u8; 32], 500)), None),
+            ([0u8; 32], None, Some(true)),
+            ([3u8; 32], None, Some(false)),
+        ];
+        let record_legacy = |harness: &mut Harness, index: u32, (hash, uri, private): Input| {
+            let (uri_hash, size) = uri.unwrap_or_default();
+            let ix = client::build_record_interaction_ix(
+                &legacy.pubkey(),
+                0,
+                index,
+                hash,
+                InteractionType::Quiz as u8,
+                70,
+                90,
+                uri_hash,
+                size,
+                false,
+                private.unwrap_or_default(),
+                NOW,
+            );
+            harness.process(ix, &[&legacy])
+        };
+        let record_v2 = |harness: &mut Harness, index: u32, input: InteractionInputV2| {
+            harness.process(client::build_record_interaction_v2_ix(&v2.pubkey(), 0, index, input, false, NOW), &[&v2])
+        };
+        let v2_input = |(hash, uri, private): Input| InteractionInputV2 {
+            content_uri_hash: uri.map(|(uri_hash, _)| uri_hash),
+            content_size: uri.map(|(_, size)| size),
+            private,
+            ..InteractionInputV2::new(hash, InteractionType::Quiz as u8, 70, 90)
+        };
+
+        for version in [0, InteractionInputV2::VERSION + 1] {
+            let input = InteractionInputV2 { version, ..v2_input(inputs[0]) };
+            assert_eq!(record_v2(&mut harness, 0, input), Err(custom(YourFunError::UnsupportedInputVersion)));
+        }
+        // The same checks reject the same inputs through either entry point.
+        let unsized_uri = ([1u8; 32], Some(([7u8; 32], 0)), None);
+        let unhashed = ([0u8; 32], None, None);
+        for (input, error) in [
+            (unsized_uri, YourFunError::InvalidContentReference),
+            (unhashed, YourFunError::InvalidContentHash),
+        ] {
+            assert_eq!(record_legacy(&mut harness, 0, input), Err(custom(error)));
+            assert_eq!(record_v2(&mut harness, 0, v2_input(input)), Err(custom(error)));
+        }
+
+        for (index, input) in inputs.into_iter().enumerate() {
+            record_legacy(&mut harness, index as u32, input).unwrap();
+            record_v2(&mut harness, index as u32, v2_input(input)).unwrap();
+        }
+
+        let (legacy_session, v2_session) = (session_of(&legacy), session_of(&v2));
+        let strip_session = |harness: &mut Harness, address: &Pubkey| {
+            let mut session: SessionAccount = harness.account(address);
+            (session.human_record, session.owner, session.session_nonce, session.bump) = Default::default();
+            session.try_to_vec().unwrap()
+        };
+        assert_eq!(strip_session(&mut harness, &legacy_session), strip_session(&mut harness, &v2_session));
+        for index in 0..inputs.len() as u32 {
+            let strip_log = |harness: &mut Harness, session: &Pubkey| {
+                let mut log: InteractionLog = harness.account(&client::interaction_address(session, index));
+                (log.session, log.user, log.bump) = Default::default();
+                log.try_to_vec().unwrap()
+            };
+            assert_eq!(strip_log(&mut harness, &legacy_session), strip_log(&mut harness, &v2_session), "log {index}");
+        }
+        let totals = |harness: &mut Harness, wallet: &Keypair| {
+            let record: HumanRecord = harness.account(&client::human_record_address(&wallet.pubkey()));
+            (record.total_interactions, record.last_active_at)
+        };
+        assert_eq!(totals(&mut harness, &legacy), totals(&mut harness, &v2));
+    }
+
+    /// Replays `logs` from `head`, returning the final head, or the position
+    /// of the first log whose `prev_chain_head` the replay does not reach.
+    fn replay_chain(mut head: [u8; 32], logs: &[InteractionLog]) -> std::result::Result<[u8; 32], usize> {
+        for (position, log) in logs.iter().enumerate() {
+            if log.prev_chain_head != head {
+                return Err(position);
+            }
+            head = next_chain_head(&head, log.interaction_index, &log.content_hash, log.score, log.timestamp);
+        }
+        Ok(head)
+    }
+
+    #[test]
+    fn the_interaction_chain_rebuilds_off_chain_and_exposes_a_flipped_byte() {
+        let wallet = Keypair::new();
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+
+        for index in 0..15u32 {
+            let now = NOW + 60 * (index as i64 + 1);
+            harness.warp_to(now);
+            let ix = client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                index,
+                [index as u8 + 1; 32],
+                InteractionType::Chat as u8,
+                40 + index as u8,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                now,
+            );
+            harness.process(ix, &[&wallet]).unwrap();
+        }
+        let logs: Vec<InteractionLog> = (0..15)
+            .map(|index| harness.account(&client::interaction_address(&session, index)))
+            .collect();
+        let head = harness.account::<SessionAccount>(&session).chain_head;
 
-    let capped_duration = std::cmp::min(duration_seconds, 300) as u64;
-    let duration_bonus = capped_duration / 30;
+        assert_eq!(replay_chain([0u8; 32], &logs), Ok(head));
+        // Any contiguous slice checks out from its first log's stored head.
+        assert_eq!(replay_chain(logs[6].prev_chain_head, &logs[6..]), Ok(head));
+        assert_eq!(replay_chain(logs[3].prev_chain_head, &logs[3..9]), Ok(logs[9].prev_chain_head));
 
-    let weighted = base_score
-        .saturating_mul(type_multiplier)
-        .saturating_div(100);
+        // A single flipped byte breaks the chain at the next log, and the
+        // final head when it is in the last one.
+        let mut tampered = logs.clone();
+        tampered[7].content_hash[31] ^= 1;
+        assert_eq!(replay_chain([0u8; 32], &tampered), Err(8));
+        let mut tampered = logs.clone();
+        tampered[14].score ^= 1;
+        assert_ne!(replay_chain([0u8; 32], &tampered), Ok(head));
+        // So do a removed or reordered entry.
+        let mut removed = logs.clone();
+        removed.remove(4);
+        assert_eq!(replay_chain([0u8; 32], &removed), Err(4));
+        let mut reordered = logs.clone();
+        reordered.swap(10, 11);
+        assert_eq!(replay_chain([0u8; 32], &reordered), Err(10));
 
-    weighted.saturating_add(duration_bonus)
+        // The final head is published at close.
+        let close = client::build_close_session_ix(&wallet.pubkey(), &wallet.pubkey(), 0, 0, false, None, None);
+        let events = harness.process_with_events::<crate::events::SessionClosed>(close, &[&wallet]).unwrap();
+        assert_eq!(events[0].chain_head, head);
+    }
 }
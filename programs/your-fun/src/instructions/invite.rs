@@ -0,0 +1,264 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{PlatformRegistry, InviteEntry, AdminAction, AdminActionKind};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+
+/// Maximum number of invites created by one `create_invites` call.
+pub const MAX_INVITE_BATCH: usize = 20;
+
+#[derive(Accounts)]
+pub struct CreateInvites<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    /// Pays the rent of every invite.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates an InviteEntry for each wallet. The invite PDAs follow as
+/// writable remaining accounts, in the same order as `wallets`.
+/// Authority only.
+pub fn handler_create_invites<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateInvites<'info>>,
+    wallets: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        !wallets.is_empty() && wallets.len() <= MAX_INVITE_BATCH,
+        YourFunError::InvalidBatchSize
+    );
+    require!(
+        ctx.remaining_accounts.len() == wallets.len(),
+        YourFunError::InvalidBatchSize
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let space = 8 + InviteEntry::INIT_SPACE;
+    let lamports = Rent::get()?.minimum_balance(space);
+
+    for (wallet, info) in wallets.iter().zip(ctx.remaining_accounts.iter()) {
+        require!(info.is_writable, YourFunError::InvalidBatchAccount);
+
+        let (expected, bump) = Pubkey::find_program_address(
+            &[InviteEntry::SEED, wallet.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(info.key(), expected, YourFunError::InvalidBatchAccount);
+
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: info.clone(),
+                },
+                &[&[InviteEntry::SEED, wallet.as_ref(), &[bump]]],
+            ),
+            lamports,
+            space as u64,
+            ctx.program_id,
+        )?;
+
+        let invite = InviteEntry {
+            wallet: *wallet,
+            created_at: now,
+            consumed: false,
+            consumed_at: 0,
+            bump,
+        };
+        invite.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::CreateInvites,
+        ctx.accounts.authority.key(),
+        &wallets,
+    )?;
+
+    msg!("Created {} invites", wallets.len());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeInvite<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [InviteEntry::SEED, invite.wallet.as_ref()],
+        bump = invite.bump,
+        constraint = !invite.consumed @ YourFunError::InviteAlreadyConsumed,
+    )]
+    pub invite: Box<Account<'info, InviteEntry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraws an unused invite, returning its rent to the authority.
+pub fn handler_revoke_invite(ctx: Context<RevokeInvite>) -> Result<()> {
+    let wallet = ctx.accounts.invite.wallet;
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::RevokeInvite,
+        ctx.accounts.authority.key(),
+        &wallet,
+    )?;
+
+    msg!("Invite revoked for wallet: {}", wallet);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    fn admin_action_count(harness: &mut Harness) -> u64 {
+        harness
+            .account::<PlatformRegistry>(&client::registry_address())
+            .admin_action_count
+    }
+
+    fn register(
+        harness: &mut Harness,
+        authority: &Keypair,
+        user: &Keypair,
+        invited: bool,
+    ) -> std::result::Result<(), InstructionError> {
+        let ix = client::build_register_human_ix(
+            &user.pubkey(),
+            &authority.pubkey(),
+            invited,
+            false,
+            None,
+            None,
+            [7u8; 32],
+            user.pubkey().to_bytes().to_vec(),
+        );
+        harness.process(ix, &[user])
+    }
+
+    #[test]
+    fn invites_gate_registration_once_each_until_revoked_or_opened() {
+        let authority = Keypair::new();
+        let [invited, revoked, uninvited] = [0; 3].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.permissioned_registration = true)
+            .with_region_stats(0)
+            .build();
+        let mut harness = Harness::start(accounts);
+        for wallet in [&authority, &invited, &revoked, &uninvited] {
+            harness.fund(&wallet.pubkey(), 10_000_000_000);
+        }
+
+        let create = client::build_create_invites_ix(
+            &authority.pubkey(),
+            admin_action_count(&mut harness),
+            vec![invited.pubkey(), revoked.pubkey()],
+        );
+        harness.process(create, &[&authority]).unwrap();
+
+        assert_eq!(
+            register(&mut harness, &authority, &uninvited, false),
+            Err(custom(YourFunError::InviteRequired))
+        );
+        register(&mut harness, &authority, &invited, true).unwrap();
+        let invite: InviteEntry = harness.account(&client::invite_address(&invited.pubkey()));
+        assert!(invite.consumed);
+        assert_eq!(invite.consumed_at, NOW);
+
+        // A consumed invite can be neither revoked nor used again.
+        let revoke_consumed =
+            client::build_revoke_invite_ix(&authority.pubkey(), admin_action_count(&mut harness), &invited.pubkey());
+        assert_eq!(
+            harness.process(revoke_consumed, &[&authority]),
+            Err(custom(YourFunError::InviteAlreadyConsumed))
+        );
+        let close = client::build_close_human_record_ix(&invited.pubkey(), 0, None, None);
+        harness.process(close, &[&invited]).unwrap();
+        assert_eq!(
+            register(&mut harness, &authority, &invited, true),
+            Err(custom(YourFunError::InviteAlreadyConsumed))
+        );
+
+        let revoke =
+            client::build_revoke_invite_ix(&authority.pubkey(), admin_action_count(&mut harness), &revoked.pubkey());
+        harness.process(revoke, &[&authority]).unwrap();
+        assert!(harness
+            .try_account::<InviteEntry>(&client::invite_address(&revoked.pubkey()))
+            .is_none());
+        assert_eq!(
+            register(&mut harness, &authority, &revoked, false),
+            Err(custom(YourFunError::InviteRequired))
+        );
+
+        // Open registration ignores invites altogether.
+        let open = client::build_admin_config_ix(
+            &authority.pubkey(),
+            admin_action_count(&mut harness),
+            crate::instruction::SetPermissionedRegistration { permissioned: false },
+        );
+        harness.process(open, &[&authority]).unwrap();
+        register(&mut harness, &authority, &uninvited, false).unwrap();
+        register(&mut harness, &authority, &revoked, false).unwrap();
+    }
+}
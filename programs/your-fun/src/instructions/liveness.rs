@@ -0,0 +1,279 @@
+use anchor_lang::prelude::*;
+use crate::state::{HumanRecord, PlatformRegistry, VerificationLevel, VerifierAccount};
+use crate::crypto::compute_challenge_hash;
+use crate::error::YourFunError;
+use crate::events::{LivenessDecayed, LivenessProven};
+
+/// A fresh challenge answered for a liveness proof. The response is computed
+/// over the new nonce exactly as at verification, and the nonce replaces the
+/// one stored on the record.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LivenessChallenge {
+    pub challenge_nonce: [u8; 32],
+    pub challenge_response: [u8; 32],
+}
+
+#[derive(Accounts)]
+pub struct ProveLiveness<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    /// Required with a challenge unless the verifier is the registry authority.
+    #[account(
+        seeds = [VerifierAccount::SEED, verifier_account.verifier.as_ref()],
+        bump = verifier_account.bump,
+    )]
+    pub verifier_account: Option<Box<Account<'info, VerifierAccount>>>,
+
+    /// The authority or an elected verifier vouching for the challenge.
+    pub verifier: Option<Signer<'info>>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Refreshes the owner's liveness, restarting the decay interval. A level
+/// already lost to decay is not given back: the record stays flagged as
+/// lapsed, so that re-verification may restore it.
+///
+/// When the registry requires it, a level-3 human must answer a fresh
+/// challenge co-signed by the authority or an elected verifier; the nonce
+/// must not be the one already stored on the record.
+pub fn handler_prove_liveness(
+    ctx: Context<ProveLiveness>,
+    challenge: Option<LivenessChallenge>,
+) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    require!(
+        human_record.is_active() && !human_record.is_merged(),
+        YourFunError::HumanNotVerified
+    );
+
+    let challenge_required = registry.liveness_challenge_required
+        && human_record.level()? == Some(VerificationLevel::Full);
+    match challenge {
+        Some(challenge) => {
+            let verifier = ctx
+                .accounts
+                .verifier
+                .as_ref()
+                .ok_or(YourFunError::UnauthorizedVerifier)?
+                .key();
+            require!(
+                verifier == registry.authority
+                    || ctx
+                        .accounts
+                        .verifier_account
+                        .as_ref()
+                        .is_some_and(|v| v.verifier == verifier && v.is_active),
+                YourFunError::UnauthorizedVerifier
+            );
+            require!(
+                challenge.challenge_nonce.iter().any(|&b| b != 0),
+                YourFunError::ZeroChallengeNonce
+            );
+            require!(
+                challenge.challenge_nonce != human_record.challenge_nonce,
+                YourFunError::StaleLivenessChallenge
+            );
+            let expected_response =
+                compute_challenge_hash(&challenge.challenge_nonce, &human_record.fingerprint_hash);
+            require!(
+                challenge.challenge_response == expected_response,
+                YourFunError::ChallengeMismatch
+            );
+            human_record.challenge_nonce = challenge.challenge_nonce;
+        }
+        None => require!(!challenge_required, YourFunError::LivenessChallengeRequired),
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    human_record.last_liveness_at = now;
+    human_record.liveness_decayed_at = 0;
+
+    emit!(LivenessProven {
+        wallet: human_record.wallet,
+        verification_level: human_record.verification_level,
+        proven_at: now,
+    });
+
+    msg!("Liveness proven for wallet: {}", human_record.wallet);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApplyLivenessDecay<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+}
+
+/// Lowers the verification level of a record that has not proven liveness
+/// within the registry's interval by one step, never below Basic, and flags
+/// it as lapsed. Activity and counts are untouched. Each decay restarts the
+/// interval, so a silent record loses one level per interval. Permissionless.
+pub fn handler_apply_liveness_decay(ctx: Context<ApplyLivenessDecay>) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        human_record.liveness_decay_due(registry.liveness_interval_seconds, now),
+        YourFunError::LivenessNotDue
+    );
+
+    let previous_level = human_record.verification_level;
+    human_record.verification_level = previous_level
+        .saturating_sub(1)
+        .max(VerificationLevel::Basic as u8);
+    human_record.liveness_decayed_at = now;
+    human_record.set_liveness_lapsed(true);
+
+    emit!(LivenessDecayed {
+        wallet: human_record.wallet,
+        previous_level,
+        verification_level: human_record.verification_level,
+        decayed_at: now,
+    });
+
+    msg!(
+        "Verification level of {} decayed from {} to {}",
+        human_record.wallet,
+        previous_level,
+        human_record.verification_level
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetVerificationLevel<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+}
+
+/// Returns the human's verification level as return data, with any liveness
+/// decay that is due already applied, or 0 when the record is not currently
+/// verified or still in its contest window. Meant for CPI.
+pub fn handler_verification_level(ctx: Context<GetVerificationLevel>) -> Result<u8> {
+    let human_record = &ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+    if !human_record.is_effective(now) {
+        return Ok(0);
+    }
+    Ok(human_record.decayed_level(ctx.accounts.registry.liveness_interval_seconds, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    const INTERVAL: i64 = 1_000;
+
+    fn level(harness: &mut Harness, wallet: &Pubkey) -> u8 {
+        harness.simulate(client::build_verification_level_ix(wallet), &[]).unwrap()[0]
+    }
+
+    #[test]
+    fn silent_records_decay_a_level_per_interval_down_to_basic_until_reverified() {
+        let (authority, wallet) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.liveness_interval_seconds = INTERVAL)
+            .with_region_stats(0)
+            .with_verified_human(wallet.pubkey())
+            .with_human(|record| record.verification_level = VerificationLevel::Enhanced as u8)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let address = client::human_record_address(&wallet.pubkey());
+        let decay = |harness: &mut Harness| harness.process(client::build_apply_liveness_decay_ix(&wallet.pubkey()), &[]);
+        let record = |harness: &mut Harness| harness.account::<HumanRecord>(&address);
+
+        harness.warp_to(NOW + INTERVAL - 1);
+        assert_eq!(decay(&mut harness), Err(custom(YourFunError::LivenessNotDue)));
+        assert_eq!(level(&mut harness, &wallet.pubkey()), VerificationLevel::Enhanced as u8);
+
+        // The view reflects a due decay before the crank applies it.
+        harness.warp_to(NOW + INTERVAL);
+        assert_eq!(level(&mut harness, &wallet.pubkey()), VerificationLevel::Basic as u8);
+        decay(&mut harness).unwrap();
+        let decayed = record(&mut harness);
+        assert_eq!(decayed.verification_level, VerificationLevel::Basic as u8);
+        assert!(decayed.is_liveness_lapsed() && decayed.is_active());
+        assert_eq!(decayed.liveness_decayed_at, NOW + INTERVAL);
+        // Each decay restarts the interval.
+        assert_eq!(decay(&mut harness), Err(custom(YourFunError::LivenessNotDue)));
+
+        // Basic is the floor, however long the record stays silent.
+        harness.warp_to(NOW + 5 * INTERVAL);
+        assert_eq!(level(&mut harness, &wallet.pubkey()), VerificationLevel::Basic as u8);
+        decay(&mut harness).unwrap();
+        assert_eq!(record(&mut harness).verification_level, VerificationLevel::Basic as u8);
+
+        // A liveness proof stops the decay but does not give the level back.
+        harness.process(client::build_prove_liveness_ix(&wallet.pubkey(), None, None), &[&wallet]).unwrap();
+        let proven = record(&mut harness);
+        assert_eq!((proven.last_liveness_at, proven.liveness_decayed_at), (NOW + 5 * INTERVAL, 0));
+        assert_eq!(proven.verification_level, VerificationLevel::Basic as u8);
+        assert!(proven.is_liveness_lapsed());
+        assert_eq!(decay(&mut harness), Err(custom(YourFunError::LivenessNotDue)));
+
+        // Re-verifying restores it, and clears the lapse.
+        let reverify = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            &wallet.pubkey(),
+            None,
+            &hash(&wallet.pubkey().to_bytes()).to_bytes(),
+            registered_challenge_response(&wallet.pubkey()),
+            100,
+            VerificationLevel::Enhanced as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            NOW + 5 * INTERVAL,
+        );
+        harness.process(reverify.clone(), &[&authority]).unwrap();
+        let restored = record(&mut harness);
+        assert_eq!(restored.verification_level, VerificationLevel::Enhanced as u8);
+        assert!(!restored.is_liveness_lapsed());
+        assert_eq!(level(&mut harness, &wallet.pubkey()), VerificationLevel::Enhanced as u8);
+        // Once restored, the record is simply verified again.
+        assert_eq!(harness.process(reverify, &[&authority]), Err(custom(YourFunError::AlreadyVerified)));
+    }
+}
@@ -0,0 +1,319 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{
+    PlatformRegistry, AdminAction, AdminActionKind, MatchingCampaign, ScholarshipPool,
+};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+use crate::constants::MAX_BPS;
+use crate::events::{FeeMatched, MatchingEscrowReclaimed};
+
+/// Lamports of `info` above its rent-exempt minimum.
+fn spendable(info: &AccountInfo) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(info.data_len());
+    Ok(info.lamports().saturating_sub(rent_exempt))
+}
+
+/// Moves the campaign's match for a verification fee of `fee` from its
+/// escrow into the scholarship pool, returning the lamports matched. Nothing
+/// is matched once the campaign has ended, hit its cap or run dry; a
+/// partially funded escrow matches what it holds.
+pub(crate) fn match_fee(
+    campaign: &mut Account<MatchingCampaign>,
+    pool: &mut Account<ScholarshipPool>,
+    wallet: Pubkey,
+    fee: u64,
+    now: i64,
+) -> Result<u64> {
+    if !campaign.is_active_at(now) {
+        return Ok(0);
+    }
+    let campaign_info = campaign.to_account_info();
+    let amount = campaign.match_for(fee).min(spendable(&campaign_info)?);
+    if amount == 0 {
+        return Ok(0);
+    }
+
+    let pool_info = pool.to_account_info();
+    **campaign_info.try_borrow_mut_lamports()? -= amount;
+    **pool_info.try_borrow_mut_lamports()? = pool_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    campaign.total_matched = campaign
+        .total_matched
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    pool.total_donated = pool
+        .total_donated
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(FeeMatched {
+        matcher: campaign.matcher,
+        wallet,
+        amount,
+        total_matched: campaign.total_matched,
+    });
+    Ok(amount)
+}
+
+#[derive(Accounts)]
+#[instruction(matcher: Pubkey)]
+pub struct ConfigureMatching<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + MatchingCampaign::INIT_SPACE,
+        seeds = [MatchingCampaign::SEED, matcher.as_ref()],
+        bump,
+    )]
+    pub campaign: Box<Account<'info, MatchingCampaign>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates or updates `matcher`'s campaign, matching `ratio_bps` of each
+/// user-paid verification fee into the scholarship pool until `ends_at` or
+/// until `cap_lamports` have been matched in total. Authority only.
+pub fn handler_configure_matching(
+    ctx: Context<ConfigureMatching>,
+    matcher: Pubkey,
+    ratio_bps: u16,
+    cap_lamports: u64,
+    ends_at: i64,
+) -> Result<()> {
+    require!(ratio_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+    require!(ends_at > 0, YourFunError::InvalidConfigValue);
+
+    let campaign = &mut ctx.accounts.campaign;
+    if campaign.bump == 0 {
+        campaign.matcher = matcher;
+        campaign.bump = ctx.bumps.campaign;
+    }
+    campaign.ratio_bps = ratio_bps;
+    campaign.cap_lamports = cap_lamports;
+    campaign.ends_at = ends_at;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::ConfigureMatching,
+        ctx.accounts.authority.key(),
+        &(matcher, ratio_bps, cap_lamports, ends_at),
+    )?;
+
+    msg!(
+        "Matching for {} set to {} bps up to {} lamports until {}",
+        matcher,
+        ratio_bps,
+        cap_lamports,
+        ends_at
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundMatchingEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [MatchingCampaign::SEED, matcher.key().as_ref()],
+        bump = campaign.bump,
+        has_one = matcher @ YourFunError::Unauthorized,
+    )]
+    pub campaign: Box<Account<'info, MatchingCampaign>>,
+
+    #[account(mut)]
+    pub matcher: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposits `amount` lamports into the matcher's campaign escrow.
+pub fn handler_fund_matching_escrow(ctx: Context<FundMatchingEscrow>, amount: u64) -> Result<()> {
+    require!(amount > 0, YourFunError::ZeroAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.matcher.to_account_info(),
+                to: ctx.accounts.campaign.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.total_funded = campaign
+        .total_funded
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    msg!("Matching escrow funded with {} lamports", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReclaimMatchingEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [MatchingCampaign::SEED, matcher.key().as_ref()],
+        bump = campaign.bump,
+        has_one = matcher @ YourFunError::Unauthorized,
+    )]
+    pub campaign: Box<Account<'info, MatchingCampaign>>,
+
+    #[account(mut)]
+    pub matcher: Signer<'info>,
+}
+
+/// Returns everything left in the escrow to the matcher once the campaign
+/// has ended. The campaign account stays, so it can be reconfigured.
+pub fn handler_reclaim_matching_escrow(ctx: Context<ReclaimMatchingEscrow>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !ctx.accounts.campaign.is_active_at(now),
+        YourFunError::MatchingCampaignActive
+    );
+
+    let campaign_info = ctx.accounts.campaign.to_account_info();
+    let amount = spendable(&campaign_info)?;
+    require!(amount > 0, YourFunError::InsufficientFunds);
+
+    let matcher_info = ctx.accounts.matcher.to_account_info();
+    **campaign_info.try_borrow_mut_lamports()? -= amount;
+    **matcher_info.try_borrow_mut_lamports()? = matcher_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(MatchingEscrowReclaimed {
+        matcher: matcher_info.key(),
+        amount,
+    });
+
+    msg!("{} lamports of matching escrow reclaimed", amount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    const FEE: u64 = 1_000_000;
+    const ENDS_AT: i64 = NOW + 86_400;
+
+    #[test]
+    fn fees_are_matched_up_to_the_cap_and_the_leftover_is_reclaimed_after_the_campaign() {
+        let [authority, matcher, stranger] = [0; 3].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.verification_fee_lamports = FEE)
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &matcher, &stranger] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let (campaign, pool) = (client::matching_campaign_address(&matcher.pubkey()), client::scholarship_pool_address());
+        let users: Vec<_> = (0..4).map(|_| harness.wallet()).collect();
+        let register = |harness: &mut Harness, i: usize| {
+            let ix = client::build_register_human_ix(
+                &users[i].pubkey(),
+                &authority.pubkey(),
+                false,
+                false,
+                Some(&matcher.pubkey()),
+                None,
+                [7u8; 32],
+                vec![i as u8 + 1; 64],
+            );
+            harness.process(ix, &[&users[i]])
+        };
+        let configure = |signer: &Keypair, ratio_bps: u16| {
+            client::build_configure_matching_ix(&signer.pubkey(), 0, matcher.pubkey(), ratio_bps, FEE * 3 / 4, ENDS_AT)
+        };
+
+        assert_eq!(
+            harness.process(configure(&stranger, 5_000), &[&stranger]),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        assert_eq!(
+            harness.process(configure(&authority, MAX_BPS + 1), &[&authority]),
+            Err(custom(YourFunError::InvalidConfigValue))
+        );
+        // Half of each fee, up to three quarters of a fee in total.
+        harness.process(configure(&authority, 5_000), &[&authority]).unwrap();
+        harness.process(client::build_fund_matching_escrow_ix(&matcher.pubkey(), 10 * FEE), &[&matcher]).unwrap();
+        assert_eq!(harness.account::<MatchingCampaign>(&campaign).total_funded, 10 * FEE);
+        harness.process(client::build_fund_scholarship_pool_ix(&stranger.pubkey(), FEE), &[&stranger]).unwrap();
+        let pool_before = harness.balance(&pool);
+        let escrow_before = harness.balance(&campaign);
+
+        register(&mut harness, 0).unwrap();
+        assert_eq!(harness.balance(&pool), pool_before + FEE / 2);
+        // The second registration reaches the cap and gets what is left of it.
+        register(&mut harness, 1).unwrap();
+        assert_eq!(harness.balance(&pool), pool_before + FEE * 3 / 4);
+        register(&mut harness, 2).unwrap();
+        assert_eq!(harness.balance(&pool), pool_before + FEE * 3 / 4);
+        assert_eq!(harness.balance(&campaign), escrow_before - FEE * 3 / 4);
+        assert_eq!(harness.account::<MatchingCampaign>(&campaign).total_matched, FEE * 3 / 4);
+        assert_eq!(harness.account::<ScholarshipPool>(&pool).total_donated, FEE + FEE * 3 / 4);
+
+        assert_eq!(
+            harness.process(client::build_reclaim_matching_escrow_ix(&matcher.pubkey()), &[&matcher]),
+            Err(custom(YourFunError::MatchingCampaignActive))
+        );
+        // Raising the cap matches again, but only until the campaign ends.
+        let raise = client::build_configure_matching_ix(&authority.pubkey(), 1, matcher.pubkey(), 5_000, 10 * FEE, ENDS_AT);
+        harness.process(raise, &[&authority]).unwrap();
+        harness.warp_to(ENDS_AT);
+        register(&mut harness, 3).unwrap();
+        assert_eq!(harness.balance(&pool), pool_before + FEE * 3 / 4);
+        let matcher_before = harness.balance(&matcher.pubkey());
+        harness.process(client::build_reclaim_matching_escrow_ix(&matcher.pubkey()), &[&matcher]).unwrap();
+        assert_eq!(harness.balance(&matcher.pubkey()), matcher_before + 10 * FEE - FEE * 3 / 4);
+        assert_eq!(harness.account::<MatchingCampaign>(&campaign).total_matched, FEE * 3 / 4);
+        assert_eq!(
+            harness.process(client::build_reclaim_matching_escrow_ix(&matcher.pubkey()), &[&matcher]),
+            Err(custom(YourFunError::InsufficientFunds))
+        );
+        // The campaign account stays for reconfiguring, holding its rent.
+        assert!(harness.balance(&campaign) > 0);
+    }
+}
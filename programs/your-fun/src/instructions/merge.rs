@@ -0,0 +1,293 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, RegionStats, AdminAction, AdminActionKind};
+use crate::instructions::admin::record_admin_action;
+use crate::instructions::verify_human::refresh_verification_status;
+use crate::error::YourFunError;
+use crate::events::HumanRecordsMerged;
+
+#[derive(Accounts)]
+pub struct MergeHumanRecords<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    /// The record that is kept.
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, primary_wallet.key().as_ref()],
+        bump = primary.bump,
+        constraint = primary.is_active() @ YourFunError::HumanNotVerified,
+    )]
+    pub primary: Box<Account<'info, HumanRecord>>,
+
+    /// The record folded into `primary`.
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, duplicate_wallet.key().as_ref()],
+        bump = duplicate.bump,
+        constraint = duplicate.key() != primary.key() @ YourFunError::InvalidConfigValue,
+        constraint = !duplicate.is_merged() @ YourFunError::RecordMerged,
+        constraint = duplicate.active_session_count == 0 @ YourFunError::OpenSessionsRemain,
+    )]
+    pub duplicate: Box<Account<'info, HumanRecord>>,
+
+    /// Counters of the region recorded on the duplicate.
+    #[account(
+        mut,
+        seeds = [RegionStats::SEED, &duplicate.region_code.to_le_bytes()],
+        bump = duplicate_region_stats.bump,
+    )]
+    pub duplicate_region_stats: Box<Account<'info, RegionStats>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    pub primary_wallet: Signer<'info>,
+
+    pub duplicate_wallet: Signer<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Folds a duplicate HumanRecord of the same person into the primary one.
+///
+/// Requires the authority and both wallets. The duplicate's learning score
+/// and activity counters are added to the primary and its strikes carried
+/// over; the duplicate is deactivated, removed from the verified counts and
+/// left pointing at the primary, so it can neither open sessions nor be
+/// verified again. Its `session_count` is kept, as its session addresses
+/// remain in use. All of its sessions must be closed first.
+pub fn handler_merge_human_records(ctx: Context<MergeHumanRecords>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let primary = &mut ctx.accounts.primary;
+    let duplicate = &mut ctx.accounts.duplicate;
+    let now = Clock::get()?.unix_timestamp;
+
+    primary.learning_score = primary
+        .learning_score
+        .checked_add(duplicate.learning_score)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    primary.session_count = primary
+        .session_count
+        .checked_add(duplicate.session_count)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    primary.total_interactions = primary
+        .total_interactions
+        .checked_add(duplicate.total_interactions)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    primary.force_closed_sessions = primary
+        .force_closed_sessions
+        .checked_add(duplicate.force_closed_sessions)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    primary.strikes = primary.strikes.saturating_add(duplicate.strikes);
+    primary.last_active_at = primary.last_active_at.max(duplicate.last_active_at);
+
+    if duplicate.is_counted_as_verified() {
+        registry.total_verified_humans = registry
+            .total_verified_humans
+            .checked_sub(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+    registry.uncount_active_human(duplicate);
+    if duplicate.is_region_counted() {
+        ctx.accounts.duplicate_region_stats.remove_verified()?;
+        duplicate.set_region_counted(false);
+    }
+
+    let learning_score_moved = duplicate.learning_score;
+    duplicate.learning_score = 0;
+    duplicate.total_interactions = 0;
+    duplicate.set_active(false);
+    duplicate.merged_into = primary.key();
+    refresh_verification_status(duplicate, registry, now);
+
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::MergeHumanRecords,
+        ctx.accounts.authority.key(),
+        &(primary.key(), duplicate.key()),
+    )?;
+
+    emit!(HumanRecordsMerged {
+        primary: primary.key(),
+        duplicate: duplicate.key(),
+        learning_score_moved,
+        merged_at: now,
+    });
+
+    msg!(
+        "Merged human record of {} into that of {}",
+        duplicate.wallet,
+        primary.wallet
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::error::ErrorCode;
+    use anchor_lang::solana_program::hash::hash;
+    use anchor_lang::solana_program::instruction::Instruction;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::VerificationLevel;
+
+    fn registry(harness: &mut Harness) -> PlatformRegistry {
+        harness.account(&client::registry_address())
+    }
+
+    #[test]
+    fn merging_sums_the_counters_and_blocks_the_duplicate() {
+        let authority = Keypair::new();
+        let [primary, duplicate] = [0; 2].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_verified_human(primary.pubkey())
+            .with_verified_human(duplicate.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &primary, &duplicate] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let (primary_record, duplicate_record) = (
+            client::human_record_address(&primary.pubkey()),
+            client::human_record_address(&duplicate.pubkey()),
+        );
+        for (address, learning_score, total_interactions, strikes) in
+            [(primary_record, 500, 40, 1), (duplicate_record, 300, 25, 2)]
+        {
+            let mut record: HumanRecord = harness.account(&address);
+            record.learning_score = learning_score;
+            record.total_interactions = total_interactions;
+            record.strikes = strikes;
+            harness.set_account(FixtureAccount::new(address, &record));
+        }
+        let merge = |harness: &mut Harness| {
+            client::build_merge_human_records_ix(
+                &authority.pubkey(),
+                registry(harness).admin_action_count,
+                &primary.pubkey(),
+                &duplicate.pubkey(),
+                0,
+            )
+        };
+        let unsigned = |mut ix: Instruction, signer: &Keypair| {
+            for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == signer.pubkey()) {
+                meta.is_signer = false;
+            }
+            ix
+        };
+
+        for (missing, present) in [
+            (&primary, [&duplicate, &authority]),
+            (&duplicate, [&primary, &authority]),
+            (&authority, [&primary, &duplicate]),
+        ] {
+            let ix = unsigned(merge(&mut harness), missing);
+            assert_eq!(
+                harness.process(ix, &present),
+                Err(custom(ErrorCode::AccountNotSigner)),
+                "without {}",
+                missing.pubkey()
+            );
+        }
+        let ix = merge(&mut harness);
+        assert_eq!(
+            harness.process(ix, &[&primary, &duplicate, &authority]),
+            Err(custom(YourFunError::OpenSessionsRemain))
+        );
+
+        let close = client::build_close_session_ix(&duplicate.pubkey(), &duplicate.pubkey(), 0, 0, false, None, None);
+        harness.process(close, &[&duplicate]).unwrap();
+        let ix = merge(&mut harness);
+        let events = harness
+            .process_with_events::<crate::events::HumanRecordsMerged>(ix, &[&primary, &duplicate, &authority])
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            (events[0].primary, events[0].duplicate, events[0].learning_score_moved),
+            (primary_record, duplicate_record, 300)
+        );
+
+        let merged: HumanRecord = harness.account(&primary_record);
+        assert_eq!((merged.learning_score, merged.total_interactions, merged.strikes), (800, 65, 3));
+        assert_eq!(merged.session_count, 1);
+        let folded: HumanRecord = harness.account(&duplicate_record);
+        assert!(!folded.is_active());
+        assert!(folded.is_merged());
+        assert_eq!(folded.merged_into, primary_record);
+        assert_eq!((folded.learning_score, folded.total_interactions), (0, 0));
+        assert_eq!(registry(&mut harness).total_verified_humans, 1);
+
+        // The duplicate can neither be merged again, open sessions nor be
+        // verified again.
+        let ix = merge(&mut harness);
+        assert_eq!(
+            harness.process(ix, &[&primary, &duplicate, &authority]),
+            Err(custom(YourFunError::RecordMerged))
+        );
+        let session = client::build_create_session_ix(
+            &duplicate.pubkey(),
+            None,
+            1,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        );
+        assert_eq!(
+            harness.process(session, &[&duplicate]),
+            Err(custom(YourFunError::HumanRecordRevoked))
+        );
+        let verify = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            &duplicate.pubkey(),
+            None,
+            &hash(&duplicate.pubkey().to_bytes()).to_bytes(),
+            registered_challenge_response(&duplicate.pubkey()),
+            100,
+            VerificationLevel::Basic as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            NOW,
+        );
+        assert_eq!(
+            harness.process(verify, &[&authority]),
+            Err(custom(YourFunError::RecordMerged))
+        );
+    }
+}
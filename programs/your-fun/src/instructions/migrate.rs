@@ -0,0 +1,1014 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::system_program;
+use crate::state::{
+    GoalType, HumanRecord, PersonalityConfig, PersonalityStats, PlatformRegistry, ScoringProfile,
+    SessionAccount, StatsBucket, TopicScore, VerificationStatus,
+};
+use crate::error::YourFunError;
+use crate::scoring::SUB_SCORE_COUNT;
+
+/// HumanRecord as laid out before `is_active` was packed into `flags`.
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize))]
+struct LegacyHumanRecord {
+    wallet: Pubkey,
+    verified_by: Pubkey,
+    verified_at: i64,
+    verification_level: u8,
+    fingerprint_hash: [u8; 32],
+    is_active: bool,
+    session_count: u64,
+    total_interactions: u64,
+    last_active_at: i64,
+    learning_score: u64,
+    challenge_nonce: [u8; 32],
+    bump: u8,
+    registered_at: i64,
+    strikes: u8,
+    revoked_at: i64,
+    force_closed_sessions: u32,
+}
+
+/// SessionAccount as laid out before `is_active` was packed into `flags`.
+#[derive(AnchorDeserialize)]
+#[cfg_attr(test, derive(AnchorSerialize))]
+struct LegacySessionAccount {
+    human_record: Pubkey,
+    owner: Pubkey,
+    session_index: u64,
+    created_at: i64,
+    last_interaction_at: i64,
+    expires_at: i64,
+    is_active: bool,
+    interaction_count: u32,
+    personality_id: u8,
+    current_topic: [u8; 32],
+    session_score: u64,
+    bump: u8,
+    closed_at: i64,
+    close_reason: u8,
+    open_logs: u32,
+}
+
+#[derive(Accounts)]
+pub struct MigrateHumanRecord<'info> {
+    /// CHECK: May still be in an older layout, so it cannot be typed;
+    /// ownership, discriminator and PDA derivation are checked in the handler.
+    #[account(mut)]
+    pub human_record: UncheckedAccount<'info>,
+
+    /// The record's wallet, which receives any rent freed by the migration.
+    /// CHECK: Compared against the wallet stored in the record.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// Covers the extra rent when the migration grows the record.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrades a HumanRecord of any older layout to `HumanRecord::CURRENT_VERSION`.
+///
+/// Permissionless and idempotent per record: a record already at the current
+/// version is rejected. Records in the pre-flags layout are rewritten field by
+/// field; later layouts are a byte prefix of the current one, so the appended
+/// fields are read as zeros and then given their defaults. The payer funds any
+/// growth, and rent freed by shrinking always goes back to the record's wallet.
+/// A verifier's share of the fee held on the record stays there.
+pub fn handler_migrate_human_record(ctx: Context<MigrateHumanRecord>) -> Result<()> {
+    let info = ctx.accounts.human_record.to_account_info();
+    check_program_account(&info, &HumanRecord::DISCRIMINATOR)?;
+
+    let mut record = decode_human_record(&info)?;
+    require!(
+        record.record_version < HumanRecord::CURRENT_VERSION,
+        YourFunError::AccountAlreadyMigrated
+    );
+
+    let expected = Pubkey::create_program_address(
+        &[HumanRecord::SEED, record.wallet.as_ref(), &[record.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(YourFunError::InvalidLegacyAccount))?;
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidLegacyAccount);
+    require_keys_eq!(
+        ctx.accounts.wallet.key(),
+        record.wallet,
+        YourFunError::Unauthorized
+    );
+
+    // Version 0 -> 1: `behavioral_score` was never stored before, so it stays
+    // zero until the next verification.
+    // Version 1 -> 2: the region is unknown (0) and, with
+    // `FLAG_REGION_COUNTED` clear, the record is in no region's count.
+    // Version 2 -> 3: active records start out as Active so the migration
+    // itself is never announced as a status transition.
+    // Version 3 -> 4: no document issuer is recorded, even for Full
+    // records verified before attestations were required.
+    // Version 4 -> 5: no attribute commitments, read as unused slots.
+    // Version 5 -> 6: the behavioral score was the only score, so it is both
+    // the behavioral sub-score and the composite.
+    if record.record_version < 6 {
+        record.sub_scores = [record.behavioral_score, 0, 0];
+        record.composite_score = record.behavioral_score;
+    }
+    // Version 6 -> 7: existing verifications are in effect, read as zero.
+    // Version 7 -> 8: the record was never merged, read as the default key.
+    // Version 8 -> 9: the record is unsupervised, read as the default key.
+    // Version 9 -> 10: no interaction delegate, read as the default key.
+    // Version 10 -> 11: no raffle entered yet, read as 0.
+    // Version 11 -> 12: never audited, read as 0.
+    // Version 12 -> 13: no spending cap and nothing spent, read as zeros.
+    // Version 13 -> 14: the liveness interval counts from the verification.
+    if record.record_version < 14 {
+        record.last_liveness_at = record.verified_at;
+    }
+    // Version 14 -> 15: no handle claimed, read as zeros.
+    // Version 15 -> 16: never frozen, read as 0.
+    // Version 16 -> 17: no verifier fee held, read as 0.
+    if record.record_version < 3 && record.is_active() {
+        record.verification_status = VerificationStatus::Active as u8;
+    }
+    record.record_version = HumanRecord::CURRENT_VERSION;
+
+    let new_len = 8 + HumanRecord::INIT_SPACE;
+    fund_rent_shortfall(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        new_len,
+        record.verifier_fee_escrow,
+    )?;
+    write_migrated(
+        &info,
+        &ctx.accounts.wallet.to_account_info(),
+        new_len,
+        record.verifier_fee_escrow,
+        &record,
+    )?;
+
+    msg!(
+        "Human record migrated to version {} for wallet: {}",
+        record.record_version,
+        record.wallet
+    );
+    Ok(())
+}
+
+/// Decodes a HumanRecord in any layout this program has used. Fields the
+/// layout predates read as zeros, with `record_version` telling them apart.
+pub(crate) fn decode_human_record(info: &AccountInfo) -> Result<HumanRecord> {
+    if info.data_len() == HumanRecord::LEGACY_BOOL_LAYOUT_LEN {
+        Ok(from_legacy_human_record(read_legacy(info)?))
+    } else {
+        read_padded::<HumanRecord>(info, 8 + HumanRecord::INIT_SPACE)
+    }
+}
+
+/// Carries a pre-flags record into the current layout, leaving every field
+/// added since at its zero value and the version at 0.
+fn from_legacy_human_record(legacy: LegacyHumanRecord) -> HumanRecord {
+    let mut record = HumanRecord {
+        wallet: legacy.wallet,
+        verified_by: legacy.verified_by,
+        verified_at: legacy.verified_at,
+        verification_level: legacy.verification_level,
+        fingerprint_hash: legacy.fingerprint_hash,
+        flags: 0,
+        session_count: legacy.session_count,
+        total_interactions: legacy.total_interactions,
+        last_active_at: legacy.last_active_at,
+        learning_score: legacy.learning_score,
+        challenge_nonce: legacy.challenge_nonce,
+        bump: legacy.bump,
+        registered_at: legacy.registered_at,
+        strikes: legacy.strikes,
+        revoked_at: legacy.revoked_at,
+        force_closed_sessions: legacy.force_closed_sessions,
+        active_session_count: 0,
+        _reserved: [0u8; 3],
+        record_version: 0,
+        behavioral_score: 0,
+        region_code: 0,
+        verification_status: 0,
+        document_issuer: Pubkey::default(),
+        attribute_commitments: [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+        sub_scores: [0u8; SUB_SCORE_COUNT],
+        composite_score: 0,
+        effective_at: 0,
+        merged_into: Pubkey::default(),
+        supervisor: Pubkey::default(),
+        interaction_delegate: Pubkey::default(),
+        last_raffle_epoch: 0,
+        last_audited_at: 0,
+        spending_cap_lamports: 0,
+        spend_window_start: 0,
+        spent_in_window: 0,
+        last_liveness_at: 0,
+        liveness_decayed_at: 0,
+        handle_hash: [0u8; 32],
+        frozen_until: 0,
+        verifier_fee_escrow: 0,
+    };
+    record.set_active(legacy.is_active);
+    record
+}
+
+#[derive(Accounts)]
+pub struct MigrateSession<'info> {
+    /// CHECK: Still in an older layout, so it cannot be typed; ownership,
+    /// discriminator and PDA derivation are checked in the handler.
+    #[account(mut)]
+    pub session: UncheckedAccount<'info>,
+
+    /// The session owner, who receives any rent freed by the new layout.
+    /// CHECK: Compared against the owner stored in the session.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Tops up the rent when the new layout is larger.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrades a SessionAccount of any older layout to
+/// `SessionAccount::CURRENT_VERSION`.
+///
+/// Permissionless: anyone may migrate any session, and any rent released by
+/// the new layout always goes back to the session owner. An unsettled
+/// escrow stays on the session.
+pub fn handler_migrate_session(ctx: Context<MigrateSession>) -> Result<()> {
+    let info = ctx.accounts.session.to_account_info();
+    check_program_account(&info, &SessionAccount::DISCRIMINATOR)?;
+    let mut session = if info.data_len() == SessionAccount::LEGACY_BOOL_LAYOUT_LEN {
+        from_legacy_session(read_legacy(&info)?)
+    } else {
+        read_padded::<SessionAccount>(&info, 8 + SessionAccount::INIT_SPACE)?
+    };
+    require!(
+        session.layout_version < SessionAccount::CURRENT_VERSION,
+        YourFunError::AccountAlreadyMigrated
+    );
+
+    let expected = Pubkey::create_program_address(
+        &[
+            SessionAccount::SEED,
+            session.human_record.as_ref(),
+            &session.session_index.to_le_bytes(),
+            &[session.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(YourFunError::InvalidLegacyAccount))?;
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidLegacyAccount);
+    require_keys_eq!(
+        ctx.accounts.owner.key(),
+        session.owner,
+        YourFunError::Unauthorized
+    );
+
+    // Version 0 -> 1: sessions predating templates used no template and the
+    // standard scoring, read as zeros.
+    // Version 1 -> 2: sessions predating goals have none, read as zeros.
+    // Version 2 -> 3: sessions were never reopened, read as 0.
+    // Version 3 -> 4: the whole score so far belongs to the current topic.
+    if session.layout_version < 4 {
+        session.topics[0] = TopicScore {
+            topic: session.current_topic,
+            score: session.session_score,
+        };
+        session.topic_count = 1;
+        session.active_topic = 0;
+    }
+    // Version 4 -> 5: sessions get their first nonce now.
+    if session.layout_version < 5 {
+        session.session_nonce = SessionAccount::derive_nonce(
+            &info.key(),
+            Clock::get()?.slot,
+            &session.owner,
+            &[0u8; 32],
+        );
+    }
+    // Version 5 -> 6: sessions closed before deferred settlement were settled
+    // at close and have nothing pending, read as zeros.
+    // Version 6 -> 7: sessions predating boosters have none, read as 0.
+    // Version 7 -> 8: sessions predating escrow hold none, read as zeros.
+    // Version 8 -> 9: the hash chain starts from zeros at the migration.
+    session.layout_version = SessionAccount::CURRENT_VERSION;
+
+    let new_len = 8 + SessionAccount::INIT_SPACE;
+    fund_rent_shortfall(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        new_len,
+        session.escrow_lamports,
+    )?;
+    write_migrated(
+        &info,
+        &ctx.accounts.owner.to_account_info(),
+        new_len,
+        session.escrow_lamports,
+        &session,
+    )?;
+
+    msg!("Session {} migrated", session.session_index);
+    Ok(())
+}
+
+/// Carries a pre-flags session into the current layout, leaving every field
+/// added since at its zero value and the version at 0.
+fn from_legacy_session(legacy: LegacySessionAccount) -> SessionAccount {
+    let mut session = SessionAccount {
+        human_record: legacy.human_record,
+        owner: legacy.owner,
+        session_index: legacy.session_index,
+        created_at: legacy.created_at,
+        last_interaction_at: legacy.last_interaction_at,
+        expires_at: legacy.expires_at,
+        flags: 0,
+        interaction_count: legacy.interaction_count,
+        personality_id: legacy.personality_id,
+        current_topic: legacy.current_topic,
+        session_score: legacy.session_score,
+        bump: legacy.bump,
+        closed_at: legacy.closed_at,
+        close_reason: legacy.close_reason,
+        open_logs: legacy.open_logs,
+        _reserved: [0u8; 1],
+        layout_version: 0,
+        template_id: 0,
+        scoring_profile: ScoringProfile::Standard as u8,
+        goal_type: GoalType::None as u8,
+        goal_target: 0,
+        reopen_count: 0,
+        topics: [TopicScore::default(); SessionAccount::MAX_TOPICS],
+        topic_count: 0,
+        active_topic: 0,
+        session_nonce: [0u8; 32],
+        pending_score: 0,
+        settles_at: 0,
+        booster_multiplier_bps: 0,
+        escrow_rate_per_minute: 0,
+        escrow_lamports: 0,
+        chain_head: [0; 32],
+    };
+    session.set_active(legacy.is_active);
+    session
+}
+
+#[derive(Accounts)]
+pub struct MigrateRegistry<'info> {
+    /// CHECK: May still be in an older layout, so it cannot be typed;
+    /// ownership, discriminator, address and authority are checked in the handler.
+    #[account(mut)]
+    pub registry: UncheckedAccount<'info>,
+
+    /// Must match the authority stored in the registry; funds any growth and
+    /// receives any rent freed.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrades the registry to `PlatformRegistry::CURRENT_VERSION`.
+///
+/// Authority only. Older layouts are a byte prefix of the current one, so the
+/// appended fields are read as zeros and then given their defaults.
+pub fn handler_migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+    let info = ctx.accounts.registry.to_account_info();
+    check_program_account(&info, &PlatformRegistry::DISCRIMINATOR)?;
+
+    let (expected, _) = Pubkey::find_program_address(&[PlatformRegistry::SEED], &crate::ID);
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidLegacyAccount);
+
+    let mut registry = read_padded::<PlatformRegistry>(&info, 8 + PlatformRegistry::INIT_SPACE)?;
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        YourFunError::AuthorityMismatch
+    );
+    require!(
+        registry.version < PlatformRegistry::CURRENT_VERSION,
+        YourFunError::AccountAlreadyMigrated
+    );
+
+    // Version 0 -> 1: no guardian is configured, and the zeroed
+    // `guardian_can_unpause` keeps resumption with the authority.
+    // Version 1 -> 2: the fee bounds start wide open so existing fees remain
+    // valid; the delta cap on fee updates applies regardless.
+    if registry.version < 2 {
+        registry.min_fee_lamports = 0;
+        registry.max_fee_lamports = u64::MAX;
+    }
+    // Version 2 -> 3: the audit trail starts empty, read as zero.
+    // Version 3 -> 4: registration stays open, read as false.
+    // Version 4 -> 5: region restrictions start off, read as false.
+    // Version 5 -> 6: no maintenance window is scheduled, read as zeros.
+    // Version 6 -> 7: verifications never lapse until a validity is set.
+    // Version 7 -> 8: no legacy import window is configured, read as zeros.
+    // Version 8 -> 9: appeals stay disabled until an arbiter is set.
+    // Version 9 -> 10: the composite is the behavioral score alone, as before.
+    if registry.version < 10 {
+        registry.sub_score_weights_bps = PlatformRegistry::DEFAULT_SUB_SCORE_WEIGHTS_BPS;
+    }
+    // Version 10 -> 11: verifications keep taking effect immediately.
+    // Version 11 -> 12: no curator is configured, read as the default key.
+    // Version 12 -> 13: sessions meeting their goal earn no bonus, read as 0.
+    // Version 13 -> 14: reopening stays disabled, read as 0.
+    // Version 14 -> 15: the content size limit is the default, read as 0.
+    // Version 15 -> 16: companion co-signing stays off, read as the default key.
+    // Version 16 -> 17: sessions keep settling at close, read as 0.
+    // Version 17 -> 18: no booster mints are accepted, read as zeros.
+    // Version 18 -> 19: no program officer is designated, read as the default key.
+    // Version 19 -> 20: no fee share goes to insurance, read as 0.
+    // Version 20 -> 21: topics stay unenforced, read as false.
+    // Version 21 -> 22: private interactions earn nothing, read as 0.
+    // Version 22 -> 23: verifications stay uncapped with an empty waitlist.
+    // Version 23 -> 24: verification levels never decay, read as zeros.
+    // Version 24 -> 25: no treasury is set, read as the default key, so fees
+    // keep going to the authority.
+    // Version 25 -> 26: no operation is paused on its own, read as 0.
+    // Version 26 -> 27: config changes stay immediate, read as 0.
+    // Version 27 -> 28: no co-authority is required, read as the default key.
+    // Version 28 -> 29: config history starts at the migration, read as 0.
+    // Version 29 -> 30: no fee schedule, read as empty (the flat fee applies).
+    // Version 30 -> 31: fee updates move the fee by at most the default
+    // delta, with no cooldown and no previous change on record.
+    if registry.version < 31 {
+        registry.max_fee_delta_bps = PlatformRegistry::DEFAULT_MAX_FEE_DELTA_BPS;
+    }
+    // Version 31 -> 32: sessions open at the migration are not counted in
+    // `active_sessions`; aggregation clamps at 0 as they close.
+    // Version 32 -> 33: no verifications are pending aggregation, read as 0.
+    // Version 33 -> 34: open sessions stay unlimited, read as 0.
+    // Version 34 -> 35: interactions keep no minimum gap, read as 0.
+    // Version 35 -> 36: interactions keep no minimum duration, read as 0.
+    // Version 36 -> 37: type multipliers read as all zero, the defaults.
+    // Version 37 -> 38: every personality stays allowed.
+    if registry.version < 38 {
+        registry.max_personality_id = u8::MAX;
+    }
+    // Version 38 -> 39: the platform keeps the whole fee, read as 0.
+    // Version 39 -> 40: humans verified before the migration are not counted
+    // in `active_humans` until they are verified again, read as 0. The
+    // pending verification count is retired into `_reserved_pending`.
+    if registry.version < 40 {
+        registry._reserved_pending = [0u8; 8];
+    }
+    registry.version = PlatformRegistry::CURRENT_VERSION;
+    registry.bump_state_version()?;
+
+    let new_len = 8 + PlatformRegistry::INIT_SPACE;
+    let authority = ctx.accounts.authority.to_account_info();
+    fund_rent_shortfall(
+        &info,
+        &authority,
+        &ctx.accounts.system_program.to_account_info(),
+        new_len,
+        0,
+    )?;
+    write_migrated(&info, &authority, new_len, 0, &registry)?;
+
+    msg!("Registry migrated to version {}", registry.version);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePersonalityConfig<'info> {
+    /// CHECK: May still be in an older layout, so it cannot be typed;
+    /// ownership, discriminator and PDA derivation are checked in the handler.
+    #[account(mut)]
+    pub personality_config: UncheckedAccount<'info>,
+
+    /// Covers the extra rent of the grown account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a PersonalityConfig created before partners existed to the current
+/// layout. The partner fields are read as zeros, so the personality has no
+/// partner. Permissionless; a config already in the current layout is
+/// rejected.
+pub fn handler_migrate_personality_config(ctx: Context<MigratePersonalityConfig>) -> Result<()> {
+    let info = ctx.accounts.personality_config.to_account_info();
+    check_program_account(&info, &PersonalityConfig::DISCRIMINATOR)?;
+
+    let new_len = 8 + PersonalityConfig::INIT_SPACE;
+    require!(info.data_len() < new_len, YourFunError::AccountAlreadyMigrated);
+
+    let config = read_padded::<PersonalityConfig>(&info, new_len)?;
+    let expected = Pubkey::create_program_address(
+        &[PersonalityConfig::SEED, &[config.personality_id], &[config.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(YourFunError::InvalidLegacyAccount))?;
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidLegacyAccount);
+
+    let payer = ctx.accounts.payer.to_account_info();
+    fund_rent_shortfall(
+        &info,
+        &payer,
+        &ctx.accounts.system_program.to_account_info(),
+        new_len,
+        config.partner_accrued,
+    )?;
+    write_migrated(&info, &payer, new_len, config.partner_accrued, &config)?;
+
+    msg!("Personality {} config migrated", config.personality_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateStatsBucket<'info> {
+    /// CHECK: May still be in an older layout, so it cannot be typed;
+    /// ownership, discriminator and PDA derivation are checked in the handler.
+    #[account(mut)]
+    pub stats_bucket: UncheckedAccount<'info>,
+
+    /// Covers the extra rent of the grown account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a StatsBucket created in an older layout to the current one, with
+/// the counters it lacked read as nothing pending. Permissionless; a bucket
+/// already in the current layout is rejected.
+pub fn handler_migrate_stats_bucket(ctx: Context<MigrateStatsBucket>) -> Result<()> {
+    let info = ctx.accounts.stats_bucket.to_account_info();
+    check_program_account(&info, &StatsBucket::DISCRIMINATOR)?;
+
+    let new_len = 8 + StatsBucket::INIT_SPACE;
+    require!(info.data_len() < new_len, YourFunError::AccountAlreadyMigrated);
+
+    let bucket = read_padded::<StatsBucket>(&info, new_len)?;
+    let expected = Pubkey::create_program_address(
+        &[StatsBucket::SEED, &[bucket.bucket_index], &[bucket.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(YourFunError::InvalidLegacyAccount))?;
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidLegacyAccount);
+
+    let payer = ctx.accounts.payer.to_account_info();
+    fund_rent_shortfall(
+        &info,
+        &payer,
+        &ctx.accounts.system_program.to_account_info(),
+        new_len,
+        0,
+    )?;
+    write_migrated(&info, &payer, new_len, 0, &bucket)?;
+
+    msg!("Stats bucket {} migrated", bucket.bucket_index);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePersonalityStats<'info> {
+    /// CHECK: May still be in an older layout, so it cannot be typed;
+    /// ownership, discriminator and PDA derivation are checked in the handler.
+    #[account(mut)]
+    pub personality_stats: UncheckedAccount<'info>,
+
+    /// Covers the extra rent of the grown account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a PersonalityStats created before ratings existed to the current
+/// layout, with no ratings recorded. Permissionless; stats already in the
+/// current layout are rejected.
+pub fn handler_migrate_personality_stats(ctx: Context<MigratePersonalityStats>) -> Result<()> {
+    let info = ctx.accounts.personality_stats.to_account_info();
+    check_program_account(&info, &PersonalityStats::DISCRIMINATOR)?;
+
+    let new_len = 8 + PersonalityStats::INIT_SPACE;
+    require!(info.data_len() < new_len, YourFunError::AccountAlreadyMigrated);
+
+    let stats = read_padded::<PersonalityStats>(&info, new_len)?;
+    let expected = Pubkey::create_program_address(
+        &[PersonalityStats::SEED, &[stats.personality_id], &[stats.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(YourFunError::InvalidLegacyAccount))?;
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidLegacyAccount);
+
+    let payer = ctx.accounts.payer.to_account_info();
+    fund_rent_shortfall(
+        &info,
+        &payer,
+        &ctx.accounts.system_program.to_account_info(),
+        new_len,
+        0,
+    )?;
+    write_migrated(&info, &payer, new_len, 0, &stats)?;
+
+    msg!("Personality {} stats migrated", stats.personality_id);
+    Ok(())
+}
+
+/// Checks that `info` is owned by this program and carries `discriminator`.
+fn check_program_account(info: &AccountInfo, discriminator: &[u8; 8]) -> Result<()> {
+    check_owned_account(info, &crate::ID, discriminator)
+}
+
+/// Checks that `info` is owned by `owner` and carries `discriminator`.
+pub(crate) fn check_owned_account(
+    info: &AccountInfo,
+    owner: &Pubkey,
+    discriminator: &[u8; 8],
+) -> Result<()> {
+    require_keys_eq!(*info.owner, *owner, YourFunError::InvalidLegacyAccount);
+
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == discriminator[..],
+        YourFunError::InvalidLegacyAccount
+    );
+    Ok(())
+}
+
+/// Decodes the body of an account in a legacy layout.
+fn read_legacy<T: AnchorDeserialize>(info: &AccountInfo) -> Result<T> {
+    let data = info.try_borrow_data()?;
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(YourFunError::InvalidLegacyAccount))
+}
+
+/// Decodes an account whose layout is a prefix of `T`, reading any fields
+/// beyond the end of its data as zeros.
+pub(crate) fn read_padded<T: AccountDeserialize>(info: &AccountInfo, full_len: usize) -> Result<T> {
+    let data = info.try_borrow_data()?;
+    let mut buf = vec![0u8; full_len.max(data.len())];
+    buf[..data.len()].copy_from_slice(&data);
+    T::try_deserialize(&mut &buf[..])
+}
+
+/// Tops `info` up to the rent-exempt minimum for `new_len` from `payer`.
+/// `held` lamports on the account belong to someone else, such as an
+/// escrow, and do not count towards the rent.
+fn fund_rent_shortfall<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+    held: u64,
+) -> Result<()> {
+    let required = Rent::get()?
+        .minimum_balance(new_len)
+        .checked_add(held)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    let shortfall = required.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    Ok(())
+}
+
+/// Resizes `info` to `new_len`, writes `account` into it and returns any
+/// lamports beyond the new rent-exempt minimum plus `held` to `refund_to`.
+fn write_migrated<'info, T: AccountSerialize>(
+    info: &AccountInfo<'info>,
+    refund_to: &AccountInfo<'info>,
+    new_len: usize,
+    held: u64,
+    account: &T,
+) -> Result<()> {
+    info.realloc(new_len, false)?;
+    {
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        account.try_serialize(&mut writer)?;
+    }
+
+    let required = Rent::get()?
+        .minimum_balance(new_len)
+        .checked_add(held)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    let excess = info.lamports().saturating_sub(required);
+    if excess > 0 {
+        **info.try_borrow_mut_lamports()? -= excess;
+        **refund_to.try_borrow_mut_lamports()? = refund_to
+            .lamports()
+            .checked_add(excess)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use anchor_lang::solana_program::system_instruction::SystemError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    /// A `len`-byte program account at `address` holding `discriminator` and
+    /// `body`, followed by the layout's zeroed reserved bytes.
+    fn legacy_account<T: AnchorSerialize>(
+        address: Pubkey,
+        discriminator: &[u8],
+        body: &T,
+        len: usize,
+    ) -> FixtureAccount {
+        let mut data = discriminator.to_vec();
+        body.serialize(&mut data).unwrap();
+        assert!(data.len() <= len, "legacy body overruns its layout");
+        data.resize(len, 0);
+        FixtureAccount {
+            address,
+            lamports: Rent::default().minimum_balance(len),
+            owner: crate::ID,
+            data,
+        }
+    }
+
+    #[test]
+    fn pre_flags_records_and_sessions_keep_their_active_state() {
+        let (payer, owner) = (Keypair::new(), Keypair::new());
+        let wallet = owner.pubkey();
+        let (human_record, record_bump) =
+            Pubkey::find_program_address(&[HumanRecord::SEED, wallet.as_ref()], &crate::ID);
+        let (session, session_bump) = Pubkey::find_program_address(
+            &[SessionAccount::SEED, human_record.as_ref(), &0u64.to_le_bytes()],
+            &crate::ID,
+        );
+        let record = LegacyHumanRecord {
+            wallet,
+            verified_by: Pubkey::new_unique(),
+            verified_at: NOW - 100,
+            verification_level: 1,
+            fingerprint_hash: [5u8; 32],
+            is_active: true,
+            session_count: 1,
+            total_interactions: 4,
+            last_active_at: NOW - 10,
+            learning_score: 300,
+            challenge_nonce: [6u8; 32],
+            bump: record_bump,
+            registered_at: NOW - 200,
+            strikes: 1,
+            revoked_at: 0,
+            force_closed_sessions: 0,
+        };
+        let legacy_session = LegacySessionAccount {
+            human_record,
+            owner: wallet,
+            session_index: 0,
+            created_at: NOW - 50,
+            last_interaction_at: NOW - 10,
+            expires_at: NOW + 3_000,
+            is_active: true,
+            interaction_count: 4,
+            personality_id: 2,
+            current_topic: [7u8; 32],
+            session_score: 300,
+            bump: session_bump,
+            closed_at: 0,
+            close_reason: 0,
+            open_logs: 0,
+        };
+        let mut harness = Harness::start(vec![
+            legacy_account(
+                human_record,
+                &HumanRecord::DISCRIMINATOR,
+                &record,
+                HumanRecord::LEGACY_BOOL_LAYOUT_LEN,
+            ),
+            legacy_account(
+                session,
+                &SessionAccount::DISCRIMINATOR,
+                &legacy_session,
+                SessionAccount::LEGACY_BOOL_LAYOUT_LEN,
+            ),
+        ]);
+        harness.fund(&payer.pubkey(), 1_000_000_000);
+        harness.fund(&wallet, 1_000_000_000);
+
+        let ix = client::build_migrate_human_record_ix(&payer.pubkey(), &wallet);
+        harness.process(ix, &[&payer]).unwrap();
+        let migrated: HumanRecord = harness.account(&human_record);
+        assert!(migrated.is_active());
+        assert_eq!(migrated.flags, HumanRecord::FLAG_ACTIVE);
+        assert_eq!(migrated.record_version, HumanRecord::CURRENT_VERSION);
+        assert_eq!(migrated.verification_status, VerificationStatus::Active as u8);
+        assert_eq!(
+            (migrated.session_count, migrated.learning_score, migrated.strikes),
+            (1, 300, 1)
+        );
+
+        let ix = client::build_migrate_session_ix(&payer.pubkey(), &wallet, 0);
+        harness.process(ix, &[&payer]).unwrap();
+        let migrated: SessionAccount = harness.account(&session);
+        assert!(migrated.is_active());
+        assert_eq!(migrated.flags, SessionAccount::FLAG_ACTIVE);
+        assert_eq!(migrated.layout_version, SessionAccount::CURRENT_VERSION);
+        assert_eq!(migrated.topics[0], TopicScore { topic: [7u8; 32], score: 300 });
+        assert_eq!(migrated.interaction_count, 4);
+    }
+
+    fn serialized<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn old_versions_migrate_once() {
+        let (payer, owner) = (Keypair::new(), Keypair::new());
+        let wallet = owner.pubkey();
+        let human_record = client::human_record_address(&wallet);
+        let current = FixtureBuilder::new()
+            .with_verified_human(wallet)
+            .build()
+            .into_iter()
+            .find(|account| account.address == human_record)
+            .unwrap();
+        let mut record = HumanRecord::try_deserialize(&mut &current.data[..]).unwrap();
+
+        // Version 2 ended just before the document issuer, which version 3
+        // appended, and had no verification status yet.
+        record.record_version = 2;
+        record.verification_status = 0;
+        let mut with_issuer = record.clone();
+        with_issuer.document_issuer = Pubkey::new_unique();
+        let (data, marked) = (serialized(&record), serialized(&with_issuer));
+        let v2_len = data.iter().zip(&marked).position(|(a, b)| a != b).unwrap();
+        let mut harness = Harness::start(vec![FixtureAccount {
+            data: data[..v2_len].to_vec(),
+            lamports: Rent::default().minimum_balance(v2_len),
+            ..current
+        }]);
+        harness.fund(&payer.pubkey(), 1_000_000_000);
+        harness.fund(&wallet, 1_000_000_000);
+
+        let migrate = || client::build_migrate_human_record_ix(&payer.pubkey(), &wallet);
+        harness.process(migrate(), &[&payer]).unwrap();
+        let migrated: HumanRecord = harness.account(&human_record);
+        assert_eq!(migrated.record_version, HumanRecord::CURRENT_VERSION);
+        assert_eq!(migrated.verification_status, VerificationStatus::Active as u8);
+        assert_eq!(migrated.document_issuer, Pubkey::default());
+        assert_eq!(migrated.learning_score, record.learning_score);
+        assert_eq!(migrated.fingerprint_hash, record.fingerprint_hash);
+
+        assert_eq!(
+            harness.process(migrate(), &[&payer]),
+            Err(custom(YourFunError::AccountAlreadyMigrated))
+        );
+    }
+
+    #[test]
+    fn a_v1_registry_is_refused_until_migrated() {
+        let authority = Keypair::new();
+        let address = client::registry_address();
+        let current = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.version = 1)
+            .build()
+            .into_iter()
+            .find(|account| account.address == address)
+            .unwrap();
+        let mut harness = Harness::start(vec![current.clone()]);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        let register = |harness: &mut Harness| {
+            let user = harness.wallet();
+            let ix = client::build_register_human_ix(
+                &user.pubkey(),
+                &authority.pubkey(),
+                false,
+                false,
+                None,
+                None,
+                [7u8; 32],
+                vec![0x5a; 64],
+            );
+            harness.process(ix, &[&user])
+        };
+        assert_eq!(register(&mut harness), Err(custom(YourFunError::AccountVersionMismatch)));
+
+        // Version 1 ended just before the fee bounds, which version 2
+        // appended; everything after them reads as zeros.
+        let mut registry = PlatformRegistry::try_deserialize(&mut &current.data[..]).unwrap();
+        registry.min_fee_lamports = 0;
+        let mut with_bounds = registry.clone();
+        with_bounds.min_fee_lamports = 1;
+        let (data, marked) = (serialized(&registry), serialized(&with_bounds));
+        let v1_len = data.iter().zip(&marked).position(|(a, b)| a != b).unwrap();
+        harness.set_account(FixtureAccount {
+            data: data[..v1_len].to_vec(),
+            lamports: Rent::default().minimum_balance(v1_len),
+            ..current
+        });
+
+        let stranger = harness.wallet();
+        assert_eq!(
+            harness.process(client::build_migrate_registry_ix(&stranger.pubkey()), &[&stranger]),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        let migrate = || client::build_migrate_registry_ix(&authority.pubkey());
+        harness.process(migrate(), &[&authority]).unwrap();
+        assert_eq!(harness.raw_account(&address).len(), 8 + PlatformRegistry::INIT_SPACE);
+        let migrated: PlatformRegistry = harness.account(&address);
+        assert_eq!(migrated.version, PlatformRegistry::CURRENT_VERSION);
+        assert_eq!((migrated.authority, migrated.bump), (registry.authority, registry.bump));
+        assert_eq!((migrated.min_fee_lamports, migrated.max_fee_lamports), (0, u64::MAX));
+        assert_eq!(migrated.sub_score_weights_bps, PlatformRegistry::DEFAULT_SUB_SCORE_WEIGHTS_BPS);
+        assert_eq!(migrated.max_fee_delta_bps, PlatformRegistry::DEFAULT_MAX_FEE_DELTA_BPS);
+        assert_eq!(migrated.max_personality_id, u8::MAX);
+        assert_eq!(migrated.treasury, Pubkey::default());
+
+        register(&mut harness).unwrap();
+        assert_eq!(
+            harness.process(migrate(), &[&authority]),
+            Err(custom(YourFunError::AccountAlreadyMigrated))
+        );
+    }
+
+    #[test]
+    fn the_payer_covers_growth_and_a_held_escrow_stays_on_the_session() {
+        let (payer, owner) = (Keypair::new(), Keypair::new());
+        let wallet = owner.pubkey();
+        let escrow = 250_000;
+        let session = client::session_address(&client::human_record_address(&wallet), 0);
+        let current = FixtureBuilder::new()
+            .with_verified_human(wallet)
+            .with_open_session()
+            .with_session(|session| {
+                session.layout_version = 8;
+                session.escrow_lamports = escrow;
+                session.interaction_count = 4;
+            })
+            .build()
+            .into_iter()
+            .find(|account| account.address == session)
+            .unwrap();
+
+        // Version 8 ended just before the hash chain, which version 9 appended.
+        let legacy = SessionAccount::try_deserialize(&mut &current.data[..]).unwrap();
+        let mut chained = legacy.clone();
+        chained.chain_head = [1u8; 32];
+        let (data, marked) = (serialized(&legacy), serialized(&chained));
+        let v8_len = data.iter().zip(&marked).position(|(a, b)| a != b).unwrap();
+        let mut harness = Harness::start(vec![FixtureAccount {
+            data: data[..v8_len].to_vec(),
+            lamports: Rent::default().minimum_balance(v8_len) + escrow,
+            ..current
+        }]);
+        let new_len = 8 + SessionAccount::INIT_SPACE;
+        let growth = Rent::default().minimum_balance(new_len) - Rent::default().minimum_balance(v8_len);
+        harness.fund(&wallet, 1_000_000_000);
+
+        // The escrow is not the payer's to spend on rent.
+        harness.fund(&payer.pubkey(), growth - 1);
+        let migrate = || client::build_migrate_session_ix(&payer.pubkey(), &wallet, 0);
+        assert_eq!(
+            harness.process(migrate(), &[&payer]),
+            Err(InstructionError::Custom(SystemError::ResultWithNegativeLamports as u32))
+        );
+        assert_eq!(harness.raw_account(&session).len(), v8_len);
+
+        harness.fund(&payer.pubkey(), growth);
+        harness.process(migrate(), &[&payer]).unwrap();
+        assert_eq!(harness.balance(&payer.pubkey()), 0);
+        assert_eq!(harness.balance(&wallet), 1_000_000_000);
+        assert_eq!(
+            harness.balance(&session),
+            Rent::default().minimum_balance(new_len) + escrow
+        );
+        let migrated: SessionAccount = harness.account(&session);
+        assert_eq!(migrated.layout_version, SessionAccount::CURRENT_VERSION);
+        assert_eq!((migrated.escrow_lamports, migrated.interaction_count), (escrow, 4));
+        assert_eq!(migrated.chain_head, [0u8; 32]);
+        assert!(migrated.is_active());
+
+        assert_eq!(
+            harness.process(migrate(), &[&payer]),
+            Err(custom(YourFunError::AccountAlreadyMigrated))
+        );
+    }
+}
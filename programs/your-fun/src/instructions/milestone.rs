@@ -0,0 +1,276 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{PlatformRegistry, HumanRecord, Milestone, Vesting};
+use crate::error::YourFunError;
+use crate::events::{MilestoneClaimed, VestedTokensClaimed};
+
+#[derive(Accounts)]
+#[instruction(milestone_id: u16)]
+pub struct CreateMilestone<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Milestone::INIT_SPACE,
+        seeds = [Milestone::SEED, &milestone_id.to_le_bytes()],
+        bump,
+    )]
+    pub milestone: Box<Account<'info, Milestone>>,
+
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [Milestone::VAULT_SEED, &milestone_id.to_le_bytes()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = milestone,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a milestone reward and its vault, which anyone may fund.
+/// Authority only.
+pub fn handler_create_milestone(
+    ctx: Context<CreateMilestone>,
+    milestone_id: u16,
+    score_threshold: u64,
+    reward_amount: u64,
+    cliff: i64,
+    vesting_duration: i64,
+) -> Result<()> {
+    require!(score_threshold > 0 && reward_amount > 0, YourFunError::InvalidConfigValue);
+    require!(cliff >= 0 && vesting_duration >= 0, YourFunError::InvalidConfigValue);
+
+    let milestone = &mut ctx.accounts.milestone;
+    milestone.milestone_id = milestone_id;
+    milestone.score_threshold = score_threshold;
+    milestone.reward_mint = ctx.accounts.reward_mint.key();
+    milestone.vault = ctx.accounts.vault.key();
+    milestone.reward_amount = reward_amount;
+    milestone.cliff = cliff;
+    milestone.vesting_duration = vesting_duration;
+    milestone.bump = ctx.bumps.milestone;
+
+    msg!("Milestone {} created", milestone_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMilestoneSchedule<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [Milestone::SEED, &milestone.milestone_id.to_le_bytes()],
+        bump = milestone.bump,
+    )]
+    pub milestone: Box<Account<'info, Milestone>>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Changes a milestone's cliff and vesting duration. Only later claims are
+/// affected; existing Vesting accounts keep their schedule. Authority only.
+pub fn handler_set_milestone_schedule(
+    ctx: Context<SetMilestoneSchedule>,
+    cliff: i64,
+    vesting_duration: i64,
+) -> Result<()> {
+    require!(cliff >= 0 && vesting_duration >= 0, YourFunError::InvalidConfigValue);
+
+    let milestone = &mut ctx.accounts.milestone;
+    milestone.cliff = cliff;
+    milestone.vesting_duration = vesting_duration;
+
+    msg!(
+        "Milestone {} vests after {}s over {}s",
+        milestone.milestone_id,
+        cliff,
+        vesting_duration
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimMilestone<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
+        constraint = human_record.learning_score >= milestone.score_threshold
+            @ YourFunError::MilestoneNotReached,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        seeds = [Milestone::SEED, &milestone.milestone_id.to_le_bytes()],
+        bump = milestone.bump,
+    )]
+    pub milestone: Box<Account<'info, Milestone>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vesting::INIT_SPACE,
+        seeds = [
+            Vesting::SEED,
+            human_record.key().as_ref(),
+            &milestone.milestone_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Claims a milestone's reward once the learning score reaches its
+/// threshold. Nothing is paid yet: the reward starts vesting under the
+/// milestone's current schedule and is released by `claim_vested`. Each
+/// human can claim each milestone once.
+pub fn handler_claim_milestone(ctx: Context<ClaimMilestone>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.registry.require_operational(now)?;
+
+    let milestone = &ctx.accounts.milestone;
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.human_record = ctx.accounts.human_record.key();
+    vesting.owner = ctx.accounts.owner.key();
+    vesting.milestone_id = milestone.milestone_id;
+    vesting.total_amount = milestone.reward_amount;
+    vesting.claimed_so_far = 0;
+    vesting.start_at = now;
+    vesting.cliff_at = now
+        .checked_add(milestone.cliff)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    vesting.vesting_duration = milestone.vesting_duration;
+    vesting.bump = ctx.bumps.vesting;
+
+    let vests_until = now
+        .checked_add(milestone.vesting_duration)
+        .ok_or(YourFunError::NumericalOverflow)?
+        .max(vesting.cliff_at);
+
+    emit!(MilestoneClaimed {
+        wallet: vesting.owner,
+        milestone_id: vesting.milestone_id,
+        total_amount: vesting.total_amount,
+        cliff_at: vesting.cliff_at,
+        vests_until,
+    });
+
+    msg!("Milestone {} claimed", vesting.milestone_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        seeds = [Milestone::SEED, &milestone.milestone_id.to_le_bytes()],
+        bump = milestone.bump,
+    )]
+    pub milestone: Box<Account<'info, Milestone>>,
+
+    #[account(
+        mut,
+        seeds = [
+            Vesting::SEED,
+            vesting.human_record.as_ref(),
+            &milestone.milestone_id.to_le_bytes(),
+        ],
+        bump = vesting.bump,
+        has_one = owner @ YourFunError::Unauthorized,
+    )]
+    pub vesting: Box<Account<'info, Vesting>>,
+
+    #[account(
+        mut,
+        address = milestone.vault,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = milestone.reward_mint,
+    )]
+    pub owner_token: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Releases the part of a milestone reward that vested since the last
+/// release. Allowed while paused, since the tokens are already granted.
+pub fn handler_claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vesting = &ctx.accounts.vesting;
+    let amount = vesting.vested_at(now).saturating_sub(vesting.claimed_so_far);
+    require!(amount > 0, YourFunError::NothingVested);
+
+    let milestone = &ctx.accounts.milestone;
+    let id = milestone.milestone_id.to_le_bytes();
+    let bump = [milestone.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[Milestone::SEED, &id, &bump]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.owner_token.to_account_info(),
+                authority: ctx.accounts.milestone.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let vesting = &mut ctx.accounts.vesting;
+    vesting.claimed_so_far = vesting
+        .claimed_so_far
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(VestedTokensClaimed {
+        wallet: vesting.owner,
+        milestone_id: vesting.milestone_id,
+        amount,
+        claimed_so_far: vesting.claimed_so_far,
+    });
+
+    msg!(
+        "{} vested tokens of milestone {} claimed",
+        amount,
+        vesting.milestone_id
+    );
+    Ok(())
+}
@@ -3,9 +3,176 @@ pub mod register_human;
 pub mod verify_human;
 pub mod session;
 pub mod interaction;
+pub mod admin;
+pub mod close_human_record;
+pub mod revoke_human;
+pub mod migrate;
+pub mod stats;
+pub mod active_index;
+pub mod election;
+pub mod invite;
+pub mod compromised;
+pub mod import;
+pub mod appeal;
+pub mod attestation;
+pub mod merge;
+pub mod template;
+pub mod topic;
+pub mod supervisor;
+pub mod bundle;
+pub mod highlights;
+pub mod settlement;
+pub mod milestone;
+pub mod tip;
+pub mod scholarship;
+pub mod insurance;
+pub mod partner;
+pub mod raffle;
+pub mod matching;
+pub mod promo;
+pub mod audit;
+pub mod spending;
+pub mod recompute;
+pub mod waitlist;
+pub mod liveness;
+pub mod handle;
+pub mod fee_vault;
+pub mod timelock;
+pub mod freeze;
 
 pub use initialize::*;
 pub use register_human::*;
 pub use verify_human::*;
 pub use session::*;
 pub use interaction::*;
+pub use admin::*;
+pub use close_human_record::*;
+pub use revoke_human::*;
+pub use migrate::*;
+pub use stats::*;
+pub use active_index::*;
+pub use election::*;
+pub use invite::*;
+pub use compromised::*;
+pub use import::*;
+pub use appeal::*;
+pub use attestation::*;
+pub use merge::*;
+pub use template::*;
+pub use topic::*;
+pub use supervisor::*;
+pub use bundle::*;
+pub use highlights::*;
+pub use settlement::*;
+pub use milestone::*;
+pub use tip::*;
+pub use scholarship::*;
+pub use insurance::*;
+pub use partner::*;
+pub use raffle::*;
+pub use matching::*;
+pub use promo::*;
+pub use audit::*;
+pub use spending::*;
+pub use recompute::*;
+pub use waitlist::*;
+pub use liveness::*;
+pub use handle::*;
+pub use fee_vault::*;
+pub use timelock::*;
+pub use freeze::*;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use anchor_lang::prelude::*;
+    use anchor_lang::Bumps;
+
+    use super::*;
+
+    /// Stack bytes an `Accounts` struct may take. Program accounts are
+    /// boxed, so each field costs a pointer or two; a single unboxed
+    /// registry or human record exceeds this on its own.
+    const ACCOUNTS_STACK_BUDGET: usize = 256;
+
+    /// Names of the `#[derive(Accounts)]` structs in the instruction sources.
+    fn declared_accounts_structs() -> BTreeSet<String> {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/instructions");
+        let mut names = BTreeSet::new();
+        for entry in std::fs::read_dir(dir).expect("instructions directory") {
+            let source = std::fs::read_to_string(entry.expect("directory entry").path())
+                .expect("instruction source");
+            let mut rest = source.as_str();
+            while let Some(at) = rest.find("\n#[derive(Accounts)]") {
+                rest = &rest[at..];
+                let start = rest.find("pub struct ").expect("struct after derive") + "pub struct ".len();
+                let name: String = rest[start..]
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || *c == '_')
+                    .collect();
+                names.insert(name);
+                rest = &rest[start..];
+            }
+        }
+        names
+    }
+
+    macro_rules! check_accounts_structs {
+        ($($name:ident),* $(,)?) => {{
+            let mut checked = BTreeSet::new();
+            $(
+                let mut accounts: &[AccountInfo<'static>] = &[];
+                let mut bumps = <$name as Bumps>::Bumps::default();
+                assert!(
+                    $name::try_accounts(&crate::ID, &mut accounts, &[], &mut bumps, &mut BTreeSet::new())
+                        .is_err(),
+                    "{} accepted an empty account list",
+                    stringify!($name),
+                );
+                let size = std::mem::size_of::<$name<'static>>();
+                assert!(
+                    size <= ACCOUNTS_STACK_BUDGET,
+                    "{} takes {size} bytes of stack; box its program accounts",
+                    stringify!($name),
+                );
+                checked.insert(stringify!($name).to_string());
+            )*
+            checked
+        }};
+    }
+
+    /// Runs every Accounts struct's deserializer and checks its size, so a
+    /// struct growing past the BPF stack frame is caught off-chain.
+    #[test]
+    fn accounts_structs_fit_the_stack_budget() {
+        let checked = check_accounts_structs!(
+        InitActiveSessionIndex, AdminConfig, CoSignedAdminConfig, SetPaused, SetPauseFlags,
+        ScheduleMaintenance, SetPersonalitySunset, FileAppeal, ResolveAppeal,
+        SetDocumentIssuer, AttestDocument, AssertHumanInvariants, SubmitInteractionBundle,
+        SetInteractionDelegate, CloseHumanRecord, CloseTombstone, ListCompromisedFingerprint,
+        DelistCompromisedFingerprint, FlagCompromisedRecord, InitElection, NominateVerifier,
+        VoteForVerifier, ElectVerifiers, RetireVerifier, WithdrawVote, InitFeeVault,
+        WithdrawFees, FreezeHuman, ClaimHandle, ReleaseHandle, ResolveHandle, PinHighlight,
+        UnpinHighlight, ImportLegacyRecord, Initialize, InitInsuranceFund, PayInsuranceClaim,
+        RecordInteraction, RecordUnloggedInteraction, PreviewScore, ReapInteraction,
+        CreateInvites, RevokeInvite, ProveLiveness, ApplyLivenessDecay, GetVerificationLevel,
+        ConfigureMatching, FundMatchingEscrow, ReclaimMatchingEscrow, MergeHumanRecords,
+        MigrateHumanRecord, MigrateSession, MigrateRegistry, MigratePersonalityConfig,
+        MigrateStatsBucket, MigratePersonalityStats, CreateMilestone, SetMilestoneSchedule, ClaimMilestone, ClaimVested,
+        SetPersonalityPartner, ClaimPartnerRevenue, CreatePromo, OpenRafflePage, FundRaffle,
+        CommitRaffle, DrawRaffle, ClaimRafflePrize, RecomputeSessionScore, RegisterHuman,
+        RevokeHuman, FundScholarshipPool, ApproveScholarship, RegisterHumanScholarship,
+        CreateSession, CloseSession, ForceCloseSession, AdminForceCloseSession, ExtendSession,
+        ReopenSession, HeartbeatSession, ReclaimSession, FinalizeSession, ContestSettlement,
+        SetSpendingCap, InitStatsBucket, InitRegionStats, AggregateStats, SetSupervisor,
+        ClearSupervisor, CreateTemplate, SetTemplateEnabled, CreateSessionFromTemplate,
+        QueueConfigUpdate, ExecuteConfigUpdate, CancelConfigUpdate, TipPersonality,
+        WithdrawPersonalityTips, RegisterTopic, SetTopicEnabled, UpdateTopic,
+        InitTopicProgress, VerifyHuman, VerifyHumansBatch, RejectVerification, VerifyAttribute,
+        HumanityScore, ContestVerification, ClaimVerifierFee, ExpireVerification,
+        AdmitFromWaitlist, RateSession,
+        );
+        assert_eq!(checked, declared_accounts_structs(), "every Accounts struct is checked");
+    }
+}
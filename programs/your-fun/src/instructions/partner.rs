@@ -0,0 +1,255 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, AdminAction, AdminActionKind, PersonalityConfig};
+use crate::instructions::admin::record_admin_action;
+use crate::instructions::migrate::read_padded;
+use crate::error::YourFunError;
+use crate::constants::MAX_BPS;
+use crate::events::{PartnerRevenueAccrued, PartnerRevenueClaimed, PersonalityPartnerSet};
+
+/// Reads the personality config at `info`, if the authority created one.
+/// Configs still in the layout predating partners read as having none.
+pub(crate) fn load_personality_config(info: &AccountInfo) -> Result<Option<PersonalityConfig>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    read_padded::<PersonalityConfig>(info, 8 + PersonalityConfig::INIT_SPACE).map(Some)
+}
+
+/// Credits `amount` lamports, already moved onto the personality config
+/// account at `info`, to its partner's claimable balance.
+pub(crate) fn accrue_partner_revenue(
+    info: &AccountInfo,
+    mut config: PersonalityConfig,
+    amount: u64,
+) -> Result<()> {
+    config.partner_accrued = config
+        .partner_accrued
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    {
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        config.try_serialize(&mut writer)?;
+    }
+
+    emit!(PartnerRevenueAccrued {
+        personality_id: config.personality_id,
+        partner: config.partner,
+        amount,
+        partner_accrued: config.partner_accrued,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(personality_id: u8)]
+pub struct SetPersonalityPartner<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    /// A config created before partners existed must be migrated first.
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PersonalityConfig::INIT_SPACE,
+        seeds = [PersonalityConfig::SEED, &[personality_id]],
+        bump,
+    )]
+    pub personality_config: Box<Account<'info, PersonalityConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Credits `partner` with `partner_share_bps` of the session fees collected
+/// for a personality from now on. The default key removes the partner, so
+/// fees go to the treasury in full. Revenue accrued and not yet claimed
+/// passes to the new partner. Authority only.
+pub fn handler_set_personality_partner(
+    ctx: Context<SetPersonalityPartner>,
+    personality_id: u8,
+    partner: Pubkey,
+    partner_share_bps: u16,
+) -> Result<()> {
+    require!(partner_share_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+
+    let personality_config = &mut ctx.accounts.personality_config;
+    if personality_config.bump == 0 {
+        personality_config.personality_id = personality_id;
+        personality_config.bump = ctx.bumps.personality_config;
+    }
+    personality_config.partner = partner;
+    personality_config.partner_share_bps = partner_share_bps;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::SetPersonalityPartner,
+        ctx.accounts.authority.key(),
+        &(personality_id, partner, partner_share_bps),
+    )?;
+
+    emit!(PersonalityPartnerSet {
+        personality_id,
+        partner,
+        partner_share_bps,
+    });
+
+    msg!(
+        "Personality {} partner set to {} at {} bps",
+        personality_id,
+        partner,
+        partner_share_bps
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimPartnerRevenue<'info> {
+    #[account(
+        mut,
+        seeds = [PersonalityConfig::SEED, &[personality_config.personality_id]],
+        bump = personality_config.bump,
+        has_one = partner @ YourFunError::Unauthorized,
+    )]
+    pub personality_config: Box<Account<'info, PersonalityConfig>>,
+
+    #[account(mut)]
+    pub partner: Signer<'info>,
+}
+
+/// Pays everything accrued to a personality's partner to the partner.
+/// Allowed while paused, since the revenue is already earned.
+pub fn handler_claim_partner_revenue(ctx: Context<ClaimPartnerRevenue>) -> Result<()> {
+    let amount = ctx.accounts.personality_config.partner_accrued;
+    require!(amount > 0, YourFunError::NoPartnerRevenue);
+
+    let config_info = ctx.accounts.personality_config.to_account_info();
+    let partner_info = ctx.accounts.partner.to_account_info();
+    **config_info.try_borrow_mut_lamports()? -= amount;
+    **partner_info.try_borrow_mut_lamports()? = partner_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let personality_config = &mut ctx.accounts.personality_config;
+    personality_config.partner_accrued = 0;
+    personality_config.partner_claimed = personality_config
+        .partner_claimed
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(PartnerRevenueClaimed {
+        personality_id: personality_config.personality_id,
+        partner: personality_config.partner,
+        amount,
+        partner_claimed: personality_config.partner_claimed,
+    });
+
+    msg!(
+        "{} lamports of partner revenue claimed for personality {}",
+        amount,
+        personality_config.personality_id
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    const FEE: u64 = 10_000_000;
+    const EXPIRES_AT: i64 = NOW + 600;
+
+    #[test]
+    fn partners_accrue_their_share_of_session_fees_and_alone_claim_it() {
+        let [authority, partner, impostor] = [0; 3].map(|_| Keypair::new());
+        let owners: Vec<_> = (0..3).map(|_| Keypair::new()).collect();
+        let mut builder = FixtureBuilder::new().with_authority(authority.pubkey()).with_registry(|registry| {
+            registry.reopen_grace_period = 120;
+            registry.reopen_fee_lamports = FEE;
+        });
+        for owner in &owners {
+            builder = builder
+                .with_verified_human(owner.pubkey())
+                .with_open_session()
+                .with_session(|session| session.expires_at = EXPIRES_AT);
+        }
+        let mut harness = Harness::start(builder.build());
+        for signer in [&authority, &partner, &impostor].into_iter().chain(&owners) {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let set_partner = |harness: &mut Harness, partner: Pubkey, partner_share_bps: u16| {
+            let count = harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count;
+            let ix = client::build_set_personality_partner_ix(&authority.pubkey(), count, 0, partner, partner_share_bps);
+            harness.process(ix, &[&authority])
+        };
+        let reopen = |harness: &mut Harness, owner: &Keypair| {
+            let ix = client::build_reopen_session_ix(&owner.pubkey(), 0, 0, &authority.pubkey(), false, 300);
+            harness.process(ix, &[owner]).unwrap();
+        };
+        let config = client::personality_config_address(0);
+
+        assert_eq!(
+            set_partner(&mut harness, partner.pubkey(), MAX_BPS + 1),
+            Err(custom(YourFunError::InvalidConfigValue))
+        );
+        set_partner(&mut harness, partner.pubkey(), 3_000).unwrap();
+
+        harness.warp_to(EXPIRES_AT);
+        let (fee_wallet, config_balance) = (harness.balance(&authority.pubkey()), harness.balance(&config));
+        for owner in &owners[..2] {
+            reopen(&mut harness, owner);
+        }
+        let share = FEE * 3 / 10;
+        assert_eq!(harness.balance(&authority.pubkey()), fee_wallet + 2 * (FEE - share));
+        assert_eq!(harness.balance(&config), config_balance + 2 * share);
+        assert_eq!(harness.account::<PersonalityConfig>(&config).partner_accrued, 2 * share);
+
+        let claim = |signer: &Keypair| client::build_claim_partner_revenue_ix(&signer.pubkey(), 0);
+        assert_eq!(harness.process(claim(&impostor), &[&impostor]), Err(custom(YourFunError::Unauthorized)));
+        let before = harness.balance(&partner.pubkey());
+        harness.process(claim(&partner), &[&partner]).unwrap();
+        assert_eq!(harness.balance(&partner.pubkey()), before + 2 * share);
+        assert_eq!(harness.balance(&config), config_balance);
+        let claimed: PersonalityConfig = harness.account(&config);
+        assert_eq!((claimed.partner_accrued, claimed.partner_claimed), (0, 2 * share));
+        assert_eq!(harness.process(claim(&partner), &[&partner]), Err(custom(YourFunError::NoPartnerRevenue)));
+
+        // Without a partner the fee goes to the treasury in full.
+        set_partner(&mut harness, Pubkey::default(), 0).unwrap();
+        let fee_wallet = harness.balance(&authority.pubkey());
+        reopen(&mut harness, &owners[2]);
+        assert_eq!(harness.balance(&authority.pubkey()), fee_wallet + FEE);
+        assert_eq!(harness.balance(&config), config_balance);
+    }
+}
@@ -0,0 +1,258 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::state::{PlatformRegistry, AdminAction, AdminActionKind, Promo, PromoRedemption};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+use crate::constants::MAX_BPS;
+use crate::events::{PromoCreated, PromoRedeemed};
+
+/// Checks `code` against the promo and its expiry and remaining uses, uses
+/// it up once and records the wallet's redemption, returning `fee` with the
+/// discount applied.
+pub(crate) fn redeem_promo(
+    promo: &mut Account<Promo>,
+    redemption: &mut PromoRedemption,
+    redemption_bump: u8,
+    code: &[u8],
+    wallet: Pubkey,
+    fee: u64,
+    now: i64,
+) -> Result<u64> {
+    require!(hash(code).to_bytes() == promo.code_hash, YourFunError::InvalidPromoCode);
+    require!(now < promo.expires_at, YourFunError::PromoExpired);
+    require!(promo.remaining_uses > 0, YourFunError::PromoExhausted);
+
+    promo.remaining_uses -= 1;
+    redemption.promo = promo.key();
+    redemption.wallet = wallet;
+    redemption.redeemed_at = now;
+    redemption.bump = redemption_bump;
+
+    let fee_paid = promo.discounted(fee);
+    emit!(PromoRedeemed {
+        promo: promo.key(),
+        wallet,
+        fee_paid,
+        remaining_uses: promo.remaining_uses,
+    });
+    Ok(fee_paid)
+}
+
+#[derive(Accounts)]
+#[instruction(code_hash: [u8; 32])]
+pub struct CreatePromo<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Promo::INIT_SPACE,
+        seeds = [Promo::SEED, code_hash.as_ref()],
+        bump,
+    )]
+    pub promo: Box<Account<'info, Promo>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a promo code, identified by the SHA-256 hash of the code, that
+/// takes `discount_bps` off the verification fee for up to `max_uses`
+/// registrations until `expires_at`. Authority only.
+pub fn handler_create_promo(
+    ctx: Context<CreatePromo>,
+    code_hash: [u8; 32],
+    discount_bps: u16,
+    max_uses: u32,
+    expires_at: i64,
+) -> Result<()> {
+    require!(discount_bps <= MAX_BPS, YourFunError::InvalidConfigValue);
+    require!(max_uses > 0, YourFunError::InvalidConfigValue);
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        YourFunError::InvalidConfigValue
+    );
+
+    let promo = &mut ctx.accounts.promo;
+    promo.code_hash = code_hash;
+    promo.discount_bps = discount_bps;
+    promo.max_uses = max_uses;
+    promo.remaining_uses = max_uses;
+    promo.expires_at = expires_at;
+    promo.bump = ctx.bumps.promo;
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::CreatePromo,
+        ctx.accounts.authority.key(),
+        &(code_hash, discount_bps, max_uses, expires_at),
+    )?;
+
+    emit!(PromoCreated {
+        promo: promo.key(),
+        discount_bps,
+        max_uses,
+        expires_at,
+    });
+
+    msg!("Promo created: {} bps off, {} uses", discount_bps, max_uses);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+    use anchor_lang::solana_program::system_instruction::SystemError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::HumanRecord;
+
+    const FEE: u64 = 1_000_000;
+    const CODE: &[u8] = b"LAUNCH50";
+
+    /// Starts from a registry charging `FEE`, paid to `authority`, with a
+    /// half-price promo for `CODE` of `max_uses` uses that expires at
+    /// `expires_at`.
+    fn start(authority: &Keypair, max_uses: u32, expires_at: i64) -> Harness {
+        let code_hash = hash(CODE).to_bytes();
+        let promo_address = client::promo_address(&code_hash);
+        let (_, bump) = Pubkey::find_program_address(&[Promo::SEED, code_hash.as_ref()], &crate::ID);
+        let promo = Promo {
+            code_hash,
+            discount_bps: 5_000,
+            max_uses,
+            remaining_uses: max_uses,
+            expires_at,
+            bump,
+        };
+        let mut accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.verification_fee_lamports = FEE)
+            .with_region_stats(0)
+            .build();
+        accounts.push(FixtureAccount::new(promo_address, &promo));
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness
+    }
+
+    fn register_ix(authority: &Keypair, user: &Keypair, code: &[u8]) -> Instruction {
+        client::build_register_human_ix(
+            &user.pubkey(),
+            &authority.pubkey(),
+            false,
+            false,
+            None,
+            Some(code.to_vec()),
+            [7u8; 32],
+            vec![0x5a; 64],
+        )
+    }
+
+    fn remaining_uses(harness: &mut Harness) -> u32 {
+        let promo: Promo = harness.account(&client::promo_address(&hash(CODE).to_bytes()));
+        promo.remaining_uses
+    }
+
+    #[test]
+    fn a_code_halves_the_fee_once_per_wallet_until_its_uses_run_out() {
+        let authority = Keypair::new();
+        let mut harness = start(&authority, 2, NOW + 100);
+        let promo = client::promo_address(&hash(CODE).to_bytes());
+
+        let first = harness.wallet();
+        let before = harness.balance(&authority.pubkey());
+        harness.process(register_ix(&authority, &first, CODE), &[&first]).unwrap();
+        assert_eq!(harness.balance(&authority.pubkey()), before + FEE / 2);
+        assert_eq!(remaining_uses(&mut harness), 1);
+        let redemption: PromoRedemption =
+            harness.account(&client::promo_redemption_address(&promo, &first.pubkey()));
+        assert_eq!((redemption.promo, redemption.wallet), (promo, first.pubkey()));
+        assert_eq!(redemption.redeemed_at, NOW);
+
+        // Closing the record does not clear the wallet's redemption.
+        let close = client::build_close_human_record_ix(&first.pubkey(), 0, None, None);
+        harness.process(close, &[&first]).unwrap();
+        assert_eq!(
+            harness.process(register_ix(&authority, &first, CODE), &[&first]),
+            Err(InstructionError::Custom(SystemError::AccountAlreadyInUse as u32))
+        );
+        assert_eq!(remaining_uses(&mut harness), 1);
+
+        let second = harness.wallet();
+        harness.process(register_ix(&authority, &second, CODE), &[&second]).unwrap();
+        assert_eq!(remaining_uses(&mut harness), 0);
+
+        let third = harness.wallet();
+        assert_eq!(
+            harness.process(register_ix(&authority, &third, CODE), &[&third]),
+            Err(custom(YourFunError::PromoExhausted))
+        );
+    }
+
+    #[test]
+    fn wrong_and_expired_codes_are_refused() {
+        let authority = Keypair::new();
+        let expires_at = NOW + 100;
+        let mut harness = start(&authority, 10, expires_at);
+        let user = harness.wallet();
+
+        // A wrong preimage presented against the real promo account.
+        let (wrong, promo) = (
+            client::promo_address(&hash(b"LAUNCH51").to_bytes()),
+            client::promo_address(&hash(CODE).to_bytes()),
+        );
+        let mut ix = register_ix(&authority, &user, b"LAUNCH51");
+        for meta in &mut ix.accounts {
+            if meta.pubkey == wrong {
+                meta.pubkey = promo;
+            } else if meta.pubkey == client::promo_redemption_address(&wrong, &user.pubkey()) {
+                meta.pubkey = client::promo_redemption_address(&promo, &user.pubkey());
+            }
+        }
+        assert_eq!(
+            harness.process(ix, &[&user]),
+            Err(custom(YourFunError::InvalidPromoCode))
+        );
+
+        harness.warp_to(expires_at);
+        assert_eq!(
+            harness.process(register_ix(&authority, &user, CODE), &[&user]),
+            Err(custom(YourFunError::PromoExpired))
+        );
+        assert_eq!(remaining_uses(&mut harness), 10);
+        assert!(harness
+            .try_account::<HumanRecord>(&client::human_record_address(&user.pubkey()))
+            .is_none());
+    }
+}
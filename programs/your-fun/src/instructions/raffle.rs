@@ -0,0 +1,540 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use crate::state::{HumanRecord, RaffleEpoch, RaffleTicketPage};
+use crate::error::YourFunError;
+use crate::events::{RaffleCommitted, RaffleDrawn, RaffleEntered, RaffleFunded, RafflePrizeClaimed};
+
+/// Gives the human a ticket in the current raffle epoch, unless they already
+/// hold one or are revoked. `raffle_page` must be the epoch's latest page and
+/// have room; `open_raffle_page` adds the next one.
+pub(crate) fn enter_raffle(
+    human_record: &mut HumanRecord,
+    raffle_epoch: &mut RaffleEpoch,
+    raffle_page: &mut RaffleTicketPage,
+    now: i64,
+) -> Result<()> {
+    require!(
+        raffle_epoch.epoch == RaffleEpoch::epoch_at(now),
+        YourFunError::RaffleEpochMismatch
+    );
+    if !human_record.is_active() || human_record.last_raffle_epoch == raffle_epoch.epoch {
+        return Ok(());
+    }
+    require!(
+        raffle_page.epoch == raffle_epoch.epoch
+            && raffle_page.page + 1 == raffle_epoch.page_count
+            && !raffle_page.is_full(),
+        YourFunError::RafflePageFull
+    );
+
+    let ticket_index = raffle_epoch.ticket_count;
+    raffle_page.tickets.push(human_record.wallet);
+    raffle_epoch.ticket_count = ticket_index
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    human_record.last_raffle_epoch = raffle_epoch.epoch;
+
+    emit!(RaffleEntered {
+        epoch: raffle_epoch.epoch,
+        wallet: human_record.wallet,
+        ticket_index,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct OpenRafflePage<'info> {
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + RaffleEpoch::INIT_SPACE,
+        seeds = [RaffleEpoch::SEED, &epoch.to_le_bytes()],
+        bump,
+    )]
+    pub raffle_epoch: Box<Account<'info, RaffleEpoch>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RaffleTicketPage::INIT_SPACE,
+        seeds = [
+            RaffleTicketPage::SEED,
+            &epoch.to_le_bytes(),
+            &raffle_epoch.page_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub raffle_page: Box<Account<'info, RaffleTicketPage>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds the next ticket page to the current raffle epoch, creating the epoch
+/// with its first page. Permissionless; a page is only added once every
+/// earlier one is full.
+pub fn handler_open_raffle_page(ctx: Context<OpenRafflePage>, epoch: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(epoch == RaffleEpoch::epoch_at(now), YourFunError::RaffleEpochMismatch);
+
+    let raffle_epoch = &mut ctx.accounts.raffle_epoch;
+    if raffle_epoch.bump == 0 {
+        raffle_epoch.epoch = epoch;
+        raffle_epoch.bump = ctx.bumps.raffle_epoch;
+    }
+    require!(
+        raffle_epoch.ticket_count
+            == raffle_epoch.page_count as u64 * RaffleTicketPage::TICKETS_PER_PAGE as u64,
+        YourFunError::RafflePageNotFull
+    );
+
+    let raffle_page = &mut ctx.accounts.raffle_page;
+    raffle_page.epoch = epoch;
+    raffle_page.page = raffle_epoch.page_count;
+    raffle_page.tickets = Vec::new();
+    raffle_page.bump = ctx.bumps.raffle_page;
+    raffle_epoch.page_count += 1;
+
+    msg!("Raffle epoch {} page {} opened", epoch, raffle_page.page);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundRaffle<'info> {
+    #[account(
+        mut,
+        seeds = [RaffleEpoch::SEED, &raffle_epoch.epoch.to_le_bytes()],
+        bump = raffle_epoch.bump,
+        constraint = !raffle_epoch.is_drawn() @ YourFunError::RaffleAlreadyDrawn,
+        constraint = raffle_epoch.ticket_count > 0 @ YourFunError::RaffleNoTickets,
+    )]
+    pub raffle_epoch: Box<Account<'info, RaffleEpoch>>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds `amount` lamports to a raffle's prize. Anyone may fund a raffle that
+/// has tickets and is not yet drawn, typically the treasury.
+pub fn handler_fund_raffle(ctx: Context<FundRaffle>, amount: u64) -> Result<()> {
+    require!(amount > 0, YourFunError::ZeroAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.raffle_epoch.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let raffle_epoch = &mut ctx.accounts.raffle_epoch;
+    raffle_epoch.prize_lamports = raffle_epoch
+        .prize_lamports
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(RaffleFunded {
+        epoch: raffle_epoch.epoch,
+        funder: ctx.accounts.funder.key(),
+        amount,
+        prize_lamports: raffle_epoch.prize_lamports,
+    });
+
+    msg!("Raffle epoch {} funded with {} lamports", raffle_epoch.epoch, amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitRaffle<'info> {
+    #[account(
+        mut,
+        seeds = [RaffleEpoch::SEED, &raffle_epoch.epoch.to_le_bytes()],
+        bump = raffle_epoch.bump,
+        constraint = !raffle_epoch.is_drawn() @ YourFunError::RaffleAlreadyDrawn,
+        constraint = raffle_epoch.ticket_count > 0 @ YourFunError::RaffleNoTickets,
+    )]
+    pub raffle_epoch: Box<Account<'info, RaffleEpoch>>,
+}
+
+/// Fixes the slot whose hash will seed the draw, `DRAW_DELAY_SLOTS` after the
+/// current one. Permissionless, once the epoch has ended, so the ticket count
+/// can no longer change. A commitment whose slot was skipped, or left
+/// undrawn until SlotHashes no longer holds it, may be renewed.
+pub fn handler_commit_raffle(ctx: Context<CommitRaffle>) -> Result<()> {
+    let clock = Clock::get()?;
+    let raffle_epoch = &mut ctx.accounts.raffle_epoch;
+    require!(clock.unix_timestamp >= raffle_epoch.ends_at(), YourFunError::RaffleEpochOpen);
+    require!(
+        raffle_epoch.draw_slot == 0
+            || clock.slot >= raffle_epoch.draw_slot + RaffleEpoch::SLOT_HASHES_DEPTH,
+        YourFunError::RaffleAlreadyCommitted
+    );
+
+    raffle_epoch.draw_slot = clock.slot + RaffleEpoch::DRAW_DELAY_SLOTS;
+
+    emit!(RaffleCommitted {
+        epoch: raffle_epoch.epoch,
+        ticket_count: raffle_epoch.ticket_count,
+        draw_slot: raffle_epoch.draw_slot,
+    });
+
+    msg!("Raffle epoch {} draws from slot {}", raffle_epoch.epoch, raffle_epoch.draw_slot);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DrawRaffle<'info> {
+    #[account(
+        mut,
+        seeds = [RaffleEpoch::SEED, &raffle_epoch.epoch.to_le_bytes()],
+        bump = raffle_epoch.bump,
+        constraint = !raffle_epoch.is_drawn() @ YourFunError::RaffleAlreadyDrawn,
+    )]
+    pub raffle_epoch: Box<Account<'info, RaffleEpoch>>,
+
+    /// The page holding the winning ticket.
+    #[account(
+        seeds = [
+            RaffleTicketPage::SEED,
+            &raffle_epoch.epoch.to_le_bytes(),
+            &winning_page.page.to_le_bytes(),
+        ],
+        bump = winning_page.bump,
+    )]
+    pub winning_page: Box<Account<'info, RaffleTicketPage>>,
+
+    /// CHECK: The SlotHashes sysvar, pinned by address.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+/// Draws the raffle's winner from the hash of the committed slot.
+/// Permissionless.
+///
+/// The winning index is the first 8 bytes of
+/// `hashv([slot_hash, epoch, ticket_count])` modulo the ticket count. Bias:
+/// the modulo bias is below `ticket_count / 2^64`, negligible for any
+/// reachable count. The slot was in the future when committed, so neither
+/// the committer nor the entrants, who were fixed when the epoch ended, can
+/// choose it. The leader producing that slot could withhold it, and anyone
+/// could decline to draw until the commitment expires and is renewed; either
+/// only trades one unknown hash for another, not for a chosen winner.
+pub fn handler_draw_raffle(ctx: Context<DrawRaffle>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let raffle_epoch = &mut ctx.accounts.raffle_epoch;
+    require!(
+        raffle_epoch.draw_slot != 0 && slot > raffle_epoch.draw_slot,
+        YourFunError::RaffleDrawNotReady
+    );
+
+    let slot_hash = find_slot_hash(&ctx.accounts.slot_hashes, raffle_epoch.draw_slot)?
+        .ok_or(YourFunError::RaffleCommitmentExpired)?;
+    let winner_index = draw_index(&slot_hash, raffle_epoch.epoch, raffle_epoch.ticket_count);
+
+    let per_page = RaffleTicketPage::TICKETS_PER_PAGE as u64;
+    let winning_page = &ctx.accounts.winning_page;
+    require!(
+        winning_page.page as u64 == winner_index / per_page,
+        YourFunError::RaffleWrongPage
+    );
+    let winner = winning_page.tickets[(winner_index % per_page) as usize];
+
+    raffle_epoch.winner_index = winner_index;
+    raffle_epoch.winner = winner;
+
+    emit!(RaffleDrawn {
+        epoch: raffle_epoch.epoch,
+        winner,
+        winner_index,
+        ticket_count: raffle_epoch.ticket_count,
+        slot_hash,
+    });
+
+    msg!("Raffle epoch {} won by ticket {}", raffle_epoch.epoch, winner_index);
+    Ok(())
+}
+
+/// The winning ticket index for a draw seeded by `slot_hash`.
+fn draw_index(slot_hash: &[u8; 32], epoch: u64, ticket_count: u64) -> u64 {
+    let seed = hashv(&[slot_hash, &epoch.to_le_bytes(), &ticket_count.to_le_bytes()]).to_bytes();
+    let mut head = [0u8; 8];
+    head.copy_from_slice(&seed[..8]);
+    u64::from_le_bytes(head) % ticket_count
+}
+
+/// Looks `slot` up in the SlotHashes sysvar, read in place since the sysvar
+/// is too large to deserialize: a little-endian `u64` entry count followed by
+/// `(slot, hash)` entries, newest first.
+fn find_slot_hash(slot_hashes: &AccountInfo, slot: u64) -> Result<Option<[u8; 32]>> {
+    const ENTRY_LEN: usize = 8 + 32;
+
+    let data = slot_hashes.try_borrow_data()?;
+    let count = data
+        .get(..8)
+        .ok_or(ProgramError::InvalidAccountData)?
+        .try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidAccountData)? as usize;
+    for entry in data[8..].chunks_exact(ENTRY_LEN).take(count) {
+        let entry_slot = entry[..8]
+            .try_into()
+            .map(u64::from_le_bytes)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        if entry_slot == slot {
+            let mut hash = [0u8; 32];
+            hash.copy_from_slice(&entry[8..]);
+            return Ok(Some(hash));
+        }
+        if entry_slot < slot {
+            break;
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Accounts)]
+pub struct ClaimRafflePrize<'info> {
+    #[account(
+        mut,
+        seeds = [RaffleEpoch::SEED, &raffle_epoch.epoch.to_le_bytes()],
+        bump = raffle_epoch.bump,
+        has_one = winner @ YourFunError::Unauthorized,
+        constraint = !raffle_epoch.claimed @ YourFunError::RafflePrizeAlreadyClaimed,
+    )]
+    pub raffle_epoch: Box<Account<'info, RaffleEpoch>>,
+
+    #[account(mut)]
+    pub winner: Signer<'info>,
+}
+
+/// Pays the raffle's prize to its winner, once. Allowed while paused, since
+/// the prize is already won.
+pub fn handler_claim_raffle_prize(ctx: Context<ClaimRafflePrize>) -> Result<()> {
+    let amount = ctx.accounts.raffle_epoch.prize_lamports;
+
+    let raffle_info = ctx.accounts.raffle_epoch.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(raffle_info.data_len());
+    let remaining = raffle_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    require!(remaining >= rent_exempt, YourFunError::InsufficientFunds);
+
+    let winner_info = ctx.accounts.winner.to_account_info();
+    **raffle_info.try_borrow_mut_lamports()? = remaining;
+    **winner_info.try_borrow_mut_lamports()? = winner_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let raffle_epoch = &mut ctx.accounts.raffle_epoch;
+    raffle_epoch.claimed = true;
+
+    emit!(RafflePrizeClaimed {
+        epoch: raffle_epoch.epoch,
+        winner: raffle_epoch.winner,
+        amount,
+    });
+
+    msg!("Raffle epoch {} prize of {} lamports claimed", raffle_epoch.epoch, amount);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    const EPOCH: u64 = (NOW / RaffleEpoch::DURATION) as u64;
+
+    /// Each wallet is a verified human with `sessions` open sessions.
+    fn start(humans: &[(&Keypair, u64)]) -> Harness {
+        let mut builder = FixtureBuilder::new();
+        for (wallet, sessions) in humans {
+            builder = builder.with_verified_human(wallet.pubkey());
+            for _ in 0..*sessions {
+                builder = builder.with_open_session();
+            }
+        }
+        let mut harness = Harness::start(builder.build());
+        for (wallet, _) in humans {
+            harness.fund(&wallet.pubkey(), 1_000_000_000);
+        }
+        harness
+    }
+
+    fn close(harness: &mut Harness, owner: &Keypair, session_index: u64) -> std::result::Result<(), InstructionError> {
+        let ix = client::build_close_session_ix(
+            &owner.pubkey(),
+            &owner.pubkey(),
+            session_index,
+            0,
+            false,
+            None,
+            Some((EPOCH, 0)),
+        );
+        harness.process(ix, &[owner])
+    }
+
+    #[test]
+    fn the_draw_index_is_spread_evenly_and_fixed_by_its_inputs() {
+        const DRAWS: u64 = 30_000;
+        let mut counts = [0u64; 3];
+        for draw in 0..DRAWS {
+            let slot_hash = hashv(&[&draw.to_le_bytes()]).to_bytes();
+            counts[draw_index(&slot_hash, EPOCH, 3) as usize] += 1;
+        }
+        // Every ticket wins about a third of the draws, within 3%.
+        for count in counts {
+            assert!(count.abs_diff(DRAWS / 3) < DRAWS / 100, "{counts:?}");
+        }
+
+        // Once the slot hash is known, the draw depends only on the epoch and
+        // the ticket count, both fixed before it.
+        let slot_hash = [7u8; 32];
+        assert_eq!(draw_index(&slot_hash, EPOCH, 1_000), draw_index(&slot_hash, EPOCH, 1_000));
+        assert_eq!(draw_index(&slot_hash, EPOCH, 1), 0);
+        let per_count: Vec<u64> = (990..1_000).map(|count| draw_index(&slot_hash, EPOCH, count)).collect();
+        assert!(per_count.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn a_human_holds_at_most_one_ticket_per_epoch() {
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&[(&alice, 2), (&bob, 1)]);
+        let payer = harness.wallet();
+
+        assert_eq!(
+            harness.process(client::build_open_raffle_page_ix(&payer.pubkey(), EPOCH + 1, 0), &[&payer]),
+            Err(custom(YourFunError::RaffleEpochMismatch))
+        );
+        harness.process(client::build_open_raffle_page_ix(&payer.pubkey(), EPOCH, 0), &[&payer]).unwrap();
+        // A second page only opens once the first is full.
+        assert_eq!(
+            harness.process(client::build_open_raffle_page_ix(&payer.pubkey(), EPOCH, 1), &[&payer]),
+            Err(custom(YourFunError::RafflePageNotFull))
+        );
+
+        harness.warp_to(NOW + 300);
+        close(&mut harness, &alice, 0).unwrap();
+        close(&mut harness, &alice, 1).unwrap();
+        close(&mut harness, &bob, 0).unwrap();
+
+        let raffle: RaffleEpoch = harness.account(&client::raffle_epoch_address(EPOCH));
+        assert_eq!((raffle.ticket_count, raffle.page_count), (2, 1));
+        let page: RaffleTicketPage = harness.account(&client::raffle_page_address(EPOCH, 0));
+        assert_eq!(page.tickets, vec![alice.pubkey(), bob.pubkey()]);
+        let record: HumanRecord = harness.account(&client::human_record_address(&alice.pubkey()));
+        assert_eq!(record.last_raffle_epoch, EPOCH);
+    }
+
+    #[test]
+    fn the_draw_uses_the_committed_slot_and_the_prize_is_claimed_once() {
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&[(&alice, 1), (&bob, 1)]);
+        let funder = harness.wallet();
+        let raffle_address = client::raffle_epoch_address(EPOCH);
+
+        harness.process(client::build_open_raffle_page_ix(&funder.pubkey(), EPOCH, 0), &[&funder]).unwrap();
+        assert_eq!(
+            harness.process(client::build_fund_raffle_ix(&funder.pubkey(), EPOCH, 1_000_000_000), &[&funder]),
+            Err(custom(YourFunError::RaffleNoTickets))
+        );
+        harness.warp_to(NOW + 300);
+        close(&mut harness, &alice, 0).unwrap();
+        close(&mut harness, &bob, 0).unwrap();
+        harness.process(client::build_fund_raffle_ix(&funder.pubkey(), EPOCH, 1_000_000_000), &[&funder]).unwrap();
+
+        // Nothing is committed while entrants can still join.
+        assert_eq!(
+            harness.process(client::build_commit_raffle_ix(EPOCH), &[]),
+            Err(custom(YourFunError::RaffleEpochOpen))
+        );
+        assert_eq!(
+            harness.process(client::build_draw_raffle_ix(EPOCH, 0), &[]),
+            Err(custom(YourFunError::RaffleDrawNotReady))
+        );
+
+        let ends_at = harness.account::<RaffleEpoch>(&raffle_address).ends_at();
+        harness.warp_to(ends_at);
+        harness.warp_to_slot(1_000);
+        harness.process(client::build_commit_raffle_ix(EPOCH), &[]).unwrap();
+        let draw_slot = harness.account::<RaffleEpoch>(&raffle_address).draw_slot;
+        assert_eq!(draw_slot, 1_000 + RaffleEpoch::DRAW_DELAY_SLOTS);
+        assert_eq!(
+            harness.process(client::build_commit_raffle_ix(EPOCH), &[]),
+            Err(custom(YourFunError::RaffleAlreadyCommitted))
+        );
+
+        // The committed slot's hash is unknown until after it.
+        harness.warp_to_slot(draw_slot);
+        assert_eq!(
+            harness.process(client::build_draw_raffle_ix(EPOCH, 0), &[]),
+            Err(custom(YourFunError::RaffleDrawNotReady))
+        );
+        harness.warp_to_slot(draw_slot + 1);
+        harness.set_slot_hash(draw_slot - 1, [9; 32]);
+        assert_eq!(
+            harness.process(client::build_draw_raffle_ix(EPOCH, 0), &[]),
+            Err(custom(YourFunError::RaffleCommitmentExpired))
+        );
+
+        let slot_hash = [9u8; 32];
+        harness.set_slot_hash(draw_slot, slot_hash);
+        let winner_index = draw_index(&slot_hash, EPOCH, 2);
+        // A page other than the winning ticket's is refused.
+        let page = 1u32;
+        let (_, bump) = Pubkey::find_program_address(
+            &[RaffleTicketPage::SEED, &EPOCH.to_le_bytes(), &page.to_le_bytes()],
+            &crate::ID,
+        );
+        harness.set_account(FixtureAccount::new(
+            client::raffle_page_address(EPOCH, page),
+            &RaffleTicketPage { epoch: EPOCH, page, tickets: vec![alice.pubkey()], bump },
+        ));
+        assert_eq!(
+            harness.process(client::build_draw_raffle_ix(EPOCH, page), &[]),
+            Err(custom(YourFunError::RaffleWrongPage))
+        );
+        harness.process(client::build_draw_raffle_ix(EPOCH, 0), &[]).unwrap();
+        assert_eq!(
+            harness.process(client::build_draw_raffle_ix(EPOCH, 0), &[]),
+            Err(custom(YourFunError::RaffleAlreadyDrawn))
+        );
+        assert_eq!(
+            harness.process(client::build_fund_raffle_ix(&funder.pubkey(), EPOCH, 1_000_000_000), &[&funder]),
+            Err(custom(YourFunError::RaffleAlreadyDrawn))
+        );
+
+        let raffle: RaffleEpoch = harness.account(&raffle_address);
+        let (winner, loser) = if winner_index == 0 { (&alice, &bob) } else { (&bob, &alice) };
+        assert_eq!((raffle.winner_index, raffle.winner), (winner_index, winner.pubkey()));
+
+        assert_eq!(
+            harness.process(client::build_claim_raffle_prize_ix(&loser.pubkey(), EPOCH), &[loser]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+        let before = harness.balance(&winner.pubkey());
+        harness.process(client::build_claim_raffle_prize_ix(&winner.pubkey(), EPOCH), &[winner]).unwrap();
+        assert_eq!(harness.balance(&winner.pubkey()), before + 1_000_000_000);
+        assert_eq!(
+            harness.process(client::build_claim_raffle_prize_ix(&winner.pubkey(), EPOCH), &[winner]),
+            Err(custom(YourFunError::RafflePrizeAlreadyClaimed))
+        );
+    }
+}
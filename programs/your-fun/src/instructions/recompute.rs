@@ -0,0 +1,242 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, AdminAction, AdminActionKind, SessionAccount, ScoringProfile};
+use crate::instructions::admin::record_admin_action;
+use crate::instructions::interaction::{load_interaction_log, session_score_increment};
+use crate::error::YourFunError;
+use crate::events::SessionScoreRecomputed;
+
+#[derive(Accounts)]
+pub struct RecomputeSessionScore<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            session.human_record.as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rescores an open session from its interaction logs with the current
+/// scoring rules and overwrites `session_score`, so sessions opened before a
+/// scoring fix or policy change pick it up. With `target_scoring_profile`
+/// the session is also moved to that profile. Authority only.
+///
+/// Remaining accounts must be the session's InteractionLogs for indices
+/// `0..interaction_count`, in order; a session with unlogged or bundled
+/// interactions cannot be rescored. Logs do not record the topic active at
+/// the time, so neither can a session that covered more than one topic.
+/// Private logs earn the registry's current `private_score_bps`.
+pub fn handler_recompute_session_score(
+    ctx: Context<RecomputeSessionScore>,
+    target_scoring_profile: Option<u8>,
+) -> Result<()> {
+    let session = &ctx.accounts.session;
+    require!(session.topic_count <= 1, YourFunError::ScoreRecomputeUnsupported);
+    let scoring_profile = match target_scoring_profile {
+        Some(profile) => ScoringProfile::try_from(profile)? as u8,
+        None => session.scoring_profile,
+    };
+
+    let logs = ctx.remaining_accounts;
+    require!(
+        logs.len() as u64 == session.interaction_count as u64,
+        YourFunError::MissingInteractionLog
+    );
+    let session_key = session.key();
+    let mut new_score = 0u64;
+    for (index, info) in logs.iter().enumerate() {
+        let interaction = load_interaction_log(info, &session_key)?;
+        if interaction.interaction_index as usize != index {
+            msg!("Interaction log for index {} is missing", index);
+            return err!(YourFunError::MissingInteractionLog);
+        }
+        let increment = session_score_increment(
+            &ctx.accounts.registry,
+            scoring_profile,
+            session.booster_multiplier_bps,
+            interaction.interaction_type,
+            interaction.score,
+            interaction.duration_seconds,
+            interaction.private,
+        )?;
+        new_score = new_score
+            .checked_add(increment)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+
+    let session = &mut ctx.accounts.session;
+    let old_score = session.session_score;
+    session.session_score = new_score;
+    session.scoring_profile = scoring_profile;
+    if session.topic_count == 1 {
+        session.topics[0].score = new_score;
+    }
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::RecomputeSessionScore,
+        ctx.accounts.authority.key(),
+        &(session_key, target_scoring_profile, old_score, new_score),
+    )?;
+
+    emit!(SessionScoreRecomputed {
+        session: session_key,
+        owner: session.owner,
+        session_index: session.session_index,
+        scoring_profile,
+        old_score,
+        new_score,
+    });
+
+    msg!(
+        "Session {} rescored from {} to {}",
+        session.session_index,
+        old_score,
+        new_score
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::InteractionType;
+
+    /// A session holding a logged chat, quiz and chat interaction.
+    fn start(authority: &Keypair, owner: &Keypair) -> Harness {
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&owner.pubkey(), 1_000_000_000);
+        let types = [InteractionType::Chat, InteractionType::Quiz, InteractionType::Chat];
+        for (index, interaction_type) in types.into_iter().enumerate() {
+            let ix = client::build_record_interaction_ix(
+                &owner.pubkey(),
+                0,
+                index as u32,
+                [index as u8 + 1; 32],
+                interaction_type as u8,
+                60 + 10 * index as u8,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            );
+            harness.process(ix, &[owner]).unwrap();
+        }
+        harness
+    }
+
+    fn recompute(harness: &mut Harness, authority: &Keypair, owner: &Keypair, logs: u32, profile: Option<u8>) -> Instruction {
+        let count = harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count;
+        client::build_recompute_session_score_ix(&authority.pubkey(), count, &owner.pubkey(), 0, logs, profile)
+    }
+
+    fn rescore(
+        harness: &mut Harness,
+        authority: &Keypair,
+        owner: &Keypair,
+        profile: Option<u8>,
+    ) -> std::result::Result<(u64, u64), InstructionError> {
+        let ix = recompute(harness, authority, owner, 3, profile);
+        let events = harness.process_with_events::<SessionScoreRecomputed>(ix, &[authority])?;
+        Ok((events[0].old_score, events[0].new_score))
+    }
+
+    #[test]
+    fn rescoring_under_the_same_rules_changes_nothing_and_a_new_profile_rescores() {
+        let (authority, owner, stranger) = (Keypair::new(), Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &owner);
+        harness.fund(&stranger.pubkey(), 1_000_000_000);
+        let session = client::session_address(&client::human_record_address(&owner.pubkey()), 0);
+        let scored = harness.account::<SessionAccount>(&session).session_score;
+
+        assert_eq!(
+            rescore(&mut harness, &stranger, &owner, None),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        assert_eq!(rescore(&mut harness, &authority, &owner, None), Ok((scored, scored)));
+
+        // Chat-heavy scoring boosts the two chats and leaves the quiz alone.
+        let (old, rescored) = rescore(&mut harness, &authority, &owner, Some(ScoringProfile::ChatHeavy as u8)).unwrap();
+        assert_eq!(old, scored);
+        assert!(rescored > scored);
+        let state: SessionAccount = harness.account(&session);
+        assert_eq!((state.session_score, state.topics[0].score), (rescored, rescored));
+        assert_eq!(state.scoring_profile, ScoringProfile::ChatHeavy as u8);
+        // The session keeps its new profile.
+        assert_eq!(rescore(&mut harness, &authority, &owner, None), Ok((rescored, rescored)));
+        assert_eq!(rescore(&mut harness, &authority, &owner, Some(0)), Ok((rescored, scored)));
+
+        assert_eq!(
+            rescore(&mut harness, &authority, &owner, Some(ScoringProfile::ExerciseHeavy as u8 + 1)),
+            Err(custom(YourFunError::InvalidScoringProfile))
+        );
+    }
+
+    #[test]
+    fn every_log_must_be_passed_in_order_and_the_session_open() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &owner);
+
+        let ix = recompute(&mut harness, &authority, &owner, 2, None);
+        assert_eq!(harness.process(ix, &[&authority]), Err(custom(YourFunError::MissingInteractionLog)));
+        let mut ix = recompute(&mut harness, &authority, &owner, 3, None);
+        let last = ix.accounts.len() - 1;
+        ix.accounts.swap(last - 1, last);
+        assert_eq!(harness.process(ix, &[&authority]), Err(custom(YourFunError::MissingInteractionLog)));
+
+        let close = client::build_close_session_ix(&owner.pubkey(), &owner.pubkey(), 0, 0, false, None, None);
+        harness.process(close, &[&owner]).unwrap();
+        assert_eq!(
+            rescore(&mut harness, &authority, &owner, None),
+            Err(custom(YourFunError::SessionInactive))
+        );
+    }
+}
@@ -1,83 +1,768 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{PlatformRegistry, HumanRecord};
+use anchor_lang::solana_program::hash::hash;
+use crate::state::{
+    PlatformRegistry, HumanRecord, FingerprintData, Tombstone, InviteEntry, VerificationStatus,
+    InsuranceFund, MatchingCampaign, ScholarshipPool, Promo, PromoRedemption,
+};
+use crate::instructions::compromised::require_not_compromised;
+use crate::instructions::fee_vault::{credit_fee_vault, load_fee_vault};
+use crate::instructions::matching::match_fee;
+use crate::instructions::promo::redeem_promo;
+use crate::instructions::scholarship::move_lamports;
+use crate::instructions::partner::{accrue_partner_revenue, load_personality_config};
 use crate::error::YourFunError;
+use crate::scoring::SUB_SCORE_COUNT;
+use crate::events::HumanRegistered;
 
 #[derive(Accounts)]
+#[instruction(challenge_nonce: [u8; 32], fingerprint_data: Vec<u8>)]
 pub struct RegisterHuman<'info> {
     #[account(
         mut,
         seeds = [PlatformRegistry::SEED],
         bump = registry.bump,
     )]
-    pub registry: Account<'info, PlatformRegistry>,
+    pub registry: Box<Account<'info, PlatformRegistry>>,
 
     #[account(
         init,
         payer = user,
-        space = HumanRecord::SIZE,
+        space = 8 + HumanRecord::INIT_SPACE,
         seeds = [HumanRecord::SEED, user.key().as_ref()],
         bump,
     )]
-    pub human_record: Account<'info, HumanRecord>,
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + FingerprintData::space_for(fingerprint_data.len()),
+        seeds = [FingerprintData::SEED, human_record.key().as_ref()],
+        bump,
+    )]
+    pub fingerprint: Box<Account<'info, FingerprintData>>,
+
+    /// Tombstone left by a previously closed record for this wallet, if any.
+    /// CHECK: Address is pinned by seeds; contents are read only when the
+    /// account is owned by this program.
+    #[account(
+        mut,
+        seeds = [Tombstone::SEED, user.key().as_ref()],
+        bump,
+    )]
+    pub tombstone: UncheckedAccount<'info>,
+
+    /// The user's invite; required, and consumed, only while registration
+    /// is permissioned.
+    #[account(
+        mut,
+        seeds = [InviteEntry::SEED, user.key().as_ref()],
+        bump = invite.bump,
+    )]
+    pub invite: Option<Box<Account<'info, InviteEntry>>>,
+
+    /// CompromisedFingerprint address for the submitted fingerprint; normally empty.
+    /// CHECK: Address and contents are checked by `require_not_compromised`.
+    pub compromised: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub user: Signer<'info>,
 
     /// The treasury account that receives the verification fee.
-    /// CHECK: This is validated against the registry authority.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: AccountInfo<'info>,
+
+    /// Receives the insurance share of the fee; required while one is set.
     #[account(
         mut,
-        constraint = fee_receiver.key() == registry.authority @ YourFunError::AuthorityMismatch
+        seeds = [InsuranceFund::SEED],
+        bump = insurance_fund.bump,
     )]
-    pub fee_receiver: AccountInfo<'info>,
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    /// A sponsor's matching campaign; with `scholarship_pool`, matches the
+    /// fee into the pool while the campaign runs.
+    #[account(
+        mut,
+        seeds = [MatchingCampaign::SEED, matching_campaign.matcher.as_ref()],
+        bump = matching_campaign.bump,
+    )]
+    pub matching_campaign: Option<Box<Account<'info, MatchingCampaign>>>,
+
+    #[account(
+        mut,
+        seeds = [ScholarshipPool::SEED],
+        bump = scholarship_pool.bump,
+    )]
+    pub scholarship_pool: Option<Box<Account<'info, ScholarshipPool>>>,
+
+    /// Promo code to discount the fee with; its code is passed as
+    /// `promo_code`.
+    #[account(
+        mut,
+        seeds = [Promo::SEED, promo.code_hash.as_ref()],
+        bump = promo.bump,
+    )]
+    pub promo: Option<Box<Account<'info, Promo>>>,
+
+    /// Marks the wallet's redemption of `promo`; creating it fails if the
+    /// wallet already redeemed the code.
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PromoRedemption::INIT_SPACE,
+        seeds = [
+            PromoRedemption::SEED,
+            promo.as_ref().map(|promo| promo.key()).unwrap_or_default().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub promo_redemption: Option<Box<Account<'info, PromoRedemption>>>,
 
     pub system_program: Program<'info, System>,
 }
 
+/// Registers the user's wallet and collects the verification fee, holding
+/// the registry's verifier share on the new record until it is verified. A promo
+/// and its code discount the fee, once per wallet and code. When a matching
+/// campaign and the scholarship pool are supplied, the campaign matches the
+/// fee paid into the pool as far as its cap and escrow allow.
 pub fn handler_register_human(
     ctx: Context<RegisterHuman>,
     challenge_nonce: [u8; 32],
-    fingerprint_hash: [u8; 32],
+    fingerprint_data: Vec<u8>,
+    promo_code: Option<Vec<u8>>,
 ) -> Result<()> {
     let registry = &ctx.accounts.registry;
+    let clock = Clock::get()?;
+
+    let fingerprint_hash = check_registration(
+        registry,
+        &challenge_nonce,
+        &fingerprint_data,
+        &ctx.accounts.compromised,
+        ctx.accounts.invite.as_deref_mut(),
+        clock.unix_timestamp,
+    )?;
 
-    require!(!registry.is_paused, YourFunError::RegistryPaused);
+    let tombstone = consume_tombstone(
+        &ctx.accounts.tombstone,
+        &ctx.accounts.user,
+        registry.reregistration_cooldown,
+        clock.unix_timestamp,
+    )?;
 
-    if registry.verification_fee_lamports > 0 {
+    validate_fee_receiver(&ctx.accounts.fee_receiver, registry)?;
+
+    let fee_paid = match ctx.accounts.promo.as_deref_mut() {
+        Some(promo) => {
+            let (Some(code), Some(redemption)) =
+                (promo_code.as_deref(), ctx.accounts.promo_redemption.as_deref_mut())
+            else {
+                return err!(YourFunError::PromoCodeRequired);
+            };
+            redeem_promo(
+                promo,
+                redemption,
+                ctx.bumps.promo_redemption.ok_or(YourFunError::PromoCodeRequired)?,
+                code,
+                ctx.accounts.user.key(),
+                registry.effective_verification_fee(),
+                clock.unix_timestamp,
+            )?
+        }
+        None => {
+            require!(
+                ctx.accounts.promo_redemption.is_none(),
+                YourFunError::PromoCodeRequired
+            );
+            registry.effective_verification_fee()
+        }
+    };
+    let verifier_fee = registry.verifier_fee_share(fee_paid);
+    collect_fee(
+        registry,
+        &ctx.accounts.user,
+        &ctx.accounts.fee_receiver,
+        ctx.accounts.insurance_fund.as_deref_mut(),
+        None,
+        &ctx.accounts.system_program,
+        fee_paid - verifier_fee,
+    )?;
+    if verifier_fee > 0 {
         system_program::transfer(
             CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
                 system_program::Transfer {
                     from: ctx.accounts.user.to_account_info(),
-                    to: ctx.accounts.fee_receiver.to_account_info(),
+                    to: ctx.accounts.human_record.to_account_info(),
                 },
             ),
-            registry.verification_fee_lamports,
+            verifier_fee,
         )?;
     }
 
-    let clock = Clock::get()?;
-    let human_record = &mut ctx.accounts.human_record;
+    if let (Some(campaign), Some(pool)) = (
+        ctx.accounts.matching_campaign.as_deref_mut(),
+        ctx.accounts.scholarship_pool.as_deref_mut(),
+    ) {
+        match_fee(campaign, pool, ctx.accounts.user.key(), fee_paid, clock.unix_timestamp)?;
+    }
+
+    init_registration(
+        &mut ctx.accounts.human_record,
+        ctx.bumps.human_record,
+        &mut ctx.accounts.fingerprint,
+        ctx.bumps.fingerprint,
+        ctx.accounts.user.key(),
+        challenge_nonce,
+        fingerprint_hash,
+        fingerprint_data,
+        tombstone.as_ref(),
+        fee_paid,
+        verifier_fee,
+        clock.unix_timestamp,
+    );
+    Ok(())
+}
 
-    human_record.wallet = ctx.accounts.user.key();
+/// Validates a registration request, consuming the invite while
+/// registration is permissioned, and returns the fingerprint hash.
+pub(crate) fn check_registration(
+    registry: &PlatformRegistry,
+    challenge_nonce: &[u8; 32],
+    fingerprint_data: &[u8],
+    compromised: &UncheckedAccount,
+    invite: Option<&mut Account<InviteEntry>>,
+    now: i64,
+) -> Result<[u8; 32]> {
+    registry.require_unpaused(PlatformRegistry::PAUSE_REGISTRATIONS, now)?;
+
+    require!(!fingerprint_data.is_empty(), YourFunError::FingerprintEmpty);
+    require!(
+        fingerprint_data.len() <= registry.fingerprint_len_limit(),
+        YourFunError::FingerprintTooLong
+    );
+    require!(
+        challenge_nonce.iter().any(|&b| b != 0),
+        YourFunError::ZeroChallengeNonce
+    );
+    require!(
+        fingerprint_data.iter().any(|&b| b != 0),
+        YourFunError::ZeroFingerprintHash
+    );
+
+    let fingerprint_hash = hash(fingerprint_data).to_bytes();
+    require!(
+        *challenge_nonce != fingerprint_hash,
+        YourFunError::NonceMatchesFingerprint
+    );
+    require_not_compromised(compromised, &fingerprint_hash)?;
+
+    if registry.permissioned_registration {
+        let invite = invite.ok_or(YourFunError::InviteRequired)?;
+        require!(!invite.consumed, YourFunError::InviteAlreadyConsumed);
+        invite.consumed = true;
+        invite.consumed_at = now;
+    }
+
+    Ok(fingerprint_hash)
+}
+
+/// Writes a freshly registered, not yet verified human record and its
+/// fingerprint, and emits `HumanRegistered`. `verifier_fee` is the part of
+/// `fee_paid` the caller has already moved onto the record.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn init_registration(
+    human_record: &mut Account<HumanRecord>,
+    human_record_bump: u8,
+    fingerprint: &mut Account<FingerprintData>,
+    fingerprint_bump: u8,
+    wallet: Pubkey,
+    challenge_nonce: [u8; 32],
+    fingerprint_hash: [u8; 32],
+    fingerprint_data: Vec<u8>,
+    tombstone: Option<&Tombstone>,
+    fee_paid: u64,
+    verifier_fee: u64,
+    now: i64,
+) {
+    human_record.wallet = wallet;
     human_record.verified_by = Pubkey::default();
     human_record.verified_at = 0;
     human_record.verification_level = 0;
     human_record.fingerprint_hash = fingerprint_hash;
-    human_record.is_active = false;
-    human_record.session_count = 0;
+    human_record.set_active(false);
+    human_record.session_count = tombstone.map_or(0, |t| t.session_count);
     human_record.total_interactions = 0;
-    human_record.last_active_at = clock.unix_timestamp;
+    human_record.last_active_at = now;
     human_record.learning_score = 0;
     human_record.challenge_nonce = challenge_nonce;
-    human_record.bump = ctx.bumps.human_record;
-    human_record._reserved = [0u8; 32];
+    human_record.bump = human_record_bump;
+    human_record.registered_at = now;
+    human_record.strikes = tombstone.map_or(0, |t| t.strikes);
+    human_record.revoked_at = 0;
+    human_record.force_closed_sessions = 0;
+    human_record.active_session_count = 0;
+    human_record._reserved = [0u8; 3];
+    human_record.record_version = HumanRecord::CURRENT_VERSION;
+    human_record.behavioral_score = 0;
+    human_record.region_code = 0;
+    human_record.verification_status = VerificationStatus::Inactive as u8;
+    human_record.document_issuer = Pubkey::default();
+    human_record.attribute_commitments = [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES];
+    human_record.sub_scores = [0u8; SUB_SCORE_COUNT];
+    human_record.composite_score = 0;
+    human_record.effective_at = 0;
+    human_record.merged_into = Pubkey::default();
+    human_record.supervisor = Pubkey::default();
+    human_record.interaction_delegate = Pubkey::default();
+    human_record.last_raffle_epoch = 0;
+    human_record.last_audited_at = 0;
+    human_record.spending_cap_lamports = 0;
+    human_record.spend_window_start = 0;
+    human_record.spent_in_window = 0;
+    human_record.last_liveness_at = 0;
+    human_record.liveness_decayed_at = 0;
+    human_record.handle_hash = [0u8; 32];
+    human_record.frozen_until = 0;
+    human_record.verifier_fee_escrow = verifier_fee;
+
+    fingerprint.human_record = human_record.key();
+    fingerprint.data = fingerprint_data;
+    fingerprint.bump = fingerprint_bump;
+
+    emit!(HumanRegistered {
+        wallet,
+        human_record: human_record.key(),
+        fee_paid,
+        registered_at: now,
+    });
+
+    msg!("Human registration initiated for wallet: {}", wallet);
+}
+
+/// Transfers `fee` from `payer`, routing the registry's insurance share to
+/// the insurance fund, the partner's share of a session fee to the
+/// personality config, and the rest to the fee receiver.
+pub(crate) fn collect_fee<'info>(
+    registry: &PlatformRegistry,
+    payer: &Signer<'info>,
+    fee_receiver: &AccountInfo<'info>,
+    insurance_fund: Option<&mut Account<'info, InsuranceFund>>,
+    personality_config: Option<&AccountInfo<'info>>,
+    system_program: &Program<'info, System>,
+    fee: u64,
+) -> Result<()> {
+    let insured = registry.insurance_share(fee);
+    if insured > 0 {
+        let insurance_fund = insurance_fund.ok_or(YourFunError::InsuranceFundRequired)?;
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: insurance_fund.to_account_info(),
+                },
+            ),
+            insured,
+        )?;
+        insurance_fund.total_in = insurance_fund
+            .total_in
+            .checked_add(insured)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+
+    let mut remainder = fee - insured;
+    if let Some(info) = personality_config {
+        if let Some(config) = load_personality_config(info)? {
+            let partner_cut = config.partner_share(fee).min(remainder);
+            if partner_cut > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: payer.to_account_info(),
+                            to: info.clone(),
+                        },
+                    ),
+                    partner_cut,
+                )?;
+                accrue_partner_revenue(info, config, partner_cut)?;
+                remainder -= partner_cut;
+            }
+        }
+    }
+
+    if remainder > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: fee_receiver.clone(),
+                },
+            ),
+            remainder,
+        )?;
+        credit_fee_vault(fee_receiver, remainder)?;
+    }
+    Ok(())
+}
+
+/// Moves the verifier's share of the fee held on `human_record` to `to` and
+/// clears it. Returns the amount moved.
+pub(crate) fn release_verifier_fee(human_record: &mut Account<HumanRecord>, to: &AccountInfo) -> Result<u64> {
+    let amount = human_record.verifier_fee_escrow;
+    if amount > 0 {
+        move_lamports(&human_record.to_account_info(), to, amount)?;
+        human_record.verifier_fee_escrow = 0;
+    }
+    Ok(amount)
+}
 
-    msg!(
-        "Human registration initiated for wallet: {}",
-        ctx.accounts.user.key()
+/// Hands the verifier's share still held on `human_record` to the platform
+/// when the registration ends without an approval. The fee receiver is
+/// required only while a share is held.
+pub(crate) fn forfeit_verifier_fee(
+    registry: &PlatformRegistry,
+    human_record: &mut Account<HumanRecord>,
+    fee_receiver: Option<&AccountInfo>,
+) -> Result<()> {
+    if human_record.verifier_fee_escrow == 0 {
+        return Ok(());
+    }
+    let fee_receiver = fee_receiver.ok_or(YourFunError::FeeReceiverRequired)?;
+    validate_fee_receiver(fee_receiver, registry)?;
+    let amount = release_verifier_fee(human_record, fee_receiver)?;
+    credit_fee_vault(fee_receiver, amount)
+}
+
+/// Ensures the fee receiver is the registry's fee wallet (the treasury, or
+/// the authority until one is set) and can take the fee.
+///
+/// A program-owned receiver is accepted only when the treasury has been set
+/// to it and it is the FeeVault; any other fee wallet must be a plain
+/// system-owned wallet carrying no data.
+pub(crate) fn validate_fee_receiver(fee_receiver: &AccountInfo, registry: &PlatformRegistry) -> Result<()> {
+    require_keys_eq!(
+        fee_receiver.key(),
+        registry.fee_wallet(),
+        YourFunError::TreasuryMismatch
+    );
+
+    if load_fee_vault(fee_receiver)?.is_some() {
+        return Ok(());
+    }
+
+    require!(
+        fee_receiver.owner == &system_program::ID && fee_receiver.data_is_empty(),
+        YourFunError::InvalidFeeReceiver
     );
 
     Ok(())
 }
+
+/// Enforces the re-registration cooldown when the wallet has a tombstone and
+/// returns it so its strikes and session count carry into the new record.
+/// Carrying the session count keeps new session PDAs from landing on indices
+/// used by the previous record. The tombstone is closed once consumed,
+/// refunding its rent to the user; a later close writes a fresh one.
+pub(crate) fn consume_tombstone<'info>(
+    tombstone_info: &UncheckedAccount<'info>,
+    user: &Signer<'info>,
+    cooldown: i64,
+    now: i64,
+) -> Result<Option<Tombstone>> {
+    if tombstone_info.owner != &crate::ID || tombstone_info.data_is_empty() {
+        return Ok(None);
+    }
+
+    let tombstone = {
+        let data = tombstone_info.try_borrow_data()?;
+        Tombstone::try_deserialize(&mut &data[..])?
+    };
+
+    let reopens_at = tombstone
+        .closed_at
+        .checked_add(cooldown)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    require!(now >= reopens_at, YourFunError::ReregistrationCooldownActive);
+
+    let user_info = user.to_account_info();
+    let refund = tombstone_info.lamports();
+    **user_info.try_borrow_mut_lamports()? = user_info
+        .lamports()
+        .checked_add(refund)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    **tombstone_info.try_borrow_mut_lamports()? = 0;
+    tombstone_info.assign(&system_program::ID);
+    tombstone_info.realloc(0, false)?;
+
+    Ok(Some(tombstone))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::FeeVault;
+
+    fn registry(authority: Pubkey, treasury: Pubkey) -> PlatformRegistry {
+        let zeroed = vec![0u8; PlatformRegistry::INIT_SPACE];
+        let mut registry = PlatformRegistry::deserialize(&mut &zeroed[..]).unwrap();
+        registry.authority = authority;
+        registry.treasury = treasury;
+        registry
+    }
+
+    fn vault_data() -> Vec<u8> {
+        let (_, bump) = Pubkey::find_program_address(&[FeeVault::SEED], &crate::ID);
+        let vault = FeeVault { total_fees_collected: 0, total_fees_withdrawn: 0, bump };
+        let mut data = Vec::new();
+        vault.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn check(registry: &PlatformRegistry, key: Pubkey, owner: Pubkey, mut data: Vec<u8>) -> Result<()> {
+        let mut lamports = 1_000_000_000;
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+        validate_fee_receiver(&info, registry)
+    }
+
+    #[test]
+    fn fee_receiver_must_be_the_configured_fee_wallet() {
+        let authority = Pubkey::new_unique();
+        let vault = FeeVault::address();
+        let unset = registry(authority, Pubkey::default());
+
+        assert!(check(&unset, authority, system_program::ID, Vec::new()).is_ok());
+        assert_eq!(
+            check(&unset, Pubkey::new_unique(), system_program::ID, Vec::new()).unwrap_err(),
+            YourFunError::TreasuryMismatch.into()
+        );
+        assert_eq!(
+            check(&unset, authority, system_program::ID, vec![0; 8]).unwrap_err(),
+            YourFunError::InvalidFeeReceiver.into()
+        );
+        // The vault is accepted only once the treasury points at it.
+        assert_eq!(
+            check(&unset, vault, crate::ID, vault_data()).unwrap_err(),
+            YourFunError::TreasuryMismatch.into()
+        );
+
+        let to_vault = registry(authority, vault);
+        assert!(check(&to_vault, vault, crate::ID, vault_data()).is_ok());
+        assert_eq!(
+            check(&to_vault, authority, system_program::ID, Vec::new()).unwrap_err(),
+            YourFunError::TreasuryMismatch.into()
+        );
+
+        let program_owned = Pubkey::new_unique();
+        let to_program_owned = registry(authority, program_owned);
+        assert_eq!(
+            check(&to_program_owned, program_owned, crate::ID, vault_data()).unwrap_err(),
+            YourFunError::InvalidFeeReceiver.into()
+        );
+    }
+
+    /// Starts from a registry run by `authority`, which also takes the fees.
+    fn start(authority: &Keypair, configure: impl FnOnce(&mut PlatformRegistry)) -> Harness {
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(configure)
+            .build();
+        Harness::start(accounts)
+    }
+
+    fn register(
+        harness: &mut Harness,
+        authority: &Keypair,
+        user: &Keypair,
+        challenge_nonce: [u8; 32],
+        fingerprint_data: Vec<u8>,
+    ) -> std::result::Result<(), InstructionError> {
+        let ix = client::build_register_human_ix(
+            &user.pubkey(),
+            &authority.pubkey(),
+            false,
+            false,
+            None,
+            None,
+            challenge_nonce,
+            fingerprint_data,
+        );
+        harness.process(ix, &[user])
+    }
+
+    #[test]
+    fn degenerate_nonces_and_fingerprints_are_rejected() {
+        let authority = Keypair::new();
+        let mut harness = start(&authority, |_| {});
+        let user = harness.wallet();
+        let fingerprint = vec![0x5a; 64];
+
+        assert_eq!(
+            register(&mut harness, &authority, &user, [0u8; 32], fingerprint.clone()),
+            Err(custom(YourFunError::ZeroChallengeNonce))
+        );
+        assert_eq!(
+            register(&mut harness, &authority, &user, [7u8; 32], vec![0u8; 64]),
+            Err(custom(YourFunError::ZeroFingerprintHash))
+        );
+        assert_eq!(
+            register(&mut harness, &authority, &user, hash(&fingerprint).to_bytes(), fingerprint),
+            Err(custom(YourFunError::NonceMatchesFingerprint))
+        );
+        assert!(harness
+            .try_account::<HumanRecord>(&client::human_record_address(&user.pubkey()))
+            .is_none());
+    }
+
+    #[test]
+    fn registration_records_registered_at() {
+        let authority = Keypair::new();
+        let mut harness = start(&authority, |_| {});
+        let user = harness.wallet();
+        let registered_at = NOW + 90;
+        harness.warp_to(registered_at);
+
+        register(&mut harness, &authority, &user, [7u8; 32], vec![0x5a; 64]).unwrap();
+        let record: HumanRecord = harness.account(&client::human_record_address(&user.pubkey()));
+        assert_eq!(record.registered_at, registered_at);
+        assert_eq!(record.last_active_at, registered_at);
+        assert_eq!(record.fingerprint_hash, hash(&[0x5a; 64]).to_bytes());
+    }
+
+    #[test]
+    fn reregistration_waits_out_the_cooldown_and_keeps_strikes() {
+        let (authority, user) = (Keypair::new(), Keypair::new());
+        let cooldown = 1_000;
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.reregistration_cooldown = cooldown)
+            .with_region_stats(0)
+            .with_verified_human(user.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&user.pubkey(), 10_000_000_000);
+        let human_record = client::human_record_address(&user.pubkey());
+        let mut record: HumanRecord = harness.account(&human_record);
+        record.strikes = 2;
+        record.session_count = 3;
+        harness.set_account(FixtureAccount::new(human_record, &record));
+
+        let close = client::build_close_human_record_ix(&user.pubkey(), 0, None, None);
+        harness.process(close, &[&user]).unwrap();
+        let tombstone: Tombstone = harness.account(&client::tombstone_address(&user.pubkey()));
+        assert_eq!((tombstone.closed_at, tombstone.strikes), (NOW, 2));
+
+        harness.warp_to(NOW + cooldown - 1);
+        assert_eq!(
+            register(&mut harness, &authority, &user, [7u8; 32], vec![0x5a; 64]),
+            Err(custom(YourFunError::ReregistrationCooldownActive))
+        );
+
+        harness.warp_to(NOW + cooldown);
+        register(&mut harness, &authority, &user, [7u8; 32], vec![0x5a; 64]).unwrap();
+        let record: HumanRecord = harness.account(&human_record);
+        assert_eq!(record.strikes, 2);
+        assert_eq!(record.session_count, 3);
+        assert!(harness
+            .try_account::<Tombstone>(&client::tombstone_address(&user.pubkey()))
+            .is_none());
+    }
+
+    #[test]
+    fn fees_go_to_the_authority_until_a_treasury_is_set() {
+        let (authority, treasury) = (Keypair::new(), Pubkey::new_unique());
+        let fee = 1_000_000;
+        let mut harness = start(&authority, |registry| registry.verification_fee_lamports = fee);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&treasury, 1_000_000_000);
+        let register_paying = |harness: &mut Harness, fee_receiver: &Pubkey| {
+            let user = harness.wallet();
+            let ix = client::build_register_human_ix(
+                &user.pubkey(),
+                fee_receiver,
+                false,
+                false,
+                None,
+                None,
+                [7u8; 32],
+                vec![0x5a; 64],
+            );
+            harness.process(ix, &[&user])
+        };
+        let update_treasury = |harness: &mut Harness, signer: &Keypair| {
+            let registry: PlatformRegistry = harness.account(&client::registry_address());
+            let ix = client::build_co_signed_admin_config_ix(
+                &signer.pubkey(),
+                None,
+                registry.admin_action_count,
+                crate::instruction::UpdateTreasury { treasury },
+            );
+            harness.process(ix, &[signer])
+        };
+
+        // An unset treasury reads as the authority, as on migrated registries.
+        let before = harness.balance(&authority.pubkey());
+        register_paying(&mut harness, &authority.pubkey()).unwrap();
+        assert_eq!(harness.balance(&authority.pubkey()), before + fee);
+        assert_eq!(
+            register_paying(&mut harness, &treasury),
+            Err(custom(YourFunError::TreasuryMismatch))
+        );
+
+        let stranger = harness.wallet();
+        assert_eq!(
+            update_treasury(&mut harness, &stranger),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        update_treasury(&mut harness, &authority).unwrap();
+        let registry: PlatformRegistry = harness.account(&client::registry_address());
+        assert_eq!(registry.treasury, treasury);
+
+        assert_eq!(
+            register_paying(&mut harness, &authority.pubkey()),
+            Err(custom(YourFunError::TreasuryMismatch))
+        );
+        let (before, authority_before) = (harness.balance(&treasury), harness.balance(&authority.pubkey()));
+        register_paying(&mut harness, &treasury).unwrap();
+        assert_eq!(harness.balance(&treasury), before + fee);
+        assert_eq!(harness.balance(&authority.pubkey()), authority_before);
+    }
+
+    #[test]
+    fn fingerprints_up_to_the_limit_are_stored_in_full() {
+        let authority = Keypair::new();
+        let mut harness = start(&authority, |_| {});
+        let limit = PlatformRegistry::DEFAULT_MAX_FINGERPRINT_LEN as usize;
+        let user = harness.wallet();
+
+        assert_eq!(
+            register(&mut harness, &authority, &user, [7u8; 32], Vec::new()),
+            Err(custom(YourFunError::FingerprintEmpty))
+        );
+        assert_eq!(
+            register(&mut harness, &authority, &user, [7u8; 32], vec![0x5a; limit + 1]),
+            Err(custom(YourFunError::FingerprintTooLong))
+        );
+
+        let fingerprint: Vec<u8> = (0..limit).map(|i| i as u8 | 1).collect();
+        register(&mut harness, &authority, &user, [7u8; 32], fingerprint.clone()).unwrap();
+        let human_record = client::human_record_address(&user.pubkey());
+        let record: HumanRecord = harness.account(&human_record);
+        assert_eq!(record.fingerprint_hash, hash(&fingerprint).to_bytes());
+        let stored: FingerprintData = harness.account(&client::fingerprint_address(&human_record));
+        assert_eq!(stored.data, fingerprint);
+    }
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, RegionStats, AdminAction, AdminActionKind, Handle};
+use crate::instructions::admin::record_admin_action;
+use crate::instructions::handle::release_handle;
+use crate::instructions::verify_human::refresh_verification_status;
+use crate::error::YourFunError;
+use crate::events::HumanRevoked;
+
+#[derive(Accounts)]
+pub struct RevokeHuman<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanNotVerified,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    /// Counters of the region recorded on the human.
+    #[account(
+        mut,
+        seeds = [RegionStats::SEED, &human_record.region_code.to_le_bytes()],
+        bump = region_stats.bump,
+    )]
+    pub region_stats: Box<Account<'info, RegionStats>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    /// The human's handle, released by the revocation. Required if one is
+    /// held.
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [Handle::SEED, human_record.handle_hash.as_ref()],
+        bump = handle.bump,
+    )]
+    pub handle: Option<Box<Account<'info, Handle>>>,
+
+    /// Receives the released handle's rent. Anchor checks a close target
+    /// even when the closed account is absent, so it is always required.
+    /// CHECK: Only used as the lamport destination; validated against the record.
+    #[account(
+        mut,
+        constraint = wallet.key() == human_record.wallet @ YourFunError::Unauthorized,
+    )]
+    pub wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deactivates a verified human and removes it from the verified total,
+/// releasing any handle it holds. The record can be reinstated later
+/// through `verify_human`; the handle has to be claimed again.
+pub fn handler_revoke_human(ctx: Context<RevokeHuman>) -> Result<()> {
+    if ctx.accounts.human_record.has_handle() {
+        let handle = ctx.accounts.handle.as_ref().ok_or(YourFunError::HandleAccountRequired)?;
+        release_handle(&mut ctx.accounts.human_record, handle)?;
+    }
+
+    let registry = &mut ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let clock = Clock::get()?;
+
+    human_record.set_active(false);
+    human_record.revoked_at = clock.unix_timestamp;
+    refresh_verification_status(human_record, registry, clock.unix_timestamp);
+
+    registry.total_verified_humans = registry
+        .total_verified_humans
+        .checked_sub(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    registry.uncount_active_human(human_record);
+    if human_record.is_region_counted() {
+        ctx.accounts.region_stats.remove_verified()?;
+        human_record.set_region_counted(false);
+    }
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::RevokeHuman,
+        ctx.accounts.authority.key(),
+        &human_record.wallet,
+    )?;
+
+    emit!(HumanRevoked {
+        wallet: human_record.wallet,
+        revoked_by: ctx.accounts.authority.key(),
+        revoked_at: clock.unix_timestamp,
+    });
+
+    msg!("Human record revoked for wallet: {}", human_record.wallet);
+
+    Ok(())
+}
@@ -0,0 +1,400 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{
+    PlatformRegistry, HumanRecord, FingerprintData, Tombstone, InviteEntry, ScholarshipPool,
+    ScholarshipApproval, InsuranceFund,
+};
+use crate::instructions::register_human::{
+    check_registration, consume_tombstone, init_registration, validate_fee_receiver,
+};
+use crate::instructions::fee_vault::credit_fee_vault;
+use crate::error::YourFunError;
+use crate::events::{ScholarshipApproved, ScholarshipPoolFunded, ScholarshipRedeemed};
+
+#[derive(Accounts)]
+pub struct FundScholarshipPool<'info> {
+    #[account(
+        init_if_needed,
+        payer = donor,
+        space = 8 + ScholarshipPool::INIT_SPACE,
+        seeds = [ScholarshipPool::SEED],
+        bump,
+    )]
+    pub pool: Box<Account<'info, ScholarshipPool>>,
+
+    #[account(mut)]
+    pub donor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Donates `amount` lamports to the scholarship pool. Anyone may donate; the
+/// first donor also pays the pool's rent.
+pub fn handler_fund_scholarship_pool(ctx: Context<FundScholarshipPool>, amount: u64) -> Result<()> {
+    require!(amount > 0, YourFunError::ZeroAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.donor.to_account_info(),
+                to: ctx.accounts.pool.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.bump = ctx.bumps.pool;
+    pool.total_donated = pool
+        .total_donated
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(ScholarshipPoolFunded {
+        donor: ctx.accounts.donor.key(),
+        amount,
+        total_donated: pool.total_donated,
+    });
+
+    msg!("Scholarship pool funded with {} lamports", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ApproveScholarship<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.is_program_officer(&officer.key()) @ YourFunError::Unauthorized,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init_if_needed,
+        payer = officer,
+        space = 8 + ScholarshipApproval::INIT_SPACE,
+        seeds = [ScholarshipApproval::SEED, wallet.as_ref()],
+        bump,
+    )]
+    pub approval: Box<Account<'info, ScholarshipApproval>>,
+
+    /// The authority or the registry's program officer.
+    #[account(mut)]
+    pub officer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approves `wallet` to register with its verification fee paid by the
+/// scholarship pool, for `ScholarshipApproval::VALIDITY` seconds. An unused
+/// approval can be renewed; a used one cannot be reissued.
+pub fn handler_approve_scholarship(ctx: Context<ApproveScholarship>, wallet: Pubkey) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let approval = &mut ctx.accounts.approval;
+    require!(!approval.consumed, YourFunError::ScholarshipAlreadyUsed);
+
+    approval.wallet = wallet;
+    approval.approved_by = ctx.accounts.officer.key();
+    approval.approved_at = now;
+    approval.expires_at = now
+        .checked_add(ScholarshipApproval::VALIDITY)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    approval.bump = ctx.bumps.approval;
+
+    emit!(ScholarshipApproved {
+        wallet,
+        approved_by: approval.approved_by,
+        expires_at: approval.expires_at,
+    });
+
+    msg!("Scholarship approved for wallet: {}", wallet);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(challenge_nonce: [u8; 32], fingerprint_data: Vec<u8>)]
+pub struct RegisterHumanScholarship<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + HumanRecord::INIT_SPACE,
+        seeds = [HumanRecord::SEED, user.key().as_ref()],
+        bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + FingerprintData::space_for(fingerprint_data.len()),
+        seeds = [FingerprintData::SEED, human_record.key().as_ref()],
+        bump,
+    )]
+    pub fingerprint: Box<Account<'info, FingerprintData>>,
+
+    /// Tombstone left by a previously closed record for this wallet, if any.
+    /// CHECK: Address is pinned by seeds; contents are read only when the
+    /// account is owned by this program.
+    #[account(
+        mut,
+        seeds = [Tombstone::SEED, user.key().as_ref()],
+        bump,
+    )]
+    pub tombstone: UncheckedAccount<'info>,
+
+    /// The user's invite; required, and consumed, only while registration
+    /// is permissioned.
+    #[account(
+        mut,
+        seeds = [InviteEntry::SEED, user.key().as_ref()],
+        bump = invite.bump,
+    )]
+    pub invite: Option<Box<Account<'info, InviteEntry>>>,
+
+    /// CompromisedFingerprint address for the submitted fingerprint; normally empty.
+    /// CHECK: Address and contents are checked by `require_not_compromised`.
+    pub compromised: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ScholarshipPool::SEED],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, ScholarshipPool>>,
+
+    #[account(
+        mut,
+        seeds = [ScholarshipApproval::SEED, user.key().as_ref()],
+        bump = approval.bump,
+    )]
+    pub approval: Box<Account<'info, ScholarshipApproval>>,
+
+    /// Pays the rent of the new accounts; the fee comes from the pool.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// The treasury account that receives the verification fee.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: AccountInfo<'info>,
+
+    /// Receives the insurance share of the fee; required while one is set.
+    #[account(
+        mut,
+        seeds = [InsuranceFund::SEED],
+        bump = insurance_fund.bump,
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Registers a human like `register_human`, except that the verification
+/// fee is paid from the scholarship pool. Consumes the wallet's unexpired
+/// approval, and fails if the pool cannot cover the fee while staying
+/// rent-exempt.
+pub fn handler_register_human_scholarship(
+    ctx: Context<RegisterHumanScholarship>,
+    challenge_nonce: [u8; 32],
+    fingerprint_data: Vec<u8>,
+) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let now = Clock::get()?.unix_timestamp;
+
+    let approval = &mut ctx.accounts.approval;
+    require!(!approval.consumed, YourFunError::ScholarshipAlreadyUsed);
+    require!(now < approval.expires_at, YourFunError::ScholarshipNotApproved);
+    approval.consumed = true;
+
+    let fingerprint_hash = check_registration(
+        registry,
+        &challenge_nonce,
+        &fingerprint_data,
+        &ctx.accounts.compromised,
+        ctx.accounts.invite.as_deref_mut(),
+        now,
+    )?;
+
+    let tombstone = consume_tombstone(
+        &ctx.accounts.tombstone,
+        &ctx.accounts.user,
+        registry.reregistration_cooldown,
+        now,
+    )?;
+
+    validate_fee_receiver(&ctx.accounts.fee_receiver, registry)?;
+
+    let fee_paid = registry.effective_verification_fee();
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(pool_info.data_len());
+    require!(
+        pool_info.lamports().saturating_sub(rent_exempt) >= fee_paid,
+        YourFunError::ScholarshipPoolExhausted
+    );
+    let verifier_fee = registry.verifier_fee_share(fee_paid);
+    move_lamports(&pool_info, &ctx.accounts.human_record.to_account_info(), verifier_fee)?;
+    let platform_fee = fee_paid - verifier_fee;
+    let insured = registry.insurance_share(platform_fee);
+    if insured > 0 {
+        let insurance_fund = ctx
+            .accounts
+            .insurance_fund
+            .as_mut()
+            .ok_or(YourFunError::InsuranceFundRequired)?;
+        move_lamports(&pool_info, &insurance_fund.to_account_info(), insured)?;
+        insurance_fund.total_in = insurance_fund
+            .total_in
+            .checked_add(insured)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+    move_lamports(&pool_info, &ctx.accounts.fee_receiver, platform_fee - insured)?;
+    credit_fee_vault(&ctx.accounts.fee_receiver, platform_fee - insured)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.total_disbursed = pool
+        .total_disbursed
+        .checked_add(fee_paid)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    pool.scholarships_used = pool
+        .scholarships_used
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    init_registration(
+        &mut ctx.accounts.human_record,
+        ctx.bumps.human_record,
+        &mut ctx.accounts.fingerprint,
+        ctx.bumps.fingerprint,
+        ctx.accounts.user.key(),
+        challenge_nonce,
+        fingerprint_hash,
+        fingerprint_data,
+        tombstone.as_ref(),
+        fee_paid,
+        verifier_fee,
+        now,
+    );
+
+    emit!(ScholarshipRedeemed {
+        wallet: ctx.accounts.user.key(),
+        fee_paid,
+    });
+    Ok(())
+}
+
+/// Moves lamports out of a program-owned account, which must stay
+/// rent-exempt.
+pub(crate) fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let remaining = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(YourFunError::InsufficientFunds)?;
+    require!(
+        remaining >= Rent::get()?.minimum_balance(from.data_len()),
+        YourFunError::InsufficientFunds
+    );
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::error::ErrorCode;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    const FEE: u64 = 1_000_000;
+
+    #[test]
+    fn approved_wallets_register_on_the_pool_until_it_or_their_approval_runs_out() {
+        let [authority, officer, donor, stranger] = [0; 4].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.verification_fee_lamports = FEE;
+                registry.program_officer = officer.pubkey();
+            })
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &officer, &donor, &stranger] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let users: Vec<_> = (0..4).map(|_| harness.wallet()).collect();
+        let pool = client::scholarship_pool_address();
+        let register = |harness: &mut Harness, i: usize| {
+            let ix = client::build_register_human_scholarship_ix(
+                &users[i].pubkey(),
+                &authority.pubkey(),
+                false,
+                false,
+                [7u8; 32],
+                vec![i as u8 + 1; 64],
+            );
+            harness.process(ix, &[&users[i]])
+        };
+        let approve = |harness: &mut Harness, officer: &Keypair, i: usize| {
+            harness.process(client::build_approve_scholarship_ix(&officer.pubkey(), users[i].pubkey()), &[officer])
+        };
+
+        harness.process(client::build_fund_scholarship_pool_ix(&donor.pubkey(), FEE * 3 / 2), &[&donor]).unwrap();
+        let rent = harness.balance(&pool) - FEE * 3 / 2;
+        assert_eq!(harness.account::<ScholarshipPool>(&pool).total_donated, FEE * 3 / 2);
+
+        assert_eq!(approve(&mut harness, &stranger, 0), Err(custom(YourFunError::Unauthorized)));
+        approve(&mut harness, &officer, 0).unwrap();
+        approve(&mut harness, &authority, 1).unwrap();
+        approve(&mut harness, &officer, 2).unwrap();
+        let approval: ScholarshipApproval =
+            harness.account(&client::scholarship_approval_address(&users[2].pubkey()));
+        assert_eq!(
+            (approval.approved_by, approval.expires_at),
+            (officer.pubkey(), NOW + ScholarshipApproval::VALIDITY)
+        );
+        assert_eq!(register(&mut harness, 3), Err(custom(ErrorCode::AccountNotInitialized)));
+
+        let (user_before, fee_wallet_before) =
+            (harness.balance(&users[0].pubkey()), harness.balance(&authority.pubkey()));
+        register(&mut harness, 0).unwrap();
+        assert_eq!(harness.balance(&pool), rent + FEE / 2);
+        assert_eq!(harness.balance(&authority.pubkey()), fee_wallet_before + FEE);
+        let human_record = client::human_record_address(&users[0].pubkey());
+        let record_rent = harness.balance(&human_record) + harness.balance(&client::fingerprint_address(&human_record));
+        // The user paid only the new accounts' rent; the harness payer pays
+        // transaction fees.
+        assert_eq!(user_before - harness.balance(&users[0].pubkey()), record_rent);
+        assert!(harness.account::<ScholarshipApproval>(&client::scholarship_approval_address(&users[0].pubkey())).consumed);
+        let state: ScholarshipPool = harness.account(&pool);
+        assert_eq!((state.total_disbursed, state.scholarships_used), (FEE, 1));
+        assert_eq!(approve(&mut harness, &officer, 0), Err(custom(YourFunError::ScholarshipAlreadyUsed)));
+
+        // Half a fee is left, which covers no one.
+        assert_eq!(register(&mut harness, 1), Err(custom(YourFunError::ScholarshipPoolExhausted)));
+        harness.process(client::build_fund_scholarship_pool_ix(&donor.pubkey(), FEE), &[&donor]).unwrap();
+        register(&mut harness, 1).unwrap();
+
+        harness.process(client::build_fund_scholarship_pool_ix(&donor.pubkey(), FEE), &[&donor]).unwrap();
+        harness.warp_to(NOW + ScholarshipApproval::VALIDITY);
+        assert_eq!(register(&mut harness, 2), Err(custom(YourFunError::ScholarshipNotApproved)));
+        // An expired approval that was never used can be renewed.
+        approve(&mut harness, &officer, 2).unwrap();
+        register(&mut harness, 2).unwrap();
+        assert_eq!(harness.account::<ScholarshipPool>(&pool).scholarships_used, 3);
+    }
+}
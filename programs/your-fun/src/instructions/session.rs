@@ -1,30 +1,46 @@
 use anchor_lang::prelude::*;
-use crate::state::{PlatformRegistry, HumanRecord, SessionAccount};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::state::{PlatformRegistry, HumanRecord, AdminAction, AdminActionKind, SessionAccount, StatsBucket, ActiveSessionIndex, CloseReason, PersonalityConfig, PersonalityStats, ScoringProfile, Topic, TopicScore, VerificationStatus, InsuranceFund, RaffleEpoch, RaffleTicketPage};
+use crate::instructions::active_index::track_session;
+use crate::instructions::admin::record_admin_action;
+use crate::instructions::verify_human::refresh_verification_status;
+use crate::instructions::register_human::{collect_fee, validate_fee_receiver};
+use crate::instructions::fee_vault::credit_fee_vault;
+use crate::instructions::settlement::settle_session_score;
+use crate::instructions::partner::{accrue_partner_revenue, load_personality_config};
+use crate::instructions::raffle::enter_raffle;
+use crate::instructions::topic::require_registered_topic;
+use crate::instructions::stats::{record_epoch_activity, EpochActivity};
 use crate::error::YourFunError;
+use crate::constants::MAX_BPS;
+use crate::events::{
+    SessionClosed, SessionEscrowSettled, SessionForceClosed, SessionRated, SessionReclaimed,
+    SessionReopened,
+};
 
 #[derive(Accounts)]
 #[instruction(personality_id: u8, initial_topic: [u8; 32])]
 pub struct CreateSession<'info> {
     #[account(
-        mut,
         seeds = [PlatformRegistry::SEED],
         bump = registry.bump,
     )]
-    pub registry: Account<'info, PlatformRegistry>,
+    pub registry: Box<Account<'info, PlatformRegistry>>,
 
     #[account(
         mut,
         seeds = [HumanRecord::SEED, owner.key().as_ref()],
         bump = human_record.bump,
-        constraint = human_record.is_active @ YourFunError::HumanRecordRevoked,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
         constraint = human_record.wallet == owner.key() @ YourFunError::Unauthorized,
     )]
-    pub human_record: Account<'info, HumanRecord>,
+    pub human_record: Box<Account<'info, HumanRecord>>,
 
     #[account(
         init,
         payer = owner,
-        space = SessionAccount::SIZE,
+        space = 8 + SessionAccount::INIT_SPACE,
         seeds = [
             SessionAccount::SEED,
             human_record.key().as_ref(),
@@ -32,63 +48,388 @@ pub struct CreateSession<'info> {
         ],
         bump,
     )]
-    pub session: Account<'info, SessionAccount>,
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ActiveSessionIndex::SEED, human_record.key().as_ref()],
+        bump = active_index.bump,
+    )]
+    pub active_index: Option<Box<Account<'info, ActiveSessionIndex>>>,
+
+    /// Settings of the requested personality, if the authority created any.
+    /// CHECK: Address is pinned by seeds; contents are read only when the
+    /// account is owned by this program.
+    #[account(
+        seeds = [PersonalityConfig::SEED, &[personality_id]],
+        bump,
+    )]
+    pub personality_config: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    /// Required when the human is supervised.
+    pub supervisor: Option<Signer<'info>>,
+
+    /// Accepted booster mint of the token to burn, with `booster_token` and
+    /// `token_program`.
+    #[account(mut)]
+    pub booster_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// The owner's token account of `booster_mint`.
+    #[account(mut)]
+    pub booster_token: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    /// Required while registered topics are enforced.
+    #[account(
+        seeds = [Topic::SEED, initial_topic.as_ref()],
+        bump = registered_topic.bump,
+    )]
+    pub registered_topic: Option<Box<Account<'info, Topic>>>,
+
     pub system_program: Program<'info, System>,
 }
 
+/// Opens a new session for a verified human and returns its
+/// `session_nonce` for the companion backend.
+///
+/// A supervised human's supervisor must co-sign. While the registry
+/// enforces registered topics, `initial_topic` must be registered and
+/// enabled.
+///
+/// A personality whose sunset has passed is rejected; sessions opened with
+/// it before then keep working until closed. Humans whose verification has
+/// lapsed are rejected too; during the grace period sessions can still be
+/// opened but renewal is due.
+///
+/// The owner may declare a goal (`GoalType`, 0 for none) that earns the
+/// registry's goal bonus if met when the session is closed. The goal cannot
+/// be changed once the session exists.
+///
+/// When a booster is supplied, one token of its mint is burned from the
+/// owner's account and the session's interactions earn the multiplier the
+/// registry lists for that mint. A session gets at most the one booster it
+/// was created with.
+///
+/// With a nonzero `escrow_rate_per_minute` the session is billed by usage:
+/// the rate times the session's maximum duration in minutes, rounded up, is
+/// locked on the session account. Closing the session bills only the whole
+/// minutes it ran and refunds the rest. The whole deposit counts against
+/// the owner's spending cap.
+///
+/// When the owner's active session index is supplied the session is appended
+/// to it. If the index is full, session accounts passed as remaining accounts
+/// are checked and closed ones are pruned first.
 pub fn handler_create_session(
     ctx: Context<CreateSession>,
     personality_id: u8,
     initial_topic: [u8; 32],
+    goal_type: u8,
+    goal_target: u64,
+    escrow_rate_per_minute: u64,
+) -> Result<[u8; 32]> {
+    let accounts = ctx.accounts;
+    require_registered_topic(&accounts.registry, accounts.registered_topic.as_deref())?;
+    let booster_multiplier_bps = burn_booster(
+        &accounts.registry,
+        &accounts.owner,
+        accounts.booster_mint.as_deref(),
+        accounts.booster_token.as_deref(),
+        accounts.token_program.as_ref(),
+    )?;
+    let session_nonce = open_session(
+        &accounts.registry,
+        &mut accounts.human_record,
+        &mut accounts.session,
+        ctx.bumps.session,
+        &mut accounts.stats_bucket,
+        accounts.active_index.as_deref_mut(),
+        &accounts.personality_config,
+        accounts.owner.key(),
+        accounts.supervisor.as_ref().map(|s| s.key()),
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &SessionSetup {
+            personality_id,
+            topic: initial_topic,
+            duration: accounts.registry.max_session_duration,
+            template_id: 0,
+            scoring_profile: ScoringProfile::Standard as u8,
+            goal_type,
+            goal_target,
+            booster_multiplier_bps,
+        },
+    )?;
+
+    if escrow_rate_per_minute > 0 {
+        let minutes = (accounts.registry.max_session_duration as u64).div_ceil(60);
+        let escrow_lamports = escrow_rate_per_minute
+            .checked_mul(minutes)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        accounts
+            .human_record
+            .record_spend(escrow_lamports, Clock::get()?.unix_timestamp)?;
+        system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: accounts.owner.to_account_info(),
+                    to: accounts.session.to_account_info(),
+                },
+            ),
+            escrow_lamports,
+        )?;
+        accounts.session.escrow_rate_per_minute = escrow_rate_per_minute;
+        accounts.session.escrow_lamports = escrow_lamports;
+    }
+
+    record_epoch_activity(
+        &accounts.epoch_stats,
+        &accounts.owner,
+        &accounts.system_program,
+        Clock::get()?.unix_timestamp,
+        EpochActivity { sessions_created: 1, ..Default::default() },
+    )?;
+    Ok(session_nonce)
+}
+
+/// Bills an escrowed session for the whole minutes it ran until it closed
+/// or expired, whichever came first, paying that to the fee receiver less
+/// the personality partner's share, and refunding the rest of the escrow to
+/// the owner. Does nothing for sessions without escrow or already settled.
+fn settle_escrow<'info>(
+    registry: &PlatformRegistry,
+    session: &mut Account<'info, SessionAccount>,
+    owner: &AccountInfo<'info>,
+    fee_receiver: Option<&UncheckedAccount<'info>>,
+    personality_config: &AccountInfo<'info>,
 ) -> Result<()> {
-    let registry = &mut ctx.accounts.registry;
-    let human_record = &mut ctx.accounts.human_record;
-    let session = &mut ctx.accounts.session;
+    if session.escrow_lamports == 0 {
+        return Ok(());
+    }
+    let fee_receiver = fee_receiver.ok_or(YourFunError::FeeReceiverRequired)?;
+    validate_fee_receiver(fee_receiver, registry)?;
+
+    let ended_at = session.closed_at.min(session.expires_at);
+    let billed = session
+        .escrow_billed(ended_at)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    let refunded = session.escrow_lamports - billed;
+    let config = load_personality_config(personality_config)?;
+    let partner_cut = config.as_ref().map_or(0, |config| config.partner_share(billed));
+
+    let session_info = session.to_account_info();
+    for (to, amount) in [
+        (fee_receiver.to_account_info(), billed - partner_cut),
+        (personality_config.clone(), partner_cut),
+        (owner.clone(), refunded),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        **session_info.try_borrow_mut_lamports()? -= amount;
+        **to.try_borrow_mut_lamports()? = to
+            .lamports()
+            .checked_add(amount)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+    session.escrow_lamports = 0;
+    credit_fee_vault(fee_receiver, billed - partner_cut)?;
+    if let Some(config) = config.filter(|_| partner_cut > 0) {
+        accrue_partner_revenue(personality_config, config, partner_cut)?;
+    }
+
+    emit!(SessionEscrowSettled {
+        session: session.key(),
+        owner: session.owner,
+        session_index: session.session_index,
+        billed,
+        refunded,
+    });
+    Ok(())
+}
+
+/// Burns one token of an accepted booster mint from the owner's account and
+/// returns the multiplier it grants, or 0 when no booster was supplied.
+fn burn_booster<'info>(
+    registry: &PlatformRegistry,
+    owner: &Signer<'info>,
+    mint: Option<&Account<'info, Mint>>,
+    token_account: Option<&Account<'info, TokenAccount>>,
+    token_program: Option<&Program<'info, Token>>,
+) -> Result<u16> {
+    let (mint, token_account, token_program) = match (mint, token_account, token_program) {
+        (None, None, _) => return Ok(0),
+        (Some(mint), Some(token_account), Some(token_program)) => (mint, token_account, token_program),
+        _ => return err!(YourFunError::InvalidBooster),
+    };
+    let multiplier_bps = registry
+        .booster_multiplier(&mint.key())
+        .ok_or(YourFunError::BoosterNotAccepted)?;
+    require_keys_eq!(token_account.mint, mint.key(), YourFunError::InvalidBooster);
+    require_keys_eq!(token_account.owner, owner.key(), YourFunError::InvalidBooster);
+
+    token::burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: mint.to_account_info(),
+                from: token_account.to_account_info(),
+                authority: owner.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+    Ok(multiplier_bps)
+}
+
+/// Parameters of a new session, taken from the caller or a template.
+pub(crate) struct SessionSetup {
+    pub personality_id: u8,
+    pub topic: [u8; 32],
+    pub duration: i64,
+    pub template_id: u16,
+    pub scoring_profile: u8,
+    pub goal_type: u8,
+    pub goal_target: u64,
+    pub booster_multiplier_bps: u16,
+}
+
+/// Validates and initializes a new session, returning its `session_nonce`.
+/// Shared by `create_session` and `create_session_from_template`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn open_session<'info>(
+    registry: &PlatformRegistry,
+    human_record: &mut Account<'info, HumanRecord>,
+    session: &mut Account<'info, SessionAccount>,
+    session_bump: u8,
+    stats_bucket: &mut StatsBucket,
+    active_index: Option<&mut Account<'info, ActiveSessionIndex>>,
+    personality_config: &UncheckedAccount<'info>,
+    owner: Pubkey,
+    supervisor: Option<Pubkey>,
+    remaining_accounts: &[AccountInfo],
+    program_id: &Pubkey,
+    setup: &SessionSetup,
+) -> Result<[u8; 32]> {
     let clock = Clock::get()?;
 
-    require!(!registry.is_paused, YourFunError::RegistryPaused);
+    registry.require_unpaused(PlatformRegistry::PAUSE_SESSIONS, clock.unix_timestamp)?;
+    require!(
+        !human_record.is_frozen(clock.unix_timestamp),
+        YourFunError::HumanRecordFrozen
+    );
+    human_record.require_supervisor(supervisor)?;
+    registry.require_known_personality(setup.personality_id)?;
+    require!(
+        registry.max_active_sessions == 0
+            || human_record.active_session_count < registry.max_active_sessions,
+        YourFunError::TooManyActiveSessions
+    );
+    require!(
+        !personality_deprecated(personality_config, clock.unix_timestamp)?,
+        YourFunError::PersonalityDeprecated
+    );
+    let status = refresh_verification_status(human_record, registry, clock.unix_timestamp);
+    require!(
+        status != VerificationStatus::Lapsed,
+        YourFunError::VerificationLapsed
+    );
+    require!(
+        status != VerificationStatus::Pending,
+        YourFunError::VerificationPending
+    );
+    if status == VerificationStatus::GracePeriod {
+        msg!("Verification renewal due for wallet: {}", human_record.wallet);
+    }
+    SessionAccount::validate_goal(setup.goal_type, setup.goal_target)?;
 
     let expires_at = clock
         .unix_timestamp
-        .checked_add(registry.max_session_duration)
+        .checked_add(setup.duration)
         .ok_or(YourFunError::NumericalOverflow)?;
 
     session.human_record = human_record.key();
-    session.owner = ctx.accounts.owner.key();
+    session.owner = owner;
     session.session_index = human_record.session_count;
     session.created_at = clock.unix_timestamp;
     session.last_interaction_at = clock.unix_timestamp;
     session.expires_at = expires_at;
-    session.is_active = true;
+    session.set_active(true);
     session.interaction_count = 0;
-    session.personality_id = personality_id;
-    session.current_topic = initial_topic;
+    session.personality_id = setup.personality_id;
+    session.current_topic = setup.topic;
+    session.topics = [TopicScore::default(); SessionAccount::MAX_TOPICS];
+    session.topics[0].topic = setup.topic;
+    session.topic_count = 1;
+    session.active_topic = 0;
+    session.session_nonce =
+        SessionAccount::derive_nonce(&session.key(), clock.slot, &owner, &[0u8; 32]);
     session.session_score = 0;
-    session.bump = ctx.bumps.session;
-    session._reserved = [0u8; 16];
+    session.pending_score = 0;
+    session.settles_at = 0;
+    session.booster_multiplier_bps = setup.booster_multiplier_bps;
+    session.escrow_rate_per_minute = 0;
+    session.escrow_lamports = 0;
+    session.bump = session_bump;
+    session.closed_at = 0;
+    session.close_reason = CloseReason::None as u8;
+    session.open_logs = 0;
+    session._reserved = [0u8; 1];
+    session.layout_version = SessionAccount::CURRENT_VERSION;
+    session.template_id = setup.template_id;
+    session.scoring_profile = setup.scoring_profile;
+    session.goal_type = setup.goal_type;
+    session.goal_target = setup.goal_target;
+    session.reopen_count = 0;
 
     human_record.session_count = human_record
         .session_count
         .checked_add(1)
         .ok_or(YourFunError::NumericalOverflow)?;
+    human_record.active_session_count = human_record
+        .active_session_count
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
     human_record.last_active_at = clock.unix_timestamp;
 
-    registry.total_sessions_created = registry
-        .total_sessions_created
+    stats_bucket.pending_sessions_created = stats_bucket
+        .pending_sessions_created
         .checked_add(1)
         .ok_or(YourFunError::NumericalOverflow)?;
 
+    if let Some(active_index) = active_index {
+        track_session(active_index, session.key(), remaining_accounts, program_id)?;
+    }
+
     msg!(
         "Session {} created with personality {}",
         session.session_index,
-        personality_id
+        setup.personality_id
     );
 
-    Ok(())
+    Ok(session.session_nonce)
+}
+
+/// Whether the personality behind `info` has passed its sunset. A missing
+/// PersonalityConfig means the personality was never deprecated.
+fn personality_deprecated(info: &UncheckedAccount, now: i64) -> Result<bool> {
+    Ok(load_personality_config(info)?.is_some_and(|config| config.is_deprecated_at(now)))
 }
 
 #[derive(Accounts)]
@@ -98,14 +439,14 @@ pub struct CloseSession<'info> {
         seeds = [PlatformRegistry::SEED],
         bump = registry.bump,
     )]
-    pub registry: Account<'info, PlatformRegistry>,
+    pub registry: Box<Account<'info, PlatformRegistry>>,
 
     #[account(
         mut,
         seeds = [HumanRecord::SEED, owner.key().as_ref()],
         bump = human_record.bump,
     )]
-    pub human_record: Account<'info, HumanRecord>,
+    pub human_record: Box<Account<'info, HumanRecord>>,
 
     #[account(
         mut,
@@ -115,26 +456,210 @@ pub struct CloseSession<'info> {
             &session.session_index.to_le_bytes(),
         ],
         bump = session.bump,
-        constraint = session.is_active @ YourFunError::SessionInactive,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
         constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
     )]
-    pub session: Account<'info, SessionAccount>,
+    pub session: Box<Account<'info, SessionAccount>>,
 
-    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ActiveSessionIndex::SEED, human_record.key().as_ref()],
+        bump = active_index.bump,
+    )]
+    pub active_index: Option<Box<Account<'info, ActiveSessionIndex>>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    #[account(
+        init_if_needed,
+        payer = closer,
+        space = 8 + PersonalityStats::INIT_SPACE,
+        seeds = [PersonalityStats::SEED, &[session.personality_id]],
+        bump,
+    )]
+    pub personality_stats: Box<Account<'info, PersonalityStats>>,
+
+    /// The session owner; receives any escrow refund.
+    /// CHECK: Pins the human record and must match the session's owner.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The owner, or the human's supervisor; pays for the personality stats
+    /// account if it is the first.
+    #[account(
+        mut,
+        constraint = closer.key() == owner.key() || human_record.is_supervisor(&closer.key())
+            @ YourFunError::Unauthorized,
+    )]
+    pub closer: Signer<'info>,
+
+    /// Receives the billed part of the escrow; required for escrowed sessions.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// The current raffle epoch; with `raffle_page`, enters the human in
+    /// the raffle.
+    #[account(
+        mut,
+        seeds = [RaffleEpoch::SEED, &raffle_epoch.epoch.to_le_bytes()],
+        bump = raffle_epoch.bump,
+    )]
+    pub raffle_epoch: Option<Box<Account<'info, RaffleEpoch>>>,
+
+    /// The raffle epoch's latest ticket page.
+    #[account(
+        mut,
+        seeds = [
+            RaffleTicketPage::SEED,
+            &raffle_page.epoch.to_le_bytes(),
+            &raffle_page.page.to_le_bytes(),
+        ],
+        bump = raffle_page.bump,
+    )]
+    pub raffle_page: Option<Box<Account<'info, RaffleTicketPage>>>,
+
+    /// Settings of the session's personality, credited with its partner's
+    /// share of the billed escrow.
+    /// CHECK: Address is pinned by seeds; contents are read only when the
+    /// account is owned by this program.
+    #[account(
+        mut,
+        seeds = [PersonalityConfig::SEED, &[session.personality_id]],
+        bump,
+    )]
+    pub personality_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
+/// Closes a session and settles its score into the human's learning score.
+/// Either the owner or, for a supervised human, the supervisor may close it.
+///
+/// With a nonzero `settlement_window` the score is only recorded as pending
+/// and the session left settling; `finalize_session` settles it once the
+/// window has passed, unless `contest_settlement` forfeits it first.
+///
+/// Closing is always allowed, even while the registry is paused or after the
+/// human has been revoked, so users can wind sessions down and reclaim rent.
+/// A revoked human's session is closed without settling its score, since
+/// revocation must not leave a channel for earning further credit.
+///
+/// A session that reached its goal is flagged as such and settles the
+/// registry's goal bonus on top of its score.
+///
+/// TopicProgress accounts of the session's topics passed as remaining
+/// accounts are credited with the score earned under each topic when the
+/// score settles; the goal bonus is not attributed to any topic.
+///
+/// An escrowed session is billed for the whole minutes it ran, and the rest
+/// of its escrow is refunded to the owner.
+///
+/// Supplying the current raffle epoch and its latest ticket page gives the
+/// human a ticket in that epoch's raffle, at most one per epoch.
+///
+/// The whole session is attributed to the personality it has when closed;
+/// the closer pays for that personality's stats account if it is the first.
 pub fn handler_close_session(ctx: Context<CloseSession>) -> Result<()> {
+    let registry = &ctx.accounts.registry;
     let session = &mut ctx.accounts.session;
     let human_record = &mut ctx.accounts.human_record;
     let clock = Clock::get()?;
 
-    session.is_active = false;
-    session.last_interaction_at = clock.unix_timestamp;
+    let close_reason = if session.expires_at > clock.unix_timestamp {
+        CloseReason::Completed as u8
+    } else {
+        CloseReason::Expired as u8
+    };
 
-    human_record.learning_score = human_record
-        .learning_score
+    let goal_met = session.goal_reached();
+    session.set_active(false);
+    session.set_goal_met(goal_met);
+    session.closed_at = clock.unix_timestamp;
+    session.close_reason = close_reason;
+    human_record.active_session_count = human_record.active_session_count.saturating_sub(1);
+    ctx.accounts.stats_bucket.count_session_closed()?;
+    settle_escrow(
+        registry,
+        session,
+        &ctx.accounts.owner.to_account_info(),
+        ctx.accounts.fee_receiver.as_ref(),
+        &ctx.accounts.personality_config,
+    )?;
+
+    if let Some(active_index) = ctx.accounts.active_index.as_mut() {
+        active_index.remove(&session.key());
+    }
+
+    if let (Some(raffle_epoch), Some(raffle_page)) =
+        (ctx.accounts.raffle_epoch.as_mut(), ctx.accounts.raffle_page.as_mut())
+    {
+        enter_raffle(human_record, raffle_epoch, raffle_page, clock.unix_timestamp)?;
+    }
+
+    let personality_stats = &mut ctx.accounts.personality_stats;
+    personality_stats.init_if_new(session.personality_id, ctx.bumps.personality_stats);
+    personality_stats.session_count = personality_stats
+        .session_count
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    personality_stats.cumulative_score = personality_stats
+        .cumulative_score
         .checked_add(session.session_score)
         .ok_or(YourFunError::NumericalOverflow)?;
+    personality_stats.cumulative_interactions = personality_stats
+        .cumulative_interactions
+        .checked_add(session.interaction_count as u64)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let (earned_score, goal_bonus) = if !human_record.is_active() {
+        (0, 0)
+    } else if goal_met {
+        let goal_bonus = registry
+            .goal_bonus(session.session_score)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        let earned_score = session
+            .session_score
+            .checked_add(goal_bonus)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        (earned_score, goal_bonus)
+    } else {
+        (session.session_score, 0)
+    };
+    let (settled_score, pending_score) = if registry.settlement_window == 0 {
+        let settled_score =
+            settle_session_score(ctx.remaining_accounts, human_record, session, earned_score)?;
+        (settled_score, 0)
+    } else {
+        session.set_settling(true);
+        session.pending_score = earned_score;
+        session.settles_at = clock
+            .unix_timestamp
+            .checked_add(registry.settlement_window)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        (0, earned_score)
+    };
+
+    emit!(SessionClosed {
+        session: session.key(),
+        owner: session.owner,
+        session_index: session.session_index,
+        session_score: session.session_score,
+        settled_score,
+        last_interaction_at: session.last_interaction_at,
+        closed_at: session.closed_at,
+        close_reason,
+        goal_met,
+        goal_bonus,
+        pending_score,
+        settles_at: session.settles_at,
+        chain_head: session.chain_head,
+    });
 
     msg!(
         "Session {} closed with score {}",
@@ -145,19 +670,268 @@ pub fn handler_close_session(ctx: Context<CloseSession>) -> Result<()> {
     Ok(())
 }
 
+#[derive(Accounts)]
+pub struct ForceCloseSession<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.is_moderator(&moderator.key()) @ YourFunError::Unauthorized,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ActiveSessionIndex::SEED, human_record.key().as_ref()],
+        bump = active_index.bump,
+    )]
+    pub active_index: Option<Box<Account<'info, ActiveSessionIndex>>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    pub moderator: Signer<'info>,
+}
+
+/// Terminates a session on behalf of trust & safety.
+///
+/// Only `force_close_settle_bps` of the session score reaches the human's
+/// learning score. The session stays in place so the owner can reap its logs
+/// and reclaim the rent as usual.
+pub fn handler_force_close_session(
+    ctx: Context<ForceCloseSession>,
+    reason_code: u8,
+) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    let settled_score = (session.session_score as u128)
+        .checked_mul(registry.force_close_settle_bps as u128)
+        .ok_or(YourFunError::NumericalOverflow)?
+        / MAX_BPS as u128;
+    let settled_score = settled_score as u64;
+
+    force_close(
+        human_record,
+        session,
+        ctx.accounts.active_index.as_deref_mut(),
+        &mut ctx.accounts.stats_bucket,
+        settled_score,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(SessionForceClosed {
+        session: session.key(),
+        owner: session.owner,
+        moderator: ctx.accounts.moderator.key(),
+        reason_code,
+        session_score: session.session_score,
+        settled_score,
+        closed_at: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Session {} force-closed with reason {}",
+        session.session_index,
+        reason_code
+    );
+
+    Ok(())
+}
+
+/// Marks `session` force-closed and credits `settled_score` to the human's
+/// learning score. Shared by `force_close_session` and
+/// `admin_force_close_session`.
+fn force_close<'info>(
+    human_record: &mut HumanRecord,
+    session: &mut Account<'info, SessionAccount>,
+    active_index: Option<&mut Account<'info, ActiveSessionIndex>>,
+    stats_bucket: &mut StatsBucket,
+    settled_score: u64,
+    now: i64,
+) -> Result<()> {
+    session.set_active(false);
+    session.closed_at = now;
+    session.close_reason = CloseReason::ForceClosed as u8;
+    human_record.active_session_count = human_record.active_session_count.saturating_sub(1);
+    stats_bucket.count_session_closed()?;
+
+    if let Some(active_index) = active_index {
+        active_index.remove(&session.key());
+    }
+
+    human_record.learning_score = human_record
+        .learning_score
+        .checked_add(settled_score)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    human_record.force_closed_sessions = human_record
+        .force_closed_sessions
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdminForceCloseSession<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ActiveSessionIndex::SEED, human_record.key().as_ref()],
+        bump = active_index.bump,
+    )]
+    pub active_index: Option<Box<Account<'info, ActiveSessionIndex>>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Terminates an abusive or stuck session on the authority's behalf,
+/// freeing the human's session slot. With `forfeit_score` the session score
+/// is zeroed and nothing reaches the learning score; otherwise the whole
+/// score is credited, unlike a moderator's `force_close_session`.
+pub fn handler_admin_force_close_session(
+    ctx: Context<AdminForceCloseSession>,
+    reason_code: u8,
+    forfeit_score: bool,
+) -> Result<()> {
+    let human_record = &mut ctx.accounts.human_record;
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    let session_score = session.session_score;
+    let settled_score = if forfeit_score {
+        session.session_score = 0;
+        0
+    } else {
+        session_score
+    };
+    force_close(
+        human_record,
+        session,
+        ctx.accounts.active_index.as_deref_mut(),
+        &mut ctx.accounts.stats_bucket,
+        settled_score,
+        now,
+    )?;
+    let (session_key, owner, session_index) = (session.key(), session.owner, session.session_index);
+
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::AdminForceCloseSession,
+        ctx.accounts.authority.key(),
+        &(session_key, reason_code, forfeit_score),
+    )?;
+
+    emit!(SessionForceClosed {
+        session: session_key,
+        owner,
+        moderator: ctx.accounts.authority.key(),
+        reason_code,
+        session_score,
+        settled_score,
+        closed_at: now,
+    });
+
+    msg!(
+        "Session {} force-closed by the authority with reason {} (score forfeited: {})",
+        session_index,
+        reason_code,
+        forfeit_score
+    );
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct ExtendSession<'info> {
     #[account(
         seeds = [PlatformRegistry::SEED],
         bump = registry.bump,
     )]
-    pub registry: Account<'info, PlatformRegistry>,
+    pub registry: Box<Account<'info, PlatformRegistry>>,
 
     #[account(
         seeds = [HumanRecord::SEED, owner.key().as_ref()],
         bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
     )]
-    pub human_record: Account<'info, HumanRecord>,
+    pub human_record: Box<Account<'info, HumanRecord>>,
 
     #[account(
         mut,
@@ -167,14 +941,23 @@ pub struct ExtendSession<'info> {
             &session.session_index.to_le_bytes(),
         ],
         bump = session.bump,
-        constraint = session.is_active @ YourFunError::SessionInactive,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
         constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
     )]
-    pub session: Account<'info, SessionAccount>,
+    pub session: Box<Account<'info, SessionAccount>>,
 
     pub owner: Signer<'info>,
+
+    /// Required when the human is supervised.
+    pub supervisor: Option<Signer<'info>>,
 }
 
+/// Pushes back the expiry of an active session.
+///
+/// Extending is blocked while the registry is paused and for revoked humans,
+/// as it would otherwise keep a session accruing score past either. A
+/// supervised human's supervisor must co-sign. The session nonce is rotated,
+/// since the extension renews the session.
 pub fn handler_extend_session(
     ctx: Context<ExtendSession>,
     additional_duration: i64,
@@ -183,6 +966,11 @@ pub fn handler_extend_session(
     let session = &mut ctx.accounts.session;
     let clock = Clock::get()?;
 
+    registry.require_unpaused(PlatformRegistry::PAUSE_SESSIONS, clock.unix_timestamp)?;
+    ctx.accounts
+        .human_record
+        .require_supervisor(ctx.accounts.supervisor.as_ref().map(|s| s.key()))?;
+
     require!(
         session.expires_at > clock.unix_timestamp,
         YourFunError::SessionExpired
@@ -204,6 +992,8 @@ pub fn handler_extend_session(
     );
 
     session.expires_at = new_expiry;
+    let session_key = session.key();
+    session.rotate_nonce(&session_key, clock.slot);
 
     msg!(
         "Session {} extended to {}",
@@ -213,3 +1003,1095 @@ pub fn handler_extend_session(
 
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct ReopenSession<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    /// Its spending window is charged the reopen fee.
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+        constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The treasury account that receives the reopen fee.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: AccountInfo<'info>,
+
+    /// Receives the insurance share of the fee; required while one is set.
+    #[account(
+        mut,
+        seeds = [InsuranceFund::SEED],
+        bump = insurance_fund.bump,
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    /// Settings of the session's personality, credited with its partner's
+    /// share of the fee.
+    /// CHECK: Address is pinned by seeds; contents are read only when the
+    /// account is owned by this program.
+    #[account(
+        mut,
+        seeds = [PersonalityConfig::SEED, &[session.personality_id]],
+        bump,
+    )]
+    pub personality_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Revives an expired session that was never closed, for
+/// `reopen_fee_lamports`, which counts against the owner's spending cap. The
+/// partner of the session's personality, if any, accrues its share of the
+/// fee.
+///
+/// Only allowed within `reopen_grace_period` seconds of expiry and at most
+/// `SessionAccount::MAX_REOPENS` times per session. The new expiry is
+/// `additional_duration` seconds from now, at most `max_session_duration`.
+/// The session nonce is rotated.
+pub fn handler_reopen_session(ctx: Context<ReopenSession>, additional_duration: i64) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let clock = Clock::get()?;
+
+    registry.require_unpaused(PlatformRegistry::PAUSE_SESSIONS, clock.unix_timestamp)?;
+
+    let session = &ctx.accounts.session;
+    let window_ends_at = session
+        .expires_at
+        .checked_add(registry.reopen_grace_period)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    require!(
+        registry.reopen_grace_period > 0
+            && session.expires_at <= clock.unix_timestamp
+            && clock.unix_timestamp < window_ends_at,
+        YourFunError::ReopenWindowClosed
+    );
+    require!(
+        session.reopen_count < SessionAccount::MAX_REOPENS,
+        YourFunError::ReopenLimitReached
+    );
+    require!(
+        additional_duration > 0 && additional_duration <= registry.max_session_duration,
+        YourFunError::SessionDurationExceeded
+    );
+
+    validate_fee_receiver(&ctx.accounts.fee_receiver, registry)?;
+
+    let fee_paid = registry.reopen_fee_lamports;
+    ctx.accounts
+        .human_record
+        .record_spend(fee_paid, clock.unix_timestamp)?;
+    let registry = &ctx.accounts.registry;
+    collect_fee(
+        registry,
+        &ctx.accounts.owner,
+        &ctx.accounts.fee_receiver,
+        ctx.accounts.insurance_fund.as_deref_mut(),
+        Some(&ctx.accounts.personality_config),
+        &ctx.accounts.system_program,
+        fee_paid,
+    )?;
+
+    let session = &mut ctx.accounts.session;
+    session.expires_at = clock
+        .unix_timestamp
+        .checked_add(additional_duration)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    session.reopen_count += 1;
+    let session_key = session.key();
+    session.rotate_nonce(&session_key, clock.slot);
+
+    emit!(SessionReopened {
+        session: session.key(),
+        owner: session.owner,
+        session_index: session.session_index,
+        reopen_count: session.reopen_count,
+        fee_paid,
+        expires_at: session.expires_at,
+    });
+
+    msg!(
+        "Session {} reopened until {}",
+        session.session_index,
+        session.expires_at
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct HeartbeatSession<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+        constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Marks a session as still in use without recording an interaction.
+///
+/// Only `last_interaction_at` and the human's `last_active_at` move; no log
+/// is created, nothing is scored, and `interaction_count` is untouched.
+/// Accepted at most once per `HEARTBEAT_MIN_INTERVAL` seconds of inactivity.
+pub fn handler_heartbeat_session(ctx: Context<HeartbeatSession>) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    registry.require_unpaused(PlatformRegistry::PAUSE_INTERACTIONS, clock.unix_timestamp)?;
+
+    require!(
+        session.expires_at > clock.unix_timestamp,
+        YourFunError::SessionExpired
+    );
+
+    let next_allowed = session
+        .last_interaction_at
+        .checked_add(SessionAccount::HEARTBEAT_MIN_INTERVAL)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    require!(
+        clock.unix_timestamp >= next_allowed,
+        YourFunError::HeartbeatTooSoon
+    );
+
+    session.last_interaction_at = clock.unix_timestamp;
+    human_record.last_active_at = clock.unix_timestamp;
+    refresh_verification_status(human_record, registry, clock.unix_timestamp);
+
+    msg!("Session {} heartbeat", session.session_index);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RateSession<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = !session.is_active() @ YourFunError::SessionStillActive,
+        constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
+        constraint = !session.is_rated() @ YourFunError::SessionAlreadyRated,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + PersonalityStats::INIT_SPACE,
+        seeds = [PersonalityStats::SEED, &[session.personality_id]],
+        bump,
+    )]
+    pub personality_stats: Box<Account<'info, PersonalityStats>>,
+
+    /// Pays for the personality stats account if it is the first.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rates a closed session from `MIN_RATING` to `MAX_RATING`, once, adding
+/// the rating to the stats of the personality the session closed with.
+/// Owner only; the session account must not have been reclaimed yet.
+pub fn handler_rate_session(ctx: Context<RateSession>, rating: u8) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let personality_stats = &mut ctx.accounts.personality_stats;
+    personality_stats.init_if_new(session.personality_id, ctx.bumps.personality_stats);
+    personality_stats.record_rating(rating)?;
+    session.set_rated(true);
+
+    emit!(SessionRated {
+        session: session.key(),
+        owner: session.owner,
+        personality_id: session.personality_id,
+        rating,
+        rated_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReclaimSession<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = !session.is_active() @ YourFunError::SessionStillActive,
+        constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
+        constraint = session.open_logs == 0 @ YourFunError::SessionHasOpenLogs,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ActiveSessionIndex::SEED, human_record.key().as_ref()],
+        bump = active_index.bump,
+    )]
+    pub active_index: Option<Box<Account<'info, ActiveSessionIndex>>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Receives the billed part of an escrow still held, as after a force
+    /// close.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Settings of the session's personality, credited with its partner's
+    /// share of any escrow billed.
+    /// CHECK: Address is pinned by seeds; contents are read only when the
+    /// account is owned by this program.
+    #[account(
+        mut,
+        seeds = [PersonalityConfig::SEED, &[session.personality_id]],
+        bump,
+    )]
+    pub personality_config: UncheckedAccount<'info>,
+}
+
+/// Closes a settled session account and returns its rent to the owner.
+///
+/// Every interaction log must have been reaped first so none are orphaned.
+/// Escrow still held, as by a force-closed session, is settled first.
+/// The session index is never handed out again because `session_count` on
+/// the HumanRecord only grows; the emitted event preserves the terminal state
+/// for indexers once the account itself is gone.
+pub fn handler_reclaim_session(ctx: Context<ReclaimSession>) -> Result<()> {
+    settle_escrow(
+        &ctx.accounts.registry,
+        &mut ctx.accounts.session,
+        &ctx.accounts.owner.to_account_info(),
+        ctx.accounts.fee_receiver.as_ref(),
+        &ctx.accounts.personality_config,
+    )?;
+    let session = &ctx.accounts.session;
+
+    if let Some(active_index) = ctx.accounts.active_index.as_mut() {
+        active_index.remove(&session.key());
+    }
+
+    emit!(SessionReclaimed {
+        session: session.key(),
+        human_record: session.human_record,
+        owner: session.owner,
+        session_index: session.session_index,
+        interaction_count: session.interaction_count,
+        session_score: session.session_score,
+        closed_at: session.closed_at,
+        close_reason: session.close_reason,
+    });
+
+    msg!("Session {} reclaimed", session.session_index);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::GoalType;
+
+    const LAST_INTERACTION: i64 = NOW + 60;
+    const EXPIRES_AT: i64 = NOW + 600;
+
+    /// Starts with `owner` verified and holding one open session, index 0,
+    /// last used `LAST_INTERACTION` and expiring at `EXPIRES_AT`.
+    fn start(authority: &Keypair, owner: &Keypair) -> Harness {
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_session(|session| {
+                session.last_interaction_at = LAST_INTERACTION;
+                session.expires_at = EXPIRES_AT;
+            })
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&owner.pubkey(), 1_000_000_000);
+        harness
+    }
+
+    fn session(harness: &mut Harness, owner: &Keypair) -> SessionAccount {
+        harness.account(&client::session_address(&client::human_record_address(&owner.pubkey()), 0))
+    }
+
+    fn close(harness: &mut Harness, owner: &Keypair) {
+        let ix = client::build_close_session_ix(&owner.pubkey(), &owner.pubkey(), 0, 0, false, None, None);
+        harness.process(ix, &[owner]).unwrap();
+    }
+
+    #[test]
+    fn closing_records_the_time_and_reason_and_keeps_the_last_interaction() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+
+        let mut harness = start(&authority, &owner);
+        harness.warp_to(NOW + 300);
+        close(&mut harness, &owner);
+        let closed = session(&mut harness, &owner);
+        assert_eq!(closed.close_reason, CloseReason::Completed as u8);
+        assert_eq!(closed.closed_at, NOW + 300);
+        assert_eq!(closed.last_interaction_at, LAST_INTERACTION);
+
+        let mut harness = start(&authority, &owner);
+        harness.warp_to(EXPIRES_AT + 1);
+        close(&mut harness, &owner);
+        let expired = session(&mut harness, &owner);
+        assert_eq!(expired.close_reason, CloseReason::Expired as u8);
+        assert_eq!(expired.closed_at, EXPIRES_AT + 1);
+        assert_eq!(expired.last_interaction_at, LAST_INTERACTION);
+
+        let mut harness = start(&authority, &owner);
+        harness.warp_to(NOW + 300);
+        let ix = client::build_force_close_session_ix(&authority.pubkey(), &owner.pubkey(), 0, false, 1);
+        harness.process(ix, &[&authority]).unwrap();
+        let forced = session(&mut harness, &owner);
+        assert_eq!(forced.close_reason, CloseReason::ForceClosed as u8);
+        assert_eq!(forced.closed_at, NOW + 300);
+        assert_eq!(forced.last_interaction_at, LAST_INTERACTION);
+    }
+
+    #[test]
+    fn sessions_with_open_logs_cannot_be_reclaimed() {
+        let owner = Keypair::new();
+        let accounts = FixtureBuilder::new().with_verified_human(owner.pubkey()).build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&owner.pubkey(), 10_000_000_000);
+        let human_record = client::human_record_address(&owner.pubkey());
+
+        let create = client::build_create_session_ix(
+            &owner.pubkey(),
+            None,
+            0,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        );
+        harness.process(create, &[&owner]).unwrap();
+        let record = client::build_record_interaction_ix(
+            &owner.pubkey(),
+            0,
+            0,
+            [3u8; 32],
+            1,
+            80,
+            60,
+            [0u8; 32],
+            0,
+            false,
+            false,
+            NOW,
+        );
+        harness.process(record, &[&owner]).unwrap();
+        assert_eq!(session(&mut harness, &owner).open_logs, 1);
+
+        close(&mut harness, &owner);
+        let reclaim = || client::build_reclaim_session_ix(&owner.pubkey(), 0, 0, false, None);
+        assert_eq!(
+            harness.process(reclaim(), &[&owner]),
+            Err(custom(YourFunError::SessionHasOpenLogs))
+        );
+
+        let reap = client::build_reap_interaction_ix(&owner.pubkey(), 0, 0);
+        harness.process(reap, &[&owner]).unwrap();
+        assert_eq!(session(&mut harness, &owner).open_logs, 0);
+        harness.process(reclaim(), &[&owner]).unwrap();
+
+        let session_address = client::session_address(&human_record, 0);
+        assert!(harness.try_account::<SessionAccount>(&session_address).is_none());
+        let human: HumanRecord = harness.account(&human_record);
+        assert_eq!(human.session_count, 1);
+        assert_eq!(human.active_session_count, 0);
+        assert_eq!(human.total_interactions, 1);
+    }
+
+    #[test]
+    fn force_closing_withholds_score_and_counts_against_the_human() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.force_close_settle_bps = 2_500)
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_session(|session| session.session_score = 1_000)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&owner.pubkey(), 1_000_000_000);
+        let human_record = client::human_record_address(&owner.pubkey());
+        let before: HumanRecord = harness.account(&human_record);
+
+        let by_owner = client::build_force_close_session_ix(&owner.pubkey(), &owner.pubkey(), 0, false, 1);
+        assert_eq!(
+            harness.process(by_owner, &[&owner]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+
+        let by_authority = client::build_force_close_session_ix(&authority.pubkey(), &owner.pubkey(), 0, false, 1);
+        harness.process(by_authority, &[&authority]).unwrap();
+        let after: HumanRecord = harness.account(&human_record);
+        assert_eq!(after.learning_score, before.learning_score + 250);
+        assert_eq!(after.force_closed_sessions, before.force_closed_sessions + 1);
+        assert_eq!(after.active_session_count, 0);
+
+        let reclaim = client::build_reclaim_session_ix(&owner.pubkey(), 0, 0, false, None);
+        harness.process(reclaim, &[&owner]).unwrap();
+        let session_address = client::session_address(&human_record, 0);
+        assert!(harness.try_account::<SessionAccount>(&session_address).is_none());
+    }
+
+    #[test]
+    fn the_authority_closes_sessions_crediting_or_forfeiting_their_score() {
+        for forfeit_score in [false, true] {
+            let (authority, owner) = (Keypair::new(), Keypair::new());
+            let accounts = FixtureBuilder::new()
+                .with_authority(authority.pubkey())
+                .with_registry(|registry| registry.force_close_settle_bps = 2_500)
+                .with_verified_human(owner.pubkey())
+                .with_open_session()
+                .with_session(|session| session.session_score = 1_000)
+                .build();
+            let mut harness = Harness::start(accounts);
+            harness.fund(&authority.pubkey(), 1_000_000_000);
+            harness.fund(&owner.pubkey(), 1_000_000_000);
+            let human_record = client::human_record_address(&owner.pubkey());
+            let before: HumanRecord = harness.account(&human_record);
+
+            let by_owner = client::build_admin_force_close_session_ix(&owner.pubkey(), 0, &owner.pubkey(), 0, false, 7, forfeit_score);
+            assert_eq!(
+                harness.process(by_owner, &[&owner]),
+                Err(custom(YourFunError::AuthorityMismatch))
+            );
+
+            let ix = client::build_admin_force_close_session_ix(&authority.pubkey(), 0, &owner.pubkey(), 0, false, 7, forfeit_score);
+            let events = harness.process_with_events::<SessionForceClosed>(ix, &[&authority]).unwrap();
+            // Credited in full rather than at the moderator's settle share.
+            let settled = if forfeit_score { 0 } else { 1_000 };
+            let event = &events[0];
+            assert_eq!(
+                (event.session, event.owner, event.moderator),
+                (client::session_address(&human_record, 0), owner.pubkey(), authority.pubkey())
+            );
+            assert_eq!((event.reason_code, event.session_score, event.settled_score), (7, 1_000, settled));
+
+            let after: HumanRecord = harness.account(&human_record);
+            assert_eq!(after.learning_score, before.learning_score + settled);
+            assert_eq!(after.active_session_count, 0);
+            let closed = session(&mut harness, &owner);
+            assert!(!closed.is_active());
+            assert_eq!(closed.closed_at, NOW);
+            assert_eq!(closed.session_score, settled);
+
+            let again = client::build_admin_force_close_session_ix(&authority.pubkey(), 1, &owner.pubkey(), 0, false, 7, forfeit_score);
+            assert_eq!(
+                harness.process(again, &[&authority]),
+                Err(custom(YourFunError::SessionInactive))
+            );
+        }
+    }
+
+    #[test]
+    fn open_sessions_are_capped_per_human_and_legacy_counts_saturate() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        // A session opened before the counter existed: open, yet not counted.
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_human(|record| record.active_session_count = 0)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&owner.pubkey(), 1_000_000_000);
+        let human_record = client::human_record_address(&owner.pubkey());
+        let active = |harness: &mut Harness| harness.account::<HumanRecord>(&human_record).active_session_count;
+        let create = |harness: &mut Harness, session_index| {
+            let ix = client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                session_index,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                NOW,
+            );
+            harness.process(ix, &[&owner])
+        };
+        let close_index = |harness: &mut Harness, session_index| {
+            let ix = client::build_close_session_ix(&owner.pubkey(), &owner.pubkey(), session_index, 0, false, None, None);
+            harness.process(ix, &[&owner]).unwrap();
+        };
+
+        let set_cap = client::build_admin_config_ix(
+            &authority.pubkey(),
+            0,
+            crate::instruction::SetMaxActiveSessions { max_active_sessions: 2 },
+        );
+        harness.process(set_cap, &[&authority]).unwrap();
+
+        create(&mut harness, 1).unwrap();
+        create(&mut harness, 2).unwrap();
+        assert_eq!(active(&mut harness), 2);
+        assert_eq!(create(&mut harness, 3), Err(custom(YourFunError::TooManyActiveSessions)));
+
+        close_index(&mut harness, 1);
+        assert_eq!(active(&mut harness), 1);
+        create(&mut harness, 3).unwrap();
+        assert_eq!(create(&mut harness, 4), Err(custom(YourFunError::TooManyActiveSessions)));
+
+        // Closing the uncounted session takes one off the count, so the last
+        // close finds it at zero and saturates rather than underflowing.
+        for session_index in [0, 2, 3] {
+            close_index(&mut harness, session_index);
+        }
+        assert_eq!(active(&mut harness), 0);
+        create(&mut harness, 4).unwrap();
+        assert_eq!(active(&mut harness), 1);
+    }
+
+    #[test]
+    fn personalities_above_the_configured_maximum_are_rejected() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(owner.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&owner.pubkey(), 1_000_000_000);
+        let set_max = |harness: &mut Harness, count, max_personality_id| {
+            let data = crate::instruction::SetMaxPersonalityId { max_personality_id };
+            harness.process(client::build_admin_config_ix(&authority.pubkey(), count, data), &[&authority]).unwrap();
+        };
+        let create = |harness: &mut Harness, session_index, personality_id| {
+            let ix = client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                session_index,
+                personality_id,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                NOW,
+            );
+            harness.process(ix, &[&owner])
+        };
+
+        set_max(&mut harness, 0, 11);
+        create(&mut harness, 0, 11).unwrap();
+        assert_eq!(create(&mut harness, 1, 12), Err(custom(YourFunError::InvalidPersonalityId)));
+        assert_eq!(create(&mut harness, 1, u8::MAX), Err(custom(YourFunError::InvalidPersonalityId)));
+
+        // 255 lifts the restriction.
+        set_max(&mut harness, 1, u8::MAX);
+        create(&mut harness, 1, 200).unwrap();
+        create(&mut harness, 2, u8::MAX).unwrap();
+    }
+
+    #[test]
+    fn revoked_or_paused_sessions_cannot_be_extended_but_still_close() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_session(|session| session.session_score = 1_000)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&owner.pubkey(), 1_000_000_000);
+        let human_record = client::human_record_address(&owner.pubkey());
+        let extend = || client::build_extend_session_ix(&owner.pubkey(), None, 0, 60);
+
+        let pause = client::build_set_paused_ix(&authority.pubkey(), None, 0, true);
+        harness.process(pause, &[&authority]).unwrap();
+        assert_eq!(
+            harness.process(extend(), &[&owner]),
+            Err(custom(YourFunError::RegistryPaused))
+        );
+        let resume = client::build_set_paused_ix(&authority.pubkey(), None, 1, false);
+        harness.process(resume, &[&authority]).unwrap();
+        harness.process(extend(), &[&owner]).unwrap();
+
+        let revoke = client::build_revoke_human_ix(&authority.pubkey(), &owner.pubkey(), 0, 2, None);
+        harness.process(revoke, &[&authority]).unwrap();
+        assert_eq!(
+            harness.process(extend(), &[&owner]),
+            Err(custom(YourFunError::HumanRecordRevoked))
+        );
+
+        let before: HumanRecord = harness.account(&human_record);
+        close(&mut harness, &owner);
+        assert!(!session(&mut harness, &owner).is_active());
+        let after: HumanRecord = harness.account(&human_record);
+        assert_eq!(after.learning_score, before.learning_score);
+    }
+
+    #[test]
+    fn heartbeats_are_rate_limited_and_move_only_the_activity_timestamps() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &owner);
+        let human_record = client::human_record_address(&owner.pubkey());
+        let heartbeat = || client::build_heartbeat_session_ix(&owner.pubkey(), 0);
+        let before_session = session(&mut harness, &owner);
+        let before_human: HumanRecord = harness.account(&human_record);
+
+        let first = LAST_INTERACTION + SessionAccount::HEARTBEAT_MIN_INTERVAL;
+        harness.warp_to(first - 1);
+        assert_eq!(
+            harness.process(heartbeat(), &[&owner]),
+            Err(custom(YourFunError::HeartbeatTooSoon))
+        );
+        harness.warp_to(first);
+        harness.process(heartbeat(), &[&owner]).unwrap();
+        harness.warp_to(first + SessionAccount::HEARTBEAT_MIN_INTERVAL - 1);
+        assert_eq!(
+            harness.process(heartbeat(), &[&owner]),
+            Err(custom(YourFunError::HeartbeatTooSoon))
+        );
+
+        let after_session = session(&mut harness, &owner);
+        let after_human: HumanRecord = harness.account(&human_record);
+        assert_eq!(after_session.last_interaction_at, first);
+        assert_eq!(after_human.last_active_at, first);
+        assert_eq!(after_session.session_score, before_session.session_score);
+        assert_eq!(after_session.interaction_count, before_session.interaction_count);
+        assert_eq!(after_session.open_logs, before_session.open_logs);
+        assert_eq!(after_human.total_interactions, before_human.total_interactions);
+        assert_eq!(after_human.learning_score, before_human.learning_score);
+
+        let pause = client::build_set_paused_ix(&authority.pubkey(), None, 0, true);
+        harness.process(pause, &[&authority]).unwrap();
+        harness.warp_to(first + SessionAccount::HEARTBEAT_MIN_INTERVAL);
+        assert_eq!(
+            harness.process(heartbeat(), &[&owner]),
+            Err(custom(YourFunError::RegistryPaused))
+        );
+        let resume = client::build_set_paused_ix(&authority.pubkey(), None, 1, false);
+        harness.process(resume, &[&authority]).unwrap();
+
+        harness.warp_to(EXPIRES_AT);
+        assert_eq!(
+            harness.process(heartbeat(), &[&owner]),
+            Err(custom(YourFunError::SessionExpired))
+        );
+    }
+
+    #[test]
+    fn a_sunset_personality_starts_no_sessions_but_running_ones_finish() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(owner.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&owner.pubkey(), 10_000_000_000);
+        let sunset = NOW + 100;
+        let create = |session_index, now| {
+            client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                session_index,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                now,
+            )
+        };
+
+        let set_sunset = client::build_set_personality_sunset_ix(&authority.pubkey(), 0, 0, sunset);
+        harness.process(set_sunset, &[&authority]).unwrap();
+        harness.warp_to(sunset);
+        harness.process(create(0, sunset), &[&owner]).unwrap();
+        harness.warp_to(sunset + 1);
+        assert_eq!(
+            harness.process(create(1, sunset + 1), &[&owner]),
+            Err(custom(YourFunError::PersonalityDeprecated))
+        );
+
+        let later = sunset + 200;
+        harness.warp_to(later);
+        let record = client::build_record_interaction_ix(
+            &owner.pubkey(),
+            0,
+            0,
+            [3u8; 32],
+            1,
+            80,
+            60,
+            [0u8; 32],
+            0,
+            false,
+            false,
+            later,
+        );
+        harness.process(record, &[&owner]).unwrap();
+        assert_eq!(session(&mut harness, &owner).interaction_count, 1);
+        close(&mut harness, &owner);
+        assert!(!session(&mut harness, &owner).is_active());
+    }
+
+    #[test]
+    fn goals_are_validated_fixed_at_creation_and_rewarded_only_when_met() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_verified_human(owner.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        harness.fund(&owner.pubkey(), 10_000_000_000);
+        let human_record = client::human_record_address(&owner.pubkey());
+        let create = |session_index, goal_type, goal_target| {
+            client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                session_index,
+                0,
+                [2u8; 32],
+                goal_type,
+                goal_target,
+                false,
+                None,
+                0,
+                false,
+                NOW,
+            )
+        };
+        let interact = |harness: &mut Harness, session_index, interaction_index: u32| {
+            let now = NOW + 60 * (interaction_index as i64 + 1);
+            harness.warp_to(now);
+            let ix = client::build_record_interaction_ix(
+                &owner.pubkey(),
+                session_index,
+                interaction_index,
+                [3u8; 32],
+                1,
+                80,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                now,
+            );
+            harness.process(ix, &[&owner]).unwrap();
+        };
+        let close = |harness: &mut Harness, session_index| {
+            let ix = client::build_close_session_ix(&owner.pubkey(), &owner.pubkey(), session_index, 0, false, None, None);
+            let mut events = harness.process_with_events::<SessionClosed>(ix, &[&owner]).unwrap();
+            assert_eq!(events.len(), 1);
+            events.remove(0)
+        };
+        let session = |harness: &mut Harness, session_index| -> SessionAccount {
+            harness.account(&client::session_address(&human_record, session_index))
+        };
+
+        let (interactions, score) = (GoalType::Interactions as u8, GoalType::SessionScore as u8);
+        for (goal_type, goal_target) in [
+            (interactions, 0),
+            (interactions, SessionAccount::MAX_GOAL_INTERACTIONS + 1),
+            (score, 0),
+            (score, SessionAccount::MAX_GOAL_SCORE + 1),
+            (GoalType::None as u8, 5),
+            (GoalType::SessionScore as u8 + 1, 1),
+        ] {
+            assert_eq!(
+                harness.process(create(0, goal_type, goal_target), &[&owner]),
+                Err(custom(YourFunError::InvalidGoal)),
+                "{goal_type}/{goal_target}"
+            );
+        }
+        let bonus = client::build_admin_config_ix(
+            &authority.pubkey(),
+            harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count,
+            crate::instruction::SetGoalBonus { goal_bonus_flat: 50, goal_bonus_bps: 1_000 },
+        );
+        harness.process(bonus, &[&authority]).unwrap();
+
+        harness.process(create(0, interactions, 2), &[&owner]).unwrap();
+        harness.process(create(1, score, SessionAccount::MAX_GOAL_SCORE), &[&owner]).unwrap();
+        // Sessions only open at the next index, so an existing session's
+        // goal cannot be restated.
+        assert_eq!(
+            harness.process(create(0, interactions, 1), &[&owner]),
+            Err(custom(anchor_lang::error::ErrorCode::ConstraintSeeds))
+        );
+        interact(&mut harness, 0, 0);
+        interact(&mut harness, 0, 1);
+        interact(&mut harness, 1, 0);
+
+        let met_score = session(&mut harness, 0).session_score;
+        let met = close(&mut harness, 0);
+        assert!(met.goal_met);
+        assert_eq!(met.goal_bonus, 50 + met_score / 10);
+        assert_eq!(met.settled_score, met_score + met.goal_bonus);
+        let met_session = session(&mut harness, 0);
+        assert!(met_session.goal_met());
+        assert_eq!((met_session.goal_type, met_session.goal_target), (interactions, 2));
+
+        let missed_score = session(&mut harness, 1).session_score;
+        let missed = close(&mut harness, 1);
+        assert!(!missed.goal_met);
+        assert_eq!((missed.goal_bonus, missed.settled_score), (0, missed_score));
+        let missed_session = session(&mut harness, 1);
+        assert!(!missed_session.goal_met());
+        assert_eq!(
+            (missed_session.goal_type, missed_session.goal_target),
+            (score, SessionAccount::MAX_GOAL_SCORE)
+        );
+    }
+
+    #[test]
+    fn expired_sessions_reopen_for_a_fee_inside_the_grace_window_up_to_the_cap() {
+        let (authority, owner) = (Keypair::new(), Keypair::new());
+        let mut harness = start(&authority, &owner);
+        let (grace, fee) = (120, 5_000);
+        let policy = client::build_admin_config_ix(
+            &authority.pubkey(),
+            harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count,
+            crate::instruction::SetReopenPolicy { reopen_grace_period: grace, reopen_fee_lamports: fee },
+        );
+        harness.process(policy, &[&authority]).unwrap();
+        let reopen = |harness: &mut Harness| {
+            let ix = client::build_reopen_session_ix(&owner.pubkey(), 0, 0, &authority.pubkey(), false, 300);
+            harness.process(ix, &[&owner])
+        };
+
+        harness.warp_to(EXPIRES_AT - 1);
+        assert_eq!(reopen(&mut harness), Err(custom(YourFunError::ReopenWindowClosed)));
+
+        for reopen_count in 1..=SessionAccount::MAX_REOPENS {
+            let expires_at = session(&mut harness, &owner).expires_at;
+            let now = expires_at + grace - 1;
+            harness.warp_to(now);
+            let (treasury, wallet) = (harness.balance(&authority.pubkey()), harness.balance(&owner.pubkey()));
+            reopen(&mut harness).unwrap();
+            assert_eq!(harness.balance(&authority.pubkey()), treasury + fee);
+            assert_eq!(harness.balance(&owner.pubkey()), wallet - fee);
+            let reopened = session(&mut harness, &owner);
+            assert_eq!((reopened.expires_at, reopened.reopen_count), (now + 300, reopen_count));
+        }
+
+        let expires_at = session(&mut harness, &owner).expires_at;
+        harness.warp_to(expires_at);
+        assert_eq!(reopen(&mut harness), Err(custom(YourFunError::ReopenLimitReached)));
+        harness.warp_to(expires_at + grace);
+        assert_eq!(reopen(&mut harness), Err(custom(YourFunError::ReopenWindowClosed)));
+
+        // A closed session stays closed, even inside the window.
+        let mut harness = start(&authority, &owner);
+        let policy = client::build_admin_config_ix(
+            &authority.pubkey(),
+            harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count,
+            crate::instruction::SetReopenPolicy { reopen_grace_period: grace, reopen_fee_lamports: fee },
+        );
+        harness.process(policy, &[&authority]).unwrap();
+        harness.warp_to(EXPIRES_AT);
+        close(&mut harness, &owner);
+        assert_eq!(reopen(&mut harness), Err(custom(YourFunError::SessionInactive)));
+    }
+
+    #[test]
+    fn a_burned_booster_multiplies_its_session_score_once() {
+        let owner = Keypair::new();
+        let (booster, unlisted) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (booster_token, unlisted_token) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let multiplier_bps = 15_000;
+        let mut accounts = FixtureBuilder::new()
+            .with_registry(|registry| {
+                registry.boosters[0] = crate::state::BoosterMint { mint: booster, multiplier_bps };
+            })
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .build();
+        for (mint, token) in [(booster, booster_token), (unlisted, unlisted_token)] {
+            accounts.push(FixtureAccount::token(
+                mint,
+                spl_token::state::Mint { supply: 1, decimals: 0, is_initialized: true, ..Default::default() },
+            ));
+            accounts.push(FixtureAccount::token(
+                token,
+                spl_token::state::Account {
+                    mint,
+                    owner: owner.pubkey(),
+                    amount: 1,
+                    state: spl_token::state::AccountState::Initialized,
+                    ..Default::default()
+                },
+            ));
+        }
+        let mut harness = Harness::start(accounts);
+        harness.fund(&owner.pubkey(), 1_000_000_000);
+        let human_record = client::human_record_address(&owner.pubkey());
+        let create = |session_index: u64, booster: (&Pubkey, &Pubkey)| {
+            client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                session_index,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                Some(booster),
+                0,
+                false,
+                NOW,
+            )
+        };
+        let token_amount = |harness: &mut Harness, address: &Pubkey| {
+            spl_token::state::Account::unpack(&harness.raw_account(address)).unwrap().amount
+        };
+
+        assert_eq!(
+            harness.process(create(1, (&unlisted, &unlisted_token)), &[&owner]),
+            Err(custom(YourFunError::BoosterNotAccepted))
+        );
+        assert_eq!(token_amount(&mut harness, &unlisted_token), 1);
+
+        harness.process(create(1, (&booster, &booster_token)), &[&owner]).unwrap();
+        assert_eq!(token_amount(&mut harness, &booster_token), 0);
+        let mint = spl_token::state::Mint::unpack(&harness.raw_account(&booster)).unwrap();
+        assert_eq!(mint.supply, 0);
+        let boosted: SessionAccount = harness.account(&client::session_address(&human_record, 1));
+        assert_eq!(boosted.booster_multiplier_bps, multiplier_bps);
+
+        // The booster is used up, so it cannot boost another session.
+        assert_eq!(
+            harness.process(create(2, (&booster, &booster_token)), &[&owner]),
+            Err(custom(spl_token::error::TokenError::InsufficientFunds as u32))
+        );
+
+        // The same interaction earns the multiplier only in the boosted
+        // session; session 0 has no booster.
+        let mut increments = Vec::new();
+        for session_index in [0, 1] {
+            let session = client::session_address(&human_record, session_index);
+            let record = client::build_record_interaction_ix(
+                &owner.pubkey(),
+                session_index,
+                0,
+                [1u8; 32],
+                crate::state::InteractionType::Chat as u8,
+                80,
+                300,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            );
+            harness.process(record, &[&owner]).unwrap();
+            increments.push(harness.account::<SessionAccount>(&session).session_score);
+        }
+        assert!(increments[0] > 0);
+        assert_eq!(increments[1], increments[0] * multiplier_bps as u64 / MAX_BPS as u64);
+    }
+}
@@ -0,0 +1,238 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, SessionAccount, VerifierAccount};
+use crate::instructions::topic::settle_topic_progress;
+use crate::error::YourFunError;
+use crate::events::{SessionFinalized, SettlementContested};
+
+/// Adds `score` to the human's learning score and credits the session's
+/// TopicProgress accounts among `remaining_accounts`, returning the score
+/// settled. A revoked human is credited nothing.
+pub(crate) fn settle_session_score(
+    remaining_accounts: &[AccountInfo],
+    human_record: &mut Account<HumanRecord>,
+    session: &SessionAccount,
+    score: u64,
+) -> Result<u64> {
+    if !human_record.is_active() {
+        return Ok(0);
+    }
+    human_record.learning_score = human_record
+        .learning_score
+        .checked_add(score)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    settle_topic_progress(remaining_accounts, session, &human_record.key())?;
+    Ok(score)
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSession<'info> {
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_settling() @ YourFunError::SessionNotSettling,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+}
+
+/// Settles a settling session's pending score into the human's learning
+/// score once its settlement window has passed. Permissionless, and allowed
+/// while paused like `close_session`. A human revoked in the meantime is
+/// credited nothing.
+///
+/// TopicProgress accounts of the session's topics may be passed as remaining
+/// accounts, as for `close_session`.
+pub fn handler_finalize_session(ctx: Context<FinalizeSession>) -> Result<()> {
+    let human_record = &mut ctx.accounts.human_record;
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(now >= session.settles_at, YourFunError::SettlementWindowOpen);
+
+    let settled_score = settle_session_score(
+        ctx.remaining_accounts,
+        human_record,
+        session,
+        session.pending_score,
+    )?;
+    session.pending_score = 0;
+    session.set_settling(false);
+
+    emit!(SessionFinalized {
+        session: session.key(),
+        owner: session.owner,
+        session_index: session.session_index,
+        settled_score,
+        finalized_at: now,
+    });
+
+    msg!(
+        "Session {} finalized with score {}",
+        session.session_index,
+        settled_score
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ContestSettlement<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_settling() @ YourFunError::SessionNotSettling,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    /// Required unless the signer is the registry authority.
+    #[account(
+        seeds = [VerifierAccount::SEED, verifier.key().as_ref()],
+        bump = verifier_account.bump,
+    )]
+    pub verifier_account: Option<Box<Account<'info, VerifierAccount>>>,
+
+    /// The authority or an elected verifier.
+    #[account(
+        constraint = verifier.key() == registry.authority
+            || verifier_account.as_ref().is_some_and(|v| v.is_active)
+            @ YourFunError::UnauthorizedVerifier,
+    )]
+    pub verifier: Signer<'info>,
+}
+
+/// Forfeits a settling session's pending score while its settlement window
+/// is still open, so it never reaches the learning score. The session is
+/// marked contested.
+pub fn handler_contest_settlement(ctx: Context<ContestSettlement>, reason_code: u8) -> Result<()> {
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(now < session.settles_at, YourFunError::SettlementWindowClosed);
+
+    let forfeited_score = session.pending_score;
+    session.pending_score = 0;
+    session.set_settling(false);
+    session.set_contested(true);
+
+    emit!(SettlementContested {
+        session: session.key(),
+        owner: session.owner,
+        session_index: session.session_index,
+        contested_by: ctx.accounts.verifier.key(),
+        reason_code,
+        forfeited_score,
+        contested_at: now,
+    });
+
+    msg!(
+        "Settlement of session {} contested with reason {}",
+        session.session_index,
+        reason_code
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    const WINDOW: i64 = 300;
+
+    #[test]
+    fn settling_scores_reach_the_record_after_the_window_unless_contested() {
+        let [authority, owner, other, stranger] = [0; 4].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.settlement_window = WINDOW)
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_session(|session| session.session_score = 500)
+            .with_verified_human(other.pubkey())
+            .with_open_session()
+            .with_session(|session| session.session_score = 400)
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &owner, &other, &stranger] {
+            harness.fund(&signer.pubkey(), 1_000_000_000);
+        }
+        let record = |wallet: &Keypair| client::human_record_address(&wallet.pubkey());
+        let session = |wallet: &Keypair| client::session_address(&record(wallet), 0);
+        for wallet in [&owner, &other] {
+            let close = client::build_close_session_ix(&wallet.pubkey(), &wallet.pubkey(), 0, 0, false, None, None);
+            harness.process(close, &[wallet]).unwrap();
+            let settling: SessionAccount = harness.account(&session(wallet));
+            assert!(settling.is_settling());
+            assert_eq!(settling.settles_at, NOW + WINDOW);
+            assert_eq!(harness.account::<HumanRecord>(&record(wallet)).learning_score, 0);
+        }
+        let finalize = |wallet: &Keypair| client::build_finalize_session_ix(&wallet.pubkey(), 0);
+        let contest = |verifier: &Keypair, wallet: &Keypair| {
+            client::build_contest_settlement_ix(&verifier.pubkey(), false, &wallet.pubkey(), 0, 4)
+        };
+
+        harness.warp_to(NOW + WINDOW - 1);
+        assert_eq!(
+            harness.process(finalize(&owner), &[]),
+            Err(custom(YourFunError::SettlementWindowOpen))
+        );
+        assert_eq!(
+            harness.process(contest(&stranger, &other), &[&stranger]),
+            Err(custom(YourFunError::UnauthorizedVerifier))
+        );
+        harness.process(contest(&authority, &other), &[&authority]).unwrap();
+        let contested: SessionAccount = harness.account(&session(&other));
+        assert!(contested.is_contested() && !contested.is_settling());
+        assert_eq!(contested.pending_score, 0);
+
+        harness.warp_to(NOW + WINDOW);
+        assert_eq!(
+            harness.process(contest(&authority, &owner), &[&authority]),
+            Err(custom(YourFunError::SettlementWindowClosed))
+        );
+        // Anyone may finalize once the window has passed.
+        harness.process(finalize(&owner), &[]).unwrap();
+        assert_eq!(harness.account::<HumanRecord>(&record(&owner)).learning_score, 500);
+        let finalized: SessionAccount = harness.account(&session(&owner));
+        assert!(!finalized.is_settling());
+        assert_eq!(finalized.pending_score, 0);
+        for wallet in [&owner, &other] {
+            assert_eq!(
+                harness.process(finalize(wallet), &[]),
+                Err(custom(YourFunError::SessionNotSettling))
+            );
+        }
+        assert_eq!(harness.account::<HumanRecord>(&record(&other)).learning_score, 0);
+    }
+}
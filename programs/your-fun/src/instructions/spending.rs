@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use crate::state::HumanRecord;
+
+#[derive(Accounts)]
+pub struct SetSpendingCap<'info> {
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Caps the lamports the owner's session escrow deposits and reopen fees may
+/// total per spend window; 0 removes the cap. Lamports already spent in the
+/// current window count against a new cap.
+pub fn handler_set_spending_cap(ctx: Context<SetSpendingCap>, lamports_per_30d: u64) -> Result<()> {
+    let human_record = &mut ctx.accounts.human_record;
+    human_record.spending_cap_lamports = lamports_per_30d;
+
+    msg!(
+        "Spending cap of {} set to {} lamports per 30 days",
+        human_record.wallet,
+        lamports_per_30d
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::Instruction;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use crate::client;
+    use crate::error::YourFunError;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, ed25519_instruction, Harness, NOW};
+    use crate::instructions::{bundle_message, InteractionInput};
+    use crate::state::{HumanRecord, InteractionType};
+
+    const REOPEN_FEE: u64 = 10_000_000;
+    /// Escrowed for a one-hour session at the fixture's maximum duration.
+    const RATE: u64 = 100_000;
+    const EXPIRES_AT: i64 = NOW + 600;
+    /// Two escrow deposits at `RATE` and a reopen fee exceed it; one and a
+    /// half and the fee meet it exactly.
+    const CAP: u64 = 90 * RATE + REOPEN_FEE;
+
+    fn create(owner: &Keypair, session_index: u64, escrow_rate_per_minute: u64, now: i64) -> Instruction {
+        client::build_create_session_ix(
+            &owner.pubkey(),
+            None,
+            session_index,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            escrow_rate_per_minute,
+            false,
+            now,
+        )
+    }
+
+    #[test]
+    fn spends_accumulate_across_instructions_up_to_the_cap_and_reset_with_the_window() {
+        let [authority, owner, delegate, relayer] = [0; 4].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.reopen_grace_period = 120;
+                registry.reopen_fee_lamports = REOPEN_FEE;
+            })
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_session(|session| session.expires_at = EXPIRES_AT)
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &owner, &relayer] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let human_record = client::human_record_address(&owner.pubkey());
+        let spent = |harness: &mut Harness| {
+            let record: HumanRecord = harness.account(&human_record);
+            (record.spend_window_start, record.spent_in_window)
+        };
+
+        harness.process(client::build_set_spending_cap_ix(&owner.pubkey(), CAP), &[&owner]).unwrap();
+        assert_eq!(harness.account::<HumanRecord>(&human_record).spending_cap_lamports, CAP);
+
+        // An escrow deposit opens the window.
+        harness.warp_to(NOW + 10);
+        harness.process(create(&owner, 1, RATE, NOW + 10), &[&owner]).unwrap();
+        assert_eq!(spent(&mut harness), (NOW + 10, 60 * RATE));
+
+        // The delegate only signs fee-free bundles, which spend nothing.
+        harness
+            .process(client::build_set_interaction_delegate_ix(&owner.pubkey(), delegate.pubkey()), &[&owner])
+            .unwrap();
+        let session = client::session_address(&human_record, 0);
+        let entries = vec![InteractionInput {
+            content_hash: [1u8; 32],
+            interaction_type: InteractionType::Chat as u8,
+            score: 60,
+            duration_seconds: 30,
+            occurred_at: NOW + 10,
+        }];
+        let (verify, signature) = ed25519_instruction(&delegate, &bundle_message(&session, &entries).unwrap());
+        let submit =
+            client::build_submit_interaction_bundle_ix(&relayer.pubkey(), &owner.pubkey(), 0, entries, signature, NOW + 10);
+        let owner_before = harness.balance(&owner.pubkey());
+        harness.process_all(&[verify, submit], &[&relayer]).unwrap();
+        assert_eq!(harness.balance(&owner.pubkey()), owner_before);
+        assert_eq!(spent(&mut harness), (NOW + 10, 60 * RATE));
+
+        // The reopen fee adds to the same window.
+        harness.warp_to(EXPIRES_AT);
+        let reopen = client::build_reopen_session_ix(&owner.pubkey(), 0, 0, &authority.pubkey(), false, 300);
+        harness.process(reopen, &[&owner]).unwrap();
+        assert_eq!(spent(&mut harness), (NOW + 10, 60 * RATE + REOPEN_FEE));
+
+        assert_eq!(
+            harness.process(create(&owner, 2, RATE, EXPIRES_AT), &[&owner]),
+            Err(custom(YourFunError::SpendingCapExceeded))
+        );
+        // Up to the cap exactly is allowed.
+        harness.process(create(&owner, 2, RATE / 2, EXPIRES_AT), &[&owner]).unwrap();
+        assert_eq!(spent(&mut harness), (NOW + 10, CAP));
+
+        // A new window opens once the last one has run its 30 days.
+        let rollover = NOW + 10 + HumanRecord::SPEND_WINDOW;
+        harness.warp_to(rollover - 1);
+        assert_eq!(
+            harness.process(create(&owner, 3, RATE, rollover - 1), &[&owner]),
+            Err(custom(YourFunError::SpendingCapExceeded))
+        );
+        harness.warp_to(rollover);
+        harness.process(create(&owner, 3, RATE, rollover), &[&owner]).unwrap();
+        assert_eq!(spent(&mut harness), (rollover, 60 * RATE));
+
+        // Without a cap spends are still counted, so a later cap applies to
+        // the current window.
+        harness.process(client::build_set_spending_cap_ix(&owner.pubkey(), 0), &[&owner]).unwrap();
+        harness.process(create(&owner, 4, 4 * RATE, rollover), &[&owner]).unwrap();
+        assert_eq!(spent(&mut harness), (rollover, 300 * RATE));
+        harness.process(client::build_set_spending_cap_ix(&owner.pubkey(), 300 * RATE), &[&owner]).unwrap();
+        assert_eq!(
+            harness.process(create(&owner, 5, 1, rollover), &[&owner]),
+            Err(custom(YourFunError::SpendingCapExceeded))
+        );
+    }
+}
@@ -0,0 +1,605 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{EpochStats, PlatformRegistry, RegionStats, StatsBucket};
+use crate::error::YourFunError;
+
+#[derive(Accounts)]
+#[instruction(bucket_index: u8)]
+pub struct InitStatsBucket<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + StatsBucket::INIT_SPACE,
+        seeds = [StatsBucket::SEED, &[bucket_index]],
+        bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates one of the counter shards. Permissionless, since the bucket only
+/// ever holds counters.
+pub fn handler_init_stats_bucket(ctx: Context<InitStatsBucket>, bucket_index: u8) -> Result<()> {
+    require!(
+        bucket_index < StatsBucket::COUNT,
+        YourFunError::InvalidStatsBucket
+    );
+
+    let bucket = &mut ctx.accounts.stats_bucket;
+    bucket.bucket_index = bucket_index;
+    bucket.pending_sessions_created = 0;
+    bucket.pending_interactions = 0;
+    bucket.last_aggregated_at = 0;
+    bucket.bump = ctx.bumps.stats_bucket;
+    bucket.pending_sessions_closed = 0;
+    bucket._reserved_score = [0u8; 8];
+
+    msg!("Stats bucket {} initialized", bucket_index);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(region_code: u16)]
+pub struct InitRegionStats<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RegionStats::INIT_SPACE,
+        seeds = [RegionStats::SEED, &region_code.to_le_bytes()],
+        bump,
+    )]
+    pub region_stats: Box<Account<'info, RegionStats>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a region's counter ahead of its first verification, which
+/// otherwise creates it. Permissionless, like the stats buckets.
+pub fn handler_init_region_stats(ctx: Context<InitRegionStats>, region_code: u16) -> Result<()> {
+    require!(
+        region_code <= RegionStats::MAX_REGION_CODE,
+        YourFunError::InvalidRegionCode
+    );
+
+    let region_stats = &mut ctx.accounts.region_stats;
+    region_stats.region_code = region_code;
+    region_stats.verified_humans = 0;
+    region_stats.bump = ctx.bumps.region_stats;
+
+    msg!("Region stats {} initialized", region_code);
+    Ok(())
+}
+
+/// Reads the RegionStats for `region_code` at `info`, creating the account,
+/// paid by `payer`, when no one has verified from that region yet. The
+/// caller writes it back with `store_region_stats`.
+pub(crate) fn load_region_stats<'info>(
+    info: &AccountInfo<'info>,
+    region_code: u16,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<RegionStats> {
+    require!(
+        region_code <= RegionStats::MAX_REGION_CODE,
+        YourFunError::InvalidRegionCode
+    );
+
+    if info.owner == &crate::ID && !info.data_is_empty() {
+        let region_stats = RegionStats::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let expected = Pubkey::create_program_address(
+            &[RegionStats::SEED, &region_code.to_le_bytes(), &[region_stats.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| error!(YourFunError::InvalidRegionCode))?;
+        require_keys_eq!(info.key(), expected, YourFunError::InvalidRegionCode);
+        return Ok(region_stats);
+    }
+
+    let (expected, bump) = Pubkey::find_program_address(
+        &[RegionStats::SEED, &region_code.to_le_bytes()],
+        &crate::ID,
+    );
+    require_keys_eq!(info.key(), expected, YourFunError::InvalidRegionCode);
+    create_stats_account(
+        info,
+        payer,
+        system_program,
+        8 + RegionStats::INIT_SPACE,
+        &[RegionStats::SEED, &region_code.to_le_bytes(), &[bump]],
+    )?;
+    msg!("Region stats {} initialized", region_code);
+    Ok(RegionStats {
+        region_code,
+        verified_humans: 0,
+        bump,
+    })
+}
+
+/// Writes back a RegionStats read by `load_region_stats`.
+pub(crate) fn store_region_stats(info: &AccountInfo, region_stats: &RegionStats) -> Result<()> {
+    region_stats.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Activity to add to the current day's EpochStats.
+#[derive(Default)]
+pub(crate) struct EpochActivity {
+    pub verified_humans: u64,
+    pub sessions_created: u64,
+    pub interactions: u64,
+    pub score_awarded: u64,
+}
+
+/// Adds `activity` to the EpochStats of the day containing `now` at `info`,
+/// creating the account, paid by `payer`, on the day's first activity.
+pub(crate) fn record_epoch_activity<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    now: i64,
+    activity: EpochActivity,
+) -> Result<()> {
+    let epoch = EpochStats::epoch_at(now);
+    let mut epoch_stats = if info.owner == &crate::ID && !info.data_is_empty() {
+        let epoch_stats = EpochStats::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let expected = Pubkey::create_program_address(
+            &[EpochStats::SEED, &epoch.to_le_bytes(), &[epoch_stats.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| error!(YourFunError::StatsEpochMismatch))?;
+        require_keys_eq!(info.key(), expected, YourFunError::StatsEpochMismatch);
+        epoch_stats
+    } else {
+        let (expected, bump) = Pubkey::find_program_address(
+            &[EpochStats::SEED, &epoch.to_le_bytes()],
+            &crate::ID,
+        );
+        require_keys_eq!(info.key(), expected, YourFunError::StatsEpochMismatch);
+        create_stats_account(
+            info,
+            payer,
+            system_program,
+            8 + EpochStats::INIT_SPACE,
+            &[EpochStats::SEED, &epoch.to_le_bytes(), &[bump]],
+        )?;
+        EpochStats {
+            epoch,
+            verified_humans: 0,
+            sessions_created: 0,
+            interactions: 0,
+            total_score_awarded: 0,
+            bump,
+        }
+    };
+
+    epoch_stats.verified_humans = epoch_stats
+        .verified_humans
+        .checked_add(activity.verified_humans)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    epoch_stats.sessions_created = epoch_stats
+        .sessions_created
+        .checked_add(activity.sessions_created)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    epoch_stats.interactions = epoch_stats
+        .interactions
+        .checked_add(activity.interactions)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    epoch_stats.total_score_awarded = epoch_stats
+        .total_score_awarded
+        .checked_add(activity.score_awarded)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    epoch_stats.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Creates the program-owned account at PDA `info`, signed for by `seeds`.
+/// The address of a stats account is predictable, so it may already hold
+/// lamports; `payer` then only tops it up to rent exemption.
+fn create_stats_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+    if info.lamports() == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+                &[seeds],
+            ),
+            rent_exempt,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    let shortfall = rent_exempt.saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: info.clone(),
+            },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: info.clone(),
+            },
+            &[seeds],
+        ),
+        &crate::ID,
+    )
+}
+
+#[derive(Accounts)]
+pub struct AggregateStats<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[stats_bucket.bucket_index]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+}
+
+/// Folds a bucket's pending deltas into the registry totals and resets it.
+/// Permissionless crank; the registry totals lag live activity until the
+/// buckets are aggregated.
+pub fn handler_aggregate_stats(ctx: Context<AggregateStats>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let bucket = &mut ctx.accounts.stats_bucket;
+
+    registry.total_sessions_created = registry
+        .total_sessions_created
+        .checked_add(bucket.pending_sessions_created)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    registry.total_interactions = registry
+        .total_interactions
+        .checked_add(bucket.pending_interactions)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    let opened = registry
+        .active_sessions
+        .checked_add(bucket.pending_sessions_created)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    if bucket.pending_sessions_closed > opened {
+        // Sessions opened before the counter existed close without having
+        // been counted; clamp rather than fail the crank.
+        msg!(
+            "Warning: {} sessions closed but only {} counted open; clamping to 0",
+            bucket.pending_sessions_closed,
+            opened
+        );
+    }
+    registry.active_sessions = opened.saturating_sub(bucket.pending_sessions_closed);
+    registry.bump_state_version()?;
+
+    msg!(
+        "Stats bucket {} aggregated: sessions={}, closed={}, interactions={}",
+        bucket.bucket_index,
+        bucket.pending_sessions_created,
+        bucket.pending_sessions_closed,
+        bucket.pending_interactions
+    );
+
+    bucket.pending_sessions_created = 0;
+    bucket.pending_sessions_closed = 0;
+    bucket.pending_interactions = 0;
+    bucket.last_aggregated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use anchor_lang::solana_program::hash::hash;
+    use anchor_lang::solana_program::instruction::Instruction;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureBuilder};
+    use crate::harness::{Harness, NOW};
+    use crate::state::{HumanRecord, SessionAccount, VerificationLevel};
+
+    /// Two wallets whose first sessions land in different StatsBuckets.
+    fn wallets_in_distinct_buckets() -> (Keypair, Keypair) {
+        let bucket = |wallet: &Keypair| {
+            let session = client::session_address(&client::human_record_address(&wallet.pubkey()), 0);
+            StatsBucket::index_for(&session)
+        };
+        let first = Keypair::new();
+        let second = std::iter::repeat_with(Keypair::new)
+            .find(|second| bucket(second) != bucket(&first))
+            .unwrap();
+        (first, second)
+    }
+
+    fn record_interaction(wallet: &Keypair) -> Instruction {
+        client::build_record_interaction_ix(
+            &wallet.pubkey(),
+            0,
+            0,
+            [3u8; 32],
+            1,
+            80,
+            60,
+            [0u8; 32],
+            0,
+            false,
+            false,
+            NOW,
+        )
+    }
+
+    fn writable(ix: &Instruction) -> BTreeSet<Pubkey> {
+        ix.accounts.iter().filter(|meta| meta.is_writable).map(|meta| meta.pubkey).collect()
+    }
+
+    #[test]
+    fn interactions_of_different_humans_share_only_the_day_stats() {
+        let (first, second) = wallets_in_distinct_buckets();
+        let (first, second) = (record_interaction(&first), record_interaction(&second));
+
+        assert!(!writable(&first).contains(&client::registry_address()));
+        // The day's EpochStats is the one account every activity writes, so
+        // the dashboard totals stay exact without a crank.
+        let shared: Vec<_> = writable(&first).intersection(&writable(&second)).copied().collect();
+        assert_eq!(shared, [client::epoch_stats_address(NOW)]);
+    }
+
+    #[test]
+    fn aggregation_folds_every_bucket_into_the_registry_once() {
+        let (first, second) = wallets_in_distinct_buckets();
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(first.pubkey())
+            .with_open_session()
+            .with_verified_human(second.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        for wallet in [&first, &second] {
+            harness.fund(&wallet.pubkey(), 1_000_000_000);
+            harness.process(record_interaction(wallet), &[wallet]).unwrap();
+        }
+        let create_session = client::build_create_session_ix(
+            &first.pubkey(),
+            None,
+            1,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        );
+        harness.process(create_session, &[&first]).unwrap();
+        let before: PlatformRegistry = harness.account(&client::registry_address());
+
+        for bucket_index in 0..StatsBucket::COUNT {
+            harness.process(client::build_aggregate_stats_ix(bucket_index), &[]).unwrap();
+        }
+        let after: PlatformRegistry = harness.account(&client::registry_address());
+        assert_eq!(after.total_interactions, before.total_interactions + 2);
+        assert_eq!(after.total_sessions_created, before.total_sessions_created + 1);
+
+        // Buckets are reset, so a second pass adds nothing.
+        for bucket_index in 0..StatsBucket::COUNT {
+            harness.process(client::build_aggregate_stats_ix(bucket_index), &[]).unwrap();
+        }
+        let again: PlatformRegistry = harness.account(&client::registry_address());
+        assert_eq!(again.total_interactions, after.total_interactions);
+    }
+
+    #[test]
+    fn active_counters_follow_create_close_cycles_and_revocation() {
+        let (authority, user) = (Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_registered_human(user.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        harness.fund(&user.pubkey(), 10_000_000_000);
+        let registry = |harness: &mut Harness| harness.account::<PlatformRegistry>(&client::registry_address());
+        let human_record = client::human_record_address(&user.pubkey());
+        let mut buckets: Vec<u8> = (0..3)
+            .map(|index| StatsBucket::index_for(&client::session_address(&human_record, index)))
+            .collect();
+        buckets.sort_unstable();
+        buckets.dedup();
+        let aggregate = |harness: &mut Harness| {
+            for &bucket_index in &buckets {
+                harness.process(client::build_aggregate_stats_ix(bucket_index), &[]).unwrap();
+            }
+            registry(harness).active_sessions
+        };
+        let create = |harness: &mut Harness, session_index| {
+            let ix = client::build_create_session_ix(
+                &user.pubkey(),
+                None,
+                session_index,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                NOW,
+            );
+            harness.process(ix, &[&user]).unwrap();
+        };
+        let close = |harness: &mut Harness, session_index| {
+            let ix = client::build_close_session_ix(&user.pubkey(), &user.pubkey(), session_index, 0, false, None, None);
+            harness.process(ix, &[&user]).unwrap();
+        };
+
+        let verify = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            &user.pubkey(),
+            None,
+            &hash(&user.pubkey().to_bytes()).to_bytes(),
+            registered_challenge_response(&user.pubkey()),
+            100,
+            VerificationLevel::Basic as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            NOW,
+        );
+        harness.process(verify, &[&authority]).unwrap();
+        assert_eq!(registry(&mut harness).active_humans, 1);
+
+        create(&mut harness, 0);
+        create(&mut harness, 1);
+        assert_eq!(aggregate(&mut harness), 2);
+        close(&mut harness, 0);
+        assert_eq!(aggregate(&mut harness), 1);
+        // Opens and closes between two aggregations cancel out.
+        create(&mut harness, 2);
+        close(&mut harness, 2);
+        close(&mut harness, 1);
+        assert_eq!(aggregate(&mut harness), 0);
+        assert_eq!(aggregate(&mut harness), 0);
+
+        let revoke = client::build_revoke_human_ix(&authority.pubkey(), &user.pubkey(), 0, 0, None);
+        harness.process(revoke, &[&authority]).unwrap();
+        let after = registry(&mut harness);
+        assert_eq!((after.active_humans, after.total_verified_humans), (0, 0));
+    }
+
+    #[test]
+    fn each_day_collects_only_the_activity_within_it() {
+        let (authority, first, second) = (Keypair::new(), Keypair::new(), Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_registered_human(first.pubkey())
+            .with_registered_human(second.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        for wallet in [&authority, &first, &second] {
+            harness.fund(&wallet.pubkey(), 10_000_000_000);
+        }
+        let midnight = (EpochStats::epoch_at(NOW) as i64 + 1) * EpochStats::DURATION;
+        let verify = |harness: &mut Harness, wallet: &Keypair, now| {
+            harness.warp_to(now);
+            let ix = client::build_verify_human_ix(
+                &authority.pubkey(),
+                false,
+                &wallet.pubkey(),
+                None,
+                &hash(&wallet.pubkey().to_bytes()).to_bytes(),
+                registered_challenge_response(&wallet.pubkey()),
+                100,
+                VerificationLevel::Basic as u8,
+                0,
+                [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+                Pubkey::default(),
+                None,
+                now,
+            );
+            harness.process(ix, &[&authority]).unwrap();
+        };
+        let create = |harness: &mut Harness, wallet: &Keypair, now| {
+            harness.warp_to(now);
+            let ix = client::build_create_session_ix(
+                &wallet.pubkey(),
+                None,
+                0,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                now,
+            );
+            harness.process(ix, &[wallet]).unwrap();
+        };
+        let session = client::session_address(&client::human_record_address(&first.pubkey()), 0);
+        // Records an interaction in `first`'s session, returning the score it awarded.
+        let interact = |harness: &mut Harness, interaction_index, score, now| {
+            harness.warp_to(now);
+            let before = harness.account::<SessionAccount>(&session).session_score;
+            let ix = client::build_record_interaction_ix(
+                &first.pubkey(),
+                0,
+                interaction_index,
+                [3u8; 32],
+                1,
+                score,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                now,
+            );
+            harness.process(ix, &[&first]).unwrap();
+            harness.account::<SessionAccount>(&session).session_score - before
+        };
+
+        verify(&mut harness, &first, midnight - 20);
+        create(&mut harness, &first, midnight - 20);
+        let day_one_score = interact(&mut harness, 0, 80, midnight - 10) + interact(&mut harness, 1, 60, midnight - 1);
+        // The session spans midnight; its next interaction counts towards the new day.
+        let day_two_score = interact(&mut harness, 2, 90, midnight);
+        verify(&mut harness, &second, midnight + 5);
+        create(&mut harness, &second, midnight + 5);
+
+        let totals = |harness: &mut Harness, now| {
+            let stats: EpochStats = harness.account(&client::epoch_stats_address(now));
+            assert_eq!(stats.epoch, EpochStats::epoch_at(now));
+            (stats.verified_humans, stats.sessions_created, stats.interactions, stats.total_score_awarded)
+        };
+        assert!(day_one_score > 0 && day_two_score > 0);
+        assert_eq!(totals(&mut harness, midnight - 1), (1, 1, 2, day_one_score));
+        assert_eq!(totals(&mut harness, midnight), (1, 1, 1, day_two_score));
+    }
+}
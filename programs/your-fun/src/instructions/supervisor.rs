@@ -0,0 +1,281 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, AdminAction, AdminActionKind};
+use crate::instructions::admin::record_admin_action;
+use crate::error::YourFunError;
+use crate::events::SupervisorChanged;
+
+#[derive(Accounts)]
+pub struct SetSupervisor<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Places a human under `supervisor`, replacing any previous one. Clearing
+/// goes through `clear_supervisor`, which also needs the owner.
+pub fn handler_set_supervisor(ctx: Context<SetSupervisor>, supervisor: Pubkey) -> Result<()> {
+    require!(supervisor != Pubkey::default(), YourFunError::InvalidConfigValue);
+
+    let human_record = &mut ctx.accounts.human_record;
+    require!(
+        supervisor != human_record.wallet,
+        YourFunError::InvalidConfigValue
+    );
+    human_record.supervisor = supervisor;
+    let wallet = human_record.wallet;
+
+    record_admin_action(
+        &mut ctx.accounts.registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::SetSupervisor,
+        ctx.accounts.authority.key(),
+        &(wallet, supervisor),
+    )?;
+
+    emit!(SupervisorChanged {
+        wallet,
+        supervisor,
+        changed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Supervisor of wallet {} set to {}", wallet, supervisor);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClearSupervisor<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_supervised() @ YourFunError::InvalidConfigValue,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Lifts a human's supervision. Needs both the owner and the authority, so
+/// neither can drop the guardian alone.
+pub fn handler_clear_supervisor(ctx: Context<ClearSupervisor>) -> Result<()> {
+    let human_record = &mut ctx.accounts.human_record;
+    human_record.supervisor = Pubkey::default();
+    let wallet = human_record.wallet;
+
+    record_admin_action(
+        &mut ctx.accounts.registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::SetSupervisor,
+        ctx.accounts.authority.key(),
+        &(wallet, Pubkey::default()),
+    )?;
+
+    emit!(SupervisorChanged {
+        wallet,
+        supervisor: Pubkey::default(),
+        changed_by: ctx.accounts.authority.key(),
+    });
+
+    msg!("Supervisor of wallet {} cleared", wallet);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::error::ErrorCode;
+    use anchor_lang::solana_program::hash::hash;
+    use anchor_lang::solana_program::instruction::Instruction;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::VerificationLevel;
+
+    fn admin_action_count(harness: &mut Harness) -> u64 {
+        harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count
+    }
+
+    fn create(owner: &Keypair, supervisor: Option<&Pubkey>, session_index: u64) -> Instruction {
+        client::build_create_session_ix(
+            &owner.pubkey(),
+            supervisor,
+            session_index,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        )
+    }
+
+    #[test]
+    fn supervised_humans_need_the_supervisor_to_open_and_extend_and_either_to_close() {
+        let [authority, owner, guardian, stranger] = [0; 4].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_registered_human(owner.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &owner, &guardian, &stranger] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let human_record = client::human_record_address(&owner.pubkey());
+
+        // Supervision is set at verification from the verifier's approval.
+        let verify = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            &owner.pubkey(),
+            None,
+            &hash(&owner.pubkey().to_bytes()).to_bytes(),
+            registered_challenge_response(&owner.pubkey()),
+            100,
+            VerificationLevel::Basic as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            guardian.pubkey(),
+            None,
+            NOW,
+        );
+        harness.process(verify, &[&authority]).unwrap();
+        assert_eq!(harness.account::<HumanRecord>(&human_record).supervisor, guardian.pubkey());
+
+        for (supervisor, signers) in [(None, vec![&owner]), (Some(&stranger), vec![&owner, &stranger])] {
+            assert_eq!(
+                harness.process(create(&owner, supervisor.map(|s| s.pubkey()).as_ref(), 0), &signers),
+                Err(custom(YourFunError::SupervisorSignatureRequired))
+            );
+        }
+        harness.process(create(&owner, Some(&guardian.pubkey()), 0), &[&owner, &guardian]).unwrap();
+
+        let extend = |supervisor: Option<&Pubkey>| client::build_extend_session_ix(&owner.pubkey(), supervisor, 0, 60);
+        assert_eq!(
+            harness.process(extend(None), &[&owner]),
+            Err(custom(YourFunError::SupervisorSignatureRequired))
+        );
+        harness.process(extend(Some(&guardian.pubkey())), &[&owner, &guardian]).unwrap();
+
+        let close = |closer: &Keypair, session_index: u64| {
+            client::build_close_session_ix(&owner.pubkey(), &closer.pubkey(), session_index, 0, false, None, None)
+        };
+        assert_eq!(
+            harness.process(close(&stranger, 0), &[&stranger]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+        harness.process(close(&guardian, 0), &[&guardian]).unwrap();
+        harness.process(create(&owner, Some(&guardian.pubkey()), 1), &[&owner, &guardian]).unwrap();
+        harness.process(close(&owner, 1), &[&owner]).unwrap();
+
+        // Only the authority may set a supervisor.
+        let set = client::build_set_supervisor_ix(
+            &stranger.pubkey(),
+            admin_action_count(&mut harness),
+            &owner.pubkey(),
+            stranger.pubkey(),
+        );
+        assert_eq!(
+            harness.process(set, &[&stranger]),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+
+        // Clearing needs both the owner and the authority.
+        let clear = |harness: &mut Harness, missing: Option<&Keypair>| {
+            let mut ix = client::build_clear_supervisor_ix(
+                &owner.pubkey(),
+                &authority.pubkey(),
+                admin_action_count(harness),
+            );
+            let mut signers = vec![&owner, &authority];
+            if let Some(missing) = missing {
+                for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == missing.pubkey()) {
+                    meta.is_signer = false;
+                }
+                signers.retain(|signer| signer.pubkey() != missing.pubkey());
+            }
+            harness.process(ix, &signers)
+        };
+        for missing in [&owner, &authority] {
+            assert_eq!(clear(&mut harness, Some(missing)), Err(custom(ErrorCode::AccountNotSigner)));
+        }
+        clear(&mut harness, None).unwrap();
+        assert!(!harness.account::<HumanRecord>(&human_record).is_supervised());
+        harness.process(create(&owner, None, 2), &[&owner]).unwrap();
+
+        // The authority can place the human under supervision again.
+        let set = client::build_set_supervisor_ix(
+            &authority.pubkey(),
+            admin_action_count(&mut harness),
+            &owner.pubkey(),
+            guardian.pubkey(),
+        );
+        harness.process(set, &[&authority]).unwrap();
+        assert_eq!(
+            harness.process(create(&owner, None, 3), &[&owner]),
+            Err(custom(YourFunError::SupervisorSignatureRequired))
+        );
+    }
+}
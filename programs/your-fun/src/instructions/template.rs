@@ -0,0 +1,303 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    PlatformRegistry, HumanRecord, SessionAccount, SessionTemplate, StatsBucket, ActiveSessionIndex,
+    PersonalityConfig, ScoringProfile, GoalType,
+};
+use crate::instructions::session::{open_session, SessionSetup};
+use crate::instructions::stats::{record_epoch_activity, EpochActivity};
+use crate::error::YourFunError;
+
+#[derive(Accounts)]
+#[instruction(template_id: u16)]
+pub struct CreateTemplate<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.is_curator(&curator.key()) @ YourFunError::Unauthorized,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = curator,
+        space = 8 + SessionTemplate::INIT_SPACE,
+        seeds = [SessionTemplate::SEED, &template_id.to_le_bytes()],
+        bump,
+    )]
+    pub template: Box<Account<'info, SessionTemplate>>,
+
+    /// The authority or the registry's curator.
+    #[account(mut)]
+    pub curator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Publishes a session template, enabled from the start.
+/// `duration_override` of 0 uses the registry's session duration; otherwise
+/// it may be at most twice that, the longest an extended session can last.
+pub fn handler_create_template(
+    ctx: Context<CreateTemplate>,
+    template_id: u16,
+    personality_id: u8,
+    topic: [u8; 32],
+    duration_override: i64,
+    scoring_profile: u8,
+) -> Result<()> {
+    require!(template_id != 0, YourFunError::InvalidConfigValue);
+    ScoringProfile::try_from(scoring_profile)?;
+    ctx.accounts.registry.require_known_personality(personality_id)?;
+    template_duration(&ctx.accounts.registry, duration_override)?;
+
+    let template = &mut ctx.accounts.template;
+    template.template_id = template_id;
+    template.personality_id = personality_id;
+    template.topic = topic;
+    template.duration_override = duration_override;
+    template.scoring_profile = scoring_profile;
+    template.enabled = true;
+    template.created_by = ctx.accounts.curator.key();
+    template.bump = ctx.bumps.template;
+
+    msg!("Session template {} created", template_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTemplateEnabled<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.is_curator(&curator.key()) @ YourFunError::Unauthorized,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [SessionTemplate::SEED, &template.template_id.to_le_bytes()],
+        bump = template.bump,
+    )]
+    pub template: Box<Account<'info, SessionTemplate>>,
+
+    /// The authority or the registry's curator.
+    pub curator: Signer<'info>,
+}
+
+/// Enables or disables launching sessions from a template. Sessions already
+/// launched from it are unaffected.
+pub fn handler_set_template_enabled(ctx: Context<SetTemplateEnabled>, enabled: bool) -> Result<()> {
+    let template = &mut ctx.accounts.template;
+    template.enabled = enabled;
+
+    msg!("Session template {} enabled: {}", template.template_id, enabled);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: u16)]
+pub struct CreateSessionFromTemplate<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        seeds = [SessionTemplate::SEED, &template_id.to_le_bytes()],
+        bump = template.bump,
+        constraint = template.enabled @ YourFunError::TemplateDisabled,
+    )]
+    pub template: Box<Account<'info, SessionTemplate>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
+        constraint = human_record.wallet == owner.key() @ YourFunError::Unauthorized,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + SessionAccount::INIT_SPACE,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &human_record.session_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    #[account(
+        mut,
+        seeds = [StatsBucket::SEED, &[StatsBucket::index_for(&session.key())]],
+        bump = stats_bucket.bump,
+    )]
+    pub stats_bucket: Box<Account<'info, StatsBucket>>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [ActiveSessionIndex::SEED, human_record.key().as_ref()],
+        bump = active_index.bump,
+    )]
+    pub active_index: Option<Box<Account<'info, ActiveSessionIndex>>>,
+
+    /// Settings of the template's personality, if the authority created any.
+    /// CHECK: Address is pinned by seeds; contents are read only when the
+    /// account is owned by this program.
+    #[account(
+        seeds = [PersonalityConfig::SEED, &[template.personality_id]],
+        bump,
+    )]
+    pub personality_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Required when the human is supervised.
+    pub supervisor: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a session with the personality, topic, duration and scoring profile
+/// of an enabled template, recording the template on the session. Otherwise
+/// identical to `create_session`, including the returned session nonce.
+pub fn handler_create_session_from_template(
+    ctx: Context<CreateSessionFromTemplate>,
+    template_id: u16,
+) -> Result<[u8; 32]> {
+    let accounts = ctx.accounts;
+    let template = &accounts.template;
+    let setup = SessionSetup {
+        personality_id: template.personality_id,
+        topic: template.topic,
+        duration: template_duration(&accounts.registry, template.duration_override)?,
+        template_id,
+        scoring_profile: template.scoring_profile,
+        goal_type: GoalType::None as u8,
+        goal_target: 0,
+        booster_multiplier_bps: 0,
+    };
+    let session_nonce = open_session(
+        &accounts.registry,
+        &mut accounts.human_record,
+        &mut accounts.session,
+        ctx.bumps.session,
+        &mut accounts.stats_bucket,
+        accounts.active_index.as_deref_mut(),
+        &accounts.personality_config,
+        accounts.owner.key(),
+        accounts.supervisor.as_ref().map(|s| s.key()),
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &setup,
+    )?;
+    record_epoch_activity(
+        &accounts.epoch_stats,
+        &accounts.owner,
+        &accounts.system_program,
+        Clock::get()?.unix_timestamp,
+        EpochActivity { sessions_created: 1, ..Default::default() },
+    )?;
+    Ok(session_nonce)
+}
+
+/// Session duration for a template's `duration_override` under the current
+/// registry settings, rejecting overrides beyond twice the registry duration.
+fn template_duration(registry: &PlatformRegistry, duration_override: i64) -> Result<i64> {
+    if duration_override == 0 {
+        return Ok(registry.max_session_duration);
+    }
+    let max_allowed = registry
+        .max_session_duration
+        .checked_mul(2)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    require!(
+        duration_override > 0 && duration_override <= max_allowed,
+        YourFunError::SessionDurationExceeded
+    );
+    Ok(duration_override)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    #[test]
+    fn sessions_launch_only_from_enabled_templates_with_bounded_durations() {
+        let [authority, curator, owner, outsider] = [0; 4].map(|_| Keypair::new());
+        let curator_key = curator.pubkey();
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.curator = curator_key)
+            .with_verified_human(owner.pubkey())
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &curator, &owner, &outsider] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let default_duration = harness
+            .account::<PlatformRegistry>(&client::registry_address())
+            .max_session_duration;
+        let (topic, quiz_heavy) = ([8u8; 32], ScoringProfile::QuizHeavy as u8);
+        let create = |signer: &Keypair, template_id, duration_override| {
+            client::build_create_template_ix(&signer.pubkey(), template_id, 3, topic, duration_override, quiz_heavy)
+        };
+        let launch = |harness: &mut Harness, session_index, template_id| {
+            let ix = client::build_create_session_from_template_ix(&owner.pubkey(), session_index, template_id, 3, NOW);
+            harness.process(ix, &[&owner])
+        };
+        let session = |harness: &mut Harness, session_index| -> SessionAccount {
+            let human_record = client::human_record_address(&owner.pubkey());
+            harness.account(&client::session_address(&human_record, session_index))
+        };
+
+        assert_eq!(
+            harness.process(create(&outsider, 1, 0), &[&outsider]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+        assert_eq!(
+            harness.process(create(&curator, 1, 2 * default_duration + 1), &[&curator]),
+            Err(custom(YourFunError::SessionDurationExceeded))
+        );
+        harness.process(create(&curator, 1, 2 * default_duration), &[&curator]).unwrap();
+        harness.process(create(&authority, 2, 0), &[&authority]).unwrap();
+
+        launch(&mut harness, 0, 1).unwrap();
+        let from_override = session(&mut harness, 0);
+        assert_eq!(
+            (from_override.template_id, from_override.personality_id, from_override.scoring_profile),
+            (1, 3, quiz_heavy)
+        );
+        assert_eq!(from_override.current_topic, topic);
+        assert_eq!(from_override.expires_at, NOW + 2 * default_duration);
+
+        launch(&mut harness, 1, 2).unwrap();
+        let from_default = session(&mut harness, 1);
+        assert_eq!(from_default.template_id, 2);
+        assert_eq!(from_default.expires_at, NOW + default_duration);
+
+        harness
+            .process(client::build_set_template_enabled_ix(&curator.pubkey(), 1, false), &[&curator])
+            .unwrap();
+        assert_eq!(launch(&mut harness, 2, 1), Err(custom(YourFunError::TemplateDisabled)));
+        harness
+            .process(client::build_set_template_enabled_ix(&curator.pubkey(), 1, true), &[&curator])
+            .unwrap();
+        launch(&mut harness, 2, 1).unwrap();
+    }
+}
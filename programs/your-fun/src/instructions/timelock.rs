@@ -0,0 +1,298 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, AdminAction, AdminActionKind, ConfigUpdate, PendingConfig};
+use crate::instructions::admin::{publish_config_change, record_admin_action};
+use crate::error::YourFunError;
+use crate::events::{ConfigUpdateCancelled, ConfigUpdateExecuted, ConfigUpdateQueued};
+
+#[derive(Accounts)]
+pub struct QueueConfigUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    /// Fails to initialize while another update is pending.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PendingConfig::INIT_SPACE,
+        seeds = [PendingConfig::SEED],
+        bump,
+    )]
+    pub pending_config: Box<Account<'info, PendingConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Queues `update` to take effect once the registry's config timelock has
+/// passed. The values are validated now, so integrators can rely on what
+/// they see queued, and again at execution. Authority only.
+pub fn handler_queue_config_update(ctx: Context<QueueConfigUpdate>, update: ConfigUpdate) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let now = Clock::get()?.unix_timestamp;
+    let effective_at = now
+        .checked_add(registry.config_timelock_seconds)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    PlatformRegistry::clone(registry).apply_config_update(&update, effective_at)?;
+    let pending_config = &mut ctx.accounts.pending_config;
+    pending_config.update = update;
+    pending_config.queued_at = now;
+    pending_config.effective_at = effective_at;
+    pending_config.bump = ctx.bumps.pending_config;
+
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::QueueConfigUpdate,
+        ctx.accounts.authority.key(),
+        &(update, effective_at),
+    )?;
+
+    emit!(ConfigUpdateQueued { update, effective_at });
+
+    msg!("Config update queued, effective at {}", effective_at);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = !registry.config_frozen @ YourFunError::ConfigFrozen,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PendingConfig::SEED],
+        bump = pending_config.bump,
+    )]
+    pub pending_config: Box<Account<'info, PendingConfig>>,
+
+    /// Paid for the pending update and receives its rent back.
+    /// CHECK: Only used as the lamport destination; validated against the registry.
+    #[account(
+        mut,
+        constraint = authority.key() == registry.authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub authority: UncheckedAccount<'info>,
+}
+
+/// Applies the queued update once its timelock has passed. Permissionless,
+/// so a queued change cannot be held back by the authority alone; use
+/// `cancel_config_update` to withdraw it.
+pub fn handler_execute_config_update(ctx: Context<ExecuteConfigUpdate>) -> Result<()> {
+    let pending_config = &ctx.accounts.pending_config;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= pending_config.effective_at,
+        YourFunError::ConfigTimelockNotElapsed
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    let old_fee = registry.verification_fee_lamports;
+    registry.apply_config_update(&pending_config.update, now)?;
+    registry.bump_state_version()?;
+    publish_config_change(
+        registry,
+        pending_config.update.field_mask(),
+        old_fee,
+        ctx.accounts.authority.key(),
+    )?;
+
+    emit!(ConfigUpdateExecuted {
+        update: pending_config.update,
+        executed_at: now,
+    });
+
+    msg!("Queued config update executed");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [PendingConfig::SEED],
+        bump = pending_config.bump,
+    )]
+    pub pending_config: Box<Account<'info, PendingConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraws the queued config update. Authority only.
+pub fn handler_cancel_config_update(ctx: Context<CancelConfigUpdate>) -> Result<()> {
+    let update = ctx.accounts.pending_config.update;
+    let registry = &mut ctx.accounts.registry;
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::CancelConfigUpdate,
+        ctx.accounts.authority.key(),
+        &update,
+    )?;
+
+    emit!(ConfigUpdateCancelled {
+        update,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Queued config update cancelled");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+
+    const DELAY: i64 = 86_400;
+
+    fn registry(harness: &mut Harness) -> PlatformRegistry {
+        harness.account(&client::registry_address())
+    }
+
+    fn queue(harness: &mut Harness, signer: &Keypair, update: ConfigUpdate) -> std::result::Result<(), InstructionError> {
+        let count = registry(harness).admin_action_count;
+        harness.process(client::build_queue_config_update_ix(&signer.pubkey(), count, update), &[signer])
+    }
+
+    fn cancel(harness: &mut Harness, signer: &Keypair) -> std::result::Result<(), InstructionError> {
+        let count = registry(harness).admin_action_count;
+        harness.process(client::build_cancel_config_update_ix(&signer.pubkey(), count), &[signer])
+    }
+
+    #[test]
+    fn queued_updates_apply_only_after_the_delay_unless_cancelled() {
+        let authority = Keypair::new();
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.config_timelock_seconds = DELAY)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 1_000_000_000);
+        let stranger = harness.wallet();
+        let execute = client::build_execute_config_update_ix(&authority.pubkey());
+        let raise_score = ConfigUpdate { min_behavioral_score: Some(50), ..ConfigUpdate::default() };
+
+        // The immediate setter is closed while the timelock is set.
+        let immediate = client::build_admin_config_ix(
+            &authority.pubkey(),
+            0,
+            crate::instruction::UpdateConfig {
+                verification_fee_lamports: None,
+                min_behavioral_score: Some(50),
+                max_session_duration: None,
+                max_interactions_per_session: None,
+            },
+        );
+        assert_eq!(
+            harness.process(immediate, &[&authority]),
+            Err(custom(YourFunError::ConfigTimelocked))
+        );
+        // Invalid values are rejected when queued, not only at execution.
+        let invalid = ConfigUpdate { max_session_duration: Some(0), ..ConfigUpdate::default() };
+        assert_eq!(
+            queue(&mut harness, &authority, invalid),
+            Err(custom(YourFunError::SessionDurationExceeded))
+        );
+        assert_eq!(
+            queue(&mut harness, &stranger, raise_score),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+
+        let queued = registry(&mut harness).admin_action_count;
+        let events = harness
+            .process_with_events::<ConfigUpdateQueued>(
+                client::build_queue_config_update_ix(&authority.pubkey(), queued, raise_score),
+                &[&authority],
+            )
+            .unwrap();
+        assert_eq!(events[0].effective_at, NOW + DELAY);
+        // Only one update can be pending at a time.
+        assert!(queue(&mut harness, &authority, raise_score).is_err());
+
+        harness.warp_to(NOW + DELAY - 1);
+        assert_eq!(
+            harness.process(execute.clone(), &[]),
+            Err(custom(YourFunError::ConfigTimelockNotElapsed))
+        );
+        assert_eq!(registry(&mut harness).min_behavioral_score, 0);
+
+        harness.warp_to(NOW + DELAY);
+        let before = harness.balance(&authority.pubkey());
+        let events = harness.process_with_events::<ConfigUpdateExecuted>(execute.clone(), &[]).unwrap();
+        assert_eq!(events[0].executed_at, NOW + DELAY);
+        assert_eq!(registry(&mut harness).min_behavioral_score, 50);
+        assert!(harness.try_account::<PendingConfig>(&client::pending_config_address()).is_none());
+        assert!(harness.balance(&authority.pubkey()) > before);
+
+        // A cancelled update is gone and can no longer be executed.
+        let lower_score = ConfigUpdate { min_behavioral_score: Some(10), ..ConfigUpdate::default() };
+        queue(&mut harness, &authority, lower_score).unwrap();
+        assert_eq!(cancel(&mut harness, &stranger), Err(custom(YourFunError::AuthorityMismatch)));
+        cancel(&mut harness, &authority).unwrap();
+        assert!(harness.try_account::<PendingConfig>(&client::pending_config_address()).is_none());
+        harness.warp_to(NOW + 3 * DELAY);
+        assert!(harness.process(execute, &[]).is_err());
+        assert_eq!(registry(&mut harness).min_behavioral_score, 50);
+    }
+}
@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{PlatformRegistry, PersonalityConfig, PersonalityTreasury};
+use crate::error::YourFunError;
+use crate::events::{PersonalityTipped, PersonalityTipsWithdrawn};
+
+#[derive(Accounts)]
+#[instruction(personality_id: u8)]
+pub struct TipPersonality<'info> {
+    /// Only personalities the authority has configured can be tipped.
+    #[account(
+        seeds = [PersonalityConfig::SEED, &[personality_id]],
+        bump = personality_config.bump,
+    )]
+    pub personality_config: Box<Account<'info, PersonalityConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = tipper,
+        space = 8 + PersonalityTreasury::INIT_SPACE,
+        seeds = [PersonalityTreasury::SEED, &[personality_id]],
+        bump,
+    )]
+    pub treasury: Box<Account<'info, PersonalityTreasury>>,
+
+    #[account(mut)]
+    pub tipper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sends `amount` lamports to a personality's treasury. Deprecated
+/// personalities can still be tipped; the first tipper pays the treasury's
+/// rent.
+pub fn handler_tip_personality(
+    ctx: Context<TipPersonality>,
+    personality_id: u8,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, YourFunError::ZeroAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.tipper.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let treasury = &mut ctx.accounts.treasury;
+    if treasury.bump == 0 {
+        treasury.personality_id = personality_id;
+        treasury.bump = ctx.bumps.treasury;
+    }
+    treasury.total_tips = treasury
+        .total_tips
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+    treasury.tip_count = treasury
+        .tip_count
+        .checked_add(1)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(PersonalityTipped {
+        personality_id,
+        tipper: ctx.accounts.tipper.key(),
+        amount,
+        total_tips: treasury.total_tips,
+    });
+
+    msg!("Personality {} tipped {} lamports", personality_id, amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPersonalityTips<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [PersonalityTreasury::SEED, &[treasury.personality_id]],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, PersonalityTreasury>>,
+
+    /// CHECK: Only receives lamports.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Moves `amount` tipped lamports from a personality treasury to
+/// `recipient`, keeping the treasury rent-exempt. Authority only.
+pub fn handler_withdraw_personality_tips(
+    ctx: Context<WithdrawPersonalityTips>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, YourFunError::ZeroAmount);
+
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(treasury_info.data_len());
+    let available = treasury_info.lamports().saturating_sub(rent_exempt);
+    require!(amount <= available, YourFunError::InsufficientFunds);
+
+    let recipient_info = ctx.accounts.recipient.to_account_info();
+    **treasury_info.try_borrow_mut_lamports()? -= amount;
+    **recipient_info.try_borrow_mut_lamports()? = recipient_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_withdrawn = treasury
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(YourFunError::NumericalOverflow)?;
+
+    emit!(PersonalityTipsWithdrawn {
+        personality_id: treasury.personality_id,
+        recipient: recipient_info.key(),
+        amount,
+    });
+
+    msg!(
+        "{} lamports of tips withdrawn from personality {}",
+        amount,
+        treasury.personality_id
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::error::ErrorCode;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+
+    #[test]
+    fn tips_reach_registered_personalities_and_only_the_authority_withdraws() {
+        let [authority, tipper, stranger] = [0; 3].map(|_| Keypair::new());
+        let recipient = Pubkey::new_unique();
+        let (config, bump) = Pubkey::find_program_address(&[PersonalityConfig::SEED, &[1]], &crate::ID);
+        let mut accounts = FixtureBuilder::new().with_authority(authority.pubkey()).build();
+        // Deprecated, which does not stop tips.
+        accounts.push(FixtureAccount::new(
+            config,
+            &PersonalityConfig {
+                personality_id: 1,
+                deprecated_after: NOW - 1,
+                bump,
+                partner: Pubkey::default(),
+                partner_share_bps: 0,
+                partner_accrued: 0,
+                partner_claimed: 0,
+            },
+        ));
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &tipper, &stranger] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let treasury = client::personality_treasury_address(1);
+
+        assert_eq!(
+            harness.process(client::build_tip_personality_ix(&tipper.pubkey(), 2, 1_000), &[&tipper]),
+            Err(custom(ErrorCode::AccountNotInitialized))
+        );
+        assert_eq!(
+            harness.process(client::build_tip_personality_ix(&tipper.pubkey(), 1, 0), &[&tipper]),
+            Err(custom(YourFunError::ZeroAmount))
+        );
+
+        let before = harness.balance(&tipper.pubkey());
+        harness.process(client::build_tip_personality_ix(&tipper.pubkey(), 1, 3_000_000), &[&tipper]).unwrap();
+        let rent = harness.balance(&treasury) - 3_000_000;
+        assert_eq!(before - harness.balance(&tipper.pubkey()), rent + 3_000_000);
+        harness.process(client::build_tip_personality_ix(&stranger.pubkey(), 1, 2_000_000), &[&stranger]).unwrap();
+        assert_eq!(harness.balance(&treasury), rent + 5_000_000);
+        let state: PersonalityTreasury = harness.account(&treasury);
+        assert_eq!((state.personality_id, state.total_tips, state.tip_count), (1, 5_000_000, 2));
+
+        let withdraw = |signer: &Keypair, amount: u64| {
+            client::build_withdraw_personality_tips_ix(&signer.pubkey(), 1, &recipient, amount)
+        };
+        assert_eq!(
+            harness.process(withdraw(&stranger, 1_000_000), &[&stranger]),
+            Err(custom(YourFunError::AuthorityMismatch))
+        );
+        // The treasury's rent stays put.
+        assert_eq!(
+            harness.process(withdraw(&authority, 5_000_001), &[&authority]),
+            Err(custom(YourFunError::InsufficientFunds))
+        );
+        harness.process(withdraw(&authority, 5_000_000), &[&authority]).unwrap();
+        assert_eq!(harness.balance(&recipient), 5_000_000);
+        assert_eq!(harness.balance(&treasury), rent);
+        assert_eq!(harness.account::<PersonalityTreasury>(&treasury).total_withdrawn, 5_000_000);
+    }
+}
@@ -0,0 +1,424 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, HumanRecord, SessionAccount, Topic, TopicProgress};
+use crate::error::YourFunError;
+
+/// While the registry enforces registered topics, requires the Topic
+/// account of the topic being used, enabled. Otherwise any topic goes.
+pub(crate) fn require_registered_topic(
+    registry: &PlatformRegistry,
+    registered_topic: Option<&Account<Topic>>,
+) -> Result<()> {
+    if !registry.enforce_registered_topics {
+        return Ok(());
+    }
+    let registered_topic = registered_topic.ok_or(YourFunError::TopicNotRegistered)?;
+    require!(registered_topic.enabled, YourFunError::TopicDisabled);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(topic: [u8; 32])]
+pub struct RegisterTopic<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.is_curator(&curator.key()) @ YourFunError::Unauthorized,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = curator,
+        space = 8 + Topic::INIT_SPACE,
+        seeds = [Topic::SEED, topic.as_ref()],
+        bump,
+    )]
+    pub registered_topic: Box<Account<'info, Topic>>,
+
+    /// The authority or the registry's curator.
+    #[account(mut)]
+    pub curator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Registers a topic with the hash of its metadata, enabled from the start.
+pub fn handler_register_topic(
+    ctx: Context<RegisterTopic>,
+    topic: [u8; 32],
+    metadata_hash: [u8; 32],
+) -> Result<()> {
+    require!(topic.iter().any(|&b| b != 0), YourFunError::InvalidContentHash);
+
+    let registered_topic = &mut ctx.accounts.registered_topic;
+    registered_topic.topic = topic;
+    registered_topic.metadata_hash = metadata_hash;
+    registered_topic.enabled = true;
+    registered_topic.registered_by = ctx.accounts.curator.key();
+    registered_topic.bump = ctx.bumps.registered_topic;
+
+    msg!("Topic {} registered", registered_topic.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTopicEnabled<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        constraint = registry.is_curator(&curator.key()) @ YourFunError::Unauthorized,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [Topic::SEED, registered_topic.topic.as_ref()],
+        bump = registered_topic.bump,
+    )]
+    pub registered_topic: Box<Account<'info, Topic>>,
+
+    /// The authority or the registry's curator.
+    pub curator: Signer<'info>,
+}
+
+/// Enables or disables a registered topic. Sessions already using it keep
+/// it; while disabled, no session can start or switch to it under
+/// enforcement.
+pub fn handler_set_topic_enabled(ctx: Context<SetTopicEnabled>, enabled: bool) -> Result<()> {
+    let registered_topic = &mut ctx.accounts.registered_topic;
+    registered_topic.enabled = enabled;
+
+    msg!("Topic {} enabled: {}", registered_topic.key(), enabled);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(topic: [u8; 32])]
+pub struct UpdateTopic<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+        constraint = human_record.is_active() @ YourFunError::HumanRecordRevoked,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SessionAccount::SEED,
+            human_record.key().as_ref(),
+            &session.session_index.to_le_bytes(),
+        ],
+        bump = session.bump,
+        constraint = session.is_active() @ YourFunError::SessionInactive,
+        constraint = session.owner == owner.key() @ YourFunError::Unauthorized,
+    )]
+    pub session: Box<Account<'info, SessionAccount>>,
+
+    /// Required while registered topics are enforced.
+    #[account(
+        seeds = [Topic::SEED, topic.as_ref()],
+        bump = registered_topic.bump,
+    )]
+    pub registered_topic: Option<Box<Account<'info, Topic>>>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Switches the session's active topic. A topic the session has not covered
+/// yet takes the next of its `SessionAccount::MAX_TOPICS` slots; later
+/// interactions are attributed to the active topic. While the registry
+/// enforces registered topics, the topic must be registered and enabled.
+pub fn handler_update_topic(ctx: Context<UpdateTopic>, topic: [u8; 32]) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let session = &mut ctx.accounts.session;
+    let now = Clock::get()?.unix_timestamp;
+
+    registry.require_operational(now)?;
+    require!(session.expires_at > now, YourFunError::SessionExpired);
+    require!(
+        topic.iter().any(|&b| b != 0),
+        YourFunError::InvalidContentHash
+    );
+    require_registered_topic(registry, ctx.accounts.registered_topic.as_deref())?;
+
+    session.switch_topic(topic)?;
+
+    msg!(
+        "Session {} switched to topic slot {}",
+        session.session_index,
+        session.active_topic
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(topic: [u8; 32])]
+pub struct InitTopicProgress<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, owner.key().as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TopicProgress::INIT_SPACE,
+        seeds = [TopicProgress::SEED, human_record.key().as_ref(), topic.as_ref()],
+        bump,
+    )]
+    pub topic_progress: Box<Account<'info, TopicProgress>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the owner's progress account for one topic, so closing sessions
+/// can credit it.
+pub fn handler_init_topic_progress(ctx: Context<InitTopicProgress>, topic: [u8; 32]) -> Result<()> {
+    let topic_progress = &mut ctx.accounts.topic_progress;
+    topic_progress.human_record = ctx.accounts.human_record.key();
+    topic_progress.topic = topic;
+    topic_progress.cumulative_score = 0;
+    topic_progress.session_count = 0;
+    topic_progress.bump = ctx.bumps.topic_progress;
+
+    msg!("Topic progress created for human: {}", topic_progress.human_record);
+    Ok(())
+}
+
+/// Credits each TopicProgress account in `accounts` with the score `session`
+/// earned under its topic. Every account must belong to `human_record` and
+/// to a topic the session covered, and each topic is credited at most once.
+pub(crate) fn settle_topic_progress(
+    accounts: &[AccountInfo],
+    session: &SessionAccount,
+    human_record: &Pubkey,
+) -> Result<()> {
+    let mut settled = [false; SessionAccount::MAX_TOPICS];
+    for info in accounts {
+        require_keys_eq!(*info.owner, crate::ID, YourFunError::InvalidTopicProgress);
+        let mut data = info.try_borrow_mut_data()?;
+        let mut progress = TopicProgress::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(
+            progress.human_record,
+            *human_record,
+            YourFunError::InvalidTopicProgress
+        );
+        let slot = session
+            .topic_slot(&progress.topic)
+            .ok_or(YourFunError::InvalidTopicProgress)?;
+        require!(!settled[slot], YourFunError::InvalidTopicProgress);
+        settled[slot] = true;
+
+        progress.cumulative_score = progress
+            .cumulative_score
+            .checked_add(session.topics[slot].score)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        progress.session_count = progress
+            .session_count
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        progress.try_serialize(&mut &mut data[..])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::error::ErrorCode;
+    use solana_sdk::instruction::AccountMeta;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::FixtureBuilder;
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::InteractionType;
+
+    #[test]
+    fn session_score_is_split_across_topics_and_settled_into_their_progress() {
+        let wallet = Keypair::new();
+        let accounts = FixtureBuilder::new()
+            .with_verified_human(wallet.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&wallet.pubkey(), 1_000_000_000);
+        let human_record = client::human_record_address(&wallet.pubkey());
+        let session = client::session_address(&human_record, 0);
+        // The fixture session starts under topic a.
+        let (a, b, c) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let mut interactions = 0;
+        let mut record = |harness: &mut Harness, score: u8| {
+            let ix = client::build_record_interaction_ix(
+                &wallet.pubkey(),
+                0,
+                interactions,
+                [interactions as u8 + 1; 32],
+                InteractionType::Chat as u8,
+                score,
+                60,
+                [0u8; 32],
+                0,
+                false,
+                false,
+                NOW,
+            );
+            interactions += 1;
+            harness.process(ix, &[&wallet]).unwrap();
+        };
+        let switch = |harness: &mut Harness, topic: [u8; 32]| {
+            harness.process(client::build_update_topic_ix(&wallet.pubkey(), 0, topic, false), &[&wallet])
+        };
+
+        record(&mut harness, 40);
+        switch(&mut harness, b).unwrap();
+        record(&mut harness, 70);
+        switch(&mut harness, c).unwrap();
+        record(&mut harness, 90);
+        // Returning to a covered topic reuses its slot.
+        switch(&mut harness, a).unwrap();
+        record(&mut harness, 60);
+
+        let state: SessionAccount = harness.account(&session);
+        assert_eq!((state.topic_count, state.active_topic, state.current_topic), (3, 0, a));
+        let splits: Vec<_> = state.topics[..3].iter().map(|t| (t.topic, t.score)).collect();
+        assert_eq!(splits.iter().map(|(topic, _)| *topic).collect::<Vec<_>>(), [a, b, c]);
+        assert!(splits.iter().all(|(_, score)| *score > 0));
+        assert_eq!(splits.iter().map(|(_, score)| score).sum::<u64>(), state.session_score);
+
+        switch(&mut harness, [4u8; 32]).unwrap();
+        assert_eq!(
+            switch(&mut harness, [5u8; 32]),
+            Err(custom(YourFunError::TopicLimitReached))
+        );
+
+        for topic in [a, b, c, [6u8; 32]] {
+            harness
+                .process(client::build_init_topic_progress_ix(&wallet.pubkey(), topic), &[&wallet])
+                .unwrap();
+        }
+        let close = |progress: &[[u8; 32]]| {
+            let mut ix = client::build_close_session_ix(&wallet.pubkey(), &wallet.pubkey(), 0, 0, false, None, None);
+            ix.accounts.extend(
+                progress
+                    .iter()
+                    .map(|topic| AccountMeta::new(client::topic_progress_address(&human_record, topic), false)),
+            );
+            ix
+        };
+        // A topic the session never covered is not credited.
+        assert_eq!(
+            harness.process(close(&[a, [6u8; 32]]), &[&wallet]),
+            Err(custom(YourFunError::InvalidTopicProgress))
+        );
+        harness.process(close(&[a, b, c]), &[&wallet]).unwrap();
+
+        for (topic, score) in splits {
+            let progress: TopicProgress = harness.account(&client::topic_progress_address(&human_record, &topic));
+            assert_eq!((progress.cumulative_score, progress.session_count), (score, 1));
+        }
+    }
+
+    #[test]
+    fn registered_topics_are_required_only_while_enforced_and_enabled() {
+        let [authority, curator, stranger, owner] = [0; 4].map(|_| Keypair::new());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.curator = curator.pubkey())
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .build();
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &curator, &stranger, &owner] {
+            harness.fund(&signer.pubkey(), 1_000_000_000);
+        }
+        // a is unregistered; b is registered below.
+        let (a, b) = ([5u8; 32], [6u8; 32]);
+        let mut sessions = 1;
+        let mut create = |harness: &mut Harness, topic: [u8; 32], registered_topic: bool| {
+            let ix = client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                sessions,
+                0,
+                topic,
+                0,
+                0,
+                false,
+                None,
+                0,
+                registered_topic,
+                NOW,
+            );
+            let result = harness.process(ix, &[&owner]);
+            sessions += result.is_ok() as u64;
+            result
+        };
+        let switch = |harness: &mut Harness, topic: [u8; 32], registered_topic: bool| {
+            harness.process(client::build_update_topic_ix(&owner.pubkey(), 0, topic, registered_topic), &[&owner])
+        };
+        let enforce = |harness: &mut Harness, enforce: bool| {
+            let count = harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count;
+            let ix = client::build_admin_config_ix(
+                &authority.pubkey(),
+                count,
+                crate::instruction::SetEnforceRegisteredTopics { enforce },
+            );
+            harness.process(ix, &[&authority]).unwrap();
+        };
+
+        assert_eq!(
+            harness.process(client::build_register_topic_ix(&stranger.pubkey(), b, [9u8; 32]), &[&stranger]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+        assert_eq!(
+            harness.process(client::build_register_topic_ix(&curator.pubkey(), [0u8; 32], [9u8; 32]), &[&curator]),
+            Err(custom(YourFunError::InvalidContentHash))
+        );
+        harness.process(client::build_register_topic_ix(&curator.pubkey(), b, [9u8; 32]), &[&curator]).unwrap();
+        let registered: Topic = harness.account(&client::topic_address(&b));
+        assert_eq!(
+            (registered.topic, registered.metadata_hash, registered.enabled, registered.registered_by),
+            (b, [9u8; 32], true, curator.pubkey())
+        );
+
+        // Off: any topic goes, as before the registry existed.
+        create(&mut harness, a, false).unwrap();
+        switch(&mut harness, a, false).unwrap();
+
+        enforce(&mut harness, true);
+        assert_eq!(create(&mut harness, a, false), Err(custom(YourFunError::TopicNotRegistered)));
+        // Naming the unregistered topic's account does not help.
+        assert_eq!(create(&mut harness, a, true), Err(custom(ErrorCode::AccountNotInitialized)));
+        assert_eq!(switch(&mut harness, b, false), Err(custom(YourFunError::TopicNotRegistered)));
+        create(&mut harness, b, true).unwrap();
+        switch(&mut harness, b, true).unwrap();
+
+        assert_eq!(
+            harness.process(client::build_set_topic_enabled_ix(&stranger.pubkey(), b, false), &[&stranger]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+        harness.process(client::build_set_topic_enabled_ix(&authority.pubkey(), b, false), &[&authority]).unwrap();
+        assert_eq!(create(&mut harness, b, true), Err(custom(YourFunError::TopicDisabled)));
+        assert_eq!(switch(&mut harness, b, true), Err(custom(YourFunError::TopicDisabled)));
+        // Sessions already on the disabled topic keep it.
+        let session = client::session_address(&client::human_record_address(&owner.pubkey()), 0);
+        assert_eq!(harness.account::<SessionAccount>(&session).current_topic, b);
+
+        enforce(&mut harness, false);
+        create(&mut harness, a, false).unwrap();
+        create(&mut harness, b, false).unwrap();
+        assert_eq!(sessions, 5);
+    }
+}
@@ -1,6 +1,24 @@
 use anchor_lang::prelude::*;
-use crate::state::{PlatformRegistry, HumanRecord};
+use crate::state::{
+    PlatformRegistry, HumanRecord, RegionStats, VerificationApproval, VerificationLevel,
+    VerificationStatus, VerifierAccount, VerifierStats, DocumentAttestation, DocumentIssuer,
+    WaitlistEntry,
+};
+use crate::instructions::attestation::check_document_attestation;
+use crate::instructions::compromised::require_not_compromised;
+use crate::instructions::register_human::{forfeit_verifier_fee, release_verifier_fee};
+use crate::instructions::stats::{
+    load_region_stats, record_epoch_activity, store_region_stats, EpochActivity,
+};
 use crate::error::YourFunError;
+use crate::events::{
+    HumanWaitlisted, SupervisorChanged, VerificationContested, VerificationRejected,
+    VerificationStatusChanged, VerifierFeePaid,
+};
+use crate::crypto::compute_challenge_hash;
+use crate::constants::MAX_SCORE;
+use crate::scoring::{compute_composite_score, SUB_SCORE_COUNT};
+use crate::compute::{require_remaining_compute_units, VERIFY_HUMAN_WRITE_RESERVE_CU};
 
 #[derive(Accounts)]
 pub struct VerifyHuman<'info> {
@@ -9,18 +27,74 @@ pub struct VerifyHuman<'info> {
         seeds = [PlatformRegistry::SEED],
         bump = registry.bump,
     )]
-    pub registry: Account<'info, PlatformRegistry>,
+    pub registry: Box<Account<'info, PlatformRegistry>>,
 
     #[account(
         mut,
         seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
         bump = human_record.bump,
-        constraint = !human_record.is_active @ YourFunError::AlreadyVerified,
     )]
-    pub human_record: Account<'info, HumanRecord>,
+    pub human_record: Box<Account<'info, HumanRecord>>,
 
-    /// The verifier authority that confirms the proof-of-human.
+    /// Counters of the region being recorded; created, paid by the verifier,
+    /// on the region's first verification.
+    /// CHECK: Derived from the `region_code` argument by `load_region_stats`.
+    #[account(mut)]
+    pub region_stats: UncheckedAccount<'info>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    /// CompromisedFingerprint address for the record's fingerprint; normally empty.
+    /// CHECK: Address and contents are checked by `require_not_compromised`.
+    pub compromised: UncheckedAccount<'info>,
+
+    /// The human's document attestation; required for Full verification
+    /// only. Its wallet and expiry are checked in the handler.
+    pub document_attestation: Option<Box<Account<'info, DocumentAttestation>>>,
+
+    /// The approval of the attestation's issuer; required with the attestation.
+    pub document_issuer: Option<Box<Account<'info, DocumentIssuer>>>,
+
+    /// Required unless the verifier is the registry authority.
+    #[account(
+        seeds = [VerifierAccount::SEED, verifier.key().as_ref()],
+        bump = verifier_account.bump,
+    )]
+    pub verifier_account: Option<Box<Account<'info, VerifierAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = verifier,
+        space = 8 + VerifierStats::INIT_SPACE,
+        seeds = [VerifierStats::SEED, verifier.key().as_ref()],
+        bump,
+    )]
+    pub verifier_stats: Box<Account<'info, VerifierStats>>,
+
+    /// Next waitlist slot; required exactly when the approval is waitlisted.
+    #[account(
+        init,
+        payer = verifier,
+        space = 8 + WaitlistEntry::INIT_SPACE,
+        seeds = [WaitlistEntry::SEED, &registry.waitlist_count.to_le_bytes()],
+        bump,
+    )]
+    pub waitlist_entry: Option<Box<Account<'info, WaitlistEntry>>>,
+
+    /// The authority or an elected verifier confirming the proof-of-human;
+    /// pays for its stats account on first use.
+    #[account(
+        mut,
+        constraint = verifier.key() == registry.authority
+            || verifier_account.as_ref().is_some_and(|v| v.is_active)
+            @ YourFunError::UnauthorizedVerifier,
+    )]
     pub verifier: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
 /// Verifies a human identity by confirming the challenge-response proof
@@ -31,20 +105,402 @@ pub struct VerifyHuman<'info> {
 /// 2. An off-chain verifier analyzes behavioral data and generates a proof.
 /// 3. The verifier submits the proof on-chain via this instruction.
 /// 4. If the proof matches and the score passes the threshold, the human record is activated.
+///
+/// An active record can be verified again once it has left the Active status,
+/// which renews it, or once liveness decay has lowered its level, which
+/// restores it. Full verification additionally requires an unexpired
+/// document attestation for the human's wallet from an approved issuer.
+///
+/// The approval takes effect once the registry's contest window has passed
+/// without a `contest_verification`; until then the record is Pending.
+///
+/// A non-default `supervisor`, backed by consent collected off-chain, puts
+/// the human under that guardian's supervision; the default key leaves any
+/// existing supervisor in place.
+///
+/// While `max_verified_humans` is reached, or earlier approvals are still
+/// waitlisted, an approval that would newly count a human is stored in the
+/// next WaitlistEntry instead, which must then be passed. The record stays
+/// inactive, and so unable to open sessions, until `admit_from_waitlist`
+/// applies the approval. Renewals are never waitlisted.
+#[allow(clippy::too_many_arguments)]
 pub fn handler_verify_human(
     ctx: Context<VerifyHuman>,
     challenge_response: [u8; 32],
     behavioral_score: u8,
     verification_level: u8,
+    region_code: u16,
+    attribute_commitments: [[u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+    supervisor: Pubkey,
+) -> Result<()> {
+    let entry = VerifyEntry {
+        challenge_response,
+        behavioral_score,
+        verification_level,
+        region_code,
+        attribute_commitments,
+        supervisor,
+    };
+    verify_single(ctx, &entry, [behavioral_score, 0, 0])
+}
+
+/// Like `verify_human`, but takes the behavioral, social-graph and longevity
+/// sub-scores. Their composite under the registry's weights is what must
+/// reach `min_behavioral_score`.
+#[allow(clippy::too_many_arguments)]
+pub fn handler_verify_human_v2(
+    ctx: Context<VerifyHuman>,
+    challenge_response: [u8; 32],
+    sub_scores: [u8; SUB_SCORE_COUNT],
+    verification_level: u8,
+    region_code: u16,
+    attribute_commitments: [[u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+    supervisor: Pubkey,
+) -> Result<()> {
+    require!(
+        sub_scores.iter().all(|&score| score <= MAX_SCORE),
+        YourFunError::InvalidSubScore
+    );
+    let composite = compute_composite_score(&sub_scores, &ctx.accounts.registry.sub_score_weights_bps);
+
+    let entry = VerifyEntry {
+        challenge_response,
+        behavioral_score: composite,
+        verification_level,
+        region_code,
+        attribute_commitments,
+        supervisor,
+    };
+    verify_single(ctx, &entry, sub_scores)
+}
+
+/// Shared body of `verify_human` and `verify_human_v2`; `entry` carries the
+/// composite score in place of the behavioral one.
+fn verify_single(
+    ctx: Context<VerifyHuman>,
+    entry: &VerifyEntry,
+    sub_scores: [u8; SUB_SCORE_COUNT],
 ) -> Result<()> {
     let registry = &mut ctx.accounts.registry;
     let human_record = &mut ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+
+    registry.require_unpaused(PlatformRegistry::PAUSE_VERIFICATIONS, now)?;
+    require_not_compromised(&ctx.accounts.compromised, &human_record.fingerprint_hash)?;
+
+    let document_issuer = if entry.verification_level == VerificationLevel::Full as u8 {
+        check_document_attestation(
+            ctx.accounts.document_attestation.as_deref().map(|a| &**a),
+            ctx.accounts.document_issuer.as_deref().map(|i| &**i),
+            &human_record.wallet,
+            now,
+        )?
+    } else {
+        Pubkey::default()
+    };
+
+    let mut region_stats = load_region_stats(
+        &ctx.accounts.region_stats,
+        entry.region_code,
+        &ctx.accounts.verifier,
+        &ctx.accounts.system_program,
+    )?;
+    let outcome = apply_verification(
+        registry,
+        human_record,
+        &mut region_stats,
+        &ctx.accounts.verifier.key(),
+        entry,
+        document_issuer,
+        sub_scores,
+    )?;
+    store_region_stats(&ctx.accounts.region_stats, &region_stats)?;
+    let verifier_stats = &mut ctx.accounts.verifier_stats;
+    init_verifier_stats(verifier_stats, ctx.accounts.verifier.key(), ctx.bumps.verifier_stats);
+    verifier_stats.record_verification(entry.behavioral_score, now)?;
+    if let VerificationOutcome::Activated { newly_verified: true } = outcome {
+        record_epoch_activity(
+            &ctx.accounts.epoch_stats,
+            &ctx.accounts.verifier,
+            &ctx.accounts.system_program,
+            now,
+            EpochActivity { verified_humans: 1, ..Default::default() },
+        )?;
+    }
+    // Paid after the stats accounts are created: lamports moved by hand
+    // before a CPI that leaves out the record would unbalance the call.
+    if human_record.is_effective(now) {
+        pay_verifier_fee(human_record, &ctx.accounts.verifier, now)?;
+    }
+
+    match (outcome, ctx.accounts.waitlist_entry.as_deref_mut()) {
+        (VerificationOutcome::Activated { .. }, None) => {
+            msg!(
+                "Human verified at level {} with score {}",
+                entry.verification_level,
+                entry.behavioral_score
+            );
+        }
+        (VerificationOutcome::Waitlisted(approval), Some(waitlist_entry)) => {
+            waitlist_entry.position = registry.waitlist_count;
+            waitlist_entry.human_record = human_record.key();
+            waitlist_entry.wallet = human_record.wallet;
+            waitlist_entry.approval = *approval;
+            waitlist_entry.waitlisted_at = now;
+            waitlist_entry.admitted_at = 0;
+            waitlist_entry.skipped = false;
+            waitlist_entry.bump = ctx.bumps.waitlist_entry.ok_or(YourFunError::InvalidWaitlistEntry)?;
+            registry.waitlist_count = registry
+                .waitlist_count
+                .checked_add(1)
+                .ok_or(YourFunError::NumericalOverflow)?;
+            human_record.set_waitlisted(true);
+
+            emit!(HumanWaitlisted {
+                wallet: human_record.wallet,
+                position: waitlist_entry.position,
+                waitlisted_at: now,
+            });
+            msg!("Human waitlisted at position {}", waitlist_entry.position);
+        }
+        _ => return err!(YourFunError::InvalidWaitlistEntry),
+    }
+    registry.bump_state_version()?;
+
+    Ok(())
+}
+
+/// Maximum number of records accepted by `verify_humans_batch`.
+pub const MAX_VERIFY_BATCH: usize = 20;
+
+/// Remaining accounts supplied per entry of `verify_humans_batch`.
+pub const BATCH_ACCOUNTS_PER_ENTRY: usize = 3;
+
+/// One record's verification proof inside `verify_humans_batch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VerifyEntry {
+    pub challenge_response: [u8; 32],
+    pub behavioral_score: u8,
+    pub verification_level: u8,
+    pub region_code: u16,
+    pub attribute_commitments: [[u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+    /// Supervisor to place the human under (`Pubkey::default()` = unchanged).
+    pub supervisor: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct VerifyHumansBatch<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    /// Required unless the verifier is the registry authority.
+    #[account(
+        seeds = [VerifierAccount::SEED, verifier.key().as_ref()],
+        bump = verifier_account.bump,
+    )]
+    pub verifier_account: Option<Box<Account<'info, VerifierAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = verifier,
+        space = 8 + VerifierStats::INIT_SPACE,
+        seeds = [VerifierStats::SEED, verifier.key().as_ref()],
+        bump,
+    )]
+    pub verifier_stats: Box<Account<'info, VerifierStats>>,
+
+    /// The authority or an elected verifier confirming the proofs-of-human;
+    /// pays for its stats account on first use.
+    #[account(
+        mut,
+        constraint = verifier.key() == registry.authority
+            || verifier_account.as_ref().is_some_and(|v| v.is_active)
+            @ YourFunError::UnauthorizedVerifier,
+    )]
+    pub verifier: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    // Each entry is followed in the remaining accounts by its writable
+    // HumanRecord, the writable RegionStats address of its region (created
+    // on the region's first verification) and the CompromisedFingerprint
+    // address of the record's fingerprint, in entry order.
+}
+
+/// Verifies several humans in one transaction.
+///
+/// Each entry is validated exactly as in `verify_human` against the
+/// HumanRecord, RegionStats and CompromisedFingerprint accounts at the same
+/// position in `remaining_accounts`. Full verifications need a document
+/// attestation and must go through `verify_human` instead. The batch is
+/// all-or-nothing: any invalid entry fails the whole transaction, so no
+/// record is verified unless every one of them is. Approvals cannot be
+/// waitlisted here: a batch meeting the verification cap or a non-empty
+/// waitlist fails with `VerificationCapReached`.
+pub fn handler_verify_humans_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, VerifyHumansBatch<'info>>,
+    entries: Vec<VerifyEntry>,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let now = Clock::get()?.unix_timestamp;
+
+    registry.require_unpaused(PlatformRegistry::PAUSE_VERIFICATIONS, now)?;
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_VERIFY_BATCH,
+        YourFunError::InvalidBatchSize
+    );
+    require!(
+        ctx.remaining_accounts.len() == entries.len() * BATCH_ACCOUNTS_PER_ENTRY,
+        YourFunError::InvalidBatchSize
+    );
+
+    let verifier = ctx.accounts.verifier.key();
+    let verifier_stats = &mut ctx.accounts.verifier_stats;
+    init_verifier_stats(verifier_stats, verifier, ctx.bumps.verifier_stats);
+
+    let mut newly_verified_count = 0u64;
+    let mut payable = Vec::new();
+    let accounts = ctx.remaining_accounts.chunks_exact(BATCH_ACCOUNTS_PER_ENTRY);
+    for (entry, group) in entries.iter().zip(accounts) {
+        let (info, region_info, compromised_info) = (&group[0], &group[1], &group[2]);
+        require!(
+            info.is_writable && region_info.is_writable,
+            YourFunError::InvalidBatchAccount
+        );
+
+        let mut human_record = Account::<HumanRecord>::try_from(info)?;
+        let (expected, _) = Pubkey::find_program_address(
+            &[HumanRecord::SEED, human_record.wallet.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(info.key(), expected, YourFunError::InvalidBatchAccount);
+        require_not_compromised(compromised_info, &human_record.fingerprint_hash)?;
+
+        let mut region_stats = load_region_stats(
+            region_info,
+            entry.region_code,
+            &ctx.accounts.verifier,
+            &ctx.accounts.system_program,
+        )?;
 
-    require!(!registry.is_paused, YourFunError::RegistryPaused);
+        let outcome = apply_verification(
+            registry,
+            &mut human_record,
+            &mut region_stats,
+            &verifier,
+            entry,
+            Pubkey::default(),
+            [entry.behavioral_score, 0, 0],
+        )?;
+        match outcome {
+            VerificationOutcome::Activated { newly_verified } => {
+                newly_verified_count += newly_verified as u64;
+            }
+            VerificationOutcome::Waitlisted(_) => {
+                return err!(YourFunError::VerificationCapReached);
+            }
+        }
+        if human_record.is_effective(now) {
+            payable.push(info);
+        }
+        verifier_stats.record_verification(entry.behavioral_score, human_record.verified_at)?;
+        human_record.exit(ctx.program_id)?;
+        store_region_stats(region_info, &region_stats)?;
+    }
+
+    registry.bump_state_version()?;
+    record_epoch_activity(
+        &ctx.accounts.epoch_stats,
+        &ctx.accounts.verifier,
+        &ctx.accounts.system_program,
+        now,
+        EpochActivity { verified_humans: newly_verified_count, ..Default::default() },
+    )?;
+    // Verifier fees are paid once every stats account has been created, as
+    // in `verify_human`.
+    for info in payable {
+        let mut human_record = Account::<HumanRecord>::try_from(info)?;
+        pay_verifier_fee(&mut human_record, &ctx.accounts.verifier, now)?;
+        human_record.exit(ctx.program_id)?;
+    }
 
+    msg!("Verified {} humans in batch", entries.len());
+
+    Ok(())
+}
+
+/// What became of a valid verification proof.
+pub(crate) enum VerificationOutcome {
+    /// The record was activated; `newly_verified` unless it was a renewal.
+    Activated { newly_verified: bool },
+    /// The verification cap is reached or others are waiting, so the
+    /// approval must go to the waitlist; the record is untouched.
+    Waitlisted(Box<VerificationApproval>),
+}
+
+/// Validates one verification proof and activates the record, unless it
+/// would newly count towards a reached verification cap. Shared by the
+/// single and batch instructions. `document_issuer` is the issuer of the
+/// checked attestation, which a Full verification must have.
+/// `entry.behavioral_score` is the composite of `sub_scores`.
+fn apply_verification(
+    registry: &mut PlatformRegistry,
+    human_record: &mut HumanRecord,
+    region_stats: &mut RegionStats,
+    verifier: &Pubkey,
+    entry: &VerifyEntry,
+    document_issuer: Pubkey,
+    sub_scores: [u8; SUB_SCORE_COUNT],
+) -> Result<VerificationOutcome> {
+    let VerifyEntry {
+        challenge_response,
+        behavioral_score,
+        verification_level,
+        region_code,
+        attribute_commitments,
+        supervisor,
+    } = *entry;
+
+    let verification_level = VerificationLevel::try_from(verification_level)?;
     require!(
-        verification_level >= 1 && verification_level <= 3,
-        YourFunError::InvalidVerificationLevel
+        verification_level != VerificationLevel::Full || document_issuer != Pubkey::default(),
+        YourFunError::DocumentAttestationRequired
+    );
+
+    require!(!human_record.is_merged(), YourFunError::RecordMerged);
+    require!(!human_record.is_waitlisted(), YourFunError::AlreadyWaitlisted);
+
+    let clock = Clock::get()?;
+    if human_record.is_active() {
+        let status = human_record.verification_status(registry, clock.unix_timestamp);
+        // A level lowered by liveness decay is restored by re-verifying.
+        require!(
+            (status != VerificationStatus::Active || human_record.is_liveness_lapsed())
+                && status != VerificationStatus::Pending,
+            YourFunError::AlreadyVerified
+        );
+        // The record stays counted in its region, so a renewal cannot move it.
+        require!(
+            !human_record.is_region_counted() || human_record.region_code == region_code,
+            YourFunError::InvalidRegionCode
+        );
+    }
+
+    require!(
+        region_code <= RegionStats::MAX_REGION_CODE && region_stats.region_code == region_code,
+        YourFunError::InvalidRegionCode
+    );
+    require!(
+        registry.is_region_allowed(region_code),
+        YourFunError::RegionNotAllowed
     );
 
     require!(
@@ -62,46 +518,1132 @@ pub fn handler_verify_human(
         YourFunError::ChallengeMismatch
     );
 
-    let clock = Clock::get()?;
-    human_record.verified_by = ctx.accounts.verifier.key();
-    human_record.verified_at = clock.unix_timestamp;
-    human_record.verification_level = verification_level;
-    human_record.is_active = true;
-    human_record.last_active_at = clock.unix_timestamp;
-
-    registry.total_verified_humans = registry
-        .total_verified_humans
-        .checked_add(1)
+    require_remaining_compute_units(VERIFY_HUMAN_WRITE_RESERVE_CU)?;
+
+    let approval = VerificationApproval {
+        verifier: *verifier,
+        verification_level: verification_level as u8,
+        composite_score: behavioral_score,
+        sub_scores,
+        region_code,
+        attribute_commitments,
+        supervisor,
+        document_issuer,
+    };
+    // Renewals are already counted, so only newcomers wait for room.
+    let newly_verified = human_record.verified_at == 0 || human_record.revoked_at != 0;
+    if newly_verified && registry.must_waitlist() {
+        return Ok(VerificationOutcome::Waitlisted(Box::new(approval)));
+    }
+    activate(registry, human_record, region_stats, &approval, clock.unix_timestamp)?;
+    Ok(VerificationOutcome::Activated { newly_verified })
+}
+
+/// Applies a validated approval to the record, making it active and bumping
+/// the registry and region counters. Used at verification time and when a
+/// waitlisted approval is admitted.
+pub(crate) fn activate(
+    registry: &mut PlatformRegistry,
+    human_record: &mut HumanRecord,
+    region_stats: &mut RegionStats,
+    approval: &VerificationApproval,
+    now: i64,
+) -> Result<()> {
+    let first_verification = human_record.verified_at == 0;
+    let reinstated = human_record.revoked_at != 0;
+
+    human_record.verified_by = approval.verifier;
+    human_record.verified_at = now;
+    human_record.verification_level = approval.verification_level;
+    human_record.behavioral_score = approval.sub_scores[0];
+    human_record.sub_scores = approval.sub_scores;
+    human_record.composite_score = approval.composite_score;
+    human_record.set_active(true);
+    human_record.last_active_at = now;
+    human_record.revoked_at = 0;
+    human_record.region_code = approval.region_code;
+    human_record.document_issuer = approval.document_issuer;
+    human_record.attribute_commitments = approval.attribute_commitments;
+    if approval.supervisor != Pubkey::default() && approval.supervisor != human_record.supervisor {
+        human_record.supervisor = approval.supervisor;
+        emit!(SupervisorChanged {
+            wallet: human_record.wallet,
+            supervisor: approval.supervisor,
+            changed_by: approval.verifier,
+        });
+    }
+    human_record.set_requires_reverification(false);
+    human_record.set_waitlisted(false);
+    human_record.set_liveness_lapsed(false);
+    human_record.last_liveness_at = now;
+    human_record.liveness_decayed_at = 0;
+    human_record.effective_at = now
+        .checked_add(registry.contest_window)
         .ok_or(YourFunError::NumericalOverflow)?;
 
-    msg!(
-        "Human verified at level {} with behavioral score {}",
-        verification_level,
-        behavioral_score
+    // A record is counted once for as long as it stays verified: revocation
+    // removes it from the total and reinstatement puts it back, while any other
+    // re-verification only bumps the renewal counter.
+    count_verified(registry, human_record, region_stats, first_verification || reinstated)?;
+    if !first_verification {
+        registry.reverifications = registry
+            .reverifications
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+
+    refresh_verification_status(human_record, registry, now);
+
+    Ok(())
+}
+
+/// Adds a record entering the verified state to the registry total (when
+/// `newly_verified`), to the active humans and to its region's count, unless
+/// already counted there. Shared by verification and appeal-based
+/// reinstatement.
+pub(crate) fn count_verified(
+    registry: &mut PlatformRegistry,
+    human_record: &mut HumanRecord,
+    region_stats: &mut RegionStats,
+    newly_verified: bool,
+) -> Result<()> {
+    if newly_verified {
+        registry.total_verified_humans = registry
+            .total_verified_humans
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+    }
+    registry.count_active_human(human_record)?;
+    if !human_record.is_region_counted() {
+        region_stats.add_verified()?;
+        human_record.set_region_counted(true);
+    }
+    Ok(())
+}
+
+/// Pays the verifier the share of the registration fee held on the record,
+/// if any. Only an approval that has taken effect earns it: one that is
+/// waitlisted or inside its contest window leaves it held for
+/// `claim_verifier_fee`.
+fn pay_verifier_fee(human_record: &mut Account<HumanRecord>, verifier: &AccountInfo, now: i64) -> Result<()> {
+    let amount = release_verifier_fee(human_record, verifier)?;
+    if amount > 0 {
+        emit!(VerifierFeePaid {
+            wallet: human_record.wallet,
+            verifier: verifier.key(),
+            amount,
+            paid_at: now,
+        });
+    }
+    Ok(())
+}
+
+/// Fills in a VerifierStats account created by `init_if_needed`.
+fn init_verifier_stats(verifier_stats: &mut VerifierStats, verifier: Pubkey, bump: u8) {
+    if verifier_stats.bump == 0 {
+        verifier_stats.verifier = verifier;
+        verifier_stats.bump = bump;
+    }
+}
+
+#[derive(Accounts)]
+pub struct RejectVerification<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+        constraint = !human_record.is_active() @ YourFunError::AlreadyVerified,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    /// Required unless the verifier is the registry authority.
+    #[account(
+        seeds = [VerifierAccount::SEED, verifier.key().as_ref()],
+        bump = verifier_account.bump,
+    )]
+    pub verifier_account: Option<Box<Account<'info, VerifierAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = verifier,
+        space = 8 + VerifierStats::INIT_SPACE,
+        seeds = [VerifierStats::SEED, verifier.key().as_ref()],
+        bump,
+    )]
+    pub verifier_stats: Box<Account<'info, VerifierStats>>,
+
+    #[account(
+        mut,
+        constraint = verifier.key() == registry.authority
+            || verifier_account.as_ref().is_some_and(|v| v.is_active)
+            @ YourFunError::UnauthorizedVerifier,
+    )]
+    pub verifier: Signer<'info>,
+
+    /// Receives the verifier's share of the fee still held on the record;
+    /// required while one is held.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Records that a verifier turned down a pending verification. The human
+/// may still be verified later, but the verifier's share of the fee held on
+/// the record goes to the platform; otherwise the rejection only counts
+/// towards the verifier's statistics.
+pub fn handler_reject_verification(ctx: Context<RejectVerification>, reason_code: u8) -> Result<()> {
+    forfeit_verifier_fee(
+        &ctx.accounts.registry,
+        &mut ctx.accounts.human_record,
+        ctx.accounts.fee_receiver.as_deref(),
+    )?;
+    let now = Clock::get()?.unix_timestamp;
+    let verifier = ctx.accounts.verifier.key();
+    let verifier_stats = &mut ctx.accounts.verifier_stats;
+    init_verifier_stats(verifier_stats, verifier, ctx.bumps.verifier_stats);
+    verifier_stats.record_rejection(now)?;
+
+    emit!(VerificationRejected {
+        wallet: ctx.accounts.human_record.wallet,
+        verifier,
+        reason_code,
+        rejected_at: now,
+    });
+
+    msg!("Verification rejected with reason {}", reason_code);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VerifyAttribute<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+}
+
+/// Checks a revealed attribute against the commitment in slot `index` and
+/// returns the result as return data, so other programs can CPI it to test
+/// predicates without the attribute ever being published. Records that are
+/// not currently verified match nothing.
+pub fn handler_verify_attribute(
+    ctx: Context<VerifyAttribute>,
+    index: u8,
+    value_hash: [u8; 32],
+    salt: [u8; 32],
+) -> Result<bool> {
+    let human_record = &ctx.accounts.human_record;
+    let matches = human_record.attribute_matches(index, &value_hash, &salt)?;
+    Ok(matches && human_record.is_effective(Clock::get()?.unix_timestamp))
+}
+
+#[derive(Accounts)]
+pub struct HumanityScore<'info> {
+    #[account(
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+}
+
+/// Returns the human's composite humanity score as return data, or 0 when
+/// the record is not currently verified or still in its contest window.
+/// Meant for CPI.
+pub fn handler_humanity_score(ctx: Context<HumanityScore>) -> Result<u8> {
+    let human_record = &ctx.accounts.human_record;
+    if human_record.is_effective(Clock::get()?.unix_timestamp) {
+        Ok(human_record.composite_score)
+    } else {
+        Ok(0)
+    }
+}
+
+#[derive(Accounts)]
+pub struct ContestVerification<'info> {
+    #[account(
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    /// Required unless the signer is the registry authority.
+    #[account(
+        seeds = [VerifierAccount::SEED, verifier.key().as_ref()],
+        bump = verifier_account.bump,
+    )]
+    pub verifier_account: Option<Box<Account<'info, VerifierAccount>>>,
+
+    /// The authority or an elected verifier other than the one that approved.
+    #[account(
+        constraint = verifier.key() == registry.authority
+            || verifier_account.as_ref().is_some_and(|v| v.is_active)
+            @ YourFunError::UnauthorizedVerifier,
+        constraint = verifier.key() != human_record.verified_by @ YourFunError::Unauthorized,
+    )]
+    pub verifier: Signer<'info>,
+
+    /// Receives the verifier's share of the fee still held on the record;
+    /// required while one is held.
+    /// CHECK: Validated in the handler by `validate_fee_receiver`.
+    #[account(mut)]
+    pub fee_receiver: Option<UncheckedAccount<'info>>,
+}
+
+/// Voids a verification that is still inside its contest window. The record
+/// is left requiring re-verification, exactly like a record whose
+/// fingerprint was found compromised, so it only becomes usable again
+/// through a fresh `verify_human`. The verifier's share of the fee, still
+/// held on the record, goes to the platform.
+pub fn handler_contest_verification(ctx: Context<ContestVerification>, reason_code: u8) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        refresh_verification_status(human_record, registry, now) == VerificationStatus::Pending,
+        YourFunError::ContestWindowClosed
     );
 
+    human_record.set_requires_reverification(true);
+    refresh_verification_status(human_record, registry, now);
+    forfeit_verifier_fee(registry, human_record, ctx.accounts.fee_receiver.as_deref())?;
+
+    emit!(VerificationContested {
+        wallet: human_record.wallet,
+        verifier: human_record.verified_by,
+        contested_by: ctx.accounts.verifier.key(),
+        reason_code,
+        contested_at: now,
+    });
+
+    msg!("Verification contested for wallet: {}", human_record.wallet);
     Ok(())
 }
 
-/// Computes a deterministic challenge hash from the nonce and fingerprint.
-/// Uses XOR folding as a lightweight on-chain hash combination.
-fn compute_challenge_hash(nonce: &[u8; 32], fingerprint: &[u8; 32]) -> [u8; 32] {
-    let mut result = [0u8; 32];
-    for i in 0..32 {
-        result[i] = nonce[i] ^ fingerprint[i];
-        result[i] = result[i].wrapping_add(nonce[(i + 7) % 32]);
-        result[i] ^= fingerprint[(i + 13) % 32];
+#[derive(Accounts)]
+pub struct ClaimVerifierFee<'info> {
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+
+    /// The verifier whose approval is on the record.
+    /// CHECK: Only receives lamports; pinned to `human_record.verified_by`.
+    #[account(mut, address = human_record.verified_by @ YourFunError::Unauthorized)]
+    pub verifier: UncheckedAccount<'info>,
+}
+
+/// Pays the approving verifier the share of the fee held back while the
+/// approval was waitlisted or inside its contest window, once it has taken
+/// effect. Anyone may crank it.
+pub fn handler_claim_verifier_fee(ctx: Context<ClaimVerifierFee>) -> Result<()> {
+    let human_record = &mut ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+    require!(human_record.is_effective(now), YourFunError::VerificationPending);
+    pay_verifier_fee(human_record, &ctx.accounts.verifier, now)
+}
+
+#[derive(Accounts)]
+pub struct ExpireVerification<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [HumanRecord::SEED, human_record.wallet.as_ref()],
+        bump = human_record.bump,
+    )]
+    pub human_record: Box<Account<'info, HumanRecord>>,
+}
+
+/// Removes a human whose verification has lapsed from the registry's active
+/// humans. Lapsing happens with time alone, so this is a permissionless
+/// crank; the record is counted again when it is re-verified.
+pub fn handler_expire_verification(ctx: Context<ExpireVerification>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let human_record = &mut ctx.accounts.human_record;
+    let now = Clock::get()?.unix_timestamp;
+    let status = refresh_verification_status(human_record, registry, now);
+    require!(
+        status == VerificationStatus::Lapsed && human_record.is_active_counted(),
+        YourFunError::VerificationNotLapsed
+    );
+
+    registry.uncount_active_human(human_record);
+    registry.bump_state_version()?;
+
+    msg!("Verification of {} expired", human_record.wallet);
+    Ok(())
+}
+
+/// Computes the human's verification status and, when it differs from the
+/// one last stored on the record, stores it and emits
+/// `VerificationStatusChanged`. Transitions caused purely by time are thus
+/// detected lazily, by the first instruction that touches the record.
+pub(crate) fn refresh_verification_status(
+    human_record: &mut HumanRecord,
+    registry: &PlatformRegistry,
+    now: i64,
+) -> VerificationStatus {
+    let status = human_record.verification_status(registry, now);
+    if human_record.verification_status != status as u8 {
+        emit!(VerificationStatusChanged {
+            wallet: human_record.wallet,
+            previous_status: human_record.verification_status,
+            status: status as u8,
+            observed_at: now,
+        });
+        human_record.verification_status = status as u8;
     }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureAccount, FixtureBuilder};
+    use crate::harness::{custom, through_cpi_caller, Harness, NOW};
+    use crate::state::{Election, SessionAccount, VerificationLevel};
 
-    for round in 0..4 {
-        for i in 0..32 {
-            let prev = result[(i + 31) % 32];
-            let next = result[(i + 1) % 32];
-            result[i] = result[i]
-                .wrapping_add(prev.wrapping_mul(next))
-                .wrapping_add(round as u8);
+    fn registry(harness: &mut Harness) -> PlatformRegistry {
+        harness.account(&client::registry_address())
+    }
+
+    /// Verifies `wallet` at the Basic level in region 0, as the authority,
+    /// at `now`.
+    fn verify(harness: &mut Harness, authority: &Keypair, wallet: &Pubkey, now: i64) {
+        try_verify(harness, authority, wallet, VerificationLevel::Basic as u8, 0, now).unwrap();
+    }
+
+    fn try_verify(
+        harness: &mut Harness,
+        authority: &Keypair,
+        wallet: &Pubkey,
+        verification_level: u8,
+        region_code: u16,
+        now: i64,
+    ) -> std::result::Result<(), InstructionError> {
+        let ix = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            wallet,
+            None,
+            &hash(&wallet.to_bytes()).to_bytes(),
+            registered_challenge_response(wallet),
+            100,
+            verification_level,
+            region_code,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            now,
+        );
+        harness.process(ix, &[authority])
+    }
+
+    #[test]
+    fn revoking_and_reverifying_leaves_the_verified_total_unchanged() {
+        let (authority, wallet) = (Keypair::new(), Pubkey::new_unique());
+        let validity = 1_000;
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.verification_validity = validity)
+            .with_region_stats(0)
+            .with_registered_human(wallet)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+
+        verify(&mut harness, &authority, &wallet, NOW);
+        let verified = registry(&mut harness);
+        assert_eq!((verified.total_verified_humans, verified.reverifications), (1, 0));
+
+        let revoke =
+            client::build_revoke_human_ix(&authority.pubkey(), &wallet, 0, verified.admin_action_count, None);
+        harness.process(revoke, &[&authority]).unwrap();
+        assert_eq!(registry(&mut harness).total_verified_humans, 0);
+
+        verify(&mut harness, &authority, &wallet, NOW);
+        let reinstated = registry(&mut harness);
+        assert_eq!(reinstated.total_verified_humans, verified.total_verified_humans);
+        assert_eq!(reinstated.active_humans, verified.active_humans);
+        assert_eq!(reinstated.reverifications, 1);
+        let region: RegionStats = harness.account(&client::region_stats_address(0));
+        assert_eq!(region.verified_humans, 1);
+
+        // Renewing an expired verification does not count the record again.
+        harness.warp_to(NOW + validity);
+        verify(&mut harness, &authority, &wallet, NOW + validity);
+        let renewed = registry(&mut harness);
+        assert_eq!(renewed.total_verified_humans, verified.total_verified_humans);
+        assert_eq!(renewed.reverifications, 2);
+    }
+
+    fn batch_entry(wallet: &Pubkey) -> (Pubkey, [u8; 32], VerifyEntry) {
+        let entry = VerifyEntry {
+            challenge_response: registered_challenge_response(wallet),
+            behavioral_score: 100,
+            verification_level: VerificationLevel::Basic as u8,
+            region_code: 0,
+            attribute_commitments: [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            supervisor: Pubkey::default(),
+        };
+        (*wallet, hash(&wallet.to_bytes()).to_bytes(), entry)
+    }
+
+    #[test]
+    fn batches_verify_every_entry_or_none() {
+        let authority = Keypair::new();
+        let wallets: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut builder = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0);
+        for wallet in &wallets {
+            builder = builder.with_registered_human(*wallet);
         }
+        let mut harness = Harness::start(builder.build());
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        let is_active = |harness: &mut Harness, wallet: &Pubkey| {
+            harness
+                .account::<HumanRecord>(&client::human_record_address(wallet))
+                .is_active()
+        };
+
+        let mut tampered: Vec<_> = wallets[..2].iter().map(batch_entry).collect();
+        tampered[1].2.challenge_response[0] ^= 1;
+        let batch = client::build_verify_humans_batch_ix(&authority.pubkey(), false, tampered, NOW);
+        assert_eq!(
+            harness.process(batch, &[&authority]),
+            Err(custom(YourFunError::ChallengeMismatch))
+        );
+        assert!(!is_active(&mut harness, &wallets[0]));
+        assert_eq!(registry(&mut harness).total_verified_humans, 0);
+
+        let entries = wallets.iter().map(batch_entry).collect();
+        let batch = client::build_verify_humans_batch_ix(&authority.pubkey(), false, entries, NOW);
+        harness.process(batch, &[&authority]).unwrap();
+        for wallet in &wallets {
+            assert!(is_active(&mut harness, wallet));
+        }
+        assert_eq!(registry(&mut harness).total_verified_humans, wallets.len() as u64);
+        let region: RegionStats = harness.account(&client::region_stats_address(0));
+        assert_eq!(region.verified_humans, wallets.len() as u64);
+    }
+
+    #[test]
+    fn verification_levels_outside_the_enum_are_rejected() {
+        let (authority, wallet) = (Keypair::new(), Pubkey::new_unique());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_region_stats(0)
+            .with_registered_human(wallet)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+
+        for level in [0, VerificationLevel::Full as u8 + 1, u8::MAX] {
+            assert_eq!(
+                try_verify(&mut harness, &authority, &wallet, level, 0, NOW).unwrap_err(),
+                custom(YourFunError::InvalidVerificationLevel),
+                "level {level}"
+            );
+        }
+
+        try_verify(&mut harness, &authority, &wallet, VerificationLevel::Enhanced as u8, 0, NOW).unwrap();
+        let record: HumanRecord = harness.account(&client::human_record_address(&wallet));
+        assert_eq!(record.verification_level, 2);
+        assert_eq!(record.level().unwrap(), Some(VerificationLevel::Enhanced));
+    }
+
+    #[test]
+    fn restricted_regions_gate_verification_and_keep_symmetric_counts() {
+        let authority = Keypair::new();
+        let [allowed, blocked] = [0; 2].map(|_| Pubkey::new_unique());
+        let (open_region, closed_region) = (840, 250);
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.restrict_regions = true;
+                registry.set_region_allowed(open_region, true);
+            })
+            .with_registered_human(allowed)
+            .with_registered_human(blocked)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        let basic = VerificationLevel::Basic as u8;
+        let count = |harness: &mut Harness, region_code| {
+            harness
+                .account::<RegionStats>(&client::region_stats_address(region_code))
+                .verified_humans
+        };
+
+        try_verify(&mut harness, &authority, &allowed, basic, open_region, NOW).unwrap();
+        let record: HumanRecord = harness.account(&client::human_record_address(&allowed));
+        assert_eq!(record.region_code, open_region);
+        assert_eq!(count(&mut harness, open_region), 1);
+        assert_eq!(
+            try_verify(&mut harness, &authority, &blocked, basic, closed_region, NOW),
+            Err(custom(YourFunError::RegionNotAllowed))
+        );
+
+        let revoke = client::build_revoke_human_ix(
+            &authority.pubkey(),
+            &allowed,
+            open_region,
+            registry(&mut harness).admin_action_count,
+            None,
+        );
+        harness.process(revoke, &[&authority]).unwrap();
+        assert_eq!(count(&mut harness, open_region), 0);
+        try_verify(&mut harness, &authority, &allowed, basic, open_region, NOW).unwrap();
+        assert_eq!(count(&mut harness, open_region), 1);
+
+        // Lifting the restriction allows every region again.
+        let lift = client::build_admin_config_ix(
+            &authority.pubkey(),
+            registry(&mut harness).admin_action_count,
+            crate::instruction::SetRegionRestriction { restrict_regions: false },
+        );
+        harness.process(lift, &[&authority]).unwrap();
+        try_verify(&mut harness, &authority, &blocked, basic, closed_region, NOW).unwrap();
+        assert_eq!(count(&mut harness, closed_region), 1);
+        assert_eq!(registry(&mut harness).total_verified_humans, 2);
+    }
+
+    #[test]
+    fn status_transitions_are_detected_lazily_and_announced_once() {
+        let owner = Keypair::new();
+        let (validity, grace) = (1_000, 500);
+        let accounts = FixtureBuilder::new()
+            .with_registry(|registry| {
+                registry.verification_validity = validity;
+                registry.verification_grace_period = grace;
+            })
+            .with_verified_human(owner.pubkey())
+            .with_open_session()
+            .with_session(|session| session.expires_at = NOW + 10_000)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&owner.pubkey(), 10_000_000_000);
+        let heartbeat_at = |harness: &mut Harness, now| {
+            harness.warp_to(now);
+            harness
+                .process_with_events::<VerificationStatusChanged>(
+                    client::build_heartbeat_session_ix(&owner.pubkey(), 0),
+                    &[&owner],
+                )
+                .unwrap()
+        };
+        let create_session = |session_index, now| {
+            client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                session_index,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                now,
+            )
+        };
+
+        let interval = SessionAccount::HEARTBEAT_MIN_INTERVAL;
+        assert!(heartbeat_at(&mut harness, NOW + validity - interval).is_empty());
+        let events = heartbeat_at(&mut harness, NOW + validity);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            (events[0].wallet, events[0].previous_status, events[0].status, events[0].observed_at),
+            (
+                owner.pubkey(),
+                VerificationStatus::Active as u8,
+                VerificationStatus::GracePeriod as u8,
+                NOW + validity
+            )
+        );
+        assert!(heartbeat_at(&mut harness, NOW + validity + interval).is_empty());
+        // Sessions can still start during the grace period.
+        harness
+            .process(create_session(1, NOW + validity + interval), &[&owner])
+            .unwrap();
+
+        let events = heartbeat_at(&mut harness, NOW + validity + grace);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].status, VerificationStatus::Lapsed as u8);
+        assert!(heartbeat_at(&mut harness, NOW + validity + grace + interval).is_empty());
+        assert_eq!(
+            harness.process(create_session(2, NOW + validity + grace + interval), &[&owner]),
+            Err(custom(YourFunError::VerificationLapsed))
+        );
+    }
+
+    /// The VerifierAccount of `verifier`, seated in the first election.
+    fn elected_verifier(verifier: &Pubkey) -> FixtureAccount {
+        let (address, bump) =
+            Pubkey::find_program_address(&[VerifierAccount::SEED, verifier.as_ref()], &crate::ID);
+        FixtureAccount::new(
+            address,
+            &VerifierAccount {
+                verifier: *verifier,
+                is_active: true,
+                elected_epoch: 1,
+                stake: 0,
+                bump,
+            },
+        )
     }
 
-    result
+    #[test]
+    fn verifier_stats_aggregate_verifications_and_rejections_and_outlive_the_seat() {
+        let verifier = Keypair::new();
+        let wallets: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let (election, election_bump) = Pubkey::find_program_address(&[Election::SEED], &crate::ID);
+        let mut builder = FixtureBuilder::new().with_region_stats(0);
+        for wallet in &wallets {
+            builder = builder.with_registered_human(*wallet);
+        }
+        let mut accounts = builder.build();
+        accounts.push(elected_verifier(&verifier.pubkey()));
+        // Two elections on, the verifier's term has lapsed.
+        accounts.push(FixtureAccount::new(
+            election,
+            &Election {
+                governance_mint: Pubkey::new_unique(),
+                vault: client::election_vault_address(),
+                epoch_duration: 1_000,
+                seats: 1,
+                current_epoch: 3,
+                epoch_started_at: NOW,
+                candidate_count: 0,
+                bump: election_bump,
+                vault_bump: 0,
+            },
+        ));
+        let mut harness = Harness::start(accounts);
+        harness.fund(&verifier.pubkey(), 10_000_000_000);
+        let verify_with_score = |wallet: &Pubkey, behavioral_score| {
+            client::build_verify_human_ix(
+                &verifier.pubkey(),
+                true,
+                wallet,
+                None,
+                &hash(&wallet.to_bytes()).to_bytes(),
+                registered_challenge_response(wallet),
+                behavioral_score,
+                VerificationLevel::Basic as u8,
+                0,
+                [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+                Pubkey::default(),
+                None,
+                NOW,
+            )
+        };
+        let reject =
+            |wallet| client::build_reject_verification_ix(&verifier.pubkey(), true, wallet, None, 4);
+        let stats = |harness: &mut Harness| -> VerifierStats {
+            harness.account(&client::verifier_stats_address(&verifier.pubkey()))
+        };
+
+        harness.process(verify_with_score(&wallets[0], 80), &[&verifier]).unwrap();
+        harness.process(reject(&wallets[2]), &[&verifier]).unwrap();
+        harness.warp_to(NOW + 300);
+        harness.process(verify_with_score(&wallets[1], 95), &[&verifier]).unwrap();
+        harness.warp_to(NOW + 600);
+        harness.process(reject(&wallets[3]), &[&verifier]).unwrap();
+        // A verified human can no longer be rejected, and the attempt counts
+        // for nothing.
+        assert_eq!(
+            harness.process(reject(&wallets[0]), &[&verifier]),
+            Err(custom(YourFunError::AlreadyVerified))
+        );
+
+        let active = stats(&mut harness);
+        assert_eq!(active.verifier, verifier.pubkey());
+        assert_eq!((active.verifications, active.rejections), (2, 2));
+        assert_eq!(active.behavioral_score_sum, 175);
+        assert_eq!(active.last_active_at, NOW + 600);
+
+        harness
+            .process(client::build_retire_verifier_ix(&verifier.pubkey()), &[])
+            .unwrap();
+        harness.warp_to(NOW + 900);
+        assert_eq!(
+            harness.process(reject(&wallets[3]), &[&verifier]),
+            Err(custom(YourFunError::UnauthorizedVerifier))
+        );
+        let retired = stats(&mut harness);
+        assert_eq!(
+            (retired.verifications, retired.rejections, retired.behavioral_score_sum, retired.last_active_at),
+            (active.verifications, active.rejections, active.behavioral_score_sum, active.last_active_at)
+        );
+    }
+
+    #[test]
+    fn attribute_reveals_match_only_their_commitment_directly_and_through_cpi() {
+        let (authority, wallet) = (Keypair::new(), Pubkey::new_unique());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.verification_validity = 1_000)
+            .with_region_stats(0)
+            .with_registered_human(wallet)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        let (over_18, salt) = (hash(b"over_18").to_bytes(), [4u8; 32]);
+        let verify_with = |attribute_commitments, now| {
+            client::build_verify_human_ix(
+                &authority.pubkey(),
+                false,
+                &wallet,
+                None,
+                &hash(&wallet.to_bytes()).to_bytes(),
+                registered_challenge_response(&wallet),
+                100,
+                VerificationLevel::Basic as u8,
+                0,
+                attribute_commitments,
+                Pubkey::default(),
+                None,
+                now,
+            )
+        };
+        let reveal = |harness: &mut Harness, index, salt| {
+            harness.simulate(client::build_verify_attribute_ix(&wallet, index, over_18, salt), &[])
+        };
+
+        let mut commitments = [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES];
+        commitments[0] = HumanRecord::attribute_commitment(&over_18, &salt);
+        // Nothing matches before the record is verified.
+        assert_eq!(reveal(&mut harness, 0, salt), Ok(vec![0]));
+        harness.process(verify_with(commitments, NOW), &[&authority]).unwrap();
+
+        assert_eq!(reveal(&mut harness, 0, salt), Ok(vec![1]));
+        assert_eq!(reveal(&mut harness, 0, [5u8; 32]), Ok(vec![0]));
+        assert_eq!(reveal(&mut harness, 1, salt), Ok(vec![0]));
+        assert_eq!(
+            reveal(&mut harness, HumanRecord::MAX_ATTRIBUTES as u8, salt),
+            Err(custom(YourFunError::InvalidAttributeIndex))
+        );
+
+        let through_cpi = |harness: &mut Harness, salt| {
+            let ix = client::build_verify_attribute_ix(&wallet, 0, over_18, salt);
+            harness.simulate(through_cpi_caller(ix), &[])
+        };
+        assert_eq!(through_cpi(&mut harness, salt), Ok(vec![1]));
+        assert_eq!(through_cpi(&mut harness, [5u8; 32]), Ok(vec![0]));
+
+        // Only a re-verification, here of the expired record, replaces the
+        // commitments.
+        harness.warp_to(NOW + 1_000);
+        let mut replaced = [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES];
+        replaced[0] = HumanRecord::attribute_commitment(&over_18, &[6u8; 32]);
+        harness.process(verify_with(replaced, NOW + 1_000), &[&authority]).unwrap();
+        assert_eq!(reveal(&mut harness, 0, salt), Ok(vec![0]));
+        assert_eq!(reveal(&mut harness, 0, [6u8; 32]), Ok(vec![1]));
+    }
+
+    #[test]
+    fn composite_scores_gate_v2_verification_under_configured_weights() {
+        let authority = Keypair::new();
+        let [low, high] = [0; 2].map(|_| Pubkey::new_unique());
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.min_behavioral_score = 60)
+            .with_region_stats(0)
+            .with_registered_human(low)
+            .with_registered_human(high)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        let set_weights = |harness: &mut Harness, weights_bps| {
+            let ix = client::build_admin_config_ix(
+                &authority.pubkey(),
+                registry(harness).admin_action_count,
+                crate::instruction::SetSubScoreWeights { weights_bps },
+            );
+            harness.process(ix, &[&authority])
+        };
+        let verify_v2 = |harness: &mut Harness, wallet: &Pubkey, sub_scores| {
+            let ix = client::build_verify_human_v2_ix(
+                &authority.pubkey(),
+                false,
+                wallet,
+                None,
+                &hash(&wallet.to_bytes()).to_bytes(),
+                registered_challenge_response(wallet),
+                sub_scores,
+                VerificationLevel::Basic as u8,
+                0,
+                [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+                Pubkey::default(),
+                None,
+                NOW,
+            );
+            harness.process(ix, &[&authority])
+        };
+
+        for weights_bps in [[5_000, 3_000, 1_999], [5_000, 3_000, 2_001], [0, 0, 0], [u16::MAX, u16::MAX, 2]] {
+            assert_eq!(
+                set_weights(&mut harness, weights_bps),
+                Err(custom(YourFunError::InvalidSubScoreWeights)),
+                "{weights_bps:?}"
+            );
+        }
+        set_weights(&mut harness, [5_000, 3_000, 2_000]).unwrap();
+        assert_eq!(registry(&mut harness).sub_score_weights_bps, [5_000, 3_000, 2_000]);
+
+        // 45 + 12 + 2.8 = 59.8, rounded down below the threshold of 60.
+        assert_eq!(
+            verify_v2(&mut harness, &low, [90, 40, 14]),
+            Err(custom(YourFunError::BehavioralScoreTooLow))
+        );
+        assert_eq!(
+            verify_v2(&mut harness, &low, [101, 100, 100]),
+            Err(custom(YourFunError::InvalidSubScore))
+        );
+        verify_v2(&mut harness, &low, [90, 40, 15]).unwrap();
+        verify_v2(&mut harness, &high, [90, 60, 30]).unwrap();
+
+        for (wallet, sub_scores, composite) in [(low, [90, 40, 15], 60), (high, [90, 60, 30], 69)] {
+            let record: HumanRecord = harness.account(&client::human_record_address(&wallet));
+            assert_eq!((record.sub_scores, record.composite_score), (sub_scores, composite));
+            assert_eq!(record.behavioral_score, sub_scores[0]);
+            let score = harness.simulate(client::build_humanity_score_ix(&wallet), &[]);
+            assert_eq!(score, Ok(vec![composite]));
+        }
+    }
+
+    #[test]
+    fn verifications_take_effect_only_after_an_uncontested_window() {
+        let (authority, contester) = (Keypair::new(), Keypair::new());
+        let [uncontested, contested, instant] = [0; 3].map(|_| Keypair::new());
+        let window = 100;
+        let mut builder = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.contest_window = window)
+            .with_region_stats(0);
+        for owner in [&uncontested, &contested, &instant] {
+            builder = builder.with_registered_human(owner.pubkey());
+        }
+        let mut accounts = builder.build();
+        accounts.push(elected_verifier(&contester.pubkey()));
+        let mut harness = Harness::start(accounts);
+        for signer in [&authority, &contester, &uncontested, &contested, &instant] {
+            harness.fund(&signer.pubkey(), 10_000_000_000);
+        }
+        let create_session = |harness: &mut Harness, owner: &Keypair, now| {
+            let ix = client::build_create_session_ix(
+                &owner.pubkey(),
+                None,
+                0,
+                0,
+                [2u8; 32],
+                0,
+                0,
+                false,
+                None,
+                0,
+                false,
+                now,
+            );
+            harness.process(ix, &[owner])
+        };
+        let contest = |verifier: &Keypair, elected, owner: &Keypair| {
+            client::build_contest_verification_ix(&verifier.pubkey(), elected, &owner.pubkey(), None, 2)
+        };
+        let score = |harness: &mut Harness, owner: &Keypair| {
+            harness.simulate(client::build_humanity_score_ix(&owner.pubkey()), &[]).unwrap()
+        };
+
+        verify(&mut harness, &authority, &uncontested.pubkey(), NOW);
+        verify(&mut harness, &authority, &contested.pubkey(), NOW);
+        let record: HumanRecord = harness.account(&client::human_record_address(&uncontested.pubkey()));
+        assert_eq!(record.effective_at, NOW + window);
+        assert_eq!(record.verification_status, VerificationStatus::Pending as u8);
+        assert_eq!(registry(&mut harness).total_verified_humans, 2);
+
+        harness.warp_to(NOW + window - 1);
+        assert_eq!(
+            create_session(&mut harness, &uncontested, NOW + window - 1),
+            Err(custom(YourFunError::VerificationPending))
+        );
+        assert_eq!(score(&mut harness, &uncontested), vec![0]);
+        // The approving verifier cannot contest its own approval.
+        assert_eq!(
+            harness.process(contest(&authority, false, &contested), &[&authority]),
+            Err(custom(YourFunError::Unauthorized))
+        );
+        harness.process(contest(&contester, true, &contested), &[&contester]).unwrap();
+        let record: HumanRecord = harness.account(&client::human_record_address(&contested.pubkey()));
+        assert!(record.requires_reverification());
+        assert_eq!(record.verification_status, VerificationStatus::Lapsed as u8);
+        // Like a compromised fingerprint, a contest leaves the record counted
+        // until it is revoked or closed.
+        assert_eq!(registry(&mut harness).total_verified_humans, 2);
+
+        harness.warp_to(NOW + window);
+        create_session(&mut harness, &uncontested, NOW + window).unwrap();
+        assert_eq!(score(&mut harness, &uncontested), vec![100]);
+        assert_eq!(
+            harness.process(contest(&contester, true, &uncontested), &[&contester]),
+            Err(custom(YourFunError::ContestWindowClosed))
+        );
+        assert_eq!(
+            create_session(&mut harness, &contested, NOW + window),
+            Err(custom(YourFunError::VerificationLapsed))
+        );
+        assert_eq!(score(&mut harness, &contested), vec![0]);
+
+        // Without a window, verification takes effect at once.
+        let no_window = client::build_admin_config_ix(
+            &authority.pubkey(),
+            registry(&mut harness).admin_action_count,
+            crate::instruction::SetContestWindow { contest_window: 0 },
+        );
+        harness.process(no_window, &[&authority]).unwrap();
+        verify(&mut harness, &authority, &instant.pubkey(), NOW + window);
+        create_session(&mut harness, &instant, NOW + window).unwrap();
+    }
+
+    /// Starts from a registry whose fees, split by `share_bps`, go to
+    /// `treasury`.
+    fn start_with_fee_share(authority: &Keypair, treasury: &Pubkey, fee: u64, share_bps: u16) -> Harness {
+        let treasury = *treasury;
+        let accounts = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| {
+                registry.treasury = treasury;
+                registry.verification_fee_lamports = fee;
+                registry.verifier_fee_share_bps = share_bps;
+            })
+            .with_region_stats(0)
+            .build();
+        let mut harness = Harness::start(accounts);
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        harness.fund(&treasury, 1_000_000_000);
+        harness
+    }
+
+    /// Registers a new wallet so that `verify` accepts it, paying the fee to
+    /// `treasury`.
+    fn register_paying(harness: &mut Harness, treasury: &Pubkey) -> Pubkey {
+        let user = harness.wallet();
+        let ix = client::build_register_human_ix(
+            &user.pubkey(),
+            treasury,
+            false,
+            false,
+            None,
+            None,
+            client::human_record_address(&user.pubkey()).to_bytes(),
+            user.pubkey().to_bytes().to_vec(),
+        );
+        harness.process(ix, &[&user]).unwrap();
+        user.pubkey()
+    }
+
+    /// Verifies `wallet` as the authority at NOW, returning the verifier fee
+    /// payouts it emitted.
+    fn verify_paying_verifier(harness: &mut Harness, authority: &Keypair, wallet: &Pubkey) -> Vec<VerifierFeePaid> {
+        let ix = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            wallet,
+            None,
+            &hash(&wallet.to_bytes()).to_bytes(),
+            registered_challenge_response(wallet),
+            100,
+            VerificationLevel::Basic as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            None,
+            NOW,
+        );
+        harness.process_with_events::<VerifierFeePaid>(ix, &[authority]).unwrap()
+    }
+
+    #[test]
+    fn verifier_share_is_held_until_approval_and_rounds_down() {
+        let (authority, treasury) = (Keypair::new(), Pubkey::new_unique());
+        let fee = 1_000_001;
+        let mut harness = start_with_fee_share(&authority, &treasury, fee, 3_333);
+
+        let before = harness.balance(&treasury);
+        let wallet = register_paying(&mut harness, &treasury);
+        let human_record = client::human_record_address(&wallet);
+        let record: HumanRecord = harness.account(&human_record);
+        assert_eq!(record.verifier_fee_escrow, 333_300);
+        assert_eq!(harness.balance(&treasury), before + fee - 333_300);
+
+        let events = verify_paying_verifier(&mut harness, &authority, &wallet);
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].wallet, events[0].verifier), (wallet, authority.pubkey()));
+        assert_eq!(events[0].amount, 333_300);
+        let record: HumanRecord = harness.account(&human_record);
+        assert_eq!(record.verifier_fee_escrow, 0);
+
+        // A rejected registration hands the share to the platform instead.
+        let rejected = register_paying(&mut harness, &treasury);
+        let before = harness.balance(&treasury);
+        let reject = client::build_reject_verification_ix(&authority.pubkey(), false, &rejected, None, 1);
+        assert_eq!(
+            harness.process(reject, &[&authority]),
+            Err(custom(YourFunError::FeeReceiverRequired))
+        );
+        let reject = client::build_reject_verification_ix(&authority.pubkey(), false, &rejected, Some(&treasury), 1);
+        harness.process(reject, &[&authority]).unwrap();
+        assert_eq!(harness.balance(&treasury), before + 333_300);
+        let record: HumanRecord = harness.account(&client::human_record_address(&rejected));
+        assert_eq!(record.verifier_fee_escrow, 0);
+    }
+
+    #[test]
+    fn verifier_share_bounds_and_a_zero_fee() {
+        for (fee, share_bps, share) in [(1_000_000, 0, 0), (1_000_000, crate::constants::MAX_BPS, 1_000_000), (0, 5_000, 0)] {
+            let (authority, treasury) = (Keypair::new(), Pubkey::new_unique());
+            let mut harness = start_with_fee_share(&authority, &treasury, fee, share_bps);
+
+            let before = harness.balance(&treasury);
+            let wallet = register_paying(&mut harness, &treasury);
+            let record: HumanRecord = harness.account(&client::human_record_address(&wallet));
+            assert_eq!(record.verifier_fee_escrow, share);
+            assert_eq!(harness.balance(&treasury), before + fee - share);
+
+            let events = verify_paying_verifier(&mut harness, &authority, &wallet);
+            let paid: Vec<u64> = events.iter().map(|event| event.amount).collect();
+            assert_eq!(paid, if share > 0 { vec![share] } else { Vec::new() });
+        }
+    }
 }
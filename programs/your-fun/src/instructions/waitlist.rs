@@ -0,0 +1,346 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformRegistry, AdminAction, AdminActionKind, HumanRecord, RegionStats, WaitlistEntry};
+use crate::instructions::admin::record_admin_action;
+use crate::instructions::verify_human::activate;
+use crate::instructions::stats::{record_epoch_activity, EpochActivity};
+use crate::error::YourFunError;
+use crate::events::WaitlistEntryProcessed;
+
+/// Maximum number of entries processed by one `admit_from_waitlist`.
+pub const MAX_ADMIT_BATCH: u8 = 10;
+
+/// Remaining accounts supplied per entry of `admit_from_waitlist`.
+pub const ADMIT_ACCOUNTS_PER_ENTRY: usize = 3;
+
+#[derive(Accounts)]
+pub struct AdmitFromWaitlist<'info> {
+    #[account(
+        mut,
+        seeds = [PlatformRegistry::SEED],
+        bump = registry.bump,
+        has_one = authority @ YourFunError::AuthorityMismatch,
+    )]
+    pub registry: Box<Account<'info, PlatformRegistry>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAction::INIT_SPACE,
+        seeds = [
+            AdminAction::SEED,
+            registry.key().as_ref(),
+            &registry.admin_action_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_action: Box<Account<'info, AdminAction>>,
+
+    /// The current day's EpochStats; created by the day's first activity.
+    /// CHECK: Derived from the clock by record_epoch_activity.
+    #[account(mut)]
+    pub epoch_stats: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    // Each entry, from `registry.waitlist_next` on, is followed in the
+    // remaining accounts by its writable WaitlistEntry, the writable
+    // HumanRecord address it names and the writable RegionStats of its
+    // approval's region.
+}
+
+/// Processes the next `count` waitlist entries in order, applying each
+/// approval with every effect `verify_human` would have had, as of now.
+/// The verifier's share of the fee stays held on the record until
+/// `claim_verifier_fee`. Authority only.
+///
+/// An entry whose record was closed, merged or verified again since is
+/// passed over and marked skipped; its region account is then not used.
+/// Admitting past `max_verified_humans` fails with `VerificationCapReached`,
+/// so the cap must be raised first. Raising the cap admits no one by itself.
+pub fn handler_admit_from_waitlist<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AdmitFromWaitlist<'info>>,
+    count: u8,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(
+        count > 0 && count <= MAX_ADMIT_BATCH,
+        YourFunError::InvalidBatchSize
+    );
+    require!(
+        registry.waitlist_next + count as u64 <= registry.waitlist_count,
+        YourFunError::InvalidBatchSize
+    );
+    require!(
+        ctx.remaining_accounts.len() == count as usize * ADMIT_ACCOUNTS_PER_ENTRY,
+        YourFunError::InvalidBatchSize
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let first_position = registry.waitlist_next;
+    let mut admitted = 0u8;
+    for group in ctx.remaining_accounts.chunks_exact(ADMIT_ACCOUNTS_PER_ENTRY) {
+        let (entry_info, record_info, region_info) = (&group[0], &group[1], &group[2]);
+        require!(
+            entry_info.is_writable && record_info.is_writable,
+            YourFunError::InvalidBatchAccount
+        );
+
+        let mut entry = Account::<WaitlistEntry>::try_from(entry_info)?;
+        let expected = Pubkey::create_program_address(
+            &[WaitlistEntry::SEED, &entry.position.to_le_bytes(), &[entry.bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| error!(YourFunError::InvalidBatchAccount))?;
+        require_keys_eq!(entry_info.key(), expected, YourFunError::InvalidBatchAccount);
+        require!(
+            entry.position == registry.waitlist_next,
+            YourFunError::InvalidBatchAccount
+        );
+        require_keys_eq!(record_info.key(), entry.human_record, YourFunError::InvalidBatchAccount);
+
+        let mut human_record = if record_info.owner == ctx.program_id && !record_info.data_is_empty() {
+            Some(Account::<HumanRecord>::try_from(record_info)?)
+        } else {
+            None
+        };
+        let waiting = human_record
+            .as_ref()
+            .is_some_and(|record| record.is_waitlisted() && !record.is_merged());
+
+        match human_record.as_mut() {
+            Some(record) if waiting => {
+                require!(!registry.verification_cap_reached(), YourFunError::VerificationCapReached);
+                require!(region_info.is_writable, YourFunError::InvalidBatchAccount);
+                let mut region_stats = Account::<RegionStats>::try_from(region_info)?;
+                let (expected, _) = Pubkey::find_program_address(
+                    &[RegionStats::SEED, &entry.approval.region_code.to_le_bytes()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(region_info.key(), expected, YourFunError::InvalidBatchAccount);
+
+                activate(registry, record, &mut region_stats, &entry.approval, now)?;
+                region_stats.exit(ctx.program_id)?;
+                entry.admitted_at = now;
+                admitted += 1;
+            }
+            Some(record) => {
+                record.set_waitlisted(false);
+                entry.skipped = true;
+            }
+            None => entry.skipped = true,
+        }
+        if let Some(record) = human_record {
+            record.exit(ctx.program_id)?;
+        }
+        entry.exit(ctx.program_id)?;
+        registry.waitlist_next += 1;
+
+        emit!(WaitlistEntryProcessed {
+            wallet: entry.wallet,
+            position: entry.position,
+            admitted: !entry.skipped,
+            processed_at: now,
+        });
+    }
+
+    registry.bump_state_version()?;
+    record_admin_action(
+        registry,
+        &mut ctx.accounts.admin_action,
+        ctx.bumps.admin_action,
+        AdminActionKind::AdmitFromWaitlist,
+        ctx.accounts.authority.key(),
+        &(first_position, count),
+    )?;
+    record_epoch_activity(
+        &ctx.accounts.epoch_stats,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        now,
+        EpochActivity { verified_humans: admitted as u64, ..Default::default() },
+    )?;
+
+    msg!("{} of {} waitlist entries admitted", admitted, count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::client;
+    use crate::fixtures::{registered_challenge_response, FixtureBuilder};
+    use crate::harness::{custom, Harness, NOW};
+    use crate::state::VerificationLevel;
+
+    fn verify(
+        harness: &mut Harness,
+        authority: &Keypair,
+        wallet: &Pubkey,
+        waitlist_position: Option<u64>,
+    ) -> std::result::Result<(), InstructionError> {
+        let ix = client::build_verify_human_ix(
+            &authority.pubkey(),
+            false,
+            wallet,
+            None,
+            &hash(&wallet.to_bytes()).to_bytes(),
+            registered_challenge_response(wallet),
+            100,
+            VerificationLevel::Basic as u8,
+            0,
+            [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+            Pubkey::default(),
+            waitlist_position,
+            NOW,
+        );
+        harness.process(ix, &[authority])
+    }
+
+    fn admin_action_count(harness: &mut Harness) -> u64 {
+        harness.account::<PlatformRegistry>(&client::registry_address()).admin_action_count
+    }
+
+    fn set_cap(harness: &mut Harness, authority: &Keypair, max_verified_humans: u64) {
+        let count = admin_action_count(harness);
+        let ix = client::build_admin_config_ix(
+            &authority.pubkey(),
+            count,
+            crate::instruction::SetMaxVerifiedHumans { max_verified_humans },
+        );
+        harness.process(ix, &[authority]).unwrap();
+    }
+
+    fn admit(
+        harness: &mut Harness,
+        authority: &Keypair,
+        first_position: u64,
+        wallets: &[&Keypair],
+    ) -> std::result::Result<(), InstructionError> {
+        let entries: Vec<_> = wallets.iter().map(|wallet| (wallet.pubkey(), 0)).collect();
+        let count = admin_action_count(harness);
+        let ix = client::build_admit_from_waitlist_ix(&authority.pubkey(), count, first_position, &entries, NOW);
+        harness.process(ix, &[authority])
+    }
+
+    fn create_session(harness: &mut Harness, owner: &Keypair) -> std::result::Result<(), InstructionError> {
+        let ix = client::build_create_session_ix(
+            &owner.pubkey(),
+            None,
+            0,
+            0,
+            [2u8; 32],
+            0,
+            0,
+            false,
+            None,
+            0,
+            false,
+            NOW,
+        );
+        harness.process(ix, &[owner])
+    }
+
+    #[test]
+    fn approvals_past_the_cap_wait_and_are_admitted_in_order_once_it_is_raised() {
+        let authority = Keypair::new();
+        let wallets: Vec<_> = (0..5).map(|_| Keypair::new()).collect();
+        let mut builder = FixtureBuilder::new()
+            .with_authority(authority.pubkey())
+            .with_registry(|registry| registry.max_verified_humans = 2)
+            .with_region_stats(0);
+        for wallet in &wallets {
+            builder = builder.with_registered_human(wallet.pubkey());
+        }
+        let mut harness = Harness::start(builder.build());
+        harness.fund(&authority.pubkey(), 10_000_000_000);
+        for wallet in &wallets {
+            harness.fund(&wallet.pubkey(), 1_000_000_000);
+        }
+        let record = |harness: &mut Harness, i: usize| -> HumanRecord {
+            harness.account(&client::human_record_address(&wallets[i].pubkey()))
+        };
+
+        // Filling the cap.
+        verify(&mut harness, &authority, &wallets[0].pubkey(), None).unwrap();
+        verify(&mut harness, &authority, &wallets[1].pubkey(), None).unwrap();
+        assert_eq!(harness.account::<PlatformRegistry>(&client::registry_address()).total_verified_humans, 2);
+
+        // Past it, the approval needs the next waitlist slot.
+        assert_eq!(
+            verify(&mut harness, &authority, &wallets[2].pubkey(), None),
+            Err(custom(YourFunError::InvalidWaitlistEntry))
+        );
+        verify(&mut harness, &authority, &wallets[2].pubkey(), Some(0)).unwrap();
+        verify(&mut harness, &authority, &wallets[3].pubkey(), Some(1)).unwrap();
+        assert_eq!(
+            verify(&mut harness, &authority, &wallets[2].pubkey(), Some(2)),
+            Err(custom(YourFunError::AlreadyWaitlisted))
+        );
+        let waiting = record(&mut harness, 2);
+        assert!(waiting.is_waitlisted() && !waiting.is_active());
+        let entry: WaitlistEntry = harness.account(&client::waitlist_entry_address(0));
+        assert_eq!(
+            (entry.position, entry.wallet, entry.waitlisted_at, entry.admitted_at),
+            (0, wallets[2].pubkey(), NOW, 0)
+        );
+        let registry: PlatformRegistry = harness.account(&client::registry_address());
+        assert_eq!((registry.total_verified_humans, registry.waitlist_count, registry.waitlist_next), (2, 2, 0));
+
+        // Waitlisted humans cannot open sessions.
+        assert_eq!(create_session(&mut harness, &wallets[2]), Err(custom(YourFunError::HumanRecordRevoked)));
+
+        assert_eq!(
+            admit(&mut harness, &authority, 0, &[&wallets[2]]),
+            Err(custom(YourFunError::VerificationCapReached))
+        );
+        set_cap(&mut harness, &authority, 3);
+        // Raising the cap does not let a new approval jump the queue.
+        assert_eq!(
+            verify(&mut harness, &authority, &wallets[4].pubkey(), None),
+            Err(custom(YourFunError::InvalidWaitlistEntry))
+        );
+        // Entries are admitted from the front only.
+        let ix = client::build_admit_from_waitlist_ix(
+            &authority.pubkey(),
+            admin_action_count(&mut harness),
+            1,
+            &[(wallets[3].pubkey(), 0)],
+            NOW,
+        );
+        assert_eq!(harness.process(ix, &[&authority]), Err(custom(YourFunError::InvalidBatchAccount)));
+
+        admit(&mut harness, &authority, 0, &[&wallets[2]]).unwrap();
+        let admitted = record(&mut harness, 2);
+        assert!(admitted.is_active() && !admitted.is_waitlisted());
+        assert_eq!(admitted.verified_at, NOW);
+        assert_eq!(harness.account::<WaitlistEntry>(&client::waitlist_entry_address(0)).admitted_at, NOW);
+        let region: RegionStats = harness.account(&client::region_stats_address(0));
+        assert_eq!(region.verified_humans, 3);
+        create_session(&mut harness, &wallets[2]).unwrap();
+
+        // The cap is met again, so the next entry waits for another raise.
+        assert_eq!(
+            admit(&mut harness, &authority, 1, &[&wallets[3]]),
+            Err(custom(YourFunError::VerificationCapReached))
+        );
+        set_cap(&mut harness, &authority, 5);
+        admit(&mut harness, &authority, 1, &[&wallets[3]]).unwrap();
+        assert!(record(&mut harness, 3).is_active());
+        assert_eq!(
+            admit(&mut harness, &authority, 2, &[&wallets[4]]),
+            Err(custom(YourFunError::InvalidBatchSize))
+        );
+        let registry: PlatformRegistry = harness.account(&client::registry_address());
+        assert_eq!((registry.total_verified_humans, registry.waitlist_next), (4, 2));
+
+        // With the waitlist drained, approvals under the cap apply directly.
+        verify(&mut harness, &authority, &wallets[4].pubkey(), None).unwrap();
+        assert!(record(&mut harness, 4).is_active());
+    }
+}
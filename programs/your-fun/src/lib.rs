@@ -1,10 +1,26 @@
 use anchor_lang::prelude::*;
 
+#[cfg(all(any(test, feature = "client"), not(target_os = "solana")))]
+pub mod client;
+pub mod compute;
+pub mod constants;
+pub mod crypto;
 pub mod error;
+pub mod events;
+#[cfg(all(any(test, feature = "test-utils"), not(target_os = "solana")))]
+pub mod fixtures;
+#[cfg(test)]
+mod harness;
 pub mod instructions;
+pub mod scoring;
+pub mod sigverify;
 pub mod state;
 
 use instructions::*;
+use state::{ConfigUpdate, FeeSchedule};
+
+/// Versioned input of `record_interaction_v2`, for client builders.
+pub use instructions::InteractionInputV2;
 
 declare_id!("YRFunHP2kVerify1111111111111111111111111111");
 
@@ -13,13 +29,18 @@ pub mod your_fun {
     use super::*;
 
     /// Initializes the platform registry with global configuration.
-    /// Can only be called once by the deploying authority.
+    /// Can only be called once by the deploying authority. A default
+    /// `treasury` sends fees to the authority.
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         ctx: Context<Initialize>,
         verification_fee_lamports: u64,
         min_behavioral_score: u8,
         max_session_duration: i64,
         max_interactions_per_session: u32,
+        min_fee_lamports: u64,
+        max_fee_lamports: u64,
+        treasury: Pubkey,
     ) -> Result<()> {
         handler_initialize(
             ctx,
@@ -27,17 +48,81 @@ pub mod your_fun {
             min_behavioral_score,
             max_session_duration,
             max_interactions_per_session,
+            min_fee_lamports,
+            max_fee_lamports,
+            treasury,
         )
     }
 
     /// Begins the human verification process.
-    /// Creates a HumanRecord PDA with challenge data for subsequent verification.
+    /// Creates a HumanRecord PDA with challenge data for subsequent verification,
+    /// storing the raw fingerprint alongside it and its SHA-256 hash on the record.
+    /// `promo_code` redeems the supplied promo for a discounted fee.
     pub fn register_human(
         ctx: Context<RegisterHuman>,
         challenge_nonce: [u8; 32],
-        fingerprint_hash: [u8; 32],
+        fingerprint_data: Vec<u8>,
+        promo_code: Option<Vec<u8>>,
+    ) -> Result<()> {
+        handler_register_human(ctx, challenge_nonce, fingerprint_data, promo_code)
+    }
+
+    /// Registers like `register_human` with the fee paid by the scholarship
+    /// pool, consuming the wallet's approval.
+    pub fn register_human_scholarship(
+        ctx: Context<RegisterHumanScholarship>,
+        challenge_nonce: [u8; 32],
+        fingerprint_data: Vec<u8>,
+    ) -> Result<()> {
+        handler_register_human_scholarship(ctx, challenge_nonce, fingerprint_data)
+    }
+
+    /// Donates lamports to the scholarship pool.
+    pub fn fund_scholarship_pool(ctx: Context<FundScholarshipPool>, amount: u64) -> Result<()> {
+        handler_fund_scholarship_pool(ctx, amount)
+    }
+
+    /// Approves a wallet for a scholarship. Authority or program officer.
+    pub fn approve_scholarship(ctx: Context<ApproveScholarship>, wallet: Pubkey) -> Result<()> {
+        handler_approve_scholarship(ctx, wallet)
+    }
+
+    /// Creates the insurance fund fed by a share of every fee.
+    pub fn init_insurance_fund(ctx: Context<InitInsuranceFund>) -> Result<()> {
+        handler_init_insurance_fund(ctx)
+    }
+
+    /// Compensates an approved appeal from the insurance fund. Authority only.
+    pub fn pay_insurance_claim(
+        ctx: Context<PayInsuranceClaim>,
+        amount: u64,
+        claim_ref_hash: [u8; 32],
+    ) -> Result<()> {
+        handler_pay_insurance_claim(ctx, amount, claim_ref_hash)
+    }
+
+    /// Creates the fee vault fees can be paid into. Authority only.
+    pub fn init_fee_vault(ctx: Context<InitFeeVault>) -> Result<()> {
+        handler_init_fee_vault(ctx)
+    }
+
+    /// Withdraws collected fees from the fee vault. Authority only.
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        handler_withdraw_fees(ctx, amount)
+    }
+
+    /// Invites wallets to register while registration is permissioned.
+    /// The invite accounts follow as remaining accounts. Authority only.
+    pub fn create_invites<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateInvites<'info>>,
+        wallets: Vec<Pubkey>,
     ) -> Result<()> {
-        handler_register_human(ctx, challenge_nonce, fingerprint_hash)
+        handler_create_invites(ctx, wallets)
+    }
+
+    /// Withdraws an unused invite. Authority only.
+    pub fn revoke_invite(ctx: Context<RevokeInvite>) -> Result<()> {
+        handler_revoke_invite(ctx)
     }
 
     /// Completes the human verification process.
@@ -47,17 +132,260 @@ pub mod your_fun {
         challenge_response: [u8; 32],
         behavioral_score: u8,
         verification_level: u8,
+        region_code: u16,
+        attribute_commitments: [[u8; 32]; 4],
+        supervisor: Pubkey,
+    ) -> Result<()> {
+        handler_verify_human(
+            ctx,
+            challenge_response,
+            behavioral_score,
+            verification_level,
+            region_code,
+            attribute_commitments,
+            supervisor,
+        )
+    }
+
+    /// Verifies a human from behavioral, social-graph and longevity
+    /// sub-scores, checking their weighted composite against the threshold.
+    pub fn verify_human_v2(
+        ctx: Context<VerifyHuman>,
+        challenge_response: [u8; 32],
+        sub_scores: [u8; 3],
+        verification_level: u8,
+        region_code: u16,
+        attribute_commitments: [[u8; 32]; 4],
+        supervisor: Pubkey,
+    ) -> Result<()> {
+        handler_verify_human_v2(
+            ctx,
+            challenge_response,
+            sub_scores,
+            verification_level,
+            region_code,
+            attribute_commitments,
+            supervisor,
+        )
+    }
+
+    /// Voids a verification inside its contest window. Authority or another
+    /// elected verifier.
+    pub fn contest_verification(ctx: Context<ContestVerification>, reason_code: u8) -> Result<()> {
+        handler_contest_verification(ctx, reason_code)
+    }
+
+    /// Pays the approving verifier the fee share held back until the
+    /// verification took effect. Permissionless.
+    pub fn claim_verifier_fee(ctx: Context<ClaimVerifierFee>) -> Result<()> {
+        handler_claim_verifier_fee(ctx)
+    }
+
+    /// Removes a human whose verification has lapsed from the active
+    /// humans. Permissionless.
+    pub fn expire_verification(ctx: Context<ExpireVerification>) -> Result<()> {
+        handler_expire_verification(ctx)
+    }
+
+    /// Returns the human's composite humanity score, or 0 while unverified.
+    pub fn humanity_score(ctx: Context<HumanityScore>) -> Result<u8> {
+        handler_humanity_score(ctx)
+    }
+
+    /// Returns whether a revealed attribute opens the human's commitment in
+    /// slot `index`. Read-only; meant for CPI.
+    pub fn verify_attribute(
+        ctx: Context<VerifyAttribute>,
+        index: u8,
+        value_hash: [u8; 32],
+        salt: [u8; 32],
+    ) -> Result<bool> {
+        handler_verify_attribute(ctx, index, value_hash, salt)
+    }
+
+    /// Verifies several humans at once; the records follow as remaining accounts.
+    /// Any invalid entry aborts the whole batch.
+    pub fn verify_humans_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyHumansBatch<'info>>,
+        entries: Vec<VerifyEntry>,
     ) -> Result<()> {
-        handler_verify_human(ctx, challenge_response, behavioral_score, verification_level)
+        handler_verify_humans_batch(ctx, entries)
     }
 
-    /// Creates a new AI companion session for a verified human.
+    /// Blocklists a fingerprint hash. Authority or an elected verifier.
+    pub fn list_compromised_fingerprint(
+        ctx: Context<ListCompromisedFingerprint>,
+        fingerprint_hash: [u8; 32],
+        reason_code: u8,
+    ) -> Result<()> {
+        handler_list_compromised_fingerprint(ctx, fingerprint_hash, reason_code)
+    }
+
+    /// Removes a fingerprint hash from the blocklist. Authority only.
+    pub fn delist_compromised_fingerprint(ctx: Context<DelistCompromisedFingerprint>) -> Result<()> {
+        handler_delist_compromised_fingerprint(ctx)
+    }
+
+    /// Requires re-verification of a record whose fingerprint is blocklisted.
+    /// Permissionless.
+    pub fn flag_compromised_record(ctx: Context<FlagCompromisedRecord>) -> Result<()> {
+        handler_flag_compromised_record(ctx)
+    }
+
+    /// Turns down a pending verification, counting it in the verifier's stats.
+    pub fn reject_verification(ctx: Context<RejectVerification>, reason_code: u8) -> Result<()> {
+        handler_reject_verification(ctx, reason_code)
+    }
+
+    /// Sets up stake-weighted verifier elections. Authority only.
+    pub fn init_election(ctx: Context<InitElection>, epoch_duration: i64, seats: u8) -> Result<()> {
+        handler_init_election(ctx, epoch_duration, seats)
+    }
+
+    /// Nominates a verifier candidate for the current election epoch.
+    pub fn nominate_verifier(ctx: Context<NominateVerifier>, candidate: Pubkey) -> Result<()> {
+        handler_nominate_verifier(ctx, candidate)
+    }
+
+    /// Locks governance tokens behind a candidate for the current epoch.
+    pub fn vote_for_verifier(ctx: Context<VoteForVerifier>, amount: u64) -> Result<()> {
+        handler_vote_for_verifier(ctx, amount)
+    }
+
+    /// Seats the top candidates of an ended epoch and opens the next one.
+    /// Candidates and their verifier accounts follow as remaining accounts.
+    pub fn elect_verifiers<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ElectVerifiers<'info>>,
+    ) -> Result<()> {
+        handler_elect_verifiers(ctx)
+    }
+
+    /// Unseats a verifier that was not re-elected.
+    pub fn retire_verifier(ctx: Context<RetireVerifier>) -> Result<()> {
+        handler_retire_verifier(ctx)
+    }
+
+    /// Returns locked vote tokens after their epoch has been elected.
+    pub fn withdraw_vote(ctx: Context<WithdrawVote>) -> Result<()> {
+        handler_withdraw_vote(ctx)
+    }
+
+    /// Suspends a human until `until`, blocking new sessions and
+    /// interactions. Authority only.
+    pub fn freeze_human(ctx: Context<FreezeHuman>, until: i64) -> Result<()> {
+        handler_freeze_human(ctx, until)
+    }
+
+    /// Lifts a human's freeze early. Authority only.
+    pub fn unfreeze_human(ctx: Context<FreezeHuman>) -> Result<()> {
+        handler_unfreeze_human(ctx)
+    }
+
+    /// Revokes a verified human. Authority only.
+    pub fn revoke_human(ctx: Context<RevokeHuman>) -> Result<()> {
+        handler_revoke_human(ctx)
+    }
+
+    /// Appeals the revocation of the caller's record within the appeal window.
+    pub fn file_appeal(ctx: Context<FileAppeal>, evidence_hash: [u8; 32]) -> Result<()> {
+        handler_file_appeal(ctx, evidence_hash)
+    }
+
+    /// Approves or denies a pending appeal. Arbiter only.
+    pub fn resolve_appeal(
+        ctx: Context<ResolveAppeal>,
+        approve: bool,
+        note_hash: [u8; 32],
+    ) -> Result<()> {
+        handler_resolve_appeal(ctx, approve, note_hash)
+    }
+
+    /// Approves or withdraws a document issuer. Authority only.
+    pub fn set_document_issuer(
+        ctx: Context<SetDocumentIssuer>,
+        issuer: Pubkey,
+        issuer_id: u16,
+        approved: bool,
+    ) -> Result<()> {
+        handler_set_document_issuer(ctx, issuer, issuer_id, approved)
+    }
+
+    /// Attests a wallet's identity document. Approved issuers only.
+    pub fn attest_document(
+        ctx: Context<AttestDocument>,
+        wallet: Pubkey,
+        document_commitment: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        handler_attest_document(ctx, wallet, document_commitment, expires_at)
+    }
+
+    /// Folds a duplicate HumanRecord into the primary record of the same
+    /// person. Authority plus both wallets.
+    pub fn merge_human_records(ctx: Context<MergeHumanRecords>) -> Result<()> {
+        handler_merge_human_records(ctx)
+    }
+
+    /// Places a human under a supervisor's guardianship. Authority only.
+    pub fn set_supervisor(ctx: Context<SetSupervisor>, supervisor: Pubkey) -> Result<()> {
+        handler_set_supervisor(ctx, supervisor)
+    }
+
+    /// Lifts a human's supervision. Owner and authority together.
+    pub fn clear_supervisor(ctx: Context<ClearSupervisor>) -> Result<()> {
+        handler_clear_supervisor(ctx)
+    }
+
+    /// Creates a new AI companion session for a verified human and returns
+    /// its session nonce.
     pub fn create_session(
         ctx: Context<CreateSession>,
         personality_id: u8,
         initial_topic: [u8; 32],
+        goal_type: u8,
+        goal_target: u64,
+        escrow_rate_per_minute: u64,
+    ) -> Result<[u8; 32]> {
+        handler_create_session(
+            ctx,
+            personality_id,
+            initial_topic,
+            goal_type,
+            goal_target,
+            escrow_rate_per_minute,
+        )
+    }
+
+    /// Publishes a session template. Authority or curator.
+    pub fn create_template(
+        ctx: Context<CreateTemplate>,
+        template_id: u16,
+        personality_id: u8,
+        topic: [u8; 32],
+        duration_override: i64,
+        scoring_profile: u8,
     ) -> Result<()> {
-        handler_create_session(ctx, personality_id, initial_topic)
+        handler_create_template(
+            ctx,
+            template_id,
+            personality_id,
+            topic,
+            duration_override,
+            scoring_profile,
+        )
+    }
+
+    /// Enables or disables a session template. Authority or curator.
+    pub fn set_template_enabled(ctx: Context<SetTemplateEnabled>, enabled: bool) -> Result<()> {
+        handler_set_template_enabled(ctx, enabled)
+    }
+
+    /// Creates a session with the parameters of an enabled template.
+    pub fn create_session_from_template(
+        ctx: Context<CreateSessionFromTemplate>,
+        template_id: u16,
+    ) -> Result<[u8; 32]> {
+        handler_create_session_from_template(ctx, template_id)
     }
 
     /// Closes an active session and accumulates the session score.
@@ -65,6 +393,80 @@ pub mod your_fun {
         handler_close_session(ctx)
     }
 
+    /// Settles a closed session's pending score after its settlement window.
+    pub fn finalize_session(ctx: Context<FinalizeSession>) -> Result<()> {
+        handler_finalize_session(ctx)
+    }
+
+    /// Forfeits a closed session's pending score inside its settlement
+    /// window. Authority or an elected verifier.
+    pub fn contest_settlement(ctx: Context<ContestSettlement>, reason_code: u8) -> Result<()> {
+        handler_contest_settlement(ctx, reason_code)
+    }
+
+    /// Creates a vesting token reward for reaching a learning score.
+    pub fn create_milestone(
+        ctx: Context<CreateMilestone>,
+        milestone_id: u16,
+        score_threshold: u64,
+        reward_amount: u64,
+        cliff: i64,
+        vesting_duration: i64,
+    ) -> Result<()> {
+        handler_create_milestone(
+            ctx,
+            milestone_id,
+            score_threshold,
+            reward_amount,
+            cliff,
+            vesting_duration,
+        )
+    }
+
+    /// Changes the vesting schedule of later claims of a milestone.
+    pub fn set_milestone_schedule(
+        ctx: Context<SetMilestoneSchedule>,
+        cliff: i64,
+        vesting_duration: i64,
+    ) -> Result<()> {
+        handler_set_milestone_schedule(ctx, cliff, vesting_duration)
+    }
+
+    /// Claims a reached milestone, starting its reward's vesting.
+    pub fn claim_milestone(ctx: Context<ClaimMilestone>) -> Result<()> {
+        handler_claim_milestone(ctx)
+    }
+
+    /// Releases the newly vested part of a milestone reward.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        handler_claim_vested(ctx)
+    }
+
+    /// Tips a configured personality in lamports.
+    pub fn tip_personality(ctx: Context<TipPersonality>, personality_id: u8, amount: u64) -> Result<()> {
+        handler_tip_personality(ctx, personality_id, amount)
+    }
+
+    /// Withdraws tips from a personality treasury. Authority only.
+    pub fn withdraw_personality_tips(ctx: Context<WithdrawPersonalityTips>, amount: u64) -> Result<()> {
+        handler_withdraw_personality_tips(ctx, amount)
+    }
+
+    /// Terminates an abusive session. Authority or moderator only.
+    pub fn force_close_session(ctx: Context<ForceCloseSession>, reason_code: u8) -> Result<()> {
+        handler_force_close_session(ctx, reason_code)
+    }
+
+    /// Force-closes a session as the authority, optionally forfeiting its
+    /// score. Authority only.
+    pub fn admin_force_close_session(
+        ctx: Context<AdminForceCloseSession>,
+        reason_code: u8,
+        forfeit_score: bool,
+    ) -> Result<()> {
+        handler_admin_force_close_session(ctx, reason_code, forfeit_score)
+    }
+
     /// Extends the expiration of an active session.
     pub fn extend_session(
         ctx: Context<ExtendSession>,
@@ -73,14 +475,659 @@ pub mod your_fun {
         handler_extend_session(ctx, additional_duration)
     }
 
+    /// Reopens an expired, unclosed session within the grace window for a fee.
+    pub fn reopen_session(ctx: Context<ReopenSession>, additional_duration: i64) -> Result<()> {
+        handler_reopen_session(ctx, additional_duration)
+    }
+
+    /// Applies a signed bundle of interactions recorded offline.
+    pub fn submit_interaction_bundle(
+        ctx: Context<SubmitInteractionBundle>,
+        bundle: Vec<InteractionInput>,
+        bundle_signature: [u8; 64],
+    ) -> Result<()> {
+        handler_submit_interaction_bundle(ctx, bundle, bundle_signature)
+    }
+
+    /// Registers the key that may sign interaction bundles for the owner.
+    pub fn set_interaction_delegate(
+        ctx: Context<SetInteractionDelegate>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        handler_set_interaction_delegate(ctx, delegate)
+    }
+
+    /// Pins a logged interaction of one of the owner's sessions.
+    pub fn pin_highlight(ctx: Context<PinHighlight>) -> Result<()> {
+        handler_pin_highlight(ctx)
+    }
+
+    /// Unpins an interaction, freeing its highlight slot.
+    pub fn unpin_highlight(
+        ctx: Context<UnpinHighlight>,
+        session: Pubkey,
+        interaction_index: u32,
+    ) -> Result<()> {
+        handler_unpin_highlight(ctx, session, interaction_index)
+    }
+
+    /// Registers a curriculum topic. Authority or curator.
+    pub fn register_topic(
+        ctx: Context<RegisterTopic>,
+        topic: [u8; 32],
+        metadata_hash: [u8; 32],
+    ) -> Result<()> {
+        handler_register_topic(ctx, topic, metadata_hash)
+    }
+
+    /// Enables or disables a registered topic. Authority or curator.
+    pub fn set_topic_enabled(ctx: Context<SetTopicEnabled>, enabled: bool) -> Result<()> {
+        handler_set_topic_enabled(ctx, enabled)
+    }
+
+    /// Switches a session's active topic, adding it if new.
+    pub fn update_topic(ctx: Context<UpdateTopic>, topic: [u8; 32]) -> Result<()> {
+        handler_update_topic(ctx, topic)
+    }
+
+    /// Creates the owner's score account for one topic.
+    pub fn init_topic_progress(ctx: Context<InitTopicProgress>, topic: [u8; 32]) -> Result<()> {
+        handler_init_topic_progress(ctx, topic)
+    }
+
     /// Records a learning interaction within an active session.
+    #[allow(clippy::too_many_arguments)]
     pub fn record_interaction(
         ctx: Context<RecordInteraction>,
         content_hash: [u8; 32],
         interaction_type: u8,
         score: u8,
         duration_seconds: u32,
+        content_uri_hash: [u8; 32],
+        content_size: u32,
+        private: bool,
+    ) -> Result<()> {
+        handler_record_interaction(
+            ctx,
+            content_hash,
+            interaction_type,
+            score,
+            duration_seconds,
+            content_uri_hash,
+            content_size,
+            private,
+        )
+    }
+
+    /// Records a learning interaction described by a versioned input.
+    pub fn record_interaction_v2(
+        ctx: Context<RecordInteraction>,
+        input: InteractionInputV2,
+    ) -> Result<()> {
+        handler_record_interaction_v2(ctx, input)
+    }
+
+    /// Records an interaction below the log threshold without an InteractionLog account.
+    pub fn record_unlogged_interaction(
+        ctx: Context<RecordUnloggedInteraction>,
+        content_hash: [u8; 32],
+        interaction_type: u8,
+        score: u8,
+        duration_seconds: u32,
+    ) -> Result<()> {
+        handler_record_unlogged_interaction(
+            ctx,
+            content_hash,
+            interaction_type,
+            score,
+            duration_seconds,
+        )
+    }
+
+    /// Returns the score increment an interaction would earn, without recording it.
+    pub fn preview_score(
+        ctx: Context<PreviewScore>,
+        score: u8,
+        interaction_type: u8,
+        duration_seconds: u32,
+        private: bool,
+    ) -> Result<u64> {
+        handler_preview_score(ctx, score, interaction_type, duration_seconds, private)
+    }
+
+    /// Closes an interaction log of a closed session, refunding its rent.
+    pub fn reap_interaction(ctx: Context<ReapInteraction>) -> Result<()> {
+        handler_reap_interaction(ctx)
+    }
+
+    /// Keeps a session's activity timestamp fresh without recording an interaction.
+    pub fn heartbeat_session(ctx: Context<HeartbeatSession>) -> Result<()> {
+        handler_heartbeat_session(ctx)
+    }
+
+    /// Rates a closed session once, for its personality's stats. Owner only.
+    pub fn rate_session(ctx: Context<RateSession>, rating: u8) -> Result<()> {
+        handler_rate_session(ctx, rating)
+    }
+
+    /// Reclaims the rent of a closed session once all its logs are reaped.
+    pub fn reclaim_session(ctx: Context<ReclaimSession>) -> Result<()> {
+        handler_reclaim_session(ctx)
+    }
+
+    /// Creates the owner's index of open sessions.
+    pub fn init_active_session_index(ctx: Context<InitActiveSessionIndex>) -> Result<()> {
+        handler_init_active_session_index(ctx)
+    }
+
+    /// Creates one of the sharded platform counter buckets. Permissionless.
+    pub fn init_stats_bucket(ctx: Context<InitStatsBucket>, bucket_index: u8) -> Result<()> {
+        handler_init_stats_bucket(ctx, bucket_index)
+    }
+
+    /// Creates the verified-human counter of a region. Permissionless.
+    pub fn init_region_stats(ctx: Context<InitRegionStats>, region_code: u16) -> Result<()> {
+        handler_init_region_stats(ctx, region_code)
+    }
+
+    /// Folds a stats bucket's pending counters into the registry. Permissionless.
+    pub fn aggregate_stats(ctx: Context<AggregateStats>) -> Result<()> {
+        handler_aggregate_stats(ctx)
+    }
+
+    /// Closes the caller's human record and leaves a tombstone behind.
+    pub fn close_human_record(ctx: Context<CloseHumanRecord>) -> Result<()> {
+        handler_close_human_record(ctx)
+    }
+
+    /// Removes a wallet's tombstone. Authority only.
+    pub fn close_tombstone(ctx: Context<CloseTombstone>) -> Result<()> {
+        handler_close_tombstone(ctx)
+    }
+
+    /// Imports a wallet's HumanRecord from the previous deployment while the
+    /// import window is open. Permissionless.
+    pub fn import_legacy_record(ctx: Context<ImportLegacyRecord>, wallet: Pubkey) -> Result<()> {
+        handler_import_legacy_record(ctx, wallet)
+    }
+
+    /// Migrates a HumanRecord to the current layout version. Permissionless.
+    pub fn migrate_human_record(ctx: Context<MigrateHumanRecord>) -> Result<()> {
+        handler_migrate_human_record(ctx)
+    }
+
+    /// Migrates a SessionAccount to the current layout. Permissionless.
+    pub fn migrate_session(ctx: Context<MigrateSession>) -> Result<()> {
+        handler_migrate_session(ctx)
+    }
+
+    /// Upgrades the registry to the current layout. Authority only.
+    pub fn migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+        handler_migrate_registry(ctx)
+    }
+
+    /// Checks a human's counters against its sessions and stamps the audit.
+    pub fn assert_human_invariants(ctx: Context<AssertHumanInvariants>) -> Result<()> {
+        handler_assert_human_invariants(ctx)
+    }
+
+    /// Rescores an open session from its interaction logs. Authority only.
+    pub fn recompute_session_score(
+        ctx: Context<RecomputeSessionScore>,
+        target_scoring_profile: Option<u8>,
+    ) -> Result<()> {
+        handler_recompute_session_score(ctx, target_scoring_profile)
+    }
+
+    /// Caps the owner's program spending per 30 days; 0 removes the cap.
+    pub fn set_spending_cap(ctx: Context<SetSpendingCap>, lamports_per_30d: u64) -> Result<()> {
+        handler_set_spending_cap(ctx, lamports_per_30d)
+    }
+
+    /// Grows a personality config to the layout with partner revenue.
+    pub fn migrate_personality_config(ctx: Context<MigratePersonalityConfig>) -> Result<()> {
+        handler_migrate_personality_config(ctx)
+    }
+
+    /// Grows a stats bucket to the layout that counts closed sessions.
+    pub fn migrate_stats_bucket(ctx: Context<MigrateStatsBucket>) -> Result<()> {
+        handler_migrate_stats_bucket(ctx)
+    }
+
+    /// Grows a personality's stats to the layout with rating totals.
+    pub fn migrate_personality_stats(ctx: Context<MigratePersonalityStats>) -> Result<()> {
+        handler_migrate_personality_stats(ctx)
+    }
+
+    /// Sets how long a wallet must wait to register again after closing its record.
+    pub fn set_reregistration_cooldown(
+        ctx: Context<AdminConfig>,
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        handler_set_reregistration_cooldown(ctx, cooldown_seconds)
+    }
+
+    /// Sets the maximum accepted length of raw fingerprint data.
+    pub fn set_max_fingerprint_len(
+        ctx: Context<AdminConfig>,
+        max_fingerprint_len: u16,
+    ) -> Result<()> {
+        handler_set_max_fingerprint_len(ctx, max_fingerprint_len)
+    }
+
+    /// Designates the moderator allowed to force-close sessions.
+    pub fn set_moderator(ctx: Context<AdminConfig>, moderator: Pubkey) -> Result<()> {
+        handler_set_moderator(ctx, moderator)
+    }
+
+    /// Sets the share of a force-closed session's score that is still settled.
+    pub fn set_force_close_settle_bps(ctx: Context<AdminConfig>, settle_bps: u16) -> Result<()> {
+        handler_set_force_close_settle_bps(ctx, settle_bps)
+    }
+
+    /// Sets the minimum score for which an interaction gets its own log account.
+    pub fn set_log_threshold_score(ctx: Context<AdminConfig>, log_threshold_score: u8) -> Result<()> {
+        handler_set_log_threshold_score(ctx, log_threshold_score)
+    }
+
+    /// Sets the verification validity and grace periods.
+    pub fn set_verification_validity(
+        ctx: Context<AdminConfig>,
+        validity_seconds: i64,
+        grace_period_seconds: i64,
+    ) -> Result<()> {
+        handler_set_verification_validity(ctx, validity_seconds, grace_period_seconds)
+    }
+
+    /// Updates the verification fee within the configured bounds.
+    pub fn set_verification_fee(ctx: Context<AdminConfig>, fee_lamports: u64) -> Result<()> {
+        handler_set_verification_fee(ctx, fee_lamports)
+    }
+
+    /// Permanently freezes the registry configuration. Authority only.
+    pub fn freeze_config(ctx: Context<AdminConfig>) -> Result<()> {
+        handler_freeze_config(ctx)
+    }
+
+    /// Restricts registration to invited wallets, or opens it again.
+    pub fn set_permissioned_registration(
+        ctx: Context<AdminConfig>,
+        permissioned: bool,
+    ) -> Result<()> {
+        handler_set_permissioned_registration(ctx, permissioned)
+    }
+
+    /// Turns region restrictions on verification on or off.
+    pub fn set_region_restriction(ctx: Context<AdminConfig>, restrict_regions: bool) -> Result<()> {
+        handler_set_region_restriction(ctx, restrict_regions)
+    }
+
+    /// Allows or blocks verification for one region.
+    pub fn set_region_allowed(
+        ctx: Context<AdminConfig>,
+        region_code: u16,
+        allowed: bool,
+    ) -> Result<()> {
+        handler_set_region_allowed(ctx, region_code, allowed)
+    }
+
+    /// Stops new sessions from starting with a personality after `deprecated_after`.
+    pub fn set_personality_sunset(
+        ctx: Context<SetPersonalitySunset>,
+        personality_id: u8,
+        deprecated_after: i64,
+    ) -> Result<()> {
+        handler_set_personality_sunset(ctx, personality_id, deprecated_after)
+    }
+
+    /// Sets the partner credited with a share of a personality's session fees.
+    pub fn set_personality_partner(
+        ctx: Context<SetPersonalityPartner>,
+        personality_id: u8,
+        partner: Pubkey,
+        partner_share_bps: u16,
+    ) -> Result<()> {
+        handler_set_personality_partner(ctx, personality_id, partner, partner_share_bps)
+    }
+
+    /// Pays a personality's accrued partner revenue to its partner.
+    pub fn claim_partner_revenue(ctx: Context<ClaimPartnerRevenue>) -> Result<()> {
+        handler_claim_partner_revenue(ctx)
+    }
+
+    /// Adds the next ticket page to the current raffle epoch.
+    pub fn open_raffle_page(ctx: Context<OpenRafflePage>, epoch: u64) -> Result<()> {
+        handler_open_raffle_page(ctx, epoch)
+    }
+
+    /// Adds lamports to a raffle epoch's prize.
+    pub fn fund_raffle(ctx: Context<FundRaffle>, amount: u64) -> Result<()> {
+        handler_fund_raffle(ctx, amount)
+    }
+
+    /// Fixes the future slot whose hash seeds an ended raffle's draw.
+    pub fn commit_raffle(ctx: Context<CommitRaffle>) -> Result<()> {
+        handler_commit_raffle(ctx)
+    }
+
+    /// Draws an ended raffle's winner from the committed slot hash.
+    pub fn draw_raffle(ctx: Context<DrawRaffle>) -> Result<()> {
+        handler_draw_raffle(ctx)
+    }
+
+    /// Pays a raffle's prize to its winner.
+    pub fn claim_raffle_prize(ctx: Context<ClaimRafflePrize>) -> Result<()> {
+        handler_claim_raffle_prize(ctx)
+    }
+
+    /// Creates a limited-use promo code discounting the verification fee.
+    pub fn create_promo(
+        ctx: Context<CreatePromo>,
+        code_hash: [u8; 32],
+        discount_bps: u16,
+        max_uses: u32,
+        expires_at: i64,
+    ) -> Result<()> {
+        handler_create_promo(ctx, code_hash, discount_bps, max_uses, expires_at)
+    }
+
+    /// Creates or updates a sponsor's verification fee matching campaign.
+    pub fn configure_matching(
+        ctx: Context<ConfigureMatching>,
+        matcher: Pubkey,
+        ratio_bps: u16,
+        cap_lamports: u64,
+        ends_at: i64,
+    ) -> Result<()> {
+        handler_configure_matching(ctx, matcher, ratio_bps, cap_lamports, ends_at)
+    }
+
+    /// Deposits lamports into the matcher's campaign escrow.
+    pub fn fund_matching_escrow(ctx: Context<FundMatchingEscrow>, amount: u64) -> Result<()> {
+        handler_fund_matching_escrow(ctx, amount)
+    }
+
+    /// Returns a matcher's leftover escrow once its campaign has ended.
+    pub fn reclaim_matching_escrow(ctx: Context<ReclaimMatchingEscrow>) -> Result<()> {
+        handler_reclaim_matching_escrow(ctx)
+    }
+
+    /// Opens the import window for records of the previous deployment.
+    pub fn set_legacy_import(
+        ctx: Context<AdminConfig>,
+        legacy_program_id: Pubkey,
+        import_ends_at: i64,
+    ) -> Result<()> {
+        handler_set_legacy_import(ctx, legacy_program_id, import_ends_at)
+    }
+
+    /// Sets the basis-point weights of the three verification sub-scores.
+    pub fn set_sub_score_weights(ctx: Context<AdminConfig>, weights_bps: [u16; 3]) -> Result<()> {
+        handler_set_sub_score_weights(ctx, weights_bps)
+    }
+
+    /// Sets how long new verifications can be contested before taking effect.
+    pub fn set_contest_window(ctx: Context<AdminConfig>, contest_window: i64) -> Result<()> {
+        handler_set_contest_window(ctx, contest_window)
+    }
+
+    /// Sets the companion backend key that must co-sign interactions.
+    pub fn set_companion_oracle(ctx: Context<AdminConfig>, companion_oracle: Pubkey) -> Result<()> {
+        handler_set_companion_oracle(ctx, companion_oracle)
+    }
+
+    /// Sets how long closed sessions wait before their score settles.
+    pub fn set_settlement_window(ctx: Context<AdminConfig>, settlement_window: i64) -> Result<()> {
+        handler_set_settlement_window(ctx, settlement_window)
+    }
+
+    /// Lists, updates or delists a booster mint accepted by `create_session`.
+    pub fn set_booster(ctx: Context<AdminConfig>, mint: Pubkey, multiplier_bps: u16) -> Result<()> {
+        handler_set_booster(ctx, mint, multiplier_bps)
+    }
+
+    /// Designates the key that may approve scholarships.
+    pub fn set_program_officer(ctx: Context<AdminConfig>, program_officer: Pubkey) -> Result<()> {
+        handler_set_program_officer(ctx, program_officer)
+    }
+
+    /// Sets the share of every fee routed to the insurance fund.
+    pub fn set_insurance_fee_bps(ctx: Context<AdminConfig>, insurance_fee_bps: u16) -> Result<()> {
+        handler_set_insurance_fee_bps(ctx, insurance_fee_bps)
+    }
+
+    /// Sets the share of the verification fee paid to the approving verifier.
+    pub fn set_verifier_fee_share(ctx: Context<AdminConfig>, verifier_fee_share_bps: u16) -> Result<()> {
+        handler_set_verifier_fee_share(ctx, verifier_fee_share_bps)
+    }
+
+    /// Sets the per-type score multipliers in basis points; all zero restores
+    /// the defaults.
+    pub fn set_type_multipliers(
+        ctx: Context<AdminConfig>,
+        type_multipliers: [u16; 8],
+    ) -> Result<()> {
+        handler_set_type_multipliers(ctx, type_multipliers)
+    }
+
+    /// Sets the shortest duration an interaction may report; 0 disables it.
+    pub fn set_min_interaction_duration(
+        ctx: Context<AdminConfig>,
+        duration_seconds: u16,
+    ) -> Result<()> {
+        handler_set_min_interaction_duration(ctx, duration_seconds)
+    }
+
+    /// Sets the minimum seconds between a session's interactions; 0 disables it.
+    pub fn set_min_interaction_gap(ctx: Context<AdminConfig>, gap_seconds: u16) -> Result<()> {
+        handler_set_min_interaction_gap(ctx, gap_seconds)
+    }
+
+    /// Sets the highest personality id sessions may use; 255 allows any.
+    pub fn set_max_personality_id(ctx: Context<AdminConfig>, max_personality_id: u8) -> Result<()> {
+        handler_set_max_personality_id(ctx, max_personality_id)
+    }
+
+    /// Caps the sessions a human may have open at once; 0 removes the cap.
+    pub fn set_max_active_sessions(ctx: Context<AdminConfig>, max_active_sessions: u8) -> Result<()> {
+        handler_set_max_active_sessions(ctx, max_active_sessions)
+    }
+
+    /// Caps the number of verified humans; 0 removes the cap.
+    pub fn set_max_verified_humans(ctx: Context<AdminConfig>, max_verified_humans: u64) -> Result<()> {
+        handler_set_max_verified_humans(ctx, max_verified_humans)
+    }
+
+    /// Applies the next waitlisted approvals in order. Authority only.
+    pub fn admit_from_waitlist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AdmitFromWaitlist<'info>>,
+        count: u8,
+    ) -> Result<()> {
+        handler_admit_from_waitlist(ctx, count)
+    }
+
+    /// Raises the delay queued config updates wait before execution.
+    pub fn set_config_timelock(ctx: Context<AdminConfig>, delay_seconds: i64) -> Result<()> {
+        handler_set_config_timelock(ctx, delay_seconds)
+    }
+
+    /// Queues a config update behind the timelock. Authority only.
+    pub fn queue_config_update(ctx: Context<QueueConfigUpdate>, update: ConfigUpdate) -> Result<()> {
+        handler_queue_config_update(ctx, update)
+    }
+
+    /// Applies the queued config update once its timelock has passed.
+    pub fn execute_config_update(ctx: Context<ExecuteConfigUpdate>) -> Result<()> {
+        handler_execute_config_update(ctx)
+    }
+
+    /// Withdraws the queued config update. Authority only.
+    pub fn cancel_config_update(ctx: Context<CancelConfigUpdate>) -> Result<()> {
+        handler_cancel_config_update(ctx)
+    }
+
+    /// Sets the wallet fees are paid to; the default key reverts to the authority.
+    pub fn update_treasury(ctx: Context<CoSignedAdminConfig>, treasury: Pubkey) -> Result<()> {
+        handler_update_treasury(ctx, treasury)
+    }
+
+    /// Updates any of the fee, score threshold, session duration and
+    /// interaction cap; `None` leaves a parameter unchanged.
+    pub fn update_config(
+        ctx: Context<AdminConfig>,
+        verification_fee_lamports: Option<u64>,
+        min_behavioral_score: Option<u8>,
+        max_session_duration: Option<i64>,
+        max_interactions_per_session: Option<u32>,
+    ) -> Result<()> {
+        handler_update_config(
+            ctx,
+            verification_fee_lamports,
+            min_behavioral_score,
+            max_session_duration,
+            max_interactions_per_session,
+        )
+    }
+
+    /// Sets the liveness interval and whether level-3 proofs need a challenge.
+    pub fn set_liveness_policy(
+        ctx: Context<AdminConfig>,
+        interval_seconds: i64,
+        challenge_required: bool,
+    ) -> Result<()> {
+        handler_set_liveness_policy(ctx, interval_seconds, challenge_required)
+    }
+
+    /// Refreshes the owner's liveness, restarting the decay interval.
+    pub fn prove_liveness(ctx: Context<ProveLiveness>, challenge: Option<LivenessChallenge>) -> Result<()> {
+        handler_prove_liveness(ctx, challenge)
+    }
+
+    /// Lowers the level of a record past its liveness interval by one step.
+    pub fn apply_liveness_decay(ctx: Context<ApplyLivenessDecay>) -> Result<()> {
+        handler_apply_liveness_decay(ctx)
+    }
+
+    /// Returns the human's liveness-decayed verification level. Meant for CPI.
+    pub fn verification_level(ctx: Context<GetVerificationLevel>) -> Result<u8> {
+        handler_verification_level(ctx)
+    }
+
+    /// Binds a unique handle to the owner's verified identity.
+    pub fn claim_handle(ctx: Context<ClaimHandle>, handle: String) -> Result<()> {
+        handler_claim_handle(ctx, handle)
+    }
+
+    /// Gives up the owner's handle.
+    pub fn release_handle(ctx: Context<ReleaseHandle>) -> Result<()> {
+        handler_release_handle(ctx)
+    }
+
+    /// Returns the wallet holding a handle. Meant for CPI.
+    pub fn resolve_handle(ctx: Context<ResolveHandle>, handle: String) -> Result<Pubkey> {
+        handler_resolve_handle(ctx, handle)
+    }
+
+    /// Sets the share of the score private interactions earn.
+    pub fn set_private_score_bps(ctx: Context<AdminConfig>, private_score_bps: u16) -> Result<()> {
+        handler_set_private_score_bps(ctx, private_score_bps)
+    }
+
+    /// Requires sessions to use registered, enabled topics.
+    pub fn set_enforce_registered_topics(ctx: Context<AdminConfig>, enforce: bool) -> Result<()> {
+        handler_set_enforce_registered_topics(ctx, enforce)
+    }
+
+    /// Sets the largest content size an interaction log may reference.
+    pub fn set_max_content_size(ctx: Context<AdminConfig>, max_content_size: u32) -> Result<()> {
+        handler_set_max_content_size(ctx, max_content_size)
+    }
+
+    /// Sets the reopen grace window and fee for expired sessions.
+    pub fn set_reopen_policy(
+        ctx: Context<AdminConfig>,
+        reopen_grace_period: i64,
+        reopen_fee_lamports: u64,
+    ) -> Result<()> {
+        handler_set_reopen_policy(ctx, reopen_grace_period, reopen_fee_lamports)
+    }
+
+    /// Sets the bonus settled for sessions that meet their goal.
+    pub fn set_goal_bonus(
+        ctx: Context<AdminConfig>,
+        goal_bonus_flat: u64,
+        goal_bonus_bps: u16,
+    ) -> Result<()> {
+        handler_set_goal_bonus(ctx, goal_bonus_flat, goal_bonus_bps)
+    }
+
+    /// Designates the curator allowed to publish session templates.
+    pub fn set_curator(ctx: Context<AdminConfig>, curator: Pubkey) -> Result<()> {
+        handler_set_curator(ctx, curator)
+    }
+
+    /// Designates the appeal arbiter and the appeal window.
+    pub fn set_arbiter(
+        ctx: Context<AdminConfig>,
+        arbiter: Pubkey,
+        appeal_window: i64,
+    ) -> Result<()> {
+        handler_set_arbiter(ctx, arbiter, appeal_window)
+    }
+
+    /// Pauses or resumes the registry. Authority or guardian.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        handler_set_paused(ctx, paused)
+    }
+
+    /// Pauses registrations, verifications, sessions or interactions
+    /// individually. Authority only.
+    pub fn set_pause_flags(ctx: Context<SetPauseFlags>, flags: u8) -> Result<()> {
+        handler_set_pause_flags(ctx, flags)
+    }
+
+    /// Schedules a maintenance window during which the registry acts paused.
+    pub fn schedule_maintenance(
+        ctx: Context<ScheduleMaintenance>,
+        start_at: i64,
+        end_at: i64,
+    ) -> Result<()> {
+        handler_schedule_maintenance(ctx, start_at, end_at)
+    }
+
+    /// Cancels the pending maintenance window. Authority only.
+    pub fn cancel_maintenance(ctx: Context<ScheduleMaintenance>) -> Result<()> {
+        handler_cancel_maintenance(ctx)
+    }
+
+    /// Replaces the tiered verification fee schedule; an empty schedule
+    /// restores the flat fee.
+    pub fn set_fee_schedule(ctx: Context<AdminConfig>, fee_schedule: FeeSchedule) -> Result<()> {
+        handler_set_fee_schedule(ctx, fee_schedule)
+    }
+
+    /// Sets the maximum per-update change of the flat fee and the cooldown
+    /// between changes.
+    pub fn set_fee_guardrails(
+        ctx: Context<CoSignedAdminConfig>,
+        max_fee_delta_bps: u16,
+        fee_update_cooldown_seconds: i64,
+    ) -> Result<()> {
+        handler_set_fee_guardrails(ctx, max_fee_delta_bps, fee_update_cooldown_seconds)
+    }
+
+    /// Sets the key that must co-sign high-impact admin instructions; the
+    /// default key disables the requirement.
+    pub fn set_co_authority(ctx: Context<CoSignedAdminConfig>, co_authority: Pubkey) -> Result<()> {
+        handler_set_co_authority(ctx, co_authority)
+    }
+
+    /// Designates the guardian key and whether it may resume a paused registry.
+    pub fn set_guardian(
+        ctx: Context<AdminConfig>,
+        guardian: Pubkey,
+        guardian_can_unpause: bool,
     ) -> Result<()> {
-        handler_record_interaction(ctx, content_hash, interaction_type, score, duration_seconds)
+        handler_set_guardian(ctx, guardian, guardian_can_unpause)
     }
 }
@@ -0,0 +1,309 @@
+//! Interaction scoring shared by the program and off-chain clients.
+//! Free of Anchor types so it builds unchanged for any target.
+
+/// Denominator of every score multiplier, expressed in basis points.
+pub const BPS_DENOMINATOR: u128 = 10_000;
+/// Seconds of interaction worth one bonus point.
+pub const DURATION_BONUS_PERIOD: u128 = 30;
+/// Duration beyond which no further bonus accrues.
+pub const DURATION_BONUS_CAP_SECONDS: u32 = 300;
+
+/// Number of interaction type multipliers the registry can configure, one
+/// per type value, leaving room for types not defined yet.
+pub const TYPE_MULTIPLIER_COUNT: usize = 8;
+
+/// Multipliers used while the registry's `type_multipliers` are all zero,
+/// in basis points of the base score:
+///   - Chat (0): 1x base
+///   - Quiz (1): 2x base
+///   - Exercise (2): 3x base
+///   - Review (3): 1.5x base
+///   - anything else: 1x base
+pub const DEFAULT_TYPE_MULTIPLIERS_BPS: [u16; TYPE_MULTIPLIER_COUNT] =
+    [10_000, 20_000, 30_000, 15_000, 10_000, 10_000, 10_000, 10_000];
+
+/// Multiplier for `interaction_type` in basis points: the configured one,
+/// or the default when `type_multipliers` is all zero. Types past the end
+/// of the table earn 1x.
+#[inline]
+pub fn type_multiplier_bps(type_multipliers: &[u16; TYPE_MULTIPLIER_COUNT], interaction_type: u8) -> u16 {
+    let table = if type_multipliers.iter().all(|&m| m == 0) {
+        &DEFAULT_TYPE_MULTIPLIERS_BPS
+    } else {
+        type_multipliers
+    };
+    table
+        .get(interaction_type as usize)
+        .copied()
+        .unwrap_or(BPS_DENOMINATOR as u16)
+}
+
+/// Computes a weighted score increment based on interaction quality metrics.
+///
+/// The score is weighted by the interaction type's multiplier from
+/// `type_multipliers`, in basis points (10 000 = 1x), falling back to
+/// `DEFAULT_TYPE_MULTIPLIERS_BPS` while none is configured. A u16 multiplier
+/// caps an increment at about 6.6x the base, so no configuration can bring a
+/// session score near u64 range.
+///
+/// Duration acts as a diminishing-returns bonus capped at 300 seconds, worth
+/// one point per 30 seconds.
+///
+/// Everything is accumulated exactly in u128 over a common denominator and
+/// divided once at the end, rounding half up. Compared to the previous
+/// truncating arithmetic this only differs when the fractional parts of the
+/// weighted score and the duration bonus add up to at least one half, in
+/// which case the result is one point higher (e.g. a review scoring 1 with
+/// 15 seconds now yields 2 instead of 1).
+///
+/// Returns `None` only on arithmetic overflow.
+#[inline]
+pub fn compute_score_increment(
+    score: u8,
+    interaction_type: u8,
+    duration_seconds: u32,
+    type_multipliers: &[u16; TYPE_MULTIPLIER_COUNT],
+) -> Option<u64> {
+    let type_multiplier_bps = type_multiplier_bps(type_multipliers, interaction_type) as u128;
+
+    let capped_duration = std::cmp::min(duration_seconds, DURATION_BONUS_CAP_SECONDS) as u128;
+
+    // Numerator and denominator are both scaled by BPS_DENOMINATOR * DURATION_BONUS_PERIOD
+    // so the weighted score and the duration bonus share one exact fraction.
+    let weighted = (score as u128)
+        .checked_mul(type_multiplier_bps)
+        .and_then(|v| v.checked_mul(DURATION_BONUS_PERIOD))?;
+    let bonus = capped_duration.checked_mul(BPS_DENOMINATOR)?;
+    let numerator = weighted.checked_add(bonus)?;
+    let denominator = BPS_DENOMINATOR * DURATION_BONUS_PERIOD;
+
+    let rounded = numerator.checked_add(denominator / 2)? / denominator;
+
+    u64::try_from(rounded).ok()
+}
+
+/// Multiplier a session's scoring profile applies to the interaction type it
+/// favours.
+pub const PROFILE_BOOST_BPS: u128 = 15_000;
+
+/// Applies a session's scoring profile to a score increment: the profile's
+/// favoured interaction type (chat, quiz and exercise for profiles 1 to 3)
+/// earns `PROFILE_BOOST_BPS`, rounding down; anything else is unchanged.
+///
+/// Returns `None` only on arithmetic overflow.
+#[inline]
+pub fn apply_scoring_profile(increment: u64, scoring_profile: u8, interaction_type: u8) -> Option<u64> {
+    let favoured = match scoring_profile {
+        1 => Some(0),
+        2 => Some(1),
+        3 => Some(2),
+        _ => None,
+    };
+    if favoured != Some(interaction_type) {
+        return Some(increment);
+    }
+    let boosted = (increment as u128).checked_mul(PROFILE_BOOST_BPS)? / BPS_DENOMINATOR;
+    u64::try_from(boosted).ok()
+}
+
+/// Applies a session's booster multiplier to a score increment, rounding
+/// down. A multiplier of 0 means no booster and leaves it unchanged.
+///
+/// Returns `None` only on arithmetic overflow.
+#[inline]
+pub fn apply_booster(increment: u64, multiplier_bps: u16) -> Option<u64> {
+    if multiplier_bps == 0 {
+        return Some(increment);
+    }
+    let boosted = (increment as u128).checked_mul(multiplier_bps as u128)? / BPS_DENOMINATOR;
+    u64::try_from(boosted).ok()
+}
+
+/// Applies the registry's `private_score_bps` to the score increment of a
+/// private interaction, whose content cannot be verified, rounding down. A
+/// share of 0 means private interactions earn nothing.
+///
+/// Returns `None` only on arithmetic overflow.
+#[inline]
+pub fn apply_private_share(increment: u64, private_score_bps: u16) -> Option<u64> {
+    let reduced = (increment as u128).checked_mul(private_score_bps as u128)? / BPS_DENOMINATOR;
+    u64::try_from(reduced).ok()
+}
+
+/// Number of sub-scores produced by the verifier pipeline: behavioral,
+/// social-graph and longevity, in that order.
+pub const SUB_SCORE_COUNT: usize = 3;
+
+/// Combines the verifier's sub-scores into the composite humanity score,
+/// weighting each by its basis points and rounding down. With weights that
+/// sum to 10 000 and sub-scores of at most 100, so is the result.
+#[inline]
+pub fn compute_composite_score(
+    sub_scores: &[u8; SUB_SCORE_COUNT],
+    weights_bps: &[u16; SUB_SCORE_COUNT],
+) -> u8 {
+    let weighted: u128 = sub_scores
+        .iter()
+        .zip(weights_bps)
+        .map(|(&score, &weight)| score as u128 * weight as u128)
+        .sum();
+    (weighted / BPS_DENOMINATOR).min(u8::MAX as u128) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAX_SCORE;
+
+    const DEFAULTS: [u16; TYPE_MULTIPLIER_COUNT] = [0; TYPE_MULTIPLIER_COUNT];
+
+    /// The scoring before the fixed-point rework: a truncating percentage
+    /// multiplier plus a truncated duration bonus, all saturating.
+    fn legacy_score_increment(score: u8, interaction_type: u8, duration_seconds: u32) -> u64 {
+        let type_multiplier: u64 = match interaction_type {
+            0 => 100,
+            1 => 200,
+            2 => 300,
+            3 => 150,
+            _ => 100,
+        };
+        let duration_bonus = std::cmp::min(duration_seconds, 300) as u64 / 30;
+        (score as u64)
+            .saturating_mul(type_multiplier)
+            .saturating_div(100)
+            .saturating_add(duration_bonus)
+    }
+
+    #[test]
+    fn scoring_known_answers() {
+        assert_eq!(compute_score_increment(80, 1, 120, &DEFAULTS), Some(164));
+        assert_eq!(compute_score_increment(100, 2, 300, &DEFAULTS), Some(310));
+        assert_eq!(compute_score_increment(55, 3, 10, &DEFAULTS), Some(83));
+        assert_eq!(apply_scoring_profile(100, 2, 1), Some(150));
+        assert_eq!(apply_scoring_profile(100, 2, 0), Some(100));
+        assert_eq!(apply_booster(101, 12_500), Some(126));
+        assert_eq!(apply_booster(101, 0), Some(101));
+        assert_eq!(apply_private_share(101, 5_000), Some(50));
+        assert_eq!(compute_composite_score(&[90, 60, 30], &[5_000, 3_000, 2_000]), 69);
+    }
+
+    #[test]
+    fn duration_only_adds_a_bonus_on_top_of_the_weighted_score() {
+        // Without a registry minimum, a zero-second interaction still earns
+        // the full type-weighted score.
+        assert_eq!(compute_score_increment(80, 1, 0, &DEFAULTS), Some(160));
+        assert_eq!(compute_score_increment(80, 1, 30, &DEFAULTS), Some(161));
+        assert_eq!(compute_score_increment(0, 1, 0, &DEFAULTS), Some(0));
+    }
+
+    #[test]
+    fn rework_matches_legacy_table() {
+        // (score, type, duration, legacy, reworked)
+        let cases: &[(u8, u8, u32, u64, u64)] = &[
+            (0, 0, 0, 0, 0),
+            (50, 0, 0, 50, 50),
+            (100, 2, 300, 310, 310),
+            (100, 3, 600, 160, 160),
+            (40, 7, 60, 42, 42),
+            // Half points from the review multiplier now round up.
+            (1, 3, 0, 1, 2),
+            (3, 3, 0, 4, 5),
+            // Fractional duration bonus now counts towards rounding.
+            (1, 3, 15, 1, 2),
+            (10, 0, 29, 10, 11),
+            (77, 1, 45, 155, 156),
+            (99, 3, 59, 149, 150),
+        ];
+        for &(score, kind, duration, legacy, reworked) in cases {
+            assert_eq!(legacy_score_increment(score, kind, duration), legacy, "legacy {score}/{kind}/{duration}");
+            assert_eq!(
+                compute_score_increment(score, kind, duration, &DEFAULTS),
+                Some(reworked),
+                "reworked {score}/{kind}/{duration}"
+            );
+        }
+    }
+
+    #[test]
+    fn rework_differs_from_legacy_by_rounding_only() {
+        for score in 0..=100u8 {
+            for kind in 0..=4u8 {
+                for duration in 0..=330u32 {
+                    let legacy = legacy_score_increment(score, kind, duration);
+                    let reworked = compute_score_increment(score, kind, duration, &DEFAULTS).unwrap();
+                    assert!(
+                        reworked == legacy || reworked == legacy + 1,
+                        "{score}/{kind}/{duration}: legacy {legacy}, reworked {reworked}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// The largest increment any input can produce: a u8::MAX score at the
+    /// largest multiplier with the full duration bonus.
+    const INCREMENT_CEILING: u64 = 1_681;
+
+    #[test]
+    fn extreme_inputs_stay_below_the_ceiling() {
+        let multipliers = [0, 1, 9_999, 10_000, 30_000, u16::MAX];
+        for multiplier in multipliers {
+            let table = [multiplier; TYPE_MULTIPLIER_COUNT];
+            for score in [0, 1, MAX_SCORE, u8::MAX] {
+                for duration in [0, 1, DURATION_BONUS_CAP_SECONDS, DURATION_BONUS_CAP_SECONDS + 1, u32::MAX] {
+                    for kind in 0..=TYPE_MULTIPLIER_COUNT as u8 {
+                        let increment = compute_score_increment(score, kind, duration, &table)
+                            .unwrap_or_else(|| panic!("{multiplier}/{score}/{duration}/{kind} overflowed"));
+                        assert!(increment <= INCREMENT_CEILING, "{multiplier}/{score}/{duration}/{kind}: {increment}");
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            compute_score_increment(u8::MAX, 0, u32::MAX, &[u16::MAX; TYPE_MULTIPLIER_COUNT]),
+            Some(INCREMENT_CEILING)
+        );
+    }
+
+    #[test]
+    fn no_multiplier_configuration_overflows_a_session_score() {
+        // Worst case per interaction: the largest increment, boosted by the
+        // favoured profile and the largest booster.
+        let worst = apply_scoring_profile(INCREMENT_CEILING, 1, 0)
+            .and_then(|increment| apply_booster(increment, u16::MAX))
+            .expect("worst increment fits");
+        assert!(worst.checked_mul(u32::MAX as u64).is_some());
+
+        // Sweep single-type configurations, leaving the rest at 1x, over a
+        // session of max_interactions_per_session = u32::MAX interactions.
+        for multiplier in (0..=u16::MAX).step_by(257).chain([u16::MAX]) {
+            let mut table = [10_000; TYPE_MULTIPLIER_COUNT];
+            table[0] = multiplier;
+            let increment = compute_score_increment(u8::MAX, 0, u32::MAX, &table)
+                .and_then(|increment| apply_scoring_profile(increment, 1, 0))
+                .and_then(|increment| apply_booster(increment, u16::MAX))
+                .expect("increment fits");
+            assert!(increment <= worst, "{multiplier}: {increment} above {worst}");
+            assert!(increment.checked_mul(u32::MAX as u64).is_some(), "{multiplier} overflowed");
+        }
+    }
+
+    #[test]
+    fn composite_scores_weight_each_sub_score_and_round_down() {
+        let even = [3_333, 3_333, 3_334];
+        assert_eq!(compute_composite_score(&[MAX_SCORE; SUB_SCORE_COUNT], &even), MAX_SCORE);
+        assert_eq!(compute_composite_score(&[0; SUB_SCORE_COUNT], &even), 0);
+        // 99 * 0.3333 + 1 * 0.3333 = 33.33
+        assert_eq!(compute_composite_score(&[99, 1, 0], &even), 33);
+        // 0.9999 of a point is not a point.
+        assert_eq!(compute_composite_score(&[1, 0, 0], &[9_999, 1, 0]), 0);
+        for (index, weights) in [[10_000, 0, 0], [0, 10_000, 0], [0, 0, 10_000]].iter().enumerate() {
+            let mut sub_scores = [20; SUB_SCORE_COUNT];
+            sub_scores[index] = 85;
+            assert_eq!(compute_composite_score(&sub_scores, weights), 85);
+        }
+        for weights in [[5_000, 3_000, 2_000], [1, 1, 9_998], [0, 5_000, 5_000]] {
+            assert_eq!(compute_composite_score(&[MAX_SCORE; SUB_SCORE_COUNT], &weights), MAX_SCORE);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Checks of signatures verified by the ed25519 program earlier in the same
+//! transaction, for messages signed off-chain.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+/// Whether an ed25519 program instruction before the current one checked a
+/// signature of `message` by `signer`, and that it is `signature` when one
+/// is given. Only signatures whose parts all lie in that instruction's own
+/// data are considered.
+pub fn has_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: Option<&[u8; 64]>,
+) -> Result<bool> {
+    let current = load_current_index_checked(instructions)?;
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index as usize, instructions)?;
+        if ix.program_id == ed25519_program::ID
+            && ed25519_data_matches(&ix.data, signer, message, signature)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Scans the signature offsets of an ed25519 instruction's data for the
+/// given key and message, and signature if any.
+fn ed25519_data_matches(
+    data: &[u8],
+    signer: &Pubkey,
+    message: &[u8],
+    signature: Option<&[u8; 64]>,
+) -> bool {
+    const HEADER_LEN: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const THIS_INSTRUCTION: u16 = u16::MAX;
+
+    let Some(&count) = data.first() else {
+        return false;
+    };
+    (0..count as usize).any(|i| {
+        let start = HEADER_LEN + i * OFFSETS_LEN;
+        let Some(offsets) = data.get(start..start + OFFSETS_LEN) else {
+            return false;
+        };
+        let field = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+        let bytes = |offset: u16, len: usize| data.get(offset as usize..offset as usize + len);
+
+        field(2) == THIS_INSTRUCTION
+            && field(6) == THIS_INSTRUCTION
+            && field(12) == THIS_INSTRUCTION
+            && field(10) as usize == message.len()
+            && signature.map_or(true, |sig| bytes(field(0), 64) == Some(&sig[..]))
+            && bytes(field(4), 32) == Some(signer.as_ref())
+            && bytes(field(8), message.len()) == Some(message)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+    use crate::harness::ed25519_instruction;
+
+    #[test]
+    fn only_the_signed_key_message_and_signature_match() {
+        let (signer, other) = (Keypair::new(), Keypair::new());
+        let message = b"signed message";
+        let (ix, signature) = ed25519_instruction(&signer, message);
+        let key = signer.pubkey();
+
+        assert!(ed25519_data_matches(&ix.data, &key, message, None));
+        assert!(ed25519_data_matches(&ix.data, &key, message, Some(&signature)));
+        assert!(!ed25519_data_matches(&ix.data, &other.pubkey(), message, None));
+        assert!(!ed25519_data_matches(&ix.data, &key, b"signed massage", None));
+        assert!(!ed25519_data_matches(&ix.data, &key, b"signed", None));
+        assert!(!ed25519_data_matches(&ix.data, &key, message, Some(&[0u8; 64])));
+    }
+
+    #[test]
+    fn parts_in_other_instructions_or_past_the_data_do_not_match() {
+        let signer = Keypair::new();
+        let message = b"signed message";
+        let (ix, _) = ed25519_instruction(&signer, message);
+        let key = signer.pubkey();
+
+        // Each of the signature, key and message instruction indexes.
+        for at in [4, 8, 14] {
+            let mut data = ix.data.clone();
+            data[at..at + 2].copy_from_slice(&0u16.to_le_bytes());
+            assert!(!ed25519_data_matches(&data, &key, message, None), "index at {at}");
+        }
+        assert!(!ed25519_data_matches(&ix.data[..ix.data.len() - 1], &key, message, None));
+        assert!(!ed25519_data_matches(&ix.data[..10], &key, message, None));
+        assert!(!ed25519_data_matches(&[], &key, message, None));
+        let mut none = ix.data.clone();
+        none[0] = 0;
+        assert!(!ed25519_data_matches(&none, &key, message, None));
+    }
+}
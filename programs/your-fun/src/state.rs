@@ -1,19 +1,183 @@
 use anchor_lang::prelude::*;
+use crate::error::YourFunError;
+use crate::scoring::{SUB_SCORE_COUNT, TYPE_MULTIPLIER_COUNT};
+use crate::constants::{MAX_BPS, MAX_SCORE};
+
+/// Sets or clears `mask` within a packed flags field.
+fn set_flag(flags: &mut u16, mask: u16, on: bool) {
+    if on {
+        *flags |= mask;
+    } else {
+        *flags &= !mask;
+    }
+}
+
+/// Kind of a recorded interaction, stored as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum InteractionType {
+    Chat = 0,
+    Quiz = 1,
+    Exercise = 2,
+    Review = 3,
+}
+
+impl TryFrom<u8> for InteractionType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Chat),
+            1 => Ok(Self::Quiz),
+            2 => Ok(Self::Exercise),
+            3 => Ok(Self::Review),
+            _ => err!(YourFunError::InvalidInteractionType),
+        }
+    }
+}
+
+/// Interaction weighting applied to a session, stored as its `u8`
+/// discriminant. Each non-standard profile boosts one interaction type.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum ScoringProfile {
+    Standard = 0,
+    ChatHeavy = 1,
+    QuizHeavy = 2,
+    ExerciseHeavy = 3,
+}
+
+impl TryFrom<u8> for ScoringProfile {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Standard),
+            1 => Ok(Self::ChatHeavy),
+            2 => Ok(Self::QuizHeavy),
+            3 => Ok(Self::ExerciseHeavy),
+            _ => err!(YourFunError::InvalidScoringProfile),
+        }
+    }
+}
+
+/// What a session goal measures, stored as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum GoalType {
+    None = 0,
+    /// At least `goal_target` interactions recorded.
+    Interactions = 1,
+    /// A session score of at least `goal_target`.
+    SessionScore = 2,
+}
+
+impl TryFrom<u8> for GoalType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Interactions),
+            2 => Ok(Self::SessionScore),
+            _ => err!(YourFunError::InvalidGoal),
+        }
+    }
+}
+
+/// Assurance level granted by a verifier, stored as its `u8` discriminant.
+/// A stored 0 means the record has not been verified yet.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum VerificationLevel {
+    Basic = 1,
+    Enhanced = 2,
+    /// Backed by a document attestation from an approved issuer.
+    Full = 3,
+}
+
+impl TryFrom<u8> for VerificationLevel {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            1 => Ok(Self::Basic),
+            2 => Ok(Self::Enhanced),
+            3 => Ok(Self::Full),
+            _ => err!(YourFunError::InvalidVerificationLevel),
+        }
+    }
+}
+
+/// Where a human stands in the verification lifecycle, derived from the
+/// record and the registry's validity settings. Stored as its `u8`
+/// discriminant in `HumanRecord::verification_status`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum VerificationStatus {
+    /// Never verified, or revoked.
+    Inactive = 0,
+    /// Verified and within the validity period.
+    Active = 1,
+    /// Past the validity period but within the grace period: existing
+    /// sessions keep working and renewal is due.
+    GracePeriod = 2,
+    /// Past the grace period: no new sessions until re-verified.
+    Lapsed = 3,
+    /// Approved, but inside the contest window that precedes activation.
+    Pending = 4,
+}
+
+/// Progress of a revocation appeal, stored as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum AppealStatus {
+    Pending = 0,
+    Approved = 1,
+    Denied = 2,
+}
+
+/// Why a session was closed, stored as its `u8` discriminant.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum CloseReason {
+    None = 0,
+    Completed = 1,
+    Expired = 2,
+    Cancelled = 3,
+    ForceClosed = 4,
+}
+
+impl TryFrom<u8> for CloseReason {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Completed),
+            2 => Ok(Self::Expired),
+            3 => Ok(Self::Cancelled),
+            4 => Ok(Self::ForceClosed),
+            _ => err!(YourFunError::InvalidCloseReason),
+        }
+    }
+}
 
 /// Global platform configuration and statistics.
 /// Seeds: ["registry"]
 #[account]
-#[derive(Default)]
+#[derive(InitSpace)]
 pub struct PlatformRegistry {
     /// The authority that can pause/unpause and update fees.
     pub authority: Pubkey,
     /// Base fee in lamports for human verification registration.
     pub verification_fee_lamports: u64,
-    /// Total number of verified humans on the platform.
+    /// Number of humans currently verified: added at verification and import,
+    /// removed again at revocation, record closure and merge.
     pub total_verified_humans: u64,
-    /// Total number of AI companion sessions created.
+    /// Total number of AI companion sessions created, as of the last stats aggregation.
     pub total_sessions_created: u64,
-    /// Total interactions recorded across all sessions.
+    /// Total interactions recorded across all sessions, as of the last stats aggregation.
     pub total_interactions: u64,
     /// Whether new registrations are paused.
     pub is_paused: bool,
@@ -25,18 +189,739 @@ pub struct PlatformRegistry {
     pub max_interactions_per_session: u32,
     /// Bump seed for PDA derivation.
     pub bump: u8,
+    /// Seconds a wallet must wait after closing its HumanRecord before registering again.
+    pub reregistration_cooldown: i64,
+    /// Maximum raw fingerprint length in bytes (0 = `DEFAULT_MAX_FINGERPRINT_LEN`).
+    pub max_fingerprint_len: u16,
+    /// Number of verifications that reactivated a previously verified record.
+    pub reverifications: u64,
+    /// Optional moderator that may force-close sessions alongside the authority.
+    pub moderator: Pubkey,
+    /// Share of a force-closed session's score still settled to the human, in basis points.
+    pub force_close_settle_bps: u16,
+    /// Interactions scoring below this are recorded without an InteractionLog account.
+    pub log_threshold_score: u8,
+    /// Set once by `freeze_config`; configuration can never change afterwards.
+    pub config_frozen: bool,
+    /// Incremented once by every instruction that writes the registry, so
+    /// pollers can detect changes by reading this field alone. Its offset is
+    /// stable; see `constants::REGISTRY_STATE_VERSION_OFFSET`.
+    pub state_version: u64,
+    /// Layout version of this registry. Registries created before versioning
+    /// read 0 here. Fields after `_reserved` are appended by later versions,
+    /// so an older registry is a byte prefix of the current layout and
+    /// `migrate_registry` only has to grow it and fill them in.
+    pub version: u8,
     /// Reserved space for future upgrades.
-    pub _reserved: [u8; 64],
+    pub _reserved: [u8; 1],
+    /// Emergency key that may pause the registry but change nothing else
+    /// (`Pubkey::default()` = none).
+    pub guardian: Pubkey,
+    /// Whether the guardian may also lift a pause.
+    pub guardian_can_unpause: bool,
+    /// Lowest verification fee a fee update may set.
+    pub min_fee_lamports: u64,
+    /// Highest verification fee a fee update may set.
+    pub max_fee_lamports: u64,
+    /// Number of AdminAction entries written; the index of the next one.
+    pub admin_action_count: u64,
+    /// When set, `register_human` requires an unconsumed InviteEntry.
+    pub permissioned_registration: bool,
+    /// When set, only region codes marked in `allowed_regions` may be
+    /// verified; otherwise every region is allowed.
+    pub restrict_regions: bool,
+    /// Bitmap of allowed ISO 3166 numeric region codes, bit `code` set when
+    /// allowed. Consulted only while `restrict_regions` is set.
+    pub allowed_regions: [u8; 125],
+    /// Start of the scheduled maintenance window (0 = none scheduled).
+    pub maintenance_start_at: i64,
+    /// End of the scheduled maintenance window, exclusive.
+    pub maintenance_end_at: i64,
+    /// Seconds a verification stays Active (0 = verifications never lapse).
+    pub verification_validity: i64,
+    /// Seconds after the validity period during which a verification is in
+    /// its GracePeriod before it lapses.
+    pub verification_grace_period: i64,
+    /// Program id of the previous deployment whose HumanRecords may be
+    /// imported (`Pubkey::default()` = none).
+    pub legacy_program_id: Pubkey,
+    /// Imports are accepted until this Unix timestamp, exclusive.
+    pub legacy_import_ends_at: i64,
+    /// Key that resolves revocation appeals; never the authority
+    /// (`Pubkey::default()` = appeals disabled).
+    pub arbiter: Pubkey,
+    /// Seconds after a revocation during which its appeal may be filed.
+    pub appeal_window: i64,
+    /// Basis-point weights of the behavioral, social-graph and longevity
+    /// sub-scores in the composite humanity score; they sum to 10 000.
+    pub sub_score_weights_bps: [u16; SUB_SCORE_COUNT],
+    /// Seconds after a verification during which it can be contested before
+    /// it takes effect (0 = effective immediately).
+    pub contest_window: i64,
+    /// Key that may publish session templates alongside the authority
+    /// (`Pubkey::default()` = none).
+    pub curator: Pubkey,
+    /// Flat bonus added to the settled score of a session that met its goal.
+    pub goal_bonus_flat: u64,
+    /// Bonus, in basis points of the session score, added on top of
+    /// `goal_bonus_flat` when a session met its goal.
+    pub goal_bonus_bps: u16,
+    /// Seconds after expiry during which an open session may still be
+    /// reopened (0 = reopening disabled).
+    pub reopen_grace_period: i64,
+    /// Lamports charged to the owner for each reopen.
+    pub reopen_fee_lamports: u64,
+    /// Largest content size in bytes an InteractionLog may reference
+    /// (0 = `DEFAULT_MAX_CONTENT_SIZE`).
+    pub max_content_size: u32,
+    /// Companion backend key that must co-sign every recorded interaction
+    /// (`Pubkey::default()` = co-signing off).
+    pub companion_oracle: Pubkey,
+    /// Seconds after `close_session` before `finalize_session` may settle
+    /// the score, during which it can be contested (0 = settled at close).
+    pub settlement_window: i64,
+    /// Booster mints accepted by `create_session`; unused entries have the
+    /// default mint.
+    pub boosters: [BoosterMint; 8],
+    /// Key that may approve scholarships alongside the authority
+    /// (`Pubkey::default()` = none).
+    pub program_officer: Pubkey,
+    /// Share of every fee, in basis points, routed to the InsuranceFund
+    /// instead of the fee receiver.
+    pub insurance_fee_bps: u16,
+    /// When set, `create_session` and `update_topic` only accept topics
+    /// with a registered, enabled Topic account.
+    pub enforce_registered_topics: bool,
+    /// Share of the score increment, in basis points, that a private
+    /// interaction earns (0 = nothing).
+    pub private_score_bps: u16,
+    /// Most humans that may be verified at once (0 = uncapped). Approvals
+    /// beyond it are waitlisted by `verify_human`.
+    pub max_verified_humans: u64,
+    /// Number of WaitlistEntry accounts written; the position of the next.
+    pub waitlist_count: u64,
+    /// Position of the next WaitlistEntry `admit_from_waitlist` processes.
+    pub waitlist_next: u64,
+    /// Seconds a verified human may go without proving liveness before
+    /// `apply_liveness_decay` lowers their level (0 = never decays).
+    pub liveness_interval_seconds: i64,
+    /// When set, level-3 humans prove liveness with a fresh challenge
+    /// response co-signed by a verifier.
+    pub liveness_challenge_required: bool,
+    /// Wallet that receives fees (`Pubkey::default()` = the authority).
+    pub treasury: Pubkey,
+    /// Operations paused independently of `is_paused`; see the `PAUSE_*`
+    /// bits. `is_paused` still pauses all of them.
+    pub pause_flags: u8,
+    /// Seconds a queued config update waits before it can be executed
+    /// (0 = no timelock). While set, `update_config` and
+    /// `set_verification_fee` are disabled.
+    pub config_timelock_seconds: i64,
+    /// Second key that must co-sign high-impact admin instructions
+    /// (`Pubkey::default()` = not required).
+    pub co_authority: Pubkey,
+    /// Incremented once by every administrative instruction and by
+    /// `execute_config_update`; reported in `ConfigUpdated`.
+    pub config_version: u32,
+    /// Verification fee by number of verified humans; while empty the flat
+    /// `verification_fee_lamports` applies.
+    pub fee_schedule: FeeSchedule,
+    /// Largest change of the flat fee a single update may make, in basis
+    /// points of the current fee.
+    pub max_fee_delta_bps: u16,
+    /// Seconds that must pass between flat fee changes (0 = no cooldown).
+    pub fee_update_cooldown_seconds: i64,
+    /// Unix timestamp of the last flat fee change.
+    pub last_fee_update_at: i64,
+    /// Sessions currently open, as of the last stats aggregation.
+    pub active_sessions: u64,
+    /// Reserved. Held the verifications pending aggregation until layout
+    /// version 40, when EpochStats began counting them as they happen.
+    pub _reserved_pending: [u8; 8],
+    /// Most sessions a human may have open at once (0 = unlimited).
+    pub max_active_sessions: u8,
+    /// Seconds that must pass between a session's interactions (0 = no
+    /// minimum). A session's first interaction is exempt.
+    pub min_interaction_gap_seconds: u16,
+    /// Shortest `duration_seconds` an interaction may report (0 = no
+    /// minimum).
+    pub min_interaction_duration_seconds: u16,
+    /// Score multiplier per interaction type in basis points (10 000 = 1x).
+    /// All zero means the built-in `DEFAULT_TYPE_MULTIPLIERS_BPS`.
+    pub type_multipliers: [u16; TYPE_MULTIPLIER_COUNT],
+    /// Highest personality sessions may be opened with (255 = any).
+    pub max_personality_id: u8,
+    /// Share of the verification fee, in basis points, held on the human
+    /// record at registration and paid to the verifier who approves it.
+    pub verifier_fee_share_bps: u16,
+    /// Humans currently verified: counted at verification and removed on
+    /// revocation, on closure or merge of the record, and by
+    /// `expire_verification` once the verification has lapsed.
+    pub active_humans: u64,
 }
 
 impl PlatformRegistry {
     pub const SEED: &'static [u8] = b"registry";
-    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 4 + 1 + 64;
+    #[deprecated(note = "use 8 + INIT_SPACE")]
+    pub const SIZE: usize = 8 + Self::INIT_SPACE;
+
+    /// `pause_flags` bit pausing registrations.
+    pub const PAUSE_REGISTRATIONS: u8 = 1 << 0;
+    /// `pause_flags` bit pausing verifications.
+    pub const PAUSE_VERIFICATIONS: u8 = 1 << 1;
+    /// `pause_flags` bit pausing session creation, extension and reopening.
+    pub const PAUSE_SESSIONS: u8 = 1 << 2;
+    /// `pause_flags` bit pausing interactions, bundled ones included.
+    pub const PAUSE_INTERACTIONS: u8 = 1 << 3;
+    /// Every defined `pause_flags` bit.
+    pub const PAUSE_ALL: u8 = (1 << 4) - 1;
+
+    /// `ConfigUpdated::field_mask` bit: verification fee, its bounds and the
+    /// insurance share.
+    pub const CONFIG_FEES: u32 = 1 << 0;
+    /// `field_mask` bit: fee treasury.
+    pub const CONFIG_TREASURY: u32 = 1 << 1;
+    /// `field_mask` bit: `is_paused`, `pause_flags` and the maintenance window.
+    pub const CONFIG_PAUSE: u32 = 1 << 2;
+    /// `field_mask` bit: moderator, guardian, arbiter, curator, companion
+    /// oracle, program officer and co-authority.
+    pub const CONFIG_ROLES: u32 = 1 << 3;
+    /// `field_mask` bit: registration cooldown, fingerprint length,
+    /// permissioned registration, regions and legacy import.
+    pub const CONFIG_REGISTRATION: u32 = 1 << 4;
+    /// `field_mask` bit: score threshold, validity, contest window, liveness
+    /// policy and the verification cap.
+    pub const CONFIG_VERIFICATION: u32 = 1 << 5;
+    /// `field_mask` bit: session duration and interaction limits, reopen
+    /// policy, settlement window, force-close share, content size and topic
+    /// enforcement.
+    pub const CONFIG_SESSIONS: u32 = 1 << 6;
+    /// `field_mask` bit: log threshold, sub-score weights, goal bonus,
+    /// boosters and the private score share.
+    pub const CONFIG_SCORING: u32 = 1 << 7;
+    /// `field_mask` bit: `config_frozen`.
+    pub const CONFIG_FREEZE: u32 = 1 << 8;
+    /// `field_mask` bit: `config_timelock_seconds`.
+    pub const CONFIG_TIMELOCK: u32 = 1 << 9;
+    /// Every defined `field_mask` bit.
+    pub const CONFIG_ALL: u32 = (1 << 10) - 1;
+
+    /// Layout version written by this program build.
+    pub const CURRENT_VERSION: u8 = 40;
+
+    /// Weights under which the composite equals the behavioral sub-score.
+    pub const DEFAULT_SUB_SCORE_WEIGHTS_BPS: [u16; SUB_SCORE_COUNT] = [10_000, 0, 0];
+
+    /// `max_fee_delta_bps` of new and migrated registries: a fee update may
+    /// move the fee by at most half.
+    pub const DEFAULT_MAX_FEE_DELTA_BPS: u16 = 5_000;
+
+    /// Checks fee bounds given to `initialize`, and the initial fee against
+    /// them.
+    pub fn validate_fee_bounds(
+        min_fee_lamports: u64,
+        max_fee_lamports: u64,
+        verification_fee_lamports: u64,
+    ) -> Result<()> {
+        require!(min_fee_lamports <= max_fee_lamports, YourFunError::InvalidFeeBounds);
+        require!(
+            (min_fee_lamports..=max_fee_lamports).contains(&verification_fee_lamports),
+            YourFunError::FeeOutOfBounds
+        );
+        Ok(())
+    }
+
+    /// Checks a new verification fee against the absolute bounds, the
+    /// per-update delta cap and the cooldown since the last change. A zero
+    /// fee can be raised to anything within the bounds, since no relative
+    /// step exists from zero.
+    pub fn validate_fee_update(&self, new_fee: u64, now: i64) -> Result<()> {
+        require!(
+            new_fee >= self.min_fee_lamports && new_fee <= self.max_fee_lamports,
+            YourFunError::FeeOutOfBounds
+        );
+        require!(
+            now.saturating_sub(self.last_fee_update_at) >= self.fee_update_cooldown_seconds,
+            YourFunError::FeeUpdateTooSoon
+        );
+
+        let current = self.verification_fee_lamports;
+        if current > 0 {
+            let max_delta = (current as u128 * self.max_fee_delta_bps as u128) / MAX_BPS as u128;
+            require!(
+                (new_fee.abs_diff(current) as u128) <= max_delta,
+                YourFunError::FeeDeltaTooLarge
+            );
+        }
+        Ok(())
+    }
+
+    /// Fee a registration pays right now: the fee schedule's tier for
+    /// `total_verified_humans`, or the flat fee without a schedule.
+    pub fn effective_verification_fee(&self) -> u64 {
+        self.fee_schedule
+            .fee_for(self.total_verified_humans)
+            .unwrap_or(self.verification_fee_lamports)
+    }
+
+    /// Checks that a fee schedule's tiers start at 0, ascend strictly and
+    /// charge fees within the registry's fee bounds. An empty schedule is
+    /// always valid.
+    pub fn validate_fee_schedule(&self, schedule: &FeeSchedule) -> Result<()> {
+        require!(
+            schedule.tier_count as usize <= FeeSchedule::MAX_TIERS,
+            YourFunError::InvalidFeeSchedule
+        );
+        let tiers = schedule.tiers();
+        if let Some(first) = tiers.first() {
+            require!(first.threshold == 0, YourFunError::InvalidFeeSchedule);
+        }
+        require!(
+            tiers.windows(2).all(|w| w[0].threshold < w[1].threshold),
+            YourFunError::InvalidFeeSchedule
+        );
+        require!(
+            tiers.iter().all(|t| {
+                t.fee_lamports >= self.min_fee_lamports && t.fee_lamports <= self.max_fee_lamports
+            }),
+            YourFunError::FeeOutOfBounds
+        );
+        Ok(())
+    }
+
+    /// Wallet fees are paid to: the treasury, or the authority until one is
+    /// set.
+    pub fn fee_wallet(&self) -> Pubkey {
+        if self.treasury == Pubkey::default() {
+            self.authority
+        } else {
+            self.treasury
+        }
+    }
+
+    /// Checks the score threshold, session duration and interaction cap set
+    /// by `initialize` and `update_config`.
+    pub fn validate_core_config(
+        min_behavioral_score: u8,
+        max_session_duration: i64,
+        max_interactions_per_session: u32,
+    ) -> Result<()> {
+        require!(
+            min_behavioral_score <= MAX_SCORE,
+            YourFunError::InvalidScoreConfig
+        );
+        require!(
+            max_session_duration > 0,
+            YourFunError::SessionDurationExceeded
+        );
+        require!(
+            max_interactions_per_session > 0,
+            YourFunError::InvalidConfigValue
+        );
+        Ok(())
+    }
+
+    /// Validates `update` against the current values and applies it as of
+    /// `now`, leaving fields passed as `None` unchanged. Shared by
+    /// `update_config` and `execute_config_update`.
+    pub fn apply_config_update(&mut self, update: &ConfigUpdate, now: i64) -> Result<()> {
+        if let Some(fee) = update.verification_fee_lamports {
+            self.validate_fee_update(fee, now)?;
+        }
+        let min_behavioral_score = update.min_behavioral_score.unwrap_or(self.min_behavioral_score);
+        let max_session_duration = update.max_session_duration.unwrap_or(self.max_session_duration);
+        let max_interactions_per_session = update
+            .max_interactions_per_session
+            .unwrap_or(self.max_interactions_per_session);
+        Self::validate_core_config(
+            min_behavioral_score,
+            max_session_duration,
+            max_interactions_per_session,
+        )?;
+        let config_timelock_seconds = update
+            .config_timelock_seconds
+            .unwrap_or(self.config_timelock_seconds);
+        require!(config_timelock_seconds >= 0, YourFunError::InvalidConfigValue);
+        if let Some(fee_schedule) = &update.fee_schedule {
+            self.validate_fee_schedule(fee_schedule)?;
+        }
+
+        if let Some(fee) = update.verification_fee_lamports {
+            self.verification_fee_lamports = fee;
+            self.last_fee_update_at = now;
+        }
+        self.min_behavioral_score = min_behavioral_score;
+        self.max_session_duration = max_session_duration;
+        self.max_interactions_per_session = max_interactions_per_session;
+        self.config_timelock_seconds = config_timelock_seconds;
+        if let Some(fee_schedule) = update.fee_schedule {
+            self.fee_schedule = fee_schedule;
+        }
+        Ok(())
+    }
+
+    /// Fails unless the registry is at `CURRENT_VERSION`, i.e. until
+    /// `migrate_registry` has run after an upgrade.
+    pub fn require_current_version(&self) -> Result<()> {
+        require!(
+            self.version == Self::CURRENT_VERSION,
+            YourFunError::AccountVersionMismatch
+        );
+        Ok(())
+    }
+
+    /// Fails while the registry is paused, inside a maintenance window or
+    /// not yet migrated to the current layout. Used by every pause-sensitive
+    /// instruction.
+    pub fn require_operational(&self, now: i64) -> Result<()> {
+        self.require_current_version()?;
+        require!(!self.is_paused, YourFunError::RegistryPaused);
+        require!(!self.in_maintenance(now), YourFunError::MaintenanceWindow);
+        Ok(())
+    }
+
+    /// Like `require_operational`, but also fails while the operation's
+    /// `PAUSE_*` bit is set.
+    pub fn require_unpaused(&self, flag: u8, now: i64) -> Result<()> {
+        self.require_operational(now)?;
+        require!(self.pause_flags & flag == 0, YourFunError::RegistryPaused);
+        Ok(())
+    }
+
+    pub fn in_maintenance(&self, now: i64) -> bool {
+        self.maintenance_start_at <= now && now < self.maintenance_end_at
+    }
+
+    /// Whether a maintenance window is scheduled and not yet over.
+    pub fn has_pending_maintenance(&self, now: i64) -> bool {
+        now < self.maintenance_end_at
+    }
+
+    /// Whether `import_legacy_record` is currently accepted.
+    pub fn legacy_import_open(&self, now: i64) -> bool {
+        self.legacy_program_id != Pubkey::default() && now < self.legacy_import_ends_at
+    }
+
+    /// Whether humans from `region_code` may currently be verified.
+    pub fn is_region_allowed(&self, region_code: u16) -> bool {
+        if !self.restrict_regions {
+            return true;
+        }
+        let code = region_code as usize;
+        self.allowed_regions
+            .get(code / 8)
+            .is_some_and(|byte| byte & (1 << (code % 8)) != 0)
+    }
+
+    /// Callers must have checked `region_code <= RegionStats::MAX_REGION_CODE`.
+    pub fn set_region_allowed(&mut self, region_code: u16, allowed: bool) {
+        let code = region_code as usize;
+        let bit = 1u8 << (code % 8);
+        if allowed {
+            self.allowed_regions[code / 8] |= bit;
+        } else {
+            self.allowed_regions[code / 8] &= !bit;
+        }
+    }
+
+    /// Whether `key` is the configured guardian.
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        self.guardian != Pubkey::default() && *key == self.guardian
+    }
+
+    /// Checks that `co_signer` is the configured co-authority. Always passes
+    /// while no co-authority is set.
+    pub fn require_co_authority(&self, co_signer: Option<Pubkey>) -> Result<()> {
+        if self.co_authority == Pubkey::default() {
+            return Ok(());
+        }
+        let co_signer = co_signer.ok_or(YourFunError::CoAuthorityRequired)?;
+        require_keys_eq!(co_signer, self.co_authority, YourFunError::CoAuthorityMismatch);
+        Ok(())
+    }
+
+    /// Whether `max_verified_humans` humans are already verified.
+    pub fn verification_cap_reached(&self) -> bool {
+        self.max_verified_humans != 0 && self.total_verified_humans >= self.max_verified_humans
+    }
+
+    /// Whether a new verification must be waitlisted: the cap is reached,
+    /// or earlier approvals are still waiting to be admitted.
+    pub fn must_waitlist(&self) -> bool {
+        self.waitlist_next < self.waitlist_count || self.verification_cap_reached()
+    }
+
+    /// Counts the record in `active_humans`, unless it already is.
+    pub fn count_active_human(&mut self, human_record: &mut HumanRecord) -> Result<()> {
+        if !human_record.is_active_counted() {
+            self.active_humans = self
+                .active_humans
+                .checked_add(1)
+                .ok_or(YourFunError::NumericalOverflow)?;
+            human_record.set_active_counted(true);
+        }
+        Ok(())
+    }
+
+    /// Removes the record from `active_humans` if it is counted there. A
+    /// count already at zero stays there, with a logged warning.
+    pub fn uncount_active_human(&mut self, human_record: &mut HumanRecord) {
+        if !human_record.is_active_counted() {
+            return;
+        }
+        if self.active_humans == 0 {
+            msg!("Warning: active humans already 0 when removing {}", human_record.wallet);
+        }
+        self.active_humans = self.active_humans.saturating_sub(1);
+        human_record.set_active_counted(false);
+    }
+
+    /// Records an administrative change.
+    pub fn bump_config_version(&mut self) -> Result<()> {
+        self.config_version = self
+            .config_version
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Records that the registry changed.
+    pub fn bump_state_version(&mut self) -> Result<()> {
+        self.state_version = self
+            .state_version
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Rejects a personality above `max_personality_id`.
+    pub fn require_known_personality(&self, personality_id: u8) -> Result<()> {
+        require!(
+            personality_id <= self.max_personality_id,
+            YourFunError::InvalidPersonalityId
+        );
+        Ok(())
+    }
+
+    /// Whether `key` may publish session templates (the authority always can).
+    pub fn is_curator(&self, key: &Pubkey) -> bool {
+        *key == self.authority || (self.curator != Pubkey::default() && *key == self.curator)
+    }
+
+    /// Bonus settled on top of `session_score` for a session that met its
+    /// goal.
+    pub fn goal_bonus(&self, session_score: u64) -> Option<u64> {
+        let share = (session_score as u128)
+            .checked_mul(self.goal_bonus_bps as u128)?
+            / MAX_BPS as u128;
+        self.goal_bonus_flat.checked_add(u64::try_from(share).ok()?)
+    }
+
+    /// Part of `fee` routed to the InsuranceFund, rounding down.
+    pub fn insurance_share(&self, fee: u64) -> u64 {
+        ((fee as u128) * (self.insurance_fee_bps as u128) / MAX_BPS as u128) as u64
+    }
+
+    /// Part of a verification fee held for the approving verifier, rounding
+    /// down; the platform keeps the remainder.
+    pub fn verifier_fee_share(&self, fee: u64) -> u64 {
+        ((fee as u128) * (self.verifier_fee_share_bps as u128) / MAX_BPS as u128) as u64
+    }
+
+    /// Whether `key` may approve scholarships (the authority always can).
+    pub fn is_program_officer(&self, key: &Pubkey) -> bool {
+        *key == self.authority
+            || (self.program_officer != Pubkey::default() && *key == self.program_officer)
+    }
+
+    /// Whether `key` may act as a moderator (the authority always can).
+    pub fn is_moderator(&self, key: &Pubkey) -> bool {
+        *key == self.authority || (self.moderator != Pubkey::default() && *key == self.moderator)
+    }
+
+    /// Fingerprint length limit applied when none has been configured.
+    pub const DEFAULT_MAX_FINGERPRINT_LEN: u16 = 128;
+    /// Upper bound the authority may raise the fingerprint length limit to.
+    pub const MAX_FINGERPRINT_LEN_CEILING: u16 = 1024;
+
+    /// Effective fingerprint length limit, honoring the default for registries
+    /// created before the limit was configurable.
+    pub fn fingerprint_len_limit(&self) -> usize {
+        if self.max_fingerprint_len == 0 {
+            Self::DEFAULT_MAX_FINGERPRINT_LEN as usize
+        } else {
+            self.max_fingerprint_len as usize
+        }
+    }
+
+    /// Content size limit applied when none has been configured.
+    pub const DEFAULT_MAX_CONTENT_SIZE: u32 = 16 * 1024 * 1024;
+
+    /// Effective limit on `InteractionLog::content_size`.
+    pub fn content_size_limit(&self) -> u32 {
+        if self.max_content_size == 0 {
+            Self::DEFAULT_MAX_CONTENT_SIZE
+        } else {
+            self.max_content_size
+        }
+    }
+
+    /// Number of booster mints the registry can list.
+    pub const MAX_BOOSTERS: usize = 8;
+    /// Highest score multiplier a booster may grant.
+    pub const MAX_BOOSTER_MULTIPLIER_BPS: u16 = 30_000;
+
+    /// Score multiplier granted by burning a token of `mint`, if listed.
+    pub fn booster_multiplier(&self, mint: &Pubkey) -> Option<u16> {
+        self.boosters
+            .iter()
+            .find(|b| b.mint != Pubkey::default() && b.mint == *mint)
+            .map(|b| b.multiplier_bps)
+    }
+
+    /// Lists `mint` with `multiplier_bps`, updating it if already listed, or
+    /// delists it when `multiplier_bps` is 0.
+    pub fn set_booster(&mut self, mint: Pubkey, multiplier_bps: u16) -> Result<()> {
+        if let Some(entry) = self.boosters.iter_mut().find(|b| b.mint == mint) {
+            if multiplier_bps == 0 {
+                *entry = BoosterMint::default();
+            } else {
+                entry.multiplier_bps = multiplier_bps;
+            }
+            return Ok(());
+        }
+        if multiplier_bps == 0 {
+            return Ok(());
+        }
+        let free = self
+            .boosters
+            .iter_mut()
+            .find(|b| b.mint == Pubkey::default())
+            .ok_or(YourFunError::BoosterListFull)?;
+        *free = BoosterMint { mint, multiplier_bps };
+        Ok(())
+    }
+}
+
+/// One shard of the high-frequency platform counters. Sessions are spread
+/// across buckets so concurrent activity does not contend on the registry;
+/// `aggregate_stats` periodically folds the pending deltas into the registry.
+/// Seeds: ["stats", &[bucket_index]]
+#[account]
+#[derive(InitSpace)]
+pub struct StatsBucket {
+    /// Index of this bucket, below `StatsBucket::COUNT`.
+    pub bucket_index: u8,
+    /// Sessions created since the last aggregation.
+    pub pending_sessions_created: u64,
+    /// Interactions recorded since the last aggregation.
+    pub pending_interactions: u64,
+    /// Unix timestamp of the last aggregation into the registry.
+    pub last_aggregated_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+    /// Sessions closed or force-closed since the last aggregation.
+    pub pending_sessions_closed: u64,
+    /// Reserved. Held the score awarded since the last aggregation until
+    /// EpochStats began counting it as interactions happen.
+    pub _reserved_score: [u8; 8],
+}
+
+impl StatsBucket {
+    pub const SEED: &'static [u8] = b"stats";
+    /// Number of buckets the counters are sharded across.
+    pub const COUNT: u8 = 16;
+
+    /// Bucket that absorbs the counters for `session`. Session addresses are
+    /// PDA hashes, so their first byte is already uniformly distributed.
+    pub fn index_for(session: &Pubkey) -> u8 {
+        session.as_ref()[0] % Self::COUNT
+    }
+
+    /// Counts a session leaving the open state, for
+    /// `PlatformRegistry::active_sessions`.
+    pub fn count_session_closed(&mut self) -> Result<()> {
+        self.pending_sessions_closed = self
+            .pending_sessions_closed
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        Ok(())
+    }
+}
+
+/// Platform activity within one day, counted by `verify_human`,
+/// `create_session`, `record_interaction` and their variants as it happens.
+/// The day's first activity creates the account.
+/// Seeds: ["epoch_stats", &epoch.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct EpochStats {
+    /// Epoch index, `unix_timestamp / DURATION`.
+    pub epoch: u64,
+    /// Humans newly verified, including admissions from the waitlist.
+    pub verified_humans: u64,
+    /// Sessions created.
+    pub sessions_created: u64,
+    /// Interactions recorded.
+    pub interactions: u64,
+    /// Interaction score awarded.
+    pub total_score_awarded: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl EpochStats {
+    pub const SEED: &'static [u8] = b"epoch_stats";
+    /// Length of a stats epoch in seconds.
+    pub const DURATION: i64 = 86_400;
+
+    /// The stats epoch containing `now`.
+    pub fn epoch_at(now: i64) -> u64 {
+        now.max(0) as u64 / Self::DURATION as u64
+    }
+}
+
+/// Number of verified humans per region, for jurisdictional reporting.
+/// Seeds: ["region", &region_code.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct RegionStats {
+    /// ISO 3166 numeric region code (0 = unknown).
+    pub region_code: u16,
+    /// Humans currently verified in this region.
+    pub verified_humans: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl RegionStats {
+    pub const SEED: &'static [u8] = b"region";
+    /// Highest ISO 3166 numeric code.
+    pub const MAX_REGION_CODE: u16 = 999;
+
+    /// Counts a human entering the verified state in this region.
+    pub fn add_verified(&mut self) -> Result<()> {
+        self.verified_humans = self
+            .verified_humans
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Removes a human counted by `add_verified`.
+    pub fn remove_verified(&mut self) -> Result<()> {
+        self.verified_humans = self
+            .verified_humans
+            .checked_sub(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        Ok(())
+    }
 }
 
 /// A record of a verified human identity on the platform.
 /// Seeds: ["human", wallet.key().as_ref()]
 #[account]
+#[derive(InitSpace)]
 pub struct HumanRecord {
     /// The wallet address of the verified human.
     pub wallet: Pubkey,
@@ -44,12 +929,13 @@ pub struct HumanRecord {
     pub verified_by: Pubkey,
     /// Unix timestamp of when the verification was completed.
     pub verified_at: i64,
-    /// Verification level: 1=basic, 2=enhanced, 3=full.
+    /// Verification level as a `VerificationLevel`: 1=basic, 2=enhanced,
+    /// 3=full; 0 until verified.
     pub verification_level: u8,
     /// Hash of the behavioral fingerprint used during verification.
     pub fingerprint_hash: [u8; 32],
-    /// Whether this human record is currently active.
-    pub is_active: bool,
+    /// Packed boolean state; see the `FLAG_*` constants and accessors.
+    pub flags: u16,
     /// Number of AI companion sessions this human has created.
     pub session_count: u64,
     /// Total interaction count across all sessions.
@@ -62,18 +948,666 @@ pub struct HumanRecord {
     pub challenge_nonce: [u8; 32],
     /// Bump seed for PDA derivation.
     pub bump: u8,
+    /// Unix timestamp of when registration was initiated.
+    pub registered_at: i64,
+    /// Moderation strikes, carried across close and re-registration.
+    pub strikes: u8,
+    /// Unix timestamp of the most recent revocation (0 if not currently revoked).
+    pub revoked_at: i64,
+    /// Number of this human's sessions terminated by a moderator.
+    pub force_closed_sessions: u32,
+    /// Sessions currently open. Takes one of the formerly reserved bytes, so
+    /// records that had sessions open before it existed undercount; the
+    /// decrement saturates at zero for them.
+    pub active_session_count: u8,
     /// Reserved space for future upgrades.
-    pub _reserved: [u8; 32],
+    pub _reserved: [u8; 3],
+    /// Layout version of this record. Fields from here on are appended by
+    /// later versions, so an older record is a byte prefix of the current
+    /// layout and `migrate_human_record` only has to grow it and fill them in.
+    /// A record that has not been migrated is too short to load as a typed
+    /// account, so every handler implicitly requires the current version.
+    pub record_version: u8,
+    /// Behavioral score granted at the most recent verification.
+    pub behavioral_score: u8,
+    /// ISO 3166 numeric region recorded at the most recent verification
+    /// (0 = unknown).
+    pub region_code: u16,
+    /// `VerificationStatus` as last observed by an instruction, so each
+    /// transition is announced once.
+    pub verification_status: u8,
+    /// Issuer of the document attestation behind the most recent Full
+    /// verification; `Pubkey::default()` for lower levels.
+    pub document_issuer: Pubkey,
+    /// Salted commitments to attributes checked at the most recent
+    /// verification, `hashv([value_hash, salt])` each; all zeros marks an
+    /// unused slot. Replaced only by re-verification.
+    pub attribute_commitments: [[u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+    /// Behavioral, social-graph and longevity sub-scores granted at the most
+    /// recent verification. Verifications through `verify_human` only
+    /// supply the behavioral one.
+    pub sub_scores: [u8; SUB_SCORE_COUNT],
+    /// Composite humanity score checked against `min_behavioral_score` at
+    /// the most recent verification.
+    pub composite_score: u8,
+    /// Unix timestamp at which the most recent verification takes effect,
+    /// once its contest window has passed.
+    pub effective_at: i64,
+    /// The record this one was merged into as a duplicate of the same
+    /// person (`Pubkey::default()` = not merged). A merged record stays
+    /// inactive and can never be verified again.
+    pub merged_into: Pubkey,
+    /// Guardian who must co-sign session creation and extension, and may
+    /// close sessions on their own (`Pubkey::default()` = unsupervised).
+    pub supervisor: Pubkey,
+    /// Key allowed to sign offline interaction bundles alongside the owner
+    /// (`Pubkey::default()` = none).
+    pub interaction_delegate: Pubkey,
+    /// Last raffle epoch the human holds a ticket in (0 = none). Keeps
+    /// `close_session` from entering the human twice in one epoch.
+    pub last_raffle_epoch: u64,
+    /// Unix timestamp at which `assert_human_invariants` last passed for
+    /// this record (0 = never audited).
+    pub last_audited_at: i64,
+    /// Most lamports the owner's program fees and escrow deposits may total
+    /// within one spend window (0 = unlimited). Set by `set_spending_cap`.
+    pub spending_cap_lamports: u64,
+    /// Unix timestamp at which the current spend window opened (0 = none).
+    pub spend_window_start: i64,
+    /// Lamports spent within the current spend window.
+    pub spent_in_window: u64,
+    /// Unix timestamp of the most recent verification or liveness proof.
+    pub last_liveness_at: i64,
+    /// Unix timestamp of the most recent liveness decay (0 = none since the
+    /// last proof). Each decay restarts the interval.
+    pub liveness_decayed_at: i64,
+    /// `Handle::seed_hash` of the handle the human holds (all zeros = none).
+    pub handle_hash: [u8; 32],
+    /// Unix timestamp until which `freeze_human` suspended the human (0 =
+    /// never frozen). A freeze lapses on its own.
+    pub frozen_until: i64,
+    /// Verifier's share of the registration fee, in lamports held on this
+    /// account until an approval pays it out, or a rejection or closure
+    /// sends it to the fee receiver.
+    pub verifier_fee_escrow: u64,
 }
 
 impl HumanRecord {
     pub const SEED: &'static [u8] = b"human";
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 1 + 32 + 1 + 8 + 8 + 8 + 8 + 32 + 1 + 32;
+    #[deprecated(note = "use 8 + INIT_SPACE")]
+    pub const SIZE: usize = 8 + Self::INIT_SPACE;
+
+    /// Layout version written by this program build.
+    pub const CURRENT_VERSION: u8 = 17;
+
+    /// Length in seconds of a spend window: 30 days.
+    pub const SPEND_WINDOW: i64 = 30 * 24 * 60 * 60;
+
+    /// Number of attribute commitment slots.
+    pub const MAX_ATTRIBUTES: usize = 4;
+
+    /// Set while the human is verified and not revoked.
+    pub const FLAG_ACTIVE: u16 = 1 << 0;
+    /// Set while the human is included in its region's RegionStats count.
+    /// Records verified before regions were tracked never have it.
+    pub const FLAG_REGION_COUNTED: u16 = 1 << 1;
+    /// Set when the record's fingerprint was found on the compromised list;
+    /// the verification counts as lapsed until the human is re-verified.
+    pub const FLAG_REVERIFY_REQUIRED: u16 = 1 << 2;
+    /// Set while an approval of the record waits in a WaitlistEntry; the
+    /// record stays inactive until admitted.
+    pub const FLAG_WAITLISTED: u16 = 1 << 3;
+    /// Set when `apply_liveness_decay` lowered the level; cleared by the next
+    /// verification, which restores it. Leaves `FLAG_ACTIVE` alone.
+    pub const FLAG_LIVENESS_LAPSED: u16 = 1 << 4;
+    /// Set while the record is counted in `PlatformRegistry::active_humans`.
+    pub const FLAG_ACTIVE_COUNTED: u16 = 1 << 5;
+
+    /// Account length of the layout that stored `is_active` as a bool,
+    /// before `migrate_human_record` packs it into `flags`.
+    pub const LEGACY_BOOL_LAYOUT_LEN: usize = 211;
+
+    pub fn is_active(&self) -> bool {
+        self.flags & Self::FLAG_ACTIVE != 0
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        set_flag(&mut self.flags, Self::FLAG_ACTIVE, active);
+    }
+
+    pub fn is_region_counted(&self) -> bool {
+        self.flags & Self::FLAG_REGION_COUNTED != 0
+    }
+
+    pub fn set_region_counted(&mut self, counted: bool) {
+        set_flag(&mut self.flags, Self::FLAG_REGION_COUNTED, counted);
+    }
+
+    pub fn requires_reverification(&self) -> bool {
+        self.flags & Self::FLAG_REVERIFY_REQUIRED != 0
+    }
+
+    pub fn set_requires_reverification(&mut self, required: bool) {
+        set_flag(&mut self.flags, Self::FLAG_REVERIFY_REQUIRED, required);
+    }
+
+    pub fn is_waitlisted(&self) -> bool {
+        self.flags & Self::FLAG_WAITLISTED != 0
+    }
+
+    pub fn set_waitlisted(&mut self, waitlisted: bool) {
+        set_flag(&mut self.flags, Self::FLAG_WAITLISTED, waitlisted);
+    }
+
+    pub fn is_liveness_lapsed(&self) -> bool {
+        self.flags & Self::FLAG_LIVENESS_LAPSED != 0
+    }
+
+    pub fn set_liveness_lapsed(&mut self, lapsed: bool) {
+        set_flag(&mut self.flags, Self::FLAG_LIVENESS_LAPSED, lapsed);
+    }
+
+    pub fn is_active_counted(&self) -> bool {
+        self.flags & Self::FLAG_ACTIVE_COUNTED != 0
+    }
+
+    pub fn set_active_counted(&mut self, counted: bool) {
+        set_flag(&mut self.flags, Self::FLAG_ACTIVE_COUNTED, counted);
+    }
+
+    /// Whether `apply_liveness_decay` may act on the record: it is verified
+    /// and neither its last proof nor its last decay is within `interval`
+    /// seconds. Records keep being flagged at level 1, never going below.
+    pub fn liveness_decay_due(&self, interval: i64, now: i64) -> bool {
+        if interval == 0 || !self.is_counted_as_verified() {
+            return false;
+        }
+        let since = self.last_liveness_at.max(self.liveness_decayed_at);
+        now >= since.saturating_add(interval)
+    }
+
+    /// The verification level with every decay `apply_liveness_decay` could
+    /// apply by `now` already taken off, never below Basic; 0 while
+    /// unverified.
+    pub fn decayed_level(&self, interval: i64, now: i64) -> u8 {
+        if !self.liveness_decay_due(interval, now) {
+            return self.verification_level;
+        }
+        let since = self.last_liveness_at.max(self.liveness_decayed_at);
+        let steps = (now - since) / interval;
+        let steps = u8::try_from(steps).unwrap_or(u8::MAX);
+        self.verification_level
+            .saturating_sub(steps)
+            .max(VerificationLevel::Basic as u8)
+    }
+
+    /// The commitment stored for an attribute with the given value hash and
+    /// salt. Verifiers compute slots with it; `verify_attribute` compares.
+    pub fn attribute_commitment(value_hash: &[u8; 32], salt: &[u8; 32]) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hashv(&[value_hash, salt]).to_bytes()
+    }
+
+    /// Whether the attribute in slot `index` opens to `value_hash` and `salt`.
+    /// An unused slot matches nothing.
+    pub fn attribute_matches(&self, index: u8, value_hash: &[u8; 32], salt: &[u8; 32]) -> Result<bool> {
+        let commitment = self
+            .attribute_commitments
+            .get(index as usize)
+            .ok_or(YourFunError::InvalidAttributeIndex)?;
+        Ok(*commitment != [0u8; 32]
+            && *commitment == Self::attribute_commitment(value_hash, salt))
+    }
+
+    /// The granted verification level, or `None` while unverified.
+    pub fn level(&self) -> Result<Option<VerificationLevel>> {
+        match self.verification_level {
+            0 => Ok(None),
+            level => VerificationLevel::try_from(level).map(Some),
+        }
+    }
+
+    /// Counts `amount` lamports paid by the owner to the program against the
+    /// spending cap. A window opens at the first spend after the previous
+    /// one ran out, and lasts `SPEND_WINDOW` seconds. Spends are counted
+    /// even without a cap, so one set later applies to the current window.
+    pub fn record_spend(&mut self, amount: u64, now: i64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        if now >= self.spend_window_start.saturating_add(Self::SPEND_WINDOW) {
+            self.spend_window_start = now;
+            self.spent_in_window = 0;
+        }
+        let spent = self
+            .spent_in_window
+            .checked_add(amount)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        require!(
+            self.spending_cap_lamports == 0 || spent <= self.spending_cap_lamports,
+            YourFunError::SpendingCapExceeded
+        );
+        self.spent_in_window = spent;
+        Ok(())
+    }
+
+    pub fn has_handle(&self) -> bool {
+        self.handle_hash != [0u8; 32]
+    }
+
+    /// Whether a freeze is in effect at `now`.
+    pub fn is_frozen(&self, now: i64) -> bool {
+        now < self.frozen_until
+    }
+
+    pub fn is_supervised(&self) -> bool {
+        self.supervisor != Pubkey::default()
+    }
+
+    /// Whether `key` is the record's supervisor.
+    pub fn is_supervisor(&self, key: &Pubkey) -> bool {
+        self.is_supervised() && *key == self.supervisor
+    }
+
+    /// Requires the supervisor's signature on a supervised record.
+    /// `supervisor` is the key of the optional supervisor signer, if any.
+    pub fn require_supervisor(&self, supervisor: Option<Pubkey>) -> Result<()> {
+        require!(
+            !self.is_supervised() || supervisor == Some(self.supervisor),
+            YourFunError::SupervisorSignatureRequired
+        );
+        Ok(())
+    }
+
+    pub fn is_merged(&self) -> bool {
+        self.merged_into != Pubkey::default()
+    }
+
+    /// Whether the record is verified, uncontested and past its contest
+    /// window, regardless of the registry's validity settings.
+    pub fn is_effective(&self, now: i64) -> bool {
+        self.is_active() && !self.requires_reverification() && now >= self.effective_at
+    }
+
+    /// Current lifecycle status under the registry's validity settings.
+    pub fn verification_status(&self, registry: &PlatformRegistry, now: i64) -> VerificationStatus {
+        if !self.is_active() {
+            return VerificationStatus::Inactive;
+        }
+        if self.requires_reverification() {
+            return VerificationStatus::Lapsed;
+        }
+        if now < self.effective_at {
+            return VerificationStatus::Pending;
+        }
+        if registry.verification_validity == 0 {
+            return VerificationStatus::Active;
+        }
+        let valid_until = self.verified_at.saturating_add(registry.verification_validity);
+        if now < valid_until {
+            VerificationStatus::Active
+        } else if now < valid_until.saturating_add(registry.verification_grace_period) {
+            VerificationStatus::GracePeriod
+        } else {
+            VerificationStatus::Lapsed
+        }
+    }
+
+    /// Whether this record is currently included in
+    /// `PlatformRegistry::total_verified_humans`. Merged records were
+    /// removed from it at the merge.
+    pub fn is_counted_as_verified(&self) -> bool {
+        self.verified_at != 0 && self.revoked_at == 0 && !self.is_merged()
+    }
+}
+
+/// A fingerprint hash blocklisted platform-wide because the model that
+/// produced it can be spoofed. Its existence blocks registration and
+/// verification with that fingerprint; closing it lifts the block.
+/// Seeds: ["compromised", fingerprint_hash.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct CompromisedFingerprint {
+    /// SHA-256 of the raw fingerprint, as stored on HumanRecord.
+    pub fingerprint_hash: [u8; 32],
+    /// Operator-defined reason for the listing.
+    pub reason_code: u8,
+    /// The authority or verifier that listed it; receives the rent on de-listing.
+    pub listed_by: Pubkey,
+    /// Unix timestamp of the listing.
+    pub listed_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl CompromisedFingerprint {
+    pub const SEED: &'static [u8] = b"compromised";
+}
+
+/// Marks a wallet whose HumanRecord was imported from the previous
+/// deployment, so it cannot be imported twice. Never closed.
+/// Seeds: ["legacy_import", wallet.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct LegacyImport {
+    /// The imported wallet.
+    pub wallet: Pubkey,
+    /// The HumanRecord account it was imported from.
+    pub legacy_record: Pubkey,
+    /// Unix timestamp of the import.
+    pub imported_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl LegacyImport {
+    pub const SEED: &'static [u8] = b"legacy_import";
+}
+
+/// A key approved by the authority to attest identity documents.
+/// Seeds: ["doc_issuer", issuer.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct DocumentIssuer {
+    /// The issuer's signing key.
+    pub issuer: Pubkey,
+    /// Off-chain identifier of the issuing organisation.
+    pub issuer_id: u16,
+    /// Whether the issuer's attestations are currently accepted.
+    pub is_active: bool,
+    /// Unix timestamp of the most recent approval change.
+    pub updated_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl DocumentIssuer {
+    pub const SEED: &'static [u8] = b"doc_issuer";
+}
+
+/// An issuer's statement that it checked an identity document of `wallet`,
+/// required for a Full verification. Only the commitment to the
+/// document is stored.
+/// Seeds: ["doc_attestation", wallet.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct DocumentAttestation {
+    /// The wallet whose owner presented the document.
+    pub wallet: Pubkey,
+    /// The attesting issuer key.
+    pub issuer: Pubkey,
+    /// `DocumentIssuer::issuer_id` at the time of attestation.
+    pub issuer_id: u16,
+    /// Hash commitment to the checked document.
+    pub document_commitment: [u8; 32],
+    /// Unix timestamp of the attestation.
+    pub attested_at: i64,
+    /// Unix timestamp after which the attestation is no longer accepted.
+    pub expires_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl DocumentAttestation {
+    pub const SEED: &'static [u8] = b"doc_attestation";
+
+    pub fn is_expired_at(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// A human's appeal against one revocation. Keyed by the revocation time, so
+/// each revocation can be appealed exactly once; resolved appeals are kept
+/// as the record of the decision.
+/// Seeds: ["appeal", human_record.key().as_ref(), &revoked_at.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct Appeal {
+    /// The revoked human record.
+    pub human_record: Pubkey,
+    /// The record's wallet, which filed the appeal.
+    pub wallet: Pubkey,
+    /// `revoked_at` of the revocation being appealed.
+    pub revoked_at: i64,
+    /// Hash of the off-chain evidence submitted with the appeal.
+    pub evidence_hash: [u8; 32],
+    /// Unix timestamp of filing.
+    pub filed_at: i64,
+    /// `AppealStatus` discriminant.
+    pub status: u8,
+    /// Unix timestamp of the arbiter's decision (0 while pending).
+    pub resolved_at: i64,
+    /// Hash of the arbiter's off-chain reasoning.
+    pub note_hash: [u8; 32],
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Appeal {
+    pub const SEED: &'static [u8] = b"appeal";
+}
+
+/// Raw behavioral fingerprint bytes backing `HumanRecord::fingerprint_hash`.
+/// Sized to the submitted data at registration.
+/// Seeds: ["fingerprint", human_record.key().as_ref()]
+#[account]
+pub struct FingerprintData {
+    /// Reference to the owning human record.
+    pub human_record: Pubkey,
+    /// The raw fingerprint bytes as produced by the behavioral model.
+    pub data: Vec<u8>,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl FingerprintData {
+    pub const SEED: &'static [u8] = b"fingerprint";
+
+    /// Account space, excluding the discriminator, for `len` bytes of data.
+    pub fn space_for(len: usize) -> usize {
+        32 + 4 + len + 1
+    }
+}
+
+/// Written when a HumanRecord is closed so that re-registration can enforce
+/// the cooldown and carry forward moderation history.
+/// Seeds: ["tombstone", wallet.key().as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct Tombstone {
+    /// The wallet whose HumanRecord was closed.
+    pub wallet: Pubkey,
+    /// Unix timestamp of when the HumanRecord was closed.
+    pub closed_at: i64,
+    /// Learning score held by the record at closure.
+    pub prior_learning_score: u64,
+    /// Strike count held by the record at closure.
+    pub strikes: u8,
+    /// Session count held by the record at closure, so a re-registered record
+    /// continues from the next unused session index.
+    pub session_count: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Tombstone {
+    pub const SEED: &'static [u8] = b"tombstone";
+}
+
+/// A canned session setup that clients can launch with
+/// `create_session_from_template`.
+/// Seeds: ["template", &template_id.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct SessionTemplate {
+    /// Identifier chosen by the curator; never 0.
+    pub template_id: u16,
+    /// Companion personality of sessions launched from the template.
+    pub personality_id: u8,
+    /// Initial topic of sessions launched from the template.
+    pub topic: [u8; 32],
+    /// Session duration in seconds (0 = the registry's `max_session_duration`).
+    pub duration_override: i64,
+    /// `ScoringProfile` of sessions launched from the template.
+    pub scoring_profile: u8,
+    /// Whether sessions can currently be launched from the template.
+    pub enabled: bool,
+    /// The authority or curator that published the template.
+    pub created_by: Pubkey,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl SessionTemplate {
+    pub const SEED: &'static [u8] = b"template";
+}
+
+/// A booster NFT mint accepted by `create_session` and the score multiplier
+/// burning one of its tokens grants.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug, InitSpace)]
+pub struct BoosterMint {
+    pub mint: Pubkey,
+    pub multiplier_bps: u16,
+}
+
+/// Score a session earned while one topic was active.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug, InitSpace)]
+pub struct TopicScore {
+    pub topic: [u8; 32],
+    pub score: u64,
+}
+
+/// Score a human has settled from sessions under one topic. Created by the
+/// owner; `close_session` credits the ones passed to it.
+/// Seeds: ["topic_progress", human_record.key().as_ref(), topic]
+#[account]
+#[derive(InitSpace)]
+pub struct TopicProgress {
+    /// Reference to the owning human record.
+    pub human_record: Pubkey,
+    /// The topic identifier.
+    pub topic: [u8; 32],
+    /// Total score settled under this topic.
+    pub cumulative_score: u64,
+    /// Closed sessions that covered this topic and were settled here.
+    pub session_count: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl TopicProgress {
+    pub const SEED: &'static [u8] = b"topic_progress";
+}
+
+/// A verifier's approval, as applied to a HumanRecord when it is activated.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct VerificationApproval {
+    pub verifier: Pubkey,
+    pub verification_level: u8,
+    /// Composite of `sub_scores`, checked against `min_behavioral_score`.
+    pub composite_score: u8,
+    pub sub_scores: [u8; SUB_SCORE_COUNT],
+    pub region_code: u16,
+    pub attribute_commitments: [[u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+    /// Supervisor to place the human under (`Pubkey::default()` = unchanged).
+    pub supervisor: Pubkey,
+    /// Issuer of the attestation behind a Full verification.
+    pub document_issuer: Pubkey,
+}
+
+/// An approval `verify_human` could not apply because the verification cap
+/// was reached, waiting for `admit_from_waitlist`. Entries are admitted in
+/// position order and kept afterwards.
+/// Seeds: ["waitlist", &position.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct WaitlistEntry {
+    /// Place in the waitlist, from `PlatformRegistry::waitlist_count`.
+    pub position: u64,
+    /// The waitlisted record.
+    pub human_record: Pubkey,
+    /// Wallet of the waitlisted record.
+    pub wallet: Pubkey,
+    /// The approval to apply on admission.
+    pub approval: VerificationApproval,
+    /// Unix timestamp at which the approval was waitlisted.
+    pub waitlisted_at: i64,
+    /// Unix timestamp of admission (0 while waiting).
+    pub admitted_at: i64,
+    /// Set when the entry was passed over because its record was closed,
+    /// merged or verified again in the meantime.
+    pub skipped: bool,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl WaitlistEntry {
+    pub const SEED: &'static [u8] = b"waitlist";
+}
+
+/// A vanity handle bound to a verified human, so other apps can resolve it
+/// to a wallet. Its address makes each handle unique, case-insensitively.
+/// Seeds: ["handle", Handle::seed_hash(handle)]
+#[account]
+#[derive(InitSpace)]
+pub struct Handle {
+    /// The handle, lowercased.
+    #[max_len(20)]
+    pub handle: String,
+    /// The wallet of the human holding the handle; receives its rent back.
+    pub wallet: Pubkey,
+    /// Unix timestamp of the claim.
+    pub claimed_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Handle {
+    pub const SEED: &'static [u8] = b"handle";
+    /// Must match the `max_len` of `handle`.
+    pub const MAX_LEN: usize = 20;
+    pub const MIN_LEN: usize = 3;
+
+    /// Seed hash of a handle: SHA-256 of its lowercase form.
+    pub fn seed_hash(handle: &str) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(handle.to_ascii_lowercase().as_bytes()).to_bytes()
+    }
+
+    /// Whether a lowercased handle is 3 to 20 characters of a-z, 0-9 and `_`.
+    pub fn is_valid(handle: &str) -> bool {
+        (Self::MIN_LEN..=Self::MAX_LEN).contains(&handle.len())
+            && handle
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+    }
+}
+
+/// A curriculum topic registered by the authority or curator, so sessions
+/// can be grouped by it.
+/// Seeds: ["topic", topic]
+#[account]
+#[derive(InitSpace)]
+pub struct Topic {
+    /// The topic identifier, as passed to `create_session` and `update_topic`.
+    pub topic: [u8; 32],
+    /// Hash of the topic's off-chain metadata.
+    pub metadata_hash: [u8; 32],
+    /// Whether sessions may currently use the topic while registered topics
+    /// are enforced.
+    pub enabled: bool,
+    /// The authority or curator that registered the topic.
+    pub registered_by: Pubkey,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Topic {
+    pub const SEED: &'static [u8] = b"topic";
 }
 
 /// An active AI companion session associated with a verified human.
 /// Seeds: ["session", human_record.key().as_ref(), &session_index.to_le_bytes()]
 #[account]
+#[derive(InitSpace)]
 pub struct SessionAccount {
     /// Reference to the parent human record.
     pub human_record: Pubkey,
@@ -87,8 +1621,8 @@ pub struct SessionAccount {
     pub last_interaction_at: i64,
     /// Unix timestamp when this session expires.
     pub expires_at: i64,
-    /// Whether this session is currently active.
-    pub is_active: bool,
+    /// Packed boolean state; see the `FLAG_*` constants and accessors.
+    pub flags: u16,
     /// Number of interactions recorded in this session.
     pub interaction_count: u32,
     /// Companion personality identifier (0-255 mapped to personality archetypes).
@@ -99,24 +1633,1243 @@ pub struct SessionAccount {
     pub session_score: u64,
     /// Bump seed for PDA derivation.
     pub bump: u8,
+    /// Unix timestamp of when the session was closed (0 while open).
+    pub closed_at: i64,
+    /// Why the session was closed; a `CloseReason` discriminant.
+    pub close_reason: u8,
+    /// Interaction logs created in this session that have not been reaped yet.
+    pub open_logs: u32,
     /// Reserved space.
-    pub _reserved: [u8; 16],
+    pub _reserved: [u8; 1],
+    /// Layout version of this session. As for `HumanRecord::record_version`,
+    /// later fields are appended so `migrate_session` only has to grow older
+    /// sessions, which cannot be loaded until migrated.
+    pub layout_version: u8,
+    /// SessionTemplate the session was launched from (0 = none).
+    pub template_id: u16,
+    /// `ScoringProfile` applied to the session's interactions.
+    pub scoring_profile: u8,
+    /// `GoalType` the owner declared at creation; never changed afterwards.
+    pub goal_type: u8,
+    /// Interactions or session score the goal requires (0 without a goal).
+    pub goal_target: u64,
+    /// Times the session was reopened after expiring.
+    pub reopen_count: u8,
+    /// Topics covered so far with the score earned under each, in the order
+    /// they were first used. Only the first `topic_count` entries are set.
+    pub topics: [TopicScore; 4],
+    /// Number of entries of `topics` in use.
+    pub topic_count: u8,
+    /// Index into `topics` of `current_topic`.
+    pub active_topic: u8,
+    /// Value bound into companion co-signatures so they cannot be replayed
+    /// into another session or into this one after it is reopened or
+    /// extended; see `derive_nonce`.
+    pub session_nonce: [u8; 32],
+    /// Score awaiting `finalize_session` while the session is settling.
+    pub pending_score: u64,
+    /// When the settlement window ends (0 if the session never settled).
+    pub settles_at: i64,
+    /// Score multiplier from the booster burned at creation (0 = none).
+    pub booster_multiplier_bps: u16,
+    /// Lamports billed per whole minute the session runs (0 = no escrow).
+    pub escrow_rate_per_minute: u64,
+    /// Escrowed lamports held on this account above rent until the session
+    /// is settled by `close_session` or `reclaim_session`.
+    pub escrow_lamports: u64,
+    /// Head of the hash chain over the session's interactions; see
+    /// `crypto::next_chain_head`. Zero before the first interaction, and for
+    /// sessions migrated mid-way, whose chain starts at the migration.
+    pub chain_head: [u8; 32],
 }
 
 impl SessionAccount {
     pub const SEED: &'static [u8] = b"session";
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 4 + 1 + 32 + 8 + 1 + 16;
-}
+    #[deprecated(note = "use 8 + INIT_SPACE")]
+    pub const SIZE: usize = 8 + Self::INIT_SPACE;
 
-/// A single recorded interaction within a session.
-/// Seeds: ["interaction", session.key().as_ref(), &interaction_index.to_le_bytes()]
-#[account]
-pub struct InteractionLog {
-    /// Reference to the parent session.
-    pub session: Pubkey,
-    /// The wallet that created this interaction.
-    pub user: Pubkey,
-    /// Index of this interaction within its session.
+    /// Layout version written by this program build.
+    pub const CURRENT_VERSION: u8 = 9;
+
+    /// Set while the session accepts interactions.
+    pub const FLAG_ACTIVE: u16 = 1 << 0;
+
+    /// Set when the session was closed having met its goal.
+    pub const FLAG_GOAL_MET: u16 = 1 << 1;
+
+    /// Set from a deferred close until the score is finalized or contested.
+    pub const FLAG_SETTLING: u16 = 1 << 2;
+
+    /// Set when the pending score was contested and forfeited.
+    pub const FLAG_CONTESTED: u16 = 1 << 3;
+
+    /// Set once the owner has rated the closed session.
+    pub const FLAG_RATED: u16 = 1 << 4;
+
+    /// Highest `goal_target` accepted for an interactions goal.
+    pub const MAX_GOAL_INTERACTIONS: u64 = 10_000;
+
+    /// Highest `goal_target` accepted for a session score goal.
+    pub const MAX_GOAL_SCORE: u64 = 1_000_000;
+
+    /// Times a session may be reopened by `reopen_session`.
+    pub const MAX_REOPENS: u8 = 3;
+
+    /// Number of distinct topics a session can cover.
+    pub const MAX_TOPICS: usize = 4;
+
+    /// Minimum seconds between activity updates accepted by `heartbeat_session`.
+    pub const HEARTBEAT_MIN_INTERVAL: i64 = 60;
+
+    /// Account length of the layout that stored `is_active` as a bool,
+    /// before `migrate_session` packs it into `flags`.
+    pub const LEGACY_BOOL_LAYOUT_LEN: usize = 167;
+
+    pub fn is_active(&self) -> bool {
+        self.flags & Self::FLAG_ACTIVE != 0
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        set_flag(&mut self.flags, Self::FLAG_ACTIVE, active);
+    }
+
+    pub fn goal_met(&self) -> bool {
+        self.flags & Self::FLAG_GOAL_MET != 0
+    }
+
+    pub fn set_goal_met(&mut self, met: bool) {
+        set_flag(&mut self.flags, Self::FLAG_GOAL_MET, met);
+    }
+
+    pub fn is_settling(&self) -> bool {
+        self.flags & Self::FLAG_SETTLING != 0
+    }
+
+    pub fn set_settling(&mut self, settling: bool) {
+        set_flag(&mut self.flags, Self::FLAG_SETTLING, settling);
+    }
+
+    pub fn is_contested(&self) -> bool {
+        self.flags & Self::FLAG_CONTESTED != 0
+    }
+
+    pub fn set_contested(&mut self, contested: bool) {
+        set_flag(&mut self.flags, Self::FLAG_CONTESTED, contested);
+    }
+
+    pub fn is_rated(&self) -> bool {
+        self.flags & Self::FLAG_RATED != 0
+    }
+
+    pub fn set_rated(&mut self, rated: bool) {
+        set_flag(&mut self.flags, Self::FLAG_RATED, rated);
+    }
+
+    /// Rejects unknown goal types and targets of 0 or above the hard caps.
+    /// A session without a goal must have a target of 0.
+    pub fn validate_goal(goal_type: u8, goal_target: u64) -> Result<()> {
+        let max_target = match GoalType::try_from(goal_type)? {
+            GoalType::None => {
+                require!(goal_target == 0, YourFunError::InvalidGoal);
+                return Ok(());
+            }
+            GoalType::Interactions => Self::MAX_GOAL_INTERACTIONS,
+            GoalType::SessionScore => Self::MAX_GOAL_SCORE,
+        };
+        require!(
+            goal_target != 0 && goal_target <= max_target,
+            YourFunError::InvalidGoal
+        );
+        Ok(())
+    }
+
+    /// Nonce for `session` owned by `owner` issued at `slot`. `previous` is
+    /// the nonce being rotated out, all zeros for a new session.
+    pub fn derive_nonce(session: &Pubkey, slot: u64, owner: &Pubkey, previous: &[u8; 32]) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hashv(&[
+            session.as_ref(),
+            &slot.to_le_bytes(),
+            owner.as_ref(),
+            previous,
+        ])
+        .to_bytes()
+    }
+
+    /// Escrow billed for a session that ran from `created_at` to `ended_at`:
+    /// the rate times the whole minutes elapsed, rounding down, and never
+    /// more than the escrow.
+    pub fn escrow_billed(&self, ended_at: i64) -> Option<u64> {
+        let minutes = ended_at.saturating_sub(self.created_at).max(0) as u64 / 60;
+        Some(self.escrow_rate_per_minute.checked_mul(minutes)?.min(self.escrow_lamports))
+    }
+
+    /// Replaces `session_nonce`, voiding co-signatures over the old one.
+    pub fn rotate_nonce(&mut self, session: &Pubkey, slot: u64) {
+        self.session_nonce = Self::derive_nonce(session, slot, &self.owner, &self.session_nonce);
+    }
+
+    /// Index into `topics` of `topic`, if the session has covered it.
+    pub fn topic_slot(&self, topic: &[u8; 32]) -> Option<usize> {
+        self.topics[..self.topic_count as usize]
+            .iter()
+            .position(|t| t.topic == *topic)
+    }
+
+    /// Makes `topic` the active one, appending it if it is new.
+    pub fn switch_topic(&mut self, topic: [u8; 32]) -> Result<()> {
+        let slot = match self.topic_slot(&topic) {
+            Some(slot) => slot,
+            None => {
+                let slot = self.topic_count as usize;
+                require!(slot < Self::MAX_TOPICS, YourFunError::TopicLimitReached);
+                self.topics[slot] = TopicScore { topic, score: 0 };
+                self.topic_count += 1;
+                slot
+            }
+        };
+        self.active_topic = slot as u8;
+        self.current_topic = topic;
+        Ok(())
+    }
+
+    /// Attributes a score increment to the active topic.
+    pub fn credit_active_topic(&mut self, increment: u64) -> Option<()> {
+        let entry = self.topics.get_mut(self.active_topic as usize)?;
+        entry.score = entry.score.checked_add(increment)?;
+        Some(())
+    }
+
+    /// Whether the session has reached its goal so far. Always false for a
+    /// session without one.
+    pub fn goal_reached(&self) -> bool {
+        match GoalType::try_from(self.goal_type) {
+            Ok(GoalType::Interactions) => self.interaction_count as u64 >= self.goal_target,
+            Ok(GoalType::SessionScore) => self.session_score >= self.goal_target,
+            _ => false,
+        }
+    }
+
+    pub const CLOSE_REASON_NONE: u8 = CloseReason::None as u8;
+    pub const CLOSE_REASON_COMPLETED: u8 = CloseReason::Completed as u8;
+    pub const CLOSE_REASON_EXPIRED: u8 = CloseReason::Expired as u8;
+    pub const CLOSE_REASON_CANCELLED: u8 = CloseReason::Cancelled as u8;
+    pub const CLOSE_REASON_FORCE_CLOSED: u8 = CloseReason::ForceClosed as u8;
+
+    /// The stored close reason as a typed value.
+    pub fn close_reason_kind(&self) -> Result<CloseReason> {
+        CloseReason::try_from(self.close_reason)
+    }
+}
+
+/// The currently open sessions of a human, so clients can resume without
+/// scanning program accounts. Best effort: entries are removed on close and
+/// reclaim, and stale ones are pruned lazily on the next `create_session`.
+/// Seeds: ["active", human_record.key().as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct ActiveSessionIndex {
+    /// Reference to the owning human record.
+    pub human_record: Pubkey,
+    /// Open session accounts, oldest first.
+    #[max_len(16)]
+    pub sessions: Vec<Pubkey>,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl ActiveSessionIndex {
+    pub const SEED: &'static [u8] = b"active";
+
+    /// Maximum number of sessions tracked at once.
+    pub const CAPACITY: usize = 16;
+
+    pub fn is_full(&self) -> bool {
+        self.sessions.len() >= Self::CAPACITY
+    }
+
+    /// Appends a session, preserving creation order.
+    pub fn push(&mut self, session: Pubkey) -> bool {
+        if self.is_full() || self.sessions.contains(&session) {
+            return false;
+        }
+        self.sessions.push(session);
+        true
+    }
+
+    /// Removes a session if present. Missing entries are not an error, so an
+    /// inconsistent index never blocks closing a session.
+    pub fn remove(&mut self, session: &Pubkey) -> bool {
+        match self.sessions.iter().position(|s| s == session) {
+            Some(pos) => {
+                self.sessions.remove(pos);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// One interaction a human pinned from a session.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct Highlight {
+    pub session: Pubkey,
+    pub interaction_index: u32,
+    pub content_hash: [u8; 32],
+    pub pinned_at: i64,
+}
+
+/// Interactions a human pinned to surface on their profile, oldest first.
+/// Created on the first `pin_highlight`.
+/// Seeds: ["highlights", human_record.key().as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct Highlights {
+    /// Reference to the owning human record.
+    pub human_record: Pubkey,
+    /// Pinned interactions.
+    #[max_len(16)]
+    pub entries: Vec<Highlight>,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Highlights {
+    pub const SEED: &'static [u8] = b"highlights";
+
+    /// Maximum number of pinned interactions.
+    pub const CAPACITY: usize = 16;
+
+    fn position(&self, session: &Pubkey, interaction_index: u32) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|h| h.session == *session && h.interaction_index == interaction_index)
+    }
+
+    /// Pins an interaction, rejecting duplicates and a full list.
+    pub fn pin(&mut self, highlight: Highlight) -> Result<()> {
+        require!(
+            self.position(&highlight.session, highlight.interaction_index).is_none(),
+            YourFunError::HighlightAlreadyPinned
+        );
+        require!(self.entries.len() < Self::CAPACITY, YourFunError::HighlightsFull);
+        self.entries.push(highlight);
+        Ok(())
+    }
+
+    /// Unpins an interaction, freeing its slot.
+    pub fn unpin(&mut self, session: &Pubkey, interaction_index: u32) -> Result<Highlight> {
+        let pos = self
+            .position(session, interaction_index)
+            .ok_or(YourFunError::HighlightNotFound)?;
+        Ok(self.entries.remove(pos))
+    }
+}
+
+/// Lamports tipped to one personality, held in this account until the
+/// authority withdraws them. Created by the first tip.
+/// Seeds: ["ptreasury", &[personality_id]]
+#[account]
+#[derive(InitSpace)]
+pub struct PersonalityTreasury {
+    /// The personality the tips are for.
+    pub personality_id: u8,
+    /// Lamports tipped over the treasury's lifetime.
+    pub total_tips: u64,
+    /// Number of tips received.
+    pub tip_count: u64,
+    /// Lamports withdrawn so far.
+    pub total_withdrawn: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl PersonalityTreasury {
+    pub const SEED: &'static [u8] = b"ptreasury";
+}
+
+/// Token reward for reaching a learning score, paid out linearly through a
+/// Vesting account per claimant. The reward tokens are held in the
+/// milestone's vault.
+/// Seeds: ["milestone", &milestone_id.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct Milestone {
+    /// Identifier chosen by the authority.
+    pub milestone_id: u16,
+    /// Learning score a human needs to claim the reward.
+    pub score_threshold: u64,
+    /// Mint of the reward token.
+    pub reward_mint: Pubkey,
+    /// Token account, owned by this milestone, that rewards are paid from.
+    pub vault: Pubkey,
+    /// Tokens granted to each claimant.
+    pub reward_amount: u64,
+    /// Seconds after a claim before anything vests.
+    pub cliff: i64,
+    /// Seconds after a claim over which the reward vests linearly
+    /// (0 = all at the cliff).
+    pub vesting_duration: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Milestone {
+    pub const SEED: &'static [u8] = b"milestone";
+    /// Seeds: ["milestone_vault", &milestone_id.to_le_bytes()]
+    pub const VAULT_SEED: &'static [u8] = b"milestone_vault";
+}
+
+/// One human's claimed reward of one milestone, released by `claim_vested`.
+/// Seeds: ["vesting", human_record.key().as_ref(), &milestone_id.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct Vesting {
+    /// Reference to the claiming human record.
+    pub human_record: Pubkey,
+    /// Wallet the vested tokens are released to.
+    pub owner: Pubkey,
+    /// The milestone that granted the reward.
+    pub milestone_id: u16,
+    /// Tokens granted in total.
+    pub total_amount: u64,
+    /// Tokens released so far.
+    pub claimed_so_far: u64,
+    /// Unix timestamp of the milestone claim, when vesting starts.
+    pub start_at: i64,
+    /// Nothing vests before this Unix timestamp.
+    pub cliff_at: i64,
+    /// Seconds from `start_at` until everything has vested.
+    pub vesting_duration: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Vesting {
+    pub const SEED: &'static [u8] = b"vesting";
+
+    /// Tokens vested by `now`, whether released or not.
+    pub fn vested_at(&self, now: i64) -> u64 {
+        if now < self.cliff_at {
+            return 0;
+        }
+        let elapsed = now.saturating_sub(self.start_at);
+        if self.vesting_duration <= 0 || elapsed >= self.vesting_duration {
+            return self.total_amount;
+        }
+        ((self.total_amount as u128) * (elapsed as u128) / (self.vesting_duration as u128)) as u64
+    }
+
+    /// Whether every granted token has been released.
+    pub fn is_fully_claimed(&self) -> bool {
+        self.claimed_so_far >= self.total_amount
+    }
+}
+
+/// Authority-managed settings of one companion personality. Personalities
+/// without one are available without restriction.
+/// Seeds: ["personality", &[personality_id]]
+#[account]
+#[derive(InitSpace)]
+pub struct PersonalityConfig {
+    /// The personality these settings belong to.
+    pub personality_id: u8,
+    /// Unix timestamp after which no new session may start with this
+    /// personality (0 = not deprecated). Existing sessions are unaffected.
+    pub deprecated_after: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+    /// Third-party studio credited with a share of this personality's
+    /// session fees (default key = none).
+    pub partner: Pubkey,
+    /// Share of each session fee accrued to the partner, in basis points.
+    pub partner_share_bps: u16,
+    /// Lamports accrued to the partner and not yet claimed; held on this
+    /// account on top of its rent.
+    pub partner_accrued: u64,
+    /// Lamports the partner has claimed over the account's lifetime.
+    pub partner_claimed: u64,
+}
+
+impl PersonalityConfig {
+    pub const SEED: &'static [u8] = b"personality";
+
+    pub fn is_deprecated_at(&self, now: i64) -> bool {
+        self.deprecated_after != 0 && now > self.deprecated_after
+    }
+
+    /// Part of a session fee accrued to the partner, rounding down; 0 when
+    /// there is no partner.
+    pub fn partner_share(&self, fee: u64) -> u64 {
+        if self.partner == Pubkey::default() {
+            return 0;
+        }
+        ((fee as u128) * (self.partner_share_bps as u128) / MAX_BPS as u128) as u64
+    }
+}
+
+/// Lifetime totals of the sessions closed under one companion personality,
+/// and of the ratings their owners gave with `rate_session`. Created on the
+/// first `close_session` or `rate_session` for that personality.
+/// Seeds: ["pstats", &[personality_id]]
+#[account]
+#[derive(InitSpace)]
+pub struct PersonalityStats {
+    /// The personality these totals belong to.
+    pub personality_id: u8,
+    /// Number of sessions closed under this personality.
+    pub session_count: u64,
+    /// Sum of the final scores of those sessions.
+    pub cumulative_score: u64,
+    /// Sum of the interactions recorded in those sessions.
+    pub cumulative_interactions: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+    /// Sum of the ratings given to sessions with this personality.
+    pub rating_sum: u64,
+    /// Number of sessions rated; the average is `rating_sum / rating_count`.
+    pub rating_count: u64,
+}
+
+impl PersonalityStats {
+    pub const SEED: &'static [u8] = b"pstats";
+    /// Lowest rating `rate_session` accepts.
+    pub const MIN_RATING: u8 = 1;
+    /// Highest rating `rate_session` accepts.
+    pub const MAX_RATING: u8 = 5;
+
+    /// Fills in an account created by `init_if_needed`.
+    pub fn init_if_new(&mut self, personality_id: u8, bump: u8) {
+        if self.bump == 0 {
+            self.personality_id = personality_id;
+            self.bump = bump;
+        }
+    }
+
+    /// Adds one session rating to the totals.
+    pub fn record_rating(&mut self, rating: u8) -> Result<()> {
+        require!(
+            (Self::MIN_RATING..=Self::MAX_RATING).contains(&rating),
+            YourFunError::InvalidRating
+        );
+        self.rating_sum = self
+            .rating_sum
+            .checked_add(rating as u64)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        self.rating_count = self
+            .rating_count
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        Ok(())
+    }
+}
+
+/// Administrative operation recorded in an AdminAction entry.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+#[repr(u8)]
+pub enum AdminActionKind {
+    SetPaused = 0,
+    SetReregistrationCooldown = 1,
+    SetMaxFingerprintLen = 2,
+    SetModerator = 3,
+    SetForceCloseSettleBps = 4,
+    SetLogThresholdScore = 5,
+    FreezeConfig = 6,
+    SetGuardian = 7,
+    SetVerificationFee = 8,
+    RevokeHuman = 9,
+    SetPermissionedRegistration = 10,
+    CreateInvites = 11,
+    RevokeInvite = 12,
+    SetRegionRestriction = 13,
+    SetRegionAllowed = 14,
+    SetPersonalitySunset = 15,
+    ScheduleMaintenance = 16,
+    CancelMaintenance = 17,
+    SetVerificationValidity = 18,
+    SetLegacyImport = 19,
+    SetArbiter = 20,
+    SetDocumentIssuer = 21,
+    SetSubScoreWeights = 22,
+    SetContestWindow = 23,
+    MergeHumanRecords = 24,
+    SetCurator = 25,
+    SetGoalBonus = 26,
+    SetReopenPolicy = 27,
+    SetSupervisor = 28,
+    SetMaxContentSize = 29,
+    SetCompanionOracle = 30,
+    SetSettlementWindow = 31,
+    SetBooster = 32,
+    SetProgramOfficer = 33,
+    SetInsuranceFeeBps = 34,
+    PayInsuranceClaim = 35,
+    SetPersonalityPartner = 36,
+    ConfigureMatching = 37,
+    CreatePromo = 38,
+    SetEnforceRegisteredTopics = 39,
+    RecomputeSessionScore = 40,
+    SetPrivateScoreBps = 41,
+    SetMaxVerifiedHumans = 42,
+    AdmitFromWaitlist = 43,
+    SetLivenessPolicy = 44,
+    UpdateConfig = 45,
+    UpdateTreasury = 46,
+    WithdrawFees = 47,
+    SetPauseFlags = 48,
+    SetConfigTimelock = 49,
+    QueueConfigUpdate = 50,
+    CancelConfigUpdate = 51,
+    SetCoAuthority = 52,
+    SetFeeSchedule = 53,
+    SetFeeGuardrails = 54,
+    FreezeHuman = 55,
+    UnfreezeHuman = 56,
+    AdminForceCloseSession = 57,
+    SetMaxActiveSessions = 58,
+    SetMinInteractionGap = 59,
+    SetMinInteractionDuration = 60,
+    SetTypeMultipliers = 61,
+    SetMaxPersonalityId = 62,
+    SetVerifierFeeShare = 63,
+}
+
+impl AdminActionKind {
+    /// `PlatformRegistry::CONFIG_*` bits of the registry fields this action
+    /// may change; 0 for actions that leave the configuration alone.
+    pub fn config_fields(self) -> u32 {
+        use AdminActionKind::*;
+        match self {
+            SetVerificationFee | SetFeeSchedule | SetFeeGuardrails | SetInsuranceFeeBps
+            | SetVerifierFeeShare => {
+                PlatformRegistry::CONFIG_FEES
+            }
+            UpdateTreasury => PlatformRegistry::CONFIG_TREASURY,
+            SetPaused | SetPauseFlags | ScheduleMaintenance | CancelMaintenance => {
+                PlatformRegistry::CONFIG_PAUSE
+            }
+            SetModerator | SetGuardian | SetArbiter | SetCurator | SetCompanionOracle
+            | SetProgramOfficer | SetCoAuthority => PlatformRegistry::CONFIG_ROLES,
+            SetReregistrationCooldown | SetMaxFingerprintLen | SetPermissionedRegistration
+            | SetRegionRestriction | SetRegionAllowed | SetLegacyImport => {
+                PlatformRegistry::CONFIG_REGISTRATION
+            }
+            SetVerificationValidity | SetContestWindow | SetLivenessPolicy | SetMaxVerifiedHumans => {
+                PlatformRegistry::CONFIG_VERIFICATION
+            }
+            SetForceCloseSettleBps | SetReopenPolicy | SetMaxContentSize | SetSettlementWindow
+            | SetEnforceRegisteredTopics | SetMaxActiveSessions | SetMinInteractionGap
+            | SetMaxPersonalityId => {
+                PlatformRegistry::CONFIG_SESSIONS
+            }
+            SetLogThresholdScore | SetSubScoreWeights | SetGoalBonus | SetBooster
+            | SetPrivateScoreBps | SetMinInteractionDuration | SetTypeMultipliers => {
+                PlatformRegistry::CONFIG_SCORING
+            }
+            FreezeConfig => PlatformRegistry::CONFIG_FREEZE,
+            SetConfigTimelock => PlatformRegistry::CONFIG_TIMELOCK,
+            UpdateConfig => {
+                PlatformRegistry::CONFIG_FEES
+                    | PlatformRegistry::CONFIG_VERIFICATION
+                    | PlatformRegistry::CONFIG_SESSIONS
+            }
+            RevokeHuman | CreateInvites | RevokeInvite | SetPersonalitySunset | SetDocumentIssuer
+            | MergeHumanRecords | SetSupervisor | PayInsuranceClaim | SetPersonalityPartner
+            | ConfigureMatching | CreatePromo | RecomputeSessionScore | AdmitFromWaitlist
+            | WithdrawFees | QueueConfigUpdate | CancelConfigUpdate | FreezeHuman
+            | UnfreezeHuman | AdminForceCloseSession => 0,
+        }
+    }
+}
+
+/// Immutable audit entry written by every administrative instruction. No
+/// instruction modifies or closes these accounts.
+/// Seeds: ["admin_action", registry.key().as_ref(), &action_index.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct AdminAction {
+    /// Position of this entry in the registry's audit trail.
+    pub action_index: u64,
+    /// The operation performed.
+    pub action: AdminActionKind,
+    /// The signer that performed it.
+    pub actor: Pubkey,
+    /// SHA-256 of the Borsh-serialized instruction parameters.
+    pub params_hash: [u8; 32],
+    /// Unix timestamp of the operation.
+    pub timestamp: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl AdminAction {
+    pub const SEED: &'static [u8] = b"admin_action";
+}
+
+/// Invitation allowing a wallet to register while registration is
+/// permissioned. Consumed invites stay on chain so they cannot be reissued
+/// under the same address.
+/// Seeds: ["invite", wallet.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct InviteEntry {
+    /// The invited wallet.
+    pub wallet: Pubkey,
+    /// Unix timestamp at which the invite was created.
+    pub created_at: i64,
+    /// Whether the wallet has registered with this invite.
+    pub consumed: bool,
+    /// Unix timestamp of the registration that consumed it (0 if unconsumed).
+    pub consumed_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl InviteEntry {
+    pub const SEED: &'static [u8] = b"invite";
+}
+
+/// Lamports set aside from fees to compensate wrongful slashing or reversed
+/// clawbacks, kept apart from the treasury.
+/// Seeds: ["insurance_fund"]
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceFund {
+    /// Lamports routed in from fees over the fund's lifetime.
+    pub total_in: u64,
+    /// Lamports paid out in claims.
+    pub total_out: u64,
+    /// Number of claims paid.
+    pub claims_paid: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl InsuranceFund {
+    pub const SEED: &'static [u8] = b"insurance_fund";
+}
+
+/// Program-owned account fees can be paid into instead of a wallet, so they
+/// only leave through `withdraw_fees`. Its totals let indexers reconcile
+/// the balance.
+/// Seeds: ["vault"]
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    /// Lamports paid in as fees over the vault's lifetime.
+    pub total_fees_collected: u64,
+    /// Lamports withdrawn by the authority.
+    pub total_fees_withdrawn: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl FeeVault {
+    pub const SEED: &'static [u8] = b"vault";
+
+    pub fn address() -> Pubkey {
+        Pubkey::find_program_address(&[Self::SEED], &crate::ID).0
+    }
+
+    /// Address of this vault, derived from its stored bump.
+    pub fn derived_address(&self) -> Result<Pubkey> {
+        Pubkey::create_program_address(&[Self::SEED, &[self.bump]], &crate::ID)
+            .map_err(|_| error!(YourFunError::InvalidFeeReceiver))
+    }
+}
+
+/// One step of a `FeeSchedule`: `fee_lamports` applies once `threshold`
+/// humans are verified.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug, InitSpace)]
+pub struct FeeTier {
+    pub threshold: u64,
+    pub fee_lamports: u64,
+}
+
+/// Verification fee tiered by `total_verified_humans`. The first
+/// `tier_count` entries are used; the first starts at 0 and thresholds
+/// ascend, so every count falls in exactly one tier.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug, InitSpace)]
+pub struct FeeSchedule {
+    pub tiers: [FeeTier; 4],
+    pub tier_count: u8,
+}
+
+impl FeeSchedule {
+    pub const MAX_TIERS: usize = 4;
+
+    /// The configured tiers.
+    pub fn tiers(&self) -> &[FeeTier] {
+        &self.tiers[..(self.tier_count as usize).min(Self::MAX_TIERS)]
+    }
+
+    /// Fee of the highest tier whose threshold `total_verified` has
+    /// reached, or `None` for an empty schedule.
+    pub fn fee_for(&self, total_verified: u64) -> Option<u64> {
+        self.tiers()
+            .iter()
+            .rev()
+            .find(|tier| total_verified >= tier.threshold)
+            .map(|tier| tier.fee_lamports)
+    }
+}
+
+/// Changes to the parameters fixed at `initialize`, plus the config timelock
+/// and fee schedule; `None` leaves a value unchanged.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct ConfigUpdate {
+    pub verification_fee_lamports: Option<u64>,
+    pub min_behavioral_score: Option<u8>,
+    pub max_session_duration: Option<i64>,
+    pub max_interactions_per_session: Option<u32>,
+    pub config_timelock_seconds: Option<i64>,
+    pub fee_schedule: Option<FeeSchedule>,
+}
+
+impl ConfigUpdate {
+    /// `PlatformRegistry::CONFIG_*` bits of the values this update sets.
+    pub fn field_mask(&self) -> u32 {
+        let mut mask = 0;
+        if self.verification_fee_lamports.is_some() || self.fee_schedule.is_some() {
+            mask |= PlatformRegistry::CONFIG_FEES;
+        }
+        if self.min_behavioral_score.is_some() {
+            mask |= PlatformRegistry::CONFIG_VERIFICATION;
+        }
+        if self.max_session_duration.is_some() || self.max_interactions_per_session.is_some() {
+            mask |= PlatformRegistry::CONFIG_SESSIONS;
+        }
+        if self.config_timelock_seconds.is_some() {
+            mask |= PlatformRegistry::CONFIG_TIMELOCK;
+        }
+        mask
+    }
+}
+
+/// A config update queued behind the registry's timelock. There is at most
+/// one; it is closed when executed or cancelled.
+/// Seeds: ["pending_config"]
+#[account]
+#[derive(InitSpace)]
+pub struct PendingConfig {
+    /// The values to apply.
+    pub update: ConfigUpdate,
+    /// Unix timestamp of the queueing.
+    pub queued_at: i64,
+    /// Unix timestamp from which `execute_config_update` applies it.
+    pub effective_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl PendingConfig {
+    pub const SEED: &'static [u8] = b"pending_config";
+}
+
+/// Record of the insurance payout made for one approved appeal, which it
+/// keys so no appeal is paid twice.
+/// Seeds: ["insurance_claim", appeal.key().as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceClaim {
+    /// The appeal the payout compensates.
+    pub appeal: Pubkey,
+    /// Wallet that received the payout.
+    pub recipient: Pubkey,
+    /// Lamports paid.
+    pub amount: u64,
+    /// Hash of the off-chain claim file.
+    pub claim_ref_hash: [u8; 32],
+    /// Unix timestamp of the payout.
+    pub paid_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl InsuranceClaim {
+    pub const SEED: &'static [u8] = b"insurance_claim";
+}
+
+/// Donated lamports that pay the verification fee of approved scholarship
+/// registrations. Created by the first donation.
+/// Seeds: ["scholarship_pool"]
+#[account]
+#[derive(InitSpace)]
+pub struct ScholarshipPool {
+    /// Lamports donated over the pool's lifetime.
+    pub total_donated: u64,
+    /// Lamports paid out as verification fees.
+    pub total_disbursed: u64,
+    /// Registrations whose fee the pool paid.
+    pub scholarships_used: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl ScholarshipPool {
+    pub const SEED: &'static [u8] = b"scholarship_pool";
+}
+
+/// Approval for one wallet to register with its fee paid by the scholarship
+/// pool. Consumed approvals stay on chain so they cannot be reissued.
+/// Seeds: ["scholarship", wallet.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct ScholarshipApproval {
+    /// The approved wallet.
+    pub wallet: Pubkey,
+    /// The authority or program officer that approved it.
+    pub approved_by: Pubkey,
+    /// Unix timestamp of the latest approval.
+    pub approved_at: i64,
+    /// The approval can be used until this Unix timestamp, exclusive.
+    pub expires_at: i64,
+    /// Whether the wallet has registered with this approval.
+    pub consumed: bool,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl ScholarshipApproval {
+    pub const SEED: &'static [u8] = b"scholarship";
+
+    /// Seconds an approval stays usable.
+    pub const VALIDITY: i64 = 30 * 86_400;
+}
+
+/// A limited-use promo code discounting the verification fee. Only the
+/// code's hash is stored; registrants present the code itself.
+/// Seeds: ["promo", code_hash.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct Promo {
+    /// SHA-256 hash of the code.
+    pub code_hash: [u8; 32],
+    /// Discount on the verification fee, in basis points.
+    pub discount_bps: u16,
+    /// Redemptions allowed in total.
+    pub max_uses: u32,
+    /// Redemptions still available.
+    pub remaining_uses: u32,
+    /// The code can be redeemed until this Unix timestamp, exclusive.
+    pub expires_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Promo {
+    pub const SEED: &'static [u8] = b"promo";
+
+    /// The verification fee after the discount, rounding the discount down.
+    pub fn discounted(&self, fee: u64) -> u64 {
+        fee.saturating_sub(((fee as u128) * (self.discount_bps as u128) / MAX_BPS as u128) as u64)
+    }
+}
+
+/// Marks that a wallet redeemed a promo code, so it cannot redeem it again
+/// even after closing and re-creating its record.
+/// Seeds: ["promo_redemption", promo.as_ref(), wallet.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct PromoRedemption {
+    /// The promo redeemed.
+    pub promo: Pubkey,
+    /// The wallet that redeemed it.
+    pub wallet: Pubkey,
+    /// Unix timestamp of the redemption.
+    pub redeemed_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl PromoRedemption {
+    pub const SEED: &'static [u8] = b"promo_redemption";
+}
+
+/// A sponsor's campaign matching user-paid verification fees into the
+/// scholarship pool. The lamports held above rent are the matcher's escrow.
+/// Seeds: ["matching", matcher.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct MatchingCampaign {
+    /// Sponsor funding the escrow; the only key that may fund or reclaim it.
+    pub matcher: Pubkey,
+    /// Lamports matched per verification fee, in basis points of the fee.
+    pub ratio_bps: u16,
+    /// Most lamports the campaign matches over its lifetime.
+    pub cap_lamports: u64,
+    /// Unix timestamp at which matching stops and leftovers are reclaimable.
+    pub ends_at: i64,
+    /// Lamports matched so far.
+    pub total_matched: u64,
+    /// Lamports the matcher deposited over the campaign's lifetime.
+    pub total_funded: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl MatchingCampaign {
+    pub const SEED: &'static [u8] = b"matching";
+
+    pub fn is_active_at(&self, now: i64) -> bool {
+        now < self.ends_at
+    }
+
+    /// Lamports to match for a fee, before the escrow balance is considered:
+    /// the ratio's share of `fee`, limited to what remains under the cap.
+    pub fn match_for(&self, fee: u64) -> u64 {
+        let matched = ((fee as u128) * (self.ratio_bps as u128) / MAX_BPS as u128) as u64;
+        matched.min(self.cap_lamports.saturating_sub(self.total_matched))
+    }
+}
+
+/// One raffle epoch. Every human closing a session during the epoch gets a
+/// single ticket, stored across RaffleTicketPage accounts. Once the epoch
+/// ends `commit_raffle` fixes a future slot, whose hash `draw_raffle` turns
+/// into the winning ticket. The prize is whatever was funded onto this
+/// account before the draw.
+/// Seeds: ["raffle", &epoch.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct RaffleEpoch {
+    /// Epoch index, `unix_timestamp / DURATION`.
+    pub epoch: u64,
+    /// Tickets issued so far.
+    pub ticket_count: u64,
+    /// Ticket pages created so far.
+    pub page_count: u32,
+    /// Lamports funded as the prize.
+    pub prize_lamports: u64,
+    /// Slot whose hash seeds the draw (0 = not committed).
+    pub draw_slot: u64,
+    /// Index of the winning ticket, valid once drawn.
+    pub winner_index: u64,
+    /// Holder of the winning ticket (`Pubkey::default()` = not drawn).
+    pub winner: Pubkey,
+    /// Whether the winner has claimed the prize.
+    pub claimed: bool,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl RaffleEpoch {
+    pub const SEED: &'static [u8] = b"raffle";
+    /// Length of a raffle epoch in seconds.
+    pub const DURATION: i64 = 7 * 86_400;
+    /// Slots between the commitment and the slot whose hash is drawn from.
+    pub const DRAW_DELAY_SLOTS: u64 = 32;
+    /// Slots for which SlotHashes keeps a hash; a commitment not drawn
+    /// within them must be renewed.
+    pub const SLOT_HASHES_DEPTH: u64 = 512;
+
+    /// The raffle epoch containing `now`.
+    pub fn epoch_at(now: i64) -> u64 {
+        now.max(0) as u64 / Self::DURATION as u64
+    }
+
+    /// Unix timestamp at which the epoch ends.
+    pub fn ends_at(&self) -> i64 {
+        ((self.epoch + 1) * Self::DURATION as u64) as i64
+    }
+
+    pub fn is_drawn(&self) -> bool {
+        self.winner != Pubkey::default()
+    }
+}
+
+/// One page of a raffle epoch's tickets, each the wallet of an entrant.
+/// Seeds: ["raffle_page", &epoch.to_le_bytes(), &page.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct RaffleTicketPage {
+    /// The raffle epoch the tickets belong to.
+    pub epoch: u64,
+    /// Position of this page; it holds tickets from
+    /// `page * TICKETS_PER_PAGE` on.
+    pub page: u32,
+    /// Entrant wallets in ticket order.
+    #[max_len(128)]
+    pub tickets: Vec<Pubkey>,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl RaffleTicketPage {
+    pub const SEED: &'static [u8] = b"raffle_page";
+    /// Must match the `max_len` of `tickets`.
+    pub const TICKETS_PER_PAGE: usize = 128;
+
+    pub fn is_full(&self) -> bool {
+        self.tickets.len() >= Self::TICKETS_PER_PAGE
+    }
+}
+
+/// Stake-weighted verifier election. Each epoch candidates are nominated and
+/// backed with locked governance tokens; once it ends `elect_verifiers`
+/// seats the top candidates and the next epoch begins.
+/// Seeds: ["election"]
+#[account]
+#[derive(InitSpace)]
+pub struct Election {
+    /// Mint of the governance token used for voting.
+    pub governance_mint: Pubkey,
+    /// Token account holding every locked vote.
+    pub vault: Pubkey,
+    /// Length of an election epoch in seconds.
+    pub epoch_duration: i64,
+    /// Number of verifiers seated per election.
+    pub seats: u8,
+    /// Epoch currently open for nominations and votes.
+    pub current_epoch: u64,
+    /// Unix timestamp at which the current epoch opened.
+    pub epoch_started_at: i64,
+    /// Candidates nominated in the current epoch.
+    pub candidate_count: u32,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+    /// Bump seed of the vault PDA.
+    pub vault_bump: u8,
+}
+
+impl Election {
+    pub const SEED: &'static [u8] = b"election";
+    pub const VAULT_SEED: &'static [u8] = b"election_vault";
+    /// Upper bound on seats, keeping `elect_verifiers` within compute limits.
+    pub const MAX_SEATS: u8 = 16;
+    /// Upper bound on candidates per epoch, for the same reason.
+    pub const MAX_CANDIDATES: u32 = 32;
+
+    pub fn epoch_ends_at(&self) -> Result<i64> {
+        self.epoch_started_at
+            .checked_add(self.epoch_duration)
+            .ok_or(error!(YourFunError::NumericalOverflow))
+    }
+}
+
+/// A verifier candidate in one election epoch.
+/// Seeds: ["candidate", &epoch.to_le_bytes(), candidate.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct Candidate {
+    /// The nominated verifier key.
+    pub candidate: Pubkey,
+    /// Epoch of the nomination.
+    pub epoch: u64,
+    /// Governance tokens locked in support of this candidate.
+    pub total_stake: u64,
+    /// Position among the epoch's nominations; the earlier nomination wins
+    /// a tie on stake.
+    pub nomination_order: u32,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl Candidate {
+    pub const SEED: &'static [u8] = b"candidate";
+}
+
+/// Governance tokens one voter locked for one candidate in one epoch.
+/// Seeds: ["vote", &epoch.to_le_bytes(), candidate.as_ref(), voter.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct VoteEscrow {
+    pub voter: Pubkey,
+    pub candidate: Pubkey,
+    pub epoch: u64,
+    /// Tokens locked; withdrawable once the epoch has been elected.
+    pub amount: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl VoteEscrow {
+    pub const SEED: &'static [u8] = b"vote";
+}
+
+/// Approval state of a verifier key, maintained by the election.
+/// Seeds: ["verifier", verifier.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct VerifierAccount {
+    /// The verifier key.
+    pub verifier: Pubkey,
+    /// Whether the verifier may currently verify humans.
+    pub is_active: bool,
+    /// Epoch of the election that last seated this verifier.
+    pub elected_epoch: u64,
+    /// Stake behind the verifier at that election.
+    pub stake: u64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl VerifierAccount {
+    pub const SEED: &'static [u8] = b"verifier";
+}
+
+/// Lifetime activity of one verifier, for quality monitoring. Created on the
+/// verifier's first verification or rejection and never closed, so it stays
+/// readable after the verifier is retired.
+/// Seeds: ["verifier_stats", verifier.as_ref()]
+#[account]
+#[derive(InitSpace)]
+pub struct VerifierStats {
+    /// The verifier key.
+    pub verifier: Pubkey,
+    /// Successful verifications performed.
+    pub verifications: u64,
+    /// Verifications rejected through `reject_verification`.
+    pub rejections: u64,
+    /// Sum of the behavioral scores granted; divide by `verifications` for
+    /// the average.
+    pub behavioral_score_sum: u64,
+    /// Unix timestamp of the verifier's most recent verification or rejection.
+    pub last_active_at: i64,
+    /// Bump seed for PDA derivation.
+    pub bump: u8,
+}
+
+impl VerifierStats {
+    pub const SEED: &'static [u8] = b"verifier_stats";
+
+    pub fn record_verification(&mut self, behavioral_score: u8, now: i64) -> Result<()> {
+        self.verifications = self
+            .verifications
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        self.behavioral_score_sum = self
+            .behavioral_score_sum
+            .checked_add(behavioral_score as u64)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        self.last_active_at = now;
+        Ok(())
+    }
+
+    pub fn record_rejection(&mut self, now: i64) -> Result<()> {
+        self.rejections = self
+            .rejections
+            .checked_add(1)
+            .ok_or(YourFunError::NumericalOverflow)?;
+        self.last_active_at = now;
+        Ok(())
+    }
+
+    /// Average behavioral score granted, rounded down, for readers of the
+    /// account. The program itself only keeps the sum and count.
+    pub fn average_behavioral_score(&self) -> Option<u64> {
+        self.behavioral_score_sum.checked_div(self.verifications)
+    }
+}
+
+/// A single recorded interaction within a session.
+/// Seeds: ["interaction", session.key().as_ref(), &interaction_index.to_le_bytes()]
+#[account]
+#[derive(InitSpace)]
+pub struct InteractionLog {
+    /// Reference to the parent session.
+    pub session: Pubkey,
+    /// The wallet that created this interaction.
+    pub user: Pubkey,
+    /// Index of this interaction within its session.
     pub interaction_index: u32,
     /// Unix timestamp of when the interaction occurred.
     pub timestamp: i64,
@@ -130,9 +2883,499 @@ pub struct InteractionLog {
     pub duration_seconds: u32,
     /// Bump seed for PDA derivation.
     pub bump: u8,
+    /// Hash of where the content is stored, such as an IPFS CID or an
+    /// Arweave transaction id (all zeros = not provided).
+    pub content_uri_hash: [u8; 32],
+    /// Size in bytes of the stored content (0 = not provided).
+    pub content_size: u32,
+    /// The session's `chain_head` before this interaction was chained.
+    pub prev_chain_head: [u8; 32],
+    /// Set when the owner recorded the interaction in privacy mode: the
+    /// content hash may be all zeros and the score was reduced to
+    /// `private_score_bps`. There is no content to check such a log against.
+    pub private: bool,
 }
 
 impl InteractionLog {
     pub const SEED: &'static [u8] = b"interaction";
-    pub const SIZE: usize = 8 + 32 + 32 + 4 + 8 + 32 + 1 + 1 + 4 + 1;
+    #[deprecated(note = "use 8 + INIT_SPACE")]
+    pub const SIZE: usize = 8 + Self::INIT_SPACE;
+
+    /// The stored interaction type as a typed value.
+    pub fn kind(&self) -> Result<InteractionType> {
+        InteractionType::try_from(self.interaction_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{HUMAN_RECORD_FLAGS_OFFSET, REGISTRY_TREASURY_OFFSET, SESSION_FLAGS_OFFSET};
+
+    /// Decodes a `T` from zeroed bytes: every number 0, every flag false and
+    /// every vector or string empty.
+    fn zeroed<T: AnchorDeserialize>(len: usize) -> T {
+        T::deserialize(&mut &vec![0u8; len][..]).expect("zeroed bytes decode")
+    }
+
+    /// Length of the account as written on chain, discriminator included.
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).expect("account serializes");
+        data.len()
+    }
+
+    macro_rules! assert_fixed_layouts {
+        ($($account:ty),* $(,)?) => {$(
+            let account: $account = zeroed(<$account>::INIT_SPACE);
+            assert_eq!(
+                serialized_len(&account),
+                8 + <$account>::INIT_SPACE,
+                "{} serializes to a different length than its space",
+                stringify!($account),
+            );
+        )*};
+    }
+
+    /// Checks that each setter flips exactly its own bit in `flags` and that
+    /// the getter reads it back.
+    macro_rules! assert_flag_accessors {
+        ($account:ty { $($flag:ident => $get:ident, $set:ident;)* }) => {$(
+            let mut account: $account = zeroed(<$account>::INIT_SPACE);
+            account.$set(true);
+            assert!(account.$get());
+            assert_eq!(account.flags, <$account>::$flag, stringify!($set));
+            account.flags = u16::MAX;
+            account.$set(false);
+            assert!(!account.$get());
+            assert_eq!(account.flags, !<$account>::$flag, stringify!($set));
+        )*};
+    }
+
+    #[test]
+    fn fixed_layouts_match_init_space() {
+        assert_fixed_layouts!(
+            PlatformRegistry,
+            StatsBucket,
+            EpochStats,
+            RegionStats,
+            HumanRecord,
+            CompromisedFingerprint,
+            LegacyImport,
+            DocumentIssuer,
+            DocumentAttestation,
+            Appeal,
+            Tombstone,
+            SessionTemplate,
+            TopicProgress,
+            WaitlistEntry,
+            Topic,
+            SessionAccount,
+            PersonalityTreasury,
+            Milestone,
+            Vesting,
+            PersonalityConfig,
+            PersonalityStats,
+            AdminAction,
+            InviteEntry,
+            InsuranceFund,
+            FeeVault,
+            InsuranceClaim,
+            ScholarshipPool,
+            ScholarshipApproval,
+            Promo,
+            PromoRedemption,
+            MatchingCampaign,
+            RaffleEpoch,
+            Election,
+            Candidate,
+            VoteEscrow,
+            VerifierAccount,
+            VerifierStats,
+            InteractionLog,
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_size_aliases_match_init_space() {
+        assert_eq!(PlatformRegistry::SIZE, 8 + PlatformRegistry::INIT_SPACE);
+        assert_eq!(HumanRecord::SIZE, 8 + HumanRecord::INIT_SPACE);
+        assert_eq!(SessionAccount::SIZE, 8 + SessionAccount::INIT_SPACE);
+        assert_eq!(InteractionLog::SIZE, 8 + InteractionLog::INIT_SPACE);
+    }
+
+    #[test]
+    fn variable_layouts_at_capacity_match_init_space() {
+        let mut handle: Handle = zeroed(Handle::INIT_SPACE);
+        handle.handle = "a".repeat(Handle::MAX_LEN);
+        assert_eq!(serialized_len(&handle), 8 + Handle::INIT_SPACE);
+
+        let mut index: ActiveSessionIndex = zeroed(ActiveSessionIndex::INIT_SPACE);
+        index.sessions = vec![Pubkey::new_unique(); ActiveSessionIndex::CAPACITY];
+        assert_eq!(serialized_len(&index), 8 + ActiveSessionIndex::INIT_SPACE);
+
+        let mut highlights: Highlights = zeroed(Highlights::INIT_SPACE);
+        highlights.entries = vec![zeroed(Highlight::INIT_SPACE); Highlights::CAPACITY];
+        assert_eq!(serialized_len(&highlights), 8 + Highlights::INIT_SPACE);
+
+        let mut pending: PendingConfig = zeroed(PendingConfig::INIT_SPACE);
+        pending.update = ConfigUpdate {
+            verification_fee_lamports: Some(0),
+            min_behavioral_score: Some(0),
+            max_session_duration: Some(0),
+            max_interactions_per_session: Some(0),
+            config_timelock_seconds: Some(0),
+            fee_schedule: Some(FeeSchedule::default()),
+        };
+        assert_eq!(serialized_len(&pending), 8 + PendingConfig::INIT_SPACE);
+
+        let mut page: RaffleTicketPage = zeroed(RaffleTicketPage::INIT_SPACE);
+        page.tickets = vec![Pubkey::new_unique(); RaffleTicketPage::TICKETS_PER_PAGE];
+        assert_eq!(serialized_len(&page), 8 + RaffleTicketPage::INIT_SPACE);
+    }
+
+    #[test]
+    fn fingerprint_data_matches_space_for() {
+        for len in [0, 1, 32, 128] {
+            let fingerprint = FingerprintData {
+                human_record: Pubkey::new_unique(),
+                data: vec![0xab; len],
+                bump: 255,
+            };
+            assert_eq!(serialized_len(&fingerprint), 8 + FingerprintData::space_for(len));
+        }
+    }
+
+    /// First byte at which two serializations of the same account differ.
+    fn changed_offset<T: AccountSerialize>(before: &T, after: &T) -> usize {
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+        before.try_serialize(&mut a).expect("account serializes");
+        after.try_serialize(&mut b).expect("account serializes");
+        a.iter().zip(&b).position(|(x, y)| x != y).expect("accounts differ")
+    }
+
+    /// Whether `source` assigns exactly `offset` to the constant `name`.
+    fn declares(source: &str, name: &str, offset: usize) -> bool {
+        let assignment = format!("{name} = {offset}");
+        source.match_indices(&assignment).any(|(at, _)| {
+            !source[at + assignment.len()..].starts_with(|c: char| c.is_ascii_digit())
+        })
+    }
+
+    #[test]
+    fn core_config_rejects_a_score_threshold_above_the_maximum() {
+        assert!(PlatformRegistry::validate_core_config(MAX_SCORE, 60, 1).is_ok());
+        assert_eq!(
+            PlatformRegistry::validate_core_config(MAX_SCORE + 1, 60, 1).unwrap_err(),
+            YourFunError::InvalidScoreConfig.into()
+        );
+    }
+
+    #[test]
+    fn fee_bounds_distinguish_inverted_bounds_from_an_out_of_range_fee() {
+        assert!(PlatformRegistry::validate_fee_bounds(10, 10, 10).is_ok());
+        assert!(PlatformRegistry::validate_fee_bounds(0, u64::MAX, u64::MAX).is_ok());
+        assert_eq!(
+            PlatformRegistry::validate_fee_bounds(11, 10, 10).unwrap_err(),
+            YourFunError::InvalidFeeBounds.into()
+        );
+        for fee in [9, 21] {
+            assert_eq!(
+                PlatformRegistry::validate_fee_bounds(10, 20, fee).unwrap_err(),
+                YourFunError::FeeOutOfBounds.into()
+            );
+        }
+    }
+
+    #[test]
+    fn promo_discount_never_exceeds_the_fee() {
+        let mut promo: Promo = zeroed(Promo::INIT_SPACE);
+        promo.discount_bps = 2_500;
+        assert_eq!(promo.discounted(1_000), 750);
+        assert_eq!(promo.discounted(u64::MAX), u64::MAX - u64::MAX / 4);
+        promo.discount_bps = MAX_BPS;
+        assert_eq!(promo.discounted(1_000), 0);
+        promo.discount_bps = u16::MAX;
+        assert_eq!(promo.discounted(1_000), 0);
+    }
+
+    #[test]
+    fn active_humans_follow_verification_and_revocation() {
+        let mut registry: PlatformRegistry = zeroed(PlatformRegistry::INIT_SPACE);
+        let mut first: HumanRecord = zeroed(HumanRecord::INIT_SPACE);
+        let mut second: HumanRecord = zeroed(HumanRecord::INIT_SPACE);
+
+        for _ in 0..2 {
+            registry.count_active_human(&mut first).unwrap();
+            registry.count_active_human(&mut second).unwrap();
+            // Renewing an already counted record does not count it twice.
+            registry.count_active_human(&mut first).unwrap();
+            assert_eq!(registry.active_humans, 2);
+
+            registry.uncount_active_human(&mut first);
+            registry.uncount_active_human(&mut first);
+            assert_eq!(registry.active_humans, 1);
+            registry.uncount_active_human(&mut second);
+            assert_eq!(registry.active_humans, 0);
+            assert!(!first.is_active_counted() && !second.is_active_counted());
+        }
+
+        // A count out of step with the records saturates instead of failing.
+        registry.count_active_human(&mut first).unwrap();
+        registry.active_humans = 0;
+        registry.uncount_active_human(&mut first);
+        assert_eq!(registry.active_humans, 0);
+        assert!(!first.is_active_counted());
+    }
+
+    #[test]
+    fn verification_status_changes_exactly_at_each_phase_boundary() {
+        let mut registry: PlatformRegistry = zeroed(PlatformRegistry::INIT_SPACE);
+        registry.verification_validity = 1_000;
+        registry.verification_grace_period = 500;
+        let mut record: HumanRecord = zeroed(HumanRecord::INIT_SPACE);
+        let verified_at = 10_000;
+        assert_eq!(record.verification_status(&registry, verified_at), VerificationStatus::Inactive);
+
+        record.set_active(true);
+        record.verified_at = verified_at;
+        let (valid_until, grace_until) = (verified_at + 1_000, verified_at + 1_500);
+        let phases = [
+            (verified_at, VerificationStatus::Active),
+            (valid_until - 1, VerificationStatus::Active),
+            (valid_until, VerificationStatus::GracePeriod),
+            (grace_until - 1, VerificationStatus::GracePeriod),
+            (grace_until, VerificationStatus::Lapsed),
+        ];
+        for (now, status) in phases {
+            assert_eq!(record.verification_status(&registry, now), status, "at {now}");
+        }
+
+        // Without a validity period a verification never ages out.
+        registry.verification_validity = 0;
+        assert_eq!(record.verification_status(&registry, i64::MAX), VerificationStatus::Active);
+    }
+
+    #[test]
+    fn flag_accessors_round_trip_their_own_bit() {
+        assert_flag_accessors!(HumanRecord {
+            FLAG_ACTIVE => is_active, set_active;
+            FLAG_REGION_COUNTED => is_region_counted, set_region_counted;
+            FLAG_REVERIFY_REQUIRED => requires_reverification, set_requires_reverification;
+            FLAG_WAITLISTED => is_waitlisted, set_waitlisted;
+            FLAG_LIVENESS_LAPSED => is_liveness_lapsed, set_liveness_lapsed;
+            FLAG_ACTIVE_COUNTED => is_active_counted, set_active_counted;
+        });
+        assert_flag_accessors!(SessionAccount {
+            FLAG_ACTIVE => is_active, set_active;
+            FLAG_GOAL_MET => goal_met, set_goal_met;
+            FLAG_SETTLING => is_settling, set_settling;
+            FLAG_CONTESTED => is_contested, set_contested;
+            FLAG_RATED => is_rated, set_rated;
+        });
+    }
+
+    /// Asserts that `$enum` decodes exactly the bytes of `$variants`, each
+    /// back to itself, and rejects every other byte with `$error`.
+    macro_rules! assert_discriminants {
+        ($enum:ident, $error:ident, [$($variant:ident),*]) => {
+            let accepted = [$($enum::$variant),*];
+            for value in 0..=u8::MAX {
+                match accepted.iter().find(|variant| **variant as u8 == value) {
+                    Some(variant) => assert_eq!($enum::try_from(value).unwrap(), *variant),
+                    None => assert_eq!(
+                        $enum::try_from(value).unwrap_err(),
+                        YourFunError::$error.into(),
+                        "{} {value}",
+                        stringify!($enum)
+                    ),
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn typed_enums_decode_only_their_own_discriminants() {
+        assert_discriminants!(InteractionType, InvalidInteractionType, [Chat, Quiz, Exercise, Review]);
+        assert_discriminants!(VerificationLevel, InvalidVerificationLevel, [Basic, Enhanced, Full]);
+        assert_discriminants!(CloseReason, InvalidCloseReason, [None, Completed, Expired, Cancelled, ForceClosed]);
+
+        // An unverified record stores 0, which is no level rather than an
+        // invalid one.
+        let mut record: HumanRecord = zeroed(HumanRecord::INIT_SPACE);
+        assert_eq!(record.level().unwrap(), None);
+        record.verification_level = VerificationLevel::Full as u8;
+        assert_eq!(record.level().unwrap(), Some(VerificationLevel::Full));
+        record.verification_level = 4;
+        assert_eq!(record.level().unwrap_err(), YourFunError::InvalidVerificationLevel.into());
+    }
+
+    #[test]
+    fn personality_ratings_accumulate_within_range() {
+        let mut stats: PersonalityStats = zeroed(PersonalityStats::INIT_SPACE);
+        for rating in [PersonalityStats::MIN_RATING, 4, PersonalityStats::MAX_RATING] {
+            stats.record_rating(rating).unwrap();
+        }
+        assert_eq!((stats.rating_sum, stats.rating_count), (10, 3));
+
+        for rating in [0, PersonalityStats::MAX_RATING + 1] {
+            assert_eq!(stats.record_rating(rating).unwrap_err(), YourFunError::InvalidRating.into());
+        }
+        assert_eq!((stats.rating_sum, stats.rating_count), (10, 3));
+    }
+
+    #[test]
+    fn fee_schedule_switches_tiers_exactly_at_each_threshold() {
+        let tier = |threshold, fee_lamports| FeeTier { threshold, fee_lamports };
+        let mut schedule = FeeSchedule {
+            tiers: [tier(0, 0), tier(1_000, 10_000_000), tier(10_000, 50_000_000), tier(20_000, 1)],
+            tier_count: 3,
+        };
+        for (total_verified, fee) in [
+            (0, 0),
+            (999, 0),
+            (1_000, 10_000_000),
+            (9_999, 10_000_000),
+            (10_000, 50_000_000),
+            // The fourth tier is past `tier_count` and ignored.
+            (20_000, 50_000_000),
+            (u64::MAX, 50_000_000),
+        ] {
+            assert_eq!(schedule.fee_for(total_verified), Some(fee), "at {total_verified}");
+        }
+
+        schedule.tier_count = 0;
+        assert_eq!(schedule.fee_for(0), None);
+        assert_eq!(schedule.fee_for(u64::MAX), None);
+    }
+
+    #[test]
+    fn handles_must_be_short_lowercase_ascii() {
+        let max = "a".repeat(Handle::MAX_LEN);
+        for valid in ["abc", "user_01", "___", max.as_str()] {
+            assert!(Handle::is_valid(valid), "{valid:?} should be valid");
+        }
+
+        let too_long = "a".repeat(Handle::MAX_LEN + 1);
+        for invalid in [
+            "",
+            "ab",
+            too_long.as_str(),
+            "Alice",
+            "ALICE",
+            "caf\u{e9}",
+            "\u{0430}bc",
+            "\u{1f600}\u{1f600}",
+            "with space",
+            "dash-ed",
+        ] {
+            assert!(!Handle::is_valid(invalid), "{invalid:?} should be invalid");
+        }
+    }
+
+    #[test]
+    fn vesting_releases_nothing_before_the_cliff_and_everything_after_the_end() {
+        let mut vesting: Vesting = zeroed(Vesting::INIT_SPACE);
+        vesting.total_amount = 1_000_000;
+        vesting.start_at = 1_000;
+        vesting.cliff_at = 1_100;
+        vesting.vesting_duration = 1_000;
+
+        assert_eq!(vesting.vested_at(i64::MIN), 0);
+        assert_eq!(vesting.vested_at(1_099), 0);
+        // At the cliff, the time since the start vests at once.
+        assert_eq!(vesting.vested_at(1_100), 100_000);
+        assert_eq!(vesting.vested_at(1_500), 500_000);
+        assert_eq!(vesting.vested_at(1_999), 999_000);
+        assert_eq!(vesting.vested_at(2_000), 1_000_000);
+        assert_eq!(vesting.vested_at(5_000), 1_000_000);
+        assert_eq!(vesting.vested_at(i64::MAX), 1_000_000);
+
+        // Neither the elapsed time nor the product with the amount overflows.
+        vesting.total_amount = u64::MAX;
+        vesting.start_at = i64::MIN;
+        vesting.cliff_at = i64::MIN;
+        vesting.vesting_duration = i64::MAX;
+        assert_eq!(vesting.vested_at(i64::MAX), u64::MAX);
+        vesting.start_at = 0;
+        vesting.cliff_at = 0;
+        assert_eq!(vesting.vested_at(i64::MAX / 2), u64::MAX / 2 - 1);
+        assert_eq!(vesting.vested_at(i64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn escrow_bills_whole_minutes_up_to_the_escrow() {
+        let mut session: SessionAccount = zeroed(SessionAccount::INIT_SPACE);
+        session.created_at = 1_000;
+        session.escrow_rate_per_minute = 100;
+        session.escrow_lamports = 1_000;
+
+        for (ended_at, billed) in [
+            (0, 0),
+            (1_000, 0),
+            // Partial minutes are not billed.
+            (1_059, 0),
+            (1_060, 100),
+            (1_119, 100),
+            (1_120, 200),
+            // The tenth minute exhausts the escrow; later ones are free.
+            (1_540, 900),
+            (1_600, 1_000),
+            (1_660, 1_000),
+            (i64::MAX, 1_000),
+        ] {
+            assert_eq!(session.escrow_billed(ended_at), Some(billed), "at {ended_at}");
+        }
+
+        session.escrow_lamports = 0;
+        assert_eq!(session.escrow_billed(1_600), Some(0));
+
+        session.escrow_lamports = u64::MAX;
+        session.escrow_rate_per_minute = u64::MAX;
+        assert_eq!(session.escrow_billed(1_060), Some(u64::MAX));
+        assert_eq!(session.escrow_billed(1_120), None);
+    }
+
+    #[test]
+    fn sdk_offsets_match_borsh() {
+        let human: HumanRecord = zeroed(HumanRecord::INIT_SPACE);
+        let mut flagged = human.clone();
+        flagged.flags = HumanRecord::FLAG_ACTIVE;
+        assert_eq!(changed_offset(&human, &flagged), HUMAN_RECORD_FLAGS_OFFSET);
+
+        let session: SessionAccount = zeroed(SessionAccount::INIT_SPACE);
+        let mut flagged = session.clone();
+        flagged.flags = SessionAccount::FLAG_ACTIVE;
+        assert_eq!(changed_offset(&session, &flagged), SESSION_FLAGS_OFFSET);
+
+        let registry: PlatformRegistry = zeroed(PlatformRegistry::INIT_SPACE);
+        let mut with_treasury = registry.clone();
+        with_treasury.treasury = Pubkey::new_from_array([1; 32]);
+        assert_eq!(changed_offset(&registry, &with_treasury), REGISTRY_TREASURY_OFFSET);
+
+        let python = include_str!("../../../sdk-python/yourfun/solana.py");
+        let typescript = include_str!("../../../sdk/typescript/src/client.ts");
+        let api = include_str!("../../../api/src/services/solana.ts");
+        let offsets = [
+            ("HUMAN_RECORD_FLAGS_OFFSET", HUMAN_RECORD_FLAGS_OFFSET),
+            ("SESSION_FLAGS_OFFSET", SESSION_FLAGS_OFFSET),
+            ("REGISTRY_TREASURY_OFFSET", REGISTRY_TREASURY_OFFSET),
+        ];
+        for (name, offset) in offsets {
+            assert!(declares(python, name, offset), "python SDK {name}");
+            assert!(declares(typescript, name, offset), "typescript SDK {name}");
+        }
+        for (name, offset) in [offsets[0], offsets[2]] {
+            assert!(declares(api, name, offset), "api {name}");
+        }
+
+        let pda = include_str!("../../../sdk/typescript/src/pda.ts");
+        let buckets = StatsBucket::COUNT as usize;
+        for (source, sdk) in [(python, "python SDK"), (pda, "typescript SDK"), (api, "api")] {
+            assert!(declares(source, "STATS_BUCKET_COUNT", buckets), "{sdk} STATS_BUCKET_COUNT");
+            let day = EpochStats::DURATION as usize;
+            assert!(declares(source, "EPOCH_STATS_DURATION", day), "{sdk} EPOCH_STATS_DURATION");
+        }
+    }
 }
@@ -0,0 +1,304 @@
+//! Compute-unit benchmarks.
+//!
+//! Runs every instruction listed in `compute::CU_CEILINGS` against the SBF
+//! build under solana-program-test, prints a report of measured consumption
+//! against the ceilings, and fails if any instruction exceeds its ceiling.
+//!
+//! The SBF build is not produced by `cargo test`, so the benchmarks are
+//! ignored by default. Run them after `anchor build` with:
+//!
+//! ```text
+//! cargo test --features test-utils --test compute_units -- --ignored --nocapture
+//! ```
+//!
+//! CI runs them this way after `cargo build-sbf`; see
+//! `.github/workflows/program.yml`.
+
+use solana_program_test::{tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+
+use your_fun::client;
+use your_fun::compute::{cu_ceiling, CU_CEILINGS, RECORD_INTERACTION_BUDGET_CU};
+use your_fun::fixtures::{registered_challenge_response, FixtureBuilder};
+use your_fun::instructions::{VerifyEntry, MAX_VERIFY_BATCH};
+use your_fun::state::{HumanRecord, VerificationLevel};
+
+/// Timestamp the fixtures are written at.
+const NOW: i64 = 1_700_000_000;
+/// Bank clock time the benchmarks run at, just past the fixtures.
+const RUN_AT: i64 = NOW + 120;
+/// Region every benchmarked verification is recorded in.
+const REGION: u16 = 0;
+/// Score high enough for the logged path and low enough for the unlogged one.
+const LOG_THRESHOLD: u8 = 50;
+
+/// Wallets the benchmarks sign with; all are funded system accounts.
+struct Wallets {
+    authority: Keypair,
+    user: Keypair,
+    newcomer: Keypair,
+    pending: Vec<Keypair>,
+}
+
+async fn start(wallets: &Wallets) -> ProgramTestContext {
+    let mut builder = FixtureBuilder::new()
+        .at(NOW)
+        .with_authority(wallets.authority.pubkey())
+        .with_registry(|registry| registry.log_threshold_score = LOG_THRESHOLD)
+        .with_region_stats(REGION)
+        .with_verified_human(wallets.user.pubkey())
+        .with_open_session();
+    for pending in &wallets.pending {
+        builder = builder.with_registered_human(pending.pubkey());
+    }
+
+    let mut program_test = ProgramTest::new("your_fun", your_fun::ID, None);
+    program_test.prefer_bpf(true);
+    for fixture in builder.build() {
+        program_test.add_account(
+            fixture.address,
+            Account {
+                lamports: fixture.lamports,
+                data: fixture.data,
+                owner: fixture.owner,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+    let signers = [&wallets.authority, &wallets.user, &wallets.newcomer];
+    for wallet in signers.into_iter().chain(&wallets.pending) {
+        program_test.add_account(
+            wallet.pubkey(),
+            Account::new(10 * LAMPORTS_PER_SOL, 0, &system_program::ID),
+        );
+    }
+
+    let mut context = program_test.start_with_context().await;
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = RUN_AT;
+    context.set_sysvar(&clock);
+    context
+}
+
+fn transaction(context: &ProgramTestContext, ix: Instruction, signers: &[&Keypair]) -> Transaction {
+    // Lift the default 200k limit so instructions over it are measured
+    // rather than aborted; the budget instruction itself costs 150 units.
+    let instructions = [ComputeBudgetInstruction::set_compute_unit_limit(1_400_000), ix];
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    )
+}
+
+fn fits_in_packet(tx: &Transaction) -> bool {
+    1 + tx.signatures.len() * 64 + tx.message.serialize().len() <= PACKET_DATA_SIZE
+}
+
+/// Simulates `tx` and returns the units it consumed, failing on any error.
+async fn measure(context: &mut ProgramTestContext, name: &str, tx: Transaction) -> u64 {
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    let details = simulation.simulation_details.expect("simulation details");
+    match simulation.result {
+        Some(Ok(())) => details.units_consumed,
+        other => panic!("{name} failed: {other:?}\n{}", details.logs.join("\n")),
+    }
+}
+
+/// Measures each of `names`, all of which must be in `CU_CEILINGS`.
+async fn measure_each(names: &[&'static str]) -> Vec<(&'static str, u64)> {
+    let wallets = Wallets {
+        authority: Keypair::new(),
+        user: Keypair::new(),
+        newcomer: Keypair::new(),
+        pending: (0..=MAX_VERIFY_BATCH).map(|_| Keypair::new()).collect(),
+    };
+    let mut context = start(&wallets).await;
+    let authority = wallets.authority.pubkey();
+    let user = wallets.user.pubkey();
+    let verify_entry = |wallet: &Pubkey| VerifyEntry {
+        challenge_response: registered_challenge_response(wallet),
+        behavioral_score: 100,
+        verification_level: VerificationLevel::Basic as u8,
+        region_code: REGION,
+        attribute_commitments: [[0u8; 32]; HumanRecord::MAX_ATTRIBUTES],
+        supervisor: Pubkey::default(),
+    };
+    let fingerprint_hash = |wallet: &Pubkey| hash(&wallet.to_bytes()).to_bytes();
+
+    let mut measured = Vec::new();
+    for &name in names {
+        let tx = match name {
+            "register_human" => transaction(
+                &context,
+                client::build_register_human_ix(
+                    &wallets.newcomer.pubkey(),
+                    &authority,
+                    false,
+                    false,
+                    None,
+                    None,
+                    [7u8; 32],
+                    vec![0x5a; 64],
+                ),
+                &[&wallets.newcomer],
+            ),
+            "verify_human" => {
+                let (single, _) = wallets.pending.split_last().unwrap();
+                let wallet = single.pubkey();
+                let entry = verify_entry(&wallet);
+                transaction(
+                    &context,
+                    client::build_verify_human_ix(
+                        &authority,
+                        false,
+                        &wallet,
+                        None,
+                        &fingerprint_hash(&wallet),
+                        entry.challenge_response,
+                        entry.behavioral_score,
+                        entry.verification_level,
+                        entry.region_code,
+                        entry.attribute_commitments,
+                        entry.supervisor,
+                        None,
+                        RUN_AT,
+                    ),
+                    &[&wallets.authority],
+                )
+            }
+            "verify_humans_batch" => {
+                // The largest batch that still fits in one transaction.
+                let (_, batch) = wallets.pending.split_last().unwrap();
+                (1..=batch.len())
+                    .rev()
+                    .map(|len| {
+                        let entries = batch[..len]
+                            .iter()
+                            .map(|wallet| {
+                                let wallet = wallet.pubkey();
+                                (wallet, fingerprint_hash(&wallet), verify_entry(&wallet))
+                            })
+                            .collect();
+                        let ix = client::build_verify_humans_batch_ix(
+                            &authority,
+                            false,
+                            entries,
+                            RUN_AT,
+                        );
+                        transaction(&context, ix, &[&wallets.authority])
+                    })
+                    .find(fits_in_packet)
+                    .unwrap()
+            }
+            "create_session" => transaction(
+                &context,
+                client::build_create_session_ix(
+                    &user,
+                    None,
+                    1,
+                    0,
+                    [2u8; 32],
+                    0,
+                    0,
+                    false,
+                    None,
+                    0,
+                    false,
+                    RUN_AT,
+                ),
+                &[&wallets.user],
+            ),
+            "record_interaction" => transaction(
+                &context,
+                client::build_record_interaction_ix(
+                    &user,
+                    0,
+                    0,
+                    [3u8; 32],
+                    1,
+                    LOG_THRESHOLD + 30,
+                    60,
+                    [0u8; 32],
+                    0,
+                    false,
+                    false,
+                    RUN_AT,
+                ),
+                &[&wallets.user],
+            ),
+            "record_unlogged_interaction" => transaction(
+                &context,
+                client::build_record_unlogged_interaction_ix(
+                    &user,
+                    0,
+                    [4u8; 32],
+                    0,
+                    LOG_THRESHOLD - 40,
+                    30,
+                    false,
+                    RUN_AT,
+                ),
+                &[&wallets.user],
+            ),
+            other => panic!("no benchmark for {other}; add one alongside its ceiling"),
+        };
+        measured.push((name, measure(&mut context, name, tx).await));
+    }
+    measured
+}
+
+fn run<T>(future: impl std::future::Future<Output = T>) -> T {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run after `anchor build`"]
+fn instructions_stay_under_their_ceilings() {
+    let names: Vec<_> = CU_CEILINGS.iter().map(|&(name, _)| name).collect();
+    let measured = run(measure_each(&names));
+
+    println!("{:<30} {:>10} {:>10} {:>6}", "instruction", "consumed", "ceiling", "used");
+    let mut over = Vec::new();
+    for &(name, units) in &measured {
+        let ceiling = cu_ceiling(name).unwrap() as u64;
+        println!(
+            "{name:<30} {units:>10} {ceiling:>10} {:>5}%",
+            units * 100 / ceiling
+        );
+        if units > ceiling {
+            over.push(name);
+        }
+    }
+    assert!(over.is_empty(), "over their compute-unit ceiling: {over:?}");
+}
+
+/// The SBF build must be made without `verbose-logs`, as the budget assumes.
+#[test]
+#[ignore = "needs the SBF build in target/deploy; run after `anchor build`"]
+fn record_interaction_stays_within_its_budget() {
+    let (_, units) = run(measure_each(&["record_interaction"]))[0];
+    println!("record_interaction: {units} of {RECORD_INTERACTION_BUDGET_CU} units");
+    assert!(
+        units <= RECORD_INTERACTION_BUDGET_CU as u64,
+        "record_interaction used {units} units, over its {RECORD_INTERACTION_BUDGET_CU} budget"
+    );
+}
@@ -6,6 +6,7 @@ Handles instruction creation, PDA derivation, and transaction serialization.
 from __future__ import annotations
 import hashlib
 import struct
+import time
 from typing import Optional
 
 from solders.pubkey import Pubkey
@@ -23,6 +24,28 @@ SEED_REGISTRY = b"registry"
 SEED_HUMAN = b"human"
 SEED_SESSION = b"session"
 SEED_INTERACTION = b"interaction"
+SEED_FINGERPRINT = b"fingerprint"
+SEED_TOMBSTONE = b"tombstone"
+SEED_COMPROMISED = b"compromised"
+SEED_INVITE = b"invite"
+SEED_INSURANCE_FUND = b"insurance_fund"
+SEED_STATS = b"stats"
+SEED_PERSONALITY = b"personality"
+SEED_EPOCH_STATS = b"epoch_stats"
+
+# Number of StatsBucket shards the session counters are spread over.
+STATS_BUCKET_COUNT = 16
+
+# Length in seconds of the day an EpochStats account counts activity for.
+EPOCH_STATS_DURATION = 86400
+
+SYSVAR_INSTRUCTIONS_ID = Pubkey.from_string("Sysvar1nstructions1111111111111111111111111")
+
+# Byte offsets of the packed u16 `flags` fields, discriminator included.
+# Checked against the program's Borsh layout by its unit tests.
+HUMAN_RECORD_FLAGS_OFFSET = 113
+SESSION_FLAGS_OFFSET = 104
+REGISTRY_TREASURY_OFFSET = 905
 
 
 class SolanaClient:
@@ -69,35 +92,113 @@ class SolanaClient:
             [SEED_INTERACTION, bytes(session), index_bytes], self._program_id
         )
 
+    def derive_fingerprint_address(self, human_record: Pubkey) -> tuple[Pubkey, int]:
+        return Pubkey.find_program_address(
+            [SEED_FINGERPRINT, bytes(human_record)], self._program_id
+        )
+
+    def derive_tombstone_address(self, wallet: Pubkey) -> tuple[Pubkey, int]:
+        return Pubkey.find_program_address(
+            [SEED_TOMBSTONE, bytes(wallet)], self._program_id
+        )
+
+    def derive_compromised_address(self, fingerprint_hash: bytes) -> tuple[Pubkey, int]:
+        return Pubkey.find_program_address(
+            [SEED_COMPROMISED, fingerprint_hash], self._program_id
+        )
+
+    def derive_invite_address(self, wallet: Pubkey) -> tuple[Pubkey, int]:
+        return Pubkey.find_program_address(
+            [SEED_INVITE, bytes(wallet)], self._program_id
+        )
+
+    def derive_insurance_fund_address(self) -> tuple[Pubkey, int]:
+        return Pubkey.find_program_address([SEED_INSURANCE_FUND], self._program_id)
+
+    def derive_stats_bucket_address(self, session: Pubkey) -> tuple[Pubkey, int]:
+        index = bytes(session)[0] % STATS_BUCKET_COUNT
+        return Pubkey.find_program_address(
+            [SEED_STATS, bytes([index])], self._program_id
+        )
+
+    def derive_personality_config_address(self, personality_id: int) -> tuple[Pubkey, int]:
+        return Pubkey.find_program_address(
+            [SEED_PERSONALITY, bytes([personality_id])], self._program_id
+        )
+
+    def derive_epoch_stats_address(self, now: Optional[int] = None) -> tuple[Pubkey, int]:
+        """EpochStats of the day containing `now` (default: the current time)."""
+        if now is None:
+            now = int(time.time())
+        epoch = max(now, 0) // EPOCH_STATS_DURATION
+        return Pubkey.find_program_address(
+            [SEED_EPOCH_STATS, struct.pack("<Q", epoch)], self._program_id
+        )
+
     # -- Transaction Builders --
 
     async def build_register_human_tx(
         self,
         wallet: Pubkey,
         challenge_nonce: bytes,
-        fingerprint_hash: bytes,
+        fingerprint_data: bytes,
+        invited: bool = False,
+        insured: bool = False,
     ) -> Transaction:
-        """Builds a register_human transaction."""
+        """Builds a register_human transaction.
+
+        ``fingerprint_data`` is the raw fingerprint, up to the registry's
+        ``max_fingerprint_len``. Set ``invited`` while registration is
+        permissioned and ``insured`` while an insurance fee is set.
+        """
         registry_addr, _ = self.derive_registry_address()
         human_record_addr, _ = self.derive_human_record_address(wallet)
+        fingerprint_hash = hashlib.sha256(fingerprint_data).digest()
+        fingerprint_addr, _ = self.derive_fingerprint_address(human_record_addr)
+        tombstone_addr, _ = self.derive_tombstone_address(wallet)
+        compromised_addr, _ = self.derive_compromised_address(fingerprint_hash)
 
         registry_info = await self._rpc.get_account_info(registry_addr)
         if not registry_info.value:
             raise ValueError("Platform registry not initialized")
 
-        authority_bytes = registry_info.value.data[8:40]
-        authority = Pubkey.from_bytes(authority_bytes)
+        # Fees go to the treasury, or to the authority until one is set.
+        registry_data = registry_info.value.data
+        fee_wallet = Pubkey.from_bytes(
+            registry_data[REGISTRY_TREASURY_OFFSET:REGISTRY_TREASURY_OFFSET + 32]
+        )
+        if fee_wallet == Pubkey.default():
+            fee_wallet = Pubkey.from_bytes(registry_data[8:40])
 
         discriminator = self._compute_discriminator("register_human")
-        data = discriminator + challenge_nonce + fingerprint_hash
+        data = (
+            discriminator
+            + challenge_nonce
+            + struct.pack("<I", len(fingerprint_data))
+            + fingerprint_data
+            + b"\x00"  # promo_code: None
+        )
 
         ix = Instruction(
             program_id=self._program_id,
             accounts=[
                 AccountMeta(registry_addr, is_signer=False, is_writable=True),
                 AccountMeta(human_record_addr, is_signer=False, is_writable=True),
+                AccountMeta(fingerprint_addr, is_signer=False, is_writable=True),
+                AccountMeta(tombstone_addr, is_signer=False, is_writable=True),
+                self._optional_account(
+                    self.derive_invite_address(wallet)[0] if invited else None
+                ),
+                AccountMeta(compromised_addr, is_signer=False, is_writable=False),
                 AccountMeta(wallet, is_signer=True, is_writable=True),
-                AccountMeta(authority, is_signer=False, is_writable=True),
+                AccountMeta(fee_wallet, is_signer=False, is_writable=True),
+                self._optional_account(
+                    self.derive_insurance_fund_address()[0] if insured else None
+                ),
+                self._optional_account(None),  # matching_campaign
+                self._optional_account(None),  # scholarship_pool
+                self._optional_account(None),  # promo
+                self._optional_account(None),  # promo_redemption
                 AccountMeta(SYSTEM_PROGRAM_ID, is_signer=False, is_writable=False),
             ],
             data=data,
@@ -114,6 +215,9 @@ class SolanaClient:
         wallet: Pubkey,
         personality_id: int,
         initial_topic: bytes,
+        goal_type: int = 0,
+        goal_target: int = 0,
+        escrow_rate_per_minute: int = 0,
     ) -> tuple[Transaction, Pubkey]:
         """Builds a create_session transaction. Returns (tx, session_address)."""
         registry_addr, _ = self.derive_registry_address()
@@ -123,21 +227,40 @@ class SolanaClient:
         if not human_info.value:
             raise ValueError("Human record not found for this wallet")
 
-        offset = 8 + 32 + 32 + 8 + 1 + 32 + 1
+        offset = HUMAN_RECORD_FLAGS_OFFSET + 2
         session_count = struct.unpack_from("<Q", human_info.value.data, offset)[0]
 
         session_addr, _ = self.derive_session_address(human_record_addr, session_count)
+        stats_bucket_addr, _ = self.derive_stats_bucket_address(session_addr)
+        personality_addr, _ = self.derive_personality_config_address(personality_id)
+        epoch_stats_addr, _ = self.derive_epoch_stats_address()
 
         discriminator = self._compute_discriminator("create_session")
-        data = discriminator + bytes([personality_id]) + initial_topic
+        data = (
+            discriminator
+            + bytes([personality_id])
+            + initial_topic
+            + bytes([goal_type])
+            + struct.pack("<Q", goal_target)
+            + struct.pack("<Q", escrow_rate_per_minute)
+        )
 
         ix = Instruction(
             program_id=self._program_id,
             accounts=[
-                AccountMeta(registry_addr, is_signer=False, is_writable=True),
+                AccountMeta(registry_addr, is_signer=False, is_writable=False),
                 AccountMeta(human_record_addr, is_signer=False, is_writable=True),
                 AccountMeta(session_addr, is_signer=False, is_writable=True),
+                AccountMeta(stats_bucket_addr, is_signer=False, is_writable=True),
+                AccountMeta(epoch_stats_addr, is_signer=False, is_writable=True),
+                self._optional_account(None),  # active_index
+                AccountMeta(personality_addr, is_signer=False, is_writable=False),
                 AccountMeta(wallet, is_signer=True, is_writable=True),
+                self._optional_account(None),  # supervisor
+                self._optional_account(None),  # booster_mint
+                self._optional_account(None),  # booster_token
+                self._optional_account(None),  # token_program
+                self._optional_account(None),  # registered_topic
                 AccountMeta(SYSTEM_PROGRAM_ID, is_signer=False, is_writable=False),
             ],
             data=data,
@@ -153,11 +276,13 @@ class SolanaClient:
         self,
         wallet: Pubkey,
         session_addr: Pubkey,
-        session_index: int,
         content_hash: bytes,
         interaction_type: int,
         score: int,
         duration_seconds: int,
+        content_uri_hash: bytes = bytes(32),
+        content_size: int = 0,
+        private: bool = False,
     ) -> Transaction:
         """Builds a record_interaction transaction."""
         registry_addr, _ = self.derive_registry_address()
@@ -167,7 +292,7 @@ class SolanaClient:
         if not session_info.value:
             raise ValueError("Session account not found")
 
-        interaction_count_offset = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1
+        interaction_count_offset = SESSION_FLAGS_OFFSET + 2
         interaction_count = struct.unpack_from(
             "<I", session_info.value.data, interaction_count_offset
         )[0]
@@ -175,6 +300,8 @@ class SolanaClient:
         interaction_addr, _ = self.derive_interaction_address(
             session_addr, interaction_count
         )
+        stats_bucket_addr, _ = self.derive_stats_bucket_address(session_addr)
+        epoch_stats_addr, _ = self.derive_epoch_stats_address()
 
         discriminator = self._compute_discriminator("record_interaction")
         data = (
@@ -183,15 +310,22 @@ class SolanaClient:
             + bytes([interaction_type])
             + bytes([score])
             + struct.pack("<I", duration_seconds)
+            + content_uri_hash
+            + struct.pack("<I", content_size)
+            + bytes([private])
         )
 
         ix = Instruction(
             program_id=self._program_id,
             accounts=[
-                AccountMeta(registry_addr, is_signer=False, is_writable=True),
+                AccountMeta(registry_addr, is_signer=False, is_writable=False),
                 AccountMeta(human_record_addr, is_signer=False, is_writable=True),
                 AccountMeta(session_addr, is_signer=False, is_writable=True),
                 AccountMeta(interaction_addr, is_signer=False, is_writable=True),
+                AccountMeta(stats_bucket_addr, is_signer=False, is_writable=True),
+                AccountMeta(epoch_stats_addr, is_signer=False, is_writable=True),
+                # Required by the program while companion co-signing is on.
+                AccountMeta(SYSVAR_INSTRUCTIONS_ID, is_signer=False, is_writable=False),
                 AccountMeta(wallet, is_signer=True, is_writable=True),
                 AccountMeta(SYSTEM_PROGRAM_ID, is_signer=False, is_writable=False),
             ],
@@ -230,10 +364,10 @@ class SolanaClient:
         offset += 8
 
         verification_level = data[offset]
-        offset += 1 + 32
+        offset = HUMAN_RECORD_FLAGS_OFFSET
 
-        is_active = data[offset] == 1
-        offset += 1
+        is_active = (struct.unpack_from("<H", data, offset)[0] & 1) != 0
+        offset += 2
 
         session_count = struct.unpack_from("<Q", data, offset)[0]
         offset += 8
@@ -285,6 +419,12 @@ class SolanaClient:
 
     # -- Private helpers --
 
+    def _optional_account(self, address: Optional[Pubkey], writable: bool = True) -> AccountMeta:
+        """An optional account; Anchor reads the program ID when it is omitted."""
+        if address is None:
+            return AccountMeta(self._program_id, is_signer=False, is_writable=False)
+        return AccountMeta(address, is_signer=False, is_writable=writable)
+
     @staticmethod
     def _compute_discriminator(name: str) -> bytes:
         full = f"global:{name}"
@@ -1,11 +1,25 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Connection, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+    Connection,
+    PublicKey,
+    SystemProgram,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    Transaction,
+} from "@solana/web3.js";
 import BN from "bn.js";
 import {
     deriveRegistryAddress,
     deriveHumanRecordAddress,
     deriveSessionAddress,
     deriveInteractionAddress,
+    deriveFingerprintAddress,
+    deriveTombstoneAddress,
+    deriveCompromisedAddress,
+    deriveInviteAddress,
+    deriveInsuranceFundAddress,
+    deriveStatsBucketAddress,
+    deriveEpochStatsAddress,
+    derivePersonalityConfigAddress,
 } from "./pda";
 import {
     PlatformRegistry,
@@ -23,13 +37,20 @@ const DEFAULT_PROGRAM_ID = new PublicKey(
     "YRFunHP2kVerify1111111111111111111111111111"
 );
 
+/** Byte offset of the packed `HumanRecord.flags` field, discriminator included. */
+export const HUMAN_RECORD_FLAGS_OFFSET = 113;
+/** Byte offset of the packed `SessionAccount.flags` field, discriminator included. */
+export const SESSION_FLAGS_OFFSET = 104;
+/** Byte offset of `PlatformRegistry.treasury`, discriminator included. */
+export const REGISTRY_TREASURY_OFFSET = 905;
+
 /**
  * YourFunClient provides a high-level interface for interacting with
  * the your.fun Proof-of-Human protocol on Solana.
  *
  * Usage:
  *   const client = new YourFunClient(connection, wallet);
- *   await client.registerHuman({ challengeNonce, fingerprintHash });
+ *   await client.registerHuman({ challengeNonce, fingerprintData });
  */
 export class YourFunClient {
     readonly connection: Connection;
@@ -78,12 +99,17 @@ export class YourFunClient {
 
     /**
      * Initializes the platform registry. Can only be called once by the authority.
+     * Registration fees go to `treasury`, or to the authority while it is the
+     * default key.
      */
     async initialize(
         verificationFeeLamports: number,
         minBehavioralScore: number,
         maxSessionDuration: number,
-        maxInteractionsPerSession: number
+        maxInteractionsPerSession: number,
+        minFeeLamports: number,
+        maxFeeLamports: number,
+        treasury: PublicKey = PublicKey.default
     ): Promise<string> {
         const registryAddress = this.getRegistryAddress();
 
@@ -91,7 +117,10 @@ export class YourFunClient {
             new BN(verificationFeeLamports),
             minBehavioralScore,
             new BN(maxSessionDuration),
-            maxInteractionsPerSession,
+            this.encodeU32(maxInteractionsPerSession),
+            new BN(minFeeLamports),
+            new BN(maxFeeLamports),
+            treasury,
         ]);
 
         const ix = {
@@ -110,23 +139,33 @@ export class YourFunClient {
 
     /**
      * Registers the connected wallet for Proof-of-Human verification.
-     * This creates the HumanRecord PDA and pays the registration fee.
+     * This creates the HumanRecord and FingerprintData PDAs and pays the
+     * registration fee to the registry's fee wallet.
      */
     async registerHuman(params: RegisterHumanParams): Promise<string> {
         const registryAddress = this.getRegistryAddress();
         const humanRecordAddress = this.getHumanRecordAddress();
+        const wallet = this.wallet.publicKey;
 
         if (params.challengeNonce.length !== 32) {
             throw new Error("Challenge nonce must be exactly 32 bytes");
         }
-        if (params.fingerprintHash.length !== 32) {
-            throw new Error("Fingerprint hash must be exactly 32 bytes");
-        }
 
-        const registry = await this.fetchRegistry();
+        const crypto = require("crypto");
+        const fingerprintHash: Buffer = crypto.createHash("sha256")
+            .update(params.fingerprintData)
+            .digest();
+        const [fingerprintAddress] = deriveFingerprintAddress(humanRecordAddress, this.programId);
+        const [tombstoneAddress] = deriveTombstoneAddress(wallet, this.programId);
+        const [compromisedAddress] = deriveCompromisedAddress(fingerprintHash, this.programId);
+        const [inviteAddress] = deriveInviteAddress(wallet, this.programId);
+        const [insuranceFundAddress] = deriveInsuranceFundAddress(this.programId);
+
+        const feeWallet = await this.fetchFeeWallet();
         const data = this.encodeInstruction("register_human", [
             Array.from(params.challengeNonce),
-            Array.from(params.fingerprintHash),
+            this.encodeBytes(params.fingerprintData),
+            this.encodeNone(), // promo_code
         ]);
 
         const ix = {
@@ -134,8 +173,17 @@ export class YourFunClient {
             keys: [
                 { pubkey: registryAddress, isSigner: false, isWritable: true },
                 { pubkey: humanRecordAddress, isSigner: false, isWritable: true },
-                { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
-                { pubkey: registry.authority, isSigner: false, isWritable: true },
+                { pubkey: fingerprintAddress, isSigner: false, isWritable: true },
+                { pubkey: tombstoneAddress, isSigner: false, isWritable: true },
+                this.optionalAccount(params.invited ? inviteAddress : null, true),
+                { pubkey: compromisedAddress, isSigner: false, isWritable: false },
+                { pubkey: wallet, isSigner: true, isWritable: true },
+                { pubkey: feeWallet, isSigner: false, isWritable: true },
+                this.optionalAccount(params.insured ? insuranceFundAddress : null, true),
+                this.optionalAccount(null), // matching_campaign
+                this.optionalAccount(null), // scholarship_pool
+                this.optionalAccount(null), // promo
+                this.optionalAccount(null), // promo_redemption
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
             data,
@@ -168,18 +216,37 @@ export class YourFunClient {
             throw new Error("Initial topic must be exactly 32 bytes");
         }
 
+        const [statsBucketAddress] = deriveStatsBucketAddress(sessionAddress, this.programId);
+        const [personalityConfigAddress] = derivePersonalityConfigAddress(
+            params.personalityId,
+            this.programId
+        );
+        const [epochStatsAddress] = deriveEpochStatsAddress(undefined, this.programId);
+
         const data = this.encodeInstruction("create_session", [
             params.personalityId,
             Array.from(params.initialTopic),
+            params.goalType ?? 0,
+            params.goalTarget ?? new BN(0),
+            params.escrowRatePerMinute ?? new BN(0),
         ]);
 
         const ix = {
             programId: this.programId,
             keys: [
-                { pubkey: registryAddress, isSigner: false, isWritable: true },
+                { pubkey: registryAddress, isSigner: false, isWritable: false },
                 { pubkey: humanRecordAddress, isSigner: false, isWritable: true },
                 { pubkey: sessionAddress, isSigner: false, isWritable: true },
+                { pubkey: statsBucketAddress, isSigner: false, isWritable: true },
+                { pubkey: epochStatsAddress, isSigner: false, isWritable: true },
+                this.optionalAccount(null), // active_index
+                { pubkey: personalityConfigAddress, isSigner: false, isWritable: false },
                 { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
+                this.optionalAccount(null), // supervisor
+                this.optionalAccount(null), // booster_mint
+                this.optionalAccount(null), // booster_token
+                this.optionalAccount(null), // token_program
+                this.optionalAccount(null), // registered_topic
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
             data,
@@ -197,31 +264,48 @@ export class YourFunClient {
     async recordInteraction(params: RecordInteractionParams): Promise<string> {
         const registryAddress = this.getRegistryAddress();
         const humanRecordAddress = this.getHumanRecordAddress();
+        const session = await this.fetchSession(params.sessionAddress);
 
         const [interactionAddress] = deriveInteractionAddress(
             params.sessionAddress,
-            params.sessionIndex,
+            session.interactionCount,
             this.programId
         );
+        const [statsBucketAddress] = deriveStatsBucketAddress(
+            params.sessionAddress,
+            this.programId
+        );
+        const [epochStatsAddress] = deriveEpochStatsAddress(undefined, this.programId);
 
         if (params.contentHash.length !== 32) {
             throw new Error("Content hash must be exactly 32 bytes");
         }
+        const contentUriHash = params.contentUriHash ?? new Uint8Array(32);
+        if (contentUriHash.length !== 32) {
+            throw new Error("Content URI hash must be exactly 32 bytes");
+        }
 
         const data = this.encodeInstruction("record_interaction", [
             Array.from(params.contentHash),
             params.interactionType,
             params.score,
-            params.durationSeconds,
+            this.encodeU32(params.durationSeconds),
+            Array.from(contentUriHash),
+            this.encodeU32(params.contentSize ?? 0),
+            params.private ? 1 : 0,
         ]);
 
         const ix = {
             programId: this.programId,
             keys: [
-                { pubkey: registryAddress, isSigner: false, isWritable: true },
+                { pubkey: registryAddress, isSigner: false, isWritable: false },
                 { pubkey: humanRecordAddress, isSigner: false, isWritable: true },
                 { pubkey: params.sessionAddress, isSigner: false, isWritable: true },
                 { pubkey: interactionAddress, isSigner: false, isWritable: true },
+                { pubkey: statsBucketAddress, isSigner: false, isWritable: true },
+                { pubkey: epochStatsAddress, isSigner: false, isWritable: true },
+                // Required by the program while companion co-signing is on.
+                { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
                 { pubkey: this.wallet.publicKey, isSigner: true, isWritable: true },
                 { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
             ],
@@ -244,6 +328,23 @@ export class YourFunClient {
         return this.deserializeRegistry(info.data);
     }
 
+    /**
+     * Returns the account registration fees are paid to: the registry's
+     * treasury, or its authority while no treasury is set.
+     */
+    async fetchFeeWallet(): Promise<PublicKey> {
+        const info = await this.connection.getAccountInfo(this.getRegistryAddress());
+        if (!info) {
+            throw new Error("Platform registry not found. Has it been initialized?");
+        }
+        const treasury = new PublicKey(
+            info.data.slice(REGISTRY_TREASURY_OFFSET, REGISTRY_TREASURY_OFFSET + 32)
+        );
+        return treasury.equals(PublicKey.default)
+            ? this.deserializeRegistry(info.data).authority
+            : treasury;
+    }
+
     /**
      * Fetches the HumanRecord for the connected wallet.
      */
@@ -306,6 +407,31 @@ export class YourFunClient {
         return hash.slice(0, 8);
     }
 
+    /**
+     * An omitted optional account: Anchor reads the program ID in its place.
+     */
+    private optionalAccount(address: PublicKey | null, isWritable = false) {
+        return address
+            ? { pubkey: address, isSigner: false, isWritable }
+            : { pubkey: this.programId, isSigner: false, isWritable: false };
+    }
+
+    /** Borsh `Vec<u8>`: a u32 length followed by the bytes. */
+    private encodeBytes(bytes: Uint8Array): Buffer {
+        return Buffer.concat([this.encodeU32(bytes.length), Buffer.from(bytes)]);
+    }
+
+    /** Borsh `None`. */
+    private encodeNone(): Buffer {
+        return Buffer.from([0]);
+    }
+
+    private encodeU32(value: number): Buffer {
+        const buf = Buffer.alloc(4);
+        buf.writeUInt32LE(value);
+        return buf;
+    }
+
     private serializeArgs(args: unknown[]): Buffer {
         const buffers: Buffer[] = [];
         for (const arg of args) {
@@ -323,6 +449,11 @@ export class YourFunClient {
                 }
             } else if (Array.isArray(arg)) {
                 buffers.push(Buffer.from(arg as number[]));
+            } else if (arg instanceof PublicKey) {
+                buffers.push(arg.toBuffer());
+            } else if (Buffer.isBuffer(arg)) {
+                // Already Borsh-encoded.
+                buffers.push(arg);
             }
         }
         return Buffer.concat(buffers);
@@ -361,8 +492,8 @@ export class YourFunClient {
         const verifiedBy = new PublicKey(data.slice(offset, offset + 32)); offset += 32;
         const verifiedAt = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
         const verificationLevel = data[offset]; offset += 1;
-        const fingerprintHash = new Uint8Array(data.slice(offset, offset + 32)); offset += 32;
-        const isActive = data[offset] === 1; offset += 1;
+        const fingerprintHash = new Uint8Array(data.slice(offset, offset + 32)); offset = HUMAN_RECORD_FLAGS_OFFSET;
+        const isActive = (data.readUInt16LE(offset) & 1) !== 0; offset += 2;
         const sessionCount = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
         const totalInteractions = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
         const lastActiveAt = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
@@ -393,8 +524,8 @@ export class YourFunClient {
         const sessionIndex = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
         const createdAt = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
         const lastInteractionAt = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
-        const expiresAt = new BN(data.slice(offset, offset + 8), "le"); offset += 8;
-        const isActive = data[offset] === 1; offset += 1;
+        const expiresAt = new BN(data.slice(offset, offset + 8), "le"); offset = SESSION_FLAGS_OFFSET;
+        const isActive = (data.readUInt16LE(offset) & 1) !== 0; offset += 2;
         const interactionCount = data.readUInt32LE(offset); offset += 4;
         const personalityId = data[offset]; offset += 1;
         const currentTopic = new Uint8Array(data.slice(offset, offset + 32)); offset += 32;
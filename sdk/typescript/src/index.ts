@@ -1,4 +1,9 @@
-export { YourFunClient } from "./client";
+export {
+    YourFunClient,
+    HUMAN_RECORD_FLAGS_OFFSET,
+    SESSION_FLAGS_OFFSET,
+    REGISTRY_TREASURY_OFFSET,
+} from "./client";
 export { AICompanion } from "./ai";
 export { ProofGenerator } from "./proof";
 export {
@@ -6,7 +11,17 @@ export {
     deriveHumanRecordAddress,
     deriveSessionAddress,
     deriveInteractionAddress,
+    deriveFingerprintAddress,
+    deriveTombstoneAddress,
+    deriveCompromisedAddress,
+    deriveInviteAddress,
+    deriveInsuranceFundAddress,
+    deriveStatsBucketAddress,
+    derivePersonalityConfigAddress,
+    deriveEpochStatsAddress,
     SEEDS,
+    STATS_BUCKET_COUNT,
+    EPOCH_STATS_DURATION,
 } from "./pda";
 export {
     PlatformRegistry,
@@ -9,8 +9,22 @@ export const SEEDS = {
     HUMAN: Buffer.from("human"),
     SESSION: Buffer.from("session"),
     INTERACTION: Buffer.from("interaction"),
+    FINGERPRINT: Buffer.from("fingerprint"),
+    TOMBSTONE: Buffer.from("tombstone"),
+    COMPROMISED: Buffer.from("compromised"),
+    INVITE: Buffer.from("invite"),
+    INSURANCE_FUND: Buffer.from("insurance_fund"),
+    STATS: Buffer.from("stats"),
+    PERSONALITY: Buffer.from("personality"),
+    EPOCH_STATS: Buffer.from("epoch_stats"),
 } as const;
 
+/** Number of StatsBucket shards the session counters are spread over. */
+export const STATS_BUCKET_COUNT = 16;
+
+/** Length in seconds of the day an EpochStats account counts activity for. */
+export const EPOCH_STATS_DURATION = 86400;
+
 /**
  * Derives the PlatformRegistry PDA.
  */
@@ -65,3 +79,108 @@ export function deriveInteractionAddress(
         programId
     );
 }
+
+/**
+ * Derives the FingerprintData PDA holding a human record's raw fingerprint.
+ */
+export function deriveFingerprintAddress(
+    humanRecord: PublicKey,
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.FINGERPRINT, humanRecord.toBuffer()],
+        programId
+    );
+}
+
+/**
+ * Derives the Tombstone PDA left by a closed human record for a wallet.
+ */
+export function deriveTombstoneAddress(
+    wallet: PublicKey,
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.TOMBSTONE, wallet.toBuffer()],
+        programId
+    );
+}
+
+/**
+ * Derives the CompromisedFingerprint PDA for a fingerprint hash.
+ */
+export function deriveCompromisedAddress(
+    fingerprintHash: Uint8Array,
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.COMPROMISED, Buffer.from(fingerprintHash)],
+        programId
+    );
+}
+
+/**
+ * Derives the InviteEntry PDA for a wallet.
+ */
+export function deriveInviteAddress(
+    wallet: PublicKey,
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.INVITE, wallet.toBuffer()],
+        programId
+    );
+}
+
+/**
+ * Derives the InsuranceFund PDA.
+ */
+export function deriveInsuranceFundAddress(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.INSURANCE_FUND],
+        programId
+    );
+}
+
+/**
+ * Derives the StatsBucket PDA that absorbs the counters for a session.
+ */
+export function deriveStatsBucketAddress(
+    session: PublicKey,
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+    const index = session.toBuffer()[0] % STATS_BUCKET_COUNT;
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.STATS, Buffer.from([index])],
+        programId
+    );
+}
+
+/**
+ * Derives the PersonalityConfig PDA for a personality ID.
+ */
+export function derivePersonalityConfigAddress(
+    personalityId: number,
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.PERSONALITY, Buffer.from([personalityId])],
+        programId
+    );
+}
+
+/**
+ * Derives the EpochStats PDA of the day containing `now`, in Unix seconds
+ * (default: the current time).
+ */
+export function deriveEpochStatsAddress(
+    now: number = Math.floor(Date.now() / 1000),
+    programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+    const epochBuffer = Buffer.alloc(8);
+    epochBuffer.writeBigUInt64LE(BigInt(Math.floor(Math.max(now, 0) / EPOCH_STATS_DURATION)));
+    return PublicKey.findProgramAddressSync(
+        [SEEDS.EPOCH_STATS, epochBuffer],
+        programId
+    );
+}
@@ -34,6 +34,9 @@ interface BehavioralMetrics {
  * Final fingerprint output for on-chain registration.
  */
 interface FingerprintOutput {
+    /** Raw fingerprint bytes submitted at registration. */
+    data: Uint8Array;
+    /** SHA-256 of `data`, as stored on chain. */
     hash: Uint8Array;
     metrics: BehavioralMetrics;
     confidence: number;
@@ -199,6 +202,7 @@ export class ProofGenerator {
         const confidence = this.computeConfidence(metrics);
 
         return {
+            data: new Uint8Array(dataBuffer),
             hash: fingerprintHash,
             metrics,
             confidence,
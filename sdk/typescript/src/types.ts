@@ -103,7 +103,12 @@ export interface YourFunConfig {
  */
 export interface RegisterHumanParams {
     challengeNonce: Uint8Array;
-    fingerprintHash: Uint8Array;
+    /** Raw fingerprint bytes, up to the registry's `max_fingerprint_len`. */
+    fingerprintData: Uint8Array;
+    /** Pass the wallet's invite; required while registration is permissioned. */
+    invited?: boolean;
+    /** Pass the insurance fund; required while an insurance fee is set. */
+    insured?: boolean;
 }
 
 /**
@@ -122,6 +127,11 @@ export interface VerifyHumanParams {
 export interface CreateSessionParams {
     personalityId: PersonalityType;
     initialTopic: Uint8Array;
+    /** Goal kind; 0 for a session without a goal. */
+    goalType?: number;
+    goalTarget?: BN;
+    /** Lamports escrowed per minute; 0 for an unbilled session. */
+    escrowRatePerMinute?: BN;
 }
 
 /**
@@ -129,11 +139,14 @@ export interface CreateSessionParams {
  */
 export interface RecordInteractionParams {
     sessionAddress: PublicKey;
-    sessionIndex: number;
     contentHash: Uint8Array;
     interactionType: InteractionType;
     score: number;
     durationSeconds: number;
+    /** Hash of the off-chain content URI; zeros when there is none. */
+    contentUriHash?: Uint8Array;
+    contentSize?: number;
+    private?: boolean;
 }
 
 /**